@@ -0,0 +1,141 @@
+//! Allocations and time for the per-action hot path: applying actions and building a
+//! view for every seat, as a broadcast does.
+//!
+//! Run with `cargo bench --bench hot_path`. One deck deals at most 6 cards to 8 players,
+//! so the 13-card round is measured with 4 players.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use german_bridge_backend::bug_report::GameJournal;
+use german_bridge_backend::connection::PlayerId;
+use german_bridge_backend::game::Game;
+use german_bridge_backend::engine::{GamePhase, GameState};
+use german_bridge_backend::engine::auto_play::LowestLegal;
+use german_bridge_backend::protocol::GameSettings;
+use german_bridge_backend::short_code::ShortCode;
+use german_bridge_backend::user_cache::PlayerProfile;
+use uuid::Uuid;
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const ROUNDS: u32 = 200;
+
+fn game(players: usize) -> Game {
+    let players: Vec<PlayerId> = (0..players).map(|_| Uuid::new_v4().to_string()).collect();
+    let profiles: HashMap<PlayerId, PlayerProfile> = players.iter().enumerate()
+        .map(|(i, id)| (id.clone(), PlayerProfile::named(format!("player{}", i))))
+        .collect();
+    let mut state = GameState::new(players.clone());
+    // Skip ahead to the round with the most cards
+    state.round_number = state.max_cards_per_player();
+    state.start_round();
+    Game {
+        id: Uuid::new_v4(),
+        state,
+        players: players.clone(),
+        created_at: Instant::now(),
+        player_names: Arc::new(profiles.iter().map(|(id, p)| (id.clone(), p.username.clone())).collect()),
+        profiles,
+        settings: GameSettings::default(),
+        journal: GameJournal::new(players.clone()),
+        departed: HashMap::new(),
+        code: ShortCode::generate(),
+        host: players[0].clone(),
+        open_seats: Default::default(),
+        pacing: Default::default(),
+        turn_deadline: None,
+        turn_paused_until: None,
+        next_round_votes: Default::default(),
+        intermission_deadline: None,
+        resumed_at: None,
+    }
+}
+
+#[derive(Default)]
+struct Totals {
+    actions: u64,
+    action_allocs: u64,
+    action_time: Duration,
+    views: u64,
+    view_allocs: u64,
+    view_time: Duration,
+}
+
+/// Play one round to the end, building every seat's view after each action
+fn play_round(game: &mut Game, totals: &mut Totals) {
+    while game.state.phase != GamePhase::RoundComplete && game.state.phase != GamePhase::GameComplete {
+        let player = game.state.current_player.clone();
+        let action = game.state.get_auto_action(&LowestLegal).expect("a legal action");
+
+        let allocs = ALLOCATIONS.load(Ordering::Relaxed);
+        let started = Instant::now();
+        game.state.apply_action(player, action).expect("legal action applies");
+        totals.action_time += started.elapsed();
+        totals.action_allocs += ALLOCATIONS.load(Ordering::Relaxed) - allocs;
+        totals.actions += 1;
+
+        let allocs = ALLOCATIONS.load(Ordering::Relaxed);
+        let started = Instant::now();
+        for seat in &game.players {
+            std::hint::black_box(game.player_view(seat.clone()));
+        }
+        totals.view_time += started.elapsed();
+        totals.view_allocs += ALLOCATIONS.load(Ordering::Relaxed) - allocs;
+        totals.views += game.players.len() as u64;
+    }
+}
+
+fn run(players: usize) {
+    let mut totals = Totals::default();
+    for _ in 0..ROUNDS {
+        let mut game = game(players);
+        // Some history, as a game in its last rounds has
+        for _ in 0..3 {
+            play_round(&mut game, &mut Totals::default());
+            game.state.phase = GamePhase::RoundComplete;
+            game.state.start_round();
+        }
+        play_round(&mut game, &mut totals);
+    }
+
+    let cards = game(players).state.cards_per_player;
+    println!(
+        "{} players x {} cards: {:.1} allocs/action, {:.0} ns/action; {:.1} allocs/view, {:.0} ns/view",
+        players,
+        cards,
+        totals.action_allocs as f64 / totals.actions as f64,
+        totals.action_time.as_nanos() as f64 / totals.actions as f64,
+        totals.view_allocs as f64 / totals.views as f64,
+        totals.view_time.as_nanos() as f64 / totals.views as f64,
+    );
+}
+
+fn main() {
+    run(8);
+    run(4);
+}
@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use crate::engine::card::{Card, Rank, Suit};
+
+/// How suits are told apart visually; negotiated per user so every client renders the same deck
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuitEncoding {
+    /// Two colors: red hearts and diamonds, black clubs and spades
+    #[default]
+    Standard,
+    /// A distinct color per suit
+    FourColor,
+    /// Four colors plus a fill pattern per suit, for players who cannot rely on color at all
+    Patterned,
+}
+
+impl SuitEncoding {
+    /// Value stored in `users.suit_encoding`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SuitEncoding::Standard => "standard",
+            SuitEncoding::FourColor => "four_color",
+            SuitEncoding::Patterned => "patterned",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "standard" => Some(SuitEncoding::Standard),
+            "four_color" => Some(SuitEncoding::FourColor),
+            "patterned" => Some(SuitEncoding::Patterned),
+            _ => None,
+        }
+    }
+}
+
+/// Rendering and screen-reader details for one suit
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuitAsset {
+    pub suit: Suit,
+    pub symbol: String,
+    /// CSS color name
+    pub color: String,
+    pub pattern: Option<String>,
+    /// Accessible description, e.g. "Hearts (red, striped)"
+    pub label: String,
+}
+
+const SUITS: [Suit; 4] = [Suit::Clubs, Suit::Spades, Suit::Hearts, Suit::Diamonds];
+
+const RANKS: [Rank; 13] = [
+    Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six, Rank::Seven, Rank::Eight,
+    Rank::Nine, Rank::Ten, Rank::Jack, Rank::Queen, Rank::King, Rank::Ace,
+];
+
+pub(crate) fn suit_name(suit: Suit) -> &'static str {
+    match suit {
+        Suit::Clubs => "Clubs",
+        Suit::Spades => "Spades",
+        Suit::Hearts => "Hearts",
+        Suit::Diamonds => "Diamonds",
+    }
+}
+
+fn rank_name(rank: Rank) -> &'static str {
+    match rank {
+        Rank::Two => "Two",
+        Rank::Three => "Three",
+        Rank::Four => "Four",
+        Rank::Five => "Five",
+        Rank::Six => "Six",
+        Rank::Seven => "Seven",
+        Rank::Eight => "Eight",
+        Rank::Nine => "Nine",
+        Rank::Ten => "Ten",
+        Rank::Jack => "Jack",
+        Rank::Queen => "Queen",
+        Rank::King => "King",
+        Rank::Ace => "Ace",
+    }
+}
+
+/// Rendering details for a suit under the given encoding
+pub fn suit_asset(suit: Suit, encoding: SuitEncoding) -> SuitAsset {
+    let symbol = match suit {
+        Suit::Clubs => "♣",
+        Suit::Spades => "♠",
+        Suit::Hearts => "♥",
+        Suit::Diamonds => "♦",
+    };
+
+    let color = match (encoding, suit) {
+        (SuitEncoding::Standard, Suit::Hearts | Suit::Diamonds) => "red",
+        (SuitEncoding::Standard, Suit::Clubs | Suit::Spades) => "black",
+        (_, Suit::Spades) => "black",
+        (_, Suit::Hearts) => "red",
+        (_, Suit::Diamonds) => "blue",
+        (_, Suit::Clubs) => "green",
+    };
+
+    let pattern = match (encoding, suit) {
+        (SuitEncoding::Patterned, Suit::Spades) => Some("solid"),
+        (SuitEncoding::Patterned, Suit::Hearts) => Some("striped"),
+        (SuitEncoding::Patterned, Suit::Diamonds) => Some("dotted"),
+        (SuitEncoding::Patterned, Suit::Clubs) => Some("crosshatched"),
+        _ => None,
+    };
+
+    let label = match (encoding, pattern) {
+        (SuitEncoding::Standard, _) => suit_name(suit).to_string(),
+        (_, Some(pattern)) => format!("{} ({}, {})", suit_name(suit), color, pattern),
+        (_, None) => format!("{} ({})", suit_name(suit), color),
+    };
+
+    SuitAsset {
+        suit,
+        symbol: symbol.to_string(),
+        color: color.to_string(),
+        pattern: pattern.map(str::to_string),
+        label,
+    }
+}
+
+/// Rendering details for all four suits
+pub fn suit_assets(encoding: SuitEncoding) -> Vec<SuitAsset> {
+    SUITS.iter().map(|&suit| suit_asset(suit, encoding)).collect()
+}
+
+/// Screen-reader text for a card, e.g. "Queen of Hearts (red, striped)"
+pub fn card_label(card: Card, encoding: SuitEncoding) -> String {
+    format!("{} of {}", rank_name(card.rank), suit_asset(card.suit, encoding).label)
+}
+
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardLabel {
+    pub card: Card,
+    pub label: String,
+}
+
+/// Screen-reader text for every card in the deck
+pub fn card_labels(encoding: SuitEncoding) -> Vec<CardLabel> {
+    SUITS
+        .iter()
+        .flat_map(|&suit| RANKS.iter().map(move |&rank| Card::new(suit, rank)))
+        .map(|card| CardLabel { card, label: card_label(card, encoding) })
+        .collect()
+}
+
+/// The DisplayProfile message for an encoding
+pub fn display_profile(encoding: SuitEncoding) -> crate::protocol::ServerMessage {
+    crate::protocol::ServerMessage::DisplayProfile {
+        suit_encoding: encoding,
+        suits: suit_assets(encoding),
+        cards: card_labels(encoding),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_four_color_suits_are_distinct() {
+        for encoding in [SuitEncoding::FourColor, SuitEncoding::Patterned] {
+            let mut colors: Vec<_> = suit_assets(encoding).into_iter().map(|a| a.color).collect();
+            colors.sort();
+            colors.dedup();
+            assert_eq!(colors.len(), 4);
+        }
+    }
+
+    #[test]
+    fn test_card_labels() {
+        let card = Card::new(Suit::Hearts, Rank::Queen);
+        assert_eq!(card_label(card, SuitEncoding::Standard), "Queen of Hearts");
+        assert_eq!(card_label(card, SuitEncoding::FourColor), "Queen of Hearts (red)");
+        assert_eq!(card_label(card, SuitEncoding::Patterned), "Queen of Hearts (red, striped)");
+        assert_eq!(card_labels(SuitEncoding::Standard).len(), 52);
+    }
+
+    #[test]
+    fn test_encoding_round_trips_through_storage() {
+        for encoding in [SuitEncoding::Standard, SuitEncoding::FourColor, SuitEncoding::Patterned] {
+            assert_eq!(SuitEncoding::parse(encoding.as_str()), Some(encoding));
+        }
+        assert_eq!(SuitEncoding::parse("rainbow"), None);
+    }
+}
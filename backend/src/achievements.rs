@@ -0,0 +1,255 @@
+use chrono::{DateTime, Utc};
+#[cfg(feature = "server")]
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, FromQueryResult, QueryFilter};
+#[cfg(feature = "server")]
+use sea_orm::sea_query::{Expr, ExprTrait, OnConflict, Query};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use uuid::Uuid;
+use crate::engine::PlayerId;
+use crate::engine::scoring::RoundResult;
+use crate::engine::tiebreak::GameOutcome;
+#[cfg(feature = "server")]
+use crate::entities::user_achievement;
+
+/// Fewest cards dealt in a round for bidding and taking them all to be a grand slam
+pub const GRAND_SLAM_MIN_CARDS: usize = 5;
+/// Fewest rounds in a game for making every bid to count as flawless
+pub const FLAWLESS_MIN_ROUNDS: usize = 5;
+
+/// Something a player unlocks by how they play, once it has happened `target` times
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Achievement {
+    /// Win a game, alone or sharing the win
+    FirstWin,
+    /// Finish ten games
+    Veteran,
+    /// Bid zero and take no tricks, five times
+    ZeroHero,
+    /// Bid and take every trick of a round dealt at least `GRAND_SLAM_MIN_CARDS` cards
+    GrandSlam,
+    /// Make every bid of a game at least `FLAWLESS_MIN_ROUNDS` rounds long
+    Flawless,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 5] = [
+        Achievement::FirstWin,
+        Achievement::Veteran,
+        Achievement::ZeroHero,
+        Achievement::GrandSlam,
+        Achievement::Flawless,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Achievement::FirstWin => "FirstWin",
+            Achievement::Veteran => "Veteran",
+            Achievement::ZeroHero => "ZeroHero",
+            Achievement::GrandSlam => "GrandSlam",
+            Achievement::Flawless => "Flawless",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|achievement| achievement.as_str() == name)
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Achievement::FirstWin => "First Win",
+            Achievement::Veteran => "Veteran",
+            Achievement::ZeroHero => "Zero Hero",
+            Achievement::GrandSlam => "Grand Slam",
+            Achievement::Flawless => "Flawless",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Achievement::FirstWin => "Win a game",
+            Achievement::Veteran => "Finish 10 games",
+            Achievement::ZeroHero => "Bid zero and take no tricks 5 times",
+            Achievement::GrandSlam => "Bid and take every trick of a round of 5 or more cards",
+            Achievement::Flawless => "Make every bid in a game of 5 or more rounds",
+        }
+    }
+
+    /// Times it has to happen to unlock
+    pub fn target(self) -> i32 {
+        match self {
+            Achievement::Veteran => 10,
+            Achievement::ZeroHero => 5,
+            Achievement::FirstWin | Achievement::GrandSlam | Achievement::Flawless => 1,
+        }
+    }
+}
+
+/// Achievements a finished round counts towards. A player with a timeout penalty had turns
+/// played for them, so their round counts for nothing.
+pub fn round_feats(round: &RoundResult) -> Vec<(PlayerId, Achievement)> {
+    let cards: usize = round.player_results.iter().map(|r| r.tricks_won as usize).sum();
+    round.player_results.iter()
+        .filter(|r| r.penalty == 0)
+        .filter_map(|r| {
+            let feat = if r.bid == 0 && r.tricks_won == 0 {
+                Achievement::ZeroHero
+            } else if cards >= GRAND_SLAM_MIN_CARDS && r.tricks_won as usize == cards && r.bid == r.tricks_won {
+                Achievement::GrandSlam
+            } else {
+                return None;
+            };
+            Some((r.player_id.clone(), feat))
+        })
+        .collect()
+}
+
+/// Achievements a finished game counts towards, for the `finishers` who played it to the end
+pub fn game_feats(history: &[RoundResult], outcome: &GameOutcome, finishers: &[PlayerId]) -> Vec<(PlayerId, Achievement)> {
+    let mut feats = Vec::new();
+    for player_id in finishers {
+        feats.push((player_id.clone(), Achievement::Veteran));
+        if outcome.winners.contains(player_id) {
+            feats.push((player_id.clone(), Achievement::FirstWin));
+        }
+        let mut results = history.iter().flat_map(|round| round.player_results.iter().filter(|r| r.player_id == *player_id));
+        if history.len() >= FLAWLESS_MIN_ROUNDS && results.all(|r| r.bid == r.tricks_won && r.penalty == 0) {
+            feats.push((player_id.clone(), Achievement::Flawless));
+        }
+    }
+    feats
+}
+
+/// Count `feats` towards the user's achievements, returning the ones this unlocks. Each count
+/// is one upsert, so games finishing at once cannot lose each other's progress.
+#[cfg(feature = "server")]
+pub async fn record(db: &impl ConnectionTrait, user_id: Uuid, feats: &[Achievement]) -> Result<Vec<Achievement>, DbErr> {
+    let mut unlocked = Vec::new();
+    for &achievement in feats {
+        let now = Utc::now();
+        let progress = Expr::col((user_achievement::Entity, user_achievement::Column::Progress)).add(1);
+        let unlock = Expr::case(progress.clone().gte(achievement.target()), Expr::value(now))
+            .finally(Expr::value(Option::<DateTime<Utc>>::None));
+        let insert = Query::insert()
+            .into_table(user_achievement::Entity)
+            .columns([
+                user_achievement::Column::UserId,
+                user_achievement::Column::Achievement,
+                user_achievement::Column::Progress,
+                user_achievement::Column::UnlockedAt,
+                user_achievement::Column::UpdatedAt,
+            ])
+            .values_panic([
+                user_id.into(),
+                achievement.as_str().into(),
+                1.into(),
+                (achievement.target() <= 1).then_some(now).into(),
+                now.into(),
+            ])
+            // Unlocked achievements are left as they are, and return no row
+            .on_conflict(
+                OnConflict::columns([user_achievement::Column::UserId, user_achievement::Column::Achievement])
+                    .value(user_achievement::Column::Progress, progress)
+                    .value(user_achievement::Column::UnlockedAt, unlock)
+                    .value(user_achievement::Column::UpdatedAt, now)
+                    .action_and_where(Expr::col((user_achievement::Entity, user_achievement::Column::UnlockedAt)).is_null())
+                    .to_owned(),
+            )
+            .returning_all()
+            .to_owned();
+        let row = user_achievement::Model::find_by_statement(db.get_database_backend().build(&insert)).one(db).await?;
+        if row.is_some_and(|row| row.unlocked_at.is_some()) {
+            unlocked.push(achievement);
+        }
+    }
+    Ok(unlocked)
+}
+
+/// Where a user stands on one achievement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AchievementProgress {
+    pub achievement: Achievement,
+    pub progress: i32,
+    pub unlocked_at: Option<DateTime<Utc>>,
+}
+
+/// The user's progress on every achievement, in catalogue order; untouched ones at 0
+#[cfg(feature = "server")]
+pub async fn progress_for_user(db: &DatabaseConnection, user_id: Uuid) -> Result<Vec<AchievementProgress>, DbErr> {
+    let rows = user_achievement::Entity::find()
+        .filter(user_achievement::Column::UserId.eq(user_id))
+        .all(db)
+        .await?;
+    Ok(Achievement::ALL.into_iter()
+        .map(|achievement| {
+            let row = rows.iter().find(|row| Achievement::parse(&row.achievement) == Some(achievement));
+            AchievementProgress {
+                achievement,
+                progress: row.map_or(0, |row| row.progress),
+                unlocked_at: row.and_then(|row| row.unlocked_at),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::scoring::PlayerRoundResult;
+    use crate::engine::tiebreak::WinReason;
+
+    fn round(results: &[(&str, u8, u8, i32)]) -> RoundResult {
+        RoundResult {
+            round_number: 1,
+            trump_suit: None,
+            player_results: results.iter()
+                .map(|&(player_id, bid, tricks_won, penalty)| PlayerRoundResult {
+                    player_id: player_id.to_string(),
+                    bid,
+                    tricks_won,
+                    penalty,
+                    score: 0,
+                })
+                .collect(),
+            deal_seed: None,
+        }
+    }
+
+    #[test]
+    fn test_round_feats() {
+        // a sweeps five cards as bid; b made a zero bid, c did too but timed out
+        let feats = round_feats(&round(&[("a", 5, 5, 0), ("b", 0, 0, 0), ("c", 0, 0, 3)]));
+        assert_eq!(feats, [("a".to_string(), Achievement::GrandSlam), ("b".to_string(), Achievement::ZeroHero)]);
+
+        // Too few cards for a grand slam, and taking them all without bidding them is no slam either
+        assert!(round_feats(&round(&[("a", 4, 4, 0), ("b", 1, 0, 0)])).is_empty());
+        assert!(round_feats(&round(&[("a", 3, 6, 0), ("b", 1, 0, 0)])).is_empty());
+    }
+
+    #[test]
+    fn test_game_feats() {
+        let players = ["a".to_string(), "b".to_string()];
+        let outcome = GameOutcome { winners: vec!["a".to_string()], reason: WinReason::HighestScore };
+        let history = vec![round(&[("a", 1, 1, 0), ("b", 1, 0, 0)]); FLAWLESS_MIN_ROUNDS];
+        let feats = game_feats(&history, &outcome, &players);
+        assert_eq!(feats, [
+            ("a".to_string(), Achievement::Veteran),
+            ("a".to_string(), Achievement::FirstWin),
+            ("a".to_string(), Achievement::Flawless),
+            ("b".to_string(), Achievement::Veteran),
+        ]);
+
+        // A short game is never flawless, and only finishers count
+        let feats = game_feats(&history[1..], &outcome, &players[1..]);
+        assert_eq!(feats, [("b".to_string(), Achievement::Veteran)]);
+    }
+
+    #[test]
+    fn test_names_round_trip() {
+        for achievement in Achievement::ALL {
+            assert_eq!(Achievement::parse(achievement.as_str()), Some(achievement));
+            assert!(achievement.target() >= 1);
+        }
+    }
+}
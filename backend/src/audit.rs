@@ -0,0 +1,131 @@
+use std::net::IpAddr;
+#[cfg(feature = "server")]
+use chrono::{DateTime, Utc};
+#[cfg(feature = "server")]
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use crate::entities::audit_log;
+#[cfg(feature = "server")]
+use tracing::warn;
+
+/// Most entries one search returns
+#[cfg(feature = "server")]
+pub const MAX_SEARCH_LIMIT: u64 = 500;
+
+/// What an audit entry records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub enum AuditKind {
+    /// A login attempt, successful or not
+    Login,
+    LobbyJoin,
+    /// A bid, card, round start or seat change sent to a game
+    GameAction,
+    /// A change made through the admin API
+    Admin,
+    /// A game started with human players connected from the same address
+    SharedAddress,
+}
+
+impl AuditKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditKind::Login => "Login",
+            AuditKind::LobbyJoin => "LobbyJoin",
+            AuditKind::GameAction => "GameAction",
+            AuditKind::Admin => "Admin",
+            AuditKind::SharedAddress => "SharedAddress",
+        }
+    }
+}
+
+/// Keep the proxy a forwarded request came through as `peer` in the entry's detail; the
+/// entry's own address is the client's
+pub fn note_peer(detail: &mut serde_json::Value, client: Option<IpAddr>, peer: Option<IpAddr>) {
+    if let Some(peer) = peer.map(|peer| peer.to_canonical()).filter(|peer| Some(*peer) != client) {
+        detail["peer"] = serde_json::json!(peer);
+    }
+}
+
+/// Filters for an audit search; entries come newest first
+#[cfg(feature = "server")]
+#[derive(Debug, Clone, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditQuery {
+    pub actor: Option<String>,
+    pub ip: Option<String>,
+    pub kind: Option<AuditKind>,
+    pub since: Option<DateTime<Utc>>,
+    /// Only entries older than this id, to page through results
+    pub before: Option<i64>,
+    #[serde(default = "default_search_limit")]
+    pub limit: u64,
+}
+
+#[cfg(feature = "server")]
+fn default_search_limit() -> u64 {
+    100
+}
+
+#[cfg(feature = "server")]
+impl Default for AuditQuery {
+    fn default() -> Self {
+        Self { actor: None, ip: None, kind: None, since: None, before: None, limit: default_search_limit() }
+    }
+}
+
+/// Append-only record of logins, lobby joins, game actions, admin changes and games started
+/// from a shared address, kept for abuse
+/// investigation. Handles are cheap to clone.
+#[cfg(feature = "server")]
+#[derive(Clone)]
+pub struct AuditLog {
+    db: DatabaseConnection,
+}
+
+#[cfg(feature = "server")]
+impl AuditLog {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Append an entry. A failed write is logged and never fails the action being audited.
+    pub async fn record(&self, kind: AuditKind, actor: Option<&str>, ip: Option<IpAddr>, detail: serde_json::Value) {
+        let entry = audit_log::ActiveModel {
+            id: sea_orm::ActiveValue::NotSet,
+            kind: Set(kind.as_str().to_string()),
+            actor: Set(actor.map(str::to_string)),
+            ip: Set(ip.map(|ip| ip.to_string())),
+            detail: Set(detail),
+            created_at: Set(Utc::now()),
+        };
+        if let Err(e) = entry.insert(&self.db).await {
+            warn!("Failed to write {} audit entry for {:?}: {}", kind.as_str(), actor, e);
+        }
+    }
+
+    pub async fn search(&self, query: &AuditQuery) -> Result<Vec<audit_log::Model>, DbErr> {
+        let mut select = audit_log::Entity::find();
+        if let Some(actor) = &query.actor {
+            select = select.filter(audit_log::Column::Actor.eq(actor.as_str()));
+        }
+        if let Some(ip) = &query.ip {
+            select = select.filter(audit_log::Column::Ip.eq(ip.as_str()));
+        }
+        if let Some(kind) = query.kind {
+            select = select.filter(audit_log::Column::Kind.eq(kind.as_str()));
+        }
+        if let Some(since) = query.since {
+            select = select.filter(audit_log::Column::CreatedAt.gte(since));
+        }
+        if let Some(before) = query.before {
+            select = select.filter(audit_log::Column::Id.lt(before));
+        }
+        select
+            .order_by_desc(audit_log::Column::Id)
+            .limit(query.limit.min(MAX_SEARCH_LIMIT))
+            .all(&self.db)
+            .await
+    }
+}
@@ -1,3 +1,4 @@
+#[cfg(feature = "server")]
 use argon2::{
     password_hash::{
         rand_core::OsRng,
@@ -6,26 +7,69 @@ use argon2::{
     Argon2
 };
 use serde::{Deserialize, Serialize};
-use jsonwebtoken::{encode, decode, Header, Validation, EncodingKey, DecodingKey};
+#[cfg(feature = "server")]
+use jsonwebtoken::{encode, decode, decode_header, Header, Validation, EncodingKey, DecodingKey};
+#[cfg(feature = "server")]
 use chrono::{Utc, Duration};
 
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct RegisterRequest {
     pub username: String,
     pub password: String,
+    #[serde(default)]
+    pub is_bot: bool,
 }
 
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct LoginRequest {
     pub username: String,
     pub password: String,
 }
 
 #[derive(Debug, Serialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
 pub struct AuthResponse {
     pub token: String,
     pub username: String,
     pub user_id: String,
+    pub role: Role,
+}
+
+/// Permission level of an account; each role can do everything the ones below it can
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    #[default]
+    Player,
+    Moderator,
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Player => "player",
+            Role::Moderator => "moderator",
+            Role::Admin => "admin",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "player" => Some(Role::Player),
+            "moderator" => Some(Role::Moderator),
+            "admin" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+
+    /// Whether this role meets a requirement of `required`
+    pub fn allows(&self, required: Role) -> bool {
+        *self >= required
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -33,10 +77,153 @@ pub struct Claims {
     pub sub: String, // user_id
     pub username: String,
     pub exp: usize,
+    /// Issue time in Unix seconds; 0 for tokens issued before it was recorded
+    #[serde(default)]
+    pub iat: usize,
+    /// Issue time in Unix milliseconds, so a session can be told apart from a reset in the
+    /// same second; 0 for tokens issued before it was recorded
+    #[serde(default)]
+    pub iat_ms: u64,
+    #[serde(default)]
+    pub is_bot: bool,
+    /// Tokens issued before roles existed are player tokens
+    #[serde(default)]
+    pub role: Role,
+}
+
+impl Claims {
+    /// Issue time in Unix milliseconds, from `iat` for tokens that only carry seconds
+    pub fn issued_at_millis(&self) -> i64 {
+        match self.iat_ms {
+            0 => self.iat as i64 * 1000,
+            ms => ms as i64,
+        }
+    }
+}
+
+/// Signing secret used when none is configured; only acceptable in development builds
+#[cfg(feature = "server")]
+pub const DEVELOPMENT_JWT_SECRET: &str = "super_secret_key_change_me_in_prod";
+
+/// An HMAC key tokens can be signed and verified with, named by its `kid`
+#[cfg(feature = "server")]
+#[derive(Clone)]
+pub struct JwtKey {
+    pub kid: String,
+    secret: String,
+}
+
+#[cfg(feature = "server")]
+impl std::fmt::Debug for JwtKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JwtKey").field("kid", &self.kid).finish_non_exhaustive()
+    }
+}
+
+/// The active JWT keys. The first signs new tokens; all of them verify.
+///
+/// Rotate by putting a new key first and keeping the old one until the tokens it signed
+/// have expired.
+#[cfg(feature = "server")]
+#[derive(Debug, Clone)]
+pub struct JwtKeys {
+    keys: Vec<JwtKey>,
+}
+
+#[cfg(feature = "server")]
+impl JwtKeys {
+    /// A single key, as configured by `JWT_SECRET`
+    pub fn single(kid: &str, secret: &str) -> Self {
+        Self { keys: vec![JwtKey { kid: kid.to_string(), secret: secret.to_string() }] }
+    }
+
+    pub fn development() -> Self {
+        Self::single("dev", DEVELOPMENT_JWT_SECRET)
+    }
+
+    /// Parse `kid:secret` pairs separated by commas, signing key first
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut keys: Vec<JwtKey> = Vec::new();
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (kid, secret) = entry.split_once(':')
+                .ok_or_else(|| format!("JWT key '{}' is not in kid:secret form", entry))?;
+            if kid.is_empty() || secret.is_empty() {
+                return Err(format!("JWT key '{}' has an empty kid or secret", entry));
+            }
+            if keys.iter().any(|k| k.kid == kid) {
+                return Err(format!("Duplicate JWT key id '{}'", kid));
+            }
+            keys.push(JwtKey { kid: kid.to_string(), secret: secret.to_string() });
+        }
+        if keys.is_empty() {
+            return Err("No JWT keys given".to_string());
+        }
+        Ok(Self { keys })
+    }
+
+    /// Whether any active key is the built-in development secret
+    pub fn uses_development_secret(&self) -> bool {
+        self.keys.iter().any(|k| k.secret == DEVELOPMENT_JWT_SECRET)
+    }
+
+    pub fn signing_kid(&self) -> &str {
+        &self.keys[0].kid
+    }
+
+    pub fn create_jwt(&self, user_id: &str, username: &str, is_bot: bool, role: Role) -> Result<String, String> {
+        let now = Utc::now();
+        let expiration = now
+            .checked_add_signed(Duration::hours(24))
+            .expect("valid timestamp")
+            .timestamp();
+
+        let claims = Claims {
+            sub: user_id.to_owned(),
+            username: username.to_owned(),
+            exp: expiration as usize,
+            iat: now.timestamp() as usize,
+            iat_ms: now.timestamp_millis() as u64,
+            is_bot,
+            role,
+        };
+
+        let key = &self.keys[0];
+        let header = Header { kid: Some(key.kid.clone()), ..Header::default() };
+        encode(&header, &claims, &EncodingKey::from_secret(key.secret.as_bytes()))
+            .map_err(|e| e.to_string())
+    }
+
+    /// Verify a token with the key its `kid` names; tokens without one are tried against every key
+    pub fn verify_jwt(&self, token: &str) -> Result<Claims, String> {
+        let header = decode_header(token).map_err(|e| e.to_string())?;
+        let candidates: Vec<&JwtKey> = match header.kid.as_deref() {
+            Some(kid) => self.keys.iter().filter(|k| k.kid == kid).collect(),
+            None => self.keys.iter().collect(),
+        };
+        if candidates.is_empty() {
+            return Err("Unknown signing key".to_string());
+        }
+
+        let validation = Validation::default();
+        let mut last_error = String::new();
+        for key in candidates {
+            match decode::<Claims>(token, &DecodingKey::from_secret(key.secret.as_bytes()), &validation) {
+                Ok(data) => return Ok(data.claims),
+                Err(e) => last_error = e.to_string(),
+            }
+        }
+        Err(last_error)
+    }
 }
 
-const JWT_SECRET: &str = "super_secret_key_change_me_in_prod";
+#[cfg(feature = "server")]
+impl Default for JwtKeys {
+    fn default() -> Self {
+        Self::development()
+    }
+}
 
+#[cfg(feature = "server")]
 pub fn hash_password(password: &str) -> Result<String, String> {
     let salt = SaltString::generate(&mut OsRng);
     let argon2 = Argon2::default();
@@ -46,6 +233,7 @@ pub fn hash_password(password: &str) -> Result<String, String> {
         .map_err(|e| e.to_string())
 }
 
+#[cfg(feature = "server")]
 pub fn verify_password(password: &str, password_hash: &str) -> Result<bool, String> {
     let parsed_hash = PasswordHash::new(password_hash)
         .map_err(|e| e.to_string())?;
@@ -55,25 +243,45 @@ pub fn verify_password(password: &str, password_hash: &str) -> Result<bool, Stri
         .is_ok())
 }
 
-pub fn create_jwt(user_id: &str, username: &str) -> Result<String, String> {
-    let expiration = Utc::now()
-        .checked_add_signed(Duration::hours(24))
-        .expect("valid timestamp")
-        .timestamp();
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
 
-    let claims = Claims {
-        sub: user_id.to_owned(),
-        username: username.to_owned(),
-        exp: expiration as usize,
-    };
+    #[test]
+    fn test_rotated_keys_verify_old_tokens() {
+        let old = JwtKeys::parse("k1:first-secret").unwrap();
+        let rotated = JwtKeys::parse("k2:second-secret, k1:first-secret").unwrap();
+        assert_eq!(rotated.signing_kid(), "k2");
 
-    encode(&Header::default(), &claims, &EncodingKey::from_secret(JWT_SECRET.as_bytes()))
-        .map_err(|e| e.to_string())
-}
+        let old_token = old.create_jwt("u1", "alice", false, Role::Player).unwrap();
+        assert_eq!(rotated.verify_jwt(&old_token).unwrap().sub, "u1");
 
-pub fn verify_jwt(token: &str) -> Result<Claims, String> {
-    let validation = Validation::default();
-    decode::<Claims>(token, &DecodingKey::from_secret(JWT_SECRET.as_bytes()), &validation)
-        .map(|data| data.claims)
-        .map_err(|e| e.to_string())
+        let new_token = rotated.create_jwt("u2", "bob", false, Role::Admin).unwrap();
+        assert_eq!(decode_header(&new_token).unwrap().kid.as_deref(), Some("k2"));
+        assert!(old.verify_jwt(&new_token).is_err());
+
+        // Retiring k1 ends the sessions it signed
+        let retired = JwtKeys::parse("k2:second-secret").unwrap();
+        assert!(retired.verify_jwt(&old_token).is_err());
+        assert_eq!(retired.verify_jwt(&new_token).unwrap().role, Role::Admin);
+    }
+
+    #[test]
+    fn test_tokens_without_kid_try_every_key() {
+        let claims = Claims { sub: "u1".into(), username: "alice".into(), exp: usize::MAX, iat: 0, iat_ms: 0, is_bot: false, role: Role::Player };
+        let legacy = encode(&Header::default(), &claims, &EncodingKey::from_secret(b"legacy")).unwrap();
+
+        assert!(JwtKeys::parse("new:fresh,old:legacy").unwrap().verify_jwt(&legacy).is_ok());
+        assert!(JwtKeys::parse("new:fresh").unwrap().verify_jwt(&legacy).is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_key_lists() {
+        assert!(JwtKeys::parse("").is_err());
+        assert!(JwtKeys::parse("no-separator").is_err());
+        assert!(JwtKeys::parse("a:x,a:y").is_err());
+        assert!(JwtKeys::parse(":secret").is_err());
+        assert!(JwtKeys::development().uses_development_secret());
+        assert!(!JwtKeys::single("primary", "real").uses_development_secret());
+    }
 }
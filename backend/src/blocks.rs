@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use sea_orm::{
+    sea_query::OnConflict, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, Set,
+};
+use serde::Serialize;
+use uuid::Uuid;
+use crate::entities::{user, user_block};
+use crate::connection::PlayerId;
+
+/// A user on someone's block list
+#[derive(Debug, Clone, Serialize)]
+pub struct BlockedUser {
+    pub user_id: Uuid,
+    pub username: String,
+    pub blocked_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Block `blocked_id` for the user. Blocking also ends any friendship between the two, so
+/// neither keeps seeing the other's presence. Blocking someone twice is a no-op.
+pub async fn block_user(db: &DatabaseConnection, user_id: Uuid, blocked_id: Uuid) -> Result<(), DbErr> {
+    let row = user_block::ActiveModel {
+        user_id: Set(user_id),
+        blocked_id: Set(blocked_id),
+        created_at: Set(chrono::Utc::now()),
+    };
+    user_block::Entity::insert(row)
+        .on_conflict(
+            OnConflict::columns([user_block::Column::UserId, user_block::Column::BlockedId])
+                .do_nothing()
+                .to_owned(),
+        )
+        .do_nothing()
+        .exec(db)
+        .await?;
+
+    crate::friends::remove_friend(db, user_id, blocked_id).await?;
+    crate::friends::remove_friend(db, blocked_id, user_id).await?;
+    Ok(())
+}
+
+/// Lift a block; false if the user was not blocked
+pub async fn unblock_user(db: &DatabaseConnection, user_id: Uuid, blocked_id: Uuid) -> Result<bool, DbErr> {
+    let result = user_block::Entity::delete_many()
+        .filter(user_block::Column::UserId.eq(user_id))
+        .filter(user_block::Column::BlockedId.eq(blocked_id))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected > 0)
+}
+
+/// The user's block list, most recent first
+pub async fn list_blocked(db: &DatabaseConnection, user_id: Uuid) -> Result<Vec<BlockedUser>, DbErr> {
+    let rows = user_block::Entity::find()
+        .filter(user_block::Column::UserId.eq(user_id))
+        .order_by_desc(user_block::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    let names: HashMap<Uuid, String> = user::Entity::find()
+        .filter(user::Column::Id.is_in(rows.iter().map(|r| r.blocked_id)))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|u| (u.id, u.username))
+        .collect();
+
+    Ok(rows.into_iter()
+        .filter_map(|block| names.get(&block.blocked_id).map(|username| BlockedUser {
+            user_id: block.blocked_id,
+            username: username.clone(),
+            blocked_at: block.created_at,
+        }))
+        .collect())
+}
+
+/// Whether `user` has blocked `other`; guests can neither block nor be blocked
+pub async fn has_blocked(db: &DatabaseConnection, user: &PlayerId, other: &PlayerId) -> Result<bool, DbErr> {
+    let (Ok(user_id), Ok(other_id)) = (Uuid::parse_str(user), Uuid::parse_str(other)) else {
+        return Ok(false);
+    };
+    let count = user_block::Entity::find()
+        .filter(user_block::Column::UserId.eq(user_id))
+        .filter(user_block::Column::BlockedId.eq(other_id))
+        .count(db)
+        .await?;
+    Ok(count > 0)
+}
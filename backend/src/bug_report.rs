@@ -0,0 +1,239 @@
+use std::collections::{HashMap, VecDeque};
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait, QueryOrder, QuerySelect, Set};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::connection::PlayerId;
+use crate::entities::bug_report;
+use crate::game::GameId;
+use crate::engine::card::{Card, Suit};
+use crate::engine::{GamePhase, GameState};
+use crate::protocol::{GameSettings, PlayerAction};
+use tracing::error;
+
+/// Number of recent events kept per game for bug reports
+pub const RECENT_EVENT_LIMIT: usize = 50;
+
+/// Reports filed per game before further problems are only logged
+pub const MAX_REPORTS_PER_GAME: usize = 3;
+
+/// Hands and trump as dealt at the start of a round
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DealRecord {
+    pub round_number: usize,
+    pub trump_suit: Option<Suit>,
+    pub hands: HashMap<String, Vec<Card>>,
+}
+
+/// An action that was applied to the game state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionRecord {
+    pub version: u64,
+    pub player: String,
+    pub action: PlayerAction,
+    /// Applied by the server on turn timeout
+    pub auto: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub at: DateTime<Utc>,
+    pub version: u64,
+    pub description: String,
+}
+
+/// Per-game record of deals, actions and recent events, kept so a problem can be replayed.
+///
+/// Players are recorded as seat labels (`seat0`, `seat1`, ...) rather than ids.
+#[derive(Debug, Clone, Default)]
+pub struct GameJournal {
+    players: Vec<PlayerId>,
+    deals: Vec<DealRecord>,
+    actions: Vec<ActionRecord>,
+    events: VecDeque<EventRecord>,
+    reports_filed: usize,
+}
+
+impl GameJournal {
+    pub fn new(players: Vec<PlayerId>) -> Self {
+        Self { players, ..Default::default() }
+    }
+
+    /// Someone new took over a seat; they keep its label
+    pub fn replace_player(&mut self, old: &PlayerId, new: &PlayerId) {
+        if let Some(seat) = self.players.iter_mut().find(|p| *p == old) {
+            *seat = new.clone();
+        }
+    }
+
+    /// Seat label used in place of a player id
+    fn seat(&self, player_id: &PlayerId) -> String {
+        match self.players.iter().position(|p| p == player_id) {
+            Some(index) => format!("seat{}", index),
+            None => "unseated".to_string(),
+        }
+    }
+
+    /// Replace every player id in free text with its seat label
+    fn redact(&self, text: &str) -> String {
+        self.players.iter().fold(text.to_string(), |acc, pid| acc.replace(pid.as_str(), &self.seat(pid)))
+    }
+
+    /// Record the hands dealt at the start of the current round
+    pub fn record_deal(&mut self, state: &GameState) {
+        let hands = state.hands.iter()
+            .map(|(pid, hand)| (self.seat(pid), hand.cards().to_vec()))
+            .collect();
+        self.deals.push(DealRecord {
+            round_number: state.round_number,
+            trump_suit: state.trump_suit,
+            hands,
+        });
+        self.record_event(state.version, format!("Round {} dealt", state.round_number));
+    }
+
+    pub fn record_action(&mut self, version: u64, player_id: &PlayerId, action: &PlayerAction, auto: bool) {
+        self.actions.push(ActionRecord {
+            version,
+            player: self.seat(player_id),
+            action: action.clone(),
+            auto,
+        });
+        let kind = if auto { "Auto action" } else { "Action" };
+        self.record_event(version, format!("{} by {}: {:?}", kind, self.seat(player_id), action));
+    }
+
+    pub fn record_event(&mut self, version: u64, description: String) {
+        let description = self.redact(&description);
+        self.events.push_back(EventRecord { at: Utc::now(), version, description });
+        while self.events.len() > RECENT_EVENT_LIMIT {
+            self.events.pop_front();
+        }
+    }
+
+    /// Build a redacted report, or None once this game has used up its report quota
+    pub fn build_report(
+        &mut self,
+        game_id: GameId,
+        reason: &str,
+        settings: &GameSettings,
+        state: &GameState,
+    ) -> Option<BugReportBundle> {
+        if self.reports_filed >= MAX_REPORTS_PER_GAME {
+            return None;
+        }
+        self.reports_filed += 1;
+
+        let reason = self.redact(reason);
+        self.record_event(state.version, format!("Bug report filed: {}", reason));
+
+        Some(BugReportBundle {
+            game_id,
+            reason,
+            created_at: Utc::now(),
+            // Decks are not seeded yet; the recorded deals stand in for the seed
+            seed: None,
+            settings: settings.clone(),
+            state_version: state.version,
+            round_number: state.round_number,
+            phase: state.phase,
+            deals: self.deals.clone(),
+            actions: self.actions.clone(),
+            recent_events: self.events.iter().cloned().collect(),
+        })
+    }
+}
+
+/// Everything needed to reproduce a game problem, with player ids replaced by seat labels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BugReportBundle {
+    pub game_id: GameId,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+    pub seed: Option<u64>,
+    pub settings: GameSettings,
+    pub state_version: u64,
+    pub round_number: usize,
+    pub phase: GamePhase,
+    pub deals: Vec<DealRecord>,
+    pub actions: Vec<ActionRecord>,
+    pub recent_events: Vec<EventRecord>,
+}
+
+/// Store a report and alert admins through the error log
+pub async fn file_report(db: &DatabaseConnection, bundle: BugReportBundle) -> Result<Uuid, DbErr> {
+    let id = Uuid::new_v4();
+    error!(
+        target: "bug_report",
+        report_id = %id,
+        game_id = %bundle.game_id,
+        "Bug report filed: {}",
+        bundle.reason
+    );
+
+    let model = bug_report::ActiveModel {
+        id: Set(id),
+        game_id: Set(bundle.game_id),
+        reason: Set(bundle.reason.clone()),
+        bundle: Set(serde_json::json!(bundle)),
+        created_at: Set(Utc::now()),
+    };
+    model.insert(db).await?;
+    Ok(id)
+}
+
+/// Most recent reports first
+pub async fn recent_reports(db: &DatabaseConnection, limit: u64) -> Result<Vec<bug_report::Model>, DbErr> {
+    bug_report::Entity::find()
+        .order_by_desc(bug_report::Column::CreatedAt)
+        .limit(limit)
+        .all(db)
+        .await
+}
+
+pub async fn get_report(db: &DatabaseConnection, id: Uuid) -> Result<Option<bug_report::Model>, DbErr> {
+    bug_report::Entity::find_by_id(id).one(db).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::bidding::Bid;
+
+    #[test]
+    fn test_report_redacts_player_ids() {
+        let players = vec!["alice-id".to_string(), "bob-id".to_string()];
+        let state = GameState::new(players.clone());
+        let mut journal = GameJournal::new(players.clone());
+        journal.record_deal(&state);
+        journal.record_action(1, &players[0], &PlayerAction::Bid(Bid { tricks: 1 }), false);
+
+        let bundle = journal
+            .build_report(Uuid::new_v4(), "Player bob-id is confused", &GameSettings::default(), &state)
+            .unwrap();
+        let json = serde_json::to_string(&bundle).unwrap();
+
+        assert!(!json.contains("alice-id"));
+        assert!(!json.contains("bob-id"));
+        assert_eq!(bundle.reason, "Player seat1 is confused");
+        assert_eq!(bundle.actions[0].player, "seat0");
+        assert!(bundle.deals[0].hands.contains_key("seat1"));
+    }
+
+    #[test]
+    fn test_report_quota_and_event_limit() {
+        let players = vec!["a".to_string(), "b".to_string()];
+        let state = GameState::new(players.clone());
+        let mut journal = GameJournal::new(players);
+
+        for i in 0..(RECENT_EVENT_LIMIT * 2) {
+            journal.record_event(i as u64, "tick".to_string());
+        }
+        assert_eq!(journal.events.len(), RECENT_EVENT_LIMIT);
+
+        for _ in 0..MAX_REPORTS_PER_GAME {
+            assert!(journal.build_report(Uuid::new_v4(), "boom", &GameSettings::default(), &state).is_some());
+        }
+        assert!(journal.build_report(Uuid::new_v4(), "boom", &GameSettings::default(), &state).is_none());
+    }
+}
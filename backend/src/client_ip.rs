@@ -0,0 +1,153 @@
+use std::net::{IpAddr, SocketAddr};
+use axum::extract::ConnectInfo;
+use axum::http::{Extensions, HeaderMap};
+
+/// Header a proxy appends the address it received the request from to
+pub const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+
+/// Header a proxy sets to the address it received the request from
+pub const REAL_IP_HEADER: &str = "x-real-ip";
+
+/// An address range such as `10.0.0.0/8`; a bare address is a range of one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid = || format!("'{}' is not an address or range like 10.0.0.0/8", spec);
+        let (address, prefix) = match spec.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix.parse::<u8>().map_err(|_| invalid())?)),
+            None => (spec, None),
+        };
+        let network = address.parse::<IpAddr>().map_err(|_| invalid())?.to_canonical();
+        let bits = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(bits);
+        if prefix > bits {
+            return Err(invalid());
+        }
+        Ok(Self { network, prefix })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Reverse proxies whose forwarding headers are believed. Behind one, every connection comes
+/// from the proxy's address, so rate limits and shared-address checks need the client's.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrustedProxies(Vec<Cidr>);
+
+impl TrustedProxies {
+    /// Comma separated addresses and ranges; empty trusts no proxy
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(Cidr::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn trusts(&self, ip: IpAddr) -> bool {
+        self.0.iter().any(|range| range.contains(ip))
+    }
+
+    /// The client behind `peer`. From a trusted proxy that is the last `X-Forwarded-For` hop
+    /// no trusted proxy added, else `X-Real-IP`; anyone else is the client themselves.
+    pub fn client_ip(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        let peer = peer.to_canonical();
+        if !self.trusts(peer) {
+            return peer;
+        }
+        let hops: Vec<&str> = headers.get_all(FORWARDED_FOR_HEADER).iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .collect();
+        // Hops are appended, so everything left of an unreadable one is unchecked
+        for hop in hops.iter().rev() {
+            match hop.parse::<IpAddr>().map(|ip| ip.to_canonical()) {
+                Ok(ip) if !self.trusts(ip) => return ip,
+                Ok(_) => continue,
+                Err(_) => break,
+            }
+        }
+        headers.get(REAL_IP_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<IpAddr>().ok())
+            .map_or(peer, |ip| ip.to_canonical())
+    }
+
+    /// The client behind an HTTP request; None if it was served without connection info
+    pub fn request_client_ip(&self, extensions: &Extensions, headers: &HeaderMap) -> Option<IpAddr> {
+        extensions.get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| self.client_ip(addr.ip(), headers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn test_parse_ranges() {
+        let proxies = TrustedProxies::parse("10.0.0.0/8, 127.0.0.1,fd00::/8").unwrap();
+        assert!(proxies.trusts(ip("10.1.2.3")));
+        assert!(proxies.trusts(ip("::ffff:127.0.0.1")));
+        assert!(proxies.trusts(ip("fd12::1")));
+        assert!(!proxies.trusts(ip("11.0.0.1")));
+        assert!(!proxies.trusts(ip("127.0.0.2")));
+        assert!(TrustedProxies::parse("").unwrap().is_empty());
+        assert!(TrustedProxies::parse("0.0.0.0/0").unwrap().trusts(ip("203.0.113.9")));
+        assert!(TrustedProxies::parse("10.0.0.0/33").is_err());
+        assert!(TrustedProxies::parse("nginx").is_err());
+    }
+
+    #[test]
+    fn test_client_ip_only_believes_trusted_proxies() {
+        let proxies = TrustedProxies::parse("172.16.0.0/12").unwrap();
+        let forwarded = headers(&[("x-forwarded-for", "198.51.100.1, 203.0.113.5"), ("x-real-ip", "203.0.113.5")]);
+
+        // The client may have sent its own X-Forwarded-For; only the hop the proxy added counts
+        assert_eq!(proxies.client_ip(ip("172.18.0.3"), &forwarded), ip("203.0.113.5"));
+        // Chained proxies are skipped
+        let chained = headers(&[("x-forwarded-for", "203.0.113.5"), ("x-forwarded-for", "172.18.0.7")]);
+        assert_eq!(proxies.client_ip(ip("172.18.0.3"), &chained), ip("203.0.113.5"));
+        assert_eq!(proxies.client_ip(ip("172.18.0.3"), &headers(&[("x-real-ip", "203.0.113.6")])), ip("203.0.113.6"));
+        assert_eq!(proxies.client_ip(ip("172.18.0.3"), &headers(&[("x-forwarded-for", "junk")])), ip("172.18.0.3"));
+
+        // Anyone else's headers are ignored
+        assert_eq!(proxies.client_ip(ip("198.51.100.1"), &forwarded), ip("198.51.100.1"));
+        assert_eq!(TrustedProxies::default().client_ip(ip("172.18.0.3"), &forwarded), ip("172.18.0.3"));
+    }
+}
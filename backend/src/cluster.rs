@@ -0,0 +1,212 @@
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::Utc;
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait, QueryFilter, ColumnTrait, Set};
+use sea_orm::sea_query::OnConflict;
+use crate::entities::node;
+use crate::game::GameManager;
+use crate::region::Region;
+use tracing::{debug, warn};
+
+/// Heartbeats older than this are treated as a dead node
+const STALE_AFTER_SECS: i64 = 30;
+
+/// Load reported by one backend instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeLoad {
+    pub node_id: String,
+    pub public_url: String,
+    pub active_games: usize,
+    pub max_games: usize,
+    pub region: Option<Region>,
+}
+
+impl NodeLoad {
+    /// Fraction of capacity in use
+    pub fn load(&self) -> f64 {
+        if self.max_games == 0 {
+            return 1.0;
+        }
+        self.active_games as f64 / self.max_games as f64
+    }
+
+    pub fn has_capacity(&self) -> bool {
+        self.active_games < self.max_games
+    }
+}
+
+/// Pick the node with the lowest load that can still take a game; ties keep the earlier node
+pub fn least_loaded(nodes: &[NodeLoad]) -> Option<&NodeLoad> {
+    least_loaded_where(nodes, |_| true)
+}
+
+/// The least loaded node in `region` that can take a game, else the least loaded anywhere
+pub fn least_loaded_near<'a>(nodes: &'a [NodeLoad], region: Option<&Region>) -> Option<&'a NodeLoad> {
+    region
+        .and_then(|region| least_loaded_where(nodes, |n| n.region.as_ref() == Some(region)))
+        .or_else(|| least_loaded(nodes))
+}
+
+fn least_loaded_where(nodes: &[NodeLoad], keep: impl Fn(&NodeLoad) -> bool) -> Option<&NodeLoad> {
+    nodes.iter()
+        .filter(|n| n.has_capacity() && keep(n))
+        .fold(None, |best: Option<&NodeLoad>, n| match best {
+            Some(b) if b.load() <= n.load() => Some(b),
+            _ => Some(n),
+        })
+}
+
+/// Membership of this instance in the shared node registry (the `nodes` table)
+pub struct ClusterRegistry {
+    db: DatabaseConnection,
+    node_id: String,
+    public_url: String,
+    max_games: usize,
+    region: Option<Region>,
+}
+
+impl ClusterRegistry {
+    pub fn new(db: DatabaseConnection, node_id: String, public_url: String, max_games: usize, region: Option<Region>) -> Self {
+        Self { db, node_id, public_url, max_games, region }
+    }
+
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// Publish this node's current load
+    pub async fn heartbeat(&self, active_games: usize) -> Result<(), DbErr> {
+        let model = node::ActiveModel {
+            id: Set(self.node_id.clone()),
+            public_url: Set(self.public_url.clone()),
+            active_games: Set(active_games as i32),
+            max_games: Set(self.max_games as i32),
+            region: Set(self.region.as_ref().map(|r| r.to_string())),
+            last_heartbeat: Set(Utc::now()),
+        };
+
+        node::Entity::insert(model)
+            .on_conflict(
+                OnConflict::column(node::Column::Id)
+                    .update_columns([
+                        node::Column::PublicUrl,
+                        node::Column::ActiveGames,
+                        node::Column::MaxGames,
+                        node::Column::Region,
+                        node::Column::LastHeartbeat,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// All nodes with a recent heartbeat
+    pub async fn live_nodes(&self) -> Result<Vec<NodeLoad>, DbErr> {
+        let cutoff = Utc::now() - chrono::Duration::seconds(STALE_AFTER_SECS);
+        let rows = node::Entity::find()
+            .filter(node::Column::LastHeartbeat.gte(cutoff))
+            .all(&self.db)
+            .await?;
+
+        Ok(rows.into_iter()
+            .map(|row| NodeLoad {
+                node_id: row.id,
+                public_url: row.public_url,
+                active_games: row.active_games.max(0) as usize,
+                max_games: row.max_games.max(0) as usize,
+                region: row.region.as_deref().and_then(Region::parse),
+            })
+            .collect())
+    }
+
+    /// Another node that should host the next game for a player in `region` instead of this
+    /// one, if any. Nodes in the player's region are preferred while they have capacity.
+    /// This node wins ties, and registry failures keep placement local.
+    pub async fn placement_hint(&self, local_active_games: usize, region: Option<&Region>) -> Option<NodeLoad> {
+        let remote = match self.live_nodes().await {
+            Ok(nodes) => nodes,
+            Err(e) => {
+                warn!("Failed to read node registry: {}", e);
+                return None;
+            }
+        };
+
+        let mut nodes = vec![NodeLoad {
+            node_id: self.node_id.clone(),
+            public_url: self.public_url.clone(),
+            active_games: local_active_games,
+            max_games: self.max_games,
+            region: self.region.clone(),
+        }];
+        nodes.extend(remote.into_iter().filter(|n| n.node_id != self.node_id));
+
+        let best = least_loaded_near(&nodes, region)?;
+        if best.node_id == self.node_id {
+            return None;
+        }
+        debug!("Node {} is less loaded than {}", best.node_id, self.node_id);
+        Some(best.clone())
+    }
+}
+
+/// Publish this node's load to the registry every `interval`
+pub fn spawn_heartbeat_task(registry: Arc<ClusterRegistry>, game_manager: Arc<GameManager>, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let active_games = game_manager.get_stats().await.active_games;
+            if let Err(e) = registry.heartbeat(active_games).await {
+                warn!("Failed to publish node heartbeat: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str, active_games: usize, max_games: usize) -> NodeLoad {
+        NodeLoad {
+            node_id: id.to_string(),
+            public_url: format!("wss://{}.example.com", id),
+            active_games,
+            max_games,
+            region: None,
+        }
+    }
+
+    #[test]
+    fn test_least_loaded_uses_relative_load() {
+        let nodes = vec![node("a", 50, 100), node("b", 60, 200), node("c", 10, 10)];
+        assert_eq!(least_loaded(&nodes).unwrap().node_id, "b");
+    }
+
+    #[test]
+    fn test_least_loaded_prefers_first_on_tie() {
+        let nodes = vec![node("a", 5, 10), node("b", 10, 20)];
+        assert_eq!(least_loaded(&nodes).unwrap().node_id, "a");
+    }
+
+    #[test]
+    fn test_least_loaded_skips_full_nodes() {
+        let nodes = vec![node("a", 10, 10), node("b", 0, 0)];
+        assert!(least_loaded(&nodes).is_none());
+    }
+
+    #[test]
+    fn test_least_loaded_near_prefers_the_region_while_it_has_room() {
+        let eu = Region::parse("eu-west");
+        let mut nodes = vec![node("a", 1, 10), node("b", 8, 10)];
+        nodes[1].region = eu.clone();
+        assert_eq!(least_loaded_near(&nodes, eu.as_ref()).unwrap().node_id, "b");
+        assert_eq!(least_loaded_near(&nodes, None).unwrap().node_id, "a");
+
+        nodes[1].active_games = 10;
+        assert_eq!(least_loaded_near(&nodes, eu.as_ref()).unwrap().node_id, "a");
+    }
+}
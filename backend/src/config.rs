@@ -1,31 +1,363 @@
 use crate::server::ServerConfig;
+use crate::protocol::GameSettings;
+use crate::settings_policy::SettingsPolicy;
+use crate::rate_limit::RateLimitConfig;
+use crate::public_api::PublicApiConfig;
+use crate::auth::JwtKeys;
+use crate::features::Feature;
+use crate::tls::TlsConfig;
+use crate::cors::CorsOrigins;
+use crate::client_ip::TrustedProxies;
+use crate::database::{DatabaseKind, DEFAULT_DATABASE_URL};
+use crate::error::ServerError;
+use crate::region::Region;
+use config::{Config, File, FileFormat, Map, Value};
+use serde::de::DeserializeOwned;
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
 use std::env;
+use std::str::FromStr;
 
-pub fn load_config() -> ServerConfig {
-    let host = env::var("SERVER_HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
-    
-    let port = env::var("SERVER_PORT")
-        .ok()
-        .and_then(|p| p.parse().ok())
-        .unwrap_or(8080);
-    
-    let max_connections = env::var("MAX_CONNECTIONS")
-        .ok()
-        .and_then(|m| m.parse().ok())
-        .unwrap_or(1000);
-    
-    let turn_timeout_secs = env::var("TURN_TIMEOUT_SECS")
-        .ok()
-        .and_then(|t| t.parse().ok())
-        .unwrap_or(30);
-    
-    let log_level = env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string());
-    
-    ServerConfig {
+/// Read when CONFIG_FILE is unset; the server starts without it
+pub const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+/// Load settings from the TOML file named by CONFIG_FILE (default `config.toml`), with
+/// environment variables taking precedence over the file. A value that does not parse, or a
+/// key the server does not know, is an error naming it.
+pub fn load_config() -> Result<ServerConfig, ServerError> {
+    let (path, required) = match env::var("CONFIG_FILE").ok().filter(|p| !p.is_empty()) {
+        Some(path) => (path, true),
+        None => (DEFAULT_CONFIG_FILE.to_string(), false),
+    };
+    let file = Config::builder()
+        .add_source(File::new(&path, FileFormat::Toml).required(required))
+        .build()
+        .map_err(|e| ServerError::Config(format!("Cannot load {}: {}", path, e)))?;
+    build(&Layers::new(file, path, |name| env::var(name).ok()))
+}
+
+/// Parse `toml` as the config file, with `env` standing in for the environment
+pub fn load_config_from(toml: &str, env: &HashMap<&str, &str>) -> Result<ServerConfig, ServerError> {
+    let file = Config::builder()
+        .add_source(File::from_str(toml, FileFormat::Toml))
+        .build()
+        .map_err(|e| ServerError::Config(format!("Cannot parse config: {}", e)))?;
+    build(&Layers::new(file, DEFAULT_CONFIG_FILE.to_string(), |name| env.get(name).map(|v| v.to_string())))
+}
+
+/// Looks a setting up in the environment, then the config file, and remembers which file
+/// keys exist so typos can be reported
+struct Layers<E> {
+    file: Config,
+    path: String,
+    env: E,
+    known: RefCell<BTreeSet<&'static str>>,
+}
+
+impl<E: Fn(&str) -> Option<String>> Layers<E> {
+    fn new(file: Config, path: String, env: E) -> Self {
+        Self { file, path, env, known: RefCell::new(BTreeSet::new()) }
+    }
+
+    fn env(&self, name: &str) -> Option<String> {
+        (self.env)(name).filter(|v| !v.is_empty())
+    }
+
+    /// A bad value that may have come from either layer
+    fn invalid(&self, key: &str, var: &str, message: impl std::fmt::Display) -> ServerError {
+        ServerError::Config(format!("{} ({} in {}): {}", var, key, self.path, message))
+    }
+
+    fn invalid_in_file(&self, key: &str, message: impl std::fmt::Display) -> ServerError {
+        ServerError::Config(format!("{} in {}: {}", key, self.path, message))
+    }
+
+    fn file_value(&self, key: &'static str) -> Option<Value> {
+        self.known.borrow_mut().insert(key);
+        self.file.get::<Value>(key).ok()
+    }
+
+    /// `key` in the file, overridden by the `var` environment variable
+    fn get<T>(&self, key: &'static str, var: &str) -> Result<Option<T>, ServerError>
+    where
+        T: FromStr + DeserializeOwned,
+    {
+        let from_file = self.file_value(key);
+        if let Some(raw) = self.env(var) {
+            return raw.trim().parse().map(Some)
+                .map_err(|_| ServerError::Config(format!("{}: '{}' is not a valid value", var, raw)));
+        }
+        from_file
+            .map(|value| value.try_deserialize().map_err(|e| self.invalid_in_file(key, e)))
+            .transpose()
+    }
+
+    fn get_or<T>(&self, key: &'static str, var: &str, default: T) -> Result<T, ServerError>
+    where
+        T: FromStr + DeserializeOwned,
+    {
+        Ok(self.get(key, var)?.unwrap_or(default))
+    }
+
+    /// A list: comma separated in the environment, an array or a comma separated string in the file
+    fn list(&self, key: &'static str, var: &str) -> Result<String, ServerError> {
+        let from_file = self.file_value(key);
+        if let Some(raw) = self.env(var) {
+            return Ok(raw);
+        }
+        let Some(value) = from_file else {
+            return Ok(String::new());
+        };
+        match value.clone().into_array() {
+            Ok(items) => items
+                .into_iter()
+                .map(|item| item.into_string().map_err(|e| self.invalid_in_file(key, e)))
+                .collect::<Result<Vec<_>, _>>()
+                .map(|items| items.join(",")),
+            Err(_) => value.into_string().map_err(|e| self.invalid_in_file(key, e)),
+        }
+    }
+
+    /// File keys no setting reads, e.g. `server.prot`
+    fn unknown_keys(&self) -> Vec<String> {
+        let known = self.known.borrow();
+        let mut leaves = Vec::new();
+        collect_leaves("", self.file.clone().try_deserialize::<Map<String, Value>>().unwrap_or_default(), &mut leaves);
+        leaves.retain(|key| !known.contains(key.as_str()));
+        leaves.sort();
+        leaves
+    }
+}
+
+fn collect_leaves(prefix: &str, table: Map<String, Value>, leaves: &mut Vec<String>) {
+    for (name, value) in table {
+        let key = if prefix.is_empty() { name } else { format!("{}.{}", prefix, name) };
+        match value.clone().into_table() {
+            Ok(table) => collect_leaves(&key, table, leaves),
+            Err(_) => leaves.push(key),
+        }
+    }
+}
+
+fn build<E: Fn(&str) -> Option<String>>(layers: &Layers<E>) -> Result<ServerConfig, ServerError> {
+    let host = layers.get_or("server.host", "SERVER_HOST", "0.0.0.0".to_string())?;
+    let port = layers.get_or("server.port", "SERVER_PORT", 8080)?;
+    let max_connections = layers.get_or("server.max_connections", "MAX_CONNECTIONS", 1000)?;
+    let log_level = layers.get_or("server.log_level", "LOG_LEVEL", "info".to_string())?;
+    let lan_discovery = layers.get_or("server.lan_discovery", "LAN_DISCOVERY", false)?;
+    let server_name = layers.get_or("server.name", "SERVER_NAME", "German Bridge".to_string())?;
+
+    // Comma separated; unset allows any origin without credentials
+    let cors_origins = CorsOrigins::parse(&layers.list("server.cors_allowed_origins", "CORS_ALLOWED_ORIGINS")?)
+        .map_err(|e| layers.invalid("server.cors_allowed_origins", "CORS_ALLOWED_ORIGINS", e))?;
+    // Comma separated addresses and ranges; unset believes no forwarding headers
+    let trusted_proxies = TrustedProxies::parse(&layers.list("server.trusted_proxies", "TRUSTED_PROXIES")?)
+        .map_err(|e| layers.invalid("server.trusted_proxies", "TRUSTED_PROXIES", e))?;
+
+    // Both or neither; serving plain HTTP by mistake must not pass for TLS
+    let tls = match (
+        layers.get::<String>("tls.cert_path", "TLS_CERT_PATH")?.filter(|p| !p.is_empty()),
+        layers.get::<String>("tls.key_path", "TLS_KEY_PATH")?.filter(|p| !p.is_empty()),
+    ) {
+        (Some(cert_path), Some(key_path)) => Some(TlsConfig { cert_path: cert_path.into(), key_path: key_path.into() }),
+        (None, None) => None,
+        _ => return Err(ServerError::Config("TLS_CERT_PATH (tls.cert_path) and TLS_KEY_PATH (tls.key_path) must be set together".to_string())),
+    };
+
+    let database_url = layers.get_or("database.url", "DATABASE_URL", DEFAULT_DATABASE_URL.to_string())?;
+    DatabaseKind::from_url(&database_url).map_err(|e| layers.invalid("database.url", "DATABASE_URL", e))?;
+
+    let admin_token = layers.get::<String>("auth.admin_token", "ADMIN_TOKEN")?.filter(|t| !t.is_empty());
+
+    // JWT_KEYS ("kid:secret,..." with the signing key first) takes precedence over JWT_SECRET
+    let jwt_keys = layers.list("auth.jwt_keys", "JWT_KEYS")?;
+    let jwt_secret = layers.get::<String>("auth.jwt_secret", "JWT_SECRET")?.filter(|s| !s.is_empty());
+    let jwt_keys = if !jwt_keys.is_empty() {
+        JwtKeys::parse(&jwt_keys).map_err(|e| layers.invalid("auth.jwt_keys", "JWT_KEYS", e))?
+    } else {
+        jwt_secret.map(|secret| JwtKeys::single("primary", &secret)).unwrap_or_default()
+    };
+
+    let default_rate_limit = RateLimitConfig::default();
+    let rate_limit = RateLimitConfig {
+        burst: layers.get_or("rate_limit.burst", "RATE_LIMIT_BURST", default_rate_limit.burst)?,
+        per_sec: layers.get_or("rate_limit.per_sec", "RATE_LIMIT_PER_SEC", default_rate_limit.per_sec)?,
+        max_violations: layers.get_or("rate_limit.max_violations", "RATE_LIMIT_MAX_VIOLATIONS", default_rate_limit.max_violations)?,
+    };
+    let bot_messages_per_sec = layers.get_or("rate_limit.bot_messages_per_sec", "BOT_MESSAGES_PER_SEC", 5)?;
+
+    let default_public_api = PublicApiConfig::default();
+    let public_api = PublicApiConfig {
+        burst: layers.get_or("public_api.burst", "PUBLIC_API_BURST", default_public_api.burst)?,
+        per_sec: layers.get_or("public_api.per_sec", "PUBLIC_API_PER_SEC", default_public_api.per_sec)?,
+        cache_ttl: layers
+            .get("public_api.cache_secs", "PUBLIC_API_CACHE_SECS")?
+            .map(std::time::Duration::from_secs)
+            .unwrap_or(default_public_api.cache_ttl),
+    };
+
+    let ping_interval_secs = layers.get_or("timeouts.ping_interval_secs", "PING_INTERVAL_SECS", 15)?;
+    let max_missed_pongs = layers.get_or("timeouts.max_missed_pongs", "MAX_MISSED_PONGS", 3)?;
+    let lobby_list_interval_ms = layers.get_or("timeouts.lobby_list_interval_ms", "LOBBY_LIST_INTERVAL_MS", 250)?;
+    // 0 turns bot backfill off
+    let queue_backfill_secs = layers.get_or("timeouts.queue_backfill_secs", "QUEUE_BACKFILL_SECS", 60)?;
+    // Players are matched with other regions after this wait; 0 ignores regions
+    let matchmaking_region_wait_secs = layers.get_or("timeouts.matchmaking_region_wait_secs", "MATCHMAKING_REGION_WAIT_SECS", 20)?;
+    // How long running games get to finish after SIGTERM before they are saved for later
+    let shutdown_grace_secs = layers.get_or("timeouts.shutdown_grace_secs", "SHUTDOWN_GRACE_SECS", 30)?;
+    // How long the turn clock of a player who drops on their turn stands still; 0 never pauses it
+    let disconnect_grace_secs = layers.get_or("timeouts.disconnect_grace_secs", "DISCONNECT_GRACE_SECS", 30)?;
+    // Lobbies without joins, leaves or ready changes for this long are closed; 0 keeps them
+    let lobby_idle_minutes = layers.get_or("timeouts.lobby_idle_minutes", "LOBBY_IDLE_MINUTES", 30)?;
+
+    // Comma separated feature names, applied on top of the defaults; a typo must not
+    // leave a subsystem in the wrong state
+    let (features, unknown) = Feature::configure(
+        &layers.list("features.enabled", "FEATURES_ENABLED")?,
+        &layers.list("features.disabled", "FEATURES_DISABLED")?,
+    );
+    if !unknown.is_empty() {
+        return Err(ServerError::Config(format!(
+            "Unknown features in FEATURES_ENABLED or FEATURES_DISABLED (features.enabled, features.disabled): {}",
+            unknown.join(", ")
+        )));
+    }
+
+    let region = layers.get::<String>("node.region", "NODE_REGION")?
+        .map(|r| Region::parse(&r).ok_or_else(|| layers.invalid("node.region", "NODE_REGION", format!("'{}' is not a region name", r))))
+        .transpose()?;
+    let node_id = layers.get::<String>("node.id", "NODE_ID")?.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let node_public_url = layers.get::<String>("node.public_url", "NODE_PUBLIC_URL")?.filter(|u| !u.is_empty());
+    let node_max_games = layers.get_or("node.max_games", "NODE_MAX_GAMES", 500)?;
+
+    let soft_delete_retention_days = layers.get_or("retention.soft_delete_days", "SOFT_DELETE_RETENTION_DAYS", 30)?;
+    let leaderboard_refresh_secs = layers.get_or("leaderboard.refresh_secs", "LEADERBOARD_REFRESH_SECS", 300)?;
+
+    let fallback = SettingsPolicy::default();
+    let turn_timeout_secs = layers.get_or("game.turn_timeout_secs", "TURN_TIMEOUT_SECS", 30)?;
+    let default_player_count = layers.get_or("game.default_player_count", "DEFAULT_PLAYER_COUNT", fallback.defaults.player_count)?;
+    let min_players = layers.get_or("game.min_players", "MIN_PLAYERS", fallback.min_players)?;
+    let max_players = layers.get_or("game.max_players", "MAX_PLAYERS", fallback.max_players)?;
+    let min_turn_timeout_secs = layers.get_or("game.min_turn_timeout_secs", "MIN_TURN_TIMEOUT_SECS", fallback.min_turn_timeout_secs)?;
+    let max_turn_timeout_secs = layers.get_or("game.max_turn_timeout_secs", "MAX_TURN_TIMEOUT_SECS", fallback.max_turn_timeout_secs)?;
+    if min_players > max_players {
+        return Err(layers.invalid("game.max_players", "MAX_PLAYERS", format!("{} is below min_players {}", max_players, min_players)));
+    }
+    if min_turn_timeout_secs > max_turn_timeout_secs {
+        return Err(layers.invalid(
+            "game.max_turn_timeout_secs",
+            "MAX_TURN_TIMEOUT_SECS",
+            format!("{} is below min_turn_timeout_secs {}", max_turn_timeout_secs, min_turn_timeout_secs),
+        ));
+    }
+
+    let game_settings = SettingsPolicy::new(
+        GameSettings {
+            player_count: default_player_count,
+            turn_timeout_secs,
+            ..GameSettings::default()
+        },
+        min_players,
+        max_players,
+        min_turn_timeout_secs,
+        max_turn_timeout_secs,
+    );
+
+    let unknown = layers.unknown_keys();
+    if !unknown.is_empty() {
+        return Err(ServerError::Config(format!("Unknown keys in {}: {}", layers.path, unknown.join(", "))));
+    }
+
+    Ok(ServerConfig {
         host,
         port,
         max_connections,
         turn_timeout_secs,
         log_level,
+        bot_messages_per_sec,
+        admin_token,
+        soft_delete_retention_days,
+        node_id,
+        node_public_url,
+        node_max_games,
+        leaderboard_refresh_secs,
+        game_settings,
+        rate_limit,
+        ping_interval_secs,
+        max_missed_pongs,
+        lobby_list_interval_ms,
+        jwt_keys,
+        public_api,
+        features,
+        lan_discovery,
+        server_name,
+        queue_backfill_secs,
+        region,
+        matchmaking_region_wait_secs,
+        shutdown_grace_secs,
+        disconnect_grace_secs,
+        lobby_idle_minutes,
+        tls,
+        cors_origins,
+        trusted_proxies,
+        database_url,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        [server]
+        port = 9000
+        cors_allowed_origins = ["https://bridge.example.com"]
+
+        [database]
+        url = "sqlite://bridge.db?mode=rwc"
+
+        [auth]
+        jwt_keys = ["v2:new-secret", "v1:old-secret"]
+
+        [game]
+        default_player_count = 5
+        turn_timeout_secs = 45
+    "#;
+
+    #[test]
+    fn test_file_values_apply_and_env_overrides_them() {
+        let config = load_config_from(CONFIG, &HashMap::new()).unwrap();
+        assert_eq!(config.port, 9000);
+        assert_eq!(config.host, "0.0.0.0");
+        assert_eq!(config.database_url, "sqlite://bridge.db?mode=rwc");
+        assert_eq!(config.jwt_keys.signing_kid(), "v2");
+        assert_eq!(config.game_settings.defaults.player_count, 5);
+        assert_eq!(config.turn_timeout_secs, 45);
+        assert!(matches!(config.cors_origins, CorsOrigins::List(ref origins) if origins.len() == 1));
+
+        let env = HashMap::from([("SERVER_PORT", "9100"), ("DEFAULT_PLAYER_COUNT", "3")]);
+        let config = load_config_from(CONFIG, &env).unwrap();
+        assert_eq!(config.port, 9100);
+        assert_eq!(config.game_settings.defaults.player_count, 3);
+    }
+
+    #[test]
+    fn test_example_config_loads() {
+        let config = load_config_from(include_str!("../config.example.toml"), &HashMap::new()).unwrap();
+        assert_eq!(config.port, 8080);
+        assert_eq!(config.cors_origins, CorsOrigins::Any);
+    }
+
+    #[test]
+    fn test_errors_name_the_offending_key() {
+        let error = |toml: &str, env: &[(&str, &str)]| {
+            load_config_from(toml, &env.iter().copied().collect()).err().unwrap().to_string()
+        };
+        assert!(error("[server]\nport = \"eighty\"", &[]).contains("server.port"));
+        assert!(error("", &[("SERVER_PORT", "eighty")]).contains("SERVER_PORT"));
+        assert!(error("[server]\nprot = 9000", &[]).contains("server.prot"));
+        assert!(error("[game]\nmin_players = 5\nmax_players = 3", &[]).contains("game.max_players"));
+        assert!(error("[database]\nurl = \"mysql://db\"", &[]).contains("database.url"));
+        assert!(error("[tls]\ncert_path = \"cert.pem\"", &[]).contains("TLS_KEY_PATH"));
     }
 }
@@ -1,29 +1,105 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
-use tokio::sync::{RwLock, mpsc};
+use tokio::sync::{RwLock, broadcast, mpsc};
 use uuid::Uuid;
 use axum::extract::ws::Message;
 use crate::protocol::ServerMessage;
+use crate::user_cache::UserCache;
+use crate::accessibility::SuitEncoding;
+use crate::rate_limit::{RateDecision, RateLimitConfig, RateLimiter};
+use crate::room::{RoomId, RoomRegistry};
+use crate::room_feed::{self, FeedControl, RoomMessage};
+use crate::outbound::OutboundSender;
+use crate::presence::PresenceSignal;
+use crate::auth::Role;
+use crate::region::Region;
 use tracing::{debug, warn, info};
 
-pub type PlayerId = String;
+pub use crate::engine::PlayerId;
 
 const DEFAULT_RECONNECT_TIMEOUT_SECS: u64 = 60;
+const DEFAULT_DISCONNECT_GRACE_SECS: u64 = 30;
+/// Longest User-Agent kept for a session
+pub const MAX_USER_AGENT_LEN: usize = 256;
+
+/// A session's socket closed or a new one took its place
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionChange {
+    Disconnected(PlayerId),
+    Reconnected(PlayerId),
+}
 
 pub struct ConnectionManager {
     sessions: Arc<RwLock<HashMap<PlayerId, PlayerSession>>>,
     reconnect_timeout: Duration,
+    /// How long the turn clock of a player who drops on their turn stands still
+    disconnect_grace: Duration,
+    session_changes: broadcast::Sender<SessionChange>,
+    user_cache: Arc<UserCache>,
+    rate_limiter: RateLimiter,
+    rooms: Arc<RoomRegistry>,
+    presence: PresenceSignal,
+    admission: Arc<Admission>,
+}
+
+/// Caps the WebSocket connections open at once
+struct Admission {
+    max: usize,
+    open: AtomicUsize,
+    rejected: AtomicU64,
+}
+
+/// Holds one of the `max_connections` places for as long as the socket is open
+pub struct ConnectionSlot {
+    admission: Arc<Admission>,
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.admission.open.fetch_sub(1, Ordering::AcqRel);
+    }
 }
 
 pub struct PlayerSession {
     pub id: PlayerId,
     pub username: String,
-    pub ws_sender: mpsc::UnboundedSender<Message>,
+    pub ws_sender: OutboundSender,
+    /// Forwards the lobby and game broadcasts this player receives
+    feed: mpsc::UnboundedSender<FeedControl>,
     pub connected_at: Instant,
     pub last_activity: Instant,
     pub is_active: bool,
     pub disconnected_at: Option<Instant>,
+    /// Set when the session is resumed; game actions must carry a state version until cleared
+    pub needs_resync: bool,
+    /// Connected with a bot account token
+    pub is_bot: bool,
+    /// Suit rendering chosen for this session; None until loaded or negotiated
+    pub suit_encoding: Option<SuitEncoding>,
+    /// Keepalive pings sent since the client was last heard from
+    pub missed_pongs: u32,
+    /// Role from the account's token
+    pub role: Role,
+    /// Coarse location, used to match nearby players first
+    pub region: Option<Region>,
+    /// Address the client connected from, as forwarded by a trusted proxy
+    pub address: Option<std::net::IpAddr>,
+    /// Address the socket itself came from; the proxy's when the client's was forwarded
+    pub peer_address: Option<std::net::IpAddr>,
+    /// User-Agent header the client connected with, cut to `MAX_USER_AGENT_LEN` characters
+    pub user_agent: Option<String>,
+    /// When the outstanding keepalive ping went out
+    pub ping_sent_at: Option<Instant>,
+    /// Round trip of the last answered keepalive ping
+    pub rtt: Option<Duration>,
+}
+
+impl Default for ConnectionManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ConnectionManager {
@@ -32,35 +108,116 @@ impl ConnectionManager {
     }
 
     pub fn with_reconnect_timeout(reconnect_timeout: Duration) -> Self {
+        let presence = PresenceSignal::new();
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             reconnect_timeout,
+            disconnect_grace: Duration::from_secs(DEFAULT_DISCONNECT_GRACE_SECS),
+            session_changes: broadcast::channel(256).0,
+            user_cache: Arc::new(UserCache::new()),
+            rate_limiter: RateLimiter::new(RateLimitConfig::default()),
+            rooms: Arc::new(RoomRegistry::with_presence(presence.clone())),
+            presence,
+            admission: Arc::new(Admission { max: usize::MAX, open: AtomicUsize::new(0), rejected: AtomicU64::new(0) }),
+        }
+    }
+
+    /// Refuse WebSocket connections beyond `max` open at once
+    pub fn with_max_connections(mut self, max: usize) -> Self {
+        self.admission = Arc::new(Admission { max, open: AtomicUsize::new(0), rejected: AtomicU64::new(0) });
+        self
+    }
+
+    /// Take a place for a new WebSocket, or `None` when `max_connections` are already open
+    pub fn try_admit(&self) -> Option<ConnectionSlot> {
+        let admission = &self.admission;
+        let admitted = admission.open
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |open| (open < admission.max).then_some(open + 1))
+            .is_ok();
+        if !admitted {
+            admission.rejected.fetch_add(1, Ordering::Relaxed);
+            return None;
         }
+        Some(ConnectionSlot { admission: Arc::clone(admission) })
+    }
+
+    /// Pause the turn clock of a player who drops on their turn for `grace`; zero turns pausing off
+    pub fn with_disconnect_grace(mut self, grace: Duration) -> Self {
+        self.disconnect_grace = grace;
+        self
+    }
+
+    pub fn disconnect_grace(&self) -> Duration {
+        self.disconnect_grace
+    }
+
+    /// Sessions losing and regaining their socket, for the game manager's turn clocks
+    pub fn session_changes(&self) -> broadcast::Receiver<SessionChange> {
+        self.session_changes.subscribe()
+    }
+
+    /// Replace the default per-connection message limits
+    pub fn with_rate_limit(mut self, config: RateLimitConfig) -> Self {
+        self.rate_limiter = RateLimiter::new(config);
+        self
+    }
+
+    /// Lobby and game membership shared with the lobby and game managers
+    pub fn rooms(&self) -> Arc<RoomRegistry> {
+        self.rooms.clone()
+    }
+
+    /// Fires when a player connects, disconnects, expires or changes rooms
+    pub fn presence_signal(&self) -> PresenceSignal {
+        self.presence.clone()
+    }
+
+    /// Charge one incoming message to the player's and the client address's buckets
+    pub fn check_rate(&self, player_id: &PlayerId, ip: Option<std::net::IpAddr>) -> RateDecision {
+        self.rate_limiter.check(player_id, ip)
     }
 
     /// Register a new player connection with a random ID and return it
-    pub async fn add_player(&self, ws_sender: mpsc::UnboundedSender<Message>) -> PlayerId {
+    pub async fn add_player(&self, ws_sender: OutboundSender) -> PlayerId {
         let player_id = Uuid::new_v4().to_string();
         self.register_player(player_id.clone(), "Guest".to_string(), ws_sender).await;
         player_id
     }
 
     /// Register a player with a specific ID (used for auth)
-    pub async fn register_player(&self, player_id: PlayerId, username: String, ws_sender: mpsc::UnboundedSender<Message>) {
+    pub async fn register_player(&self, player_id: PlayerId, username: String, ws_sender: OutboundSender) {
         let now = Instant::now();
-        
+        let feed = room_feed::spawn(player_id.clone(), ws_sender.clone());
+        self.rooms.attach_feed(&player_id, feed.clone());
+
         let session = PlayerSession {
             id: player_id.clone(),
             username: username.clone(),
             ws_sender,
+            feed,
             connected_at: now,
             last_activity: now,
             is_active: true,
             disconnected_at: None,
+            needs_resync: false,
+            is_bot: false,
+            role: Role::Player,
+            suit_encoding: None,
+            missed_pongs: 0,
+            region: None,
+            address: None,
+            peer_address: None,
+            user_agent: None,
+            ping_sent_at: None,
+            rtt: None,
         };
         
         let mut sessions = self.sessions.write().await;
         sessions.insert(player_id.clone(), session);
+        drop(sessions);
+
+        self.user_cache.set_username(player_id.clone(), username.clone()).await;
+        self.presence.notify(&player_id);
         
         debug!("Player {} ({}) connected", player_id, username);
     }
@@ -69,7 +226,9 @@ impl ConnectionManager {
     pub async fn remove_player(&self, player_id: PlayerId) {
         let mut sessions = self.sessions.write().await;
         if sessions.remove(&player_id).is_some() {
+            self.rooms.detach_feed(&player_id);
             debug!("Player {} removed", player_id);
+            self.presence.notify(&player_id);
         }
     }
 
@@ -96,6 +255,18 @@ impl ConnectionManager {
         }
     }
 
+    /// Send an Error and then close the player's socket
+    pub async fn close_connection(&self, player_id: &PlayerId, reason: &str) {
+        use axum::extract::ws::{close_code, CloseFrame};
+
+        self.send_to_player(player_id.clone(), ServerMessage::Error { message: reason.to_string(), request_id: None }).await;
+        let sessions = self.sessions.read().await;
+        if let Some(session) = sessions.get(player_id) {
+            let frame = CloseFrame { code: close_code::POLICY, reason: reason.to_string().into() };
+            let _ = session.ws_sender.send(Message::Close(Some(frame)));
+        }
+    }
+
     /// Broadcast a message to multiple players
     pub async fn broadcast_to_players(&self, player_ids: &[PlayerId], msg: ServerMessage) {
         let json = match serde_json::to_string(&msg) {
@@ -111,7 +282,7 @@ impl ConnectionManager {
         for player_id in player_ids {
             if let Some(session) = sessions.get(player_id) {
                 if session.is_active {
-                    if let Err(e) = session.ws_sender.send(Message::Text(json.clone())) {
+                    if let Err(e) = session.ws_sender.send_droppable(Message::Text(json.clone())) {
                         warn!("Failed to broadcast to player {}: {}", player_id, e);
                     }
                 }
@@ -119,15 +290,37 @@ impl ConnectionManager {
         }
     }
 
+    /// Broadcast a message to everyone currently in a lobby or game. It is published once on
+    /// the room's channel; each member's feed delivers it.
+    pub async fn broadcast_to_room(&self, room: RoomId, msg: ServerMessage) {
+        match serde_json::to_string(&msg) {
+            Ok(json) => {
+                self.rooms.publish(room, RoomMessage::Everyone(json.into()));
+            }
+            Err(e) => warn!("Failed to serialize broadcast message: {}", e),
+        }
+    }
+
+    /// Tell everyone who shares a lobby or game with the player
+    pub async fn broadcast_to_room_mates(&self, player_id: &PlayerId, msg: ServerMessage) {
+        let mates = self.rooms.room_mates(player_id);
+        self.broadcast_to_players(&mates, msg).await;
+    }
+
     /// Mark a player as inactive (disconnected)
     pub async fn mark_inactive(&self, player_id: PlayerId) -> Vec<PlayerId> {
         let mut sessions = self.sessions.write().await;
         let mut other_players = Vec::new();
         
         if let Some(session) = sessions.get_mut(&player_id) {
+            if session.is_active {
+                let _ = self.session_changes.send(SessionChange::Disconnected(player_id.clone()));
+            }
             session.is_active = false;
             session.disconnected_at = Some(Instant::now());
+            let _ = session.feed.send(FeedControl::Detach);
             info!("Player {} marked as inactive", player_id);
+            self.presence.notify(&player_id);
             
             // Collect all other active players to notify
             for (id, s) in sessions.iter() {
@@ -141,7 +334,7 @@ impl ConnectionManager {
     }
 
     /// Reconnect a player with a new WebSocket sender
-    pub async fn reconnect_player(&self, player_id: PlayerId, ws_sender: mpsc::UnboundedSender<Message>) -> Option<Vec<PlayerId>> {
+    pub async fn reconnect_player(&self, player_id: PlayerId, ws_sender: OutboundSender) -> Option<Vec<PlayerId>> {
         let mut sessions = self.sessions.write().await;
         
         if let Some(session) = sessions.get_mut(&player_id) {
@@ -153,11 +346,16 @@ impl ConnectionManager {
                 }
             }
             
+            let _ = session.feed.send(FeedControl::Attach(ws_sender.clone()));
             session.ws_sender = ws_sender;
             session.is_active = true;
             session.last_activity = Instant::now();
             session.disconnected_at = None;
+            session.needs_resync = true;
+            session.missed_pongs = 0;
             info!("Player {} reconnected", player_id);
+            self.presence.notify(&player_id);
+            let _ = self.session_changes.send(SessionChange::Reconnected(player_id.clone()));
             
             // Collect all other active players to notify
             let mut other_players = Vec::new();
@@ -173,12 +371,166 @@ impl ConnectionManager {
         }
     }
 
+    /// Whether a resumed session still has to prove it is acting on current state
+    pub async fn needs_resync(&self, player_id: &PlayerId) -> bool {
+        let sessions = self.sessions.read().await;
+        sessions.get(player_id).map(|s| s.needs_resync).unwrap_or(false)
+    }
+
+    /// Clear the resync requirement once the player has fresh state
+    pub async fn clear_resync(&self, player_id: &PlayerId) {
+        let mut sessions = self.sessions.write().await;
+        if let Some(session) = sessions.get_mut(player_id) {
+            session.needs_resync = false;
+        }
+    }
+
+    /// Flag a session as belonging to a bot account
+    pub async fn set_bot(&self, player_id: &PlayerId, is_bot: bool) {
+        let mut sessions = self.sessions.write().await;
+        if let Some(session) = sessions.get_mut(player_id) {
+            session.is_bot = is_bot;
+        }
+    }
+
+    /// Record the role the player's token was issued with
+    pub async fn set_role(&self, player_id: &PlayerId, role: Role) {
+        let mut sessions = self.sessions.write().await;
+        if let Some(session) = sessions.get_mut(player_id) {
+            session.role = role;
+        }
+    }
+
+    /// The player's role; unknown players are plain players
+    pub async fn role(&self, player_id: &PlayerId) -> Role {
+        let sessions = self.sessions.read().await;
+        sessions.get(player_id).map(|s| s.role).unwrap_or_default()
+    }
+
+    /// Check whether a player is connected with a bot account
+    pub async fn is_bot(&self, player_id: &PlayerId) -> bool {
+        let sessions = self.sessions.read().await;
+        sessions.get(player_id).map(|s| s.is_bot).unwrap_or(false)
+    }
+
+    pub async fn set_suit_encoding(&self, player_id: &PlayerId, encoding: SuitEncoding) {
+        let mut sessions = self.sessions.write().await;
+        if let Some(session) = sessions.get_mut(player_id) {
+            session.suit_encoding = Some(encoding);
+        }
+    }
+
+    /// The session's suit rendering, if one has been loaded or negotiated
+    pub async fn suit_encoding(&self, player_id: &PlayerId) -> Option<SuitEncoding> {
+        let sessions = self.sessions.read().await;
+        sessions.get(player_id).and_then(|s| s.suit_encoding)
+    }
+
+    pub async fn set_address(&self, player_id: &PlayerId, address: std::net::IpAddr) {
+        let mut sessions = self.sessions.write().await;
+        if let Some(session) = sessions.get_mut(player_id) {
+            session.address = Some(address);
+        }
+    }
+
+    /// Address of the player's current or last connection
+    pub async fn address(&self, player_id: &PlayerId) -> Option<std::net::IpAddr> {
+        let sessions = self.sessions.read().await;
+        sessions.get(player_id).and_then(|s| s.address)
+    }
+
+    pub async fn set_peer_address(&self, player_id: &PlayerId, peer: std::net::IpAddr) {
+        let mut sessions = self.sessions.write().await;
+        if let Some(session) = sessions.get_mut(player_id) {
+            session.peer_address = Some(peer);
+        }
+    }
+
+    /// Address the player's current or last socket came from, before forwarding headers
+    pub async fn peer_address(&self, player_id: &PlayerId) -> Option<std::net::IpAddr> {
+        let sessions = self.sessions.read().await;
+        sessions.get(player_id).and_then(|s| s.peer_address)
+    }
+
+    pub async fn set_user_agent(&self, player_id: &PlayerId, user_agent: Option<&str>) {
+        let mut sessions = self.sessions.write().await;
+        if let Some(session) = sessions.get_mut(player_id) {
+            session.user_agent = user_agent.map(|agent| agent.chars().take(MAX_USER_AGENT_LEN).collect());
+        }
+    }
+
+    /// User-Agent of the player's current or last connection
+    pub async fn user_agent(&self, player_id: &PlayerId) -> Option<String> {
+        let sessions = self.sessions.read().await;
+        sessions.get(player_id).and_then(|s| s.user_agent.clone())
+    }
+
+    pub async fn set_region(&self, player_id: &PlayerId, region: Option<Region>) {
+        let mut sessions = self.sessions.write().await;
+        if let Some(session) = sessions.get_mut(player_id) {
+            session.region = region;
+        }
+    }
+
+    pub async fn region(&self, player_id: &PlayerId) -> Option<Region> {
+        let sessions = self.sessions.read().await;
+        sessions.get(player_id).and_then(|s| s.region.clone())
+    }
+
+    /// Measure the round trip of the keepalive ping the client just answered
+    pub async fn record_pong(&self, player_id: &PlayerId) {
+        let mut sessions = self.sessions.write().await;
+        if let Some(session) = sessions.get_mut(player_id) {
+            if let Some(sent_at) = session.ping_sent_at.take() {
+                session.rtt = Some(sent_at.elapsed());
+            }
+        }
+    }
+
+    /// Last measured round trip to the player, in milliseconds
+    pub async fn latency_ms(&self, player_id: &PlayerId) -> Option<u32> {
+        let sessions = self.sessions.read().await;
+        sessions.get(player_id).and_then(|s| s.rtt).map(|rtt| rtt.as_millis().min(u32::MAX as u128) as u32)
+    }
+
     /// Update last activity timestamp for a player
     pub async fn update_activity(&self, player_id: PlayerId) {
         let mut sessions = self.sessions.write().await;
         if let Some(session) = sessions.get_mut(&player_id) {
             session.last_activity = Instant::now();
+            // Any frame from the client, pongs included, proves the connection is alive
+            session.missed_pongs = 0;
+        }
+    }
+
+    /// Send a keepalive ping to every active session.
+    ///
+    /// Sessions that left `max_missed` pings in a row unanswered get a Close frame and are
+    /// marked inactive, which starts their reconnect window. Returns the dropped players.
+    pub async fn ping_sessions(&self, max_missed: u32) -> Vec<PlayerId> {
+        use axum::extract::ws::{close_code, CloseFrame};
+
+        let mut unresponsive = Vec::new();
+        {
+            let mut sessions = self.sessions.write().await;
+            for (player_id, session) in sessions.iter_mut().filter(|(_, s)| s.is_active) {
+                if session.missed_pongs >= max_missed {
+                    let frame = CloseFrame { code: close_code::AWAY, reason: "Keepalive timeout".into() };
+                    let _ = session.ws_sender.send(Message::Close(Some(frame)));
+                    unresponsive.push(player_id.clone());
+                } else {
+                    session.missed_pongs += 1;
+                    session.ping_sent_at = Some(Instant::now());
+                    let _ = session.ws_sender.send(Message::Ping(Vec::new()));
+                }
+            }
+        }
+
+        for player_id in &unresponsive {
+            warn!("Player {} missed {} keepalive pings; marking inactive", player_id, max_missed);
+            self.mark_inactive(player_id.clone()).await;
         }
+        unresponsive
     }
 
     /// Check for expired inactive sessions and remove them
@@ -192,6 +544,8 @@ impl ConnectionManager {
                 if let Some(disconnected_at) = session.disconnected_at {
                     if now.duration_since(disconnected_at) > self.reconnect_timeout {
                         info!("Removing expired session for player {}", player_id);
+                        self.rooms.detach_feed(player_id);
+                        self.presence.notify(player_id);
                         expired_players.push(player_id.clone());
                         return false;
                     }
@@ -199,10 +553,22 @@ impl ConnectionManager {
             }
             true
         });
+        drop(sessions);
+        self.rate_limiter.prune();
         
         expired_players
     }
 
+    /// Whether the player has a live connection
+    pub async fn is_connected(&self, player_id: &PlayerId) -> bool {
+        self.sessions.read().await.get(player_id).is_some_and(|s| s.is_active)
+    }
+
+    /// Whether the player is connected or still inside their reconnect window
+    pub async fn has_session(&self, player_id: &PlayerId) -> bool {
+        self.sessions.read().await.contains_key(player_id)
+    }
+
     /// Get all active player IDs
     pub async fn get_active_players(&self) -> Vec<PlayerId> {
         let sessions = self.sessions.read().await;
@@ -214,8 +580,12 @@ impl ConnectionManager {
 
     /// Get username for a player ID
     pub async fn get_username(&self, player_id: &PlayerId) -> Option<String> {
-        let sessions = self.sessions.read().await;
-        sessions.get(player_id).map(|session| session.username.clone())
+        self.user_cache.get(player_id).await.map(|profile| profile.username)
+    }
+
+    /// Shared profile cache, also populated by the auth handlers
+    pub fn user_cache(&self) -> Arc<UserCache> {
+        Arc::clone(&self.user_cache)
     }
 
     /// Get connection statistics
@@ -231,13 +601,37 @@ impl ConnectionManager {
             total_connections,
             active_connections,
             inactive_connections,
+            open_sockets: self.admission.open.load(Ordering::Acquire),
+            max_connections: (self.admission.max != usize::MAX).then_some(self.admission.max),
+            rejected_connections: self.admission.rejected.load(Ordering::Relaxed),
         }
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
 pub struct ConnectionStats {
     pub total_connections: usize,
     pub active_connections: usize,
     pub inactive_connections: usize,
+    /// WebSockets open right now, counted against `max_connections`
+    pub open_sockets: usize,
+    /// Unset when connections are not capped
+    pub max_connections: Option<usize>,
+    /// Upgrades refused since startup because the server was full
+    pub rejected_connections: u64,
+}
+
+/// Ping every connection each `interval` and drop the ones that stopped answering
+pub fn spawn_keepalive_task(connection_manager: Arc<ConnectionManager>, interval: Duration, max_missed: u32) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            for player_id in connection_manager.ping_sessions(max_missed).await {
+                connection_manager
+                    .broadcast_to_room_mates(&player_id, ServerMessage::PlayerLeft { player_id: player_id.clone() })
+                    .await;
+            }
+        }
+    });
 }
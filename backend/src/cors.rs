@@ -0,0 +1,108 @@
+use axum::http::{header, HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// Which browser origins may call the HTTP API
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CorsOrigins {
+    /// Any origin, without credentials; fine for development and public read-only servers
+    #[default]
+    Any,
+    /// Only these origins, which may send cookies and `Authorization` headers
+    List(Vec<HeaderValue>),
+}
+
+impl CorsOrigins {
+    /// Comma separated origins such as `https://bridge.example.com`; empty or `*` allows any
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        if spec.is_empty() || spec == "*" {
+            return Ok(CorsOrigins::Any);
+        }
+
+        let mut origins = Vec::new();
+        for origin in spec.split(',').map(str::trim).filter(|o| !o.is_empty()) {
+            // Browsers send the origin without a path, so a trailing slash would never match
+            let origin = origin.trim_end_matches('/');
+            let scheme_ok = origin.starts_with("http://") || origin.starts_with("https://");
+            let host = origin.split_once("://").map(|(_, host)| host).unwrap_or("");
+            if !scheme_ok || host.is_empty() || host.contains('/') || origin == "*" {
+                return Err(format!("'{}' is not an origin like https://bridge.example.com", origin));
+            }
+            let value = HeaderValue::from_str(origin).map_err(|_| format!("'{}' is not a valid origin", origin))?;
+            if !origins.contains(&value) {
+                origins.push(value);
+            }
+        }
+        if origins.is_empty() {
+            return Ok(CorsOrigins::Any);
+        }
+        Ok(CorsOrigins::List(origins))
+    }
+
+    pub fn layer(&self) -> CorsLayer {
+        let layer = CorsLayer::new()
+            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::PATCH, Method::DELETE, Method::OPTIONS])
+            .allow_headers([
+                header::CONTENT_TYPE,
+                header::AUTHORIZATION,
+                HeaderName::from_static("x-admin-token"),
+            ]);
+        match self {
+            CorsOrigins::Any => layer.allow_origin(AllowOrigin::any()),
+            // Credentials are never allowed with a wildcard origin, browsers reject that
+            CorsOrigins::List(origins) => layer.allow_origin(AllowOrigin::list(origins.iter().cloned())).allow_credentials(true),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_parse_accepts_origins_and_rejects_urls() {
+        assert_eq!(CorsOrigins::parse(""), Ok(CorsOrigins::Any));
+        assert_eq!(CorsOrigins::parse("*"), Ok(CorsOrigins::Any));
+        assert_eq!(
+            CorsOrigins::parse("https://bridge.example.com/, http://localhost:5173,https://bridge.example.com"),
+            Ok(CorsOrigins::List(vec![
+                HeaderValue::from_static("https://bridge.example.com"),
+                HeaderValue::from_static("http://localhost:5173"),
+            ]))
+        );
+        assert!(CorsOrigins::parse("bridge.example.com").is_err());
+        assert!(CorsOrigins::parse("https://bridge.example.com/app").is_err());
+        assert!(CorsOrigins::parse("https://a.example.com,*").is_err());
+    }
+
+    async fn preflight(origins: &CorsOrigins, origin: &str) -> (Option<String>, Option<String>) {
+        let app = Router::new().route("/api/profile", get(|| async { "" })).layer(origins.layer());
+        let request = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/api/profile")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let header = |name| response.headers().get(name).map(|v: &HeaderValue| v.to_str().unwrap().to_string());
+        (header(header::ACCESS_CONTROL_ALLOW_ORIGIN), header(header::ACCESS_CONTROL_ALLOW_CREDENTIALS))
+    }
+
+    #[tokio::test]
+    async fn test_only_listed_origins_are_allowed_with_credentials() {
+        assert_eq!(preflight(&CorsOrigins::Any, "https://evil.example").await, (Some("*".to_string()), None));
+
+        let origins = CorsOrigins::parse("https://bridge.example.com").unwrap();
+        assert_eq!(
+            preflight(&origins, "https://bridge.example.com").await,
+            (Some("https://bridge.example.com".to_string()), Some("true".to_string()))
+        );
+        assert_eq!(preflight(&origins, "https://evil.example").await.0, None);
+    }
+}
@@ -0,0 +1,270 @@
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, QueryFilter, Set, TransactionTrait};
+use sea_orm::sea_query::{Expr, OnConflict};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tracing::warn;
+use uuid::Uuid;
+use crate::achievements::{self, Achievement};
+use crate::deal_review::{self, Deal};
+use crate::entities::{game, game_action, game_player, game_round, lobby, lobby_player, round_deal};
+use crate::transcript::{self, RecordedAction};
+
+/// Delay before the first retry; later retries double it
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(250);
+
+/// Longest wait between two retries of one write
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Unlocks a subscriber can fall behind by before it misses some
+const UNLOCKS_CAPACITY: usize = 256;
+
+/// A lobby or game change to store; each is applied in one transaction. Applying one twice
+/// leaves the same rows as applying it once, so a write whose commit failed ambiguously can
+/// be retried. Achievement progress is the exception: such a retry may count a feat twice.
+#[derive(Debug, Clone)]
+pub enum DbWrite {
+    /// A new lobby with its host as the first member
+    LobbyCreated { lobby: lobby::ActiveModel, host_id: Uuid, joined_at: DateTime<Utc> },
+    LobbyJoined { lobby_id: Uuid, player_id: Uuid, joined_at: DateTime<Utc> },
+    /// The player left or was kicked; their membership row is kept with `left_at` set
+    LobbyLeft { lobby_id: Uuid, player_id: Uuid, left_at: DateTime<Utc> },
+    LobbyHostChanged { lobby_id: Uuid, host_id: Uuid },
+    /// A game started from the lobby, a moderator closed it or it sat idle
+    LobbyClosed { lobby_id: Uuid, closed_at: DateTime<Utc> },
+    /// The last member left; the lobby and that member are soft-deleted so it can be restored
+    /// and still counts in analytics
+    LobbyDeleted { lobby_id: Uuid },
+    /// A finished round, with each seat's `final_score` brought up to the running total
+    RoundFinished { game_id: Uuid, round_number: i32, player_results: serde_json::Value, totals: Vec<(Uuid, i32)> },
+    /// Completion time, outcome and the placed players' final scores and places
+    GameCompleted { game_id: Uuid, completed_at: DateTime<Utc>, outcome: serde_json::Value, placements: Vec<FinalSeat> },
+    /// An applied action for the game's transcript, replacing any row for the same version
+    ActionRecorded { game_id: Uuid, action: RecordedAction },
+    /// A round's deal. The game's deals from that round on are replaced, as a game resumed
+    /// from a checkpoint deals its current round again.
+    DealRecorded { game_id: Uuid, deal: Deal },
+    /// Feats counted towards a user's achievements; the ones this unlocks are announced to
+    /// the outbox's unlock subscribers
+    AchievementsEarned { user_id: Uuid, feats: Vec<Achievement> },
+}
+
+/// Achievements a user unlocked, once the write that unlocked them is stored
+#[derive(Debug, Clone)]
+pub struct Unlocked {
+    pub user_id: Uuid,
+    pub achievements: Vec<Achievement>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FinalSeat {
+    pub player_id: Uuid,
+    pub score: i32,
+    pub place: i32,
+}
+
+impl DbWrite {
+    /// Store the write, returning the achievements it unlocked
+    async fn apply(&self, db: &DatabaseConnection) -> Result<Vec<Achievement>, DbErr> {
+        let txn = db.begin().await?;
+        let mut unlocked = Vec::new();
+        match self {
+            DbWrite::LobbyCreated { lobby, host_id, joined_at } => {
+                lobby::Entity::insert(lobby.clone())
+                    .on_conflict(OnConflict::column(lobby::Column::Id).do_nothing().to_owned())
+                    .exec_without_returning(&txn).await?;
+                insert_member(&txn, *lobby.id.as_ref(), *host_id, *joined_at).await?;
+            }
+            DbWrite::LobbyJoined { lobby_id, player_id, joined_at } => {
+                insert_member(&txn, *lobby_id, *player_id, *joined_at).await?;
+            }
+            DbWrite::LobbyLeft { lobby_id, player_id, left_at } => {
+                lobby_player::Entity::update_many()
+                    .col_expr(lobby_player::Column::LeftAt, Expr::value(*left_at))
+                    .filter(lobby_player::Column::LobbyId.eq(*lobby_id))
+                    .filter(lobby_player::Column::PlayerId.eq(*player_id))
+                    .filter(lobby_player::Column::LeftAt.is_null())
+                    .exec(&txn).await?;
+            }
+            DbWrite::LobbyHostChanged { lobby_id, host_id } => {
+                lobby::Entity::update_many()
+                    .col_expr(lobby::Column::HostId, Expr::value(*host_id))
+                    .filter(lobby::Column::Id.eq(*lobby_id))
+                    .exec(&txn).await?;
+            }
+            DbWrite::LobbyClosed { lobby_id, closed_at } => {
+                lobby::Entity::update_many()
+                    .col_expr(lobby::Column::ClosedAt, Expr::value(*closed_at))
+                    .filter(lobby::Column::Id.eq(*lobby_id))
+                    .exec(&txn).await?;
+            }
+            DbWrite::LobbyDeleted { lobby_id } => {
+                crate::retention::soft_delete_lobby(&txn, *lobby_id).await?;
+            }
+            DbWrite::RoundFinished { game_id, round_number, player_results, totals } => {
+                let round = game_round::ActiveModel {
+                    id: sea_orm::ActiveValue::NotSet,
+                    game_id: Set(*game_id),
+                    round_number: Set(*round_number),
+                    player_results: Set(player_results.clone()),
+                };
+                // A game's round is stored once, keyed by its number
+                game_round::Entity::insert(round)
+                    .on_conflict(OnConflict::columns([game_round::Column::GameId, game_round::Column::RoundNumber]).do_nothing().to_owned())
+                    .exec_without_returning(&txn).await?;
+                for (player_id, total) in totals {
+                    game_player::Entity::update_many()
+                        .col_expr(game_player::Column::FinalScore, Expr::value(*total))
+                        .filter(game_player::Column::GameId.eq(*game_id))
+                        .filter(game_player::Column::PlayerId.eq(*player_id))
+                        .exec(&txn).await?;
+                }
+            }
+            DbWrite::GameCompleted { game_id, completed_at, outcome, placements } => {
+                game::Entity::update_many()
+                    .col_expr(game::Column::CompletedAt, Expr::value(*completed_at))
+                    .col_expr(game::Column::Outcome, Expr::value(outcome.clone()))
+                    .filter(game::Column::Id.eq(*game_id))
+                    .exec(&txn).await?;
+
+                // Forfeited players are not placed and keep no score, so the running totals
+                // written after each round are cleared first
+                game_player::Entity::update_many()
+                    .col_expr(game_player::Column::FinalScore, Expr::value(Option::<i32>::None))
+                    .filter(game_player::Column::GameId.eq(*game_id))
+                    .exec(&txn).await?;
+                for seat in placements {
+                    game_player::Entity::update_many()
+                        .col_expr(game_player::Column::FinalScore, Expr::value(seat.score))
+                        .col_expr(game_player::Column::Placement, Expr::value(seat.place))
+                        .filter(game_player::Column::GameId.eq(*game_id))
+                        .filter(game_player::Column::PlayerId.eq(seat.player_id))
+                        .exec(&txn).await?;
+                }
+            }
+            DbWrite::ActionRecorded { game_id, action } => {
+                game_action::Entity::delete_many()
+                    .filter(game_action::Column::GameId.eq(*game_id))
+                    .filter(game_action::Column::Version.eq(action.version as i64))
+                    .exec(&txn).await?;
+                transcript::record_action(&txn, *game_id, action).await?;
+            }
+            DbWrite::DealRecorded { game_id, deal } => {
+                round_deal::Entity::delete_many()
+                    .filter(round_deal::Column::GameId.eq(*game_id))
+                    .filter(round_deal::Column::RoundNumber.gte(deal.round_number as i32))
+                    .exec(&txn).await?;
+                deal_review::record_deal(&txn, *game_id, deal).await?;
+            }
+            DbWrite::AchievementsEarned { user_id, feats } => {
+                unlocked = achievements::record(&txn, *user_id, feats).await?;
+            }
+        }
+        txn.commit().await?;
+        Ok(unlocked)
+    }
+}
+
+async fn insert_member(txn: &DatabaseTransaction, lobby_id: Uuid, player_id: Uuid, joined_at: DateTime<Utc>) -> Result<(), DbErr> {
+    let member = lobby_player::ActiveModel {
+        lobby_id: Set(lobby_id),
+        player_id: Set(player_id),
+        joined_at: Set(joined_at),
+        left_at: Set(None),
+    };
+    // Rejoining brings back the row kept when the player left
+    lobby_player::Entity::insert(member)
+        .on_conflict(
+            OnConflict::columns([lobby_player::Column::LobbyId, lobby_player::Column::PlayerId])
+                .update_columns([lobby_player::Column::JoinedAt, lobby_player::Column::LeftAt])
+                .to_owned(),
+        )
+        .exec_without_returning(txn).await?;
+    Ok(())
+}
+
+enum Command {
+    Write(DbWrite),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Stores lobby and game changes in the background, in the order they were queued.
+///
+/// A write that fails is retried with exponential backoff, capped at `MAX_RETRY_DELAY`, until
+/// it is stored. It holds back the writes queued after it, so a database outage delays them
+/// instead of losing them. Constraint violations would fail again and are given up at once.
+#[derive(Clone)]
+pub struct DbOutbox {
+    commands: mpsc::UnboundedSender<Command>,
+    unlocks: broadcast::Sender<Unlocked>,
+}
+
+impl DbOutbox {
+    pub fn start(db: DatabaseConnection) -> Self {
+        Self::start_with_base_delay(db, DEFAULT_BASE_DELAY)
+    }
+
+    pub fn start_with_base_delay(db: DatabaseConnection, base_delay: Duration) -> Self {
+        let (commands, mut pending) = mpsc::unbounded_channel();
+        let (unlocks, _) = broadcast::channel(UNLOCKS_CAPACITY);
+        let announce = unlocks.clone();
+        tokio::spawn(async move {
+            while let Some(command) = pending.recv().await {
+                match command {
+                    Command::Write(write) => {
+                        if let (DbWrite::AchievementsEarned { user_id, .. }, Some(achievements)) = (&write, store(&db, &write, base_delay).await) {
+                            if !achievements.is_empty() {
+                                let _ = announce.send(Unlocked { user_id: *user_id, achievements });
+                            }
+                        }
+                    }
+                    Command::Flush(done) => {
+                        let _ = done.send(());
+                    }
+                }
+            }
+        });
+        Self { commands, unlocks }
+    }
+
+    /// Achievements unlocked by the writes stored from now on
+    pub fn unlocks(&self) -> broadcast::Receiver<Unlocked> {
+        self.unlocks.subscribe()
+    }
+
+    /// Queue a write behind every write queued before it
+    pub fn push(&self, write: DbWrite) {
+        if let Err(mpsc::error::SendError(Command::Write(write))) = self.commands.send(Command::Write(write)) {
+            warn!("Database writer has stopped; dropping {:?}", write);
+        }
+    }
+
+    /// Wait until every write queued so far is stored or given up. During an outage this waits
+    /// for the database to come back.
+    pub async fn flush(&self) {
+        let (done, flushed) = oneshot::channel();
+        if self.commands.send(Command::Flush(done)).is_ok() {
+            let _ = flushed.await;
+        }
+    }
+}
+
+/// Store a write, returning what it unlocked; None if it was given up
+async fn store(db: &DatabaseConnection, write: &DbWrite, base_delay: Duration) -> Option<Vec<Achievement>> {
+    let mut delay = base_delay.min(MAX_RETRY_DELAY);
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let e = match write.apply(db).await {
+            Ok(unlocked) => return Some(unlocked),
+            Err(e) => e,
+        };
+        if e.sql_err().is_some() {
+            warn!("Dropping {:?}: {}", write, e);
+            return None;
+        }
+        warn!("Failed to store {:?} (attempt {}), retrying in {:?}: {}", write, attempt, delay, e);
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_RETRY_DELAY);
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, QuerySelect, Set};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::connection::PlayerId;
+use crate::entities::{game, round_deal};
+use crate::game::GameId;
+use crate::engine::card::{Card, Rank, Suit};
+use crate::engine::GameState;
+use tracing::warn;
+
+/// Most recent deals aggregated by the fairness statistics
+pub const FAIRNESS_SAMPLE_LIMIT: u64 = 5000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DealtHand {
+    pub player_id: PlayerId,
+    pub cards: Vec<Card>,
+}
+
+/// Hands and trump of one round, as dealt, with hands in seat order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Deal {
+    pub round_number: usize,
+    pub trump_suit: Option<Suit>,
+    pub hands: Vec<DealtHand>,
+}
+
+impl Deal {
+    pub fn from_state(state: &GameState) -> Self {
+        let hands = state.players
+            .iter()
+            .filter_map(|pid| {
+                state.hands.get(pid).map(|hand| DealtHand { player_id: pid.clone(), cards: hand.cards().to_vec() })
+            })
+            .collect();
+        Self {
+            round_number: state.round_number,
+            trump_suit: state.trump_suit,
+            hands,
+        }
+    }
+
+    fn from_model(model: &round_deal::Model) -> Option<Self> {
+        match serde_json::from_value(model.hands.clone()) {
+            Ok(hands) => Some(Self {
+                round_number: model.round_number as usize,
+                trump_suit: model.trump_suit.as_deref().and_then(parse_suit),
+                hands,
+            }),
+            Err(e) => {
+                warn!("Skipping unreadable deal {}: {}", model.id, e);
+                None
+            }
+        }
+    }
+}
+
+pub(crate) fn suit_key(suit: Suit) -> String {
+    serde_json::to_value(suit).ok().and_then(|v| v.as_str().map(str::to_string)).unwrap_or_default()
+}
+
+pub(crate) fn parse_suit(value: &str) -> Option<Suit> {
+    serde_json::from_value(serde_json::Value::String(value.to_string())).ok()
+}
+
+/// Store a round's deal; hands are only served back once the game has completed
+pub async fn record_deal(db: &impl ConnectionTrait, game_id: GameId, deal: &Deal) -> Result<(), DbErr> {
+    round_deal::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        game_id: Set(game_id),
+        round_number: Set(deal.round_number as i32),
+        trump_suit: Set(deal.trump_suit.map(suit_key)),
+        hands: Set(serde_json::json!(deal.hands)),
+        dealt_at: Set(Utc::now()),
+    }
+    .insert(db)
+    .await?;
+    Ok(())
+}
+
+/// Every round's deal for a game, in round order
+pub async fn deals_for_game(db: &DatabaseConnection, game_id: GameId) -> Result<Vec<Deal>, DbErr> {
+    let rows = round_deal::Entity::find()
+        .filter(round_deal::Column::GameId.eq(game_id))
+        .order_by_asc(round_deal::Column::RoundNumber)
+        .all(db)
+        .await?;
+    Ok(rows.iter().filter_map(Deal::from_model).collect())
+}
+
+/// Observed counts and a chi-square statistic against a uniform distribution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Distribution<K: std::hash::Hash + Eq> {
+    pub counts: HashMap<K, u64>,
+    /// Pearson chi-square against equal counts per category; large values suggest bias
+    pub chi_square: f64,
+    pub degrees_of_freedom: usize,
+}
+
+impl<K: std::hash::Hash + Eq + Copy> Distribution<K> {
+    fn new(categories: &[K], counts: HashMap<K, u64>) -> Self {
+        let total: u64 = categories.iter().map(|k| counts.get(k).copied().unwrap_or(0)).sum();
+        let expected = total as f64 / categories.len() as f64;
+        let chi_square = if total == 0 {
+            0.0
+        } else {
+            categories
+                .iter()
+                .map(|k| {
+                    let observed = counts.get(k).copied().unwrap_or(0) as f64;
+                    (observed - expected).powi(2) / expected
+                })
+                .sum()
+        };
+        Self { counts, chi_square, degrees_of_freedom: categories.len() - 1 }
+    }
+}
+
+/// Deal statistics across completed games
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FairnessStats {
+    pub games: usize,
+    pub rounds: usize,
+    pub cards_dealt: u64,
+    pub suits: Distribution<Suit>,
+    pub ranks: Distribution<Rank>,
+    pub trump_suits: Distribution<Suit>,
+    /// Share of dealt cards that were trump; 0.25 expected
+    pub trump_share: f64,
+}
+
+const SUITS: [Suit; 4] = [Suit::Clubs, Suit::Spades, Suit::Hearts, Suit::Diamonds];
+
+const RANKS: [Rank; 13] = [
+    Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six, Rank::Seven, Rank::Eight,
+    Rank::Nine, Rank::Ten, Rank::Jack, Rank::Queen, Rank::King, Rank::Ace,
+];
+
+/// Aggregate a set of deals
+pub fn fairness_stats(deals: &[Deal]) -> FairnessStats {
+    let mut suits: HashMap<Suit, u64> = HashMap::new();
+    let mut ranks: HashMap<Rank, u64> = HashMap::new();
+    let mut trump_suits: HashMap<Suit, u64> = HashMap::new();
+    let mut cards_dealt = 0u64;
+    let mut trump_cards = 0u64;
+
+    for deal in deals {
+        if let Some(trump) = deal.trump_suit {
+            *trump_suits.entry(trump).or_default() += 1;
+        }
+        for card in deal.hands.iter().flat_map(|hand| &hand.cards) {
+            cards_dealt += 1;
+            *suits.entry(card.suit).or_default() += 1;
+            *ranks.entry(card.rank).or_default() += 1;
+            if Some(card.suit) == deal.trump_suit {
+                trump_cards += 1;
+            }
+        }
+    }
+
+    FairnessStats {
+        games: 0,
+        rounds: deals.len(),
+        cards_dealt,
+        suits: Distribution::new(&SUITS, suits),
+        ranks: Distribution::new(&RANKS, ranks),
+        trump_suits: Distribution::new(&SUITS, trump_suits),
+        trump_share: if cards_dealt == 0 { 0.0 } else { trump_cards as f64 / cards_dealt as f64 },
+    }
+}
+
+/// Fairness statistics over the most recent deals of completed, non-deleted games
+pub async fn compute_fairness(db: &DatabaseConnection) -> Result<FairnessStats, DbErr> {
+    let rows = round_deal::Entity::find()
+        .inner_join(game::Entity)
+        .filter(game::Column::CompletedAt.is_not_null())
+        .filter(game::Column::DeletedAt.is_null())
+        .order_by_desc(round_deal::Column::DealtAt)
+        .limit(FAIRNESS_SAMPLE_LIMIT)
+        .all(db)
+        .await?;
+
+    let mut games: Vec<GameId> = rows.iter().map(|r| r.game_id).collect();
+    games.sort();
+    games.dedup();
+
+    let deals: Vec<Deal> = rows.iter().filter_map(Deal::from_model).collect();
+
+    Ok(FairnessStats { games: games.len(), ..fairness_stats(&deals) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fairness_of_a_full_deck_is_uniform() {
+        let deck: Vec<Card> = SUITS
+            .iter()
+            .flat_map(|&suit| RANKS.iter().map(move |&rank| Card::new(suit, rank)))
+            .collect();
+        let deal = Deal {
+            round_number: 13,
+            trump_suit: Some(Suit::Hearts),
+            hands: vec![
+                DealtHand { player_id: "a".to_string(), cards: deck[..26].to_vec() },
+                DealtHand { player_id: "b".to_string(), cards: deck[26..].to_vec() },
+            ],
+        };
+
+        let stats = fairness_stats(&[deal]);
+        assert_eq!(stats.cards_dealt, 52);
+        assert_eq!(stats.suits.chi_square, 0.0);
+        assert_eq!(stats.ranks.chi_square, 0.0);
+        assert_eq!(stats.ranks.degrees_of_freedom, 12);
+        assert_eq!(stats.trump_share, 0.25);
+        assert_eq!(stats.trump_suits.counts.get(&Suit::Hearts), Some(&1));
+    }
+
+    #[test]
+    fn test_suit_keys_round_trip() {
+        for suit in SUITS {
+            assert_eq!(parse_suit(&suit_key(suit)), Some(suit));
+        }
+    }
+}
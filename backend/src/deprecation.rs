@@ -0,0 +1,44 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use crate::engine::PlayerId;
+
+/// Wire forms still accepted but due to be dropped
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Deprecation {
+    /// `PlaceBid` or `PlayCard` without `state_version`; it will become required
+    UnversionedAction,
+    /// Client messages sent as binary frames; only text frames will be read
+    BinaryFrames,
+}
+
+impl Deprecation {
+    /// The last day the form is accepted; releases after it may reject it
+    pub fn remove_after(self) -> NaiveDate {
+        match self {
+            Deprecation::UnversionedAction => NaiveDate::from_ymd_opt(2027, 4, 30).unwrap(),
+            Deprecation::BinaryFrames => NaiveDate::from_ymd_opt(2027, 4, 30).unwrap(),
+        }
+    }
+}
+
+/// Which deprecations each session was already told about, so every notice goes out once
+#[derive(Debug, Default)]
+pub struct DeprecationTracker {
+    warned: Mutex<HashSet<(PlayerId, Deprecation)>>,
+}
+
+impl DeprecationTracker {
+    /// Record a use of `deprecation` by `player_id`; true the first time in their session
+    pub fn first_use(&self, player_id: &PlayerId, deprecation: Deprecation) -> bool {
+        self.warned.lock().unwrap().insert((player_id.clone(), deprecation))
+    }
+
+    /// Forget a session that ended, so a new one is warned again
+    pub fn forget(&self, player_id: &PlayerId) {
+        self.warned.lock().unwrap().retain(|(pid, _)| pid != player_id);
+    }
+}
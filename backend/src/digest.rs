@@ -0,0 +1,159 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, TimeZone, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, Set};
+use uuid::Uuid;
+use crate::deal_review::suit_key;
+use crate::entities::{game, game_player, user, weekly_digest};
+use crate::trump_stats;
+use tracing::{info, warn};
+
+/// Monday 00:00 UTC of the week containing `at`
+pub fn week_start(at: DateTime<Utc>) -> DateTime<Utc> {
+    let days_since_monday = at.weekday().num_days_from_monday() as i64;
+    let date = at.date_naive() - ChronoDuration::days(days_since_monday);
+    Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+}
+
+/// Start of the most recent week that has fully ended
+pub fn last_complete_week(now: DateTime<Utc>) -> DateTime<Utc> {
+    week_start(now) - ChronoDuration::weeks(1)
+}
+
+#[derive(Debug, Default)]
+struct WeekSummary {
+    games_played: i32,
+    wins: i32,
+    best: Option<(Uuid, i32)>,
+}
+
+/// Create digests for every opted-in user who finished a game in the week starting at `week_start`.
+///
+/// Users who already have a digest for that week are skipped, so the job can be re-run safely.
+/// Returns the number of digests created.
+pub async fn generate_weekly_digests(db: &DatabaseConnection, week_start: DateTime<Utc>) -> Result<usize, DbErr> {
+    let week_end = week_start + ChronoDuration::weeks(1);
+
+    let game_ids: Vec<Uuid> = game::Entity::find()
+        .filter(game::Column::CompletedAt.gte(week_start))
+        .filter(game::Column::CompletedAt.lt(week_end))
+        .filter(game::Column::DeletedAt.is_null())
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|g| g.id)
+        .collect();
+
+    let trumps = trump_stats::stats_for_games(db, &game_ids).await?;
+    let results = game_player::Entity::find()
+        .filter(game_player::Column::GameId.is_in(game_ids))
+        .filter(game_player::Column::FinalScore.is_not_null())
+        .all(db)
+        .await?;
+
+    let mut top_score: HashMap<Uuid, i32> = HashMap::new();
+    for row in &results {
+        let score = row.final_score.unwrap_or_default();
+        top_score.entry(row.game_id).and_modify(|t| *t = (*t).max(score)).or_insert(score);
+    }
+
+    let mut summaries: HashMap<Uuid, WeekSummary> = HashMap::new();
+    for row in &results {
+        let score = row.final_score.unwrap_or_default();
+        let summary = summaries.entry(row.player_id).or_default();
+        summary.games_played += 1;
+        // Games completed before placements were stored count the top score as the win
+        if row.placement.map_or(top_score.get(&row.game_id) == Some(&score), |place| place == 1) {
+            summary.wins += 1;
+        }
+        if summary.best.is_none_or(|(_, best)| score > best) {
+            summary.best = Some((row.game_id, score));
+        }
+    }
+
+    let opted_in: HashSet<Uuid> = user::Entity::find()
+        .filter(user::Column::Id.is_in(summaries.keys().copied().collect::<Vec<_>>()))
+        .filter(user::Column::WeeklyDigest.eq(true))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|u| u.id)
+        .collect();
+
+    let already_sent: HashSet<Uuid> = weekly_digest::Entity::find()
+        .filter(weekly_digest::Column::WeekStart.eq(week_start))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|d| d.user_id)
+        .collect();
+
+    let mut created = 0;
+    for (user_id, summary) in summaries {
+        if !opted_in.contains(&user_id) || already_sent.contains(&user_id) {
+            continue;
+        }
+
+        let digest = weekly_digest::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            week_start: Set(week_start),
+            games_played: Set(summary.games_played),
+            wins: Set(summary.wins),
+            // No rating system yet
+            rating_change: Set(None),
+            best_game_id: Set(summary.best.map(|(game_id, _)| game_id)),
+            best_score: Set(summary.best.map(|(_, score)| score)),
+            best_trump: Set(trumps.get(&user_id)
+                .and_then(|stats| trump_stats::best_trump(stats, trump_stats::MIN_ROUNDS_FOR_BEST))
+                .map(suit_key)),
+            created_at: Set(Utc::now()),
+        };
+        digest.insert(db).await?;
+        created += 1;
+    }
+
+    Ok(created)
+}
+
+/// A user's digests, newest week first
+pub async fn digests_for_user(db: &DatabaseConnection, user_id: Uuid) -> Result<Vec<weekly_digest::Model>, DbErr> {
+    weekly_digest::Entity::find()
+        .filter(weekly_digest::Column::UserId.eq(user_id))
+        .order_by_desc(weekly_digest::Column::WeekStart)
+        .all(db)
+        .await
+}
+
+/// Spawn the background job that creates digests for the last complete week
+pub fn spawn_digest_task(db: DatabaseConnection, interval: Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let week = last_complete_week(Utc::now());
+            match generate_weekly_digests(&db, week).await {
+                Ok(0) => {}
+                Ok(created) => info!("Created {} weekly digests for week of {}", created, week.date_naive()),
+                Err(e) => warn!("Failed to generate weekly digests: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_week_start_is_monday_midnight() {
+        // Saturday afternoon
+        let at = Utc.with_ymd_and_hms(2026, 10, 17, 15, 30, 0).unwrap();
+        assert_eq!(week_start(at), Utc.with_ymd_and_hms(2026, 10, 12, 0, 0, 0).unwrap());
+        assert_eq!(last_complete_week(at), Utc.with_ymd_and_hms(2026, 10, 5, 0, 0, 0).unwrap());
+
+        // Monday midnight is its own week start
+        let monday = Utc.with_ymd_and_hms(2026, 10, 12, 0, 0, 0).unwrap();
+        assert_eq!(week_start(monday), monday);
+    }
+}
@@ -0,0 +1,69 @@
+//! LAN discovery: clients broadcast a probe on [`DISCOVERY_PORT`] and every server with
+//! discovery enabled answers with an [`Announcement`]. The wire format is built without the
+//! `server` feature so the desktop app can share it.
+
+use serde::{Deserialize, Serialize};
+
+/// UDP port servers listen on for discovery probes
+pub const DISCOVERY_PORT: u16 = 41580;
+
+/// The datagram clients broadcast to find servers
+pub const PROBE: &[u8] = b"german-bridge/discover/1";
+
+/// Value of [`Announcement::service`]; other datagrams on the port are ignored
+pub const SERVICE: &str = "german-bridge";
+
+/// A server's reply to a probe, sent as JSON. Clients take the host from the reply's source
+/// address and connect to `ws://<host>:<port><ws_path>`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Announcement {
+    pub service: String,
+    pub name: String,
+    pub port: u16,
+    pub ws_path: String,
+    pub version: String,
+}
+
+impl Announcement {
+    pub fn new(name: impl Into<String>, port: u16) -> Self {
+        Self {
+            service: SERVICE.to_string(),
+            name: name.into(),
+            port,
+            ws_path: "/ws".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    /// Parse a reply datagram, rejecting anything not from a German Bridge server
+    pub fn parse(datagram: &[u8]) -> Option<Self> {
+        serde_json::from_slice::<Self>(datagram).ok().filter(|a| a.service == SERVICE)
+    }
+}
+
+/// Answer discovery probes arriving on `socket` until the task is aborted
+#[cfg(feature = "server")]
+pub fn spawn_announcer(socket: tokio::net::UdpSocket, announcement: Announcement) -> tokio::task::JoinHandle<()> {
+    let reply = serde_json::to_vec(&announcement).expect("announcement serializes");
+    tracing::info!("Answering LAN discovery probes as '{}'", announcement.name);
+
+    tokio::spawn(async move {
+        let mut buf = [0u8; 64];
+        loop {
+            let (len, from) = match socket.recv_from(&mut buf).await {
+                Ok(received) => received,
+                Err(e) => {
+                    tracing::warn!("LAN discovery receive failed: {}", e);
+                    continue;
+                }
+            };
+            if &buf[..len] != PROBE {
+                continue;
+            }
+            tracing::debug!("Answering LAN discovery probe from {}", from);
+            if let Err(e) = socket.send_to(&reply, from).await {
+                tracing::warn!("Failed to answer LAN discovery probe from {}: {}", from, e);
+            }
+        }
+    })
+}
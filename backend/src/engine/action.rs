@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use crate::engine::bidding::Bid;
+use crate::engine::card::Card;
+
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PlayerAction {
+    Bid(Bid),
+    PlayCard(Card),
+    /// Sent in place of another player's bid while blind bidding hides it; never accepted from clients
+    HiddenBid,
+}
@@ -0,0 +1,204 @@
+use crate::engine::{GamePhase, GameState, PlayerAction, PlayerId};
+use crate::engine::bidding::Bid;
+use crate::engine::card::{Card, Rank, Suit};
+
+/// How a turn is chosen for a player who did not take it themselves
+pub trait AutoPlayStrategy {
+    /// A legal action for the current player, or None if it is nobody's turn
+    fn choose(&self, state: &GameState) -> Option<PlayerAction>;
+}
+
+/// The lowest legal bid, then the first playable card in hand
+pub struct LowestLegal;
+
+impl AutoPlayStrategy for LowestLegal {
+    fn choose(&self, state: &GameState) -> Option<PlayerAction> {
+        match state.phase {
+            GamePhase::Bidding => {
+                // 0 is forbidden to the last bidder when the others bid every card
+                let constraint = state.bidding_state.as_ref()?.constraint();
+                let tricks = (0..=state.cards_per_player as u8).find(|&bid| constraint.allows(bid))?;
+                Some(PlayerAction::Bid(Bid { tricks }))
+            }
+            GamePhase::Playing => {
+                let hand = state.hands.get(&state.current_player)?;
+                hand.playable(state.current_trick.lead_suit).next().map(PlayerAction::PlayCard)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Plays towards the player's bid: once it is met, the lowest card that cannot take the
+/// trick; while short of it, the strongest card that can, trumps first. Bids the legal bid
+/// closest to what the hand looks good for (see `estimate_tricks`).
+pub struct RulesAware;
+
+impl AutoPlayStrategy for RulesAware {
+    fn choose(&self, state: &GameState) -> Option<PlayerAction> {
+        match state.phase {
+            GamePhase::Bidding => {
+                let constraint = state.bidding_state.as_ref()?.constraint();
+                return closest_bid(state, |bid| constraint.allows(bid)).map(|tricks| PlayerAction::Bid(Bid { tricks }));
+            }
+            GamePhase::Playing => {}
+            _ => return None,
+        }
+        let player = &state.current_player;
+        let hand = state.hands.get(player)?;
+        let round = state.current_round.iter().find(|pr| pr.player_id == *player)?;
+        let trump = state.trump_suit;
+        let strength = |card: &Card| (trump == Some(card.suit), card.rank);
+
+        let winning = current_winner(&state.current_trick.cards, trump);
+        let can_win = |card: &Card| match (winning, state.current_trick.lead_suit) {
+            (Some(winning), Some(lead)) => card.beats(&winning, trump, lead),
+            // Leading; any card might hold
+            _ => true,
+        };
+
+        let (winners, losers): (Vec<Card>, Vec<Card>) =
+            hand.playable(state.current_trick.lead_suit).partition(|card| can_win(card));
+        let card = if round.tricks_won >= round.bid {
+            losers.iter().chain(&winners).min_by_key(|card| strength(card))
+        } else {
+            winners.iter().max_by_key(|card| strength(card))
+                .or_else(|| losers.iter().min_by_key(|card| strength(card)))
+        };
+        card.copied().map(PlayerAction::PlayCard)
+    }
+}
+
+/// Tricks a hand looks good for: one for each trump from the jack up and each ace of another
+/// suit, at most one per card dealt
+pub fn estimate_tricks(hand: &[Card], trump: Option<Suit>, cards_per_player: usize) -> u8 {
+    let sure = hand.iter()
+        .filter(|card| if Some(card.suit) == trump { card.rank >= Rank::Jack } else { card.rank == Rank::Ace })
+        .count();
+    sure.min(cards_per_player) as u8
+}
+
+/// The bid nearest the current player's `estimate_tricks` that `allows` accepts, the lower
+/// one on a tie
+fn closest_bid(state: &GameState, allows: impl Fn(u8) -> bool) -> Option<u8> {
+    let hand = state.hands.get(&state.current_player)?;
+    let estimate = estimate_tricks(hand.cards(), state.trump_suit, state.cards_per_player);
+    (0..=state.cards_per_player as u8)
+        .filter(|&bid| allows(bid))
+        .min_by_key(|&bid| (bid.abs_diff(estimate), bid))
+}
+
+/// The card currently taking the trick
+fn current_winner(cards: &[(PlayerId, Card)], trump: Option<Suit>) -> Option<Card> {
+    let (_, lead) = cards.first()?;
+    Some(cards.iter().skip(1).fold(*lead, |winning, (_, card)| {
+        if card.beats(&winning, trump, lead.suit) { *card } else { winning }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::bidding::BiddingState;
+    use crate::engine::deck::Hand;
+    use crate::engine::trick::Trick;
+
+    /// A playing-phase state where "a" holds `hand` and follows "b", who led `led`
+    fn following(hand: Vec<Card>, led: Card, bid: u8, tricks_won: u8) -> GameState {
+        let mut state = GameState::new(vec!["a".to_string(), "b".to_string()]);
+        state.phase = GamePhase::Playing;
+        state.trump_suit = Some(Suit::Hearts);
+        state.current_player = "a".to_string();
+        state.hands.insert("a".to_string(), Hand::new(hand));
+        state.current_trick = Trick::new();
+        state.current_trick.add_card("b".to_string(), led);
+        for pr in state.current_round.iter_mut().filter(|pr| pr.player_id == "a") {
+            pr.bid = bid;
+            pr.tricks_won = tricks_won;
+        }
+        state
+    }
+
+    fn chosen(state: &GameState) -> Card {
+        match RulesAware.choose(state) {
+            Some(PlayerAction::PlayCard(card)) => card,
+            other => panic!("expected a card, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_ducks_once_the_bid_is_met() {
+        let hand = vec![Card::new(Suit::Spades, Rank::Ace), Card::new(Suit::Spades, Rank::Queen), Card::new(Suit::Spades, Rank::Two)];
+        let state = following(hand, Card::new(Suit::Spades, Rank::King), 1, 1);
+        assert_eq!(chosen(&state), Card::new(Suit::Spades, Rank::Two));
+
+        // Off suit even the ace cannot win, so it goes before the trump
+        let hand = vec![Card::new(Suit::Spades, Rank::Ace), Card::new(Suit::Hearts, Rank::Three)];
+        let state = following(hand, Card::new(Suit::Clubs, Rank::King), 0, 0);
+        assert_eq!(chosen(&state), Card::new(Suit::Spades, Rank::Ace));
+
+        // Every card wins: the weakest at least leaves the stronger ones for later
+        let hand = vec![Card::new(Suit::Spades, Rank::Ace), Card::new(Suit::Spades, Rank::King)];
+        let state = following(hand, Card::new(Suit::Spades, Rank::Two), 0, 1);
+        assert_eq!(chosen(&state), Card::new(Suit::Spades, Rank::King));
+    }
+
+    /// A bidding-phase state where "a" bids `hand`, after "b" bid `before` if anything
+    fn bidding(hand: Vec<Card>, before: Option<u8>) -> GameState {
+        let mut state = GameState::new(vec!["a".to_string(), "b".to_string()]);
+        state.phase = GamePhase::Bidding;
+        state.trump_suit = Some(Suit::Hearts);
+        state.cards_per_player = hand.len();
+        state.current_player = "a".to_string();
+        let order = if before.is_some() { ["b", "a"] } else { ["a", "b"] };
+        let mut bidding = BiddingState::new("a".to_string(), order.map(String::from).to_vec(), hand.len());
+        if let Some(bid) = before {
+            bidding.bids.insert("b".to_string(), bid);
+        }
+        state.bidding_state = Some(bidding);
+        state.hands.insert("a".to_string(), Hand::new(hand));
+        state
+    }
+
+    fn bid(state: &GameState) -> u8 {
+        match RulesAware.choose(state) {
+            Some(PlayerAction::Bid(bid)) => bid.tricks,
+            other => panic!("expected a bid, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_bids_what_the_hand_is_good_for() {
+        let aces = vec![
+            Card::new(Suit::Spades, Rank::Ace),
+            Card::new(Suit::Clubs, Rank::Ace),
+            Card::new(Suit::Diamonds, Rank::Ace),
+            Card::new(Suit::Hearts, Rank::Ace),
+        ];
+        assert_eq!(bid(&bidding(aces.clone(), None)), 4);
+
+        // High trumps count, low cards of other suits don't
+        let mixed = vec![
+            Card::new(Suit::Hearts, Rank::Jack),
+            Card::new(Suit::Hearts, Rank::Four),
+            Card::new(Suit::Spades, Rank::King),
+            Card::new(Suit::Clubs, Rank::Two),
+        ];
+        assert_eq!(bid(&bidding(mixed, None)), 1);
+
+        // The last bidder can't make the bids add up to the cards; the nearest other bid is lower
+        assert_eq!(bid(&bidding(aces, Some(0))), 3);
+    }
+
+    #[test]
+    fn test_takes_the_trick_with_its_highest_trump_when_short() {
+        let hand = vec![Card::new(Suit::Hearts, Rank::Four), Card::new(Suit::Hearts, Rank::Jack), Card::new(Suit::Spades, Rank::Ace)];
+        let state = following(hand, Card::new(Suit::Clubs, Rank::King), 1, 0);
+        assert_eq!(chosen(&state), Card::new(Suit::Hearts, Rank::Jack));
+
+        // Nothing can win, so nothing good is wasted
+        let hand = vec![Card::new(Suit::Clubs, Rank::Queen), Card::new(Suit::Clubs, Rank::Three)];
+        let state = following(hand, Card::new(Suit::Clubs, Rank::King), 1, 0);
+        assert_eq!(chosen(&state), Card::new(Suit::Clubs, Rank::Three));
+    }
+}
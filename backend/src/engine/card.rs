@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Suit {
     Clubs,
@@ -8,6 +9,7 @@ pub enum Suit {
     Diamonds,
 }
 
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Rank {
     Two,
@@ -25,6 +27,7 @@ pub enum Rank {
     Ace,
 }
 
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Card {
     pub suit: Suit,
@@ -47,8 +50,8 @@ impl Card {
     /// Within the same suit, higher rank wins
     /// Cards not following lead suit cannot win (unless trump)
     pub fn beats(&self, other: &Card, trump: Option<Suit>, lead_suit: Suit) -> bool {
-        let self_is_trump = trump.map_or(false, |t| self.suit == t);
-        let other_is_trump = trump.map_or(false, |t| other.suit == t);
+        let self_is_trump = trump == Some(self.suit);
+        let other_is_trump = trump == Some(other.suit);
 
         // Trump always beats non-trump
         if self_is_trump && !other_is_trump {
@@ -1,7 +1,72 @@
-use crate::game_logic::card::{Card, Suit, Rank};
+use crate::engine::card::{Card, Suit, Rank};
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+
+/// Seed a round is dealt from. The server publishes the seed's `commitment` when the round
+/// is dealt and the seed itself once the round is over, so players can check the deal
+/// was fixed before anyone bid.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct DeckSeed([u8; 32]);
+
+impl DeckSeed {
+    pub fn random() -> Self {
+        let mut seed = [0; 32];
+        thread_rng().fill(&mut seed);
+        Self(seed)
+    }
+
+    /// The next seed from a seeded game's seed source
+    pub fn from_rng(rng: &mut impl Rng) -> Self {
+        let mut seed = [0; 32];
+        rng.fill(&mut seed);
+        Self(seed)
+    }
+
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let bytes = hex::decode(hex).ok()?;
+        Some(Self(bytes.try_into().ok()?))
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    /// SHA-256 of the seed bytes, in hex
+    pub fn commitment(&self) -> String {
+        hex::encode(Sha256::digest(self.0))
+    }
+
+    /// The ChaCha20 generator the shuffle, and a random trump, are drawn from
+    pub fn rng(&self) -> ChaCha20Rng {
+        ChaCha20Rng::from_seed(self.0)
+    }
+}
+
+// Hex, like the seed revealed to players after the round
+impl Serialize for DeckSeed {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
 
+impl<'de> Deserialize<'de> for DeckSeed {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        Self::from_hex(&hex).ok_or_else(|| serde::de::Error::custom("expected a 64 digit hex seed"))
+    }
+}
+
+// Keeps a round's seed out of logs while the round is being played
+impl std::fmt::Debug for DeckSeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DeckSeed(..)")
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Deck {
     cards: Vec<Card>,
 }
@@ -27,14 +92,23 @@ impl Deck {
     }
 
     pub fn shuffle(&mut self) {
-        let mut rng = thread_rng();
-        self.cards.shuffle(&mut rng);
+        self.shuffle_with(&mut thread_rng());
+    }
+
+    /// Shuffle with the given generator, e.g. a seeded one so the deal can be reproduced
+    pub fn shuffle_with(&mut self, rng: &mut impl Rng) {
+        self.cards.shuffle(rng);
+    }
+
+    /// Cards left over after the deal, which nobody sees
+    pub fn remaining(&self) -> &[Card] {
+        &self.cards
     }
 
     /// Deal a specific number of cards to each player
     /// Returns a vector of Hands, one for each player
     pub fn deal(&mut self, num_players: usize, cards_per_player: usize) -> Vec<Hand> {
-        let mut hands = vec![Vec::new(); num_players];
+        let mut hands: Vec<Vec<Card>> = (0..num_players).map(|_| Vec::with_capacity(cards_per_player)).collect();
         
         // Deal cards in round-robin fashion
         let total_cards_to_deal = num_players * cards_per_player;
@@ -53,6 +127,7 @@ impl Deck {
     }
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Hand {
     cards: Vec<Card>,
 }
@@ -78,28 +153,21 @@ impl Hand {
     /// If lead_suit is None (first card of trick), all cards are valid
     /// If lead_suit is Some, must follow suit if possible
     pub fn valid_plays(&self, lead_suit: Option<Suit>) -> Vec<Card> {
-        match lead_suit {
-            None => {
-                // First card of trick - all cards are valid
-                self.cards.clone()
-            }
-            Some(suit) => {
-                // Must follow suit if possible
-                let cards_in_suit: Vec<Card> = self.cards
-                    .iter()
-                    .filter(|c| c.suit == suit)
-                    .copied()
-                    .collect();
-                
-                if cards_in_suit.is_empty() {
-                    // No cards in lead suit - can play any card
-                    self.cards.clone()
-                } else {
-                    // Must play a card in the lead suit
-                    cards_in_suit
-                }
-            }
-        }
+        self.playable(lead_suit).collect()
+    }
+
+    /// The valid plays in hand order, without collecting them
+    pub fn playable(&self, lead_suit: Option<Suit>) -> impl Iterator<Item = Card> + '_ {
+        let follow = lead_suit.filter(|&suit| self.cards.iter().any(|c| c.suit == suit));
+        self.cards.iter()
+            .filter(move |c| follow.is_none_or(|suit| c.suit == suit))
+            .copied()
+    }
+
+    /// Whether `card` is in hand and a valid play
+    pub fn can_play(&self, card: &Card, lead_suit: Option<Suit>) -> bool {
+        self.has_card(card)
+            && lead_suit.is_none_or(|suit| card.suit == suit || self.cards.iter().all(|c| c.suit != suit))
     }
 
     /// Check if the hand contains a specific card
@@ -117,6 +185,23 @@ impl Hand {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_seeded_shuffle_is_reproducible() {
+        let seed = DeckSeed::random();
+        let deal = |seed: &DeckSeed| {
+            let mut deck = Deck::new_german_bridge();
+            deck.shuffle_with(&mut seed.rng());
+            deck.deal(4, 13).into_iter().map(|hand| hand.cards().to_vec()).collect::<Vec<_>>()
+        };
+        assert_eq!(deal(&seed), deal(&seed));
+        assert_ne!(deal(&seed), deal(&DeckSeed::random()));
+
+        assert_eq!(DeckSeed::from_hex(&seed.to_hex()), Some(seed));
+        assert_eq!(DeckSeed::from_hex("abcd"), None);
+        assert_eq!(seed.commitment().len(), 64);
+        assert_eq!(format!("{:?}", seed), "DeckSeed(..)");
+    }
+
     #[test]
     fn test_new_german_bridge_creates_52_cards() {
         let deck = Deck::new_german_bridge();
@@ -408,4 +493,22 @@ mod tests {
         assert_eq!(hand.cards().len(), 0);
         assert!(!hand.has_card(&queen_diamonds));
     }
+
+    #[test]
+    fn test_can_play_matches_valid_plays() {
+        let ace_hearts = Card::new(Suit::Hearts, Rank::Ace);
+        let two_hearts = Card::new(Suit::Hearts, Rank::Two);
+        let king_spades = Card::new(Suit::Spades, Rank::King);
+        let hand = Hand::new(vec![ace_hearts, king_spades, two_hearts]);
+        let outside = Card::new(Suit::Clubs, Rank::Ace);
+
+        for lead in [None, Some(Suit::Hearts), Some(Suit::Spades), Some(Suit::Diamonds)] {
+            let valid = hand.valid_plays(lead);
+            for card in [ace_hearts, two_hearts, king_spades, outside] {
+                assert_eq!(hand.can_play(&card, lead), valid.contains(&card), "{:?} led, {:?}", lead, card);
+            }
+        }
+        assert_eq!(hand.valid_plays(Some(Suit::Hearts)), vec![ace_hearts, two_hearts]);
+        assert_eq!(hand.playable(Some(Suit::Diamonds)).count(), 3);
+    }
 }
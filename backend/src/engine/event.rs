@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+use crate::engine::PlayerId;
+use crate::engine::card::{Card, Suit};
+
+/// What an action or round change did, in the order it happened
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Event {
+    BidPlaced { player_id: PlayerId, tricks: u8 },
+    /// The last bid is in; `leader` plays the first card
+    BiddingComplete { leader: PlayerId },
+    CardPlayed { player_id: PlayerId, card: Card },
+    TrickWon { winner: PlayerId },
+    /// Every card of the round is played and scored
+    RoundComplete { round_number: usize },
+    /// Follows the last RoundComplete
+    GameComplete,
+    RoundStarted { round_number: usize, cards_per_player: usize, trump_suit: Option<Suit> },
+}
+
+/// Events from one call; a single action makes at most four
+pub type Events = SmallVec<[Event; 4]>;
@@ -0,0 +1,24 @@
+//! German Bridge rules as a plain state machine: actions go in, state changes and
+//! [`Event`]s come out. Nothing here logs, awaits or reads the clock, and every random
+//! choice can be seeded, so the same code drives server games, local play and tests.
+
+pub mod card;
+pub mod deck;
+pub mod trick;
+pub mod bidding;
+pub mod scoring;
+pub mod schedule;
+pub mod tiebreak;
+pub mod rules;
+pub mod action;
+pub mod auto_play;
+pub mod event;
+pub mod state;
+pub mod simulation;
+
+pub use action::PlayerAction;
+pub use event::{Event, Events};
+pub use state::{GamePhase, GameState};
+pub use simulation::MatchRecord;
+
+pub type PlayerId = String;
@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use crate::engine::card::Suit;
+use crate::engine::schedule::RoundSchedule;
+
+/// Cards in the single deck every round is dealt from
+const DECK_SIZE: usize = 52;
+
+/// House rules a lobby plays by, validated on lobby creation and carried into the game state
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleSet {
+    /// Most cards dealt in a round; the round schedule peaks here instead of at a full deck
+    #[serde(default)]
+    pub max_cards: Option<usize>,
+    /// Which way bidding, play and the first bidder move around the table
+    #[serde(default)]
+    pub deal_direction: DealDirection,
+    #[serde(default)]
+    pub zero_bids: ZeroBidRule,
+    /// The game ends after the round in which someone reaches this score
+    #[serde(default)]
+    pub target_score: Option<i32>,
+    /// The game ends after this many rounds, or sooner if the round schedule runs out
+    #[serde(default)]
+    pub round_limit: Option<usize>,
+    /// How many cards each round deals; unset uses the settings' deprecated `round_schedule`
+    #[serde(default)]
+    pub schedule: Option<RoundSchedule>,
+    #[serde(default)]
+    pub trump: TrumpMode,
+    #[serde(default)]
+    pub timer: TimerPolicy,
+    /// Deal every round from a seed committed to up front and revealed when the round ends
+    #[serde(default)]
+    pub seeded_deals: bool,
+}
+
+/// Turn order around the table, relative to seat order
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DealDirection {
+    /// Each turn passes to the next seat
+    #[default]
+    Clockwise,
+    /// Each turn passes to the previous seat
+    CounterClockwise,
+}
+
+/// When a bid of zero tricks is allowed. Where a zero bid is ruled out but is the bidder's only
+/// legal bid, it stays allowed.
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ZeroBidRule {
+    #[default]
+    Allowed,
+    /// Not in rounds of a single card
+    NotInOneCardRounds,
+    Never,
+}
+
+impl ZeroBidRule {
+    pub fn allows_zero(&self, cards_this_round: usize) -> bool {
+        match self {
+            ZeroBidRule::Allowed => true,
+            ZeroBidRule::NotInOneCardRounds => cards_this_round != 1,
+            ZeroBidRule::Never => false,
+        }
+    }
+}
+
+/// How each round's trump suit is chosen
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TrumpMode {
+    /// A random suit every round
+    #[default]
+    Random,
+    /// Clubs, Spades, Hearts, Diamonds in turn, starting with round 1
+    Rotating,
+    /// No trump in any round
+    NoTrump,
+}
+
+impl TrumpMode {
+    const ROTATION: [Suit; 4] = [Suit::Clubs, Suit::Spades, Suit::Hearts, Suit::Diamonds];
+
+    /// A random trump is drawn from `rng`
+    pub fn trump_for_round(&self, round_number: usize, rng: &mut impl Rng) -> Option<Suit> {
+        match self {
+            TrumpMode::Random => Self::ROTATION.choose(rng).copied(),
+            TrumpMode::Rotating => Some(Self::ROTATION[round_number.saturating_sub(1) % Self::ROTATION.len()]),
+            TrumpMode::NoTrump => None,
+        }
+    }
+}
+
+/// Turn time limits per phase; unset phases use `turn_secs`, else the settings' deprecated
+/// `turn_timeout_secs`
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TimerPolicy {
+    /// Limit for a phase without its own
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(type = "number | null"))]
+    pub turn_secs: Option<u64>,
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(type = "number | null"))]
+    pub bidding_secs: Option<u64>,
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(type = "number | null"))]
+    pub playing_secs: Option<u64>,
+    #[serde(default)]
+    pub on_timeout: TimeoutPolicy,
+}
+
+/// What happens when a player's turn time runs out
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TimeoutPolicy {
+    /// The turn is played for them
+    #[default]
+    AutoPlay,
+    /// The turn is played for them and `points` come off their score for the round
+    SkipWithPenalty { points: u32 },
+    /// The turn stays theirs until they act or the game host plays it for them
+    WaitForHost,
+}
+
+impl TimerPolicy {
+    /// The limits that are set
+    pub fn limits(&self) -> impl Iterator<Item = (&'static str, u64)> {
+        [("turn_secs", self.turn_secs), ("bidding_secs", self.bidding_secs), ("playing_secs", self.playing_secs)]
+            .into_iter()
+            .filter_map(|(name, secs)| secs.map(|secs| (name, secs)))
+    }
+}
+
+impl RuleSet {
+    /// Most cards each of `player_count` players can be dealt under these rules
+    pub fn max_cards_per_player(&self, player_count: usize) -> usize {
+        let deck_limit = DECK_SIZE / player_count.max(1);
+        self.max_cards.map_or(deck_limit, |max| max.min(deck_limit))
+    }
+
+    /// Check the rules make sense for a table of `player_count`; the error names the bad field
+    pub fn validate(&self, player_count: usize) -> Result<(), String> {
+        let deck_limit = DECK_SIZE / player_count.max(1);
+        if let Some(max_cards) = self.max_cards {
+            if !(1..=deck_limit).contains(&max_cards) {
+                return Err(format!("rules.max_cards must be between 1 and {} for {} players", deck_limit, player_count));
+            }
+        }
+        if self.target_score.is_some_and(|target| target <= 0) {
+            return Err("rules.target_score must be positive".to_string());
+        }
+        if self.round_limit == Some(0) {
+            return Err("rules.round_limit must be positive".to_string());
+        }
+        if self.timer.on_timeout == (TimeoutPolicy::SkipWithPenalty { points: 0 }) {
+            return Err("rules.timer.on_timeout penalty must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_rejects_impossible_rules() {
+        assert!(RuleSet::default().validate(4).is_ok());
+        assert!(RuleSet { max_cards: Some(13), ..Default::default() }.validate(4).is_ok());
+        assert!(RuleSet { max_cards: Some(14), ..Default::default() }.validate(4).is_err());
+        assert!(RuleSet { max_cards: Some(0), ..Default::default() }.validate(4).is_err());
+        assert!(RuleSet { target_score: Some(0), ..Default::default() }.validate(4).is_err());
+        assert!(RuleSet { target_score: Some(100), ..Default::default() }.validate(4).is_ok());
+        assert!(RuleSet { round_limit: Some(0), ..Default::default() }.validate(4).is_err());
+        assert!(RuleSet { round_limit: Some(3), ..Default::default() }.validate(4).is_ok());
+        let penalty = |points| RuleSet {
+            timer: TimerPolicy { on_timeout: TimeoutPolicy::SkipWithPenalty { points }, ..Default::default() },
+            ..Default::default()
+        };
+        assert!(penalty(0).validate(4).is_err());
+        assert!(penalty(5).validate(4).is_ok());
+
+        let capped = RuleSet { max_cards: Some(5), ..Default::default() };
+        assert_eq!(capped.max_cards_per_player(4), 5);
+        assert_eq!(capped.max_cards_per_player(6), 5);
+        assert_eq!(RuleSet::default().max_cards_per_player(6), 8);
+    }
+
+    #[test]
+    fn test_trump_modes_and_zero_bids() {
+        let rng = &mut rand::thread_rng();
+        assert_eq!(TrumpMode::Rotating.trump_for_round(1, rng), Some(Suit::Clubs));
+        assert_eq!(TrumpMode::Rotating.trump_for_round(6, rng), Some(Suit::Spades));
+        assert_eq!(TrumpMode::NoTrump.trump_for_round(3, rng), None);
+        assert!(TrumpMode::Random.trump_for_round(1, rng).is_some());
+
+        assert!(ZeroBidRule::Allowed.allows_zero(1));
+        assert!(!ZeroBidRule::NotInOneCardRounds.allows_zero(1));
+        assert!(ZeroBidRule::NotInOneCardRounds.allows_zero(2));
+        assert!(!ZeroBidRule::Never.allows_zero(5));
+    }
+
+    #[test]
+    fn test_rules_default_when_left_out() {
+        let rules: RuleSet = serde_json::from_str(r#"{"trump": "NoTrump", "timer": {"bidding_secs": 20}}"#).unwrap();
+        assert_eq!(rules.trump, TrumpMode::NoTrump);
+        assert_eq!(rules.deal_direction, DealDirection::Clockwise);
+        assert_eq!(rules.timer.limits().collect::<Vec<_>>(), vec![("bidding_secs", 20)]);
+        assert_eq!(rules.timer.on_timeout, TimeoutPolicy::AutoPlay);
+    }
+}
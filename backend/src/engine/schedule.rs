@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+/// How the number of cards dealt changes from round to round
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RoundSchedule {
+    /// 1, 2, ..., N cards, then the game ends
+    #[default]
+    Ascending,
+    /// 1, 2, ..., N, then N, N-1, ..., 1 (classic Oh Hell)
+    UpAndDown,
+}
+
+impl RoundSchedule {
+    /// Total number of rounds when at most `max_cards` can be dealt to each player
+    pub fn total_rounds(&self, max_cards: usize) -> usize {
+        match self {
+            RoundSchedule::Ascending => max_cards,
+            RoundSchedule::UpAndDown => max_cards * 2,
+        }
+    }
+
+    /// Cards per player for a 1-based round number, or None past the end of the schedule
+    pub fn cards_for_round(&self, round_number: usize, max_cards: usize) -> Option<usize> {
+        if round_number == 0 || round_number > self.total_rounds(max_cards) {
+            return None;
+        }
+
+        match self {
+            RoundSchedule::Ascending => Some(round_number),
+            RoundSchedule::UpAndDown => {
+                if round_number <= max_cards {
+                    Some(round_number)
+                } else {
+                    Some(max_cards * 2 + 1 - round_number)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequence(schedule: RoundSchedule, max_cards: usize) -> Vec<usize> {
+        (1..=schedule.total_rounds(max_cards))
+            .map(|round| schedule.cards_for_round(round, max_cards).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_ascending_schedule() {
+        assert_eq!(sequence(RoundSchedule::Ascending, 4), vec![1, 2, 3, 4]);
+        assert_eq!(RoundSchedule::Ascending.cards_for_round(5, 4), None);
+    }
+
+    #[test]
+    fn test_up_and_down_schedule() {
+        assert_eq!(sequence(RoundSchedule::UpAndDown, 4), vec![1, 2, 3, 4, 4, 3, 2, 1]);
+        assert_eq!(RoundSchedule::UpAndDown.cards_for_round(9, 4), None);
+    }
+
+    #[test]
+    fn test_round_zero_is_invalid() {
+        assert_eq!(RoundSchedule::Ascending.cards_for_round(0, 4), None);
+    }
+}
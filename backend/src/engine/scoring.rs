@@ -1,6 +1,33 @@
 use std::collections::HashMap;
-use crate::connection::PlayerId;
-use crate::game_logic::bidding::Bid;
+use serde::{Deserialize, Serialize};
+use crate::engine::PlayerId;
+use crate::engine::bidding::Bid;
+use crate::engine::card::Suit;
+
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundResult {
+    pub round_number: usize,
+    /// None for a round played without trump
+    #[serde(default)]
+    pub trump_suit: Option<Suit>,
+    pub player_results: Vec<PlayerRoundResult>,
+    /// Seed the round was dealt from, in hex, when deals are seeded
+    #[serde(default)]
+    pub deal_seed: Option<String>,
+}
+
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerRoundResult {
+    pub player_id: PlayerId,
+    pub bid: u8,
+    pub tricks_won: u8,
+    /// Points taken off for timed-out turns; `score` already includes it
+    #[serde(default)]
+    pub penalty: i32,
+    pub score: i32,
+}
 
 pub struct ScoreCalculator;
 
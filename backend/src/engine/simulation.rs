@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::engine::{Event, GamePhase, GameState, PlayerId};
+use crate::engine::auto_play::AutoPlayStrategy;
+use crate::engine::deck::DeckSeed;
+use crate::engine::rules::RuleSet;
+use crate::engine::schedule::RoundSchedule;
+use crate::engine::scoring::RoundResult;
+use crate::error::GameError;
+
+/// Everything that happened in a simulated match. The same players, rules, strategies and
+/// seed always give the same record, so records from before and after a rule change can be
+/// compared game by game.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchRecord {
+    /// Starts with the first round's RoundStarted and ends with GameComplete
+    pub events: Vec<Event>,
+    pub rounds: Vec<RoundResult>,
+    /// Sorted by player, so serialized records diff cleanly
+    pub final_scores: BTreeMap<PlayerId, i32>,
+}
+
+impl GameState {
+    /// Play a complete match by the default rules, dealt from `seed`, with each seat's turns
+    /// chosen by the strategy at the same index in `strategies`
+    pub fn simulate(players: Vec<PlayerId>, strategies: &[&dyn AutoPlayStrategy], seed: DeckSeed) -> Result<MatchRecord, GameError> {
+        Self::with_seed(players, RoundSchedule::default(), RuleSet::default(), seed).play_to_end(strategies)
+    }
+
+    /// Play this game to the end without anyone waiting between rounds, as `simulate` does.
+    /// Fails if a strategy has no move or picks an illegal one.
+    pub fn play_to_end(mut self, strategies: &[&dyn AutoPlayStrategy]) -> Result<MatchRecord, GameError> {
+        if strategies.len() != self.players.len() {
+            return Err(GameError::InvalidMove(format!(
+                "{} strategies for {} players",
+                strategies.len(),
+                self.players.len()
+            )));
+        }
+
+        let mut events = vec![Event::RoundStarted {
+            round_number: self.round_number,
+            cards_per_player: self.cards_per_player,
+            trump_suit: self.trump_suit,
+        }];
+        loop {
+            match self.phase {
+                GamePhase::GameComplete => break,
+                GamePhase::RoundComplete => events.extend(self.advance_to_next_round()),
+                GamePhase::Bidding | GamePhase::Playing => {
+                    let player = self.current_player.clone();
+                    let seat = self.players.iter().position(|p| *p == player).ok_or(GameError::PlayerNotInGame)?;
+                    let action = self.get_auto_action(strategies[seat])
+                        .ok_or_else(|| GameError::InvalidMove(format!("Strategy for {} has no move", player)))?;
+                    events.extend(self.apply_action(player, action)?);
+                }
+            }
+        }
+
+        Ok(MatchRecord {
+            events,
+            rounds: self.history.as_ref().clone(),
+            final_scores: self.total_scores.into_iter().collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::auto_play::{LowestLegal, RulesAware};
+
+    fn players() -> Vec<PlayerId> {
+        vec!["a".to_string(), "b".to_string(), "c".to_string()]
+    }
+
+    #[test]
+    fn test_same_seed_gives_the_same_record() {
+        let seed = DeckSeed::random();
+        let strategies: [&dyn AutoPlayStrategy; 3] = [&RulesAware, &LowestLegal, &RulesAware];
+        let first = GameState::simulate(players(), &strategies, seed).unwrap();
+        let second = GameState::simulate(players(), &strategies, seed).unwrap();
+        assert_eq!(serde_json::to_value(&first).unwrap(), serde_json::to_value(&second).unwrap());
+
+        // Every scheduled round is played and the totals add up
+        assert_eq!(first.rounds.len(), 17);
+        assert!(matches!(first.events.first(), Some(Event::RoundStarted { round_number: 1, cards_per_player: 1, .. })));
+        assert_eq!(first.events.last(), Some(&Event::GameComplete));
+        for (player, total) in &first.final_scores {
+            let sum: i32 = first.rounds.iter().flat_map(|r| &r.player_results).filter(|r| r.player_id == *player).map(|r| r.score).sum();
+            assert_eq!(*total, sum);
+        }
+    }
+
+    #[test]
+    fn test_plays_out_a_game_under_house_rules() {
+        let rules = RuleSet { max_cards: Some(3), ..Default::default() };
+        let state = GameState::with_seed(players(), RoundSchedule::UpAndDown, rules, DeckSeed::random());
+        let record = state.play_to_end(&[&LowestLegal, &LowestLegal, &LowestLegal]).unwrap();
+        let cards: Vec<usize> = record.events.iter().filter_map(|e| match e {
+            Event::RoundStarted { cards_per_player, .. } => Some(*cards_per_player),
+            _ => None,
+        }).collect();
+        assert_eq!(cards, [1, 2, 3, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_needs_a_strategy_per_seat() {
+        let result = GameState::simulate(players(), &[&LowestLegal], DeckSeed::random());
+        assert!(matches!(result, Err(GameError::InvalidMove(_))));
+    }
+}
@@ -0,0 +1,747 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+use crate::engine::{Event, Events, PlayerAction, PlayerId};
+use crate::engine::auto_play::AutoPlayStrategy;
+use crate::engine::card::Suit;
+use crate::engine::deck::{Deck, DeckSeed, Hand};
+use crate::engine::trick::{Trick, CompletedTrick};
+use crate::engine::bidding::{Bid, BiddingState};
+use crate::engine::schedule::RoundSchedule;
+use crate::engine::rules::{DealDirection, RuleSet};
+use crate::engine::scoring::{PlayerRoundResult, RoundResult, ScoreCalculator};
+use crate::error::GameError;
+
+/// The whole game as the server holds it, hands and seeds included. Serializable for
+/// checkpoints and transfer between servers; never send it to a client, use a view.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GameState {
+    pub phase: GamePhase,
+    pub round_number: usize,
+    pub cards_per_player: usize,
+    pub deck: Deck,
+    pub hands: HashMap<PlayerId, Hand>,
+    pub current_trick: Trick,
+    pub completed_tricks: Vec<CompletedTrick>,
+    pub total_scores: HashMap<PlayerId, i32>,
+    pub trump_suit: Option<Suit>,
+    pub current_round: Vec<PlayerRoundResult>,
+    pub current_player: PlayerId,
+    pub first_bidder: PlayerId,
+    pub bidding_state: Option<BiddingState>,
+    pub players: Vec<PlayerId>,
+    /// Shared with the views built from this state; copied only when a round is added while a view still holds it
+    pub history: Arc<Vec<RoundResult>>,
+    pub version: u64, // Bumped on every state change, used to detect stale client actions
+    pub schedule: RoundSchedule,
+    pub rules: RuleSet,
+    /// Seed of the current round's deal when the rules ask for seeded deals. Only its
+    /// commitment is shown until the round ends.
+    pub deal_seed: Option<DeckSeed>,
+    /// Where round seeds come from in a seeded game; fresh random seeds otherwise
+    seed_source: Option<ChaCha20Rng>,
+}
+
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GamePhase {
+    Bidding,
+    Playing,
+    RoundComplete,
+    GameComplete,
+}
+
+impl GameState {
+    /// Initialize a new game with players starting at round 1 with 1 card
+    pub fn new(players: Vec<PlayerId>) -> Self {
+        Self::with_schedule(players, RoundSchedule::default())
+    }
+
+    /// Initialize a new game that deals rounds according to `schedule`
+    pub fn with_schedule(players: Vec<PlayerId>, schedule: RoundSchedule) -> Self {
+        Self::with_rules(players, schedule, RuleSet::default())
+    }
+
+    /// Initialize a new game played by the lobby's house rules
+    pub fn with_rules(players: Vec<PlayerId>, schedule: RoundSchedule, rules: RuleSet) -> Self {
+        Self::build(players, schedule, rules, None)
+    }
+
+    /// Initialize a game whose every deal follows from `seed`, so the same seed and actions
+    /// always play out the same game
+    pub fn with_seed(players: Vec<PlayerId>, schedule: RoundSchedule, rules: RuleSet, seed: DeckSeed) -> Self {
+        Self::build(players, schedule, rules, Some(seed.rng()))
+    }
+
+    /// Pick a game back up after the rounds in `history`, dealing the next one. Seeded games
+    /// continue with fresh seeds, since the seed source is not part of the history.
+    pub fn resume(players: Vec<PlayerId>, schedule: RoundSchedule, rules: RuleSet, history: Vec<RoundResult>) -> Self {
+        let mut state = Self::build(players, schedule, rules, None);
+        for round in &history {
+            for result in &round.player_results {
+                *state.total_scores.entry(result.player_id.clone()).or_insert(0) += result.score;
+            }
+            // The first bidder moves one seat on each round
+            state.first_bidder = state.seat_after(&state.first_bidder);
+        }
+        state.round_number = history.len() + 1;
+        state.history = Arc::new(history);
+        state.start_round();
+        state
+    }
+
+    fn build(players: Vec<PlayerId>, schedule: RoundSchedule, rules: RuleSet, seed_source: Option<ChaCha20Rng>) -> Self {
+        let first_player = players[0].clone();
+        
+        // Initialize empty collections
+        let mut total_scores = HashMap::new();
+        for player in &players {
+            total_scores.insert(player.clone(), 0);
+        }
+        let current_round = players.iter().map(|pid| {
+            PlayerRoundResult {
+                player_id: pid.clone(),
+                bid: 0,
+                tricks_won: 0,
+                penalty: 0,
+                score: 0,
+            }
+        }).collect();
+        
+        let mut state = Self {
+            phase: GamePhase::Bidding,
+            round_number: 1,
+            cards_per_player: 1,
+            deck: Deck::new_german_bridge(),
+            hands: HashMap::new(),
+            current_trick: Trick::new(),
+            completed_tricks: Vec::new(),
+            total_scores,
+            trump_suit: None,
+            current_round,
+            current_player: first_player.clone(),
+            first_bidder: first_player.clone(),
+            bidding_state: None,
+            players,
+            history: Arc::default(),
+            version: 0,
+            schedule,
+            rules,
+            deal_seed: None,
+            seed_source,
+        };
+        
+        // Start the first round
+        state.start_round();
+        state
+    }
+
+    /// Calculate scores for the round using ScoreCalculator
+    fn calculate_round_scores(&mut self) {
+        // Convert player_bids to HashMap<PlayerId, Bid>
+        let bids: HashMap<PlayerId, Bid> = self.current_round.iter()
+            .map(|pr| (pr.player_id.clone(), Bid { tricks: pr.bid }))
+            .collect();
+        
+        let tricks_won: HashMap<PlayerId, u8> = self.current_round.iter()
+            .map(|pr| (pr.player_id.clone(), pr.tricks_won))
+            .collect();
+
+        // Calculate round scores
+        let round_scores = ScoreCalculator::calculate_round_scores(&bids, &tricks_won);
+        
+        // Update current_round with calculated scores and total scores
+        for pr in self.current_round.iter_mut() {
+            if let Some(&score) = round_scores.get(&pr.player_id) {
+                pr.score = score - pr.penalty;
+                *self.total_scores.entry(pr.player_id.clone()).or_insert(0) += pr.score;
+            }
+        }
+
+
+        // Record round history
+        let result = RoundResult {
+            round_number: self.round_number,
+            trump_suit: self.trump_suit,
+            player_results: self.current_round.clone(),
+            deal_seed: self.deal_seed.as_ref().map(DeckSeed::to_hex),
+        };
+        Arc::make_mut(&mut self.history).push(result);
+    }
+    
+    /// Start a new round: deal cards, select random trump, reset round state
+    pub fn start_round(&mut self) {
+        // Create and shuffle a new deck; a random trump comes from the same generator
+        let seed = match self.seed_source.as_mut() {
+            Some(source) => DeckSeed::from_rng(source),
+            None => DeckSeed::random(),
+        };
+        let mut rng = seed.rng();
+        self.deck = Deck::new_german_bridge();
+        self.deck.shuffle_with(&mut rng);
+        self.trump_suit = self.rules.trump.trump_for_round(self.round_number, &mut rng);
+        self.deal_seed = self.rules.seeded_deals.then_some(seed);
+        
+        // Deal cards to players
+        let num_players = self.players.len();
+        
+        // Cards per player for this round come from the round schedule
+        // Restart the schedule if we somehow ran past its end
+        let max_cards_per_player = self.max_cards_per_player();
+        match self.schedule.cards_for_round(self.round_number, max_cards_per_player) {
+            Some(cards) => self.cards_per_player = cards,
+            None => {
+                self.round_number = 1;
+                self.cards_per_player = 1;
+            }
+        }
+        
+        // Deal the cards
+        let hands = self.deck.deal(num_players, self.cards_per_player);
+        self.hands.clear();
+        for (i, hand) in hands.into_iter().enumerate() {
+            self.hands.insert(self.players[i].clone(), hand);
+        }
+        
+        // Reset round state
+        self.phase = GamePhase::Bidding;
+        self.current_trick = Trick::new();
+        self.completed_tricks.clear();
+
+        // Reset current_round for new round
+        self.current_round = self.players.iter().map(|pid| {
+            PlayerRoundResult {
+                player_id: pid.clone(),
+                bid: 0,
+                tricks_won: 0,
+                penalty: 0,
+                score: 0,
+            }
+        }).collect();
+
+        // Set up bidding state
+        self.current_player = self.first_bidder.clone();
+        let mut bidding_state = BiddingState::new(
+            self.first_bidder.clone(),
+            self.turn_order(),
+            self.cards_per_player,
+        );
+        if !self.rules.zero_bids.allows_zero(self.cards_per_player) {
+            bidding_state = bidding_state.without_zero_bids();
+        }
+        self.bidding_state = Some(bidding_state);
+        self.version += 1;
+    }
+    
+    /// Every seat in the order turns pass around the table
+    pub fn turn_order(&self) -> Vec<PlayerId> {
+        match self.rules.deal_direction {
+            DealDirection::Clockwise => self.players.clone(),
+            DealDirection::CounterClockwise => self.players.iter().rev().cloned().collect(),
+        }
+    }
+
+    /// The player whose turn follows `player_id`'s
+    pub fn seat_after(&self, player_id: &PlayerId) -> PlayerId {
+        let len = self.players.len();
+        let index = self.players.iter().position(|p| p == player_id).unwrap_or(0);
+        let next = match self.rules.deal_direction {
+            DealDirection::Clockwise => (index + 1) % len,
+            DealDirection::CounterClockwise => (index + len - 1) % len,
+        };
+        self.players[next].clone()
+    }
+
+    /// Validate a player action
+    pub fn validate_action(&self, player_id: &PlayerId, action: &PlayerAction) -> Result<(), GameError> {
+        
+        // Check if it's the player's turn
+        if *player_id != self.current_player {
+            return Err(GameError::NotPlayerTurn);
+        }
+        
+        // Check if player is in the game
+        if !self.players.contains(player_id) {
+            return Err(GameError::PlayerNotInGame);
+        }
+        
+        match action {
+            PlayerAction::Bid(bid) => {
+                // Must be in bidding phase
+                if self.phase != GamePhase::Bidding {
+                    return Err(GameError::InvalidMove(
+                        "Not in bidding phase".to_string()
+                    ));
+                }
+                
+                // Validate the bid
+                self.validate_bid(player_id, bid.tricks)?;
+            }
+            PlayerAction::PlayCard(card) => {
+                // Must be in playing phase
+                if self.phase != GamePhase::Playing {
+                    return Err(GameError::InvalidMove(
+                        "Not in playing phase".to_string()
+                    ));
+                }
+                
+                // Check if player has the card
+                let hand = self.hands.get(player_id)
+                    .ok_or(GameError::PlayerNotInGame)?;
+                
+                if !hand.has_card(card) {
+                    return Err(GameError::InvalidMove(
+                        "Card not in hand".to_string()
+                    ));
+                }
+                
+                // Check if card is a valid play (follows suit if required)
+                if !hand.can_play(card, self.current_trick.lead_suit) {
+                    return Err(GameError::InvalidMove(
+                        "Must follow suit if possible".to_string()
+                    ));
+                }
+            }
+            PlayerAction::HiddenBid => {
+                return Err(GameError::InvalidMove(
+                    "Hidden bids are only sent by the server".to_string()
+                ));
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Validate a bid
+    pub fn validate_bid(&self, player_id: &PlayerId, bid: u8) -> Result<(), GameError> {
+        // Check bid range
+        if bid as usize > self.cards_per_player {
+            return Err(GameError::InvalidMove(format!(
+                "Bid {} exceeds cards dealt {}",
+                bid, self.cards_per_player
+            )));
+        }
+        
+        // Check last bidder restriction
+        if let Some(ref bidding_state) = self.bidding_state {
+            if bidding_state.is_last_bidder(player_id) {
+                bidding_state.validate_last_bid(bid)?;
+            }
+            if bidding_state.current_bidder == *player_id {
+                bidding_state.validate_zero_bid(bid)?;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Apply a player action to update the game state; the events say what it did
+    pub fn apply_action(&mut self, player_id: PlayerId, action: PlayerAction) -> Result<Events, GameError> {
+        // Validate the action first
+        self.validate_action(&player_id, &action)?;
+
+        let mut events = Events::new();
+        match action {
+            PlayerAction::Bid(bid) => {
+                // Record the bid in current_round
+                if let Some(pr) = self.current_round.iter_mut()
+                    .find(|pr| pr.player_id == player_id) {
+                    pr.bid = bid.tricks;
+                }
+
+                // Update bidding state
+                if let Some(ref mut bidding_state) = self.bidding_state {
+                    bidding_state.place_bid(player_id.clone(), bid.tricks)?;
+                    events.push(Event::BidPlaced { player_id, tricks: bid.tricks });
+
+                    // Check if bidding is complete
+                    if bidding_state.is_complete() {
+                        // Transition to playing phase
+                        self.phase = GamePhase::Playing;
+                        self.current_player = self.first_bidder.clone();
+                        self.bidding_state = None;
+                        events.push(Event::BiddingComplete { leader: self.current_player.clone() });
+                    } else {
+                        // Move to next bidder
+                        self.current_player = bidding_state.current_bidder.clone();
+                    }
+                }
+            }
+            PlayerAction::PlayCard(card) => {
+                // Remove card from player's hand
+                if let Some(hand) = self.hands.get_mut(&player_id) {
+                    hand.play_card(card)?;
+                }
+
+                // Add card to current trick
+                self.current_trick.add_card(player_id.clone(), card);
+                events.push(Event::CardPlayed { player_id, card });
+
+                // Check if trick is complete
+                if self.current_trick.is_complete(self.players.len()) {
+                    self.complete_trick(&mut events)?;
+                } else {
+                    // Move to next player
+                    self.advance_turn();
+                }
+            }
+            // Rejected by validate_action above
+            PlayerAction::HiddenBid => unreachable!(),
+        }
+        
+        self.version += 1;
+        Ok(events)
+    }
+
+    /// Complete a trick and update state
+    fn complete_trick(&mut self, events: &mut Events) -> Result<(), GameError> {
+        // Determine the winner
+        let winner = self.current_trick.winner(self.trump_suit)
+            .ok_or_else(|| GameError::InvalidMove(
+                "Cannot determine trick winner".to_string()
+            ))?;
+        
+        // Update tricks won in current_round
+        if let Some(pr) = self.current_round.iter_mut()
+            .find(|pr| pr.player_id == winner) {
+            pr.tricks_won += 1;
+        }
+        events.push(Event::TrickWon { winner: winner.clone() });
+
+        // Store completed trick and start a new one with the winner leading
+        let trick = std::mem::take(&mut self.current_trick);
+        self.current_player = winner.clone();
+        self.completed_tricks.push(CompletedTrick { winner, cards: trick.cards });
+        
+        // Check if round is complete (all cards played)
+        let all_hands_empty = self.hands.values().all(|hand| hand.cards().is_empty());
+        if all_hands_empty {
+            self.calculate_round_scores();
+            self.phase = GamePhase::RoundComplete;
+            events.push(Event::RoundComplete { round_number: self.round_number });
+
+            // Check if game should continue
+            if !self.should_continue_game() {
+                self.phase = GamePhase::GameComplete;
+                events.push(Event::GameComplete);
+            }
+            
+            // The GameManager will wait for StartNextRound message.
+            // Set current player to the one who will start the next round
+            // This allows the frontend to show the "Start Next Round" button to the correct person
+            self.current_player = self.seat_after(&self.first_bidder);
+        }
+        
+        Ok(())
+    }
+
+    /// Advance to the next round (called by GameManager after delay)
+    pub fn advance_to_next_round(&mut self) -> Events {
+        let mut events = Events::new();
+        if self.phase == GamePhase::RoundComplete && self.should_continue_game() {
+             // Advance to next round
+            self.round_number += 1;
+            // Rotate first bidder
+            self.first_bidder = self.seat_after(&self.first_bidder);
+            
+            self.start_round();
+            events.push(Event::RoundStarted {
+                round_number: self.round_number,
+                cards_per_player: self.cards_per_player,
+                trump_suit: self.trump_suit,
+            });
+        }
+        events
+    }
+    
+
+    
+    /// Most cards each player can be dealt from a single deck, or fewer if the rules cap it
+    pub fn max_cards_per_player(&self) -> usize {
+        self.rules.max_cards_per_player(self.players.len())
+    }
+
+    /// SHA-256 of the current round's seed, in hex, when deals are seeded
+    pub fn deal_commitment(&self) -> Option<String> {
+        self.deal_seed.as_ref().map(DeckSeed::commitment)
+    }
+
+    /// Rounds the game lasts unless someone reaches the target score first
+    pub fn total_rounds(&self) -> usize {
+        let scheduled = self.schedule.total_rounds(self.max_cards_per_player());
+        self.rules.round_limit.map_or(scheduled, |limit| limit.min(scheduled))
+    }
+
+    /// Check if rounds are left and nobody has reached the target score
+    pub fn should_continue_game(&self) -> bool {
+        let target_reached = self.rules.target_score
+            .is_some_and(|target| self.total_scores.values().any(|&score| score >= target));
+        !target_reached && self.round_number < self.total_rounds()
+    }
+
+    /// Give `old`'s seat to `new`: hand, score, bids, tricks and history all move over
+    pub fn replace_player(&mut self, old: &PlayerId, new: &PlayerId) {
+        let swap = |id: &mut PlayerId| {
+            if id == old {
+                *id = new.clone();
+            }
+        };
+
+        self.players.iter_mut().for_each(swap);
+        swap(&mut self.current_player);
+        swap(&mut self.first_bidder);
+        if let Some(hand) = self.hands.remove(old) {
+            self.hands.insert(new.clone(), hand);
+        }
+        if let Some(score) = self.total_scores.remove(old) {
+            self.total_scores.insert(new.clone(), score);
+        }
+        self.current_round.iter_mut().for_each(|r| swap(&mut r.player_id));
+        for round in Arc::make_mut(&mut self.history).iter_mut() {
+            round.player_results.iter_mut().for_each(|r| swap(&mut r.player_id));
+        }
+        self.current_trick.cards.iter_mut().for_each(|(id, _)| swap(id));
+        for trick in self.completed_tricks.iter_mut() {
+            swap(&mut trick.winner);
+            trick.cards.iter_mut().for_each(|(id, _)| swap(id));
+        }
+        if let Some(bidding) = self.bidding_state.as_mut() {
+            if let Some(bid) = bidding.bids.remove(old) {
+                bidding.bids.insert(new.clone(), bid);
+            }
+            swap(&mut bidding.current_bidder);
+            bidding.player_order.iter_mut().for_each(swap);
+        }
+        self.version += 1;
+    }
+
+    /// Sanity-check internal consistency; returns a description of the first violation found
+    pub fn check_invariants(&self) -> Result<(), String> {
+        if !self.players.contains(&self.current_player) {
+            return Err(format!("Current player {} is not seated", self.current_player));
+        }
+
+        // A card can only be in one place at a time
+        let mut seen = std::collections::HashSet::new();
+        let held = self.hands.values().flat_map(|hand| hand.cards().iter());
+        let in_trick = self.current_trick.cards.iter().map(|(_, card)| card);
+        for card in held.chain(in_trick) {
+            if !seen.insert(*card) {
+                return Err(format!("Card {:?} appears more than once", card));
+            }
+        }
+
+        if matches!(self.phase, GamePhase::Bidding | GamePhase::Playing) {
+            // Every player holds what was dealt minus what they have played this round
+            for pid in &self.players {
+                let held = self.hands.get(pid).map(|hand| hand.cards().len()).unwrap_or(0);
+                let played = self.completed_tricks.len()
+                    + self.current_trick.cards.iter().filter(|(p, _)| p == pid).count();
+                if held + played != self.cards_per_player {
+                    return Err(format!(
+                        "Player {} holds {} cards after playing {}, expected {} in total",
+                        pid, held, played, self.cards_per_player
+                    ));
+                }
+            }
+
+            let tricks_won: usize = self.current_round.iter().map(|pr| pr.tricks_won as usize).sum();
+            if tricks_won != self.completed_tricks.len() {
+                return Err(format!(
+                    "{} tricks won recorded for {} completed tricks",
+                    tricks_won,
+                    self.completed_tricks.len()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+    
+    /// Advance to the next player in rotation
+    pub fn advance_turn(&mut self) {
+        self.current_player = self.seat_after(&self.current_player);
+    }
+    
+    /// The action `strategy` takes for the current player when they do not act themselves
+    pub fn get_auto_action(&self, strategy: &dyn AutoPlayStrategy) -> Option<PlayerAction> {
+        strategy.choose(self)
+    }
+
+    /// Take `points` off the player's score for the current round
+    pub fn penalize(&mut self, player_id: &PlayerId, points: u32) {
+        if let Some(result) = self.current_round.iter_mut().find(|pr| pr.player_id == *player_id) {
+            result.penalty += points as i32;
+        }
+    }
+    
+    /// Get valid actions for a specific player
+    pub fn get_valid_actions(&self, player_id: PlayerId) -> Vec<PlayerAction> {
+        
+        let mut actions = Vec::new();
+        
+        // Only current player has valid actions
+        if self.current_player != player_id {
+            return actions;
+        }
+        
+        match self.phase {
+            GamePhase::Bidding => {
+                // Check all possible bids (0 to cards_per_player)
+                for tricks in 0..=self.cards_per_player {
+                    if self.validate_bid(&player_id, tricks as u8).is_ok() {
+                        actions.push(PlayerAction::Bid(Bid { tricks: tricks as u8 }));
+                    }
+                }
+            }
+            GamePhase::Playing => {
+                if let Some(hand) = self.hands.get(&player_id) {
+                    let valid_cards = hand.valid_plays(self.current_trick.lead_suit);
+                    for card in valid_cards {
+                        actions.push(PlayerAction::PlayCard(card));
+                    }
+                }
+            }
+            _ => {}
+        }
+        
+        actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::auto_play::LowestLegal;
+
+    /// Play out the game with automatic actions, collecting every event
+    fn play_out(state: &mut GameState) -> Vec<Event> {
+        let mut events = Vec::new();
+        while state.phase != GamePhase::GameComplete {
+            if state.phase == GamePhase::RoundComplete {
+                events.extend(state.advance_to_next_round());
+                continue;
+            }
+            let player = state.current_player.clone();
+            let action = state.get_auto_action(&LowestLegal).unwrap();
+            events.extend(state.apply_action(player, action).unwrap());
+        }
+        events
+    }
+
+    #[test]
+    fn test_same_seed_and_actions_replay_the_same_game() {
+        let players = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let seed = DeckSeed::random();
+        let rules = RuleSet { round_limit: Some(4), ..Default::default() };
+        let mut first = GameState::with_seed(players.clone(), RoundSchedule::Ascending, rules.clone(), seed);
+        let mut second = GameState::with_seed(players, RoundSchedule::Ascending, rules, seed);
+
+        assert_eq!(play_out(&mut first), play_out(&mut second));
+        assert_eq!(first.total_scores, second.total_scores);
+    }
+
+    #[test]
+    fn test_resume_deals_the_round_after_the_history() {
+        let players: Vec<PlayerId> = vec!["a".into(), "b".into(), "c".into()];
+        let mut state = GameState::new(players.clone());
+        for round in 1..=2 {
+            if round > 1 {
+                state.advance_to_next_round();
+            }
+            while state.phase != GamePhase::RoundComplete {
+                let player = state.current_player.clone();
+                let action = state.get_auto_action(&LowestLegal).unwrap();
+                state.apply_action(player, action).unwrap();
+            }
+        }
+        let history = state.history.as_ref().clone();
+
+        let resumed = GameState::resume(players, RoundSchedule::default(), RuleSet::default(), history.clone());
+        state.advance_to_next_round();
+        assert_eq!(resumed.round_number, 3);
+        assert_eq!(resumed.cards_per_player, state.cards_per_player);
+        assert_eq!(resumed.first_bidder, state.first_bidder);
+        assert_eq!(resumed.total_scores, state.total_scores);
+        assert_eq!(resumed.history.len(), 2);
+        assert_eq!(resumed.phase, GamePhase::Bidding);
+        assert!(resumed.hands.values().all(|hand| hand.cards().len() == resumed.cards_per_player));
+    }
+
+    #[test]
+    fn test_state_survives_a_json_round_trip_mid_round() {
+        let players: Vec<PlayerId> = vec!["a".into(), "b".into(), "c".into()];
+        let rules = RuleSet { seeded_deals: true, ..RuleSet::default() };
+        let mut state = GameState::with_seed(players, RoundSchedule::Ascending, rules, DeckSeed::random());
+        for _ in 0..4 {
+            let player = state.current_player.clone();
+            let action = state.get_auto_action(&LowestLegal).unwrap();
+            state.apply_action(player, action).unwrap();
+        }
+        assert_eq!(state.phase, GamePhase::Playing);
+
+        let mut restored: GameState = serde_json::from_str(&serde_json::to_string(&state).unwrap()).unwrap();
+        assert_eq!(serde_json::to_value(&restored).unwrap(), serde_json::to_value(&state).unwrap());
+
+        // Both play on identically, into the next round's seeded deal
+        while state.phase != GamePhase::RoundComplete {
+            let player = state.current_player.clone();
+            let action = state.get_auto_action(&LowestLegal).unwrap();
+            state.apply_action(player.clone(), action.clone()).unwrap();
+            restored.apply_action(player, action).unwrap();
+        }
+        state.advance_to_next_round();
+        restored.advance_to_next_round();
+        assert_eq!(restored.deal_seed, state.deal_seed);
+        assert_eq!(serde_json::to_value(&restored).unwrap(), serde_json::to_value(&state).unwrap());
+    }
+
+    #[test]
+    fn test_single_card_round_emits_events_in_order() {
+        let players = vec!["a".to_string(), "b".to_string()];
+        let mut state = GameState::with_seed(players, RoundSchedule::Ascending, RuleSet::default(), DeckSeed::random());
+        let first = state.current_player.clone();
+
+        let bid = state.get_auto_action(&LowestLegal).unwrap();
+        let events = state.apply_action(first.clone(), bid).unwrap();
+        assert!(matches!(events.as_slice(), [Event::BidPlaced { player_id, .. }] if *player_id == first));
+
+        let second = state.current_player.clone();
+        let bid = state.get_auto_action(&LowestLegal).unwrap();
+        let events = state.apply_action(second.clone(), bid).unwrap();
+        assert!(matches!(events.as_slice(), [Event::BidPlaced { .. }, Event::BiddingComplete { leader }] if *leader == state.current_player));
+
+        let leader = state.current_player.clone();
+        let card = state.get_auto_action(&LowestLegal).unwrap();
+        let events = state.apply_action(leader, card).unwrap();
+        assert!(matches!(events.as_slice(), [Event::CardPlayed { .. }]));
+
+        let follower = state.current_player.clone();
+        let card = state.get_auto_action(&LowestLegal).unwrap();
+        let events = state.apply_action(follower, card).unwrap();
+        assert!(matches!(events.as_slice(), [Event::CardPlayed { .. }, Event::TrickWon { .. }, Event::RoundComplete { round_number: 1 }]));
+        assert_eq!(state.phase, GamePhase::RoundComplete);
+
+        let events = state.advance_to_next_round();
+        assert!(matches!(events.as_slice(), [Event::RoundStarted { round_number: 2, cards_per_player: 2, .. }]));
+    }
+
+    #[test]
+    fn test_penalty_comes_off_the_round_score() {
+        let players = vec!["a".to_string(), "b".to_string()];
+        let mut state = GameState::with_seed(players, RoundSchedule::Ascending, RuleSet::default(), DeckSeed::random());
+        let late = state.current_player.clone();
+        state.penalize(&late, 5);
+        while state.phase != GamePhase::RoundComplete {
+            let player = state.current_player.clone();
+            let action = state.get_auto_action(&LowestLegal).unwrap();
+            state.apply_action(player, action).unwrap();
+        }
+
+        let result = state.history[0].player_results.iter().find(|pr| pr.player_id == late).unwrap();
+        assert_eq!(result.penalty, 5);
+        assert_eq!(result.score, ScoreCalculator::calculate_player_score(result.bid, result.tricks_won) - 5);
+        assert_eq!(state.total_scores[&late], result.score);
+    }
+}
@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::engine::PlayerId;
+use crate::engine::scoring::RoundResult;
+
+/// A rule for separating players level on points, applied in the order the lobby lists them
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TieBreaker {
+    /// Most rounds where the bid was made exactly
+    MostExactBids,
+    /// Fewest points lost in failed rounds. Every player bids every round, so a plain count
+    /// of failed rounds would always mirror MostExactBids; this weighs each by how badly it was missed.
+    SmallestPenalty,
+}
+
+impl TieBreaker {
+    /// Tie-breakers used when a lobby does not choose
+    pub fn defaults() -> Vec<TieBreaker> {
+        vec![TieBreaker::MostExactBids, TieBreaker::SmallestPenalty]
+    }
+
+    /// The player's standing under this rule; higher is better
+    fn key(&self, player_id: &PlayerId, history: &[RoundResult]) -> i64 {
+        let results = history.iter()
+            .flat_map(|round| round.player_results.iter())
+            .filter(|r| r.player_id == *player_id);
+        match self {
+            TieBreaker::MostExactBids => results.filter(|r| r.bid == r.tricks_won).count() as i64,
+            TieBreaker::SmallestPenalty => results.map(|r| r.score.min(0) as i64).sum(),
+        }
+    }
+}
+
+/// How the winners were decided
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum WinReason {
+    /// One player had the most points
+    HighestScore,
+    /// Several players had the most points and `rule` separated them
+    TieBreak { rule: TieBreaker },
+    /// The tie-breakers left several players level; they share the win
+    SharedWin,
+}
+
+/// Winners of a finished game and why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameOutcome {
+    /// In seat order; more than one for a shared win
+    pub winners: Vec<PlayerId>,
+    pub reason: WinReason,
+}
+
+/// Decide the winners from final scores, breaking ties with `rules` in order.
+/// `excluded` players (those who forfeited) cannot win.
+pub fn decide_winners(
+    players: &[PlayerId],
+    scores: &HashMap<PlayerId, i32>,
+    history: &[RoundResult],
+    rules: &[TieBreaker],
+    excluded: &[PlayerId],
+) -> GameOutcome {
+    let eligible: Vec<&PlayerId> = players.iter().filter(|p| !excluded.contains(p)).collect();
+    let score = |p: &PlayerId| scores.get(p).copied().unwrap_or(0);
+    let Some(best) = eligible.iter().map(|p| score(p)).max() else {
+        return GameOutcome { winners: Vec::new(), reason: WinReason::SharedWin };
+    };
+
+    let mut contenders: Vec<&PlayerId> = eligible.into_iter().filter(|p| score(p) == best).collect();
+    if contenders.len() == 1 {
+        return GameOutcome { winners: vec![contenders[0].clone()], reason: WinReason::HighestScore };
+    }
+
+    for rule in rules {
+        let best = contenders.iter().map(|p| rule.key(p, history)).max().unwrap_or_default();
+        contenders.retain(|p| rule.key(p, history) == best);
+        if contenders.len() == 1 {
+            return GameOutcome { winners: vec![contenders[0].clone()], reason: WinReason::TieBreak { rule: *rule } };
+        }
+    }
+
+    GameOutcome {
+        winners: contenders.into_iter().cloned().collect(),
+        reason: WinReason::SharedWin,
+    }
+}
+
+/// A player's finishing position. Players level on points and every tie-breaker share a
+/// place and the next place is skipped (1st, 1st, 3rd).
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Placement {
+    pub player_id: PlayerId,
+    /// 1 for the winners
+    pub place: usize,
+    pub score: i32,
+}
+
+/// Order players by final score, then by `rules` in order; players level on all of them
+/// keep seat order. `excluded` players (those who forfeited) are not placed.
+pub fn rank_players(
+    players: &[PlayerId],
+    scores: &HashMap<PlayerId, i32>,
+    history: &[RoundResult],
+    rules: &[TieBreaker],
+    excluded: &[PlayerId],
+) -> Vec<Placement> {
+    let standing = |p: &PlayerId| {
+        let keys: Vec<i64> = rules.iter().map(|rule| rule.key(p, history)).collect();
+        (scores.get(p).copied().unwrap_or(0), keys)
+    };
+    let mut ranked: Vec<(&PlayerId, (i32, Vec<i64>))> = players.iter()
+        .filter(|p| !excluded.contains(p))
+        .map(|p| (p, standing(p)))
+        .collect();
+    // Stable, so seat order survives among equals
+    ranked.sort_by(|a, b| b.1.cmp(&a.1));
+
+    let mut placements: Vec<Placement> = Vec::with_capacity(ranked.len());
+    for (i, (player_id, standing)) in ranked.iter().enumerate() {
+        let place = match placements.last() {
+            Some(previous) if ranked[i - 1].1 == *standing => previous.place,
+            _ => i + 1,
+        };
+        placements.push(Placement { player_id: (*player_id).clone(), place, score: standing.0 });
+    }
+    placements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::scoring::PlayerRoundResult;
+    use crate::engine::scoring::ScoreCalculator;
+
+    fn round(round_number: usize, results: &[(&str, u8, u8)]) -> RoundResult {
+        RoundResult {
+            round_number,
+            trump_suit: None,
+            player_results: results.iter().map(|&(id, bid, tricks_won)| PlayerRoundResult {
+                player_id: id.to_string(),
+                bid,
+                tricks_won,
+                penalty: 0,
+                score: ScoreCalculator::calculate_player_score(bid, tricks_won),
+            }).collect(),
+            deal_seed: None,
+        }
+    }
+
+    fn totals(history: &[RoundResult]) -> HashMap<PlayerId, i32> {
+        let mut scores = HashMap::new();
+        for r in history.iter().flat_map(|round| round.player_results.iter()) {
+            *scores.entry(r.player_id.clone()).or_insert(0) += r.score;
+        }
+        scores
+    }
+
+    fn players() -> Vec<PlayerId> {
+        vec!["a".to_string(), "b".to_string(), "c".to_string()]
+    }
+
+    #[test]
+    fn test_highest_score_wins_outright() {
+        let history = vec![round(1, &[("a", 1, 1), ("b", 0, 1), ("c", 1, 0)])];
+        let outcome = decide_winners(&players(), &totals(&history), &history, &TieBreaker::defaults(), &[]);
+        assert_eq!(outcome, GameOutcome { winners: vec!["a".to_string()], reason: WinReason::HighestScore });
+    }
+
+    #[test]
+    fn test_penalty_separates_equal_exact_bids() {
+        let history = vec![
+            round(1, &[("a", 1, 1), ("b", 1, 1), ("c", 0, 1)]),
+            round(2, &[("a", 2, 0), ("b", 1, 0), ("c", 0, 1)]),
+        ];
+        // Level on points and on exact bids; a lost 4 points, b only 1
+        let level: HashMap<PlayerId, i32> = [("a", 20), ("b", 20), ("c", 0)].iter()
+            .map(|(p, s)| (p.to_string(), *s)).collect();
+
+        let outcome = decide_winners(&players(), &level, &history, &TieBreaker::defaults(), &[]);
+        assert_eq!(outcome, GameOutcome {
+            winners: vec!["b".to_string()],
+            reason: WinReason::TieBreak { rule: TieBreaker::SmallestPenalty },
+        });
+
+        let outcome = decide_winners(&players(), &level, &history, &[TieBreaker::MostExactBids], &[]);
+        assert_eq!(outcome, GameOutcome { winners: vec!["a".to_string(), "b".to_string()], reason: WinReason::SharedWin });
+    }
+
+    #[test]
+    fn test_rules_apply_in_order() {
+        let history = vec![
+            round(1, &[("a", 1, 1), ("b", 2, 0), ("c", 0, 1)]),
+            round(2, &[("a", 2, 0), ("b", 1, 1), ("c", 0, 1)]),
+            round(3, &[("a", 1, 0), ("b", 0, 0), ("c", 0, 1)]),
+        ];
+        // Both lose points, a more heavily; b made more exact bids
+        let level: HashMap<PlayerId, i32> = [("a", 10), ("b", 10), ("c", 0)].iter()
+            .map(|(p, s)| (p.to_string(), *s)).collect();
+
+        let outcome = decide_winners(&players(), &level, &history, &TieBreaker::defaults(), &[]);
+        assert_eq!(outcome, GameOutcome {
+            winners: vec!["b".to_string()],
+            reason: WinReason::TieBreak { rule: TieBreaker::MostExactBids },
+        });
+
+        let outcome = decide_winners(&players(), &level, &history, &[TieBreaker::SmallestPenalty], &[]);
+        assert_eq!(outcome, GameOutcome {
+            winners: vec!["b".to_string()],
+            reason: WinReason::TieBreak { rule: TieBreaker::SmallestPenalty },
+        });
+    }
+
+    #[test]
+    fn test_placements_share_places_and_agree_with_winners() {
+        let history = vec![
+            round(1, &[("a", 1, 1), ("b", 1, 0), ("c", 0, 1)]),
+            round(2, &[("a", 2, 0), ("b", 1, 1), ("c", 0, 1)]),
+        ];
+        let scores: HashMap<PlayerId, i32> = [("a", 10), ("b", 10), ("c", 25)].iter()
+            .map(|(p, s)| (p.to_string(), *s)).collect();
+        let places = |rules: &[TieBreaker], excluded: &[PlayerId]| -> Vec<(String, usize)> {
+            rank_players(&players(), &scores, &history, rules, excluded).into_iter()
+                .map(|p| (p.player_id, p.place))
+                .collect()
+        };
+
+        // a and b each made one exact bid; b lost fewer points
+        assert_eq!(places(&TieBreaker::defaults(), &[]), vec![("c".into(), 1), ("b".into(), 2), ("a".into(), 3)]);
+        assert_eq!(places(&[TieBreaker::MostExactBids], &[]), vec![("c".into(), 1), ("a".into(), 2), ("b".into(), 2)]);
+        assert_eq!(places(&[], &["c".to_string()]), vec![("a".into(), 1), ("b".into(), 1)]);
+
+        let placements = rank_players(&players(), &scores, &history, &[], &["c".to_string()]);
+        let winners: Vec<PlayerId> = placements.iter().filter(|p| p.place == 1).map(|p| p.player_id.clone()).collect();
+        assert_eq!(winners, decide_winners(&players(), &scores, &history, &[], &["c".to_string()]).winners);
+        assert_eq!(placements[0].score, 10);
+    }
+
+    #[test]
+    fn test_no_rules_means_shared_win_and_forfeits_cannot_win() {
+        let history = vec![round(1, &[("a", 1, 1), ("b", 1, 1), ("c", 0, 0)])];
+        let scores = totals(&history);
+        let outcome = decide_winners(&players(), &scores, &history, &[], &[]);
+        assert_eq!(outcome, GameOutcome { winners: vec!["a".to_string(), "b".to_string()], reason: WinReason::SharedWin });
+
+        let outcome = decide_winners(&players(), &scores, &history, &[], &["a".to_string()]);
+        assert_eq!(outcome, GameOutcome { winners: vec!["b".to_string()], reason: WinReason::HighestScore });
+    }
+}
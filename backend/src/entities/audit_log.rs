@@ -0,0 +1,25 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Append-only; rows are never updated or deleted
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize, utoipa::ToSchema)]
+#[sea_orm(table_name = "audit_log")]
+#[schema(as = AuditEntry)]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// An `AuditKind`
+    pub kind: String,
+    /// User or player id of whoever acted, or `admin-token` for the shared admin token
+    pub actor: Option<String>,
+    pub ip: Option<String>,
+    #[schema(value_type = Object)]
+    pub detail: Json,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
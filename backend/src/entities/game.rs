@@ -10,6 +10,17 @@ pub struct Model {
     pub state: Json,
     pub created_at: DateTimeUtc,
     pub completed_at: Option<DateTimeUtc>,
+    pub abandoned_at: Option<DateTimeUtc>,
+    /// `GameOutcome` of a completed game
+    pub outcome: Option<Json>,
+    pub deleted_at: Option<DateTimeUtc>,
+    pub variant: String,
+    pub player_count: i32,
+    pub tournament_id: Option<Uuid>,
+    #[sea_orm(indexed)]
+    pub code: Option<String>,
+    /// Left off the leaderboard
+    pub unrated: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,37 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "game_actions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub game_id: Uuid,
+    /// State version the action produced
+    pub version: i64,
+    pub round_number: i32,
+    pub player_id: String,
+    /// The `PlayerAction` as applied
+    pub action: Json,
+    /// Played for the player on a turn timeout
+    pub auto: bool,
+    pub played_at: DateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::game::Entity",
+        from = "Column::GameId",
+        to = "super::game::Column::Id"
+    )]
+    Game,
+}
+
+impl Related<super::game::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Game.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
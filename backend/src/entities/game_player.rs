@@ -9,6 +9,8 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub player_id: Uuid,
     pub final_score: Option<i32>,
+    /// Finishing place, 1 for the winners; null until the game completes and for forfeits
+    pub placement: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
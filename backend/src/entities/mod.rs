@@ -5,3 +5,15 @@ pub mod lobby_player;
 pub mod game;
 pub mod game_player;
 pub mod game_round;
+pub mod node;
+pub mod bug_report;
+pub mod weekly_digest;
+pub mod lobby_preset;
+pub mod tournament;
+pub mod round_deal;
+pub mod password_reset_token;
+pub mod friendship;
+pub mod user_block;
+pub mod game_action;
+pub mod audit_log;
+pub mod user_achievement;
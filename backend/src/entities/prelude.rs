@@ -4,3 +4,15 @@ pub use super::lobby_player::Entity as LobbyPlayer;
 pub use super::game::Entity as Game;
 pub use super::game_player::Entity as GamePlayer;
 pub use super::game_round::Entity as GameRound;
+pub use super::node::Entity as Node;
+pub use super::bug_report::Entity as BugReport;
+pub use super::weekly_digest::Entity as WeeklyDigest;
+pub use super::lobby_preset::Entity as LobbyPreset;
+pub use super::tournament::Entity as Tournament;
+pub use super::round_deal::Entity as RoundDeal;
+pub use super::password_reset_token::Entity as PasswordResetToken;
+pub use super::friendship::Entity as Friendship;
+pub use super::user_block::Entity as UserBlock;
+pub use super::game_action::Entity as GameAction;
+pub use super::audit_log::Entity as AuditLog;
+pub use super::user_achievement::Entity as UserAchievement;
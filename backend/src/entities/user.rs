@@ -10,6 +10,20 @@ pub struct Model {
     pub username: String,
     pub password_hash: String,
     pub created_at: DateTimeUtc,
+    pub is_bot: bool,
+    pub time_zone: String,
+    pub locale: String,
+    pub weekly_digest: bool,
+    pub suit_encoding: String,
+    /// player, moderator or admin
+    pub role: String,
+    /// Tokens issued before this are rejected; set when the password is reset
+    pub sessions_valid_after: Option<DateTimeUtc>,
+    /// Shown to other players instead of the username when set
+    pub display_name: Option<String>,
+    pub avatar: Option<String>,
+    /// GameSettings the user's new lobbies start from
+    pub preferred_settings: Option<Json>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -25,6 +25,27 @@ pub enum GameError {
 
     #[error("Player not in game")]
     PlayerNotInGame,
+
+    #[error("Stale game state (version {expected}, current {current}); request a resync")]
+    StaleState { expected: u64, current: u64 },
+
+    #[error("Session resumed; request a resync before acting")]
+    ResyncRequired,
+
+    #[error("Only the game host can do that")]
+    NotGameHost,
+
+    #[error("That seat is not open")]
+    SeatUnavailable,
+
+    #[error("That player's turn has not run out")]
+    TurnNotOverdue,
+
+    #[error("You already have a seat in this game")]
+    AlreadySeated,
+
+    #[error("Hints are turned off in this game")]
+    HintsDisabled,
 }
 
 #[derive(Debug, Error)]
@@ -38,10 +59,72 @@ pub enum LobbyError {
     #[error("Not enough players")]
     NotEnoughPlayers,
 
-    #[error("Only host can start game")]
+    #[error("Only the lobby host can do that")]
     NotHost,
+
+    #[error("Player not in lobby")]
+    PlayerNotInLobby,
+
+    #[error("Host cannot target themselves")]
+    CannotTargetSelf,
+
+    #[error("Not all players are ready")]
+    PlayersNotReady,
+
+    #[error("Lobby only accepts bot accounts")]
+    BotsOnly,
+
+    #[error("Lobby does not allow bots")]
+    BotsNotAllowed,
+
+    #[error("Lobby bot limit reached")]
+    BotLimitReached,
+
+    #[error("Lobby requires at least one human player")]
+    HumanRequired,
+
+    #[error("Settings not allowed on this server: {0}")]
+    SettingsOutOfRange(String),
+
+    #[error("Tournament not found")]
+    TournamentNotFound,
+
+    #[error("The host is not accepting you in this lobby")]
+    BlockedByHost,
+
+    #[error("Another player in this lobby is connected from the same address")]
+    SharedAddress,
 }
 
+#[derive(Debug, Error)]
+pub enum MatchmakingError {
+    #[error("Already in the matchmaking queue")]
+    AlreadyQueued,
+
+    #[error("Not in the matchmaking queue")]
+    NotQueued,
+
+    #[error("Unsupported player count")]
+    InvalidPlayerCount,
+}
+
+#[cfg(feature = "server")]
+#[derive(Debug, Error)]
+pub enum PresetError {
+    #[error("Preset name must be 1-{0} characters")]
+    InvalidName(usize),
+
+    #[error("Preset limit of {0} reached")]
+    LimitReached(usize),
+
+    #[error("Preset not found")]
+    NotFound,
+
+    #[error("Database error: {0}")]
+    Database(#[from] sea_orm::DbErr),
+}
+
+#[cfg(feature = "server")]
 #[derive(Debug, Error)]
 pub enum RouterError {
     #[error("Game error: {0}")]
@@ -50,19 +133,42 @@ pub enum RouterError {
     #[error("Lobby error: {0}")]
     Lobby(#[from] LobbyError),
 
+    #[error("Matchmaking error: {0}")]
+    Matchmaking(#[from] MatchmakingError),
+
+    #[error("Preset error: {0}")]
+    Preset(#[from] PresetError),
+
     #[error("Unknown message type")]
     UnknownMessage,
 
+    #[error("Requires {} role", .0.as_str())]
+    Forbidden(crate::auth::Role),
+
+    #[error("You are not a member of that {}", .0.kind())]
+    NotMember(crate::room::RoomId),
+
+    #[error("That message must be addressed to a {0}")]
+    WrongRoom(&'static str),
+
+    #[error("The {} feature is disabled on this server", .0.as_str())]
+    FeatureDisabled(crate::features::Feature),
+
+    #[error("The server is shutting down for maintenance; no new games can start")]
+    ShuttingDown,
+
     #[error("{0}")]
     Generic(String),
 }
 
+#[cfg(feature = "server")]
 impl From<String> for RouterError {
     fn from(s: String) -> Self {
         RouterError::Generic(s)
     }
 }
 
+#[cfg(feature = "server")]
 impl From<&str> for RouterError {
     fn from(s: &str) -> Self {
         RouterError::Generic(s.to_string())
@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+#[cfg(feature = "server")]
+use std::sync::RwLock;
+#[cfg(feature = "server")]
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use tracing::info;
+#[cfg(feature = "server")]
+use crate::error::RouterError;
+
+/// Subsystems an instance can switch on and off at runtime
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    /// Watching games without a seat; not served yet
+    Spectators,
+    /// Lobby and table chat; not served yet
+    Chat,
+    /// The matchmaking queue and the leaderboard
+    Ranked,
+    /// Bot account connections, on `/bot/ws` or otherwise
+    Bots,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 4] = [Feature::Spectators, Feature::Chat, Feature::Ranked, Feature::Bots];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Feature::Spectators => "spectators",
+            Feature::Chat => "chat",
+            Feature::Ranked => "ranked",
+            Feature::Bots => "bots",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|feature| feature.as_str().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Whether the feature is on when the instance's configuration does not say
+    pub fn enabled_by_default(self) -> bool {
+        matches!(self, Feature::Ranked | Feature::Bots)
+    }
+
+    /// Features on by default, adjusted by comma separated `enable` and `disable` lists.
+    /// Unknown names are returned so the caller can report them.
+    pub fn configure(enable: &str, disable: &str) -> (HashSet<Feature>, Vec<String>) {
+        let mut enabled: HashSet<Feature> = Self::ALL.into_iter().filter(|f| f.enabled_by_default()).collect();
+        let mut unknown = Vec::new();
+        for (list, on) in [(enable, true), (disable, false)] {
+            for name in list.split(',').filter(|name| !name.trim().is_empty()) {
+                match Self::parse(name) {
+                    Some(feature) if on => { enabled.insert(feature); }
+                    Some(feature) => { enabled.remove(&feature); }
+                    None => unknown.push(name.trim().to_string()),
+                }
+            }
+        }
+        (enabled, unknown)
+    }
+}
+
+/// A feature and whether it is on, as listed by the admin endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(utoipa::ToSchema))]
+pub struct FeatureStatus {
+    pub feature: Feature,
+    pub enabled: bool,
+}
+
+/// Which experimental subsystems this instance serves; admins flip them without a restart
+#[cfg(feature = "server")]
+#[derive(Debug)]
+pub struct FeatureFlags {
+    enabled: RwLock<HashSet<Feature>>,
+}
+
+#[cfg(feature = "server")]
+impl Default for FeatureFlags {
+    fn default() -> Self {
+        Self::new(Feature::ALL.into_iter().filter(|f| f.enabled_by_default()))
+    }
+}
+
+#[cfg(feature = "server")]
+impl FeatureFlags {
+    pub fn new(enabled: impl IntoIterator<Item = Feature>) -> Self {
+        Self { enabled: RwLock::new(enabled.into_iter().collect()) }
+    }
+
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        self.enabled.read().unwrap().contains(&feature)
+    }
+
+    /// Turn `feature` on or off; requests already being handled finish either way
+    pub fn set(&self, feature: Feature, enabled: bool) {
+        let mut flags = self.enabled.write().unwrap();
+        let changed = if enabled { flags.insert(feature) } else { flags.remove(&feature) };
+        if changed {
+            info!("Feature {} {}", feature.as_str(), if enabled { "enabled" } else { "disabled" });
+        }
+    }
+
+    /// For HTTP handlers: 403 with the same message WebSocket clients get while `feature` is off
+    pub fn require(&self, feature: Feature) -> Result<(), (StatusCode, String)> {
+        if self.is_enabled(feature) {
+            Ok(())
+        } else {
+            Err((StatusCode::FORBIDDEN, RouterError::FeatureDisabled(feature).to_string()))
+        }
+    }
+
+    /// Every feature in declaration order
+    pub fn statuses(&self) -> Vec<FeatureStatus> {
+        let flags = self.enabled.read().unwrap();
+        Feature::ALL.into_iter()
+            .map(|feature| FeatureStatus { feature, enabled: flags.contains(&feature) })
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configure_adjusts_defaults_and_reports_unknown_names() {
+        let (enabled, unknown) = Feature::configure("Chat, ", "bots,teleport");
+        assert_eq!(enabled, HashSet::from([Feature::Chat, Feature::Ranked]));
+        assert_eq!(unknown, vec!["teleport".to_string()]);
+
+        let flags = FeatureFlags::new(enabled);
+        flags.set(Feature::Ranked, false);
+        assert!(!flags.is_enabled(Feature::Ranked));
+        assert_eq!(flags.statuses()[1], FeatureStatus { feature: Feature::Chat, enabled: true });
+    }
+}
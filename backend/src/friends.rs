@@ -0,0 +1,109 @@
+use std::collections::{HashMap, HashSet};
+use sea_orm::{
+    sea_query::OnConflict, ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set,
+};
+use serde::Serialize;
+use uuid::Uuid;
+use crate::entities::{friendship, user};
+
+/// Someone on either side of a user's friend list
+#[derive(Debug, Clone, Serialize)]
+pub struct FriendEntry {
+    pub user_id: Uuid,
+    pub username: String,
+    /// The user added them
+    pub added: bool,
+    /// They added the user
+    pub added_you: bool,
+}
+
+impl FriendEntry {
+    /// Friends see each other's presence once both have added each other
+    pub fn is_mutual(&self) -> bool {
+        self.added && self.added_you
+    }
+}
+
+/// Add `friend_id` to the user's list; adding someone twice is a no-op
+pub async fn add_friend(db: &DatabaseConnection, user_id: Uuid, friend_id: Uuid) -> Result<(), DbErr> {
+    let row = friendship::ActiveModel {
+        user_id: Set(user_id),
+        friend_id: Set(friend_id),
+        created_at: Set(chrono::Utc::now()),
+    };
+    friendship::Entity::insert(row)
+        .on_conflict(
+            OnConflict::columns([friendship::Column::UserId, friendship::Column::FriendId])
+                .do_nothing()
+                .to_owned(),
+        )
+        .do_nothing()
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Take `friend_id` off the user's list; false if they were not on it
+pub async fn remove_friend(db: &DatabaseConnection, user_id: Uuid, friend_id: Uuid) -> Result<bool, DbErr> {
+    let result = friendship::Entity::delete_many()
+        .filter(friendship::Column::UserId.eq(user_id))
+        .filter(friendship::Column::FriendId.eq(friend_id))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected > 0)
+}
+
+/// Everyone the user added or who added the user, by username
+pub async fn list_friends(db: &DatabaseConnection, user_id: Uuid) -> Result<Vec<FriendEntry>, DbErr> {
+    let rows = friendship::Entity::find()
+        .filter(
+            Condition::any()
+                .add(friendship::Column::UserId.eq(user_id))
+                .add(friendship::Column::FriendId.eq(user_id)),
+        )
+        .all(db)
+        .await?;
+
+    let mut sides: HashMap<Uuid, (bool, bool)> = HashMap::new();
+    for row in rows {
+        if row.user_id == user_id {
+            sides.entry(row.friend_id).or_default().0 = true;
+        } else {
+            sides.entry(row.user_id).or_default().1 = true;
+        }
+    }
+
+    let names: HashMap<Uuid, String> = user::Entity::find()
+        .filter(user::Column::Id.is_in(sides.keys().copied()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|u| (u.id, u.username))
+        .collect();
+
+    let mut entries: Vec<FriendEntry> = sides.into_iter()
+        .filter_map(|(id, (added, added_you))| {
+            names.get(&id).map(|username| FriendEntry { user_id: id, username: username.clone(), added, added_you })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.username.cmp(&b.username));
+    Ok(entries)
+}
+
+/// Users who have added the user and been added back
+pub async fn mutual_friends(db: &DatabaseConnection, user_id: Uuid) -> Result<Vec<Uuid>, DbErr> {
+    let rows = friendship::Entity::find()
+        .filter(
+            Condition::any()
+                .add(friendship::Column::UserId.eq(user_id))
+                .add(friendship::Column::FriendId.eq(user_id)),
+        )
+        .all(db)
+        .await?;
+
+    let added: HashSet<Uuid> = rows.iter().filter(|r| r.user_id == user_id).map(|r| r.friend_id).collect();
+    Ok(rows.iter()
+        .filter(|r| r.friend_id == user_id && added.contains(&r.user_id))
+        .map(|r| r.user_id)
+        .collect())
+}
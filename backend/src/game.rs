@@ -1,50 +1,886 @@
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, OnceLock};
 use std::time::Instant;
-use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 use uuid::Uuid;
-use crate::connection::{PlayerId, ConnectionManager};
-use crate::game_state::GameState;
-use crate::protocol::{ServerMessage, PlayerAction, PlayerGameView};
+use crate::connection::{PlayerId, ConnectionManager, SessionChange};
+use crate::engine::{Event, Events, GamePhase, GameState};
+use crate::engine::auto_play::RulesAware;
+use crate::engine::rules::TimeoutPolicy;
+use crate::protocol::{ServerMessage, PlayerAction, PlayerGameView, GameSettings, DeparturePolicy, PlayerInfo, OpenSeatInfo, BiddingInsights, Intermission, NextRoundPolicy, PlacedBid, RoundResult, TrickCount, TurnDeadline, TurnState};
 use crate::error::GameError;
+use crate::redaction::RedactedView;
+use crate::user_directory::UserDirectory;
+use crate::user_cache::PlayerProfile;
+use crate::game_trace::GameTraceFilter;
+use crate::bug_report::{self, BugReportBundle, GameJournal};
+use crate::deal_review::Deal;
+use crate::transcript::RecordedAction;
+use crate::short_code::{IdOrCode, ShortCode};
+use crate::room::{RoomId, RoomRegistry};
+use crate::game_actor::GameRegistry;
+use crate::timer_wheel::{Timer, TimerKind, TimerWheel};
+use crate::game_trace;
+use crate::engine::tiebreak::{self, GameOutcome, Placement};
+use crate::trump_stats;
+use crate::achievements::{self, Achievement};
+use crate::pacing::PacingTracker;
+use crate::game_events::{GameEvents, GameOutbox};
+use crate::db_outbox::{DbOutbox, DbWrite, FinalSeat, Unlocked};
+use crate::webhook::{GameCompletedEvent, WebhookDispatcher};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
 use tracing::{debug, info, warn};
-use sea_orm::{DatabaseConnection, ActiveModelTrait, EntityTrait, Set, QueryFilter, ColumnTrait};
+use sea_orm::{DatabaseConnection, DbErr, ActiveModelTrait, EntityTrait, Set, QueryFilter, ColumnTrait, TransactionTrait};
 use chrono::Utc;
 
-pub type GameId = Uuid;
+pub use crate::protocol::GameId;
 
+/// Handles share every game, timer and connection; clones are cheap. Each game runs in its
+/// own task, so actions in one game never wait on another.
+#[derive(Clone)]
 pub struct GameManager {
-    games: Arc<RwLock<HashMap<GameId, Game>>>,
-    connection_manager: Arc<ConnectionManager>,
-    timer_handles: Arc<RwLock<HashMap<GameId, JoinHandle<()>>>>,
+    games: GameRegistry,
+    timers: TimerWheel,
     db: DatabaseConnection,
+    user_directory: UserDirectory,
+    trace_filter: Arc<GameTraceFilter>,
+    events: Arc<GameEvents>,
+    outbox: GameOutbox,
+    db_writes: DbOutbox,
+    /// Shared with the clone that handles expired timers, which is made before this is set
+    webhooks: Arc<OnceLock<Arc<WebhookDispatcher>>>,
+    rooms: Arc<RoomRegistry>,
+    connection_manager: Arc<ConnectionManager>,
 }
 
+#[derive(Clone)]
 pub struct Game {
     pub id: GameId,
     pub state: GameState,
     pub players: Vec<PlayerId>,
     pub created_at: Instant,
+    /// Usernames resolved at creation so views keep names for offline players; views share the map
+    pub player_names: Arc<HashMap<PlayerId, String>>,
+    /// Profiles resolved at creation, for the seat list in views
+    pub profiles: HashMap<PlayerId, PlayerProfile>,
+    pub settings: GameSettings,
+    /// Deals, actions and recent events, bundled into bug reports when something goes wrong
+    pub journal: GameJournal,
+    /// Players whose reconnect window expired; their turns are auto-played
+    pub departed: HashMap<PlayerId, DeparturePolicy>,
+    pub code: ShortCode,
+    /// The lobby host who started the game, or the first seat for matchmade games
+    pub host: PlayerId,
+    /// Departed seats the host has opened for anyone to take over
+    pub open_seats: HashSet<PlayerId>,
+    /// How long players take to act, for pace notices and the host's report
+    pub pacing: PacingTracker,
+    /// When the current turn times out, while a turn timer runs
+    pub turn_deadline: Option<Instant>,
+    /// While the current player is disconnected, when their stopped turn clock runs again
+    pub turn_paused_until: Option<Instant>,
+    /// Players who asked for the next round, when everyone has to
+    pub next_round_votes: HashSet<PlayerId>,
+    /// When the next round starts on its own, during an intermission with a time limit
+    pub intermission_deadline: Option<Instant>,
+    /// When the game was resumed after a restart; the reaper gives its players time to return
+    pub resumed_at: Option<Instant>,
+}
+
+/// Messages to send, in order, each to one player
+type Outgoing = Vec<(PlayerId, ServerMessage)>;
+
+/// Everything needed to pick a game back up after a restart, written to the game's `state`
+/// column when the server shuts down. Games resume exactly where they stopped; checkpoints
+/// without `state` resume at the start of the round in progress.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GameCheckpoint {
+    pub code: ShortCode,
+    pub players: Vec<PlayerId>,
+    pub host: PlayerId,
+    pub settings: GameSettings,
+    /// Rounds played to the end
+    pub history: Vec<RoundResult>,
+    pub departed: HashMap<PlayerId, DeparturePolicy>,
+    pub open_seats: HashSet<PlayerId>,
+    #[serde(default)]
+    pub state: Option<GameState>,
+    #[serde(default)]
+    pub next_round_votes: HashSet<PlayerId>,
+    /// Time left of a timed intermission, which carries on after the restart
+    #[serde(default)]
+    pub intermission_remaining_ms: Option<u64>,
+}
+
+/// How long a resumed game waits for its players to reconnect before the reaper may remove it
+pub const REJOIN_GRACE: std::time::Duration = std::time::Duration::from_secs(300);
+
+impl Game {
+    pub fn checkpoint(&self) -> GameCheckpoint {
+        GameCheckpoint {
+            code: self.code.clone(),
+            players: self.players.clone(),
+            host: self.host.clone(),
+            settings: self.settings.clone(),
+            history: self.state.history.as_ref().clone(),
+            departed: self.departed.clone(),
+            open_seats: self.open_seats.clone(),
+            state: Some(self.state.clone()),
+            next_round_votes: self.next_round_votes.clone(),
+            intermission_remaining_ms: self.intermission_deadline
+                .map(|deadline| deadline.saturating_duration_since(Instant::now()).as_millis() as u64),
+        }
+    }
+
+    /// Who may open seats: the starting host while still seated, else the first seat still in play
+    pub fn acting_host(&self) -> Option<&PlayerId> {
+        let seated = |p: &&PlayerId| self.players.contains(p) && !self.departed.contains_key(*p);
+        Some(&self.host).filter(seated)
+            .or_else(|| self.players.iter().find(seated))
+    }
+
+    /// Build a player's view, including the usernames of everyone at the table
+    pub fn player_view(&self, player_id: PlayerId) -> PlayerGameView {
+        let state = &self.state;
+        let mut view = PlayerGameView {
+            game_id: self.id,
+            game_code: self.code.clone(),
+            phase: state.phase,
+            your_hand: RedactedView::new(state, &player_id).own_hand().to_vec(),
+            current_trick: state.current_trick.cards.to_vec(),
+            scores: state.total_scores.clone(),
+            history: Arc::clone(&state.history),
+            round_number: state.round_number,
+            trump_suit: state.trump_suit,
+            current_player: state.current_player.clone(),
+            your_turn: match state.phase {
+                GamePhase::RoundComplete => self.may_start_next_round(&player_id),
+                GamePhase::GameComplete => false,
+                _ => state.current_player == player_id,
+            },
+            current_round: state.current_round.clone(),
+            state_version: state.version,
+            player_names: Arc::clone(&self.player_names),
+            players: self.players.iter()
+                .filter_map(|id| self.profiles.get(id).map(|profile| PlayerInfo::new(id.clone(), profile.clone(), false)))
+                .collect(),
+            bids_hidden: false,
+            departed: self.departed.clone(),
+            turn_deadline: self.turn_deadline_info(),
+            bid_constraint: state.bidding_state.as_ref()
+                .filter(|_| state.phase == GamePhase::Bidding)
+                .map(|bidding| bidding.constraint()),
+            deal_commitment: state.deal_commitment(),
+            unrated: self.settings.unrated,
+            intermission: self.intermission(),
+        };
+        if self.hides_bids() {
+            for pr in view.current_round.iter_mut().filter(|pr| pr.player_id != player_id) {
+                pr.bid = 0;
+            }
+            view.bids_hidden = true;
+            // The forbidden bid gives away the total of the hidden bids
+            view.bid_constraint = view.bid_constraint.filter(|c| c.player_id == player_id);
+        }
+        view
+    }
+
+    /// The YourTurn message for `player_id`, with bidding helpers during the bidding phase
+    pub fn turn_message(&self, player_id: &PlayerId) -> ServerMessage {
+        let mut bidding = self.bidding_insights(player_id);
+        if let Some(insights) = bidding.as_mut().filter(|_| self.hides_bids()) {
+            for placed in insights.bids.iter_mut().filter(|b| b.player_id != *player_id) {
+                placed.bid = None;
+            }
+        }
+        ServerMessage::YourTurn {
+            valid_actions: self.state.get_valid_actions(player_id.clone()),
+            bidding,
+            deadline: self.turn_deadline_info().filter(|d| d.player_id == *player_id),
+        }
+    }
+
+    /// YourTurn for whoever bids or plays next; None between rounds and once the game is over
+    pub fn next_turn_message(&self) -> Option<(PlayerId, ServerMessage)> {
+        let next_player = self.state.current_player.clone();
+        self.pending_turn_message(&next_player).map(|turn_msg| (next_player, turn_msg))
+    }
+
+    /// YourTurn for `player_id` if they have a bid or card to play now
+    pub fn pending_turn_message(&self, player_id: &PlayerId) -> Option<ServerMessage> {
+        let acting = matches!(self.state.phase, GamePhase::Bidding | GamePhase::Playing);
+        (acting && self.state.current_player == *player_id).then(|| self.turn_message(player_id))
+    }
+
+    /// Hint for `player_id`'s turn: the action the auto-player would take for them, always one
+    /// of the turn's valid actions
+    pub fn hint(&self, player_id: &PlayerId) -> Result<ServerMessage, GameError> {
+        if !self.players.contains(player_id) {
+            return Err(GameError::PlayerNotInGame);
+        }
+        if !self.settings.allow_hints {
+            return Err(GameError::HintsDisabled);
+        }
+        if self.pending_turn_message(player_id).is_none() {
+            return Err(GameError::NotPlayerTurn);
+        }
+        let action = self.state.get_auto_action(&RulesAware).ok_or(GameError::NotPlayerTurn)?;
+        Ok(ServerMessage::Hint { action, state_version: self.state.version })
+    }
+
+    /// Bid dialog helpers for `player_id`; None outside the bidding phase
+    fn bidding_insights(&self, player_id: &PlayerId) -> Option<BiddingInsights> {
+        let state = &self.state;
+        let bidding_state = state.bidding_state.as_ref().filter(|_| state.phase == GamePhase::Bidding)?;
+        let cards = RedactedView::new(state, player_id).own_hand();
+
+        // Bidding runs from the first bidder around the table
+        let order = &bidding_state.player_order;
+        let start = order.iter().position(|p| *p == state.first_bidder).unwrap_or(0);
+        let bids: Vec<PlacedBid> = order.iter().cycle().skip(start).take(order.len())
+            .filter_map(|pid| bidding_state.bids.get(pid).map(|&bid| PlacedBid { player_id: pid.clone(), bid: Some(bid) }))
+            .collect();
+
+        let last_bidder = bidding_state.is_last_bidder(player_id);
+        let constraint = bidding_state.constraint();
+        let forbidden_bid = bidding_state.hook_bid().filter(|_| last_bidder);
+
+        Some(BiddingInsights {
+            hand_size: cards.len(),
+            trump_count: cards.iter().filter(|c| Some(c.suit) == state.trump_suit).count(),
+            bids,
+            last_bidder,
+            forbidden_bid,
+            constraint,
+        })
+    }
+
+    /// Apply a validated action by `player_id`, timed out ones marked `auto`, and move the
+    /// turn clock on
+    pub fn apply_action(&mut self, player_id: &PlayerId, action: PlayerAction, auto: bool) -> Result<Events, GameError> {
+        let events = self.state.apply_action(player_id.clone(), action.clone())?;
+        // The deadline belonged to the turn this action ends; the next turn gets its own
+        self.turn_paused_until = None;
+        self.journal.record_action(self.state.version, player_id, &action, auto);
+        self.pacing.record_action(player_id, Instant::now());
+        self.start_turn_clock();
+        if self.state.phase == GamePhase::RoundComplete {
+            self.next_round_votes.clear();
+            self.intermission_deadline = self.settings.intermission_secs
+                .map(|secs| Instant::now() + std::time::Duration::from_secs(secs));
+        }
+        Ok(events)
+    }
+
+    /// Deal the next round, or end the game if the schedule is done
+    pub fn advance_round(&mut self) -> Events {
+        let events = self.state.advance_to_next_round();
+        self.next_round_votes.clear();
+        self.intermission_deadline = None;
+        self.start_turn_clock();
+        events
+    }
+
+    /// Validate and apply an action, gathering what is to be sent and stored once it is
+    /// done. A rejected action comes back with a bug report when it should never have failed.
+    fn play(
+        &mut self,
+        player_id: &PlayerId,
+        action: PlayerAction,
+        expected_version: Option<u64>,
+        auto: bool,
+        trace_filter: &GameTraceFilter,
+    ) -> Result<AppliedAction, (GameError, Option<Box<BugReportBundle>>)> {
+        let game_id = self.id;
+
+        // Check if player is in the game
+        if !self.players.contains(player_id) {
+            return Err((GameError::PlayerNotInGame, None));
+        }
+
+        // Reject actions made against an outdated view of the game
+        if let Some(expected) = expected_version {
+            if expected != self.state.version {
+                return Err((GameError::StaleState { expected, current: self.state.version }, None));
+            }
+        }
+
+        // Validate the action before applying
+        // Any validation errors are caught and returned without affecting game state
+        if let Err(e) = self.state.validate_action(player_id, &action) {
+            game_trace!(trace_filter, game_id, player = %player_id, error = %e, "Action rejected");
+            self.journal.record_event(self.state.version, format!("Rejected {:?} from {}: {}", action, player_id, e));
+            return Err((e, None));
+        }
+
+        if auto {
+            if let TimeoutPolicy::SkipWithPenalty { points } = self.state.rules.timer.on_timeout {
+                self.state.penalize(player_id, points);
+            }
+        }
+
+        // Store state before applying action to detect phase changes
+        let phase_before = self.state.phase;
+        let round_before = self.state.round_number;
+
+        // Apply the action to update state
+        // It already passed validation, so a failure here is a bug worth reporting
+        let events = match self.apply_action(player_id, action.clone(), auto) {
+            Ok(events) => events,
+            Err(e) => {
+                let report = self.report_problem(&format!("Validated action {:?} by {} failed to apply: {}", action, player_id, e));
+                return Err((e, report.map(Box::new)));
+            }
+        };
+        log_events(game_id, &events);
+        let recorded = RecordedAction {
+            version: self.state.version,
+            round_number: round_before,
+            player_id: player_id.clone(),
+            action,
+            auto,
+        };
+        let invariant_report = match self.state.check_invariants() {
+            Ok(()) => None,
+            Err(violation) => self.report_problem(&format!("Invariant violated: {}", violation)),
+        };
+
+        let phase_after = self.state.phase;
+        game_trace!(
+            trace_filter, game_id,
+            version = self.state.version,
+            round = self.state.round_number,
+            ?phase_after,
+            next_player = %self.state.current_player,
+            "Action applied"
+        );
+
+        // Check if phase changed (e.g. Bidding -> Playing)
+        let mut phase_change_updates = Vec::new();
+        if phase_before != phase_after {
+            info!("Phase changed from {:?} to {:?} in game {}", phase_before, phase_after, game_id);
+            for pid in &self.players {
+                phase_change_updates.push((pid.clone(), self.player_view(pid.clone())));
+            }
+        }
+
+        let trick_msg = events.iter()
+            .any(|event| matches!(event, Event::TrickWon { .. }))
+            .then(|| self.trick_complete_message())
+            .flatten();
+
+        let final_result = (phase_after == crate::engine::GamePhase::GameComplete).then(|| FinalResult {
+            scores: self.state.total_scores.clone(),
+            placements: self.placements(),
+            outcome: self.outcome(),
+            game_over: self.game_over_message(),
+            summaries: self.summary_messages(),
+        });
+
+        // If RoundComplete, don't auto-schedule.
+        // We wait for StartNextRound message.
+        // Save round history to DB, the final round's included
+        let round_data = if events.iter().any(|event| matches!(event, Event::RoundComplete { .. })) {
+            let results = self.state.history.last().cloned();
+            let notice = if phase_after == crate::engine::GamePhase::RoundComplete { self.pace_notice() } else { None };
+            results.map(|results| FinishedRound { results, totals: self.state.total_scores.clone(), pace_notice: notice })
+        } else {
+            None
+        };
+
+        let mut achievements = round_data.as_ref().map_or_else(Vec::new, |round| achievements::round_feats(&round.results));
+        if let Some(result) = &final_result {
+            let finishers: Vec<PlayerId> = self.players.iter().filter(|pid| !self.departed.contains_key(*pid)).cloned().collect();
+            achievements.extend(achievements::game_feats(&self.state.history, &result.outcome, &finishers));
+        }
+        achievements.retain(|(pid, _)| !self.departed.contains_key(pid));
+
+        Ok(AppliedAction {
+            recorded,
+            invariant_report,
+            players: self.players.clone(),
+            turn: self.turn_state(),
+            hide_bid: self.hides_bids(),
+            next_turn: self.next_turn_message(),
+            intermission_deadline: self.intermission_deadline.filter(|_| phase_after == crate::engine::GamePhase::RoundComplete),
+            phase_change_updates,
+            trick_msg,
+            pacing_report: final_result.as_ref().and_then(|_| self.pacing_report()),
+            final_result,
+            tournament: self.settings.tournament_id.map(|id| (id, Arc::clone(&self.player_names))),
+            forfeited: self.forfeited(),
+            round_data,
+            achievements,
+            turn_timer: self.turn_timer(),
+        })
+    }
+
+    /// Advance from RoundComplete to the next round on a player's request or when the
+    /// intermission runs out. Returns the messages to send and the new deal to record; under
+    /// `AllPlayers` a request that leaves others to wait for only announces the vote.
+    fn start_next_round(&mut self, request: NextRoundRequest, trace_filter: &GameTraceFilter) -> Result<(Outgoing, Option<Deal>), GameError> {
+        let game_id = self.id;
+        match request {
+            NextRoundRequest::Player(player_id) => {
+                if !self.players.contains(&player_id) {
+                    return Err(GameError::PlayerNotInGame);
+                }
+                if self.state.phase != crate::engine::GamePhase::RoundComplete {
+                    return Err(GameError::InvalidMove("Not in RoundComplete phase".to_string()));
+                }
+                if !self.may_start_next_round(&player_id) && !self.next_round_waiting_for().is_empty() {
+                    return Err(match self.settings.next_round {
+                        NextRoundPolicy::FirstBidder => GameError::NotPlayerTurn,
+                        NextRoundPolicy::Host => GameError::InvalidMove("Only the host can start the next round".to_string()),
+                        NextRoundPolicy::AllPlayers => GameError::InvalidMove("Already waiting for the others".to_string()),
+                    });
+                }
+                if self.settings.next_round == NextRoundPolicy::AllPlayers {
+                    self.next_round_votes.insert(player_id.clone());
+                    if let Some(intermission) = self.intermission().filter(|i| !i.waiting_for.is_empty()) {
+                        let vote = ServerMessage::NextRoundVote { player_id, intermission };
+                        return Ok((self.to_everyone(vote), None));
+                    }
+                }
+            }
+            NextRoundRequest::Timeout { round_number } => {
+                // Players already started the round, or a later one's intermission took over
+                let current = self.state.phase == crate::engine::GamePhase::RoundComplete
+                    && self.state.round_number == round_number
+                    && self.intermission_deadline.is_some_and(|deadline| Instant::now() >= deadline);
+                if !current {
+                    return Ok((Vec::new(), None));
+                }
+                info!("Intermission after round {} ran out in game {}", round_number, game_id);
+            }
+        }
+
+        // Advance
+        let events = self.advance_round();
+        log_events(game_id, &events);
+        game_trace!(trace_filter, game_id, round = self.state.round_number, phase = ?self.state.phase, "Advanced to next round");
+
+        let mut messages = Vec::new();
+        let mut deal = None;
+
+        // Broadcast new state if round started
+        if self.state.phase == crate::engine::GamePhase::Bidding {
+            self.journal.record_deal(&self.state);
+            deal = Some(Deal::from_state(&self.state));
+
+            for pid in &self.players {
+                let view = self.player_view(pid.clone());
+                messages.push((pid.clone(), ServerMessage::GameState { state: Box::new(view) }));
+
+                // Send valid actions to the first player
+                if *pid == self.state.current_player {
+                    messages.push((pid.clone(), self.turn_message(pid)));
+                }
+            }
+        } else if self.state.phase == crate::engine::GamePhase::GameComplete {
+            messages = self.to_everyone(self.game_over_message());
+            messages.extend(self.summary_messages());
+            messages.extend(self.pacing_report());
+        }
+        Ok((messages, deal))
+    }
+
+    /// `msg` addressed to every seat
+    fn to_everyone(&self, msg: ServerMessage) -> Outgoing {
+        self.players.iter().map(|pid| (pid.clone(), msg.clone())).collect()
+    }
+
+    /// Players whose StartNextRound can still start the next round; under `AllPlayers`,
+    /// the seated players who have yet to ask for it
+    pub fn next_round_waiting_for(&self) -> Vec<PlayerId> {
+        match self.settings.next_round {
+            NextRoundPolicy::FirstBidder => vec![self.state.current_player.clone()],
+            NextRoundPolicy::Host => self.acting_host().cloned().into_iter().collect(),
+            NextRoundPolicy::AllPlayers => self.players.iter()
+                .filter(|pid| !self.departed.contains_key(*pid) && !self.next_round_votes.contains(*pid))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn may_start_next_round(&self, player_id: &PlayerId) -> bool {
+        self.state.phase == GamePhase::RoundComplete && self.next_round_waiting_for().contains(player_id)
+    }
+
+    /// The pause between rounds; None outside RoundComplete
+    pub fn intermission(&self) -> Option<Intermission> {
+        (self.state.phase == GamePhase::RoundComplete).then(|| Intermission {
+            waiting_for: self.next_round_waiting_for(),
+            auto_start_ms: self.intermission_deadline.and_then(epoch_ms),
+        })
+    }
+
+    /// Set the turn deadline for the current player
+    pub fn set_turn_deadline(&mut self, timeout_secs: u64) {
+        self.turn_deadline = Some(Instant::now() + std::time::Duration::from_secs(timeout_secs));
+    }
+
+    /// The current player's turn deadline in wall-clock terms
+    pub fn turn_deadline_info(&self) -> Option<TurnDeadline> {
+        let deadline = self.turn_deadline?;
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let deadline_ms = epoch_ms(deadline)?;
+        Some(TurnDeadline {
+            player_id: self.state.current_player.clone(),
+            deadline_ms,
+            remaining_secs: remaining.as_millis().div_ceil(1000) as u64,
+        })
+    }
+
+    /// Check if the current turn has expired
+    pub fn is_turn_expired(&self) -> bool {
+        self.turn_deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Bundle the journal into a bug report, unless this game already filed its quota
+    pub fn report_problem(&mut self, reason: &str) -> Option<BugReportBundle> {
+        self.journal.build_report(self.id, reason, &self.settings, &self.state)
+    }
+
+    /// Players who forfeited their seat; they keep no final score and cannot win
+    pub fn forfeited(&self) -> Vec<PlayerId> {
+        self.departed.iter()
+            .filter(|(_, outcome)| **outcome == DeparturePolicy::Forfeit)
+            .map(|(pid, _)| pid.clone())
+            .collect()
+    }
+
+    /// Winners by final score, with the lobby's tie-breakers applied
+    pub fn outcome(&self) -> GameOutcome {
+        tiebreak::decide_winners(
+            &self.players,
+            &self.state.total_scores,
+            &self.state.history,
+            &self.settings.tie_breakers,
+            &self.forfeited(),
+        )
+    }
+
+    /// Finishing order by final score, with the lobby's tie-breakers applied
+    pub fn placements(&self) -> Vec<Placement> {
+        tiebreak::rank_players(
+            &self.players,
+            &self.state.total_scores,
+            &self.state.history,
+            &self.settings.tie_breakers,
+            &self.forfeited(),
+        )
+    }
+
+    /// GameOver for the final scores and outcome
+    pub fn game_over_message(&self) -> ServerMessage {
+        let outcome = self.outcome();
+        ServerMessage::GameOver {
+            final_scores: self.state.total_scores.clone(),
+            placements: self.placements(),
+            winners: outcome.winners,
+            win_reason: outcome.reason,
+        }
+    }
+
+    /// The player's post-game summary; any suit played this game can be their best
+    pub fn summary_message(&self, player_id: &PlayerId) -> ServerMessage {
+        let rounds = self.state.history.iter().flat_map(|round| {
+            round.player_results.iter()
+                .filter(|r| r.player_id == *player_id)
+                .map(move |r| (round.trump_suit, r))
+        });
+        let trump_stats = trump_stats::aggregate(rounds);
+        let best_trump = trump_stats::best_trump(&trump_stats, 1);
+        ServerMessage::GameSummary {
+            trump_stats,
+            best_trump,
+            best_trump_line: best_trump.map(trump_stats::best_trump_line),
+        }
+    }
+
+    /// Time limit for the current turn: the rules' limit for this phase, else the lobby's
+    pub fn turn_timeout_secs(&self) -> u64 {
+        let timer = self.settings.rules.timer;
+        match self.state.phase {
+            GamePhase::Bidding => timer.bidding_secs,
+            GamePhase::Playing => timer.playing_secs,
+            _ => None,
+        }
+        .unwrap_or(self.settings.default_turn_secs())
+    }
+
+    /// Every player's post-game summary
+    pub fn summary_messages(&self) -> Vec<(PlayerId, ServerMessage)> {
+        self.players.iter().map(|pid| (pid.clone(), self.summary_message(pid))).collect()
+    }
+
+    /// Time the turn of whoever is to act now and give it the phase's time limit
+    pub fn start_turn_clock(&mut self) {
+        let now = Instant::now();
+        self.pacing.start_turn(&self.state.current_player, self.state.phase, now);
+        let acting = matches!(self.state.phase, GamePhase::Bidding | GamePhase::Playing);
+        self.turn_deadline = acting.then(|| now + std::time::Duration::from_secs(self.turn_timeout_secs()));
+    }
+
+    /// The timer to arm for the current turn's deadline; None between rounds and once the
+    /// game is over
+    pub fn turn_timer(&self) -> Option<(Timer, Instant)> {
+        let acting = matches!(self.state.phase, GamePhase::Bidding | GamePhase::Playing);
+        let deadline = self.turn_deadline.filter(|_| acting)?;
+        Some((Timer::Turn { player_id: self.state.current_player.clone(), version: self.state.version }, deadline))
+    }
+
+    /// Close the finished round's times; the PaceNotice for its slowest seated player when
+    /// the lobby turned notices on and they were notably slow
+    pub fn pace_notice(&mut self) -> Option<(PlayerId, ServerMessage)> {
+        let seated: Vec<PlayerId> = self.players.iter()
+            .filter(|pid| !self.departed.contains_key(*pid))
+            .cloned()
+            .collect();
+        let (slowest, time, others_average) = self.pacing.finish_round(&seated)?;
+        self.settings.pace_notices.then_some((slowest, ServerMessage::PaceNotice {
+            round_number: self.state.round_number,
+            your_secs: time.as_secs(),
+            others_average_secs: others_average.as_secs(),
+        }))
+    }
+
+    /// The PacingReport and the host it goes to
+    pub fn pacing_report(&self) -> Option<(PlayerId, ServerMessage)> {
+        let host = self.acting_host()?.clone();
+        Some((host, ServerMessage::PacingReport { players: self.pacing.report(&self.players) }))
+    }
+
+    /// Who acts next and what is on the table, for the PlayerAction of the action just applied
+    pub fn turn_state(&self) -> TurnState {
+        TurnState {
+            next_player: self.state.current_player.clone(),
+            phase: self.state.phase,
+            current_trick: self.state.current_trick.cards.to_vec(),
+            state_version: self.state.version,
+        }
+    }
+
+    /// TrickComplete for the trick just won; None before the round's first trick is done
+    pub fn trick_complete_message(&self) -> Option<ServerMessage> {
+        let trick = self.state.completed_tricks.last()?;
+        let tricks = self.state.current_round.iter()
+            .map(|pr| TrickCount { player_id: pr.player_id.clone(), bid: pr.bid, tricks_won: pr.tricks_won })
+            .collect();
+        Some(ServerMessage::TrickComplete {
+            winner: trick.winner.clone(),
+            cards: trick.cards.to_vec(),
+            tricks,
+            round_complete: self.state.phase != GamePhase::Playing,
+        })
+    }
+
+    /// Whether blind bidding currently hides bid amounts from other players
+    pub fn hides_bids(&self) -> bool {
+        self.settings.blind_bidding && self.state.phase == crate::engine::GamePhase::Bidding
+    }
+}
+
+/// A future instant as server epoch milliseconds
+fn epoch_ms(instant: Instant) -> Option<i64> {
+    let remaining = instant.saturating_duration_since(Instant::now());
+    Some((Utc::now() + chrono::Duration::from_std(remaining).ok()?).timestamp_millis())
+}
+
+/// Log what the engine reports an action or round change did
+fn log_events(game_id: GameId, events: &Events) {
+    for event in events {
+        match event {
+            Event::BidPlaced { player_id, tricks } => debug!("Player {} bid {} tricks in game {}", player_id, tricks, game_id),
+            Event::BiddingComplete { leader } => debug!("Bidding complete in game {}, {} leads", game_id, leader),
+            Event::CardPlayed { player_id, card } => debug!("Player {} played {:?} in game {}", player_id, card, game_id),
+            Event::TrickWon { winner } => debug!("Trick won by {} in game {}", winner, game_id),
+            Event::RoundComplete { round_number } => info!("Round {} complete in game {}", round_number, game_id),
+            Event::GameComplete => info!("Game {} complete", game_id),
+            Event::RoundStarted { round_number, cards_per_player, trump_suit } => {
+                info!("Round {} started in game {} with {} cards, trump {:?}", round_number, game_id, cards_per_player, trump_suit)
+            }
+        }
+    }
+}
+
+/// Store a bug report if one was built; failures are only logged
+async fn submit_bug_report(db: &DatabaseConnection, report: Option<BugReportBundle>) {
+    if let Some(report) = report {
+        if let Err(e) = bug_report::file_report(db, report).await {
+            warn!("Failed to store bug report: {}", e);
+        }
+    }
+}
+
+/// What an applied action leaves to send and store, gathered while the game is changed
+struct AppliedAction {
+    recorded: RecordedAction,
+    invariant_report: Option<BugReportBundle>,
+    players: Vec<PlayerId>,
+    turn: TurnState,
+    hide_bid: bool,
+    next_turn: Option<(PlayerId, ServerMessage)>,
+    intermission_deadline: Option<Instant>,
+    phase_change_updates: Vec<(PlayerId, PlayerGameView)>,
+    trick_msg: Option<ServerMessage>,
+    final_result: Option<FinalResult>,
+    pacing_report: Option<(PlayerId, ServerMessage)>,
+    tournament: Option<(Uuid, Arc<HashMap<PlayerId, String>>)>,
+    forfeited: Vec<PlayerId>,
+    round_data: Option<FinishedRound>,
+    /// Achievements the action counted towards, for the players still seated
+    achievements: Vec<(PlayerId, Achievement)>,
+    /// The next turn's timer; None when the action ended the round or the game
+    turn_timer: Option<(Timer, Instant)>,
+}
+
+/// A round the action finished, with the running totals and the pace notice it earned
+struct FinishedRound {
+    results: RoundResult,
+    totals: HashMap<PlayerId, i32>,
+    pace_notice: Option<(PlayerId, ServerMessage)>,
+}
+
+/// How a game that just ended finished
+struct FinalResult {
+    scores: HashMap<PlayerId, i32>,
+    placements: Vec<Placement>,
+    outcome: GameOutcome,
+    game_over: ServerMessage,
+    summaries: Outgoing,
+}
+
+/// What asks for the next round to start
+enum NextRoundRequest {
+    Player(PlayerId),
+    /// The intermission after this round ran out
+    Timeout { round_number: usize },
+}
+
+/// What a turn timer found when it fired
+enum TimedOut {
+    /// The turn to play for the player
+    Play(PlayerAction),
+    /// The table to tell that the game waits for the host
+    WaitForHost(Vec<PlayerId>),
+}
+
+/// Broadcast a PlayerAction, replacing the bid amount for everyone but the bidder when `hide_bid` is set
+async fn broadcast_player_action(
+    outbox: &GameOutbox,
+    game_id: GameId,
+    players: &[PlayerId],
+    actor: PlayerId,
+    action: PlayerAction,
+    turn: TurnState,
+    hide_bid: bool,
+) {
+    if !hide_bid || !matches!(action, PlayerAction::Bid(_)) {
+        let action_msg = ServerMessage::PlayerAction { player_id: actor, action, turn };
+        outbox.broadcast(game_id, players, action_msg).await;
+        return;
+    }
+
+    for pid in players {
+        let visible = if *pid == actor { action.clone() } else { PlayerAction::HiddenBid };
+        let action_msg = ServerMessage::PlayerAction {
+            player_id: actor.clone(),
+            action: visible,
+            turn: turn.clone(),
+        };
+        outbox.send(game_id, pid, action_msg).await;
+    }
 }
 
 impl GameManager {
     /// Create a new GameManager with a reference to ConnectionManager
     pub fn new(connection_manager: Arc<ConnectionManager>, db: DatabaseConnection) -> Self {
-        Self {
-            games: Arc::new(RwLock::new(HashMap::new())),
+        let events = Arc::new(GameEvents::new());
+        let (timers, mut expired) = TimerWheel::start();
+        let manager = Self {
+            outbox: GameOutbox::new(Arc::clone(&connection_manager), Arc::clone(&events)),
+            db_writes: DbOutbox::start(db.clone()),
+            events,
+            games: GameRegistry::default(),
+            timers,
+            user_directory: UserDirectory::new(connection_manager.user_cache(), db.clone()),
+            trace_filter: Arc::new(GameTraceFilter::new()),
+            webhooks: Arc::default(),
+            rooms: connection_manager.rooms(),
             connection_manager,
-            timer_handles: Arc::new(RwLock::new(HashMap::new())),
             db,
-        }
+        };
+
+        // Each expired timer is handled in its own task so a slow game cannot hold up the rest
+        let handler = manager.clone();
+        tokio::spawn(async move {
+            while let Some((game_id, timer)) = expired.recv().await {
+                let manager = handler.clone();
+                tokio::spawn(async move { manager.on_timer(game_id, timer).await });
+            }
+        });
+
+        let handler = manager.clone();
+        let mut changes = manager.connection_manager.session_changes();
+        tokio::spawn(async move {
+            loop {
+                match changes.recv().await {
+                    Ok(SessionChange::Disconnected(player_id)) => handler.pause_turn_clock(player_id).await,
+                    Ok(SessionChange::Reconnected(player_id)) => handler.resume_turn_clock(player_id).await,
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        warn!("Missed {} session changes; some turn clocks may not pause", missed);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        let handler = manager.clone();
+        let mut unlocks = manager.db_writes.unlocks();
+        tokio::spawn(async move {
+            loop {
+                match unlocks.recv().await {
+                    Ok(unlocked) => handler.announce_unlocks(unlocked).await,
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        warn!("Missed {} achievement unlocks; their players are not told", missed);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+        manager
+    }
+
+    /// Notify tournament webhooks when tournament games complete
+    pub fn with_webhooks(self, webhooks: Arc<WebhookDispatcher>) -> Self {
+        let _ = self.webhooks.set(webhooks);
+        self
+    }
+
+    /// Lobby and game writes waiting to be stored, shared with the lobby manager
+    pub fn db_writes(&self) -> &DbOutbox {
+        &self.db_writes
+    }
+
+    /// Games selected for verbose logging, shared with the router and admin API
+    pub fn trace_filter(&self) -> Arc<GameTraceFilter> {
+        Arc::clone(&self.trace_filter)
     }
 
     /// Helper method to get a game by ID
-    async fn get_game(&self, game_id: GameId) -> Result<Game, GameError> {
-        let games = self.games.read().await;
-        games.get(&game_id)
-            .cloned()
-            .ok_or(GameError::GameNotFound)
+    pub async fn get_game(&self, game_id: GameId) -> Result<Game, GameError> {
+        self.games.get(game_id)?.call(|game| game.clone()).await
+    }
+
+    /// The running game named by an id or a short code
+    pub async fn find_game(&self, reference: &IdOrCode) -> Option<GameId> {
+        match reference {
+            IdOrCode::Id(id) => self.games.contains(*id).then_some(*id),
+            IdOrCode::Code(code) => self.games.find_by_code(code),
+        }
     }
 
     /// Create a new game with the given players and broadcast GameStarting message
@@ -54,64 +890,119 @@ impl GameManager {
 
     /// Create a new game from a lobby with the given players and broadcast GameStarting message
     pub async fn create_game_from_lobby(&self, players: Vec<PlayerId>, lobby_id: Option<Uuid>) -> GameId {
+        self.create_game_with_settings(players, lobby_id, GameSettings::default()).await
+    }
+
+    /// Create a new game that follows the lobby's settings and broadcast GameStarting message
+    pub async fn create_game_with_settings(&self, players: Vec<PlayerId>, lobby_id: Option<Uuid>, settings: GameSettings) -> GameId {
+        let host = players.first().cloned().unwrap_or_default();
+        self.create_hosted_game(players, lobby_id, settings, host).await
+    }
+
+    /// Create a game from a lobby, keeping the lobby host as the game's host
+    pub async fn create_hosted_game(&self, players: Vec<PlayerId>, lobby_id: Option<Uuid>, settings: GameSettings, host: PlayerId) -> GameId {
         // Generate unique game ID using UUID v4
         let game_id = Uuid::new_v4();
-        let game_state = GameState::new(players.clone());
-
-        let game = Game {
-            id: game_id,
-            state: game_state,
-            players: players.clone(),
-            created_at: Instant::now(),
-        };
+        let game_state = GameState::with_rules(players.clone(), settings.schedule(), settings.rules.clone());
+        let profiles = self.user_directory.resolve_many(&players).await;
+        let player_names = Arc::new(profiles.iter().map(|(id, profile)| (id.clone(), profile.username.clone())).collect());
+        let mut journal = GameJournal::new(players.clone());
+        journal.record_deal(&game_state);
+        let first_deal = Deal::from_state(&game_state);
 
-        // Calculate valid actions for the first player *before* moving game into the map
-        let first_player = game.state.current_player.clone();
-        let valid_actions = game.state.get_valid_actions(first_player.clone());
+        let variant = crate::leaderboard::Variant::from_settings(&settings);
+        let tournament_id = settings.tournament_id;
+        let unrated = settings.unrated;
+        let (code, first_player, turn_msg, turn_timer) = self.games.insert_with_code(|code| {
+            let mut game = Game {
+                id: game_id,
+                state: game_state,
+                players: players.clone(),
+                created_at: Instant::now(),
+                player_names,
+                profiles,
+                settings,
+                journal,
+                departed: HashMap::new(),
+                code: code.clone(),
+                host,
+                open_seats: HashSet::new(),
+                pacing: PacingTracker::default(),
+                turn_deadline: None,
+                turn_paused_until: None,
+                next_round_votes: HashSet::new(),
+                intermission_deadline: None,
+                resumed_at: None,
+            };
+            game.start_turn_clock();
 
-        let mut games = self.games.write().await;
-        games.insert(game_id, game);
-        drop(games); // Release lock before broadcasting
+            // Calculate valid actions for the first player *before* moving game into its task
+            let first_player = game.state.current_player.clone();
+            let turn_msg = game.turn_message(&first_player);
+            let turn_timer = game.turn_timer();
+            (game, (code, first_player, turn_msg, turn_timer))
+        });
+        for player_id in &players {
+            self.rooms.join(player_id, RoomId::Game(game_id));
+        }
 
         // Persist to database
         let game_model = crate::entities::game::ActiveModel {
             id: Set(game_id),
             lobby_id: Set(lobby_id),
             state: Set(serde_json::json!({})), // Initial empty state
-            created_at: Set(Utc::now().into()),
+            created_at: Set(Utc::now()),
             completed_at: Set(None),
+            abandoned_at: Set(None),
+            outcome: Set(None),
+            deleted_at: Set(None),
+            variant: Set(variant.as_str().to_string()),
+            player_count: Set(players.len() as i32),
+            tournament_id: Set(tournament_id),
+            code: Set(Some(code.to_string())),
+            unrated: Set(unrated),
         };
-        if let Err(e) = game_model.insert(&self.db).await {
-            warn!("Failed to persist game to DB: {}", e);
+        if let Err(e) = self.persist_new_game(game_model, &players).await {
+            warn!("Failed to persist game {} to DB: {}", game_id, e);
         }
 
-        // Persist game_players
-        for player_id in &players {
-            if let Ok(player_uuid) = Uuid::parse_str(player_id) {
-                let player_model = crate::entities::game_player::ActiveModel {
-                    game_id: Set(game_id),
-                    player_id: Set(player_uuid),
-                    final_score: Set(None),
-                };
-                if let Err(e) = player_model.insert(&self.db).await {
-                    warn!("Failed to persist game_player to DB: {}", e);
-                }
-            }
-        }
+        self.db_writes.push(DbWrite::DealRecorded { game_id, deal: first_deal });
 
-        info!("Game {} created with {} players", game_id, players.len());
+        info!("Game {} ({}) created with {} players", game_id, code, players.len());
 
         // Broadcast GameStarting message to all players
-        let msg = ServerMessage::GameStarting { game_id };
-        self.connection_manager.broadcast_to_players(&players, msg).await;
+        let msg = ServerMessage::GameStarting { game_id, code };
+        self.outbox.broadcast(game_id, &players, msg).await;
 
         // Send valid actions to the first player
-        let turn_msg = ServerMessage::YourTurn { valid_actions };
-        self.connection_manager.send_to_player(first_player.clone(), turn_msg).await;
+        self.outbox.send(game_id, &first_player, turn_msg).await;
+        self.arm_turn_timer(game_id, turn_timer);
 
         game_id
     }
 
+    /// Create an unrated matchmaking game for `humans` with `bots` more seats that the server
+    /// plays, the way it plays a departed player's seat
+    pub async fn create_backfilled_game(&self, humans: Vec<PlayerId>, bots: usize, settings: GameSettings) -> GameId {
+        let bot_seats: Vec<PlayerId> = (0..bots).map(|_| format!("bot-{}", Uuid::new_v4())).collect();
+        let players = humans.into_iter().chain(bot_seats.iter().cloned()).collect();
+        let game_id = self.create_game_with_settings(players, None, GameSettings { unrated: true, ..settings }).await;
+
+        if let Ok(game) = self.games.get(game_id) {
+            let _ = game.call(move |game| {
+                let player_names = Arc::make_mut(&mut game.player_names);
+                for (n, seat) in bot_seats.iter().enumerate() {
+                    let name = format!("Bot {}", n + 1);
+                    player_names.insert(seat.clone(), name.clone());
+                    game.profiles.insert(seat.clone(), PlayerProfile::named(name));
+                    game.departed.insert(seat.clone(), DeparturePolicy::BotTakeover);
+                }
+            }).await;
+        }
+        self.play_departed_turns(game_id).await;
+        game_id
+    }
+
     /// End a game and remove it from storage
     pub async fn end_game(&self, game_id: GameId) {
         // Mark game as completed in DB
@@ -121,26 +1012,134 @@ impl GameManager {
             .filter(crate::entities::game::Column::Id.eq(game_id))
             .exec(&self.db).await;
         
-        let mut games = self.games.write().await;
-        if games.remove(&game_id).is_some() {
+        if self.remove_game(game_id).await {
             info!("Game {} ended and removed", game_id);
         } else {
             warn!("Attempted to end non-existent game {}", game_id);
         }
     }
 
+    /// Drop a game from memory along with its room, trace flag, message log and turn timer
+    async fn remove_game(&self, game_id: GameId) -> bool {
+        if !self.games.remove(game_id) {
+            return false;
+        }
+        self.rooms.close(RoomId::Game(game_id));
+        self.trace_filter.disable(game_id);
+        self.events.remove_game(game_id);
+        self.timers.cancel_game(game_id);
+        true
+    }
+
+    /// Remove games nobody is left to play or watch: every player has departed or has no
+    /// session. Unfinished ones are recorded as abandoned. Returns the removed games.
+    pub async fn reap_dead_games(&self) -> Vec<GameId> {
+        // Each game's players who have not departed, and whether it finished
+        let mut candidates: Vec<(GameId, Vec<PlayerId>, bool)> = Vec::new();
+        for (game_id, game) in self.games.all() {
+            let candidate = game.call(|g| {
+                g.resumed_at.is_none_or(|at| at.elapsed() >= REJOIN_GRACE).then(|| {
+                    let seated = g.players.iter().filter(|p| !g.departed.contains_key(*p)).cloned().collect();
+                    (seated, g.state.phase == crate::engine::GamePhase::GameComplete)
+                })
+            }).await;
+            if let Ok(Some((seated, finished))) = candidate {
+                candidates.push((game_id, seated, finished));
+            }
+        }
+
+        let mut reaped = Vec::new();
+        for (game_id, seated, finished) in candidates {
+            let mut alive = false;
+            for player_id in &seated {
+                if self.connection_manager.has_session(player_id).await {
+                    alive = true;
+                    break;
+                }
+            }
+            if alive || !self.remove_game(game_id).await {
+                continue;
+            }
+
+            if !finished {
+                use sea_orm::sea_query::Expr;
+                let result = crate::entities::game::Entity::update_many()
+                    .col_expr(crate::entities::game::Column::AbandonedAt, Expr::value(Utc::now()))
+                    .filter(crate::entities::game::Column::Id.eq(game_id))
+                    .filter(crate::entities::game::Column::CompletedAt.is_null())
+                    .exec(&self.db).await;
+                if let Err(e) = result {
+                    warn!("Failed to mark game {} abandoned in DB: {}", game_id, e);
+                }
+            }
+            info!("Reaped {} game {}", if finished { "finished" } else { "abandoned" }, game_id);
+            reaped.push(game_id);
+        }
+
+        reaped
+    }
+
     /// Get the game state view for a specific player
     pub async fn get_game_state(&self, game_id: GameId, player_id: PlayerId) -> Result<PlayerGameView, GameError> {
-        let games = self.games.read().await;
-        let game = games.get(&game_id)
-            .ok_or(GameError::GameNotFound)?;
-        
-        // Check if player is in the game
-        if !game.players.contains(&player_id) {
-            return Err(GameError::PlayerNotInGame);
+        self.games.get(game_id)?.call(move |game| {
+            // Check if player is in the game
+            if !game.players.contains(&player_id) {
+                return Err(GameError::PlayerNotInGame);
+            }
+            Ok(game.player_view(player_id))
+        }).await?
+    }
+
+    /// Send a player their full view of the game as the next message in their stream, followed
+    /// by YourTurn when they are to act
+    pub async fn send_game_state(&self, game_id: GameId, player_id: PlayerId) -> Result<(), GameError> {
+        let pid = player_id.clone();
+        let (state, turn_msg) = self.games.get(game_id)?.call(move |game| {
+            if !game.players.contains(&pid) {
+                return Err(GameError::PlayerNotInGame);
+            }
+            Ok((game.player_view(pid.clone()), game.pending_turn_message(&pid)))
+        }).await??;
+        self.outbox.send(game_id, &player_id, ServerMessage::GameState { state: Box::new(state) }).await;
+        if let Some(turn_msg) = turn_msg {
+            self.outbox.send(game_id, &player_id, turn_msg).await;
+        }
+        Ok(())
+    }
+
+    /// Send a player a suggested bid or card for their turn, after any turn messages already
+    /// queued for them
+    pub async fn send_hint(&self, game_id: GameId, player_id: PlayerId) -> Result<(), GameError> {
+        let pid = player_id.clone();
+        let hint = self.games.get(game_id)?.call(move |game| game.hint(&pid)).await??;
+        self.outbox.send(game_id, &player_id, hint).await;
+        Ok(())
+    }
+
+    /// Resend the messages a player missed after `seq`.
+    ///
+    /// Falls back to a full GameState (plus YourTurn when they are to bid or play) if the gap is
+    /// no longer buffered.
+    pub async fn resync_from(&self, game_id: GameId, player_id: PlayerId, seq: u64) -> Result<(), GameError> {
+        let pid = player_id.clone();
+        let (view, turn_msg) = self.games.get(game_id)?.call(move |game| {
+            if !game.players.contains(&pid) {
+                return Err(GameError::PlayerNotInGame);
+            }
+            Ok((game.player_view(pid.clone()), game.pending_turn_message(&pid)))
+        }).await??;
+
+        if self.outbox.replay(game_id, &player_id, seq).await {
+            debug!("Replayed messages after seq {} to player {} in game {}", seq, player_id, game_id);
+            return Ok(());
         }
 
-        Ok(game.state.get_player_view(player_id, game_id))
+        info!("Player {} is too far behind in game {} (seq {}); sending full state", player_id, game_id, seq);
+        self.outbox.send(game_id, &player_id, ServerMessage::GameState { state: Box::new(view) }).await;
+        if let Some(turn_msg) = turn_msg {
+            self.outbox.send(game_id, &player_id, turn_msg).await;
+        }
+        Ok(())
     }
 
     /// Handle a player action (bid or card play)
@@ -151,317 +1150,720 @@ impl GameManager {
         player_id: PlayerId,
         action: PlayerAction,
     ) -> Result<(), GameError> {
-        // Cancel the turn timer since player acted
-        self.cancel_turn_timer(game_id).await;
-
-        // Get mutable access to the game
-        // Using a scoped lock ensures other games can be accessed concurrently
-        let mut games = self.games.write().await;
-        let game = games.get_mut(&game_id)
-            .ok_or(GameError::GameNotFound)?;
-
-        // Check if player is in the game
-        if !game.players.contains(&player_id) {
-            return Err(GameError::PlayerNotInGame);
-        }
-
-        // Validate the action before applying
-        // Any validation errors are caught and returned without affecting game state
-        game.state.validate_action(player_id.clone(), &action)?;
-
-        // Store state before applying action to detect phase changes
-        let phase_before = game.state.phase;
-        let trick_complete_before = game.state.current_trick.is_complete(game.players.len());
+        self.handle_player_action_at_version(game_id, player_id, action, None).await
+    }
 
-        // Apply the action to update state
-        // If this fails, the game state remains unchanged
-        game.state.apply_action(player_id.clone(), action.clone())?;
+    /// Handle a player action, rejecting it if `expected_version` no longer matches the game state
+    pub async fn handle_player_action_at_version(
+        &self,
+        game_id: GameId,
+        player_id: PlayerId,
+        action: PlayerAction,
+        expected_version: Option<u64>,
+    ) -> Result<(), GameError> {
+        self.apply_player_action(game_id, player_id, action, expected_version, false).await?;
+        self.play_departed_turns(game_id).await;
+        Ok(())
+    }
 
-        // Get the list of players for broadcasting
-        let players = game.players.clone();
-        let game_id_copy = game_id;
-        let phase_after = game.state.phase;
+    /// Validate, apply and broadcast a single action; `auto` marks one played for a timed out player
+    async fn apply_player_action(
+        &self,
+        game_id: GameId,
+        player_id: PlayerId,
+        action: PlayerAction,
+        expected_version: Option<u64>,
+        auto: bool,
+    ) -> Result<(), GameError> {
+        game_trace!(self.trace_filter, game_id, player = %player_id, ?action, ?expected_version, "Action received");
 
-        // Check if phase changed (e.g. Bidding -> Playing)
-        let mut phase_change_updates = Vec::new();
-        if phase_before != phase_after {
-            info!("Phase changed from {:?} to {:?} in game {}", phase_before, phase_after, game_id_copy);
-            for pid in &players {
-                let view = game.state.get_player_view(pid.clone(), game_id_copy);
-                phase_change_updates.push((pid.clone(), view));
+        let trace_filter = Arc::clone(&self.trace_filter);
+        let pid = player_id.clone();
+        let played = action.clone();
+        let applied = self.games.get(game_id)?.call(move |game| {
+            game.play(&pid, played, expected_version, auto, &trace_filter)
+        }).await?;
+        let AppliedAction {
+            recorded, invariant_report, players, turn, hide_bid, next_turn, intermission_deadline,
+            phase_change_updates, trick_msg, final_result, pacing_report, tournament, forfeited, round_data,
+            achievements, turn_timer,
+        } = match applied {
+            Ok(applied) => applied,
+            Err((e, report)) => {
+                submit_bug_report(&self.db, report.map(|report| *report)).await;
+                return Err(e);
             }
-        }
-
-        // Check if trick was just completed
-        let trick_just_completed = !trick_complete_before && 
-            (phase_after == crate::game_state::GamePhase::RoundComplete || 
-             phase_after == crate::game_state::GamePhase::GameComplete ||
-             game.state.current_trick.cards.is_empty());
-
-        // Get trick winner and final scores if needed
-        let trick_winner = if trick_just_completed && !game.state.completed_tricks.is_empty() {
-            Some(game.state.completed_tricks.last().unwrap().winner.clone())
-        } else {
-            None
         };
+        let game_id_copy = game_id;
 
-        let final_scores = if phase_after == crate::game_state::GamePhase::GameComplete {
-            Some(game.state.total_scores.clone())
-        } else {
-            None
-        };
+        // The turn this action ended no longer times out; a rejected action left it running
+        self.arm_turn_timer(game_id, turn_timer);
 
-        // If RoundComplete, don't auto-schedule. 
-        // We wait for StartNextRound message.
-        // Save round history to DB
-        let round_data = if phase_after == crate::game_state::GamePhase::RoundComplete && phase_before != phase_after {
-            // Collect round data before dropping lock
-            let round_number = game.state.round_number;
-            let player_results = game.state.current_round.clone();
-            Some((round_number, player_results))
-        } else {
-            None
-        };
-
-        // Release the write lock before broadcasting
-        drop(games);
+        submit_bug_report(&self.db, invariant_report).await;
+        self.db_writes.push(DbWrite::ActionRecorded { game_id: game_id_copy, action: recorded });
 
         debug!("Player {} performed action in game {}", player_id, game_id_copy);
 
         // Broadcast phase change updates if any
         for (pid, view) in phase_change_updates {
-             self.connection_manager.send_to_player(pid.clone(), ServerMessage::GameState { state: view }).await;
+             self.outbox.send(game_id_copy, &pid, ServerMessage::GameState { state: Box::new(view) }).await;
         }
         
         // Persist round data to DB if round just completed
-        if let Some((round_number, player_results)) = round_data {
-            let round_model = crate::entities::game_round::ActiveModel {
-                id: sea_orm::ActiveValue::NotSet,
-                game_id: Set(game_id_copy),
-                round_number: Set(round_number as i32),
-                player_results: Set(serde_json::json!(player_results)),
-            };
-            if let Err(e) = round_model.insert(&self.db).await {
-                warn!("Failed to persist game_round to DB: {}", e);
-            }
+        let mut pace_notice = None;
+        let mut round_results = None;
+        if let Some(FinishedRound { results, totals, pace_notice: notice }) = round_data {
+            pace_notice = notice;
+            self.persist_round(game_id_copy, &results, &totals);
+            round_results = Some(results);
         }
 
-        // Broadcast PlayerAction message to all players
-        //  game.state.current_player IS the next player.
-        
-        let games_read = self.games.read().await;
-        let next_player = if let Some(g) = games_read.get(&game_id_copy) {
-            g.state.current_player.clone()
-        } else {
-            // Should not happen, but fallback
-            player_id.clone() 
-        };
-        drop(games_read);
-
-        let action_msg = ServerMessage::PlayerAction {
-            player_id: player_id.clone(),
-            action,
-            next_player,
-        };
-        self.connection_manager.broadcast_to_players(&players, action_msg).await;
+        // Broadcast PlayerAction message to all players, with the state the action left behind
+        broadcast_player_action(&self.outbox, game_id_copy, &players, player_id.clone(), action, turn, hide_bid).await;
 
         // Broadcast TrickComplete when trick finishes
-        if let Some(winner) = trick_winner {
-            let trick_msg = ServerMessage::TrickComplete {
-                winner: winner.clone(),
-            };
-            self.connection_manager.broadcast_to_players(&players, trick_msg).await;
-            info!("Trick completed in game {}, winner: {}", game_id_copy, winner);
+        if let Some(trick_msg) = trick_msg {
+            self.outbox.broadcast(game_id_copy, &players, trick_msg).await;
+            info!("Trick completed in game {}", game_id_copy);
+        }
+
+        if let Some(results) = round_results {
+            if let Some(deadline) = intermission_deadline {
+                self.schedule_intermission_end(game_id_copy, results.round_number, deadline);
+            }
+            self.outbox.broadcast(game_id_copy, &players, ServerMessage::RoundComplete { results }).await;
+        }
+
+        if let Some((pid, notice)) = pace_notice {
+            self.outbox.send(game_id_copy, &pid, notice).await;
         }
 
         // Broadcast GameOver when game ends
-        if let Some(scores) = final_scores {
-            // Persist game completion and final scores to DB
-            use sea_orm::sea_query::Expr;
-            let _ = crate::entities::game::Entity::update_many()
-                .col_expr(crate::entities::game::Column::CompletedAt, Expr::value(Utc::now()))
-                .filter(crate::entities::game::Column::Id.eq(game_id_copy))
-                .exec(&self.db).await;
-            
-            // Save final scores for each player
-            for (pid, score) in &scores {
-                if let Ok(player_uuid) = Uuid::parse_str(pid) {
-                    let _ = crate::entities::game_player::Entity::update_many()
-                        .col_expr(crate::entities::game_player::Column::FinalScore, Expr::value(*score))
-                        .filter(crate::entities::game_player::Column::GameId.eq(game_id_copy))
-                        .filter(crate::entities::game_player::Column::PlayerId.eq(player_uuid))
-                        .exec(&self.db).await;
-                }
+        if let Some(FinalResult { scores, placements, outcome, game_over: game_over_msg, summaries }) = final_result {
+            self.db_writes.push(DbWrite::GameCompleted {
+                game_id: game_id_copy,
+                completed_at: Utc::now(),
+                outcome: serde_json::json!(outcome),
+                placements: placements.iter()
+                    .filter_map(|placement| Some(FinalSeat {
+                        player_id: Uuid::parse_str(&placement.player_id).ok()?,
+                        score: placement.score,
+                        place: placement.place as i32,
+                    }))
+                    .collect(),
+            });
+
+            if let (Some(webhooks), Some((tournament_id, names))) = (self.webhooks.get(), tournament) {
+                webhooks.game_completed(GameCompletedEvent::new(tournament_id, game_id_copy, &scores, &placements, &names, &forfeited));
+            }
+
+            self.outbox.broadcast(game_id_copy, &players, game_over_msg).await;
+            for (pid, summary) in summaries {
+                self.outbox.send(game_id_copy, &pid, summary).await;
+            }
+            if let Some((host, report)) = pacing_report {
+                self.outbox.send(game_id_copy, &host, report).await;
             }
-            
-            let game_over_msg = ServerMessage::GameOver {
-                final_scores: scores,
-            };
-            self.connection_manager.broadcast_to_players(&players, game_over_msg).await;
             info!("Game {} completed", game_id_copy);
-        } else {
+        } else if let Some((next_player, turn_msg)) = next_turn {
             // Game continues, notify next player
-            // We need to re-acquire the lock to read the *current* state (or trust our local logic)
-            // Actually, we dropped the lock on line 154. But we haven't modified the game since then.
-            // But we need the NEW current player. We didn't save it. 
-            // We need to re-read the game state or return it from apply_action? 
-            // Better: re-acquire read lock briefly.
-            
-            let games = self.games.read().await;
-            if let Some(game) = games.get(&game_id_copy) {
-                let next_player = game.state.current_player.clone();
-                let valid_actions = game.state.get_valid_actions(next_player.clone());
-                let turn_msg = ServerMessage::YourTurn { valid_actions };
-                self.connection_manager.send_to_player(next_player.clone(), turn_msg).await;
-            }
+            self.outbox.send(game_id_copy, &next_player, turn_msg).await;
         }
 
+        self.award_achievements(achievements);
+
         Ok(())
     }
 
+    /// Queue feats to count towards each player's achievements; any they unlock are announced
+    /// once stored. Seats without an account (backfill bots) have nothing to record against.
+    fn award_achievements(&self, feats: Vec<(PlayerId, Achievement)>) {
+        let mut by_player: HashMap<PlayerId, Vec<Achievement>> = HashMap::new();
+        for (pid, feat) in feats {
+            by_player.entry(pid).or_default().push(feat);
+        }
+        for (pid, feats) in by_player {
+            let Ok(user_id) = Uuid::parse_str(&pid) else { continue };
+            self.db_writes.push(DbWrite::AchievementsEarned { user_id, feats });
+        }
+    }
+
+    /// Tell a player about the achievements a stored write unlocked
+    async fn announce_unlocks(&self, Unlocked { user_id, achievements }: Unlocked) {
+        let pid = user_id.to_string();
+        for achievement in achievements {
+            info!("Player {} unlocked {}", pid, achievement.as_str());
+            self.connection_manager.send_to_player(pid.clone(), ServerMessage::AchievementUnlocked {
+                achievement,
+                title: achievement.title().to_string(),
+                description: achievement.description().to_string(),
+            }).await;
+        }
+    }
+
     /// Handle specific request to start the next round
     pub async fn handle_start_next_round(
         &self,
         game_id: GameId,
         player_id: PlayerId,
     ) -> Result<(), GameError> {
-        let mut games = self.games.write().await;
-        let game = games.get_mut(&game_id)
-            .ok_or(GameError::GameNotFound)?;
+        self.start_next_round(game_id, NextRoundRequest::Player(player_id)).await?;
+        self.play_departed_turns(game_id).await;
+        Ok(())
+    }
+
+    /// Mark a player as gone for good and take over their seat according to the game's departure policy
+    pub async fn handle_player_departed(&self, game_id: GameId, player_id: PlayerId) -> Result<(), GameError> {
+        let pid = player_id.clone();
+        let departure = self.games.get(game_id)?.call(move |game| {
+            if !game.players.contains(&pid) {
+                return Err(GameError::PlayerNotInGame);
+            }
+            if game.state.phase == crate::engine::GamePhase::GameComplete {
+                return Ok(None);
+            }
 
-        // Validation
-        if game.state.phase != crate::game_state::GamePhase::RoundComplete {
-            return Err(GameError::InvalidMove("Not in RoundComplete phase".to_string()));
+            let outcome = game.settings.departure_policy;
+            game.departed.insert(pid.clone(), outcome);
+            game.journal.record_event(game.state.version, format!("{} departed: {:?}", pid, outcome));
+            Ok(Some((game.players.clone(), outcome)))
+        }).await??;
+        let Some((players, outcome)) = departure else {
+            return Ok(());
+        };
+
+        info!("Player {} departed game {}: {:?}", player_id, game_id, outcome);
+        let msg = ServerMessage::PlayerDeparted { player_id, outcome };
+        self.outbox.broadcast(game_id, &players, msg).await;
+
+        self.play_departed_turns(game_id).await;
+        Ok(())
+    }
+
+    /// Let anyone take over a departed player's seat. Only the acting host may open one.
+    pub async fn open_seat(&self, game_id: GameId, caller: &PlayerId, seat: PlayerId) -> Result<(), GameError> {
+        let (opener, opened) = (caller.clone(), seat.clone());
+        let players = self.games.get(game_id)?.call(move |game| {
+            if game.acting_host() != Some(&opener) {
+                return Err(GameError::NotGameHost);
+            }
+            if game.state.phase == crate::engine::GamePhase::GameComplete || !game.departed.contains_key(&opened) {
+                return Err(GameError::SeatUnavailable);
+            }
+            game.open_seats.insert(opened.clone());
+            game.journal.record_event(game.state.version, format!("{} opened the seat of {}", opener, opened));
+            Ok(game.players.clone())
+        }).await??;
+
+        info!("Seat {} opened in game {} by {}", seat, game_id, caller);
+        self.outbox.broadcast(game_id, &players, ServerMessage::SeatOpened { game_id, seat }).await;
+        Ok(())
+    }
+
+    /// Every open seat across running games, oldest game first
+    pub async fn open_seats(&self) -> Vec<OpenSeatInfo> {
+        let mut games = self.games.all();
+        games.sort_by_key(|(_, g)| g.created_at);
+        let mut open = Vec::new();
+        for (_, game) in games {
+            let seats = game.call(|game| {
+                game.players.iter().filter(|p| game.open_seats.contains(*p)).map(|seat| OpenSeatInfo {
+                    game_id: game.id,
+                    code: game.code.clone(),
+                    seat: seat.clone(),
+                    username: game.player_names.get(seat).cloned().unwrap_or_default(),
+                    score: game.state.total_scores.get(seat).copied().unwrap_or(0),
+                    round_number: game.state.round_number,
+                    player_count: game.players.len(),
+                }).collect::<Vec<_>>()
+            }).await;
+            open.extend(seats.unwrap_or_default());
         }
-        
-        if game.state.current_player != player_id {
-            return Err(GameError::NotPlayerTurn);
+        open
+    }
+
+    /// Seat `player_id` in place of a departed player: they inherit the hand, bids, tricks and
+    /// score, and every identity mapping is moved over so the old id no longer acts in the game.
+    pub async fn take_seat(&self, game_id: GameId, seat: PlayerId, player_id: PlayerId) -> Result<(), GameError> {
+        let profile = self.user_directory.resolve(&player_id).await;
+
+        let (old, new) = (seat.clone(), player_id.clone());
+        let (players, view, turn_msg) = self.games.get(game_id)?.call(move |game| {
+            let (seat, player_id) = (old, new);
+            if game.players.contains(&player_id) {
+                return Err(GameError::AlreadySeated);
+            }
+            if !game.open_seats.contains(&seat) || game.state.phase == crate::engine::GamePhase::GameComplete {
+                return Err(GameError::SeatUnavailable);
+            }
+
+            game.state.replace_player(&seat, &player_id);
+            for p in game.players.iter_mut().filter(|p| **p == seat) {
+                *p = player_id.clone();
+            }
+            let player_names = Arc::make_mut(&mut game.player_names);
+            player_names.remove(&seat);
+            game.profiles.remove(&seat);
+            if let Some(profile) = profile {
+                player_names.insert(player_id.clone(), profile.username.clone());
+                game.profiles.insert(player_id.clone(), profile);
+            }
+            game.departed.remove(&seat);
+            game.open_seats.remove(&seat);
+            if game.host == seat {
+                game.host = player_id.clone();
+            }
+            game.journal.replace_player(&seat, &player_id);
+            game.pacing.replace_player(&seat, &player_id);
+            game.journal.record_event(game.state.version, format!("{} took over the seat of {}", player_id, seat));
+
+            let turn_msg = game.pending_turn_message(&player_id);
+            Ok((game.players.clone(), game.player_view(player_id.clone()), turn_msg))
+        }).await??;
+
+        self.rooms.leave(&seat, RoomId::Game(game_id));
+        self.rooms.join(&player_id, RoomId::Game(game_id));
+        self.transfer_participation(game_id, &seat, &player_id).await;
+
+        info!("Player {} took over seat {} in game {}", player_id, seat, game_id);
+        self.outbox.broadcast(game_id, &players, ServerMessage::SeatTaken { seat, player_id: player_id.clone() }).await;
+        self.outbox.send(game_id, &player_id, ServerMessage::GameState { state: Box::new(view) }).await;
+        if let Some(turn_msg) = turn_msg {
+            self.outbox.send(game_id, &player_id, turn_msg).await;
         }
+        Ok(())
+    }
 
-        // Advance
-        game.state.advance_to_next_round();
-        
-        let players = game.players.clone();
-        
-        // Broadcast new state if round started
-        if game.state.phase == crate::game_state::GamePhase::Bidding {
-             info!("Round {} started in game {}", game.state.round_number, game_id);
-             
-             for pid in &players {
-                let view = game.state.get_player_view(pid.clone(), game_id);
-                self.connection_manager.send_to_player(pid.clone(), ServerMessage::GameState { state: view }).await;
-                
-                // Send valid actions to the first player
-                if *pid == game.state.current_player {
-                    let valid_actions = game.state.get_valid_actions(pid.clone());
-                    let turn_msg = ServerMessage::YourTurn { valid_actions };
-                    self.connection_manager.send_to_player(pid.clone(), turn_msg).await;
+    /// Hand the seat's game_players row to the player taking it over
+    async fn transfer_participation(&self, game_id: GameId, seat: &PlayerId, player_id: &PlayerId) {
+        use crate::entities::game_player;
+        use sea_orm::sea_query::Expr;
+        let Ok(new_uuid) = Uuid::parse_str(player_id) else { return };
+
+        let result = match Uuid::parse_str(seat) {
+            Ok(old_uuid) => game_player::Entity::update_many()
+                .col_expr(game_player::Column::PlayerId, Expr::value(new_uuid))
+                .filter(game_player::Column::GameId.eq(game_id))
+                .filter(game_player::Column::PlayerId.eq(old_uuid))
+                .exec(&self.db).await
+                .map(|_| ()),
+            Err(_) => game_player::ActiveModel {
+                game_id: Set(game_id),
+                player_id: Set(new_uuid),
+                final_score: Set(None),
+                placement: Set(None),
+            }.insert(&self.db).await.map(|_| ()),
+        };
+        if let Err(e) = result {
+            warn!("Failed to move game_player {} to {} in DB: {}", seat, player_id, e);
+        }
+    }
+
+    /// Auto-play while the player to act has departed, so the game never waits on a dead session
+    async fn play_departed_turns(&self, game_id: GameId) {
+        let Ok(game) = self.games.get(game_id) else { return };
+        loop {
+            let next = game.call(|game| {
+                let current = game.state.current_player.clone();
+                if game.state.phase == crate::engine::GamePhase::RoundComplete {
+                    // Departures can leave nobody else to wait for
+                    let waiting_for = game.next_round_waiting_for();
+                    let held_up = waiting_for.is_empty()
+                        || (waiting_for == [current.clone()] && game.departed.contains_key(&current));
+                    if !held_up {
+                        return None;
+                    }
+                }
+                if !game.departed.contains_key(&current) && game.state.phase != crate::engine::GamePhase::RoundComplete {
+                    return None;
+                }
+                match game.state.phase {
+                    crate::engine::GamePhase::GameComplete => None,
+                    crate::engine::GamePhase::RoundComplete => Some((current, None)),
+                    _ => {
+                        // Fall back to any legal action should the timeout auto action not apply
+                        let action = game.state.get_auto_action(&RulesAware)
+                            .filter(|action| game.state.validate_action(&current, action).is_ok())
+                            .or_else(|| game.state.get_valid_actions(current.clone()).into_iter().next());
+                        action.map(|action| (current, Some(action)))
+                    }
                 }
-             }
-        } else if game.state.phase == crate::game_state::GamePhase::GameComplete {
-             let game_over_msg = ServerMessage::GameOver {
-                final_scores: game.state.total_scores.clone(),
+            }).await;
+            let Ok(Some(next)) = next else { return };
+
+            let result = match next {
+                (player_id, Some(action)) => self.apply_player_action(game_id, player_id, action, None, false).await,
+                (player_id, None) => self.start_next_round(game_id, NextRoundRequest::Player(player_id)).await,
             };
-            self.connection_manager.broadcast_to_players(&players, game_over_msg).await;
+            if let Err(e) = result {
+                warn!("Failed to auto-play for departed player in game {}: {}", game_id, e);
+                return;
+            }
+        }
+    }
+
+    /// Advance from RoundComplete to the next round and broadcast the new state. Under
+    /// `AllPlayers` a player's request only counts their vote until everyone has asked.
+    async fn start_next_round(
+        &self,
+        game_id: GameId,
+        request: NextRoundRequest,
+    ) -> Result<(), GameError> {
+        let trace_filter = Arc::clone(&self.trace_filter);
+        let (messages, deal, turn_timer) = self.games.get(game_id)?.call(move |game| {
+            game.start_next_round(request, &trace_filter).map(|(messages, deal)| (messages, deal, game.turn_timer()))
+        }).await??;
+        self.arm_turn_timer(game_id, turn_timer);
+        for (pid, msg) in messages {
+            self.outbox.send(game_id, &pid, msg).await;
+        }
+
+        if let Some(deal) = deal {
+            self.db_writes.push(DbWrite::DealRecorded { game_id, deal });
         }
 
         Ok(())
     }
 
-    /// Start a turn timer for the current player in a game
+    /// Insert a new game's row together with a seat row per registered player; bot seats have
+    /// no user to point at. Either all of it is stored or none.
+    async fn persist_new_game(&self, game_model: crate::entities::game::ActiveModel, players: &[PlayerId]) -> Result<(), DbErr> {
+        let txn = self.db.begin().await?;
+        let game_id = game_model.insert(&txn).await?.id;
+        for player_uuid in players.iter().filter_map(|player_id| Uuid::parse_str(player_id).ok()) {
+            crate::entities::game_player::ActiveModel {
+                game_id: Set(game_id),
+                player_id: Set(player_uuid),
+                final_score: Set(None),
+                placement: Set(None),
+            }.insert(&txn).await?;
+        }
+        txn.commit().await
+    }
+
+    /// Queue a finished round and bring every seat's `final_score` up to the running total, so
+    /// the game's history survives a restart
+    fn persist_round(&self, game_id: GameId, results: &RoundResult, totals: &HashMap<PlayerId, i32>) {
+        self.db_writes.push(DbWrite::RoundFinished {
+            game_id,
+            round_number: results.round_number as i32,
+            player_results: serde_json::json!(results.player_results),
+            totals: totals.iter()
+                .filter_map(|(player_id, total)| Some((Uuid::parse_str(player_id).ok()?, *total)))
+                .collect(),
+        });
+    }
+
+    /// Start the round after `round_number` at `deadline` if players have not started it by then
+    fn schedule_intermission_end(&self, game_id: GameId, round_number: usize, deadline: Instant) {
+        self.timers.schedule(game_id, Timer::Intermission { round_number }, deadline);
+    }
+
+    /// Arm the turn timer for the turn that just started, or cancel the game's turn timer when
+    /// nobody is to act. Scheduling replaces the timer of the turn before.
+    fn arm_turn_timer(&self, game_id: GameId, turn_timer: Option<(Timer, Instant)>) {
+        match turn_timer {
+            Some((timer, deadline)) => self.timers.schedule(game_id, timer, deadline),
+            None => self.timers.cancel(game_id, TimerKind::Turn),
+        }
+    }
+
+    /// Restart the current player's turn timer with `timeout_secs` instead of the game's own
+    /// limit, and repeat their YourTurn with the new deadline
     pub async fn start_turn_timer(&self, game_id: GameId, timeout_secs: u64) {
-        // Cancel any existing timer for this game
-        self.cancel_turn_timer(game_id).await;
-
-        // Get the current player and deadline
-        let (current_player, deadline) = {
-            let mut games = self.games.write().await;
-            if let Some(game) = games.get_mut(&game_id) {
-                game.state.set_turn_deadline(timeout_secs);
-                (game.state.current_player.clone(), game.state.turn_deadline)
-            } else {
-                return; // Game not found
-            }
+        let Ok(game) = self.games.get(game_id) else {
+            return; // Game not found
+        };
+        let Ok((current_player, deadline, version, turn_msg)) = game.call(move |game| {
+            game.set_turn_deadline(timeout_secs);
+            let current_player = game.state.current_player.clone();
+            let turn_msg = game.turn_message(&current_player);
+            (current_player, game.turn_deadline, game.state.version, turn_msg)
+        }).await else {
+            return;
         };
 
         let Some(deadline) = deadline else {
             return;
         };
 
-        // Clone Arc references for the async task
-        let games = Arc::clone(&self.games);
-        let connection_manager = Arc::clone(&self.connection_manager);
-        let timer_handles = Arc::clone(&self.timer_handles);
-
-        // Spawn a task to monitor the deadline
-        let handle = tokio::spawn(async move {
-            // Sleep until the deadline
-            tokio::time::sleep_until(deadline.into()).await;
-
-            // Check if the game still exists and the turn hasn't changed
-            let auto_action = {
-                let games_read = games.read().await;
-                if let Some(game) = games_read.get(&game_id) {
-                    // Check if it's still the same player's turn and deadline hasn't been updated
-                    if game.state.current_player == current_player && game.state.is_turn_expired() {
-                        game.state.get_auto_action()
-                    } else {
-                        None
-                    }
-                } else {
-                    None
+        // Repeat YourTurn so the player sees the deadline
+        self.outbox.send(game_id, &current_player, turn_msg).await;
+
+        // Replaces the game's previous turn timer
+        self.timers.schedule(game_id, Timer::Turn { player_id: current_player, version }, deadline);
+    }
+
+    /// Cancel the turn timer for a game
+    pub async fn cancel_turn_timer(&self, game_id: GameId) {
+        self.timers.cancel(game_id, TimerKind::Turn);
+    }
+
+    /// Stop the turn clock of a player who lost their connection on their turn for the
+    /// disconnect grace period, and tell the table when it runs again
+    async fn pause_turn_clock(&self, player_id: PlayerId) {
+        let grace = self.connection_manager.disconnect_grace();
+        if grace.is_zero() {
+            return;
+        }
+        for game_id in self.rooms.games_of(&player_id) {
+            let Ok(game) = self.games.get(game_id) else { continue };
+            let player = player_id.clone();
+            let paused = game.call(move |game| {
+                let deadline = game.turn_deadline.filter(|deadline| *deadline > Instant::now())?;
+                if game.state.current_player != player || game.turn_paused_until.is_some() || game.departed.contains_key(&player) {
+                    return None;
                 }
-            };
+                let resume_at = Instant::now() + grace;
+                game.turn_paused_until = Some(resume_at);
+                game.turn_deadline = Some(deadline + grace);
+                game.journal.record_event(game.state.version, format!("{} disconnected on their turn; clock paused", player));
+                Some((game.players.clone(), game.state.version, deadline + grace, epoch_ms(resume_at)?))
+            }).await.ok().flatten();
+
+            if let Some((players, version, deadline, resume_deadline)) = paused {
+                info!("Paused the turn clock of disconnected player {} in game {}", player_id, game_id);
+                self.timers.schedule(game_id, Timer::Turn { player_id: player_id.clone(), version }, deadline);
+                let msg = ServerMessage::PlayerDisconnected { game_id, player_id: player_id.clone(), resume_deadline };
+                self.outbox.broadcast(game_id, &players, msg).await;
+            }
+        }
+    }
+
+    /// Restart a paused turn clock when its player is back before the grace period ran out,
+    /// with the time they had left when they dropped
+    async fn resume_turn_clock(&self, player_id: PlayerId) {
+        for game_id in self.rooms.games_of(&player_id) {
+            let Ok(game) = self.games.get(game_id) else { continue };
+            let player = player_id.clone();
+            let resumed = game.call(move |game| {
+                if game.state.current_player != player {
+                    return None;
+                }
+                let unused_grace = game.turn_paused_until.take()?.saturating_duration_since(Instant::now());
+                let deadline = game.turn_deadline? - unused_grace;
+                game.turn_deadline = Some(deadline);
+                Some((game.state.version, deadline))
+            }).await.ok().flatten();
+
+            if let Some((version, deadline)) = resumed {
+                self.timers.schedule(game_id, Timer::Turn { player_id: player_id.clone(), version }, deadline);
+            }
+        }
+    }
+
+    /// Play the turn of a player who ran out of time while the game waits for the host under
+    /// `TimeoutPolicy::WaitForHost`. Only the acting host may.
+    pub async fn force_turn(&self, game_id: GameId, caller: &PlayerId, player_id: PlayerId) -> Result<(), GameError> {
+        let (host, late) = (caller.clone(), player_id.clone());
+        let (action, version) = self.games.get(game_id)?.call(move |game| {
+            if game.acting_host() != Some(&host) {
+                return Err(GameError::NotGameHost);
+            }
+            if game.state.current_player != late || !game.is_turn_expired() {
+                return Err(GameError::TurnNotOverdue);
+            }
+            let action = game.state.get_auto_action(&RulesAware).ok_or(GameError::TurnNotOverdue)?;
+            game.journal.record_event(game.state.version, format!("{} forced the turn of {}", host, late));
+            Ok((action, game.state.version))
+        }).await??;
+
+        info!("Host {} forced the turn of {} in game {}", caller, player_id, game_id);
+        self.apply_player_action(game_id, player_id, action, Some(version), true).await?;
+        self.play_departed_turns(game_id).await;
+        Ok(())
+    }
 
-            // If we have an auto action, apply it
-            if let Some(action) = auto_action {
-                info!("Turn timeout for player {} in game {}, applying auto action", current_player, game_id);
-                
-                // Apply the auto action
-                let mut games_write = games.write().await;
-                if let Some(game) = games_write.get_mut(&game_id) {
-                    if let Err(e) = game.state.apply_action(current_player.clone(), action.clone()) {
-                        warn!("Failed to apply auto action for player {} in game {}: {}", current_player, game_id, e);
-                        return;
+    /// Act on a deadline that passed. Timers carry the state they were armed for, so one that
+    /// fires after the game moved on does nothing.
+    async fn on_timer(&self, game_id: GameId, timer: Timer) {
+        match timer {
+            Timer::Turn { player_id, version } => {
+                let Ok(game) = self.games.get(game_id) else {
+                    return;
+                };
+                let player = player_id.clone();
+                let timed_out = game.call(move |game| {
+                    if game.state.version != version || game.state.current_player != player || !game.is_turn_expired() {
+                        return None;
                     }
+                    // With nobody left to force the turn, it is played after all
+                    if game.state.rules.timer.on_timeout == TimeoutPolicy::WaitForHost && game.acting_host().is_some() {
+                        game.journal.record_event(version, format!("{} ran out of time; waiting for the host", player));
+                        return Some(TimedOut::WaitForHost(game.players.clone()));
+                    }
+                    game.state.get_auto_action(&RulesAware).map(TimedOut::Play)
+                }).await.ok().flatten();
 
-                    let players = game.players.clone();
-                    let next_player = game.state.current_player.clone();
-                    drop(games_write);
-
-                    // Broadcast the auto action
-                    let action_msg = ServerMessage::PlayerAction {
-                        player_id: current_player.clone(),
-                        action,
-                        next_player,
-                    };
-                    connection_manager.broadcast_to_players(&players, action_msg).await;
+                // Apply it like any other action, unless the player acted meanwhile
+                match timed_out {
+                    Some(TimedOut::Play(action)) => {
+                        info!("Turn timeout for player {} in game {}, applying auto action", player_id, game_id);
+                        game_trace!(self.trace_filter, game_id, player = %player_id, ?action, "Auto action on timeout");
+                        if let Err(e) = self.apply_player_action(game_id, player_id.clone(), action, Some(version), true).await {
+                            warn!("Failed to apply auto action for player {} in game {}: {}", player_id, game_id, e);
+                            return;
+                        }
+                        self.play_departed_turns(game_id).await;
+                    }
+                    Some(TimedOut::WaitForHost(players)) => {
+                        info!("Turn timeout for player {} in game {}, waiting for the host", player_id, game_id);
+                        self.outbox.broadcast(game_id, &players, ServerMessage::TurnTimedOut { game_id, player_id }).await;
+                    }
+                    None => {}
+                }
+            }
+            Timer::Intermission { round_number } => {
+                match self.start_next_round(game_id, NextRoundRequest::Timeout { round_number }).await {
+                    Ok(()) => self.play_departed_turns(game_id).await,
+                    Err(e) => debug!("Intermission ended in game {} that no longer runs: {}", game_id, e),
                 }
             }
+        }
+    }
 
-            // Remove this timer handle
-            let mut handles = timer_handles.write().await;
-            handles.remove(&game_id);
-        });
+    /// Games still being played, not counting finished ones waiting to be reaped
+    pub async fn unfinished_games(&self) -> usize {
+        let mut unfinished = 0;
+        for (_, game) in self.games.all() {
+            if game.call(|g| g.state.phase != GamePhase::GameComplete).await.unwrap_or(false) {
+                unfinished += 1;
+            }
+        }
+        unfinished
+    }
+
+    /// Write a checkpoint of every unfinished game to its `state` column, for
+    /// `resume_suspended_games` after a restart. Returns how many were saved.
+    pub async fn suspend_games(&self) -> usize {
+        let mut checkpoints: Vec<(GameId, GameCheckpoint)> = Vec::new();
+        for (game_id, game) in self.games.all() {
+            let checkpoint = game.call(|g| (g.state.phase != GamePhase::GameComplete).then(|| g.checkpoint())).await;
+            if let Ok(Some(checkpoint)) = checkpoint {
+                checkpoints.push((game_id, checkpoint));
+            }
+        }
+
+        let mut saved = 0;
+        for (game_id, checkpoint) in checkpoints {
+            use sea_orm::sea_query::Expr;
+            let result = crate::entities::game::Entity::update_many()
+                .col_expr(crate::entities::game::Column::State, Expr::value(serde_json::json!(checkpoint)))
+                .filter(crate::entities::game::Column::Id.eq(game_id))
+                .exec(&self.db).await;
+            match result {
+                Ok(_) => saved += 1,
+                Err(e) => warn!("Failed to save checkpoint of game {}: {}", game_id, e),
+            }
+        }
+        saved
+    }
+
+    /// Bring back the games saved by `suspend_games` where they stopped. Players are put back in the game's room so it is theirs when they reconnect.
+    pub async fn resume_suspended_games(&self) -> Vec<GameId> {
+        use crate::entities::game;
+        let rows = game::Entity::find()
+            .filter(game::Column::CompletedAt.is_null())
+            .filter(game::Column::AbandonedAt.is_null())
+            .filter(game::Column::DeletedAt.is_null())
+            .all(&self.db)
+            .await;
+        let rows = match rows {
+            Ok(rows) => rows,
+            Err(e) => {
+                warn!("Failed to load suspended games: {}", e);
+                return Vec::new();
+            }
+        };
 
-        // Store the handle so we can cancel it later
-        let mut handles = self.timer_handles.write().await;
-        handles.insert(game_id, handle);
+        let mut resumed = Vec::new();
+        for row in rows {
+            // Games that were running when the server stopped without a checkpoint keep `{}`
+            let Ok(checkpoint) = serde_json::from_value::<GameCheckpoint>(row.state) else {
+                continue;
+            };
+            self.resume_game(row.id, checkpoint).await;
+            resumed.push(row.id);
+        }
+        resumed
     }
 
-    /// Cancel the turn timer for a game
-    pub async fn cancel_turn_timer(&self, game_id: GameId) {
-        let mut handles = self.timer_handles.write().await;
-        if let Some(handle) = handles.remove(&game_id) {
-            handle.abort();
-            debug!("Cancelled turn timer for game {}", game_id);
+    async fn resume_game(&self, game_id: GameId, checkpoint: GameCheckpoint) {
+        let GameCheckpoint {
+            code, players, host, settings, history, departed, open_seats, state, next_round_votes, intermission_remaining_ms,
+        } = checkpoint;
+        // Older checkpoints only kept the finished rounds; their round in progress is dealt again
+        let redeal = state.is_none();
+        let state = state.unwrap_or_else(|| GameState::resume(players.clone(), settings.schedule(), settings.rules.clone(), history));
+        let mut profiles = self.user_directory.resolve_many(&players).await;
+        let mut player_names: HashMap<PlayerId, String> = profiles.iter().map(|(id, profile)| (id.clone(), profile.username.clone())).collect();
+        // Backfill bots have no account; name them the way create_backfilled_game did
+        for (n, seat) in players.iter().filter(|p| p.starts_with("bot-")).enumerate() {
+            let name = format!("Bot {}", n + 1);
+            player_names.insert(seat.clone(), name.clone());
+            profiles.insert(seat.clone(), PlayerProfile::named(name));
         }
+        let mut journal = GameJournal::new(players.clone());
+        if redeal {
+            journal.record_deal(&state);
+        }
+        let deal = redeal.then(|| Deal::from_state(&state));
+        let round_number = state.round_number;
+        let intermission_deadline = intermission_remaining_ms
+            .filter(|_| state.phase == GamePhase::RoundComplete)
+            .map(|ms| Instant::now() + std::time::Duration::from_millis(ms));
+
+        let mut game = Game {
+            id: game_id,
+            state,
+            players: players.clone(),
+            created_at: Instant::now(),
+            player_names: Arc::new(player_names),
+            profiles,
+            settings,
+            journal,
+            departed,
+            code: code.clone(),
+            host,
+            open_seats,
+            pacing: PacingTracker::default(),
+            turn_deadline: None,
+            turn_paused_until: None,
+            next_round_votes,
+            intermission_deadline,
+            resumed_at: Some(Instant::now()),
+        };
+        game.start_turn_clock();
+        // Give the player to act time to reconnect before their turn starts running out
+        game.turn_deadline = game.turn_deadline.map(|deadline| deadline + self.connection_manager.disconnect_grace());
+        let turn_timer = game.turn_timer();
+        self.games.insert(game);
+        self.arm_turn_timer(game_id, turn_timer);
+        for player_id in &players {
+            self.rooms.join(player_id, RoomId::Game(game_id));
+        }
+        if let Some(deadline) = intermission_deadline {
+            self.schedule_intermission_end(game_id, round_number, deadline);
+        }
+
+        use crate::entities::game;
+        // Replaces the deal stored before the restart, and any deals after it
+        if let Some(deal) = deal {
+            self.db_writes.push(DbWrite::DealRecorded { game_id, deal });
+        }
+        // Resumed once; a later shutdown writes a fresh checkpoint
+        let cleared = game::ActiveModel { id: Set(game_id), state: Set(serde_json::json!({})), ..Default::default() };
+        if let Err(e) = cleared.update(&self.db).await {
+            warn!("Failed to clear checkpoint of game {}: {}", game_id, e);
+        }
+
+        info!("Game {} ({}) resumed at round {}", game_id, code, round_number);
+        self.play_departed_turns(game_id).await;
     }
 
     /// Get game statistics
     pub async fn get_stats(&self) -> GameStats {
-        let games = self.games.read().await;
-        let active_games = games.len();
+        let active_games = self.games.len();
 
         GameStats {
             active_games,
@@ -469,19 +1871,18 @@ impl GameManager {
     }
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct GameStats {
-    pub active_games: usize,
+/// Periodically remove games whose players are all gone
+pub fn spawn_game_reaper(game_manager: Arc<GameManager>, interval: std::time::Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            game_manager.reap_dead_games().await;
+        }
+    })
 }
 
-// Make Game cloneable for the helper method
-impl Clone for Game {
-    fn clone(&self) -> Self {
-        Self {
-            id: self.id,
-            state: GameState::new(self.players.clone()), // Create new state with same players
-            players: self.players.clone(),
-            created_at: self.created_at,
-        }
-    }
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
+pub struct GameStats {
+    pub active_games: usize,
 }
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+use tokio::sync::{mpsc, oneshot};
+use tracing::debug;
+use crate::error::GameError;
+use crate::game::{Game, GameId};
+use crate::short_code::ShortCode;
+
+/// A step run against a game's state inside its task
+type Command = Box<dyn FnOnce(&mut Game) + Send>;
+
+/// Handle to the task that owns one game.
+///
+/// Commands run one at a time in the order they were sent, so a game's actions never race
+/// each other while other games carry on in their own tasks. The task ends once the game is
+/// removed and the last handle is dropped.
+#[derive(Clone)]
+pub struct GameHandle {
+    commands: mpsc::UnboundedSender<Command>,
+    /// Fixed for the game's life, so lookups by code and age don't wait on the task
+    pub code: ShortCode,
+    pub created_at: Instant,
+}
+
+impl GameHandle {
+    pub fn spawn(mut game: Game) -> Self {
+        let (commands, mut rx) = mpsc::unbounded_channel::<Command>();
+        let handle = Self { commands, code: game.code.clone(), created_at: game.created_at };
+        let game_id = game.id;
+        tokio::spawn(async move {
+            while let Some(command) = rx.recv().await {
+                command(&mut game);
+            }
+            debug!("Task of game {} stopped", game_id);
+        });
+        handle
+    }
+
+    /// Run `f` on the game and return what it returns
+    pub async fn call<R: Send + 'static>(&self, f: impl FnOnce(&mut Game) -> R + Send + 'static) -> Result<R, GameError> {
+        let (reply, result) = oneshot::channel();
+        let command: Command = Box::new(move |game| {
+            let _ = reply.send(f(game));
+        });
+        self.commands.send(command).map_err(|_| GameError::GameNotFound)?;
+        result.await.map_err(|_| GameError::GameNotFound)
+    }
+}
+
+/// The running games by id. The lock only guards the map; it is never held while a game
+/// is being changed.
+#[derive(Clone, Default)]
+pub struct GameRegistry {
+    games: Arc<RwLock<HashMap<GameId, GameHandle>>>,
+}
+
+impl GameRegistry {
+    pub fn get(&self, game_id: GameId) -> Result<GameHandle, GameError> {
+        self.read().get(&game_id).cloned().ok_or(GameError::GameNotFound)
+    }
+
+    /// Start the task of the game `make_game` builds around a fresh code, passing on what else
+    /// it returns. The code is chosen with the map locked so it stays unique.
+    pub fn insert_with_code<T>(&self, make_game: impl FnOnce(ShortCode) -> (Game, T)) -> T {
+        let mut games = self.games.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let code = ShortCode::generate_unique(|code| games.values().any(|g| g.code == *code));
+        let (game, extra) = make_game(code);
+        games.insert(game.id, GameHandle::spawn(game));
+        extra
+    }
+
+    pub fn insert(&self, game: Game) -> GameHandle {
+        let game_id = game.id;
+        let handle = GameHandle::spawn(game);
+        self.games.write().unwrap_or_else(|poisoned| poisoned.into_inner()).insert(game_id, handle.clone());
+        handle
+    }
+
+    pub fn remove(&self, game_id: GameId) -> bool {
+        self.games.write().unwrap_or_else(|poisoned| poisoned.into_inner()).remove(&game_id).is_some()
+    }
+
+    pub fn contains(&self, game_id: GameId) -> bool {
+        self.read().contains_key(&game_id)
+    }
+
+    pub fn find_by_code(&self, code: &ShortCode) -> Option<GameId> {
+        self.read().iter().find(|(_, g)| g.code == *code).map(|(id, _)| *id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    /// Every game, for steps that visit them all; the map is not locked while they run
+    pub fn all(&self) -> Vec<(GameId, GameHandle)> {
+        self.read().iter().map(|(id, g)| (*id, g.clone())).collect()
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<GameId, GameHandle>> {
+        self.games.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
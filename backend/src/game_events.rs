@@ -0,0 +1,189 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use crate::connection::{ConnectionManager, PlayerId};
+use crate::game::GameId;
+use crate::protocol::ServerMessage;
+use crate::room::{RoomId, RoomRegistry};
+use crate::room_feed::RoomMessage;
+
+/// Messages kept per player for ResyncFrom; older gaps need a full GameState
+pub const REPLAY_LIMIT: usize = 256;
+
+/// A game message as sent on the wire: the usual `type`/`payload` plus `seq`
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS), ts(export))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequencedMessage {
+    /// Position in this player's message stream for the game, starting at 1
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub seq: u64,
+    #[serde(flatten)]
+    pub message: ServerMessage,
+}
+
+#[derive(Debug, Default)]
+struct PlayerStream {
+    last_seq: u64,
+    recent: VecDeque<SequencedMessage>,
+}
+
+/// Sequence counters and replay buffers for every running game.
+///
+/// Each player has their own stream per game, because views differ between players
+/// (own hand, hidden bids, YourTurn), so a gap in `seq` always means a missed message.
+#[derive(Debug, Default)]
+pub struct GameEvents {
+    streams: Mutex<HashMap<(GameId, PlayerId), PlayerStream>>,
+}
+
+impl GameEvents {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assign the next sequence number for this player and remember the message for replay
+    pub fn record(&self, game_id: GameId, player_id: &PlayerId, message: ServerMessage) -> SequencedMessage {
+        let mut streams = self.streams.lock().unwrap();
+        let seq = Self::push(&mut streams, game_id, player_id, &message);
+        SequencedMessage { seq, message }
+    }
+
+    /// Record one message for several players, returning each one's sequence number
+    pub fn record_all(&self, game_id: GameId, players: &[PlayerId], message: &ServerMessage) -> HashMap<PlayerId, u64> {
+        let mut streams = self.streams.lock().unwrap();
+        players.iter()
+            .map(|player_id| (player_id.clone(), Self::push(&mut streams, game_id, player_id, message)))
+            .collect()
+    }
+
+    fn push(
+        streams: &mut HashMap<(GameId, PlayerId), PlayerStream>,
+        game_id: GameId,
+        player_id: &PlayerId,
+        message: &ServerMessage,
+    ) -> u64 {
+        let stream = streams.entry((game_id, player_id.clone())).or_default();
+        stream.last_seq += 1;
+        stream.recent.push_back(SequencedMessage { seq: stream.last_seq, message: message.clone() });
+        while stream.recent.len() > REPLAY_LIMIT {
+            stream.recent.pop_front();
+        }
+        stream.last_seq
+    }
+
+    /// Messages after `seq`, or None if some of them are no longer buffered
+    pub fn replay(&self, game_id: GameId, player_id: &PlayerId, seq: u64) -> Option<Vec<SequencedMessage>> {
+        let streams = self.streams.lock().unwrap();
+        let Some(stream) = streams.get(&(game_id, player_id.clone())) else {
+            return (seq == 0).then(Vec::new);
+        };
+        if seq > stream.last_seq {
+            return None;
+        }
+
+        let oldest = stream.recent.front().map_or(stream.last_seq + 1, |m| m.seq);
+        if seq + 1 < oldest {
+            return None;
+        }
+        Some(stream.recent.iter().filter(|m| m.seq > seq).cloned().collect())
+    }
+
+    pub fn last_seq(&self, game_id: GameId, player_id: &PlayerId) -> u64 {
+        let streams = self.streams.lock().unwrap();
+        streams.get(&(game_id, player_id.clone())).map_or(0, |s| s.last_seq)
+    }
+
+    /// Drop all streams of a finished game
+    pub fn remove_game(&self, game_id: GameId) {
+        self.streams.lock().unwrap().retain(|(gid, _), _| *gid != game_id);
+    }
+}
+
+/// Sends game messages with sequence numbers attached.
+///
+/// Everything goes out on the game's room channel, including messages for one player, so
+/// each player receives their stream in order.
+#[derive(Clone)]
+pub struct GameOutbox {
+    rooms: Arc<RoomRegistry>,
+    events: Arc<GameEvents>,
+}
+
+impl GameOutbox {
+    pub fn new(connection_manager: Arc<ConnectionManager>, events: Arc<GameEvents>) -> Self {
+        Self { rooms: connection_manager.rooms(), events }
+    }
+
+    pub async fn send(&self, game_id: GameId, player_id: &PlayerId, message: ServerMessage) {
+        self.broadcast(game_id, std::slice::from_ref(player_id), message).await;
+    }
+
+    /// Resend the buffered messages after `seq`; false if the gap is too old to replay
+    pub async fn replay(&self, game_id: GameId, player_id: &PlayerId, seq: u64) -> bool {
+        let Some(missed) = self.events.replay(game_id, player_id, seq) else {
+            return false;
+        };
+        for sequenced in missed {
+            let seqs = HashMap::from([(player_id.clone(), sequenced.seq)]);
+            self.publish(game_id, sequenced.message, seqs);
+        }
+        true
+    }
+
+    /// Record the message in every player's stream and publish it once
+    pub async fn broadcast(&self, game_id: GameId, players: &[PlayerId], message: ServerMessage) {
+        let seqs = self.events.record_all(game_id, players, &message);
+        self.publish(game_id, message, seqs);
+    }
+
+    fn publish(&self, game_id: GameId, message: ServerMessage, seqs: HashMap<PlayerId, u64>) {
+        let message = RoomMessage::Sequenced { message: Arc::new(message), seqs: Arc::new(seqs) };
+        self.rooms.publish(RoomId::Game(game_id), message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_sequence_is_per_player() {
+        let events = GameEvents::new();
+        let game_id = Uuid::new_v4();
+        let (a, b) = ("a".to_string(), "b".to_string());
+
+        assert_eq!(events.record(game_id, &a, ServerMessage::Pong).seq, 1);
+        assert_eq!(events.record(game_id, &a, ServerMessage::Pong).seq, 2);
+        assert_eq!(events.record(game_id, &b, ServerMessage::Pong).seq, 1);
+        assert_eq!(events.last_seq(Uuid::new_v4(), &a), 0);
+    }
+
+    #[test]
+    fn test_replay_returns_delta_or_none() {
+        let events = GameEvents::new();
+        let game_id = Uuid::new_v4();
+        let a = "a".to_string();
+        for _ in 0..(REPLAY_LIMIT + 10) {
+            events.record(game_id, &a, ServerMessage::Pong);
+        }
+        let last = events.last_seq(game_id, &a);
+
+        let delta = events.replay(game_id, &a, last - 3).unwrap();
+        assert_eq!(delta.iter().map(|m| m.seq).collect::<Vec<_>>(), vec![last - 2, last - 1, last]);
+        assert!(events.replay(game_id, &a, last).unwrap().is_empty());
+        // Trimmed from the buffer, or ahead of the server
+        assert!(events.replay(game_id, &a, 1).is_none());
+        assert!(events.replay(game_id, &a, last + 1).is_none());
+    }
+
+    #[test]
+    fn test_wire_format_keeps_type_and_payload() {
+        let message = ServerMessage::TrickComplete { winner: "a".to_string(), cards: vec![], tricks: vec![], round_complete: false };
+        let msg = SequencedMessage { seq: 7, message };
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["seq"], 7);
+        assert_eq!(json["type"], "TrickComplete");
+        assert_eq!(json["payload"]["winner"], "a");
+    }
+}
@@ -1,5 +0,0 @@
-pub mod card;
-pub mod deck;
-pub mod trick;
-pub mod bidding;
-pub mod scoring;
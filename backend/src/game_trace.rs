@@ -0,0 +1,75 @@
+use std::collections::HashSet;
+use std::sync::RwLock;
+use crate::game::GameId;
+
+/// Tracing target used for per-game trace output, so it can be grepped or routed separately
+pub const TRACE_TARGET: &str = "game_trace";
+
+/// Set of game ids that get verbose logging regardless of the global log level.
+///
+/// Checked on every action, so it uses a std lock: lookups are short and never
+/// held across an await.
+#[derive(Debug, Default)]
+pub struct GameTraceFilter {
+    games: RwLock<HashSet<GameId>>,
+}
+
+impl GameTraceFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start tracing a game. Returns false if it was already traced.
+    pub fn enable(&self, game_id: GameId) -> bool {
+        self.games.write().unwrap().insert(game_id)
+    }
+
+    /// Stop tracing a game. Returns false if it was not traced.
+    pub fn disable(&self, game_id: GameId) -> bool {
+        self.games.write().unwrap().remove(&game_id)
+    }
+
+    pub fn is_traced(&self, game_id: GameId) -> bool {
+        self.games.read().unwrap().contains(&game_id)
+    }
+
+    /// Currently traced games
+    pub fn traced_games(&self) -> Vec<GameId> {
+        self.games.read().unwrap().iter().copied().collect()
+    }
+}
+
+/// Log at info level under [`TRACE_TARGET`] when the game is traced.
+///
+/// Info level keeps the output visible under the default `RUST_LOG=info`
+/// without lowering the level for the rest of the server.
+#[macro_export]
+macro_rules! game_trace {
+    ($filter:expr, $game_id:expr, $($arg:tt)+) => {
+        if $filter.is_traced($game_id) {
+            tracing::info!(target: $crate::game_trace::TRACE_TARGET, game_id = %$game_id, $($arg)+);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_enable_and_disable() {
+        let filter = GameTraceFilter::new();
+        let game_id = Uuid::new_v4();
+
+        assert!(!filter.is_traced(game_id));
+        assert!(filter.enable(game_id));
+        assert!(!filter.enable(game_id));
+        assert!(filter.is_traced(game_id));
+        assert_eq!(filter.traced_games(), vec![game_id]);
+
+        assert!(filter.disable(game_id));
+        assert!(!filter.disable(game_id));
+        assert!(!filter.is_traced(game_id));
+    }
+}
@@ -0,0 +1,563 @@
+use axum::{
+    extract::{ConnectInfo, Path, Query, Request, State},
+    http::{HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+    Json,
+};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use crate::server::AppState;
+use crate::audit::{self, AuditKind, AuditQuery};
+use crate::auth::Role;
+use crate::retention;
+use crate::bug_report;
+use crate::tournament;
+use crate::features::{Feature, FeatureStatus};
+use crate::short_code::{self, IdOrCode};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use tracing::info;
+
+/// Audit actor recorded for requests made with the shared admin token
+const ADMIN_TOKEN_ACTOR: &str = "admin-token";
+
+/// Check the caller holds `required`, by bearer token role or by the `X-Admin-Token` header,
+/// and return who they are. The shared admin token counts as the admin role and is ignored
+/// when not configured.
+async fn authorize(state: &AppState, headers: &HeaderMap, required: Role) -> Result<String, (StatusCode, String)> {
+    let provided = headers.get("x-admin-token").and_then(|v| v.to_str().ok());
+    if let Some(provided) = provided {
+        return if state.admin_token.as_deref() == Some(provided) {
+            Ok(ADMIN_TOKEN_ACTOR.to_string())
+        } else {
+            Err((StatusCode::UNAUTHORIZED, "Invalid admin token".to_string()))
+        };
+    }
+
+    let claims = crate::handlers::auth::authorize(&state.jwt_keys, headers, required)?;
+    let account = crate::password_reset::session_account(&state.db, &claims)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or_else(|| (StatusCode::UNAUTHORIZED, "Session expired".to_string()))?;
+    // The token's role may predate a demotion; the stored role decides
+    if !Role::parse(&account.role).unwrap_or_default().allows(required) {
+        return Err((StatusCode::FORBIDDEN, format!("Requires {} role", required.as_str())));
+    }
+    Ok(claims.sub)
+}
+
+/// Run an authorized request, recording every one that may change something in the audit log
+async fn run_audited(state: &AppState, actor: String, request: Request, next: Next) -> Response {
+    if request.method() == Method::GET {
+        return next.run(request).await;
+    }
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let peer = request.extensions().get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| addr.ip());
+    let ip = state.trusted_proxies.request_client_ip(request.extensions(), request.headers());
+    let response = next.run(request).await;
+    let mut detail = serde_json::json!({ "method": method, "path": path, "status": response.status().as_u16() });
+    audit::note_peer(&mut detail, ip, peer);
+    state.audit.record(AuditKind::Admin, Some(&actor), ip, detail).await;
+    response
+}
+
+/// Route layer for endpoints reserved to admins
+pub async fn require_admin(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, String)> {
+    let actor = authorize(&state, request.headers(), Role::Admin).await?;
+    Ok(run_audited(&state, actor, request, next).await)
+}
+
+/// Route layer for endpoints open to moderators and admins
+pub async fn require_moderator(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, (StatusCode, String)> {
+    let actor = authorize(&state, request.headers(), Role::Moderator).await?;
+    Ok(run_audited(&state, actor, request, next).await)
+}
+
+/// Map a "row matched" flag to 204 or 404
+fn found(matched: bool) -> Result<StatusCode, (StatusCode, String)> {
+    if matched {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err((StatusCode::NOT_FOUND, "Not found".to_string()))
+    }
+}
+
+/// Resolve a stored game's id or short code
+async fn stored_game_id(state: &AppState, reference: &IdOrCode) -> Result<Uuid, (StatusCode, String)> {
+    short_code::find_game_id(&state.db, reference)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Not found".to_string()))
+}
+
+/// Resolve a running game's id or short code
+async fn live_game_id(state: &AppState, reference: &IdOrCode) -> Result<Uuid, (StatusCode, String)> {
+    state.game_manager.find_game(reference).await
+        .ok_or((StatusCode::NOT_FOUND, "Not found".to_string()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/lobbies/{id}/restore",
+    tag = "admin",
+    params(("id" = String, Path, description = "Lobby id or short code")),
+    responses(
+        (status = 204, description = "Lobby restored, and reopened if any of its members were free"),
+        (status = 404, description = "No such deleted lobby"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Role too low"),
+    ),
+    security(("bearer" = []), ("admin_token" = [])),
+)]
+pub async fn restore_lobby(
+    State(state): State<Arc<AppState>>,
+    Path(lobby_ref): Path<IdOrCode>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let lobby_id = short_code::find_lobby_id(&state.db, &lobby_ref)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Not found".to_string()))?;
+    // The lobby's own queued writes, such as its deletion, land first
+    state.game_manager.db_writes().flush().await;
+    let matched = retention::restore_lobby(&state.db, lobby_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if matched {
+        state.message_router.lobby_manager().reload_lobby(lobby_id).await;
+    }
+    found(matched)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/games/{id}",
+    tag = "admin",
+    params(("id" = String, Path, description = "Game id or short code")),
+    responses(
+        (status = 204, description = "Game soft-deleted; purged after the retention period"),
+        (status = 404, description = "No such game"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Role too low"),
+    ),
+    security(("bearer" = []), ("admin_token" = [])),
+)]
+pub async fn delete_game(
+    State(state): State<Arc<AppState>>,
+    Path(game_ref): Path<IdOrCode>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let game_id = stored_game_id(&state, &game_ref).await?;
+    let matched = retention::soft_delete_game(&state.db, game_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    found(matched)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/games/{id}/restore",
+    tag = "admin",
+    params(("id" = String, Path, description = "Game id or short code")),
+    responses(
+        (status = 204, description = "Game restored"),
+        (status = 404, description = "No such deleted game"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Role too low"),
+    ),
+    security(("bearer" = []), ("admin_token" = [])),
+)]
+pub async fn restore_game(
+    State(state): State<Arc<AppState>>,
+    Path(game_ref): Path<IdOrCode>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let game_id = stored_game_id(&state, &game_ref).await?;
+    let matched = retention::restore_game(&state.db, game_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    found(matched)
+}
+
+/// Turn on verbose logging for a single live game
+#[utoipa::path(
+    put,
+    path = "/api/admin/games/{id}/trace",
+    tag = "moderation",
+    params(("id" = String, Path, description = "Game id or short code")),
+    responses(
+        (status = 204, description = "Game is traced"),
+        (status = 404, description = "No such running game"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Role too low"),
+    ),
+    security(("bearer" = []), ("admin_token" = [])),
+)]
+pub async fn enable_game_trace(
+    State(state): State<Arc<AppState>>,
+    Path(game_ref): Path<IdOrCode>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let game_id = live_game_id(&state, &game_ref).await?;
+    if state.game_manager.trace_filter().enable(game_id) {
+        info!("Enabled trace logging for game {}", game_id);
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/games/{id}/trace",
+    tag = "moderation",
+    params(("id" = String, Path, description = "Game id or short code")),
+    responses(
+        (status = 204, description = "Game is no longer traced"),
+        (status = 404, description = "No such traced game"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Role too low"),
+    ),
+    security(("bearer" = []), ("admin_token" = [])),
+)]
+pub async fn disable_game_trace(
+    State(state): State<Arc<AppState>>,
+    Path(game_ref): Path<IdOrCode>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let game_id = live_game_id(&state, &game_ref).await?;
+    let matched = state.game_manager.trace_filter().disable(game_id);
+    if matched {
+        info!("Disabled trace logging for game {}", game_id);
+    }
+    found(matched)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/traces",
+    tag = "moderation",
+    responses(
+        (status = 200, description = "Games being traced", body = Vec<Uuid>),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Role too low"),
+    ),
+    security(("bearer" = []), ("admin_token" = [])),
+)]
+pub async fn list_game_traces(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<Uuid>>, (StatusCode, String)> {
+    Ok(Json(state.game_manager.trace_filter().traced_games()))
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BugReportQuery {
+    /// At most 100
+    #[serde(default = "default_report_limit")]
+    pub limit: u64,
+}
+
+fn default_report_limit() -> u64 {
+    20
+}
+
+/// Summary row for the bug report list; fetch a single report for its bundle
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct BugReportSummary {
+    pub id: Uuid,
+    pub game_id: Uuid,
+    pub reason: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/bug-reports",
+    tag = "moderation",
+    params(BugReportQuery),
+    responses(
+        (status = 200, description = "Newest reports first", body = Vec<BugReportSummary>),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Role too low"),
+    ),
+    security(("bearer" = []), ("admin_token" = [])),
+)]
+pub async fn list_bug_reports(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<BugReportQuery>,
+) -> Result<Json<Vec<BugReportSummary>>, (StatusCode, String)> {
+    let reports = bug_report::recent_reports(&state.db, query.limit.min(100))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(reports.into_iter().map(|r| BugReportSummary {
+        id: r.id,
+        game_id: r.game_id,
+        reason: r.reason,
+        created_at: r.created_at,
+    }).collect()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/bug-reports/{id}",
+    tag = "moderation",
+    params(("id" = Uuid, Path, description = "Bug report id")),
+    responses(
+        (status = 200, description = "The report's bundle: game journal, views and server details", body = Object),
+        (status = 404, description = "No such report"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Role too low"),
+    ),
+    security(("bearer" = []), ("admin_token" = [])),
+)]
+pub async fn get_bug_report(
+    State(state): State<Arc<AppState>>,
+    Path(report_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let report = bug_report::get_report(&state.db, report_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Not found".to_string()))?;
+    Ok(Json(report.bundle))
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct CreateTournamentRequest {
+    pub name: String,
+    pub webhook_url: Option<String>,
+}
+
+/// Returned once on creation; the secret is needed to verify webhook signatures
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct TournamentCreated {
+    pub id: Uuid,
+    pub name: String,
+    pub webhook_url: Option<String>,
+    pub webhook_secret: String,
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct TournamentSummary {
+    pub id: Uuid,
+    pub name: String,
+    pub webhook_url: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/tournaments",
+    tag = "admin",
+    request_body = CreateTournamentRequest,
+    responses(
+        (status = 200, description = "Tournament created", body = TournamentCreated),
+        (status = 400, description = "Empty name or a webhook URL that is not http(s)"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Role too low"),
+    ),
+    security(("bearer" = []), ("admin_token" = [])),
+)]
+pub async fn create_tournament(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<CreateTournamentRequest>,
+) -> Result<Json<TournamentCreated>, (StatusCode, String)> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "Name is required".to_string()));
+    }
+    if let Some(url) = &payload.webhook_url {
+        if !tournament::is_valid_webhook_url(url) {
+            return Err((StatusCode::BAD_REQUEST, "Webhook URL must be http(s)".to_string()));
+        }
+    }
+
+    let created = tournament::create_tournament(&state.db, name, payload.webhook_url)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    info!("Admin created tournament {} ({})", created.id, created.name);
+    Ok(Json(TournamentCreated {
+        id: created.id,
+        name: created.name,
+        webhook_url: created.webhook_url,
+        webhook_secret: created.webhook_secret,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/tournaments",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Every tournament", body = Vec<TournamentSummary>),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Role too low"),
+    ),
+    security(("bearer" = []), ("admin_token" = [])),
+)]
+pub async fn list_tournaments(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<TournamentSummary>>, (StatusCode, String)> {
+    let tournaments = tournament::list_tournaments(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(tournaments.into_iter().map(|t| TournamentSummary {
+        id: t.id,
+        name: t.name,
+        webhook_url: t.webhook_url,
+        created_at: t.created_at,
+    }).collect()))
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct SetRoleRequest {
+    pub role: Role,
+}
+
+/// Change a user's role. Admin endpoints check the stored role, so it applies at once to
+/// tokens already issued; connected sessions are updated too
+#[utoipa::path(
+    put,
+    path = "/api/admin/users/{id}/role",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "User id")),
+    request_body = SetRoleRequest,
+    responses(
+        (status = 204, description = "Role changed"),
+        (status = 404, description = "No such user"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Role too low"),
+    ),
+    security(("bearer" = []), ("admin_token" = [])),
+)]
+pub async fn set_user_role(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<SetRoleRequest>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    use sea_orm::{sea_query::Expr, ColumnTrait, EntityTrait, QueryFilter};
+    use crate::entities::user;
+
+    let result = user::Entity::update_many()
+        .col_expr(user::Column::Role, Expr::value(payload.role.as_str()))
+        .filter(user::Column::Id.eq(user_id))
+        .exec(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if result.rows_affected > 0 {
+        info!("Admin set role of user {} to {}", user_id, payload.role.as_str());
+        state.connection_manager.set_role(&user_id.to_string(), payload.role).await;
+    }
+    found(result.rows_affected > 0)
+}
+
+/// Every runtime feature flag and whether it is on
+#[utoipa::path(
+    get,
+    path = "/api/admin/features",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Every feature", body = Vec<FeatureStatus>),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Role too low"),
+    ),
+    security(("bearer" = []), ("admin_token" = [])),
+)]
+pub async fn list_features(State(state): State<Arc<AppState>>) -> Json<Vec<FeatureStatus>> {
+    Json(state.message_router.features().statuses())
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct SetFeatureRequest {
+    pub enabled: bool,
+}
+
+/// Switch a feature on or off for this instance until the next restart
+#[utoipa::path(
+    put,
+    path = "/api/admin/features/{feature}",
+    tag = "admin",
+    params(("feature" = Feature, Path)),
+    request_body = SetFeatureRequest,
+    responses(
+        (status = 200, description = "Every feature after the change", body = Vec<FeatureStatus>),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Role too low"),
+    ),
+    security(("bearer" = []), ("admin_token" = [])),
+)]
+pub async fn set_feature(
+    State(state): State<Arc<AppState>>,
+    Path(feature): Path<Feature>,
+    Json(payload): Json<SetFeatureRequest>,
+) -> Json<Vec<FeatureStatus>> {
+    let features = state.message_router.features();
+    features.set(feature, payload.enabled);
+    if feature == Feature::Ranked {
+        // Cached leaderboards would otherwise outlive the switch
+        state.public_api.cache().invalidate("/api/leaderboard");
+    }
+    info!("Admin set feature {} to {}", feature.as_str(), payload.enabled);
+    Json(features.statuses())
+}
+
+/// Issue a one-hour password reset token for a user, to be handed to them out of band
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{id}/password-reset",
+    tag = "admin",
+    params(("id" = Uuid, Path, description = "User id")),
+    responses(
+        (status = 200, description = "The token; it cannot be shown again", body = crate::password_reset::IssuedResetToken),
+        (status = 404, description = "No such user"),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Role too low"),
+    ),
+    security(("bearer" = []), ("admin_token" = [])),
+)]
+pub async fn issue_password_reset(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<crate::password_reset::IssuedResetToken>, (StatusCode, String)> {
+    use sea_orm::EntityTrait;
+    use crate::entities::user;
+
+    let exists = user::Entity::find_by_id(user_id)
+        .one(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .is_some();
+    found(exists)?;
+
+    let issued = crate::password_reset::issue_reset_token(&state.db, user_id, chrono::Utc::now())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    info!("Admin issued a password reset token for user {}", user_id);
+    Ok(Json(issued))
+}
+
+/// Audit entries matching the query, newest first
+#[utoipa::path(
+    get,
+    path = "/api/admin/audit",
+    tag = "admin",
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "Matching entries, newest first", body = Vec<crate::entities::audit_log::Model>),
+        (status = 401, description = "Missing or invalid token"),
+        (status = 403, description = "Role too low"),
+    ),
+    security(("bearer" = []), ("admin_token" = [])),
+)]
+pub async fn search_audit_log(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<Vec<crate::entities::audit_log::Model>>, (StatusCode, String)> {
+    let entries = state.audit.search(&query)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(entries))
+}
@@ -1,16 +1,32 @@
 use axum::{
     Json,
-    extract::State,
-    http::StatusCode,
+    extract::{ConnectInfo, State},
+    http::{header, HeaderMap, StatusCode},
 };
+use std::net::SocketAddr;
 use std::sync::Arc;
 use sea_orm::{EntityTrait, QueryFilter, ColumnTrait, ActiveModelTrait, Set};
-use crate::auth::{self, LoginRequest, RegisterRequest, AuthResponse};
+use crate::audit::{self, AuditKind};
+use crate::auth::{self, LoginRequest, RegisterRequest, AuthResponse, JwtKeys, Role};
 use crate::server::AppState;
 use crate::entities::user;
+use crate::user_cache::PlayerProfile;
+use crate::password_reset::{self, ResetError};
+use serde::Deserialize;
+use tracing::info;
 use uuid::Uuid;
 use chrono::Utc;
 
+#[utoipa::path(
+    post,
+    path = "/api/register",
+    tag = "auth",
+    request_body = RegisterRequest,
+    responses(
+        (status = 200, description = "Account created and signed in", body = AuthResponse),
+        (status = 409, description = "Username already exists"),
+    ),
+)]
 pub async fn register(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<RegisterRequest>,
@@ -37,28 +53,58 @@ pub async fn register(
         id: Set(user_id),
         username: Set(payload.username.clone()),
         password_hash: Set(password_hash),
-        created_at: Set(Utc::now().into()),
+        created_at: Set(Utc::now()),
+        is_bot: Set(payload.is_bot),
+        time_zone: Set("UTC".to_string()),
+        locale: Set("en-US".to_string()),
+        weekly_digest: Set(true),
+        suit_encoding: Set("standard".to_string()),
+        role: Set(Role::Player.as_str().to_string()),
+        sessions_valid_after: Set(None),
+        display_name: Set(None),
+        avatar: Set(None),
+        preferred_settings: Set(None),
     };
     
     new_user.insert(&state.db)
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+    state.connection_manager.user_cache()
+        .insert(user_id.to_string(), PlayerProfile::named(payload.username.clone()))
+        .await;
+
     // 4. Generate Token
-    let token = auth::create_jwt(&user_id.to_string(), &payload.username)
+    let token = state.jwt_keys.create_jwt(&user_id.to_string(), &payload.username, payload.is_bot, Role::Player)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
 
     Ok(Json(AuthResponse {
         token,
         username: payload.username,
         user_id: user_id.to_string(),
+        role: Role::Player,
     }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Signed in", body = AuthResponse),
+        (status = 401, description = "Invalid credentials"),
+    ),
+)]
 pub async fn login(
     State(state): State<Arc<AppState>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
     Json(payload): Json<LoginRequest>,
 ) -> Result<Json<AuthResponse>, (StatusCode, String)> {
+    let peer = connect_info.map(|ConnectInfo(addr)| addr.ip());
+    let ip = peer.map(|peer| state.trusted_proxies.client_ip(peer, &headers));
+
     // 1. Find user
     let user = user::Entity::find()
         .filter(user::Column::Username.eq(&payload.username))
@@ -66,23 +112,143 @@ pub async fn login(
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
-    let user = user.ok_or((StatusCode::UNAUTHORIZED, "Invalid credentials".to_string()))?;
-
     // 2. Verify password
-    let valid = auth::verify_password(&payload.password, &user.password_hash)
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    let valid = match &user {
+        Some(user) => auth::verify_password(&payload.password, &user.password_hash)
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?,
+        None => false,
+    };
 
-    if !valid {
-        return Err((StatusCode::UNAUTHORIZED, "Invalid credentials".to_string()));
-    }
+    // Failed attempts are recorded too, under the user they named if it exists
+    let actor = user.as_ref().map(|user| user.id.to_string());
+    let mut detail = serde_json::json!({ "username": payload.username, "success": valid });
+    audit::note_peer(&mut detail, ip, peer);
+    state.audit.record(AuditKind::Login, actor.as_deref(), ip, detail).await;
+
+    let user = user
+        .filter(|_| valid)
+        .ok_or((StatusCode::UNAUTHORIZED, "Invalid credentials".to_string()))?;
+
+    state.connection_manager.user_cache()
+        .insert(user.id.to_string(), PlayerProfile::from(&user))
+        .await;
 
     // 3. Generate Token
-    let token = auth::create_jwt(&user.id.to_string(), &user.username)
+    let role = Role::parse(&user.role).unwrap_or_default();
+    let token = state.jwt_keys.create_jwt(&user.id.to_string(), &user.username, user.is_bot, role)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
 
     Ok(Json(AuthResponse {
         token,
         username: user.username,
         user_id: user.id.to_string(),
+        role,
     }))
 }
+
+#[derive(Debug, Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
+fn check_new_password(password: &str) -> Result<(), (StatusCode, String)> {
+    if password.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "New password must not be empty".to_string()));
+    }
+    Ok(())
+}
+
+/// Change the signed-in user's password; requires the current one
+pub async fn change_password(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<ChangePasswordRequest>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let account = current_account(&state, &headers).await?;
+    check_new_password(&payload.new_password)?;
+
+    let valid = auth::verify_password(&payload.current_password, &account.password_hash)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    if !valid {
+        return Err((StatusCode::UNAUTHORIZED, "Invalid credentials".to_string()));
+    }
+
+    let password_hash = auth::hash_password(&payload.new_password)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    let mut active: user::ActiveModel = account.into();
+    active.password_hash = Set(password_hash);
+    active.update(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Set a new password with a reset token, signing the user out everywhere
+pub async fn reset_password(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<ResetPasswordRequest>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    check_new_password(&payload.new_password)?;
+    let password_hash = auth::hash_password(&payload.new_password)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    let user_id = password_reset::redeem_reset_token(&state.db, &payload.token, password_hash, Utc::now())
+        .await
+        .map_err(|e| match e {
+            ResetError::InvalidToken => (StatusCode::BAD_REQUEST, e.to_string()),
+            ResetError::Database(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        })?;
+
+    info!("Password reset for user {}; ending their sessions", user_id);
+    state.connection_manager
+        .close_connection(&user_id.to_string(), "Session ended: password was reset")
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Authenticate an HTTP request from its `Authorization: Bearer <jwt>` header
+pub fn authenticate(keys: &JwtKeys, headers: &HeaderMap) -> Result<auth::Claims, (StatusCode, String)> {
+    let token = headers.get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or((StatusCode::UNAUTHORIZED, "Missing bearer token".to_string()))?;
+
+    keys.verify_jwt(token)
+        .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid token".to_string()))
+}
+
+/// Authenticate a request and load the user's row, rejecting sessions ended by a password reset
+pub async fn current_account(state: &AppState, headers: &HeaderMap) -> Result<user::Model, (StatusCode, String)> {
+    let claims = authenticate(&state.jwt_keys, headers)?;
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid token".to_string()))?;
+
+    let account = user::Entity::find_by_id(user_id)
+        .one(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+
+    if !password_reset::session_is_current(&account, &claims) {
+        return Err((StatusCode::UNAUTHORIZED, "Session expired".to_string()));
+    }
+    Ok(account)
+}
+
+/// Authenticate a request and check that its token carries at least `required`
+pub fn authorize(keys: &JwtKeys, headers: &HeaderMap, required: Role) -> Result<auth::Claims, (StatusCode, String)> {
+    let claims = authenticate(keys, headers)?;
+    if !claims.role.allows(required) {
+        return Err((StatusCode::FORBIDDEN, format!("Requires {} role", required.as_str())));
+    }
+    Ok(claims)
+}
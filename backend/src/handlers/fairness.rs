@@ -0,0 +1,18 @@
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+};
+use std::sync::Arc;
+use crate::deal_review::{self, FairnessStats};
+use crate::server::AppState;
+
+/// Deal distribution statistics over the most recent rounds of completed games
+pub async fn get_fairness(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<FairnessStats>, (StatusCode, String)> {
+    let stats = deal_review::compute_fairness(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(stats))
+}
@@ -0,0 +1,122 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use std::sync::Arc;
+use sea_orm::EntityTrait;
+use crate::handlers::auth::current_account;
+use crate::server::AppState;
+use crate::entities::user;
+use crate::friends::{self, FriendEntry};
+use crate::presence::FriendPresence;
+use crate::blocks::{self, BlockedUser};
+use uuid::Uuid;
+
+/// Everyone you added and everyone who added you
+pub async fn list_friends(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<FriendEntry>>, (StatusCode, String)> {
+    let account = current_account(&state, &headers).await?;
+    let entries = friends::list_friends(&state.db, account.id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(entries))
+}
+
+pub async fn add_friend(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(friend_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let account = current_account(&state, &headers).await?;
+    if friend_id == account.id {
+        return Err((StatusCode::BAD_REQUEST, "You cannot add yourself".to_string()));
+    }
+    user::Entity::find_by_id(friend_id)
+        .one(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+
+    friends::add_friend(&state.db, account.id, friend_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn remove_friend(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(friend_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let account = current_account(&state, &headers).await?;
+    let removed = friends::remove_friend(&state.db, account.id, friend_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !removed {
+        return Err((StatusCode::NOT_FOUND, "Not on your friend list".to_string()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Current presence of each mutual friend; PresenceUpdate messages keep it current afterwards
+pub async fn get_presence(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<FriendPresence>>, (StatusCode, String)> {
+    let account = current_account(&state, &headers).await?;
+    let presences = state.presence.friends_presence(account.id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(presences))
+}
+
+pub async fn list_blocked(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<BlockedUser>>, (StatusCode, String)> {
+    let account = current_account(&state, &headers).await?;
+    let blocked = blocks::list_blocked(&state.db, account.id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(blocked))
+}
+
+/// Block a user, which also ends any friendship with them
+pub async fn block_user(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(blocked_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let account = current_account(&state, &headers).await?;
+    if blocked_id == account.id {
+        return Err((StatusCode::BAD_REQUEST, "You cannot block yourself".to_string()));
+    }
+    user::Entity::find_by_id(blocked_id)
+        .one(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+
+    blocks::block_user(&state.db, account.id, blocked_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn unblock_user(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(blocked_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let account = current_account(&state, &headers).await?;
+    let removed = blocks::unblock_user(&state.db, account.id, blocked_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if !removed {
+        return Err((StatusCode::NOT_FOUND, "Not on your block list".to_string()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
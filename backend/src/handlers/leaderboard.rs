@@ -0,0 +1,40 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use std::sync::Arc;
+use serde::Deserialize;
+use crate::leaderboard::{self, Leaderboard, PlayerBracket, Segment, Variant};
+use crate::server::AppState;
+use crate::features::Feature;
+
+/// Number of players kept per leaderboard segment
+pub const LEADERBOARD_SIZE: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct LeaderboardQuery {
+    pub players: PlayerBracket,
+    #[serde(default)]
+    pub variant: Variant,
+}
+
+/// Leaderboard for one segment, served from the aggregation job's cache
+pub async fn get_leaderboard(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<LeaderboardQuery>,
+) -> Result<Json<Leaderboard>, (StatusCode, String)> {
+    state.message_router.features().require(Feature::Ranked)?;
+    let segment = Segment { players: query.players, variant: query.variant };
+
+    if let Some(board) = state.leaderboards.get(segment).await {
+        return Ok(Json(board));
+    }
+
+    // Not aggregated yet, e.g. right after startup
+    let board = leaderboard::compute(&state.db, segment, LEADERBOARD_SIZE)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    state.leaderboards.insert(board.clone()).await;
+    Ok(Json(board))
+}
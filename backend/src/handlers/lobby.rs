@@ -0,0 +1,18 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use std::sync::Arc;
+use crate::lobby::LobbyPage;
+use crate::protocol::LobbyQuery;
+use crate::server::AppState;
+
+/// One page of the lobby browser for visitors who are not connected. Filtering by
+/// friends needs a signed-in player, so `friends_only` is ignored here.
+pub async fn list_lobbies(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<LobbyQuery>,
+) -> Json<LobbyPage> {
+    let query = LobbyQuery { friends_only: false, ..query };
+    Json(state.message_router.lobby_manager().browse_lobbies(&"anonymous".to_string(), &query).await)
+}
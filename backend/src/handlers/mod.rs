@@ -1 +1,7 @@
 pub mod auth;
+pub mod user;
+pub mod admin;
+pub mod leaderboard;
+pub mod lobby;
+pub mod fairness;
+pub mod friends;
@@ -0,0 +1,499 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use sea_orm::{EntityTrait, QueryFilter, QueryOrder, ColumnTrait, ActiveModelTrait, DatabaseConnection, Set};
+use crate::handlers::auth::current_account;
+use crate::server::AppState;
+use crate::entities::{game, game_player, user};
+use crate::timefmt::{self, LocalizedTimestamp, TimePrefs};
+use crate::digest;
+use crate::achievements::{self, Achievement};
+use crate::deal_review::{self, parse_suit, Deal};
+use crate::accessibility::SuitEncoding;
+use crate::engine::card::Suit;
+use crate::trump_stats::{self, TrumpStats};
+use crate::user_cache::PlayerProfile;
+use crate::error::PresetError;
+use crate::lobby_preset::{self, LobbyPreset};
+use crate::protocol::GameSettings;
+use crate::short_code::{self, IdOrCode};
+use crate::transcript;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize)]
+pub struct UserSettings {
+    pub time_zone: String,
+    pub locale: String,
+    pub weekly_digest: bool,
+    pub suit_encoding: SuitEncoding,
+}
+
+impl From<&user::Model> for UserSettings {
+    fn from(user: &user::Model) -> Self {
+        Self {
+            time_zone: user.time_zone.clone(),
+            locale: user.locale.clone(),
+            weekly_digest: user.weekly_digest,
+            suit_encoding: SuitEncoding::parse(&user.suit_encoding).unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSettingsRequest {
+    pub time_zone: Option<String>,
+    pub locale: Option<String>,
+    pub weekly_digest: Option<bool>,
+    pub suit_encoding: Option<SuitEncoding>,
+}
+
+/// Avatars a profile can pick from; clients ship the matching artwork
+pub const AVATARS: &[&str] = &["fox", "owl", "bear", "cat", "rabbit", "deer", "otter", "wolf"];
+
+const MAX_NAME_LEN: usize = 32;
+
+#[derive(Debug, Serialize)]
+pub struct Profile {
+    pub user_id: Uuid,
+    pub username: String,
+    pub display_name: Option<String>,
+    pub avatar: Option<String>,
+    /// Lobby settings the user prefers to start from
+    pub preferred_settings: Option<GameSettings>,
+    /// How the user fares under each trump suit, most played first
+    pub trump_stats: Vec<TrumpStats>,
+    /// Needs at least three rounds under a suit
+    pub best_trump: Option<Suit>,
+}
+
+impl Profile {
+    /// The profile with the user's trump stats filled in
+    async fn load(db: &DatabaseConnection, user: &user::Model) -> Result<Self, (StatusCode, String)> {
+        let trump_stats = trump_stats::stats_for_user(db, user.id)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        Ok(Self {
+            best_trump: trump_stats::best_trump(&trump_stats, trump_stats::MIN_ROUNDS_FOR_BEST),
+            trump_stats,
+            ..Self::from(user)
+        })
+    }
+}
+
+impl From<&user::Model> for Profile {
+    fn from(user: &user::Model) -> Self {
+        Self {
+            user_id: user.id,
+            username: user.username.clone(),
+            display_name: user.display_name.clone(),
+            avatar: user.avatar.clone(),
+            preferred_settings: user.preferred_settings.clone()
+                .and_then(|settings| serde_json::from_value(settings).ok()),
+            trump_stats: Vec::new(),
+            best_trump: None,
+        }
+    }
+}
+
+/// Absent fields are left alone; `null` clears the nullable ones
+#[derive(Debug, Deserialize)]
+pub struct UpdateProfileRequest {
+    pub username: Option<String>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub display_name: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub avatar: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub preferred_settings: Option<Option<GameSettings>>,
+}
+
+/// Tell a present `null` apart from an absent field
+fn nullable<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Trim a name and check its length
+fn valid_name(name: &str, field: &str) -> Result<String, (StatusCode, String)> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err((StatusCode::BAD_REQUEST, format!("{} must be 1 to {} characters", field, MAX_NAME_LEN)));
+    }
+    Ok(name.to_string())
+}
+
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct GameHistoryEntry {
+    pub game_id: Uuid,
+    pub created_at: LocalizedTimestamp,
+    pub completed_at: Option<LocalizedTimestamp>,
+    /// Set instead of `completed_at` when every player left before the end
+    pub abandoned_at: Option<LocalizedTimestamp>,
+    pub final_score: Option<i32>,
+    /// Finishing place after tie-breaks, 1 for a win
+    pub placement: Option<i32>,
+}
+
+pub async fn get_settings(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<UserSettings>, (StatusCode, String)> {
+    let user = current_account(&state, &headers).await?;
+
+    Ok(Json(UserSettings::from(&user)))
+}
+
+pub async fn update_settings(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateSettingsRequest>,
+) -> Result<Json<UserSettings>, (StatusCode, String)> {
+    let user = current_account(&state, &headers).await?;
+    let mut active: user::ActiveModel = user.into();
+
+    if let Some(time_zone) = payload.time_zone {
+        if timefmt::parse_time_zone(&time_zone).is_none() {
+            return Err((StatusCode::BAD_REQUEST, "Unknown time zone".to_string()));
+        }
+        active.time_zone = Set(time_zone);
+    }
+
+    if let Some(locale) = payload.locale {
+        if !timefmt::is_valid_locale(&locale) {
+            return Err((StatusCode::BAD_REQUEST, "Invalid locale".to_string()));
+        }
+        active.locale = Set(locale);
+    }
+
+    if let Some(weekly_digest) = payload.weekly_digest {
+        active.weekly_digest = Set(weekly_digest);
+    }
+
+    if let Some(suit_encoding) = payload.suit_encoding {
+        active.suit_encoding = Set(suit_encoding.as_str().to_string());
+    }
+
+    let user = active.update(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(UserSettings::from(&user)))
+}
+
+pub async fn get_profile(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Profile>, (StatusCode, String)> {
+    let user = current_account(&state, &headers).await?;
+
+    Ok(Json(Profile::load(&state.db, &user).await?))
+}
+
+/// Another user's profile, without their preferred lobby settings
+pub async fn get_public_profile(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Profile>, (StatusCode, String)> {
+    let user = user::Entity::find_by_id(id)
+        .one(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "User not found".to_string()))?;
+
+    Ok(Json(Profile { preferred_settings: None, ..Profile::load(&state.db, &user).await? }))
+}
+
+/// Update the public profile; lobby and game views pick it up from the next change
+pub async fn update_profile(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateProfileRequest>,
+) -> Result<Json<Profile>, (StatusCode, String)> {
+    let user = current_account(&state, &headers).await?;
+    let user_id = user.id;
+    let mut active: user::ActiveModel = user.into();
+
+    if let Some(username) = payload.username {
+        let username = valid_name(&username, "username")?;
+        let taken = user::Entity::find()
+            .filter(user::Column::Username.eq(&username))
+            .filter(user::Column::Id.ne(user_id))
+            .one(&state.db)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .is_some();
+        if taken {
+            return Err((StatusCode::CONFLICT, "Username already exists".to_string()));
+        }
+        active.username = Set(username);
+    }
+
+    if let Some(display_name) = payload.display_name {
+        active.display_name = Set(display_name.map(|name| valid_name(&name, "display_name")).transpose()?);
+    }
+
+    if let Some(avatar) = payload.avatar {
+        if avatar.as_deref().is_some_and(|avatar| !AVATARS.contains(&avatar)) {
+            return Err((StatusCode::BAD_REQUEST, "Unknown avatar".to_string()));
+        }
+        active.avatar = Set(avatar);
+    }
+
+    if let Some(settings) = payload.preferred_settings {
+        active.preferred_settings = Set(settings.map(|settings| serde_json::json!(settings)));
+    }
+
+    let user = active.update(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    state.connection_manager.user_cache()
+        .insert(user.id.to_string(), PlayerProfile::from(&user))
+        .await;
+
+    Ok(Json(Profile::load(&state.db, &user).await?))
+}
+
+/// List the authenticated user's games, newest first, with timestamps in their time zone
+#[utoipa::path(
+    get,
+    path = "/api/history",
+    tag = "history",
+    responses(
+        (status = 200, description = "The caller's games, newest first", body = Vec<GameHistoryEntry>),
+        (status = 401, description = "Missing or invalid token"),
+    ),
+    security(("bearer" = [])),
+)]
+pub async fn game_history(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<GameHistoryEntry>>, (StatusCode, String)> {
+    let user = current_account(&state, &headers).await?;
+    let prefs = TimePrefs::from_user(&user);
+
+    let rows = game_player::Entity::find()
+        .filter(game_player::Column::PlayerId.eq(user.id))
+        .find_also_related(game::Entity)
+        .filter(game::Column::DeletedAt.is_null())
+        .order_by_desc(game::Column::CreatedAt)
+        .all(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let history = rows.into_iter()
+        .filter_map(|(participation, game)| {
+            game.map(|game| GameHistoryEntry {
+                game_id: game.id,
+                created_at: prefs.localize(game.created_at),
+                completed_at: game.completed_at.map(|t| prefs.localize(t)),
+                abandoned_at: game.abandoned_at.map(|t| prefs.localize(t)),
+                final_score: participation.final_score,
+                placement: participation.placement,
+            })
+        })
+        .collect();
+
+    Ok(Json(history))
+}
+
+#[derive(Debug, Serialize)]
+pub struct DigestEntry {
+    pub week_start: LocalizedTimestamp,
+    pub games_played: i32,
+    pub wins: i32,
+    /// Always null until ratings exist
+    pub rating_change: Option<i32>,
+    /// The user's best game of the week
+    pub best_game_id: Option<Uuid>,
+    pub best_score: Option<i32>,
+    pub best_trump: Option<Suit>,
+    /// e.g. "Your best trump is Hearts"
+    pub best_trump_line: Option<String>,
+}
+
+/// List the authenticated user's weekly digests, newest week first
+pub async fn weekly_digests(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<DigestEntry>>, (StatusCode, String)> {
+    let user = current_account(&state, &headers).await?;
+    let prefs = TimePrefs::from_user(&user);
+
+    let digests = digest::digests_for_user(&state.db, user.id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(digests.into_iter().map(|d| DigestEntry {
+        week_start: prefs.localize(d.week_start),
+        games_played: d.games_played,
+        wins: d.wins,
+        rating_change: d.rating_change,
+        best_game_id: d.best_game_id,
+        best_score: d.best_score,
+        best_trump: d.best_trump.as_deref().and_then(parse_suit),
+        best_trump_line: d.best_trump.as_deref().and_then(parse_suit).map(trump_stats::best_trump_line),
+    }).collect()))
+}
+
+#[derive(Debug, Serialize)]
+pub struct AchievementEntry {
+    pub achievement: Achievement,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub progress: i32,
+    pub target: i32,
+    /// Null while still locked
+    pub unlocked_at: Option<LocalizedTimestamp>,
+}
+
+/// List every achievement with the authenticated user's progress towards it
+pub async fn achievements(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<AchievementEntry>>, (StatusCode, String)> {
+    let user = current_account(&state, &headers).await?;
+    let prefs = TimePrefs::from_user(&user);
+
+    let progress = achievements::progress_for_user(&state.db, user.id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(progress.into_iter().map(|p| AchievementEntry {
+        achievement: p.achievement,
+        title: p.achievement.title(),
+        description: p.achievement.description(),
+        progress: p.progress,
+        target: p.achievement.target(),
+        unlocked_at: p.unlocked_at.map(|t| prefs.localize(t)),
+    }).collect()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SavePresetRequest {
+    pub name: String,
+    pub settings: GameSettings,
+}
+
+fn preset_error(e: PresetError) -> (StatusCode, String) {
+    let status = match e {
+        PresetError::InvalidName(_) => StatusCode::BAD_REQUEST,
+        PresetError::LimitReached(_) => StatusCode::CONFLICT,
+        PresetError::NotFound => StatusCode::NOT_FOUND,
+        PresetError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, e.to_string())
+}
+
+/// List the authenticated user's lobby presets by name
+pub async fn list_presets(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<LobbyPreset>>, (StatusCode, String)> {
+    let user = current_account(&state, &headers).await?;
+
+    let presets = lobby_preset::list_presets(&state.db, user.id).await.map_err(preset_error)?;
+    Ok(Json(presets))
+}
+
+/// Save a lobby preset; an existing preset with the same name is overwritten
+pub async fn save_preset(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<SavePresetRequest>,
+) -> Result<Json<LobbyPreset>, (StatusCode, String)> {
+    let user = current_account(&state, &headers).await?;
+
+    let preset = lobby_preset::save_preset(&state.db, user.id, &payload.name, &payload.settings)
+        .await
+        .map_err(preset_error)?;
+    Ok(Json(preset))
+}
+
+pub async fn delete_preset(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(preset_id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let user = current_account(&state, &headers).await?;
+
+    lobby_preset::delete_preset(&state.db, user.id, preset_id).await.map_err(preset_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// A game `user_id` played in that has ended; `unfinished` is the conflict message otherwise
+async fn finished_game_played_by(
+    state: &AppState,
+    user_id: Uuid,
+    game_ref: &IdOrCode,
+    unfinished: &str,
+) -> Result<game::Model, (StatusCode, String)> {
+    let game_id = short_code::find_game_id(&state.db, game_ref)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Game not found".to_string()))?;
+    let game = game::Entity::find_by_id(game_id)
+        .filter(game::Column::DeletedAt.is_null())
+        .one(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .ok_or((StatusCode::NOT_FOUND, "Game not found".to_string()))?;
+
+    let participant = game_player::Entity::find()
+        .filter(game_player::Column::GameId.eq(game_id))
+        .filter(game_player::Column::PlayerId.eq(user_id))
+        .one(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if participant.is_none() {
+        return Err((StatusCode::FORBIDDEN, "You did not play in this game".to_string()));
+    }
+
+    // Hands stay hidden while the game can still be played
+    if game.completed_at.is_none() && game.abandoned_at.is_none() {
+        return Err((StatusCode::CONFLICT, unfinished.to_string()));
+    }
+    Ok(game)
+}
+
+/// Everyone's dealt hands for each round of a finished game the user played in
+pub async fn game_deals(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(game_ref): Path<IdOrCode>,
+) -> Result<Json<Vec<Deal>>, (StatusCode, String)> {
+    let user = current_account(&state, &headers).await?;
+    let game = finished_game_played_by(&state, user.id, &game_ref, "Deals are revealed when the game ends").await?;
+
+    let deals = deal_review::deals_for_game(&state.db, game.id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(deals))
+}
+
+/// A finished game the user played in, round by round, with only their own hands
+pub async fn game_transcript(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(game_ref): Path<IdOrCode>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let user = current_account(&state, &headers).await?;
+    let game = finished_game_played_by(&state, user.id, &game_ref, "Transcripts are available when the game ends").await?;
+
+    let transcript = transcript::build(&state.db, &game, &user.id.to_string())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let filename = format!("german-bridge-{}.json", game.code.as_deref().unwrap_or(&game.id.to_string()));
+    Ok((
+        [(header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename))],
+        Json(transcript),
+    ))
+}
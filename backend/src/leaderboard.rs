@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+use crate::entities::{game, game_player, user};
+use crate::engine::schedule::RoundSchedule;
+use crate::protocol::GameSettings;
+use tracing::{info, warn};
+
+/// Table sizes that are ranked separately; scores from different sizes are not comparable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayerBracket {
+    Two,
+    Three,
+    FourPlus,
+}
+
+impl PlayerBracket {
+    pub const ALL: [PlayerBracket; 3] = [PlayerBracket::Two, PlayerBracket::Three, PlayerBracket::FourPlus];
+
+    /// Bracket for a table of `count` players; None for tables too small to rank
+    pub fn from_count(count: usize) -> Option<Self> {
+        match count {
+            2 => Some(PlayerBracket::Two),
+            3 => Some(PlayerBracket::Three),
+            n if n >= 4 => Some(PlayerBracket::FourPlus),
+            _ => None,
+        }
+    }
+}
+
+/// Rule variants that are ranked separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Variant {
+    #[default]
+    Standard,
+    UpAndDown,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 2] = [Variant::Standard, Variant::UpAndDown];
+
+    pub fn from_settings(settings: &GameSettings) -> Self {
+        match settings.schedule() {
+            RoundSchedule::Ascending => Variant::Standard,
+            RoundSchedule::UpAndDown => Variant::UpAndDown,
+        }
+    }
+
+    /// Value stored in `games.variant`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Variant::Standard => "standard",
+            Variant::UpAndDown => "up_and_down",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Segment {
+    pub players: PlayerBracket,
+    pub variant: Variant,
+}
+
+impl Segment {
+    pub fn all() -> Vec<Segment> {
+        PlayerBracket::ALL
+            .iter()
+            .flat_map(|&players| Variant::ALL.iter().map(move |&variant| Segment { players, variant }))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub player_id: Uuid,
+    pub username: String,
+    pub games_played: u32,
+    /// Games where the player had the highest final score, ties included
+    pub wins: u32,
+    pub total_score: i64,
+    pub average_score: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub segment: Segment,
+    pub computed_at: DateTime<Utc>,
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+/// Aggregate completed, non-deleted, rated games in one segment, ranked by wins then average score
+pub async fn compute(db: &DatabaseConnection, segment: Segment, limit: usize) -> Result<Leaderboard, DbErr> {
+    let mut games = game::Entity::find()
+        .filter(game::Column::CompletedAt.is_not_null())
+        .filter(game::Column::DeletedAt.is_null())
+        .filter(game::Column::Unrated.eq(false))
+        .filter(game::Column::Variant.eq(segment.variant.as_str()));
+    games = match segment.players {
+        PlayerBracket::Two => games.filter(game::Column::PlayerCount.eq(2)),
+        PlayerBracket::Three => games.filter(game::Column::PlayerCount.eq(3)),
+        PlayerBracket::FourPlus => games.filter(game::Column::PlayerCount.gte(4)),
+    };
+    let game_ids: Vec<Uuid> = games.all(db).await?.into_iter().map(|g| g.id).collect();
+
+    let results = game_player::Entity::find()
+        .filter(game_player::Column::GameId.is_in(game_ids))
+        .filter(game_player::Column::FinalScore.is_not_null())
+        .all(db)
+        .await?;
+
+    // Placements decide the winners; games completed before placements were stored fall back to the highest score
+    let mut best: HashMap<Uuid, i32> = HashMap::new();
+    for row in &results {
+        let score = row.final_score.unwrap_or_default();
+        best.entry(row.game_id).and_modify(|b| *b = (*b).max(score)).or_insert(score);
+    }
+
+    let mut totals: HashMap<Uuid, (u32, u32, i64)> = HashMap::new();
+    for row in &results {
+        let score = row.final_score.unwrap_or_default();
+        let entry = totals.entry(row.player_id).or_default();
+        entry.0 += 1;
+        if row.placement.map_or(best.get(&row.game_id) == Some(&score), |place| place == 1) {
+            entry.1 += 1;
+        }
+        entry.2 += score as i64;
+    }
+
+    let player_ids: Vec<Uuid> = totals.keys().copied().collect();
+    let usernames: HashMap<Uuid, String> = user::Entity::find()
+        .filter(user::Column::Id.is_in(player_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|u| (u.id, u.username))
+        .collect();
+
+    let mut entries: Vec<LeaderboardEntry> = totals
+        .into_iter()
+        .map(|(player_id, (games_played, wins, total_score))| LeaderboardEntry {
+            player_id,
+            username: usernames.get(&player_id).cloned().unwrap_or_else(|| "Unknown".to_string()),
+            games_played,
+            wins,
+            total_score,
+            average_score: total_score as f64 / games_played as f64,
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.wins
+            .cmp(&a.wins)
+            .then(b.average_score.total_cmp(&a.average_score))
+            .then(a.username.cmp(&b.username))
+    });
+    entries.truncate(limit);
+
+    Ok(Leaderboard { segment, computed_at: Utc::now(), entries })
+}
+
+/// Called after every refresh, e.g. to drop HTTP responses built from the old boards
+pub type RefreshHook = Box<dyn Fn() + Send + Sync>;
+
+/// Most recently aggregated leaderboard for each segment
+#[derive(Default)]
+pub struct LeaderboardCache {
+    boards: RwLock<HashMap<Segment, Leaderboard>>,
+    refresh_hooks: std::sync::Mutex<Vec<RefreshHook>>,
+}
+
+impl std::fmt::Debug for LeaderboardCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LeaderboardCache").field("boards", &self.boards).finish_non_exhaustive()
+    }
+}
+
+impl LeaderboardCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn get(&self, segment: Segment) -> Option<Leaderboard> {
+        self.boards.read().await.get(&segment).cloned()
+    }
+
+    pub async fn insert(&self, board: Leaderboard) {
+        self.boards.write().await.insert(board.segment, board);
+    }
+
+    /// Recompute every segment; segments that fail keep their previous board
+    pub async fn refresh(&self, db: &DatabaseConnection, limit: usize) {
+        for segment in Segment::all() {
+            match compute(db, segment, limit).await {
+                Ok(board) => self.insert(board).await,
+                Err(e) => warn!("Failed to aggregate leaderboard for {:?}: {}", segment, e),
+            }
+        }
+        for hook in self.refresh_hooks.lock().unwrap().iter() {
+            hook();
+        }
+    }
+
+    /// Run `hook` after every refresh
+    pub fn on_refresh(&self, hook: impl Fn() + Send + Sync + 'static) {
+        self.refresh_hooks.lock().unwrap().push(Box::new(hook));
+    }
+}
+
+/// Spawn the background job that re-aggregates all leaderboard segments
+pub fn spawn_leaderboard_task(
+    db: DatabaseConnection,
+    cache: Arc<LeaderboardCache>,
+    limit: usize,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            cache.refresh(&db, limit).await;
+            info!("Leaderboards refreshed");
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_player_brackets() {
+        assert_eq!(PlayerBracket::from_count(1), None);
+        assert_eq!(PlayerBracket::from_count(2), Some(PlayerBracket::Two));
+        assert_eq!(PlayerBracket::from_count(3), Some(PlayerBracket::Three));
+        assert_eq!(PlayerBracket::from_count(6), Some(PlayerBracket::FourPlus));
+    }
+
+    #[test]
+    fn test_variant_follows_round_schedule() {
+        let settings = GameSettings { round_schedule: RoundSchedule::UpAndDown, ..Default::default() };
+        assert_eq!(Variant::from_settings(&settings), Variant::UpAndDown);
+        assert_eq!(Variant::from_settings(&GameSettings::default()), Variant::Standard);
+        // The rule set's schedule wins over the deprecated flat one
+        let rules = crate::engine::rules::RuleSet { schedule: Some(RoundSchedule::Ascending), ..Default::default() };
+        assert_eq!(Variant::from_settings(&GameSettings { rules, ..settings }), Variant::Standard);
+        assert_eq!(Segment::all().len(), 6);
+    }
+}
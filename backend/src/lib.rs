@@ -1,14 +1,121 @@
+//! German Bridge game server. With the default `server` feature off only the game
+//! engine, its error type and the LAN discovery wire format are built, for embedding in clients;
+//! the `protocol` feature adds the WebSocket message types and the modules defining their parts.
+
+#[cfg(feature = "server")]
 pub mod server;
+#[cfg(feature = "server")]
 pub mod config;
+#[cfg(feature = "server")]
+pub mod tls;
+#[cfg(feature = "server")]
+pub mod cors;
+#[cfg(feature = "server")]
+pub mod client_ip;
+#[cfg(feature = "server")]
 pub mod connection;
+#[cfg(feature = "server")]
+pub mod outbound;
+#[cfg(feature = "protocol")]
+pub mod user_cache;
+#[cfg(feature = "server")]
+pub mod rate_limit;
+#[cfg(feature = "server")]
+pub mod public_api;
+#[cfg(feature = "protocol")]
+pub mod features;
+#[cfg(feature = "protocol")]
+pub mod deprecation;
+#[cfg(feature = "protocol")]
+pub mod room;
+#[cfg(feature = "server")]
+pub mod room_feed;
+#[cfg(feature = "protocol")]
+pub mod presence;
+#[cfg(feature = "server")]
+pub mod friends;
+#[cfg(feature = "server")]
+pub mod blocks;
+#[cfg(feature = "server")]
+pub mod user_directory;
+#[cfg(feature = "server")]
 pub mod game;
-pub mod game_logic;
-pub mod game_state;
+#[cfg(feature = "server")]
+pub mod game_actor;
+#[cfg(feature = "server")]
+pub mod timer_wheel;
+#[cfg(feature = "server")]
+pub mod game_trace;
+#[cfg(feature = "server")]
+pub mod game_events;
+#[cfg(feature = "server")]
+pub mod db_outbox;
+#[cfg(feature = "protocol")]
+pub mod audit;
+#[cfg(feature = "server")]
+pub mod bug_report;
+#[cfg(feature = "server")]
+pub mod deal_review;
+#[cfg(feature = "server")]
+pub mod transcript;
+pub mod engine;
+pub mod discovery;
+#[cfg(feature = "server")]
 pub mod lobby;
+#[cfg(feature = "server")]
+pub mod lobby_feed;
+#[cfg(feature = "server")]
+pub mod lobby_index;
+#[cfg(feature = "protocol")]
+pub mod lobby_preset;
+#[cfg(feature = "server")]
+pub mod settings_policy;
+#[cfg(feature = "protocol")]
+pub mod short_code;
+#[cfg(feature = "protocol")]
+pub mod region;
+#[cfg(feature = "server")]
+pub mod matchmaking;
+#[cfg(feature = "server")]
+pub mod cluster;
+#[cfg(feature = "protocol")]
 pub mod protocol;
+#[cfg(feature = "server")]
+pub mod redaction;
+#[cfg(feature = "protocol")]
+pub mod accessibility;
+#[cfg(feature = "server")]
 pub mod router;
+#[cfg(feature = "protocol")]
 pub mod auth;
+#[cfg(feature = "server")]
+pub mod password_reset;
+#[cfg(feature = "server")]
 pub mod handlers;
+#[cfg(feature = "server")]
+pub mod openapi;
 pub mod error;
+#[cfg(feature = "server")]
+pub mod timefmt;
+#[cfg(feature = "server")]
+pub mod retention;
+#[cfg(feature = "server")]
+pub mod leaderboard;
+#[cfg(feature = "protocol")]
+pub mod trump_stats;
+#[cfg(feature = "protocol")]
+pub mod achievements;
+#[cfg(feature = "protocol")]
+pub mod pacing;
+#[cfg(feature = "server")]
+pub mod digest;
+#[cfg(feature = "server")]
+pub mod webhook;
+#[cfg(feature = "server")]
+pub mod tournament;
+#[cfg(feature = "server")]
+pub mod database;
+#[cfg(feature = "server")]
 pub mod entities;
+#[cfg(feature = "server")]
 pub mod migrator;
@@ -1,32 +1,69 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use uuid::Uuid;
+use serde::Serialize;
 use crate::connection::PlayerId;
-use crate::protocol::GameSettings;
+use crate::protocol::{GameSettings, ServerMessage, SharedAddressPolicy};
 use crate::game::{GameManager, GameId};
+use crate::lobby_preset::LobbyPreset;
+use crate::error::PresetError;
+use crate::short_code::{IdOrCode, ShortCode};
+use crate::room::RoomId;
+use crate::db_outbox::{DbOutbox, DbWrite};
+use crate::lobby_index::{LobbyCursor, LobbyIndex};
+use crate::protocol::{LobbyInfo, LobbyQuery};
 use tracing::{debug, info, warn};
-use sea_orm::{DatabaseConnection, ActiveModelTrait, EntityTrait, Set, QueryFilter, ColumnTrait};
+use sea_orm::{DatabaseConnection, EntityTrait, Set, QueryFilter, ColumnTrait};
 use chrono::Utc;
 
-pub type LobbyId = Uuid;
+pub use crate::protocol::LobbyId;
+
+/// Lobbies per browser page when the client does not ask for a size
+pub const DEFAULT_LOBBY_PAGE_SIZE: usize = 20;
+/// Largest browser page a client may ask for
+pub const MAX_LOBBY_PAGE_SIZE: usize = 100;
+
+/// Human players connected from the same address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SharedAddress {
+    pub address: std::net::IpAddr,
+    pub players: Vec<PlayerId>,
+}
 
 pub struct LobbyManager {
     lobbies: Arc<RwLock<HashMap<LobbyId, Lobby>>>,
+    /// Only changed while holding the `lobbies` write lock
+    index: std::sync::Mutex<LobbyIndex>,
+    /// Membership changes are stored in the background, retried while the database is down
+    writes: DbOutbox,
     game_manager: Arc<GameManager>,
     connection_manager: Arc<crate::connection::ConnectionManager>,
     db: DatabaseConnection,
+    user_directory: crate::user_directory::UserDirectory,
 }
 
 #[derive(Clone)]
 pub struct Lobby {
     pub id: LobbyId,
+    pub code: ShortCode,
     pub host: PlayerId,
     pub players: Vec<PlayerId>,
     pub max_players: usize,
     pub created_at: Instant,
     pub settings: GameSettings,
+    pub ready: HashSet<PlayerId>,
+    /// Last join, leave, kick, host change or ready change; idle lobbies are closed
+    pub last_activity: Instant,
+}
+
+/// A page of the lobby browser
+#[derive(Debug, Clone, Serialize)]
+pub struct LobbyPage {
+    pub lobbies: Vec<LobbyInfo>,
+    /// Cursor of the last lobby when more match the query
+    pub next_cursor: Option<LobbyCursor>,
 }
 
 impl Lobby {
@@ -39,35 +76,77 @@ impl Lobby {
     pub fn is_host(&self, player_id: PlayerId) -> bool {
         self.host == player_id
     }
+
+    /// Check if every player other than the host is ready
+    pub fn all_ready(&self) -> bool {
+        self.players.iter()
+            .filter(|p| **p != self.host)
+            .all(|p| self.ready.contains(p))
+    }
 }
 
 impl LobbyManager {
     pub fn new(game_manager: Arc<GameManager>, connection_manager: Arc<crate::connection::ConnectionManager>, db: DatabaseConnection) -> Self {
         Self {
             lobbies: Arc::new(RwLock::new(HashMap::new())),
+            index: std::sync::Mutex::new(LobbyIndex::default()),
+            writes: game_manager.db_writes().clone(),
             game_manager,
+            user_directory: crate::user_directory::UserDirectory::new(connection_manager.user_cache(), db.clone()),
             connection_manager,
             db,
         }
     }
 
+    /// The player's saved lobby presets
+    pub async fn presets(&self, player_id: &PlayerId) -> Result<Vec<LobbyPreset>, PresetError> {
+        let user_id = Uuid::parse_str(player_id).map_err(|_| PresetError::NotFound)?;
+        crate::lobby_preset::list_presets(&self.db, user_id).await
+    }
+
+    /// Settings stored in one of the player's presets
+    pub async fn preset_settings(&self, player_id: &PlayerId, preset_id: Uuid) -> Result<GameSettings, PresetError> {
+        let user_id = Uuid::parse_str(player_id).map_err(|_| PresetError::NotFound)?;
+        Ok(crate::lobby_preset::get_preset(&self.db, user_id, preset_id).await?.settings)
+    }
+
+    /// Reject settings that reference a tournament that does not exist
+    pub async fn check_tournament(&self, settings: &GameSettings) -> Result<(), crate::error::LobbyError> {
+        let Some(tournament_id) = settings.tournament_id else {
+            return Ok(());
+        };
+        match crate::tournament::tournament_exists(&self.db, tournament_id).await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(crate::error::LobbyError::TournamentNotFound),
+            Err(e) => {
+                warn!("Failed to look up tournament {}: {}", tournament_id, e);
+                Err(crate::error::LobbyError::TournamentNotFound)
+            }
+        }
+    }
+
     /// Create a new lobby with the given host and settings
     pub async fn create_lobby(&self, host: PlayerId, settings: GameSettings) -> LobbyId {
         let lobby_id = Uuid::new_v4();
         let max_players = settings.player_count;
 
+        let mut lobbies = self.lobbies.write().await;
+        let code = ShortCode::generate_unique(|code| lobbies.values().any(|l| l.code == *code));
         let lobby = Lobby {
             id: lobby_id,
+            code: code.clone(),
             host: host.clone(),
             players: vec![host.clone()],
             max_players,
             created_at: Instant::now(),
             settings: settings.clone(),
+            ready: HashSet::new(),
+            last_activity: Instant::now(),
         };
-
-        let mut lobbies = self.lobbies.write().await;
         lobbies.insert(lobby_id, lobby);
+        self.index().insert(lobby_id, max_players, &host);
         drop(lobbies);
+        self.connection_manager.rooms().join(&host, RoomId::Lobby(lobby_id));
 
         // Persist to database
         if let Ok(host_uuid) = Uuid::parse_str(&host) {
@@ -76,24 +155,15 @@ impl LobbyManager {
                 host_id: Set(host_uuid),
                 max_players: Set(max_players as i32),
                 settings: Set(serde_json::json!(settings)),
-                created_at: Set(Utc::now().into()),
+                created_at: Set(Utc::now()),
                 closed_at: Set(None),
+                deleted_at: Set(None),
+                code: Set(Some(code.to_string())),
             };
-            if let Err(e) = lobby_model.insert(&self.db).await {
-                warn!("Failed to persist lobby to DB: {}", e);
-            }
-
-            let player_model = crate::entities::lobby_player::ActiveModel {
-                lobby_id: Set(lobby_id),
-                player_id: Set(host_uuid),
-                joined_at: Set(Utc::now().into()),
-            };
-            if let Err(e) = player_model.insert(&self.db).await {
-                warn!("Failed to persist lobby_player to DB: {}", e);
-            }
+            self.writes.push(DbWrite::LobbyCreated { lobby: lobby_model, host_id: host_uuid, joined_at: Utc::now() });
         }
 
-        info!("Lobby {} created by player {} with max {} players", lobby_id, host, max_players);
+        info!("Lobby {} ({}) created by player {} with max {} players", lobby_id, code, host, max_players);
 
         lobby_id
     }
@@ -110,6 +180,47 @@ impl LobbyManager {
             return Err(crate::error::LobbyError::LobbyFull);
         }
 
+        let is_bot = self.connection_manager.is_bot(&player_id).await;
+        if lobby.settings.bots_only && !is_bot {
+            warn!("Human player {} attempted to join bot sandbox lobby {}", player_id, lobby_id);
+            return Err(crate::error::LobbyError::BotsOnly);
+        }
+
+        if is_bot && !lobby.players.contains(&player_id) {
+            let policy = &lobby.settings.bot_policy;
+            if policy.forbid_bots {
+                return Err(crate::error::LobbyError::BotsNotAllowed);
+            }
+            if let Some(max_bots) = policy.max_bots {
+                if self.count_bots(&lobby.players).await >= max_bots {
+                    return Err(crate::error::LobbyError::BotLimitReached);
+                }
+            }
+        }
+
+        if lobby.settings.exclude_blocked && !lobby.players.contains(&player_id) {
+            // Unreadable block lists let players in rather than locking everyone out
+            match crate::blocks::has_blocked(&self.db, &lobby.host, &player_id).await {
+                Ok(true) => {
+                    info!("Player {} is blocked by the host of lobby {}", player_id, lobby_id);
+                    return Err(crate::error::LobbyError::BlockedByHost);
+                }
+                Ok(false) => {}
+                Err(e) => warn!("Failed to check block list for lobby {}: {}", lobby_id, e),
+            }
+        }
+
+        if lobby.settings.shared_address == SharedAddressPolicy::Prevent && !lobby.players.contains(&player_id) {
+            let mut seated = lobby.players.clone();
+            seated.push(player_id.clone());
+            if self.shared_addresses(&seated).await.iter().any(|shared| shared.players.contains(&player_id)) {
+                warn!("Player {} shares an address with a player in lobby {}", player_id, lobby_id);
+                return Err(crate::error::LobbyError::SharedAddress);
+            }
+        }
+
+        lobby.last_activity = Instant::now();
+
         // Don't add if already in lobby
         if !lobby.players.contains(&player_id) {
             lobby.players.push(player_id.clone());
@@ -117,18 +228,12 @@ impl LobbyManager {
             
             // Persist to database
             if let Ok(player_uuid) = Uuid::parse_str(&player_id) {
-                let player_model = crate::entities::lobby_player::ActiveModel {
-                    lobby_id: Set(lobby_id),
-                    player_id: Set(player_uuid),
-                    joined_at: Set(Utc::now().into()),
-                };
-                if let Err(e) = player_model.insert(&self.db).await {
-                    warn!("Failed to persist lobby_player to DB: {}", e);
-                }
+                self.writes.push(DbWrite::LobbyJoined { lobby_id, player_id: player_uuid, joined_at: Utc::now() });
             }
         } else {
             debug!("Player {} already in lobby {}", player_id, lobby_id);
         }
+        self.connection_manager.rooms().join(&player_id, RoomId::Lobby(lobby_id));
 
         Ok(())
     }
@@ -142,85 +247,464 @@ impl LobbyManager {
 
         // Remove player from lobby
         lobby.players.retain(|p| *p != player_id);
+        lobby.ready.remove(&player_id);
+        lobby.last_activity = Instant::now();
+        self.connection_manager.rooms().leave(&player_id, RoomId::Lobby(lobby_id));
         info!("Player {} left lobby {}", player_id, lobby_id);
-        
-        // Delete player from DB
-        if let Ok(player_uuid) = Uuid::parse_str(&player_id) {
-            let _ = crate::entities::lobby_player::Entity::delete_many()
-                .filter(crate::entities::lobby_player::Column::LobbyId.eq(lobby_id))
-                .filter(crate::entities::lobby_player::Column::PlayerId.eq(player_uuid))
-                .exec(&self.db).await;
-        }
 
         // If lobby is empty, remove it
         if lobby.players.is_empty() {
             lobbies.remove(&lobby_id);
+            self.index().remove(lobby_id);
+            self.connection_manager.rooms().close(RoomId::Lobby(lobby_id));
             info!("Lobby {} removed (empty)", lobby_id);
             
-            // Delete lobby from DB
-            let _ = crate::entities::lobby::Entity::delete_by_id(lobby_id).exec(&self.db).await;
+            // Soft-delete lobby in DB, the last member with it, so it can be restored and
+            // still counts in analytics
+            self.writes.push(DbWrite::LobbyDeleted { lobby_id });
             return Ok(());
         }
 
+        // Mark them as gone in DB
+        if let Ok(player_uuid) = Uuid::parse_str(&player_id) {
+            self.writes.push(DbWrite::LobbyLeft { lobby_id, player_id: player_uuid, left_at: Utc::now() });
+        }
+
         // If the host left, transfer to next player
         if lobby.host == player_id {
             let new_host = lobby.players[0].clone();
             lobby.host = new_host.clone();
+            self.index().set_host(lobby_id, &new_host);
             info!("Lobby {} host transferred from {} to {}", lobby_id, player_id, new_host);
             
             // Update host in DB
             if let Ok(new_host_uuid) = Uuid::parse_str(&new_host) {
-                use sea_orm::sea_query::Expr;
-                let _ = crate::entities::lobby::Entity::update_many()
-                    .col_expr(crate::entities::lobby::Column::HostId, Expr::value(new_host_uuid))
-                    .filter(crate::entities::lobby::Column::Id.eq(lobby_id))
-                    .exec(&self.db).await;
+                self.writes.push(DbWrite::LobbyHostChanged { lobby_id, host_id: new_host_uuid });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove a player from the lobby at the host's request
+    pub async fn kick_player(&self, lobby_id: LobbyId, caller: PlayerId, target: PlayerId) -> Result<(), crate::error::LobbyError> {
+        let mut lobbies = self.lobbies.write().await;
+
+        let lobby = lobbies.get_mut(&lobby_id)
+            .ok_or(crate::error::LobbyError::LobbyNotFound)?;
+
+        if !lobby.is_host(caller.clone()) {
+            warn!("Player {} attempted to kick {} from lobby {} but is not host", caller, target, lobby_id);
+            return Err(crate::error::LobbyError::NotHost);
+        }
+
+        if caller == target {
+            return Err(crate::error::LobbyError::CannotTargetSelf);
+        }
+
+        if !lobby.players.contains(&target) {
+            return Err(crate::error::LobbyError::PlayerNotInLobby);
+        }
+
+        lobby.players.retain(|p| *p != target);
+        lobby.ready.remove(&target);
+        lobby.last_activity = Instant::now();
+        self.connection_manager.rooms().leave(&target, RoomId::Lobby(lobby_id));
+        info!("Player {} kicked from lobby {} by host {}", target, lobby_id, caller);
+
+        // Mark them as gone in DB
+        if let Ok(player_uuid) = Uuid::parse_str(&target) {
+            self.writes.push(DbWrite::LobbyLeft { lobby_id, player_id: player_uuid, left_at: Utc::now() });
+        }
+
+        Ok(())
+    }
+
+    /// Shut a lobby down on a moderator's order, returning the players who were in it
+    pub async fn close_lobby(&self, lobby_id: LobbyId) -> Result<Vec<PlayerId>, crate::error::LobbyError> {
+        let mut lobbies = self.lobbies.write().await;
+        let lobby = lobbies.remove(&lobby_id)
+            .ok_or(crate::error::LobbyError::LobbyNotFound)?;
+        self.index().remove(lobby_id);
+        drop(lobbies);
+        self.mark_closed(lobby_id);
+
+        info!("Lobby {} closed by a moderator", lobby_id);
+        Ok(lobby.players)
+    }
+
+    /// Close every lobby nobody joined, left or changed in the last `idle`, telling its
+    /// members with `LobbyExpired`. Returns the closed lobbies.
+    pub async fn expire_idle_lobbies(&self, idle: Duration) -> Vec<LobbyId> {
+        let mut lobbies = self.lobbies.write().await;
+        let idle_ids: Vec<LobbyId> = lobbies.values()
+            .filter(|lobby| lobby.last_activity.elapsed() >= idle)
+            .map(|lobby| lobby.id)
+            .collect();
+        let mut expired = Vec::with_capacity(idle_ids.len());
+        for lobby_id in idle_ids {
+            if let Some(lobby) = lobbies.remove(&lobby_id) {
+                self.index().remove(lobby_id);
+                expired.push(lobby);
+            }
+        }
+        drop(lobbies);
+
+        let mut closed = Vec::with_capacity(expired.len());
+        for lobby in expired {
+            info!("Lobby {} closed after {:?} without activity", lobby.id, idle);
+            self.connection_manager.broadcast_to_players(&lobby.players, ServerMessage::LobbyExpired { lobby_id: lobby.id }).await;
+            self.mark_closed(lobby.id);
+            closed.push(lobby.id);
+        }
+        closed
+    }
+
+    /// Take a closed lobby's room down and record when it closed
+    fn mark_closed(&self, lobby_id: LobbyId) {
+        self.connection_manager.rooms().close(RoomId::Lobby(lobby_id));
+        self.writes.push(DbWrite::LobbyClosed { lobby_id, closed_at: Utc::now() });
+    }
+
+    /// Hand the host role to another player in the lobby
+    pub async fn transfer_host(&self, lobby_id: LobbyId, caller: PlayerId, new_host: PlayerId) -> Result<(), crate::error::LobbyError> {
+        let mut lobbies = self.lobbies.write().await;
+
+        let lobby = lobbies.get_mut(&lobby_id)
+            .ok_or(crate::error::LobbyError::LobbyNotFound)?;
+
+        if !lobby.is_host(caller.clone()) {
+            warn!("Player {} attempted to transfer host of lobby {} but is not host", caller, lobby_id);
+            return Err(crate::error::LobbyError::NotHost);
+        }
+
+        if caller == new_host {
+            return Err(crate::error::LobbyError::CannotTargetSelf);
+        }
+
+        if !lobby.players.contains(&new_host) {
+            return Err(crate::error::LobbyError::PlayerNotInLobby);
+        }
+
+        lobby.host = new_host.clone();
+        lobby.last_activity = Instant::now();
+        self.index().set_host(lobby_id, &new_host);
+        info!("Lobby {} host transferred from {} to {}", lobby_id, caller, new_host);
+
+        // Update host in DB
+        if let Ok(new_host_uuid) = Uuid::parse_str(&new_host) {
+            self.writes.push(DbWrite::LobbyHostChanged { lobby_id, host_id: new_host_uuid });
+        }
+
+        Ok(())
+    }
+
+    /// Mark a player as ready (or not) to start
+    pub async fn set_ready(&self, lobby_id: LobbyId, player_id: PlayerId, ready: bool) -> Result<(), crate::error::LobbyError> {
+        let mut lobbies = self.lobbies.write().await;
+
+        let lobby = lobbies.get_mut(&lobby_id)
+            .ok_or(crate::error::LobbyError::LobbyNotFound)?;
+
+        if !lobby.players.contains(&player_id) {
+            return Err(crate::error::LobbyError::PlayerNotInLobby);
+        }
+
+        if ready {
+            lobby.ready.insert(player_id.clone());
+        } else {
+            lobby.ready.remove(&player_id);
+        }
+        lobby.last_activity = Instant::now();
+        debug!("Player {} in lobby {} ready: {}", player_id, lobby_id, ready);
+
+        Ok(())
+    }
+
+    /// Count the bot accounts among the given players
+    async fn count_bots(&self, players: &[PlayerId]) -> usize {
+        let mut count = 0;
+        for player_id in players {
+            if self.connection_manager.is_bot(player_id).await {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Groups of two or more human players among `players` connected from the same client
+    /// address, the one a trusted proxy forwarded rather than the proxy's own, in seat order.
+    /// Bots and players without a known address are left out.
+    pub async fn shared_addresses(&self, players: &[PlayerId]) -> Vec<SharedAddress> {
+        let mut groups: Vec<SharedAddress> = Vec::new();
+        for player_id in players {
+            if self.connection_manager.is_bot(player_id).await {
+                continue;
+            }
+            let Some(address) = self.connection_manager.address(player_id).await else {
+                continue;
+            };
+            match groups.iter_mut().find(|group| group.address == address) {
+                Some(group) => group.players.push(player_id.clone()),
+                None => groups.push(SharedAddress { address, players: vec![player_id.clone()] }),
             }
         }
+        groups.retain(|group| group.players.len() > 1);
+        groups
+    }
+
+    /// Check the lobby's bot policy against its current players
+    async fn check_bot_policy(&self, lobby: &Lobby) -> Result<(), crate::error::LobbyError> {
+        let policy = &lobby.settings.bot_policy;
+        let bots = self.count_bots(&lobby.players).await;
+
+        if policy.forbid_bots && bots > 0 {
+            return Err(crate::error::LobbyError::BotsNotAllowed);
+        }
+        if policy.max_bots.is_some_and(|max_bots| bots > max_bots) {
+            return Err(crate::error::LobbyError::BotLimitReached);
+        }
+        if policy.require_human && bots == lobby.players.len() {
+            return Err(crate::error::LobbyError::HumanRequired);
+        }
 
         Ok(())
     }
 
+    /// Build the client-facing view of a lobby
+    pub async fn lobby_info(&self, lobby: &Lobby) -> crate::protocol::LobbyInfo {
+        // Build Vec<PlayerInfo>; profiles come from the cache, falling back to the DB for offline players
+        let mut profiles = self.user_directory.resolve_many(&lobby.players).await;
+        let players = lobby.players.iter()
+            .filter_map(|player_id| {
+                profiles.remove(player_id).map(|profile| {
+                    crate::protocol::PlayerInfo::new(player_id.clone(), profile, lobby.ready.contains(player_id))
+                })
+            })
+            .collect();
+
+        crate::protocol::LobbyInfo {
+            id: lobby.id,
+            code: lobby.code.clone(),
+            host: lobby.host.clone(),
+            players,
+            max_players: lobby.max_players,
+            settings: lobby.settings.clone(),
+            bot_count: self.count_bots(&lobby.players).await,
+            region: self.connection_manager.region(&lobby.host).await,
+            latency_ms: self.table_latency_ms(&lobby.players).await,
+        }
+    }
+
+    /// Slowest measured round trip among `players`, rounded so small jitter doesn't
+    /// republish the lobby
+    async fn table_latency_ms(&self, players: &[PlayerId]) -> Option<u32> {
+        let mut slowest = None;
+        for player_id in players {
+            if let Some(ms) = self.connection_manager.latency_ms(player_id).await {
+                slowest = slowest.max(Some(ms.div_ceil(10) * 10));
+            }
+        }
+        slowest
+    }
+
     /// List all joinable lobbies
     pub async fn list_lobbies(&self) -> Vec<crate::protocol::LobbyInfo> {
         let lobbies = self.lobbies.read().await;
         
         let mut joinable_lobbies = Vec::new();
         for lobby in lobbies.values().filter(|lobby| !lobby.is_full()) {
-            // Build Vec<PlayerInfo>
-            let mut players = Vec::new();
-            for player_id in &lobby.players {
-                if let Some(username) = self.connection_manager.get_username(player_id).await {
-                    players.push(crate::protocol::PlayerInfo {
-                        id: player_id.clone(),
-                        username,
-                    });
-                }
-            }
-            
-            joinable_lobbies.push(crate::protocol::LobbyInfo {
-                id: lobby.id,
-                host: lobby.host.clone(),
-                players,
-                max_players: lobby.max_players,
-                settings: lobby.settings.clone(),
-            });
+            joinable_lobbies.push(self.lobby_info(lobby).await);
         }
         
         debug!("Listing {} joinable lobbies", joinable_lobbies.len());
         joinable_lobbies
     }
 
+    /// One page of the lobby browser for `requester`, oldest lobbies first
+    pub async fn browse_lobbies(&self, requester: &PlayerId, query: &LobbyQuery) -> LobbyPage {
+        let limit = query.limit.unwrap_or(DEFAULT_LOBBY_PAGE_SIZE).clamp(1, MAX_LOBBY_PAGE_SIZE);
+        let hosts = if query.friends_only {
+            Some(self.friends_of(requester).await)
+        } else {
+            None
+        };
+
+        let (page, next_cursor) = {
+            let lobbies = self.lobbies.read().await;
+            let index = self.index();
+            let mut matches = index.candidates(query.player_count, hosts.as_deref(), query.after)
+                .filter_map(|(cursor, id)| lobbies.get(&id).map(|lobby| (cursor, lobby)))
+                .filter(|(_, lobby)| query.include_full || !lobby.is_full());
+
+            let page: Vec<(LobbyCursor, Lobby)> = matches.by_ref()
+                .take(limit)
+                .map(|(cursor, lobby)| (cursor, lobby.clone()))
+                .collect();
+            let more = matches.next().is_some();
+            let next_cursor = page.last().filter(|_| more).map(|(cursor, _)| *cursor);
+            (page, next_cursor)
+        };
+
+        let mut infos = Vec::with_capacity(page.len());
+        for (_, lobby) in &page {
+            infos.push(self.lobby_info(lobby).await);
+        }
+        debug!("Browsing {} lobbies for {} ({:?})", infos.len(), requester, query);
+        LobbyPage { lobbies: infos, next_cursor }
+    }
+
+    /// Mutual friends of the player who could host a lobby; guests have none
+    async fn friends_of(&self, player_id: &PlayerId) -> Vec<PlayerId> {
+        let Ok(user_id) = Uuid::parse_str(player_id) else {
+            return Vec::new();
+        };
+        match crate::friends::mutual_friends(&self.db, user_id).await {
+            Ok(friends) => friends.iter().map(Uuid::to_string).collect(),
+            Err(e) => {
+                warn!("Failed to load friends of {} for the lobby browser: {}", player_id, e);
+                Vec::new()
+            }
+        }
+    }
+
+    fn index(&self) -> std::sync::MutexGuard<'_, LobbyIndex> {
+        self.index.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     /// Get a lobby by ID (helper method)
     pub async fn get_lobby(&self, lobby_id: LobbyId) -> Option<Lobby> {
         let lobbies = self.lobbies.read().await;
         lobbies.get(&lobby_id).cloned()
     }
 
+    /// The open lobby named by an id or a short code
+    pub async fn find_lobby(&self, reference: &IdOrCode) -> Option<LobbyId> {
+        let lobbies = self.lobbies.read().await;
+        match reference {
+            IdOrCode::Id(id) => lobbies.contains_key(id).then_some(*id),
+            IdOrCode::Code(code) => lobbies.values().find(|l| l.code == *code).map(|l| l.id),
+        }
+    }
+
+    /// Reload the lobbies that were open when the server last stopped, with their members,
+    /// so players who reconnect land back in them. Ready marks are not kept.
+    pub async fn restore_lobbies(&self) -> usize {
+        use crate::entities::{lobby, lobby_player};
+        use sea_orm::QueryOrder;
+        let rows = lobby::Entity::find()
+            .filter(lobby::Column::ClosedAt.is_null())
+            .filter(lobby::Column::DeletedAt.is_null())
+            .order_by_asc(lobby::Column::CreatedAt)
+            .find_with_related(lobby_player::Entity)
+            .all(&self.db)
+            .await;
+        let rows = match rows {
+            Ok(rows) => rows,
+            Err(e) => {
+                warn!("Failed to load open lobbies: {}", e);
+                return 0;
+            }
+        };
+
+        let mut lobbies = self.lobbies.write().await;
+        let mut restored = 0;
+        for (row, members) in rows {
+            if self.register(&mut lobbies, row, members) {
+                restored += 1;
+            }
+        }
+        restored
+    }
+
+    /// Load a lobby a moderator restored. Members who joined another lobby since stay there;
+    /// with none left to return, the lobby is closed again. Returns false if it did not reopen.
+    pub async fn reload_lobby(&self, lobby_id: LobbyId) -> bool {
+        use crate::entities::{lobby, lobby_player};
+        let rows = lobby::Entity::find_by_id(lobby_id)
+            .filter(lobby::Column::ClosedAt.is_null())
+            .filter(lobby::Column::DeletedAt.is_null())
+            .find_with_related(lobby_player::Entity)
+            .all(&self.db)
+            .await;
+        let (row, members) = match rows {
+            Ok(mut rows) if !rows.is_empty() => rows.remove(0),
+            Ok(_) => return false,
+            Err(e) => {
+                warn!("Failed to load restored lobby {}: {}", lobby_id, e);
+                return false;
+            }
+        };
+
+        let rooms = self.connection_manager.rooms();
+        let (members, moved_on): (Vec<_>, Vec<_>) = members.into_iter()
+            .filter(|m| m.left_at.is_none())
+            .partition(|m| rooms.lobby_of(&m.player_id.to_string()).is_none_or(|id| id == lobby_id));
+        for member in moved_on {
+            self.writes.push(DbWrite::LobbyLeft { lobby_id, player_id: member.player_id, left_at: Utc::now() });
+        }
+
+        let mut lobbies = self.lobbies.write().await;
+        if self.register(&mut lobbies, row, members) {
+            info!("Lobby {} restored", lobby_id);
+            return true;
+        }
+        drop(lobbies);
+        self.mark_closed(lobby_id);
+        false
+    }
+
+    /// Put a stored lobby with its current members back in `lobbies`; false if it has none,
+    /// is already there or has unreadable settings
+    fn register(
+        &self,
+        lobbies: &mut HashMap<LobbyId, Lobby>,
+        row: crate::entities::lobby::Model,
+        mut members: Vec<crate::entities::lobby_player::Model>,
+    ) -> bool {
+        let Ok(settings) = serde_json::from_value::<GameSettings>(row.settings) else {
+            warn!("Lobby {} has unreadable settings; not restoring it", row.id);
+            return false;
+        };
+        members.retain(|m| m.left_at.is_none());
+        members.sort_by_key(|m| m.joined_at);
+        let players: Vec<PlayerId> = members.iter().map(|m| m.player_id.to_string()).collect();
+        if players.is_empty() || lobbies.contains_key(&row.id) {
+            return false;
+        }
+        let host = row.host_id.to_string();
+        let host = if players.contains(&host) { host } else { players[0].clone() };
+        let code = row.code.as_deref().and_then(ShortCode::parse)
+            .filter(|code| !lobbies.values().any(|l| l.code == *code))
+            .unwrap_or_else(|| ShortCode::generate_unique(|code| lobbies.values().any(|l| l.code == *code)));
+        // Keep the lobby's age across the restart
+        let age = (Utc::now() - row.created_at).to_std().unwrap_or_default();
+        let created_at = Instant::now().checked_sub(age).unwrap_or_else(Instant::now);
+
+        let max_players = row.max_players.max(0) as usize;
+        for player_id in &players {
+            self.connection_manager.rooms().join(player_id, RoomId::Lobby(row.id));
+        }
+        self.index().insert(row.id, max_players, &host);
+        lobbies.insert(row.id, Lobby {
+            id: row.id,
+            code,
+            host,
+            players,
+            max_players,
+            created_at,
+            settings,
+            ready: HashSet::new(),
+            // Idle time starts over after a restart
+            last_activity: Instant::now(),
+        });
+        true
+    }
+
     /// Start a game from a lobby
     pub async fn start_game(&self, lobby_id: LobbyId, caller: PlayerId) -> Result<GameId, crate::error::LobbyError> {
         // Get lobby info before removing it
-        let players = {
+        let (players, settings) = {
             let lobbies = self.lobbies.read().await;
             let lobby = lobbies.get(&lobby_id)
                 .ok_or(crate::error::LobbyError::LobbyNotFound)?;
@@ -237,25 +721,36 @@ impl LobbyManager {
                 return Err(crate::error::LobbyError::NotEnoughPlayers);
             }
 
-            lobby.players.clone()
+            // Everyone except the host must have readied up
+            if !lobby.all_ready() {
+                warn!("Lobby {} cannot start game until all players are ready", lobby_id);
+                return Err(crate::error::LobbyError::PlayersNotReady);
+            }
+
+            // Enforce the bot policy against the final roster
+            self.check_bot_policy(lobby).await?;
+
+            // Someone may have reconnected from another player's address since joining
+            if lobby.settings.shared_address == SharedAddressPolicy::Prevent && !self.shared_addresses(&lobby.players).await.is_empty() {
+                warn!("Lobby {} cannot start game with players sharing an address", lobby_id);
+                return Err(crate::error::LobbyError::SharedAddress);
+            }
+
+            (lobby.players.clone(), lobby.settings.clone())
         };
 
         info!("Starting game from lobby {} with {} players", lobby_id, players.len());
 
         // Create the game (passes lobby_id for DB linking)
-        let game_id = self.game_manager.create_game_from_lobby(players, Some(lobby_id)).await;
+        let game_id = self.game_manager.create_hosted_game(players, Some(lobby_id), settings, caller).await;
 
         // Remove the lobby after game starts
         let mut lobbies = self.lobbies.write().await;
         lobbies.remove(&lobby_id);
-        
-        // Mark lobby as closed in DB
-        use sea_orm::sea_query::Expr;
-        let _ = crate::entities::lobby::Entity::update_many()
-            .col_expr(crate::entities::lobby::Column::ClosedAt, Expr::value(Utc::now()))
-            .filter(crate::entities::lobby::Column::Id.eq(lobby_id))
-            .exec(&self.db).await;
-        
+        self.index().remove(lobby_id);
+        drop(lobbies);
+        self.mark_closed(lobby_id);
+
         info!("Lobby {} removed after game {} started", lobby_id, game_id);
 
         Ok(game_id)
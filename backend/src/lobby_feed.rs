@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use crate::connection::ConnectionManager;
+use crate::lobby::{LobbyId, LobbyManager};
+use crate::protocol::{LobbyInfo, ServerMessage};
+use tracing::{debug, warn};
+
+pub const DEFAULT_LOBBY_LIST_INTERVAL: Duration = Duration::from_millis(250);
+
+/// What changed in the lobby browser since the previous broadcast
+#[derive(Debug, Default)]
+pub struct LobbyListDiff {
+    pub changed: Vec<LobbyInfo>,
+    pub removed: Vec<LobbyId>,
+}
+
+impl LobbyListDiff {
+    /// Compare the lobbies last sent with the current list, updating `sent` to match
+    fn between(sent: &mut HashMap<LobbyId, serde_json::Value>, current: Vec<LobbyInfo>) -> Self {
+        let mut diff = Self::default();
+        let mut seen = HashMap::with_capacity(current.len());
+        for lobby in current {
+            let value = match serde_json::to_value(&lobby) {
+                Ok(value) => value,
+                Err(e) => {
+                    warn!("Failed to serialize lobby {}: {}", lobby.id, e);
+                    continue;
+                }
+            };
+            if sent.get(&lobby.id) != Some(&value) {
+                diff.changed.push(lobby.clone());
+            }
+            seen.insert(lobby.id, value);
+        }
+        diff.removed = sent.keys().filter(|id| !seen.contains_key(id)).copied().collect();
+        *sent = seen;
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, utoipa::ToSchema)]
+pub struct LobbyFeedStats {
+    /// Lobby changes that asked for a broadcast
+    pub requested: u64,
+    /// Deltas actually sent
+    pub broadcasts: u64,
+    /// Requests folded into a scheduled broadcast or that changed nothing visible;
+    /// once pending broadcasts go out, `requested == broadcasts + suppressed`
+    pub suppressed: u64,
+}
+
+#[derive(Default)]
+struct FeedState {
+    sent: HashMap<LobbyId, serde_json::Value>,
+    last_broadcast: Option<Instant>,
+    flush_scheduled: bool,
+}
+
+/// Coalesces lobby browser updates so a burst of lobby churn costs one broadcast.
+///
+/// The first change after a quiet period goes out at once; changes within `interval` of a
+/// broadcast are folded into a single trailing one. Each broadcast is a LobbyListDelta
+/// against the previous broadcast.
+#[derive(Clone)]
+pub struct LobbyFeed {
+    lobby_manager: Arc<LobbyManager>,
+    connection_manager: Arc<ConnectionManager>,
+    interval: Duration,
+    state: Arc<Mutex<FeedState>>,
+    requested: Arc<AtomicU64>,
+    broadcasts: Arc<AtomicU64>,
+    suppressed: Arc<AtomicU64>,
+}
+
+impl LobbyFeed {
+    pub fn new(lobby_manager: Arc<LobbyManager>, connection_manager: Arc<ConnectionManager>, interval: Duration) -> Self {
+        Self {
+            lobby_manager,
+            connection_manager,
+            interval,
+            state: Arc::new(Mutex::new(FeedState::default())),
+            requested: Arc::new(AtomicU64::new(0)),
+            broadcasts: Arc::new(AtomicU64::new(0)),
+            suppressed: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn stats(&self) -> LobbyFeedStats {
+        LobbyFeedStats {
+            requested: self.requested.load(Ordering::Relaxed),
+            broadcasts: self.broadcasts.load(Ordering::Relaxed),
+            suppressed: self.suppressed.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Note that the lobby list changed; broadcasts now or schedules a trailing broadcast
+    pub async fn lobbies_changed(&self) {
+        self.requested.fetch_add(1, Ordering::Relaxed);
+        let mut state = self.state.lock().await;
+        if state.flush_scheduled {
+            self.suppressed.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
+        let wait = state.last_broadcast
+            .map(|at| self.interval.saturating_sub(at.elapsed()))
+            .unwrap_or(Duration::ZERO);
+        if wait.is_zero() {
+            self.flush(&mut state).await;
+            return;
+        }
+
+        state.flush_scheduled = true;
+        let feed = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(wait).await;
+            let mut state = feed.state.lock().await;
+            state.flush_scheduled = false;
+            feed.flush(&mut state).await;
+        });
+    }
+
+    async fn flush(&self, state: &mut FeedState) {
+        let lobbies = self.lobby_manager.list_lobbies().await;
+        let diff = LobbyListDiff::between(&mut state.sent, lobbies);
+        state.last_broadcast = Some(Instant::now());
+        if diff.is_empty() {
+            self.suppressed.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
+        debug!("Broadcasting lobby list delta: {} changed, {} removed", diff.changed.len(), diff.removed.len());
+        self.broadcasts.fetch_add(1, Ordering::Relaxed);
+        let msg = ServerMessage::LobbyListDelta { changed: diff.changed, removed: diff.removed };
+        let all_players = self.connection_manager.get_active_players().await;
+        self.connection_manager.broadcast_to_players(&all_players, msg).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::GameSettings;
+    use crate::short_code::ShortCode;
+    use uuid::Uuid;
+
+    fn lobby(players: usize) -> LobbyInfo {
+        LobbyInfo {
+            id: Uuid::new_v4(),
+            code: ShortCode::generate(),
+            host: "host".to_string(),
+            players: Vec::new(),
+            max_players: players,
+            settings: GameSettings::default(),
+            bot_count: 0,
+            region: None,
+            latency_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_reports_only_changes() {
+        let mut sent = HashMap::new();
+        let (a, b) = (lobby(4), lobby(3));
+
+        let first = LobbyListDiff::between(&mut sent, vec![a.clone(), b.clone()]);
+        assert_eq!(first.changed.len(), 2);
+        assert!(first.removed.is_empty());
+
+        assert!(LobbyListDiff::between(&mut sent, vec![a.clone(), b.clone()]).is_empty());
+
+        let mut grown = b.clone();
+        grown.max_players = 5;
+        let next = LobbyListDiff::between(&mut sent, vec![grown]);
+        assert_eq!(next.changed.iter().map(|l| l.id).collect::<Vec<_>>(), vec![b.id]);
+        assert_eq!(next.removed, vec![a.id]);
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::{BTreeMap, HashMap};
+use crate::connection::PlayerId;
+use crate::lobby::LobbyId;
+
+pub use crate::protocol::LobbyCursor;
+
+#[derive(Debug)]
+struct Entry {
+    cursor: LobbyCursor,
+    size: usize,
+    host: PlayerId,
+}
+
+/// Browser indexes over the open lobbies, so filtered pages only visit lobbies that can match.
+///
+/// LobbyManager updates it under the same write lock as its lobby map.
+#[derive(Debug, Default)]
+pub struct LobbyIndex {
+    next_cursor: LobbyCursor,
+    entries: HashMap<LobbyId, Entry>,
+    all: BTreeMap<LobbyCursor, LobbyId>,
+    by_size: HashMap<usize, BTreeMap<LobbyCursor, LobbyId>>,
+    by_host: HashMap<PlayerId, BTreeMap<LobbyCursor, LobbyId>>,
+}
+
+impl LobbyIndex {
+    /// Add a new lobby at the end of the listing order
+    pub fn insert(&mut self, lobby_id: LobbyId, size: usize, host: &PlayerId) {
+        self.remove(lobby_id);
+        let cursor = self.next_cursor;
+        self.next_cursor += 1;
+
+        self.all.insert(cursor, lobby_id);
+        self.by_size.entry(size).or_default().insert(cursor, lobby_id);
+        self.by_host.entry(host.clone()).or_default().insert(cursor, lobby_id);
+        self.entries.insert(lobby_id, Entry { cursor, size, host: host.clone() });
+    }
+
+    pub fn remove(&mut self, lobby_id: LobbyId) {
+        let Some(entry) = self.entries.remove(&lobby_id) else { return };
+        self.all.remove(&entry.cursor);
+        if let Some(lobbies) = self.by_size.get_mut(&entry.size) {
+            lobbies.remove(&entry.cursor);
+            if lobbies.is_empty() {
+                self.by_size.remove(&entry.size);
+            }
+        }
+        self.unlink_host(&entry.host, entry.cursor);
+    }
+
+    /// Record a host change; the lobby keeps its place in the listing
+    pub fn set_host(&mut self, lobby_id: LobbyId, host: &PlayerId) {
+        let Some(entry) = self.entries.get_mut(&lobby_id) else { return };
+        let (previous, cursor) = (std::mem::replace(&mut entry.host, host.clone()), entry.cursor);
+        self.unlink_host(&previous, cursor);
+        self.by_host.entry(host.clone()).or_default().insert(cursor, lobby_id);
+    }
+
+    fn unlink_host(&mut self, host: &PlayerId, cursor: LobbyCursor) {
+        if let Some(lobbies) = self.by_host.get_mut(host) {
+            lobbies.remove(&cursor);
+            if lobbies.is_empty() {
+                self.by_host.remove(host);
+            }
+        }
+    }
+
+    /// Lobbies after `after` in listing order, for `size` players and hosted by one of
+    /// `hosts` where given
+    pub fn candidates<'a>(
+        &'a self,
+        size: Option<usize>,
+        hosts: Option<&[PlayerId]>,
+        after: Option<LobbyCursor>,
+    ) -> Box<dyn Iterator<Item = (LobbyCursor, LobbyId)> + 'a> {
+        let start = after.map_or(0, |cursor| cursor.saturating_add(1));
+
+        if let Some(hosts) = hosts {
+            // Host lists are short, so merging them up front costs little
+            let mut merged = BTreeMap::new();
+            for lobbies in hosts.iter().filter_map(|host| self.by_host.get(host)) {
+                merged.extend(lobbies.range(start..).map(|(c, id)| (*c, *id)));
+            }
+            let entries = &self.entries;
+            return Box::new(merged.into_iter().filter(move |(_, id)| {
+                size.is_none_or(|size| entries.get(id).is_some_and(|e| e.size == size))
+            }));
+        }
+
+        let lobbies = match size {
+            Some(size) => match self.by_size.get(&size) {
+                Some(lobbies) => lobbies,
+                None => return Box::new(std::iter::empty()),
+            },
+            None => &self.all,
+        };
+        Box::new(lobbies.range(start..).map(|(c, id)| (*c, *id)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn ids(candidates: impl Iterator<Item = (LobbyCursor, LobbyId)>) -> Vec<LobbyId> {
+        candidates.map(|(_, id)| id).collect()
+    }
+
+    #[test]
+    fn test_candidates_follow_creation_order_and_filters() {
+        let mut index = LobbyIndex::default();
+        let (alice, bob) = ("alice".to_string(), "bob".to_string());
+        let lobbies: Vec<LobbyId> = (0..4).map(|_| Uuid::new_v4()).collect();
+        index.insert(lobbies[0], 4, &alice);
+        index.insert(lobbies[1], 3, &bob);
+        index.insert(lobbies[2], 4, &bob);
+        index.insert(lobbies[3], 3, &alice);
+
+        assert_eq!(ids(index.candidates(None, None, None)), lobbies);
+        assert_eq!(ids(index.candidates(Some(3), None, None)), vec![lobbies[1], lobbies[3]]);
+        assert_eq!(ids(index.candidates(None, Some(std::slice::from_ref(&bob)), None)), vec![lobbies[1], lobbies[2]]);
+        assert_eq!(ids(index.candidates(Some(4), Some(std::slice::from_ref(&bob)), None)), vec![lobbies[2]]);
+        assert!(index.candidates(Some(5), None, None).next().is_none());
+
+        let (cursor, _) = index.candidates(None, None, None).nth(1).unwrap();
+        assert_eq!(ids(index.candidates(None, None, Some(cursor))), lobbies[2..].to_vec());
+    }
+
+    #[test]
+    fn test_removal_and_host_changes_keep_indexes_in_step() {
+        let mut index = LobbyIndex::default();
+        let (alice, bob) = ("alice".to_string(), "bob".to_string());
+        let first = Uuid::new_v4();
+        let second = Uuid::new_v4();
+        index.insert(first, 4, &alice);
+        index.insert(second, 4, &alice);
+
+        index.set_host(first, &bob);
+        assert_eq!(ids(index.candidates(None, Some(std::slice::from_ref(&bob)), None)), vec![first]);
+        assert_eq!(ids(index.candidates(None, Some(std::slice::from_ref(&alice)), None)), vec![second]);
+        assert_eq!(ids(index.candidates(None, None, None)), vec![first, second]);
+
+        index.remove(first);
+        index.remove(first);
+        assert_eq!(index.len(), 1);
+        assert!(index.candidates(None, Some(&[bob]), None).next().is_none());
+        assert!(!index.by_host.contains_key("bob"));
+    }
+}
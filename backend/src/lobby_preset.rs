@@ -0,0 +1,132 @@
+use chrono::{DateTime, Utc};
+#[cfg(feature = "server")]
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, Set};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+#[cfg(feature = "server")]
+use crate::entities::lobby_preset;
+#[cfg(feature = "server")]
+use crate::error::PresetError;
+use crate::protocol::GameSettings;
+#[cfg(feature = "server")]
+use tracing::warn;
+
+/// Presets a single user can keep
+pub const MAX_PRESETS_PER_USER: usize = 20;
+
+pub const MAX_PRESET_NAME_LEN: usize = 40;
+
+/// A named bundle of lobby settings saved by a user
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LobbyPreset {
+    pub id: Uuid,
+    pub name: String,
+    pub settings: GameSettings,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[cfg(feature = "server")]
+impl LobbyPreset {
+    /// None if the stored settings no longer deserialize
+    fn from_model(model: lobby_preset::Model) -> Option<Self> {
+        match serde_json::from_value(model.settings) {
+            Ok(settings) => Some(Self {
+                id: model.id,
+                name: model.name,
+                settings,
+                updated_at: model.updated_at,
+            }),
+            Err(e) => {
+                warn!("Skipping unreadable lobby preset {}: {}", model.id, e);
+                None
+            }
+        }
+    }
+}
+
+/// A user's presets in name order
+#[cfg(feature = "server")]
+pub async fn list_presets(db: &DatabaseConnection, user_id: Uuid) -> Result<Vec<LobbyPreset>, PresetError> {
+    let rows = lobby_preset::Entity::find()
+        .filter(lobby_preset::Column::UserId.eq(user_id))
+        .order_by_asc(lobby_preset::Column::Name)
+        .all(db)
+        .await?;
+    Ok(rows.into_iter().filter_map(LobbyPreset::from_model).collect())
+}
+
+#[cfg(feature = "server")]
+pub async fn get_preset(db: &DatabaseConnection, user_id: Uuid, preset_id: Uuid) -> Result<LobbyPreset, PresetError> {
+    lobby_preset::Entity::find_by_id(preset_id)
+        .filter(lobby_preset::Column::UserId.eq(user_id))
+        .one(db)
+        .await?
+        .and_then(LobbyPreset::from_model)
+        .ok_or(PresetError::NotFound)
+}
+
+/// Save a preset, replacing the user's existing preset of the same name
+#[cfg(feature = "server")]
+pub async fn save_preset(
+    db: &DatabaseConnection,
+    user_id: Uuid,
+    name: &str,
+    settings: &GameSettings,
+) -> Result<LobbyPreset, PresetError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_PRESET_NAME_LEN {
+        return Err(PresetError::InvalidName(MAX_PRESET_NAME_LEN));
+    }
+
+    let now = Utc::now();
+    let existing = lobby_preset::Entity::find()
+        .filter(lobby_preset::Column::UserId.eq(user_id))
+        .filter(lobby_preset::Column::Name.eq(name))
+        .one(db)
+        .await?;
+
+    let model = match existing {
+        Some(existing) => {
+            let mut active: lobby_preset::ActiveModel = existing.into();
+            active.settings = Set(serde_json::json!(settings));
+            active.updated_at = Set(now);
+            active.update(db).await?
+        }
+        None => {
+            let count = lobby_preset::Entity::find()
+                .filter(lobby_preset::Column::UserId.eq(user_id))
+                .count(db)
+                .await?;
+            if count as usize >= MAX_PRESETS_PER_USER {
+                return Err(PresetError::LimitReached(MAX_PRESETS_PER_USER));
+            }
+
+            lobby_preset::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                user_id: Set(user_id),
+                name: Set(name.to_string()),
+                settings: Set(serde_json::json!(settings)),
+                created_at: Set(now),
+                updated_at: Set(now),
+            }
+            .insert(db)
+            .await?
+        }
+    };
+
+    LobbyPreset::from_model(model).ok_or(PresetError::NotFound)
+}
+
+#[cfg(feature = "server")]
+pub async fn delete_preset(db: &DatabaseConnection, user_id: Uuid, preset_id: Uuid) -> Result<(), PresetError> {
+    let result = lobby_preset::Entity::delete_many()
+        .filter(lobby_preset::Column::Id.eq(preset_id))
+        .filter(lobby_preset::Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+    if result.rows_affected == 0 {
+        return Err(PresetError::NotFound);
+    }
+    Ok(())
+}
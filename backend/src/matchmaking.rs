@@ -0,0 +1,315 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use crate::connection::PlayerId;
+use crate::error::MatchmakingError;
+use crate::region::Region;
+use tracing::{debug, info};
+
+/// Smallest table the queue will form
+pub const MIN_QUEUE_PLAYERS: usize = 2;
+/// Largest table the queue will form
+pub const MAX_QUEUE_PLAYERS: usize = 6;
+
+/// Result of joining the queue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueueOutcome {
+    /// Still waiting; position is 1-based
+    Waiting { position: usize },
+    /// Enough players were queued; these players should be seated together
+    Matched(Vec<PlayerId>),
+}
+
+/// A table of opted-in players who waited too long, to be completed with bots
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackfillMatch {
+    pub player_count: usize,
+    /// Queued players in the order they joined
+    pub players: Vec<PlayerId>,
+    /// Seats left for bots
+    pub bots: usize,
+}
+
+#[derive(Debug, Clone)]
+struct QueueEntry {
+    player_id: PlayerId,
+    joined_at: Instant,
+    /// Accepts a table completed with bots
+    backfill: bool,
+    region: Option<Region>,
+}
+
+/// First-come-first-served queues, one per requested table size. Players from the same
+/// region are seated together first.
+pub struct MatchmakingQueue {
+    queues: RwLock<HashMap<usize, VecDeque<QueueEntry>>>,
+    /// How long opted-in players wait before bots fill their table; None never
+    backfill_after: Option<Duration>,
+    /// How long players wait for their own region before accepting anyone
+    region_wait: Duration,
+}
+
+impl Default for MatchmakingQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MatchmakingQueue {
+    pub fn new() -> Self {
+        Self {
+            queues: RwLock::new(HashMap::new()),
+            backfill_after: None,
+            region_wait: Duration::ZERO,
+        }
+    }
+
+    /// Keep players to tables from their own region until they have waited `wait`
+    pub fn with_region_wait(mut self, wait: Duration) -> Self {
+        self.region_wait = wait;
+        self
+    }
+
+    /// Offer bot backfill to players who opt in, once they have waited `wait`
+    pub fn with_backfill_after(mut self, wait: Duration) -> Self {
+        self.backfill_after = Some(wait);
+        self
+    }
+
+    pub fn backfill_after(&self) -> Option<Duration> {
+        self.backfill_after
+    }
+
+    /// Add a player to the queue for the given table size, accepting bots in the empty seats
+    /// after the backfill wait if `backfill` is set
+    pub async fn join(
+        &self,
+        player_id: PlayerId,
+        player_count: usize,
+        backfill: bool,
+        region: Option<Region>,
+    ) -> Result<QueueOutcome, MatchmakingError> {
+        if !(MIN_QUEUE_PLAYERS..=MAX_QUEUE_PLAYERS).contains(&player_count) {
+            return Err(MatchmakingError::InvalidPlayerCount);
+        }
+
+        let mut queues = self.queues.write().await;
+        if queues.values().flatten().any(|entry| entry.player_id == player_id) {
+            return Err(MatchmakingError::AlreadyQueued);
+        }
+
+        let queue = queues.entry(player_count).or_default();
+        let now = Instant::now();
+        queue.push_back(QueueEntry { player_id: player_id.clone(), joined_at: now, backfill, region });
+        debug!("Player {} queued for {}-player game ({} waiting)", player_id, player_count, queue.len());
+
+        // Tables the joiner isn't part of are left for `take_due_matches`
+        let joiner = queue.len() - 1;
+        if let Some(seats) = next_table(queue, player_count, now, self.region_wait).filter(|seats| seats.contains(&joiner)) {
+            let players = take_seats(queue, &seats);
+            info!("Matchmaking formed {}-player game: {:?}", player_count, players);
+            return Ok(QueueOutcome::Matched(players));
+        }
+
+        Ok(QueueOutcome::Waiting { position: queue.len() })
+    }
+
+    /// Form every table that has become possible by `now` because players waited out the
+    /// region wait. Returns the table size and players of each.
+    pub async fn take_due_matches(&self, now: Instant) -> Vec<(usize, Vec<PlayerId>)> {
+        let mut queues = self.queues.write().await;
+        let mut matches = Vec::new();
+        for (&player_count, queue) in queues.iter_mut() {
+            while let Some(seats) = next_table(queue, player_count, now, self.region_wait) {
+                let players = take_seats(queue, &seats);
+                info!("Matchmaking formed {}-player game across regions: {:?}", player_count, players);
+                matches.push((player_count, players));
+            }
+        }
+        matches
+    }
+
+    /// Remove a player from whichever queue they are in
+    pub async fn leave(&self, player_id: &PlayerId) -> Result<(), MatchmakingError> {
+        let mut queues = self.queues.write().await;
+        for queue in queues.values_mut() {
+            if let Some(index) = queue.iter().position(|entry| entry.player_id == *player_id) {
+                queue.remove(index);
+                debug!("Player {} left matchmaking queue", player_id);
+                return Ok(());
+            }
+        }
+        Err(MatchmakingError::NotQueued)
+    }
+
+    /// Check whether a player is waiting in any queue
+    pub async fn is_queued(&self, player_id: &PlayerId) -> bool {
+        let queues = self.queues.read().await;
+        queues.values().flatten().any(|entry| entry.player_id == *player_id)
+    }
+
+    /// Take every opted-in player from each queue whose longest-waiting opted-in player has
+    /// waited out the backfill wait by `now`. Players who did not opt in keep their place.
+    pub async fn take_backfills(&self, now: Instant) -> Vec<BackfillMatch> {
+        let Some(wait) = self.backfill_after else { return Vec::new() };
+        let mut queues = self.queues.write().await;
+        let mut matches = Vec::new();
+        for (&player_count, queue) in queues.iter_mut() {
+            let due = queue.iter()
+                .find(|entry| entry.backfill)
+                .is_some_and(|entry| now.saturating_duration_since(entry.joined_at) >= wait);
+            if !due {
+                continue;
+            }
+            // A full queue always forms a table on join, so there is at least one seat for a bot
+            let (opted_in, waiting): (VecDeque<QueueEntry>, VecDeque<QueueEntry>) =
+                std::mem::take(queue).into_iter().partition(|entry| entry.backfill);
+            *queue = waiting;
+            let players: Vec<PlayerId> = opted_in.into_iter().map(|entry| entry.player_id).collect();
+            info!("Matchmaking backfilled {}-player game with {} bots: {:?}", player_count, player_count - players.len(), players);
+            matches.push(BackfillMatch { player_count, bots: player_count - players.len(), players });
+        }
+        matches
+    }
+}
+
+/// Queue positions of the next table: the earliest players sharing a region, or failing
+/// that the earliest players who have waited `region_wait` and accept anyone
+fn next_table(queue: &VecDeque<QueueEntry>, player_count: usize, now: Instant, region_wait: Duration) -> Option<Vec<usize>> {
+    let seat = |keep: &dyn Fn(&QueueEntry) -> bool| -> Option<Vec<usize>> {
+        let seats: Vec<usize> = queue.iter()
+            .enumerate()
+            .filter(|(_, entry)| keep(entry))
+            .map(|(index, _)| index)
+            .take(player_count)
+            .collect();
+        (seats.len() == player_count).then_some(seats)
+    };
+    queue.iter()
+        .find_map(|first| seat(&|entry| entry.region == first.region))
+        .or_else(|| seat(&|entry| now.saturating_duration_since(entry.joined_at) >= region_wait))
+}
+
+/// Remove the entries at `seats` (ascending) and return their players in queue order
+fn take_seats(queue: &mut VecDeque<QueueEntry>, seats: &[usize]) -> Vec<PlayerId> {
+    let mut players: Vec<PlayerId> = seats.iter().rev().filter_map(|&index| queue.remove(index)).map(|entry| entry.player_id).collect();
+    players.reverse();
+    players
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(n: u8) -> PlayerId {
+        format!("player{}", n)
+    }
+
+    #[tokio::test]
+    async fn test_forms_game_when_enough_players() {
+        let queue = MatchmakingQueue::new();
+
+        assert_eq!(queue.join(player(1), 3, false, None).await.unwrap(), QueueOutcome::Waiting { position: 1 });
+        assert_eq!(queue.join(player(2), 3, false, None).await.unwrap(), QueueOutcome::Waiting { position: 2 });
+        assert_eq!(
+            queue.join(player(3), 3, false, None).await.unwrap(),
+            QueueOutcome::Matched(vec![player(1), player(2), player(3)])
+        );
+        assert!(!queue.is_queued(&player(1)).await);
+    }
+
+    #[tokio::test]
+    async fn test_queues_are_separate_per_player_count() {
+        let queue = MatchmakingQueue::new();
+
+        queue.join(player(1), 3, false, None).await.unwrap();
+        let outcome = queue.join(player(2), 2, false, None).await.unwrap();
+        assert_eq!(outcome, QueueOutcome::Waiting { position: 1 });
+    }
+
+    #[tokio::test]
+    async fn test_rejects_duplicates_and_invalid_counts() {
+        let queue = MatchmakingQueue::new();
+
+        queue.join(player(1), 4, false, None).await.unwrap();
+        assert!(matches!(queue.join(player(1), 3, false, None).await, Err(MatchmakingError::AlreadyQueued)));
+        assert!(matches!(queue.join(player(2), 1, false, None).await, Err(MatchmakingError::InvalidPlayerCount)));
+        assert!(matches!(queue.join(player(2), 7, false, None).await, Err(MatchmakingError::InvalidPlayerCount)));
+    }
+
+    #[tokio::test]
+    async fn test_leave() {
+        let queue = MatchmakingQueue::new();
+
+        queue.join(player(1), 2, false, None).await.unwrap();
+        queue.leave(&player(1)).await.unwrap();
+        assert!(matches!(queue.leave(&player(1)).await, Err(MatchmakingError::NotQueued)));
+
+        // Leaving frees the slot, so the next two players are matched with each other
+        queue.join(player(2), 2, false, None).await.unwrap();
+        assert_eq!(
+            queue.join(player(3), 2, false, None).await.unwrap(),
+            QueueOutcome::Matched(vec![player(2), player(3)])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_backfills_opted_in_players_after_the_wait() {
+        let wait = Duration::from_secs(60);
+        let queue = MatchmakingQueue::new().with_backfill_after(wait);
+
+        queue.join(player(1), 4, true, None).await.unwrap();
+        queue.join(player(2), 4, false, None).await.unwrap();
+        queue.join(player(3), 4, true, None).await.unwrap();
+        let now = Instant::now();
+        assert!(queue.take_backfills(now).await.is_empty());
+
+        let matches = queue.take_backfills(now + wait).await;
+        assert_eq!(matches, vec![BackfillMatch { player_count: 4, players: vec![player(1), player(3)], bots: 2 }]);
+        // Players who did not opt in keep waiting for humans
+        assert!(queue.is_queued(&player(2)).await);
+        assert!(!queue.is_queued(&player(1)).await);
+        assert!(queue.take_backfills(now + wait * 2).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prefers_players_from_the_same_region() {
+        let wait = Duration::from_secs(20);
+        let queue = MatchmakingQueue::new().with_region_wait(wait);
+        let (eu, us) = (Region::parse("eu-west"), Region::parse("us-east"));
+
+        queue.join(player(1), 2, false, eu.clone()).await.unwrap();
+        assert_eq!(queue.join(player(2), 2, false, us).await.unwrap(), QueueOutcome::Waiting { position: 2 });
+        assert_eq!(
+            queue.join(player(3), 2, false, eu).await.unwrap(),
+            QueueOutcome::Matched(vec![player(1), player(3)])
+        );
+
+        // A lone player is seated with anyone once both have waited long enough
+        queue.join(player(4), 2, false, None).await.unwrap();
+        let now = Instant::now();
+        assert!(queue.take_due_matches(now).await.is_empty());
+        assert_eq!(queue.take_due_matches(now + wait).await, vec![(2, vec![player(2), player(4)])]);
+        assert!(!queue.is_queued(&player(2)).await);
+    }
+
+    #[tokio::test]
+    async fn test_regions_ignored_without_a_wait() {
+        let queue = MatchmakingQueue::new();
+
+        queue.join(player(1), 2, false, Region::parse("eu-west")).await.unwrap();
+        assert_eq!(
+            queue.join(player(2), 2, false, Region::parse("us-east")).await.unwrap(),
+            QueueOutcome::Matched(vec![player(1), player(2)])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_no_backfill_unless_enabled() {
+        let queue = MatchmakingQueue::new();
+
+        queue.join(player(1), 2, true, None).await.unwrap();
+        assert!(queue.take_backfills(Instant::now() + Duration::from_secs(3600)).await.is_empty());
+        assert!(queue.is_queued(&player(1)).await);
+    }
+}
@@ -29,7 +29,7 @@ impl MigrationTrait for Migration {
                     .col(ColumnDef::new(Lobbies::Id).uuid().not_null().primary_key())
                     .col(ColumnDef::new(Lobbies::HostId).uuid().not_null())
                     .col(ColumnDef::new(Lobbies::MaxPlayers).integer().not_null().default(4))
-                    .col(ColumnDef::new(Lobbies::Settings).json_binary().not_null())
+                    .col(super::json_binary(manager, Lobbies::Settings).not_null())
                     .col(ColumnDef::new(Lobbies::CreatedAt).timestamp_with_time_zone().not_null().default(Expr::current_timestamp()))
                     .col(ColumnDef::new(Lobbies::ClosedAt).timestamp_with_time_zone().null())
                     .foreign_key(
@@ -74,7 +74,7 @@ impl MigrationTrait for Migration {
                     .if_not_exists()
                     .col(ColumnDef::new(Games::Id).uuid().not_null().primary_key())
                     .col(ColumnDef::new(Games::LobbyId).uuid().null())
-                    .col(ColumnDef::new(Games::State).json_binary().not_null())
+                    .col(super::json_binary(manager, Games::State).not_null())
                     .col(ColumnDef::new(Games::CreatedAt).timestamp_with_time_zone().not_null().default(Expr::current_timestamp()))
                     .col(ColumnDef::new(Games::CompletedAt).timestamp_with_time_zone().null())
                     .foreign_key(
@@ -120,9 +120,9 @@ impl MigrationTrait for Migration {
                     .col(ColumnDef::new(GameRounds::Id).integer().not_null().auto_increment().primary_key())
                     .col(ColumnDef::new(GameRounds::GameId).uuid().not_null())
                     .col(ColumnDef::new(GameRounds::RoundNumber).integer().not_null())
-                    .col(ColumnDef::new(GameRounds::Bids).json_binary().not_null())
-                    .col(ColumnDef::new(GameRounds::TricksWon).json_binary().not_null())
-                    .col(ColumnDef::new(GameRounds::Scores).json_binary().not_null())
+                    .col(super::json_binary(manager, GameRounds::Bids).not_null())
+                    .col(super::json_binary(manager, GameRounds::TricksWon).not_null())
+                    .col(super::json_binary(manager, GameRounds::Scores).not_null())
                     .foreign_key(
                         ForeignKey::create()
                             .from(GameRounds::Table, GameRounds::GameId)
@@ -6,19 +6,14 @@ pub struct Migration;
 #[async_trait::async_trait]
 impl MigrationTrait for Migration {
     async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
-        // Drop old columns
-        manager
-            .alter_table(
-                Table::alter()
-                    .table(GameRounds::Table)
-                    .drop_column(GameRounds::Bids)
-                    .drop_column(GameRounds::TricksWon)
-                    .drop_column(GameRounds::Scores)
-                    .to_owned(),
-            )
-            .await?;
+        // Drop old columns, one statement each as SQLite requires
+        for column in [GameRounds::Bids, GameRounds::TricksWon, GameRounds::Scores] {
+            manager
+                .alter_table(Table::alter().table(GameRounds::Table).drop_column(column).to_owned())
+                .await?;
+        }
 
-        // Add new player_results column
+        // Add new player_results column; SQLite needs a default to add a NOT NULL column
         manager
             .alter_table(
                 Table::alter()
@@ -27,6 +22,7 @@ impl MigrationTrait for Migration {
                         ColumnDef::new(GameRounds::PlayerResults)
                             .json()
                             .not_null()
+                            .default("[]")
                     )
                     .to_owned(),
             )
@@ -47,16 +43,16 @@ impl MigrationTrait for Migration {
             .await?;
 
         // Re-add old columns
-        manager
-            .alter_table(
-                Table::alter()
-                    .table(GameRounds::Table)
-                    .add_column(ColumnDef::new(GameRounds::Bids).json().not_null())
-                    .add_column(ColumnDef::new(GameRounds::TricksWon).json().not_null())
-                    .add_column(ColumnDef::new(GameRounds::Scores).json().not_null())
-                    .to_owned(),
-            )
-            .await?;
+        for column in [GameRounds::Bids, GameRounds::TricksWon, GameRounds::Scores] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(GameRounds::Table)
+                        .add_column(ColumnDef::new(column).json().not_null().default("{}"))
+                        .to_owned(),
+                )
+                .await?;
+        }
 
         Ok(())
     }
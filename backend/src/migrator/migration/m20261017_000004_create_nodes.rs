@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Shared registry of backend instances and their load
+        manager
+            .create_table(
+                Table::create()
+                    .table(Nodes::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Nodes::Id).string_len(64).not_null().primary_key())
+                    .col(ColumnDef::new(Nodes::PublicUrl).text().not_null())
+                    .col(ColumnDef::new(Nodes::ActiveGames).integer().not_null().default(0))
+                    .col(ColumnDef::new(Nodes::MaxGames).integer().not_null())
+                    .col(ColumnDef::new(Nodes::LastHeartbeat).timestamp_with_time_zone().not_null().default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(Nodes::Table).to_owned()).await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Nodes {
+    Table,
+    Id,
+    PublicUrl,
+    ActiveGames,
+    MaxGames,
+    LastHeartbeat,
+}
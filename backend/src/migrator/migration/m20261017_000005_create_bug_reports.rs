@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Automatically filed diagnostic bundles for games that hit an invariant violation or unexpected error
+        manager
+            .create_table(
+                Table::create()
+                    .table(BugReports::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(BugReports::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(BugReports::GameId).uuid().not_null())
+                    .col(ColumnDef::new(BugReports::Reason).text().not_null())
+                    .col(super::json_binary(manager, BugReports::Bundle).not_null())
+                    .col(ColumnDef::new(BugReports::CreatedAt).timestamp_with_time_zone().not_null().default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_bug_reports_game_id")
+                    .table(BugReports::Table)
+                    .col(BugReports::GameId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(BugReports::Table).to_owned()).await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum BugReports {
+    Table,
+    Id,
+    GameId,
+    Reason,
+    Bundle,
+    CreatedAt,
+}
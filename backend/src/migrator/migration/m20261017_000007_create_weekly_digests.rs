@@ -0,0 +1,93 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Users can opt out of the weekly summary
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(ColumnDef::new(Users::WeeklyDigest).boolean().not_null().default(true))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WeeklyDigests::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(WeeklyDigests::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(WeeklyDigests::UserId).uuid().not_null())
+                    .col(ColumnDef::new(WeeklyDigests::WeekStart).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(WeeklyDigests::GamesPlayed).integer().not_null())
+                    .col(ColumnDef::new(WeeklyDigests::Wins).integer().not_null())
+                    .col(ColumnDef::new(WeeklyDigests::RatingChange).integer().null())
+                    .col(ColumnDef::new(WeeklyDigests::BestGameId).uuid().null())
+                    .col(ColumnDef::new(WeeklyDigests::BestScore).integer().null())
+                    .col(ColumnDef::new(WeeklyDigests::CreatedAt).timestamp_with_time_zone().not_null().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_weekly_digests_user")
+                            .from(WeeklyDigests::Table, WeeklyDigests::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // One digest per user per week; also makes the job safe to re-run
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_weekly_digests_user_week")
+                    .table(WeeklyDigests::Table)
+                    .col(WeeklyDigests::UserId)
+                    .col(WeeklyDigests::WeekStart)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(WeeklyDigests::Table).to_owned()).await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::WeeklyDigest)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+    WeeklyDigest,
+}
+
+#[derive(DeriveIden)]
+enum WeeklyDigests {
+    Table,
+    Id,
+    UserId,
+    WeekStart,
+    GamesPlayed,
+    Wins,
+    RatingChange,
+    BestGameId,
+    BestScore,
+    CreatedAt,
+}
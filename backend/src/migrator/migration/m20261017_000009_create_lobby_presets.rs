@@ -0,0 +1,68 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LobbyPresets::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(LobbyPresets::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(LobbyPresets::UserId).uuid().not_null())
+                    .col(ColumnDef::new(LobbyPresets::Name).string().not_null())
+                    .col(super::json_binary(manager, LobbyPresets::Settings).not_null())
+                    .col(ColumnDef::new(LobbyPresets::CreatedAt).timestamp_with_time_zone().not_null().default(Expr::current_timestamp()))
+                    .col(ColumnDef::new(LobbyPresets::UpdatedAt).timestamp_with_time_zone().not_null().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_lobby_presets_user")
+                            .from(LobbyPresets::Table, LobbyPresets::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Saving under an existing name replaces that preset
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_lobby_presets_user_name")
+                    .table(LobbyPresets::Table)
+                    .col(LobbyPresets::UserId)
+                    .col(LobbyPresets::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(LobbyPresets::Table).to_owned()).await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum LobbyPresets {
+    Table,
+    Id,
+    UserId,
+    Name,
+    Settings,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,64 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Tournaments::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Tournaments::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(Tournaments::Name).string().not_null())
+                    .col(ColumnDef::new(Tournaments::WebhookUrl).string().null())
+                    .col(ColumnDef::new(Tournaments::WebhookSecret).string().not_null())
+                    .col(ColumnDef::new(Tournaments::CreatedAt).timestamp_with_time_zone().not_null().default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Games played as part of a tournament
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Games::Table)
+                    .add_column(ColumnDef::new(Games::TournamentId).uuid().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Games::Table)
+                    .drop_column(Games::TournamentId)
+                    .to_owned(),
+            )
+            .await?;
+        manager.drop_table(Table::drop().table(Tournaments::Table).to_owned()).await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Tournaments {
+    Table,
+    Id,
+    Name,
+    WebhookUrl,
+    WebhookSecret,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Games {
+    Table,
+    TournamentId,
+}
@@ -0,0 +1,67 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RoundDeals::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(RoundDeals::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(RoundDeals::GameId).uuid().not_null())
+                    .col(ColumnDef::new(RoundDeals::RoundNumber).integer().not_null())
+                    .col(ColumnDef::new(RoundDeals::TrumpSuit).string_len(16).null())
+                    .col(super::json_binary(manager, RoundDeals::Hands).not_null())
+                    .col(ColumnDef::new(RoundDeals::DealtAt).timestamp_with_time_zone().not_null().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_round_deals_game")
+                            .from(RoundDeals::Table, RoundDeals::GameId)
+                            .to(Games::Table, Games::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_round_deals_game_round")
+                    .table(RoundDeals::Table)
+                    .col(RoundDeals::GameId)
+                    .col(RoundDeals::RoundNumber)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(RoundDeals::Table).to_owned()).await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Games {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum RoundDeals {
+    Table,
+    Id,
+    GameId,
+    RoundNumber,
+    TrumpSuit,
+    Hands,
+    DealtAt,
+}
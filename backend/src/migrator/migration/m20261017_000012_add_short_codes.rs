@@ -0,0 +1,72 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Codes are only unique among live lobbies and games, so the indexes are not unique
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Lobbies::Table)
+                    .add_column(ColumnDef::new(Lobbies::Code).string_len(6).null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Games::Table)
+                    .add_column(ColumnDef::new(Games::Code).string_len(6).null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_lobbies_code")
+                    .table(Lobbies::Table)
+                    .col(Lobbies::Code)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_games_code")
+                    .table(Games::Table)
+                    .col(Games::Code)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_index(Index::drop().name("idx_games_code").table(Games::Table).to_owned()).await?;
+        manager.drop_index(Index::drop().name("idx_lobbies_code").table(Lobbies::Table).to_owned()).await?;
+        manager
+            .alter_table(Table::alter().table(Games::Table).drop_column(Games::Code).to_owned())
+            .await?;
+        manager
+            .alter_table(Table::alter().table(Lobbies::Table).drop_column(Lobbies::Code).to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Lobbies {
+    Table,
+    Code,
+}
+
+#[derive(DeriveIden)]
+enum Games {
+    Table,
+    Code,
+}
@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PasswordResetTokens::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(PasswordResetTokens::Id).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(PasswordResetTokens::UserId).uuid().not_null())
+                    .col(ColumnDef::new(PasswordResetTokens::TokenHash).string_len(64).not_null().unique_key())
+                    .col(ColumnDef::new(PasswordResetTokens::CreatedAt).timestamp_with_time_zone().not_null().default(Expr::current_timestamp()))
+                    .col(ColumnDef::new(PasswordResetTokens::ExpiresAt).timestamp_with_time_zone().not_null())
+                    .col(ColumnDef::new(PasswordResetTokens::UsedAt).timestamp_with_time_zone().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_password_reset_tokens_user")
+                            .from(PasswordResetTokens::Table, PasswordResetTokens::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Tokens issued before this time are no longer accepted
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column(ColumnDef::new(Users::SessionsValidAfter).timestamp_with_time_zone().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::SessionsValidAfter)
+                    .to_owned(),
+            )
+            .await?;
+        manager.drop_table(Table::drop().table(PasswordResetTokens::Table).to_owned()).await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+    SessionsValidAfter,
+}
+
+#[derive(DeriveIden)]
+enum PasswordResetTokens {
+    Table,
+    Id,
+    UserId,
+    TokenHash,
+    CreatedAt,
+    ExpiresAt,
+    UsedAt,
+}
@@ -0,0 +1,41 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Public profile shown to other players, and lobby settings to start from
+        // One column per statement, as SQLite requires
+        let columns = [
+            ColumnDef::new(Users::DisplayName).string_len(32).null().to_owned(),
+            ColumnDef::new(Users::Avatar).string_len(32).null().to_owned(),
+            super::json_binary(manager, Users::PreferredSettings).null().to_owned(),
+        ];
+        for column in columns {
+            manager
+                .alter_table(Table::alter().table(Users::Table).add_column(column).to_owned())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [Users::DisplayName, Users::Avatar, Users::PreferredSettings] {
+            manager
+                .alter_table(Table::alter().table(Users::Table).drop_column(column).to_owned())
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    DisplayName,
+    Avatar,
+    PreferredSettings,
+}
@@ -0,0 +1,68 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One row per direction; two users are friends once both have added each other
+        manager
+            .create_table(
+                Table::create()
+                    .table(Friendships::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Friendships::UserId).uuid().not_null())
+                    .col(ColumnDef::new(Friendships::FriendId).uuid().not_null())
+                    .col(ColumnDef::new(Friendships::CreatedAt).timestamp_with_time_zone().not_null().default(Expr::current_timestamp()))
+                    .primary_key(Index::create().col(Friendships::UserId).col(Friendships::FriendId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_friendships_user")
+                            .from(Friendships::Table, Friendships::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_friendships_friend")
+                            .from(Friendships::Table, Friendships::FriendId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_friendships_friend_id")
+                    .table(Friendships::Table)
+                    .col(Friendships::FriendId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(Friendships::Table).to_owned()).await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Friendships {
+    Table,
+    UserId,
+    FriendId,
+    CreatedAt,
+}
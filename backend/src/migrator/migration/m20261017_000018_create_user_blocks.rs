@@ -0,0 +1,57 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserBlocks::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(UserBlocks::UserId).uuid().not_null())
+                    .col(ColumnDef::new(UserBlocks::BlockedId).uuid().not_null())
+                    .col(ColumnDef::new(UserBlocks::CreatedAt).timestamp_with_time_zone().not_null().default(Expr::current_timestamp()))
+                    .primary_key(Index::create().col(UserBlocks::UserId).col(UserBlocks::BlockedId))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_blocks_user")
+                            .from(UserBlocks::Table, UserBlocks::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_blocks_blocked")
+                            .from(UserBlocks::Table, UserBlocks::BlockedId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(UserBlocks::Table).to_owned()).await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum UserBlocks {
+    Table,
+    UserId,
+    BlockedId,
+    CreatedAt,
+}
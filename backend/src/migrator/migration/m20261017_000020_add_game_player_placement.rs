@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Finishing place after tie-breaks, set when the game completes
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GamePlayers::Table)
+                    .add_column(ColumnDef::new(GamePlayers::Placement).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(GamePlayers::Table)
+                    .drop_column(GamePlayers::Placement)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum GamePlayers {
+    Table,
+    Placement,
+}
@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The suit the user scored best under that week
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WeeklyDigests::Table)
+                    .add_column(ColumnDef::new(WeeklyDigests::BestTrump).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(WeeklyDigests::Table)
+                    .drop_column(WeeklyDigests::BestTrump)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum WeeklyDigests {
+    Table,
+    BestTrump,
+}
@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(GameActions::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(GameActions::Id).integer().not_null().auto_increment().primary_key())
+                    .col(ColumnDef::new(GameActions::GameId).uuid().not_null())
+                    .col(ColumnDef::new(GameActions::Version).big_integer().not_null())
+                    .col(ColumnDef::new(GameActions::RoundNumber).integer().not_null())
+                    .col(ColumnDef::new(GameActions::PlayerId).string().not_null())
+                    .col(super::json_binary(manager, GameActions::Action).not_null())
+                    .col(ColumnDef::new(GameActions::Auto).boolean().not_null().default(false))
+                    .col(ColumnDef::new(GameActions::PlayedAt).timestamp_with_time_zone().not_null().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_game_actions_game")
+                            .from(GameActions::Table, GameActions::GameId)
+                            .to(Games::Table, Games::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_game_actions_game_version")
+                    .table(GameActions::Table)
+                    .col(GameActions::GameId)
+                    .col(GameActions::Version)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(GameActions::Table).to_owned()).await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Games {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum GameActions {
+    Table,
+    Id,
+    GameId,
+    Version,
+    RoundNumber,
+    PlayerId,
+    Action,
+    Auto,
+    PlayedAt,
+}
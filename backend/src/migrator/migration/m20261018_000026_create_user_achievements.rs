@@ -0,0 +1,54 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserAchievements::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(UserAchievements::UserId).uuid().not_null())
+                    .col(ColumnDef::new(UserAchievements::Achievement).string().not_null())
+                    .col(ColumnDef::new(UserAchievements::Progress).integer().not_null().default(0))
+                    .col(ColumnDef::new(UserAchievements::UnlockedAt).timestamp_with_time_zone().null())
+                    .col(ColumnDef::new(UserAchievements::UpdatedAt).timestamp_with_time_zone().not_null().default(Expr::current_timestamp()))
+                    .primary_key(Index::create().col(UserAchievements::UserId).col(UserAchievements::Achievement))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_achievements_user")
+                            .from(UserAchievements::Table, UserAchievements::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager.drop_table(Table::drop().table(UserAchievements::Table).to_owned()).await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum UserAchievements {
+    Table,
+    UserId,
+    Achievement,
+    Progress,
+    UnlockedAt,
+    UpdatedAt,
+}
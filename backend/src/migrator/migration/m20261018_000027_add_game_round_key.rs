@@ -0,0 +1,46 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Keep the first copy of any round stored twice by a retried write
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "DELETE FROM game_rounds WHERE id NOT IN (SELECT MIN(id) FROM game_rounds GROUP BY game_id, round_number)",
+            )
+            .await?;
+
+        // One row per round of a game, so retried writes can skip it
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_game_rounds_game_round")
+                    .table(GameRounds::Table)
+                    .col(GameRounds::GameId)
+                    .col(GameRounds::RoundNumber)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(Index::drop().name("idx_game_rounds_game_round").table(GameRounds::Table).to_owned())
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum GameRounds {
+    Table,
+    GameId,
+    RoundNumber,
+}
@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Set when a member leaves, so a deleted lobby can be restored with its members
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(LobbyPlayers::Table)
+                    .add_column(ColumnDef::new(LobbyPlayers::LeftAt).timestamp_with_time_zone().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(LobbyPlayers::Table)
+                    .drop_column(LobbyPlayers::LeftAt)
+                    .to_owned(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum LobbyPlayers {
+    Table,
+    LeftAt,
+}
@@ -1,2 +1,43 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::DbBackend;
+
 pub mod m20241207_000001_create_tables;
 pub mod m20251207_025543_add_current_round;
+pub mod m20261017_000001_add_user_is_bot;
+pub mod m20261017_000002_add_user_time_prefs;
+pub mod m20261017_000003_add_soft_delete;
+pub mod m20261017_000004_create_nodes;
+pub mod m20261017_000005_create_bug_reports;
+pub mod m20261017_000006_add_game_segment;
+pub mod m20261017_000007_create_weekly_digests;
+pub mod m20261017_000008_add_user_suit_encoding;
+pub mod m20261017_000009_create_lobby_presets;
+pub mod m20261017_000010_create_tournaments;
+pub mod m20261017_000011_create_round_deals;
+pub mod m20261017_000012_add_short_codes;
+pub mod m20261017_000013_add_user_role;
+pub mod m20261017_000014_create_password_reset_tokens;
+pub mod m20261017_000015_add_user_profile;
+pub mod m20261017_000016_add_game_abandoned_at;
+pub mod m20261017_000017_create_friendships;
+pub mod m20261017_000018_create_user_blocks;
+pub mod m20261017_000019_add_game_outcome;
+pub mod m20261017_000020_add_game_player_placement;
+pub mod m20261017_000021_add_digest_best_trump;
+pub mod m20261018_000022_create_game_actions;
+pub mod m20261018_000023_add_game_unrated;
+pub mod m20261018_000024_add_node_region;
+pub mod m20261018_000025_create_audit_log;
+pub mod m20261018_000026_create_user_achievements;
+pub mod m20261018_000027_add_game_round_key;
+pub mod m20261018_000028_add_lobby_player_left_at;
+
+/// A JSONB column on PostgreSQL and a plain JSON one elsewhere; SQLite keeps both as text
+pub(crate) fn json_binary(manager: &SchemaManager, column: impl IntoIden) -> ColumnDef {
+    let mut def = ColumnDef::new(column);
+    match manager.get_database_backend() {
+        DbBackend::Postgres => def.json_binary(),
+        _ => def.json(),
+    };
+    def
+}
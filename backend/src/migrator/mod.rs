@@ -9,6 +9,34 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(migration::m20241207_000001_create_tables::Migration),
             Box::new(migration::m20251207_025543_add_current_round::Migration),
+            Box::new(migration::m20261017_000001_add_user_is_bot::Migration),
+            Box::new(migration::m20261017_000002_add_user_time_prefs::Migration),
+            Box::new(migration::m20261017_000003_add_soft_delete::Migration),
+            Box::new(migration::m20261017_000004_create_nodes::Migration),
+            Box::new(migration::m20261017_000005_create_bug_reports::Migration),
+            Box::new(migration::m20261017_000006_add_game_segment::Migration),
+            Box::new(migration::m20261017_000007_create_weekly_digests::Migration),
+            Box::new(migration::m20261017_000008_add_user_suit_encoding::Migration),
+            Box::new(migration::m20261017_000009_create_lobby_presets::Migration),
+            Box::new(migration::m20261017_000010_create_tournaments::Migration),
+            Box::new(migration::m20261017_000011_create_round_deals::Migration),
+            Box::new(migration::m20261017_000012_add_short_codes::Migration),
+            Box::new(migration::m20261017_000013_add_user_role::Migration),
+            Box::new(migration::m20261017_000014_create_password_reset_tokens::Migration),
+            Box::new(migration::m20261017_000015_add_user_profile::Migration),
+            Box::new(migration::m20261017_000016_add_game_abandoned_at::Migration),
+            Box::new(migration::m20261017_000017_create_friendships::Migration),
+            Box::new(migration::m20261017_000018_create_user_blocks::Migration),
+            Box::new(migration::m20261017_000019_add_game_outcome::Migration),
+            Box::new(migration::m20261017_000020_add_game_player_placement::Migration),
+            Box::new(migration::m20261017_000021_add_digest_best_trump::Migration),
+            Box::new(migration::m20261018_000022_create_game_actions::Migration),
+            Box::new(migration::m20261018_000023_add_game_unrated::Migration),
+            Box::new(migration::m20261018_000024_add_node_region::Migration),
+            Box::new(migration::m20261018_000025_create_audit_log::Migration),
+            Box::new(migration::m20261018_000026_create_user_achievements::Migration),
+            Box::new(migration::m20261018_000027_add_game_round_key::Migration),
+            Box::new(migration::m20261018_000028_add_lobby_player_left_at::Migration),
         ]
     }
 }
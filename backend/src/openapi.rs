@@ -0,0 +1,86 @@
+use axum::Json;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+/// OpenAPI description of the REST endpoints; the WebSocket protocol is in API.md
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "German Bridge server", description = "REST endpoints of the German Bridge server. Games themselves are played over the WebSocket at /ws, described in API.md."),
+    paths(
+        crate::handlers::auth::register,
+        crate::handlers::auth::login,
+        crate::server::stats_handler,
+        crate::handlers::user::game_history,
+        crate::handlers::admin::enable_game_trace,
+        crate::handlers::admin::disable_game_trace,
+        crate::handlers::admin::list_game_traces,
+        crate::handlers::admin::list_bug_reports,
+        crate::handlers::admin::get_bug_report,
+        crate::handlers::admin::restore_lobby,
+        crate::handlers::admin::delete_game,
+        crate::handlers::admin::restore_game,
+        crate::handlers::admin::list_tournaments,
+        crate::handlers::admin::create_tournament,
+        crate::handlers::admin::set_user_role,
+        crate::handlers::admin::issue_password_reset,
+        crate::handlers::admin::list_features,
+        crate::handlers::admin::set_feature,
+        crate::handlers::admin::search_audit_log,
+    ),
+    // Only used in parameters, which do not pull their schemas in
+    components(schemas(crate::audit::AuditKind, crate::features::Feature)),
+    modifiers(&SecuritySchemes),
+    tags(
+        (name = "auth", description = "Accounts and sign-in"),
+        (name = "server", description = "Server health and load"),
+        (name = "history", description = "The signed-in player's past games"),
+        (name = "moderation", description = "Open to moderators and admins"),
+        (name = "admin", description = "Admins only; changes are recorded in the audit log"),
+    ),
+)]
+pub struct ApiDoc;
+
+/// `bearer` is the JWT from register or login; `admin_token` is the shared admin token,
+/// accepted wherever a role is required
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+        );
+        components.add_security_scheme(
+            "admin_token",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-Admin-Token"))),
+        );
+    }
+}
+
+/// The spec as JSON, for `/api/docs`
+pub async fn openapi_spec() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_covers_the_rest_endpoints() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        for path in ["/api/register", "/api/login", "/stats", "/api/history", "/api/admin/games/{id}/trace", "/api/admin/audit"] {
+            assert!(spec["paths"][path].is_object(), "{} missing", path);
+        }
+        assert!(spec["paths"]["/api/admin/features/{feature}"]["put"]["requestBody"].is_object());
+
+        // Every schema a path refers to is defined
+        let text = spec.to_string();
+        for reference in text.split("\"#/components/schemas/").skip(1) {
+            let name = &reference[..reference.find('"').unwrap()];
+            assert!(spec["components"]["schemas"][name].is_object(), "schema {} missing", name);
+        }
+        assert!(spec["components"]["securitySchemes"]["admin_token"].is_object());
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+use axum::extract::ws::Message;
+use tokio::sync::mpsc::error::TryRecvError;
+use tokio::sync::Notify;
+use tracing::warn;
+use crate::protocol::ServerMessage;
+use crate::room::RoomId;
+
+/// Messages queued for one connection before the overflow policy kicks in
+pub const OUTBOUND_CAPACITY: usize = 512;
+
+/// The connection is gone; nothing more can be queued
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Closed;
+
+impl std::fmt::Display for Closed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("connection closed")
+    }
+}
+
+enum Entry {
+    /// Replies and control frames; never dropped
+    Critical(Message),
+    /// Broadcasts the client can do without
+    Droppable(Message),
+    /// Part of a lobby's or game's stream; dropping one makes the client resync the room
+    Room(RoomId, Message),
+    /// Tells the client to fetch the room's state again
+    Resync(RoomId),
+}
+
+impl Entry {
+    fn droppable(&self) -> bool {
+        matches!(self, Entry::Droppable(_) | Entry::Room(..))
+    }
+}
+
+struct Queue {
+    entries: VecDeque<Entry>,
+    /// Room messages dropped since the room's queued Resync entry
+    missed: HashMap<RoomId, u64>,
+    capacity: usize,
+    senders: usize,
+    receiver_alive: bool,
+    /// Set when only critical messages were queued and the client stopped reading them
+    stalled: bool,
+}
+
+struct Shared {
+    queue: Mutex<Queue>,
+    ready: Notify,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Queue> {
+        self.queue.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// A bounded queue of frames waiting for one WebSocket.
+///
+/// A slow client cannot make it grow without limit. When it is full, the oldest lobby or game
+/// message is dropped and the client is sent `ResyncRequired` for that room instead; other
+/// broadcasts are simply dropped. Replies and control frames are never dropped: a client that
+/// lets `capacity` of those pile up is stalled and its connection is closed.
+pub fn channel() -> (OutboundSender, OutboundReceiver) {
+    with_capacity(OUTBOUND_CAPACITY)
+}
+
+pub fn with_capacity(capacity: usize) -> (OutboundSender, OutboundReceiver) {
+    let shared = Arc::new(Shared {
+        queue: Mutex::new(Queue {
+            entries: VecDeque::new(),
+            missed: HashMap::new(),
+            capacity,
+            senders: 1,
+            receiver_alive: true,
+            stalled: false,
+        }),
+        ready: Notify::new(),
+    });
+    (OutboundSender { shared: Arc::clone(&shared) }, OutboundReceiver { shared })
+}
+
+pub struct OutboundSender {
+    shared: Arc<Shared>,
+}
+
+impl Clone for OutboundSender {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        Self { shared: Arc::clone(&self.shared) }
+    }
+}
+
+impl Drop for OutboundSender {
+    fn drop(&mut self) {
+        let last = {
+            let mut queue = self.shared.lock();
+            queue.senders -= 1;
+            queue.senders == 0
+        };
+        if last {
+            self.shared.ready.notify_one();
+        }
+    }
+}
+
+impl std::fmt::Debug for OutboundSender {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OutboundSender").field("queued", &self.shared.lock().entries.len()).finish()
+    }
+}
+
+impl OutboundSender {
+    /// Queue a reply or control frame
+    pub fn send(&self, message: Message) -> Result<(), Closed> {
+        self.push(Entry::Critical(message))
+    }
+
+    /// Queue a broadcast that may be dropped if the client falls behind
+    pub fn send_droppable(&self, message: Message) -> Result<(), Closed> {
+        self.push(Entry::Droppable(message))
+    }
+
+    /// Queue a message from a lobby's or game's stream
+    pub fn send_room(&self, room: RoomId, message: Message) -> Result<(), Closed> {
+        self.push(Entry::Room(room, message))
+    }
+
+    /// Have the client fetch the room's state again because `missed` of its messages were lost
+    pub fn resync(&self, room: RoomId, missed: u64) -> Result<(), Closed> {
+        let mut queue = self.shared.lock();
+        if !queue.receiver_alive || queue.stalled {
+            return Err(Closed);
+        }
+        queue.record_missed(room, missed);
+        drop(queue);
+        self.shared.ready.notify_one();
+        Ok(())
+    }
+
+    pub fn is_closed(&self) -> bool {
+        let queue = self.shared.lock();
+        !queue.receiver_alive || queue.stalled
+    }
+
+    fn push(&self, entry: Entry) -> Result<(), Closed> {
+        let mut queue = self.shared.lock();
+        if !queue.receiver_alive || queue.stalled {
+            return Err(Closed);
+        }
+        if queue.entries.len() >= queue.capacity {
+            match queue.entries.iter().position(Entry::droppable) {
+                Some(oldest) => {
+                    if let Some(Entry::Room(room, _)) = queue.entries.remove(oldest) {
+                        queue.record_missed(room, 1);
+                    }
+                }
+                None if entry.droppable() => {
+                    if let Entry::Room(room, _) = entry {
+                        queue.record_missed(room, 1);
+                    }
+                    return Ok(());
+                }
+                None => {
+                    warn!("Outbound queue full of undelivered replies; closing the connection");
+                    queue.stalled = true;
+                    queue.entries.clear();
+                    drop(queue);
+                    self.shared.ready.notify_one();
+                    return Err(Closed);
+                }
+            }
+        }
+        queue.entries.push_back(entry);
+        drop(queue);
+        self.shared.ready.notify_one();
+        Ok(())
+    }
+}
+
+impl Queue {
+    fn record_missed(&mut self, room: RoomId, missed: u64) {
+        let count = self.missed.entry(room).or_insert(0);
+        if *count == 0 {
+            self.entries.push_back(Entry::Resync(room));
+        }
+        *count += missed;
+    }
+
+    fn pop(&mut self) -> Option<Message> {
+        loop {
+            let message = match self.entries.pop_front()? {
+                Entry::Critical(message) | Entry::Droppable(message) | Entry::Room(_, message) => message,
+                Entry::Resync(room) => {
+                    let missed = self.missed.remove(&room).unwrap_or(0);
+                    match serde_json::to_string(&ServerMessage::ResyncRequired { room, missed }) {
+                        Ok(json) => Message::Text(json),
+                        Err(_) => continue,
+                    }
+                }
+            };
+            return Some(message);
+        }
+    }
+}
+
+pub struct OutboundReceiver {
+    shared: Arc<Shared>,
+}
+
+impl Drop for OutboundReceiver {
+    fn drop(&mut self) {
+        let mut queue = self.shared.lock();
+        queue.receiver_alive = false;
+        queue.entries.clear();
+        queue.missed.clear();
+    }
+}
+
+impl OutboundReceiver {
+    /// The next frame to write, or None once every sender is gone or the client stalled
+    pub async fn recv(&mut self) -> Option<Message> {
+        loop {
+            match self.try_recv() {
+                Ok(message) => return Some(message),
+                Err(TryRecvError::Disconnected) => return None,
+                Err(TryRecvError::Empty) => self.shared.ready.notified().await,
+            }
+        }
+    }
+
+    pub fn try_recv(&mut self) -> Result<Message, TryRecvError> {
+        let mut queue = self.shared.lock();
+        if queue.stalled {
+            return Err(TryRecvError::Disconnected);
+        }
+        match queue.pop() {
+            Some(message) => Ok(message),
+            None if queue.senders == 0 => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn text(n: usize) -> Message {
+        Message::Text(n.to_string())
+    }
+
+    #[test]
+    fn test_full_queue_drops_oldest_room_message_and_asks_for_resync() {
+        let (tx, mut rx) = with_capacity(3);
+        let room = RoomId::Game(Uuid::new_v4());
+        tx.send(text(0)).unwrap();
+        tx.send_room(room, text(1)).unwrap();
+        tx.send_room(room, text(2)).unwrap();
+        tx.send_room(room, text(3)).unwrap();
+        tx.send_room(room, text(4)).unwrap();
+
+        assert_eq!(rx.try_recv().unwrap(), text(0));
+        // 1 and 2 made way for one resync notice, which the rest of the stream follows
+        let Ok(Message::Text(resync)) = rx.try_recv() else { panic!("no resync notice") };
+        assert!(matches!(
+            serde_json::from_str(&resync).unwrap(),
+            ServerMessage::ResyncRequired { room: r, missed: 2 } if r == room
+        ));
+        assert_eq!(rx.try_recv().unwrap(), text(3));
+        assert_eq!(rx.try_recv().unwrap(), text(4));
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+    }
+
+    #[test]
+    fn test_droppable_broadcasts_give_way_but_replies_do_not() {
+        let (tx, mut rx) = with_capacity(2);
+        tx.send(text(0)).unwrap();
+        tx.send(text(1)).unwrap();
+        // Nothing can make way, so the broadcast is the one dropped
+        tx.send_droppable(text(2)).unwrap();
+        assert_eq!(rx.try_recv().unwrap(), text(0));
+        tx.send_droppable(text(3)).unwrap();
+        tx.send(text(4)).unwrap();
+        assert_eq!(rx.try_recv().unwrap(), text(1));
+        assert_eq!(rx.try_recv().unwrap(), text(4));
+    }
+
+    #[test]
+    fn test_stalled_client_is_closed() {
+        let (tx, mut rx) = with_capacity(2);
+        tx.send(text(0)).unwrap();
+        tx.send(text(1)).unwrap();
+        assert_eq!(tx.send(text(2)), Err(Closed));
+        assert!(tx.is_closed());
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Disconnected)));
+    }
+
+    #[test]
+    fn test_dropped_ends_either_side() {
+        let (tx, rx) = channel();
+        drop(rx);
+        assert_eq!(tx.send(text(0)), Err(Closed));
+
+        let (tx, mut rx) = channel();
+        tx.send(text(0)).unwrap();
+        drop(tx);
+        assert_eq!(rx.try_recv().unwrap(), text(0));
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Disconnected)));
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use crate::engine::PlayerId;
+use crate::engine::GamePhase;
+
+/// The slowest player of a round is only nudged when they took at least this many times
+/// the average of everyone else
+const NOTICE_FACTOR: f64 = 1.5;
+
+/// Rounds this short never trigger a notice; nobody needs hurrying through a quick round
+const NOTICE_MIN_ROUND_TIME: Duration = Duration::from_secs(20);
+
+/// Time one player spent deciding, per phase
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerPacing {
+    pub player_id: PlayerId,
+    pub bids: u32,
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub bidding_ms: u64,
+    pub plays: u32,
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub playing_ms: u64,
+}
+
+impl PlayerPacing {
+    pub fn total_ms(&self) -> u64 {
+        self.bidding_ms + self.playing_ms
+    }
+}
+
+/// How long each player takes to act, measured from the start of their turn to their action
+#[derive(Debug, Clone, Default)]
+pub struct PacingTracker {
+    turn: Option<(PlayerId, GamePhase, Instant)>,
+    totals: HashMap<PlayerId, PlayerPacing>,
+    round: HashMap<PlayerId, Duration>,
+}
+
+impl PacingTracker {
+    /// Start timing `player_id`'s turn; a turn already running is dropped unrecorded
+    pub fn start_turn(&mut self, player_id: &PlayerId, phase: GamePhase, now: Instant) {
+        self.turn = matches!(phase, GamePhase::Bidding | GamePhase::Playing)
+            .then(|| (player_id.clone(), phase, now));
+    }
+
+    /// Record that `player_id` acted; ignored unless it is their timed turn
+    pub fn record_action(&mut self, player_id: &PlayerId, now: Instant) {
+        let Some((_, phase, started)) = self.turn.take_if(|(turn_player, _, _)| turn_player == player_id) else {
+            return;
+        };
+        let elapsed = now.saturating_duration_since(started);
+        let totals = self.totals.entry(player_id.clone())
+            .or_insert_with(|| PlayerPacing { player_id: player_id.clone(), ..Default::default() });
+        let ms = elapsed.as_millis() as u64;
+        if phase == GamePhase::Bidding {
+            totals.bids += 1;
+            totals.bidding_ms += ms;
+        } else {
+            totals.plays += 1;
+            totals.playing_ms += ms;
+        }
+        *self.round.entry(player_id.clone()).or_default() += elapsed;
+    }
+
+    /// Close the round's tally and name its slowest player among `candidates`, if they were
+    /// notably slower than the rest: their time and the average of everyone else
+    pub fn finish_round(&mut self, candidates: &[PlayerId]) -> Option<(PlayerId, Duration, Duration)> {
+        let round = std::mem::take(&mut self.round);
+        let times: Vec<(&PlayerId, Duration)> = candidates.iter()
+            .map(|pid| (pid, round.get(pid).copied().unwrap_or_default()))
+            .collect();
+        if times.len() < 2 {
+            return None;
+        }
+
+        let (slowest, slowest_time) = times.iter().copied().max_by_key(|(_, time)| *time)?;
+        let others: Duration = times.iter().filter(|(pid, _)| *pid != slowest).map(|(_, time)| *time).sum();
+        let others_average = others / (times.len() as u32 - 1);
+        let notable = slowest_time >= NOTICE_MIN_ROUND_TIME
+            && slowest_time.as_secs_f64() >= others_average.as_secs_f64() * NOTICE_FACTOR;
+        notable.then(|| (slowest.clone(), slowest_time, others_average))
+    }
+
+    /// Per-player totals in seat order, with zeros for players who never acted
+    pub fn report(&self, players: &[PlayerId]) -> Vec<PlayerPacing> {
+        players.iter()
+            .map(|pid| self.totals.get(pid).cloned()
+                .unwrap_or_else(|| PlayerPacing { player_id: pid.clone(), ..Default::default() }))
+            .collect()
+    }
+
+    /// Move `old`'s times over to `new`, who takes over their seat
+    pub fn replace_player(&mut self, old: &PlayerId, new: &PlayerId) {
+        if let Some(mut totals) = self.totals.remove(old) {
+            totals.player_id = new.clone();
+            self.totals.insert(new.clone(), totals);
+        }
+        if let Some(time) = self.round.remove(old) {
+            self.round.insert(new.clone(), time);
+        }
+        if let Some((player_id, _, _)) = self.turn.as_mut().filter(|(player_id, _, _)| player_id == old) {
+            *player_id = new.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracks_phases_and_nudges_only_notably_slow_players() {
+        let (a, b, c) = ("a".to_string(), "b".to_string(), "c".to_string());
+        let players = vec![a.clone(), b.clone(), c.clone()];
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let mut pacing = PacingTracker::default();
+
+        pacing.start_turn(&a, GamePhase::Bidding, at(0));
+        pacing.record_action(&a, at(2));
+        pacing.start_turn(&b, GamePhase::Bidding, at(2));
+        // Out of turn actions are not timed
+        pacing.record_action(&c, at(3));
+        pacing.record_action(&b, at(32));
+        pacing.start_turn(&c, GamePhase::Playing, at(32));
+        pacing.record_action(&c, at(36));
+
+        let report = pacing.report(&players);
+        assert_eq!((report[0].bids, report[0].bidding_ms), (1, 2_000));
+        assert_eq!(report[1].bidding_ms, 30_000);
+        assert_eq!((report[2].bids, report[2].plays, report[2].playing_ms), (0, 1, 4_000));
+
+        let (slowest, time, others) = pacing.finish_round(&players).unwrap();
+        assert_eq!((slowest, time, others), (b.clone(), Duration::from_secs(30), Duration::from_secs(3)));
+
+        // An even round names nobody
+        for (i, pid) in players.iter().enumerate() {
+            pacing.start_turn(pid, GamePhase::Playing, at(100 + 30 * i as u64));
+            pacing.record_action(pid, at(125 + 30 * i as u64));
+        }
+        assert_eq!(pacing.finish_round(&players), None);
+        assert_eq!(pacing.report(&players)[1].total_ms(), 55_000);
+    }
+}
@@ -0,0 +1,116 @@
+use chrono::{DateTime, Duration, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Set};
+use sea_orm::sea_query::Expr;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use crate::auth::Claims;
+use crate::entities::{password_reset_token, user};
+
+/// How long a reset token can be redeemed for
+pub const RESET_TOKEN_TTL: Duration = Duration::hours(1);
+
+/// A freshly issued reset token; `token` is not stored and cannot be shown again
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
+pub struct IssuedResetToken {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ResetError {
+    #[error("Invalid or expired reset token")]
+    InvalidToken,
+    #[error("Database error: {0}")]
+    Database(#[from] DbErr),
+}
+
+fn token_hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Create a single-use reset token for a user
+pub async fn issue_reset_token(db: &DatabaseConnection, user_id: Uuid, now: DateTime<Utc>) -> Result<IssuedResetToken, DbErr> {
+    let token: String = rand::thread_rng().sample_iter(&Alphanumeric).take(40).map(char::from).collect();
+    let expires_at = now + RESET_TOKEN_TTL;
+
+    password_reset_token::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        token_hash: Set(token_hash(&token)),
+        created_at: Set(now),
+        expires_at: Set(expires_at),
+        used_at: Set(None),
+    }
+    .insert(db)
+    .await?;
+
+    Ok(IssuedResetToken { token, expires_at })
+}
+
+/// Set a new password with a reset token.
+///
+/// The token and any other outstanding tokens for the user are used up, and every session
+/// token issued before now stops being accepted. Returns the user whose password changed.
+pub async fn redeem_reset_token(
+    db: &DatabaseConnection,
+    token: &str,
+    password_hash: String,
+    now: DateTime<Utc>,
+) -> Result<Uuid, ResetError> {
+    let reset = password_reset_token::Entity::find()
+        .filter(password_reset_token::Column::TokenHash.eq(token_hash(token)))
+        .filter(password_reset_token::Column::UsedAt.is_null())
+        .filter(password_reset_token::Column::ExpiresAt.gt(now))
+        .one(db)
+        .await?
+        .ok_or(ResetError::InvalidToken)?;
+
+    // Claim the token first so two concurrent redemptions can't both succeed
+    let claimed = password_reset_token::Entity::update_many()
+        .col_expr(password_reset_token::Column::UsedAt, Expr::value(now))
+        .filter(password_reset_token::Column::Id.eq(reset.id))
+        .filter(password_reset_token::Column::UsedAt.is_null())
+        .exec(db)
+        .await?;
+    if claimed.rows_affected == 0 {
+        return Err(ResetError::InvalidToken);
+    }
+
+    user::Entity::update_many()
+        .col_expr(user::Column::PasswordHash, Expr::value(password_hash))
+        .col_expr(user::Column::SessionsValidAfter, Expr::value(now))
+        .filter(user::Column::Id.eq(reset.user_id))
+        .exec(db)
+        .await?;
+
+    password_reset_token::Entity::update_many()
+        .col_expr(password_reset_token::Column::UsedAt, Expr::value(now))
+        .filter(password_reset_token::Column::UserId.eq(reset.user_id))
+        .filter(password_reset_token::Column::UsedAt.is_null())
+        .exec(db)
+        .await?;
+
+    Ok(reset.user_id)
+}
+
+/// Whether a session token was issued after the user's sessions were last invalidated. Tokens
+/// that only carry whole seconds must be from a later second.
+pub fn session_is_current(account: &user::Model, claims: &Claims) -> bool {
+    match account.sessions_valid_after {
+        Some(valid_after) => claims.issued_at_millis() > valid_after.timestamp_millis(),
+        None => true,
+    }
+}
+
+/// The token's user, unless the session has been invalidated
+pub async fn session_account(db: &DatabaseConnection, claims: &Claims) -> Result<Option<user::Model>, DbErr> {
+    let Ok(user_id) = Uuid::parse_str(&claims.sub) else { return Ok(None) };
+    let account = user::Entity::find_by_id(user_id).one(db).await?;
+    Ok(account.filter(|account| session_is_current(account, claims)))
+}
+
+/// Look up the token's user and check the session has not been invalidated
+pub async fn check_session(db: &DatabaseConnection, claims: &Claims) -> Result<bool, DbErr> {
+    Ok(session_account(db, claims).await?.is_some())
+}
@@ -0,0 +1,177 @@
+#[cfg(feature = "server")]
+use std::collections::HashMap;
+#[cfg(feature = "server")]
+use std::sync::Arc;
+#[cfg(feature = "server")]
+use sea_orm::{DatabaseConnection, DbErr};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use tokio::sync::{broadcast, Mutex};
+use uuid::Uuid;
+#[cfg(feature = "server")]
+use crate::connection::{ConnectionManager, PlayerId};
+#[cfg(feature = "server")]
+use crate::friends;
+use crate::protocol::{GameId, LobbyId};
+#[cfg(feature = "server")]
+use crate::protocol::ServerMessage;
+#[cfg(feature = "server")]
+use crate::room::RoomRegistry;
+#[cfg(feature = "server")]
+use tracing::{debug, warn};
+
+/// Pending signals kept for a slow presence task before the oldest are dropped
+#[cfg(feature = "server")]
+const SIGNAL_CAPACITY: usize = 1024;
+
+/// What a player's friends see of them
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status")]
+pub enum Presence {
+    Offline,
+    Online,
+    InLobby { lobby_id: LobbyId },
+    /// Seated at a game; with several, the active one
+    InGame { game_id: GameId },
+}
+
+/// Fires with a player's id whenever their session or rooms may have changed
+#[cfg(feature = "server")]
+#[derive(Debug, Clone)]
+pub struct PresenceSignal {
+    tx: broadcast::Sender<PlayerId>,
+}
+
+#[cfg(feature = "server")]
+impl Default for PresenceSignal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "server")]
+impl PresenceSignal {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(SIGNAL_CAPACITY);
+        Self { tx }
+    }
+
+    pub fn notify(&self, player_id: &PlayerId) {
+        // No receiver just means presence is not being published
+        let _ = self.tx.send(player_id.clone());
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<PlayerId> {
+        self.tx.subscribe()
+    }
+}
+
+/// A mutual friend and what they are doing
+#[derive(Debug, Clone, Serialize)]
+pub struct FriendPresence {
+    pub user_id: Uuid,
+    pub username: String,
+    pub presence: Presence,
+}
+
+/// Tells players when their mutual friends come online, join a lobby or sit down at a game
+#[cfg(feature = "server")]
+pub struct PresenceService {
+    connection_manager: Arc<ConnectionManager>,
+    rooms: Arc<RoomRegistry>,
+    db: DatabaseConnection,
+    /// Last presence sent for each player not offline, so repeated signals stay quiet
+    published: Mutex<HashMap<PlayerId, Presence>>,
+}
+
+#[cfg(feature = "server")]
+impl PresenceService {
+    pub fn new(connection_manager: Arc<ConnectionManager>, db: DatabaseConnection) -> Self {
+        Self {
+            rooms: connection_manager.rooms(),
+            connection_manager,
+            db,
+            published: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// A player's presence from their session and rooms. Players inside their reconnect
+    /// window count as offline.
+    pub async fn presence_of(&self, player_id: &PlayerId) -> Presence {
+        if !self.connection_manager.is_connected(player_id).await {
+            return Presence::Offline;
+        }
+        if let Some(game_id) = self.rooms.active_game(player_id) {
+            return Presence::InGame { game_id };
+        }
+        match self.rooms.lobby_of(player_id) {
+            Some(lobby_id) => Presence::InLobby { lobby_id },
+            None => Presence::Online,
+        }
+    }
+
+    /// Send PresenceUpdate to the player's connected mutual friends if their presence changed
+    pub async fn publish(&self, player_id: &PlayerId) {
+        let presence = self.presence_of(player_id).await;
+        {
+            let mut published = self.published.lock().await;
+            let previous = if presence == Presence::Offline {
+                published.remove(player_id)
+            } else {
+                published.insert(player_id.clone(), presence)
+            };
+            if previous.unwrap_or(Presence::Offline) == presence {
+                return;
+            }
+        }
+
+        // Guests have no account and so no friends
+        let Ok(user_id) = Uuid::parse_str(player_id) else { return };
+        let friends = match friends::mutual_friends(&self.db, user_id).await {
+            Ok(friends) => friends,
+            Err(e) => {
+                warn!("Failed to load friends of {} for presence: {}", player_id, e);
+                return;
+            }
+        };
+        if friends.is_empty() {
+            return;
+        }
+
+        debug!("Publishing presence of {} to {} friends: {:?}", player_id, friends.len(), presence);
+        let recipients: Vec<PlayerId> = friends.iter().map(Uuid::to_string).collect();
+        let msg = ServerMessage::PresenceUpdate { player_id: player_id.clone(), presence };
+        self.connection_manager.broadcast_to_players(&recipients, msg).await;
+    }
+
+    /// Presence of each of the user's mutual friends, by username
+    pub async fn friends_presence(&self, user_id: Uuid) -> Result<Vec<FriendPresence>, DbErr> {
+        let mut presences = Vec::new();
+        for friend in friends::list_friends(&self.db, user_id).await? {
+            if !friend.is_mutual() {
+                continue;
+            }
+            let presence = self.presence_of(&friend.user_id.to_string()).await;
+            presences.push(FriendPresence { user_id: friend.user_id, username: friend.username, presence });
+        }
+        Ok(presences)
+    }
+}
+
+/// Publish presence changes as ConnectionManager and the room registry signal them
+#[cfg(feature = "server")]
+pub fn spawn_presence_task(service: Arc<PresenceService>) -> tokio::task::JoinHandle<()> {
+    let mut signals = service.connection_manager.presence_signal().subscribe();
+    tokio::spawn(async move {
+        loop {
+            match signals.recv().await {
+                Ok(player_id) => service.publish(&player_id).await,
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    warn!("Presence task fell behind; {} updates skipped", missed);
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        }
+    })
+}
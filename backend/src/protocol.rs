@@ -1,17 +1,179 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use crate::connection::PlayerId;
-use crate::lobby::LobbyId;
-use crate::game::GameId;
-use crate::game_logic::card::{Card, Suit};
-use crate::game_logic::bidding::Bid;
-use crate::game_state::GamePhase;
+use std::sync::Arc;
+pub use crate::engine::PlayerId;
+use crate::engine::card::{Card, Suit};
+use crate::engine::bidding::{Bid, BidConstraint};
+use crate::engine::tiebreak::{Placement, TieBreaker, WinReason};
+use crate::engine::schedule::RoundSchedule;
+use crate::engine::rules::RuleSet;
+pub use crate::engine::PlayerAction;
+pub use crate::engine::scoring::{PlayerRoundResult, RoundResult};
+use crate::accessibility::{CardLabel, SuitAsset, SuitEncoding};
+use crate::engine::GamePhase;
+use crate::lobby_preset::LobbyPreset;
+use crate::short_code::{IdOrCode, ShortCode};
+use crate::region::Region;
+use crate::features::Feature;
+use crate::audit::AuditKind;
+use crate::deprecation::Deprecation;
+use crate::achievements::Achievement;
+use crate::auth::Role;
+use crate::room::RoomId;
+use crate::user_cache::PlayerProfile;
+use crate::presence::Presence;
+use crate::trump_stats::TrumpStats;
+use crate::pacing::PlayerPacing;
+use uuid::Uuid;
 
+pub type LobbyId = Uuid;
+
+pub type GameId = Uuid;
+
+/// Position of a lobby in the browser. Lobbies are listed oldest first and a page
+/// resumes after the cursor of the last lobby on the previous one.
+pub type LobbyCursor = u64;
+
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameSettings {
     pub player_count: usize,
+    /// @deprecated Set `rules.timer.turn_secs` instead, which wins when both are set. Still
+    /// required, and used for turns the rules give no limit.
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
     pub turn_timeout_secs: u64,
     pub allow_reconnect: bool,
+    /// Sandbox lobby that only accepts bot accounts
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(as = "Option<bool>", optional))]
+    pub bots_only: bool,
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(as = "Option<BotPolicy>", optional))]
+    pub bot_policy: BotPolicy,
+    /// @deprecated Set `rules.schedule` instead, which wins when both are set
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(as = "Option<RoundSchedule>", optional))]
+    pub round_schedule: RoundSchedule,
+    /// Hide other players' bids until everyone has bid
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(as = "Option<bool>", optional))]
+    pub blind_bidding: bool,
+    /// What happens to a seat whose player's reconnect window expires mid-game
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(as = "Option<DeparturePolicy>", optional))]
+    pub departure_policy: DeparturePolicy,
+    /// Tournament this game counts towards; its webhook is called when the game completes
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(optional = nullable))]
+    pub tournament_id: Option<Uuid>,
+    /// Turn away players the host has blocked
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(as = "Option<bool>", optional))]
+    pub exclude_blocked: bool,
+    /// Rules for separating players level on points at the end, in order; players still
+    /// level after all of them share the win
+    #[serde(default = "TieBreaker::defaults")]
+    #[cfg_attr(feature = "typescript", ts(as = "Option<Vec<TieBreaker>>", optional))]
+    pub tie_breakers: Vec<TieBreaker>,
+    /// House rules: hand size cap, turn direction, zero bids, end conditions, trump and timers
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(as = "Option<RuleSet>", optional))]
+    pub rules: RuleSet,
+    /// Privately tell a round's notably slowest player so, to keep long games moving
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(as = "Option<bool>", optional))]
+    pub pace_notices: bool,
+    /// Keep the game off the leaderboard; always set for matchmaking tables backfilled with bots
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(as = "Option<bool>", optional))]
+    pub unrated: bool,
+    /// Who starts the next round once a round's results are shown
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(as = "Option<NextRoundPolicy>", optional))]
+    pub next_round: NextRoundPolicy,
+    /// Start the next round on its own this long after a round ends; unset waits for players
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(optional, type = "number"))]
+    pub intermission_secs: Option<u64>,
+    /// What to do when players connected from the same address take seats
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(as = "Option<SharedAddressPolicy>", optional))]
+    pub shared_address: SharedAddressPolicy,
+    /// Answer RequestHint with the bid or card the server's bots would choose; off for
+    /// competitive play
+    #[serde(default = "default_allow_hints")]
+    #[cfg_attr(feature = "typescript", ts(as = "Option<bool>", optional))]
+    pub allow_hints: bool,
+}
+
+impl GameSettings {
+    /// The round schedule played: the rules' if they set one, else the flat setting
+    pub fn schedule(&self) -> RoundSchedule {
+        self.rules.schedule.unwrap_or(self.round_schedule)
+    }
+
+    /// Turn time limit for phases the rules' timer leaves unset
+    pub fn default_turn_secs(&self) -> u64 {
+        self.rules.timer.turn_secs.unwrap_or(self.turn_timeout_secs)
+    }
+}
+
+fn default_allow_hints() -> bool {
+    true
+}
+
+/// How a lobby treats human players connected from the same address, which may be one person
+/// playing several seats. Bot accounts are left out, as one owner often runs several from a host.
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SharedAddressPolicy {
+    /// Let them play without a word, e.g. for a household or a club on one network
+    #[default]
+    Allow,
+    /// Tell the lobby which players share an address when one of them joins
+    Warn,
+    /// Turn away a player whose address already has a seat, and refuse to start while two
+    /// seats share one
+    Prevent,
+}
+
+/// Who may start the next round during the intermission after a round
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum NextRoundPolicy {
+    /// The next round's first bidder
+    #[default]
+    FirstBidder,
+    /// The host, or the first seated player once the host has left
+    Host,
+    /// Every seated player has to ask for it
+    AllPlayers,
+}
+
+/// Handling of a player who left a game for good; their turns are auto-played either way
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DeparturePolicy {
+    /// A bot plays the seat and the player keeps the seat's final score
+    #[default]
+    BotTakeover,
+    /// The player forfeits: the seat is auto-played but gets no final score
+    Forfeit,
+}
+
+/// Host-controlled limits on bot accounts in a lobby
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BotPolicy {
+    /// Reject all bot accounts
+    #[serde(default)]
+    pub forbid_bots: bool,
+    /// Maximum number of bots allowed to join
+    #[serde(default)]
+    pub max_bots: Option<usize>,
+    /// Refuse to start unless at least one human is seated
+    #[serde(default)]
+    pub require_human: bool,
 }
 
 impl Default for GameSettings {
@@ -20,104 +182,566 @@ impl Default for GameSettings {
             player_count: 4,
             turn_timeout_secs: 30,
             allow_reconnect: true,
+            bots_only: false,
+            bot_policy: BotPolicy::default(),
+            round_schedule: RoundSchedule::default(),
+            blind_bidding: false,
+            departure_policy: DeparturePolicy::default(),
+            tournament_id: None,
+            exclude_blocked: false,
+            tie_breakers: TieBreaker::defaults(),
+            rules: RuleSet::default(),
+            pace_notices: false,
+            unrated: false,
+            next_round: NextRoundPolicy::default(),
+            intermission_secs: None,
+            shared_address: SharedAddressPolicy::default(),
+            allow_hints: default_allow_hints(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum PlayerAction {
-    Bid(Bid),
-    PlayCard(Card),
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RoundResult {
-    pub round_number: usize,
-    pub player_results: Vec<PlayerRoundResult>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PlayerRoundResult {
-    pub player_id: PlayerId,
-    pub bid: u8,
-    pub tricks_won: u8,
-    pub score: i32,
-}
-
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS), ts(export))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerGameView {
     pub game_id: GameId,
+    pub game_code: ShortCode,
     pub phase: GamePhase,
     pub your_hand: Vec<Card>,
     pub current_trick: Vec<(PlayerId, Card)>,
     pub scores: HashMap<PlayerId, i32>,
-    pub history: Vec<RoundResult>, // Added history
+    /// Shared with the game state and every other view built from it
+    pub history: Arc<Vec<RoundResult>>,
     pub round_number: usize,       // Added round_number
     pub trump_suit: Option<Suit>,
     pub current_player: PlayerId,
     pub your_turn: bool,
     pub current_round: Vec<PlayerRoundResult>, // Current round bids and makes
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub state_version: u64,
+    pub player_names: Arc<HashMap<PlayerId, String>>,
+    /// Everyone at the table in seat order, with their display names and avatars
+    #[serde(default)]
+    pub players: Vec<PlayerInfo>,
+    /// True while blind bidding hides other players' bids; their `bid` in `current_round` is then 0
+    pub bids_hidden: bool,
+    /// Players who left for good, and how their seat is handled
+    pub departed: HashMap<PlayerId, DeparturePolicy>,
+    /// When the current player's turn times out; None while no turn timer runs
+    pub turn_deadline: Option<TurnDeadline>,
+    /// Bids open to the current bidder during bidding; None otherwise, and for other
+    /// players while blind bidding hides bids
+    pub bid_constraint: Option<BidConstraint>,
+    /// SHA-256 of the current round's deal seed, in hex, when deals are seeded
+    #[serde(default)]
+    pub deal_commitment: Option<String>,
+    /// Results stay off the leaderboard, e.g. at a table backfilled with bots
+    #[serde(default)]
+    pub unrated: bool,
+    /// Set between rounds: who can start the next one and when it starts on its own
+    #[serde(default)]
+    pub intermission: Option<Intermission>,
+}
+
+/// The pause after a round while its results are shown
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Intermission {
+    /// Players whose StartNextRound is still needed; under `AllPlayers`, any one of them
+    /// otherwise
+    pub waiting_for: Vec<PlayerId>,
+    /// Server epoch milliseconds when the next round starts without them
+    #[cfg_attr(feature = "typescript", ts(type = "number | null"))]
+    pub auto_start_ms: Option<i64>,
+}
+
+/// Whose turn it is and what is on the table right after an action, so a client can follow
+/// the game from PlayerAction messages alone
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnState {
+    pub next_player: PlayerId,
+    pub phase: GamePhase,
+    /// Cards on the table; empty once the action completed a trick
+    pub current_trick: Vec<(PlayerId, Card)>,
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub state_version: u64,
+}
+
+/// One player's standing in the round, sent with TrickComplete
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrickCount {
+    pub player_id: PlayerId,
+    pub bid: u8,
+    pub tricks_won: u8,
+}
+
+/// When a turn times out, as an absolute server time and as the time left when sent
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnDeadline {
+    pub player_id: PlayerId,
+    /// Server epoch milliseconds; correct for clock skew with TimeSync
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub deadline_ms: i64,
+    /// Whole seconds left, rounded up, for clients that don't sync clocks
+    #[cfg_attr(feature = "typescript", ts(type = "number"))]
+    pub remaining_secs: u64,
 }
 
+/// Figures a client needs for the bid dialog, sent with YourTurn during bidding
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BiddingInsights {
+    pub hand_size: usize,
+    pub trump_count: usize,
+    /// Bids placed so far this round, in bidding order
+    pub bids: Vec<PlacedBid>,
+    pub last_bidder: bool,
+    /// The bid that would make the total equal the cards dealt; only set for the last bidder
+    pub forbidden_bid: Option<u8>,
+    /// Bids open to the current bidder
+    pub constraint: BidConstraint,
+}
+
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlacedBid {
+    pub player_id: PlayerId,
+    /// None while blind bidding hides the amount
+    pub bid: Option<u8>,
+}
+
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerInfo {
     pub id: PlayerId,
     pub username: String,
+    /// Shown instead of `username` when set
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub avatar: Option<String>,
+    /// Lobby ready flag; always false in game views
+    pub ready: bool,
+}
+
+impl PlayerInfo {
+    pub fn new(id: PlayerId, profile: PlayerProfile, ready: bool) -> Self {
+        Self {
+            id,
+            username: profile.username,
+            display_name: profile.display_name,
+            avatar: profile.avatar,
+            ready,
+        }
+    }
 }
 
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LobbyInfo {
     pub id: LobbyId,
+    /// Short code to share instead of the id
+    pub code: ShortCode,
     pub host: PlayerId,
     pub players: Vec<PlayerInfo>,
     pub max_players: usize,
     pub settings: GameSettings,
+    pub bot_count: usize,
+    /// The host's region, if known
+    #[serde(default)]
+    pub region: Option<Region>,
+    /// Slowest measured round trip among the lobby's players, rounded to 10 ms
+    #[serde(default)]
+    pub latency_ms: Option<u32>,
+}
+
+/// Filters and paging for the lobby browser; an empty query lists the first page of joinable lobbies
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LobbyQuery {
+    /// Only lobbies for this many players
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(optional))]
+    pub player_count: Option<usize>,
+    /// Also list full lobbies waiting for their host to start
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(as = "Option<bool>", optional))]
+    pub include_full: bool,
+    /// Only lobbies hosted by mutual friends
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(as = "Option<bool>", optional))]
+    pub friends_only: bool,
+    /// `next_cursor` of the previous page
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(optional, type = "number"))]
+    pub after: Option<LobbyCursor>,
+    /// Lobbies per page; defaults to 20 and is capped at 100
+    #[serde(default)]
+    #[cfg_attr(feature = "typescript", ts(optional))]
+    pub limit: Option<usize>,
 }
 
+/// A departed player's seat that the host opened mid-game
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenSeatInfo {
+    pub game_id: GameId,
+    pub code: ShortCode,
+    pub seat: PlayerId,
+    /// Name of the player who left the seat
+    pub username: String,
+    /// Score the new player inherits
+    pub score: i32,
+    pub round_number: usize,
+    pub player_count: usize,
+}
+
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS), ts(export))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "payload")]
 pub enum ClientMessage {
     // Lobby actions
     CreateLobby { settings: GameSettings },
-    JoinLobby { lobby_id: LobbyId },
+    /// Create a lobby with the settings of one of the player's saved presets
+    CreateLobbyFromPreset { preset_id: Uuid },
+    ListPresets,
+    /// `lobby_id` may also be the lobby's short code
+    JoinLobby { lobby_id: IdOrCode },
     LeaveLobby,
     StartGame,
     StartNextRound, // Added manual transition
-    ListLobbies,
+    /// The payload may be left out to list the first page of joinable lobbies
+    ListLobbies(Option<LobbyQuery>),
+    KickPlayer { player_id: PlayerId },
+    TransferHost { player_id: PlayerId },
+    SetReady { ready: bool },
+    /// Moderators only: shut down any lobby, removing everyone in it
+    CloseLobby { lobby_id: IdOrCode },
+
+    // Matchmaking
+    JoinQueue {
+        player_count: usize,
+        /// Accept a table completed with bots after waiting too long for enough players;
+        /// such games are unrated
+        #[serde(default)]
+        #[cfg_attr(feature = "typescript", ts(as = "Option<bool>", optional))]
+        backfill: bool,
+    },
+    LeaveQueue,
+
+    // Rooms
+    /// Choose which of your games (or your lobby) game actions apply to
+    SetActiveRoom { room: RoomId },
+
+    // Display
+    /// Switch suit rendering for this session; the saved default is set via /api/settings
+    SetSuitEncoding { encoding: SuitEncoding },
 
     // Game actions
-    PlaceBid { bid: Bid },
-    PlayCard { card: Card },
+    PlaceBid {
+        bid: Bid,
+        #[serde(default)]
+        #[cfg_attr(feature = "typescript", ts(optional, type = "number"))]
+        state_version: Option<u64>,
+    },
+    PlayCard {
+        card: Card,
+        #[serde(default)]
+        #[cfg_attr(feature = "typescript", ts(optional, type = "number"))]
+        state_version: Option<u64>,
+    },
     RequestGameState,
+    /// Ask what to bid or play on your own turn; answered with Hint in lobbies with
+    /// `allow_hints` on
+    RequestHint,
+    /// Host only: open a departed player's seat so someone else can take it over
+    OpenSeat { player_id: PlayerId },
+    /// Host only: play the turn of a player who ran out of time, under `TimeoutPolicy::WaitForHost`
+    ForceTurn { player_id: PlayerId },
+    /// Take over an open seat, inheriting its hand and score; `game_id` may be the game's short code
+    TakeSeat { game_id: IdOrCode, seat: PlayerId },
+    ListOpenSeats,
+    /// Ask for the game messages after `seq` that were missed, e.g. during a brief disconnect
+    ResyncFrom {
+        #[cfg_attr(feature = "typescript", ts(type = "number"))]
+        seq: u64,
+    },
 
     // Connection
     Ping,
+    /// Clock sync probe; `client_time_ms` is the client's clock when sending, echoed back
+    TimeSync {
+        #[cfg_attr(feature = "typescript", ts(type = "number"))]
+        client_time_ms: i64,
+    },
 }
 
+impl ClientMessage {
+    /// Lowest role allowed to send this message
+    pub fn required_role(&self) -> Role {
+        match self {
+            ClientMessage::CloseLobby { .. } => Role::Moderator,
+            _ => Role::Player,
+        }
+    }
+
+    /// The deprecated form this message uses, if any
+    pub fn deprecated_form(&self) -> Option<Deprecation> {
+        match self {
+            ClientMessage::PlaceBid { state_version: None, .. }
+            | ClientMessage::PlayCard { state_version: None, .. } => Some(Deprecation::UnversionedAction),
+            _ => None,
+        }
+    }
+
+    /// How the message is recorded in the audit log, if it is
+    pub fn audit_kind(&self) -> Option<AuditKind> {
+        match self {
+            ClientMessage::JoinLobby { .. } => Some(AuditKind::LobbyJoin),
+            ClientMessage::PlaceBid { .. }
+            | ClientMessage::PlayCard { .. }
+            | ClientMessage::StartNextRound
+            | ClientMessage::OpenSeat { .. }
+            | ClientMessage::ForceTurn { .. }
+            | ClientMessage::TakeSeat { .. } => Some(AuditKind::GameAction),
+            _ => None,
+        }
+    }
+
+    /// Whether the message opens a lobby or starts a game, which a draining server refuses
+    pub fn starts_something(&self) -> bool {
+        matches!(
+            self,
+            ClientMessage::CreateLobby { .. }
+                | ClientMessage::CreateLobbyFromPreset { .. }
+                | ClientMessage::StartGame
+                | ClientMessage::JoinQueue { .. }
+        )
+    }
+
+    /// The runtime-switchable subsystem this message belongs to, if any. Leaving the
+    /// queue stays possible so players are not stuck in it when ranked play is switched off.
+    pub fn required_feature(&self) -> Option<Feature> {
+        match self {
+            ClientMessage::JoinQueue { .. } => Some(Feature::Ranked),
+            _ => None,
+        }
+    }
+}
+
+/// A client message with the room it is addressed to, sent as an extra `room_id` key
+/// beside `type` and `payload`. Without one, lobby actions go to the player's lobby and
+/// game actions to their active game. A `request_id` is echoed back in the message's Ack
+/// or Error.
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS), ts(export))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientEnvelope {
+    #[serde(flatten)]
+    pub message: ClientMessage,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room_id: Option<RoomId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "typescript", ts(optional))]
+    pub request_id: Option<String>,
+}
+
+impl From<ClientMessage> for ClientEnvelope {
+    fn from(message: ClientMessage) -> Self {
+        Self { message, room_id: None, request_id: None }
+    }
+}
+
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS), ts(export))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "payload")]
 pub enum ServerMessage {
     // Connection
     Connected { player_id: PlayerId },
+    /// Suit rendering and card labels to use, sent after Connected and on SetSuitEncoding
+    DisplayProfile {
+        suit_encoding: SuitEncoding,
+        suits: Vec<SuitAsset>,
+        cards: Vec<CardLabel>,
+    },
     Pong,
-    Error { message: String },
+    /// Reply to TimeSync, with server epoch milliseconds on receipt and on reply
+    TimeSync {
+        #[cfg_attr(feature = "typescript", ts(type = "number"))]
+        client_time_ms: i64,
+        #[cfg_attr(feature = "typescript", ts(type = "number"))]
+        server_received_ms: i64,
+        #[cfg_attr(feature = "typescript", ts(type = "number"))]
+        server_sent_ms: i64,
+    },
+    Error {
+        message: String,
+        /// The failed message's `request_id`, if it had one
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[cfg_attr(feature = "typescript", ts(optional))]
+        request_id: Option<String>,
+    },
+    /// A message sent with a `request_id` was handled; its replies, if any, came first
+    Ack { request_id: String },
+    /// The message needs a subsystem this server has switched off
+    FeatureDisabled {
+        feature: Feature,
+        message: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        #[cfg_attr(feature = "typescript", ts(optional))]
+        request_id: Option<String>,
+    },
+    /// The client used a wire form that will stop working after `remove_after`; sent once per session
+    DeprecationNotice { feature: Deprecation, remove_after: chrono::NaiveDate },
+    /// The server is going down for maintenance: no new lobbies or games start, and games
+    /// still running are saved and resume at the start of their round once it is back
+    ServerMaintenance {
+        #[cfg_attr(feature = "typescript", ts(type = "number"))]
+        shutdown_in_secs: u64,
+    },
 
     // Lobby updates
-    LobbyCreated { lobby_id: LobbyId },
+    LobbyCreated { lobby_id: LobbyId, code: ShortCode },
     LobbyJoined { lobby: LobbyInfo },
     LobbyUpdated { lobby: LobbyInfo },
-    LobbyList { lobbies: Vec<LobbyInfo> },
-    GameStarting { game_id: GameId },
+    LobbyList {
+        lobbies: Vec<LobbyInfo>,
+        /// Pass as `after` to fetch the next page; absent on the last page
+        #[serde(default)]
+        #[cfg_attr(feature = "typescript", ts(type = "number | null"))]
+        next_cursor: Option<LobbyCursor>,
+    },
+    /// Changes to the lobby list since the previous delta; upsert `changed` by id, drop `removed`
+    LobbyListDelta { changed: Vec<LobbyInfo>, removed: Vec<LobbyId> },
+    PresetList { presets: Vec<LobbyPreset> },
+    KickedFromLobby { lobby_id: LobbyId },
+    /// The lobby was closed after sitting idle too long
+    LobbyExpired { lobby_id: LobbyId },
+    /// `players` in the lobby are connected from the same address; sent to the lobby when one
+    /// of them joins, if its `shared_address` policy is `Warn`
+    SharedAddressWarning { lobby_id: LobbyId, players: Vec<PlayerId> },
+    GameStarting { game_id: GameId, code: ShortCode },
+
+    // Room updates
+    /// Reply to SetActiveRoom: the active room and every room you are in, oldest first
+    ActiveRoom { room: Option<RoomId>, rooms: Vec<RoomId> },
+    /// Messages for the room were dropped because the connection fell behind; fetch its
+    /// state again (RequestGameState for a game, JoinLobby for a lobby)
+    ResyncRequired {
+        room: RoomId,
+        #[cfg_attr(feature = "typescript", ts(type = "number"))]
+        missed: u64,
+    },
+
+    // Matchmaking updates
+    QueueJoined {
+        player_count: usize,
+        position: usize,
+        /// Seconds until bots fill the table; only present when backfill was requested and the
+        /// server allows it
+        #[serde(default)]
+        #[cfg_attr(feature = "typescript", ts(type = "number | null"))]
+        backfill_after_secs: Option<u64>,
+    },
+    QueueLeft,
+    /// The queue could not fill a table in time, so bots take the empty seats. Sent after
+    /// `GameStarting`.
+    QueueBackfilled { game_id: GameId, bots: usize, message: String },
+    ConnectionHint { node_id: String, url: String },
 
     // Game updates
-    GameState { state: PlayerGameView },
-    YourTurn { valid_actions: Vec<PlayerAction> },
-    PlayerAction { player_id: PlayerId, action: PlayerAction, next_player: PlayerId },
-    TrickComplete { winner: PlayerId },
-    GameOver { final_scores: HashMap<PlayerId, i32> },
+    GameState { state: Box<PlayerGameView> },
+    YourTurn {
+        valid_actions: Vec<PlayerAction>,
+        /// Only present during bidding
+        #[serde(default)]
+        bidding: Option<BiddingInsights>,
+        /// Only present while a turn timer runs
+        #[serde(default)]
+        deadline: Option<TurnDeadline>,
+    },
+    /// Reply to RequestHint: what the server's bots would do with your turn at `state_version`
+    Hint {
+        action: PlayerAction,
+        #[cfg_attr(feature = "typescript", ts(type = "number"))]
+        state_version: u64,
+    },
+    PlayerAction {
+        player_id: PlayerId,
+        action: PlayerAction,
+        /// Where the game stands once the action is applied
+        #[serde(flatten)]
+        turn: TurnState,
+    },
+    TrickComplete {
+        winner: PlayerId,
+        /// The trick as played, in play order
+        cards: Vec<(PlayerId, Card)>,
+        /// Every player's tricks so far this round against their bid, in seat order
+        tricks: Vec<TrickCount>,
+        /// This was the round's last trick
+        round_complete: bool,
+    },
+    /// The round's bids, tricks and scores, sent when its last trick is taken
+    RoundComplete { results: RoundResult },
+    /// A player asked for the next round while every player's go-ahead is needed
+    NextRoundVote { player_id: PlayerId, intermission: Intermission },
+    GameOver {
+        final_scores: HashMap<PlayerId, i32>,
+        /// Finishing order, best first; players who forfeited are not placed
+        placements: Vec<Placement>,
+        /// In seat order; several for a shared win
+        winners: Vec<PlayerId>,
+        win_reason: WinReason,
+    },
+    /// Sent to each player after GameOver: how they fared under each trump this game
+    GameSummary {
+        trump_stats: Vec<TrumpStats>,
+        best_trump: Option<Suit>,
+        best_trump_line: Option<String>,
+    },
+    /// Sent to the host after GameOver: how long each player took over their bids and plays
+    PacingReport { players: Vec<PlayerPacing> },
+    /// Sent privately at the end of a round to its notably slowest player, in lobbies with
+    /// pace notices on
+    PaceNotice {
+        round_number: usize,
+        #[cfg_attr(feature = "typescript", ts(type = "number"))]
+        your_secs: u64,
+        #[cfg_attr(feature = "typescript", ts(type = "number"))]
+        others_average_secs: u64,
+    },
+    /// Sent privately, after the round or game that earned it, when a player unlocks an
+    /// achievement. Not sequenced: it is about the player, not the game.
+    AchievementUnlocked {
+        achievement: Achievement,
+        title: String,
+        description: String,
+    },
 
     // Player updates
     PlayerJoined { player_id: PlayerId },
     PlayerLeft { player_id: PlayerId },
     PlayerReconnected { player_id: PlayerId },
+    /// The player whose turn it is lost their connection. Their turn clock stands still until
+    /// `resume_deadline` (server epoch milliseconds) or until they are back, whichever is first.
+    PlayerDisconnected {
+        game_id: GameId,
+        player_id: PlayerId,
+        #[cfg_attr(feature = "typescript", ts(type = "number"))]
+        resume_deadline: i64,
+    },
+    /// A player's reconnect window expired mid-game; their seat is now auto-played
+    PlayerDeparted { player_id: PlayerId, outcome: DeparturePolicy },
+    /// `player_id` ran out of time and the game waits for them, or for the host to `ForceTurn`
+    TurnTimedOut { game_id: GameId, player_id: PlayerId },
+    /// The host opened a departed player's seat to anyone
+    SeatOpened { game_id: GameId, seat: PlayerId },
+    /// `player_id` took over `seat`, which no longer exists; read their hand and score under the new id
+    SeatTaken { seat: PlayerId, player_id: PlayerId },
+    OpenSeatList { seats: Vec<OpenSeatInfo> },
+    /// A mutual friend came online, went offline or moved between lobbies and games
+    PresenceUpdate { player_id: PlayerId, presence: Presence },
 }
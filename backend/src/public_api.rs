@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use axum::{
+    body::{Body, Bytes},
+    extract::{Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use crate::client_ip::TrustedProxies;
+use crate::rate_limit::{RateDecision, RateLimitConfig, RateLimiter};
+use tracing::{debug, warn};
+
+/// Responses larger than this are served but not cached
+const MAX_CACHED_BODY: usize = 1024 * 1024;
+
+/// Entries kept at most; new responses are not cached while the cache is full of fresh ones
+const MAX_CACHED_RESPONSES: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicApiConfig {
+    /// Requests one client address may make back to back
+    pub burst: u32,
+    /// Sustained requests per second per client address
+    pub per_sec: u32,
+    /// How long a response is served from the cache; zero turns caching off
+    pub cache_ttl: Duration,
+}
+
+impl Default for PublicApiConfig {
+    fn default() -> Self {
+        Self { burst: 30, per_sec: 5, cache_ttl: Duration::from_secs(10) }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedResponse {
+    content_type: Option<HeaderValue>,
+    body: Bytes,
+    stored_at: Instant,
+}
+
+/// Short-lived copies of successful GET responses, keyed by path and query
+#[derive(Debug)]
+pub struct ResponseCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, CachedResponse>>,
+}
+
+impl ResponseCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, entries: Mutex::new(HashMap::new()) }
+    }
+
+    fn get(&self, key: &str, now: Instant) -> Option<CachedResponse> {
+        let entries = self.entries.lock().unwrap();
+        entries.get(key)
+            .filter(|entry| now.saturating_duration_since(entry.stored_at) < self.ttl)
+            .cloned()
+    }
+
+    fn insert(&self, key: String, response: CachedResponse) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= MAX_CACHED_RESPONSES {
+            let now = Instant::now();
+            entries.retain(|_, entry| now.saturating_duration_since(entry.stored_at) < self.ttl);
+            if entries.len() >= MAX_CACHED_RESPONSES {
+                return;
+            }
+        }
+        entries.insert(key, response);
+    }
+
+    /// Drop cached responses for every path starting with `path`, e.g. once the data behind them changed
+    pub fn invalidate(&self, path: &str) {
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|key, _| !key.starts_with(path));
+        debug!("Invalidated {} cached responses under {}", before - entries.len(), path);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Rate limiting and response caching for the read-only endpoints anyone may poll
+/// (leaderboard, lobby snapshot, public profiles)
+#[derive(Debug)]
+pub struct PublicApi {
+    limiter: RateLimiter,
+    cache: ResponseCache,
+    trusted_proxies: TrustedProxies,
+}
+
+impl PublicApi {
+    pub fn new(config: PublicApiConfig) -> Self {
+        Self {
+            // Only the per-address bucket applies; clients are never disconnected
+            limiter: RateLimiter::new(RateLimitConfig {
+                burst: config.burst,
+                per_sec: config.per_sec,
+                max_violations: u32::MAX,
+            }),
+            cache: ResponseCache::new(config.cache_ttl),
+            trusted_proxies: TrustedProxies::default(),
+        }
+    }
+
+    /// Limit the clients these proxies forward requests for, rather than the proxies
+    pub fn with_trusted_proxies(mut self, trusted_proxies: TrustedProxies) -> Self {
+        self.trusted_proxies = trusted_proxies;
+        self
+    }
+
+    pub fn cache(&self) -> &ResponseCache {
+        &self.cache
+    }
+
+    /// Forget idle rate limit buckets
+    pub fn prune(&self) {
+        self.limiter.prune();
+    }
+
+    fn allow(&self, ip: IpAddr) -> bool {
+        self.limiter.check(&ip.to_string(), None) == RateDecision::Allowed
+    }
+}
+
+/// Middleware: answer 429 once a client address runs out of requests
+pub async fn rate_limit(State(api): State<Arc<PublicApi>>, request: Request, next: Next) -> Response {
+    // Both listeners attach connection info; without it every client would share one bucket
+    let Some(ip) = api.trusted_proxies.request_client_ip(request.extensions(), request.headers()) else {
+        warn!("Public request to {} without connection info refused", request.uri().path());
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Client address unknown").into_response();
+    };
+    if !api.allow(ip) {
+        debug!("Rate limited public request from {} to {}", ip, request.uri().path());
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, HeaderValue::from_static("1"))],
+            "Too many requests",
+        ).into_response();
+    }
+    next.run(request).await
+}
+
+/// Middleware: serve GET requests from the response cache while a fresh copy exists
+pub async fn cache_responses(State(api): State<Arc<PublicApi>>, request: Request, next: Next) -> Response {
+    if request.method() != Method::GET || api.cache.ttl.is_zero() {
+        return next.run(request).await;
+    }
+    let key = request.uri().path_and_query().map_or_else(|| request.uri().path().to_string(), ToString::to_string);
+
+    if let Some(cached) = api.cache.get(&key, Instant::now()) {
+        return cached_response(cached, "hit");
+    }
+
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+    let (parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_CACHED_BODY).await {
+        Ok(body) => body,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Response too large").into_response(),
+    };
+    let cached = CachedResponse {
+        content_type: parts.headers.get(header::CONTENT_TYPE).cloned(),
+        body,
+        stored_at: Instant::now(),
+    };
+    api.cache.insert(key, cached.clone());
+    cached_response(cached, "miss")
+}
+
+fn cached_response(cached: CachedResponse, status: &'static str) -> Response {
+    let mut response = Response::new(Body::from(cached.body));
+    if let Some(content_type) = cached.content_type {
+        response.headers_mut().insert(header::CONTENT_TYPE, content_type);
+    }
+    response.headers_mut().insert("x-cache", HeaderValue::from_static(status));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(body: &'static str, stored_at: Instant) -> CachedResponse {
+        CachedResponse { content_type: None, body: Bytes::from_static(body.as_bytes()), stored_at }
+    }
+
+    #[test]
+    fn test_entries_expire_and_invalidate_by_prefix() {
+        let cache = ResponseCache::new(Duration::from_secs(10));
+        let now = Instant::now();
+        cache.insert("/api/leaderboard?players=Four".to_string(), entry("board", now));
+        cache.insert("/api/lobbies".to_string(), entry("lobbies", now));
+
+        assert_eq!(cache.get("/api/leaderboard?players=Four", now).unwrap().body, "board");
+        assert!(cache.get("/api/leaderboard?players=Four", now + Duration::from_secs(10)).is_none());
+
+        cache.invalidate("/api/leaderboard");
+        assert!(cache.get("/api/leaderboard?players=Four", now).is_none());
+        assert_eq!(cache.len(), 1);
+    }
+}
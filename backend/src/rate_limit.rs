@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::connection::PlayerId;
+
+/// Connections from one address share a bucket this many times the size of a player's
+pub const IP_SHARE_FACTOR: u32 = 4;
+
+/// Rejected messages older than this no longer count towards a disconnect
+pub const VIOLATION_WINDOW: Duration = Duration::from_secs(10);
+
+/// Buckets untouched for this long are full again and can be forgotten
+const IDLE_BUCKET_TTL: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// Messages a player may send back to back
+    pub burst: u32,
+    /// Sustained messages per second
+    pub per_sec: u32,
+    /// Rejected messages within VIOLATION_WINDOW before the connection is closed
+    pub max_violations: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self { burst: 20, per_sec: 10, max_violations: 50 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateDecision {
+    Allowed,
+    /// Drop this message but keep the connection
+    Throttled,
+    /// Too many violations; close the connection
+    Disconnect,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum RateKey {
+    Player(PlayerId),
+    Ip(IpAddr),
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(capacity: u32, refill_per_sec: u32, now: Instant) -> Self {
+        Self {
+            capacity: capacity as f64,
+            refill_per_sec: refill_per_sec as f64,
+            tokens: capacity as f64,
+            updated_at: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.updated_at = now;
+    }
+
+    fn has_token(&self) -> bool {
+        self.tokens >= 1.0
+    }
+}
+
+#[derive(Debug)]
+struct Violations {
+    count: u32,
+    since: Instant,
+}
+
+/// Token buckets per player and per client address.
+///
+/// A message needs a token from both buckets. Every rejected message is a violation;
+/// `max_violations` of them within VIOLATION_WINDOW ask for a disconnect.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<RateKey, TokenBucket>>,
+    violations: Mutex<HashMap<PlayerId, Violations>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+            violations: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> RateLimitConfig {
+        self.config
+    }
+
+    /// Take a token for one incoming message
+    pub fn check(&self, player_id: &PlayerId, ip: Option<IpAddr>) -> RateDecision {
+        self.check_at(player_id, ip, Instant::now())
+    }
+
+    fn check_at(&self, player_id: &PlayerId, ip: Option<IpAddr>, now: Instant) -> RateDecision {
+        let allowed = {
+            let mut buckets = self.buckets.lock().unwrap();
+            let player_key = RateKey::Player(player_id.clone());
+            buckets.entry(player_key.clone())
+                .or_insert_with(|| TokenBucket::new(self.config.burst, self.config.per_sec, now))
+                .refill(now);
+            if let Some(ip) = ip {
+                buckets.entry(RateKey::Ip(ip))
+                    .or_insert_with(|| TokenBucket::new(
+                        self.config.burst * IP_SHARE_FACTOR,
+                        self.config.per_sec * IP_SHARE_FACTOR,
+                        now,
+                    ))
+                    .refill(now);
+            }
+
+            let keys: Vec<RateKey> = std::iter::once(player_key).chain(ip.map(RateKey::Ip)).collect();
+            let allowed = keys.iter().all(|key| buckets[key].has_token());
+            if allowed {
+                for key in &keys {
+                    if let Some(bucket) = buckets.get_mut(key) {
+                        bucket.tokens -= 1.0;
+                    }
+                }
+            }
+            allowed
+        };
+        if allowed {
+            return RateDecision::Allowed;
+        }
+
+        let mut violations = self.violations.lock().unwrap();
+        let entry = violations.entry(player_id.clone()).or_insert(Violations { count: 0, since: now });
+        if now.saturating_duration_since(entry.since) > VIOLATION_WINDOW {
+            *entry = Violations { count: 0, since: now };
+        }
+        entry.count += 1;
+        if entry.count >= self.config.max_violations {
+            violations.remove(player_id);
+            RateDecision::Disconnect
+        } else {
+            RateDecision::Throttled
+        }
+    }
+
+    /// Forget buckets and violation counts that have been idle for a while
+    pub fn prune(&self) {
+        let now = Instant::now();
+        self.buckets.lock().unwrap()
+            .retain(|_, bucket| now.saturating_duration_since(bucket.updated_at) < IDLE_BUCKET_TTL);
+        self.violations.lock().unwrap()
+            .retain(|_, v| now.saturating_duration_since(v.since) <= VIOLATION_WINDOW);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(burst: u32, per_sec: u32, max_violations: u32) -> RateLimiter {
+        RateLimiter::new(RateLimitConfig { burst, per_sec, max_violations })
+    }
+
+    #[test]
+    fn test_burst_then_refill() {
+        let limiter = limiter(3, 2, 100);
+        let player = "p1".to_string();
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(limiter.check_at(&player, None, start), RateDecision::Allowed);
+        }
+        assert_eq!(limiter.check_at(&player, None, start), RateDecision::Throttled);
+
+        // Two tokens per second
+        let later = start + Duration::from_millis(500);
+        assert_eq!(limiter.check_at(&player, None, later), RateDecision::Allowed);
+        assert_eq!(limiter.check_at(&player, None, later), RateDecision::Throttled);
+    }
+
+    #[test]
+    fn test_players_have_separate_buckets() {
+        let limiter = limiter(1, 1, 100);
+        let now = Instant::now();
+
+        assert_eq!(limiter.check_at(&"p1".to_string(), None, now), RateDecision::Allowed);
+        assert_eq!(limiter.check_at(&"p1".to_string(), None, now), RateDecision::Throttled);
+        assert_eq!(limiter.check_at(&"p2".to_string(), None, now), RateDecision::Allowed);
+    }
+
+    #[test]
+    fn test_address_bucket_is_shared() {
+        let limiter = limiter(1, 1, 100);
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+        let now = Instant::now();
+
+        for i in 0..IP_SHARE_FACTOR {
+            assert_eq!(limiter.check_at(&format!("p{}", i), Some(ip), now), RateDecision::Allowed);
+        }
+        // A fresh player from the same address finds the shared bucket empty
+        assert_eq!(limiter.check_at(&"late".to_string(), Some(ip), now), RateDecision::Throttled);
+        let other_ip: IpAddr = "198.51.100.1".parse().unwrap();
+        assert_eq!(limiter.check_at(&"late".to_string(), Some(other_ip), now), RateDecision::Allowed);
+    }
+
+    #[test]
+    fn test_repeated_violations_disconnect() {
+        let limiter = limiter(1, 1, 3);
+        let player = "p1".to_string();
+        let now = Instant::now();
+
+        assert_eq!(limiter.check_at(&player, None, now), RateDecision::Allowed);
+        assert_eq!(limiter.check_at(&player, None, now), RateDecision::Throttled);
+        assert_eq!(limiter.check_at(&player, None, now), RateDecision::Throttled);
+        assert_eq!(limiter.check_at(&player, None, now), RateDecision::Disconnect);
+    }
+
+    #[test]
+    fn test_violations_expire() {
+        let limiter = limiter(1, 1, 2);
+        let player = "p1".to_string();
+        let start = Instant::now();
+
+        assert_eq!(limiter.check_at(&player, None, start), RateDecision::Allowed);
+        assert_eq!(limiter.check_at(&player, None, start), RateDecision::Throttled);
+
+        // The earlier violation is outside the window by now
+        let later = start + VIOLATION_WINDOW + Duration::from_secs(1);
+        assert_eq!(limiter.check_at(&player, None, later), RateDecision::Allowed);
+        assert_eq!(limiter.check_at(&player, None, later), RateDecision::Throttled);
+    }
+}
@@ -0,0 +1,134 @@
+use std::fmt;
+use crate::connection::PlayerId;
+use crate::engine::GameState;
+use crate::engine::card::Card;
+use crate::protocol::ServerMessage;
+
+/// A game's hidden information as one player may see it: their own hand, and nothing of
+/// anyone else's or of the cards left in the deck.
+///
+/// Views and turn messages read hands through this rather than from `GameState::hands`, and
+/// [`RedactedView::check`] finds any card a message would give away.
+#[derive(Clone, Copy)]
+pub struct RedactedView<'a> {
+    state: &'a GameState,
+    viewer: &'a PlayerId,
+}
+
+/// A card a message would show to a player who may not see it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leak {
+    pub card: Card,
+    /// Whose hand the card is in; None for a card left in the deck
+    pub holder: Option<PlayerId>,
+}
+
+impl fmt::Display for Leak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.holder {
+            Some(holder) => write!(f, "{:?} is in {}'s hand", self.card, holder),
+            None => write!(f, "{:?} was not dealt", self.card),
+        }
+    }
+}
+
+impl<'a> RedactedView<'a> {
+    pub fn new(state: &'a GameState, viewer: &'a PlayerId) -> Self {
+        Self { state, viewer }
+    }
+
+    /// The viewer's cards; empty for anyone without a seat
+    pub fn own_hand(&self) -> &'a [Card] {
+        self.state.hands.get(self.viewer).map(|hand| hand.cards()).unwrap_or_default()
+    }
+
+    /// The first card in `message` that the viewer may not see, if any. Cards in the viewer's
+    /// hand and cards already played are fine.
+    pub fn check(&self, message: &ServerMessage) -> Result<(), Leak> {
+        // A legend of every card's label, not a deal
+        if matches!(message, ServerMessage::DisplayProfile { .. }) {
+            return Ok(());
+        }
+        let Ok(json) = serde_json::to_value(message) else {
+            return Ok(());
+        };
+        let mut cards = Vec::new();
+        cards_in(&json, &mut cards);
+        cards.into_iter().try_for_each(|card| self.check_card(card))
+    }
+
+    fn check_card(&self, card: Card) -> Result<(), Leak> {
+        let holder = self.state.hands.iter()
+            .find(|(player_id, hand)| *player_id != self.viewer && hand.has_card(&card))
+            .map(|(player_id, _)| player_id.clone());
+        if holder.is_some() {
+            return Err(Leak { card, holder });
+        }
+        if self.state.deck.remaining().contains(&card) {
+            return Err(Leak { card, holder: None });
+        }
+        Ok(())
+    }
+}
+
+/// Every value in `json` shaped like a card
+fn cards_in(json: &serde_json::Value, cards: &mut Vec<Card>) {
+    match json {
+        serde_json::Value::Object(map) => {
+            if map.len() == 2 && map.contains_key("suit") && map.contains_key("rank") {
+                if let Ok(card) = serde_json::from_value(json.clone()) {
+                    cards.push(card);
+                    return;
+                }
+            }
+            map.values().for_each(|value| cards_in(value, cards));
+        }
+        serde_json::Value::Array(values) => values.iter().for_each(|value| cards_in(value, cards)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{GamePhase, PlayerAction};
+    use crate::engine::auto_play::LowestLegal;
+
+    #[test]
+    fn test_flags_other_hands_and_the_deck() {
+        let players = vec!["a".to_string(), "b".to_string()];
+        let mut state = GameState::new(players);
+        let (a, b) = ("a".to_string(), "b".to_string());
+        let own = state.hands[&a].cards()[0];
+        let theirs = state.hands[&b].cards()[0];
+        let undealt = state.deck.remaining()[0];
+        let view = RedactedView::new(&state, &a);
+        let showing = |card: Card| ServerMessage::PlayerAction {
+            player_id: b.clone(),
+            action: PlayerAction::PlayCard(card),
+            turn: crate::protocol::TurnState {
+                next_player: a.clone(),
+                phase: GamePhase::Playing,
+                current_trick: vec![(b.clone(), card)],
+                state_version: 0,
+            },
+        };
+
+        assert_eq!(view.own_hand(), [own]);
+        assert!(view.check(&showing(own)).is_ok());
+        assert_eq!(view.check(&showing(theirs)), Err(Leak { card: theirs, holder: Some(b.clone()) }));
+        assert_eq!(view.check(&showing(undealt)), Err(Leak { card: undealt, holder: None }));
+
+        // Once played, a card is public
+        while state.phase == GamePhase::Bidding {
+            let player = state.current_player.clone();
+            let bid = state.get_auto_action(&LowestLegal).unwrap();
+            state.apply_action(player, bid).unwrap();
+        }
+        let leader = state.current_player.clone();
+        let card = state.hands[&leader].cards()[0];
+        state.apply_action(leader, PlayerAction::PlayCard(card)).unwrap();
+        assert!(RedactedView::new(&state, &a).check(&showing(card)).is_ok());
+        assert!(RedactedView::new(&state, &b).check(&showing(card)).is_ok());
+    }
+}
@@ -0,0 +1,82 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+/// Request header a load balancer or CDN can set with the client's region
+pub const REGION_HEADER: &str = "x-client-region";
+
+/// Longest region name accepted
+const MAX_LEN: usize = 32;
+
+/// Coarse location of a player or node, e.g. `eu-west`. Operators choose the names; players
+/// in the same region are matched together first.
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+#[cfg_attr(feature = "typescript", ts(type = "string"))]
+pub struct Region(String);
+
+impl Region {
+    /// Lowercase letters, digits and dashes; input is trimmed and lowercased
+    pub fn parse(input: &str) -> Option<Self> {
+        let region = input.trim().to_ascii_lowercase();
+        let valid = !region.is_empty()
+            && region.len() <= MAX_LEN
+            && region.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-');
+        valid.then_some(Self(region))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TryFrom<String> for Region {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value).ok_or_else(|| format!("Invalid region '{}'", value))
+    }
+}
+
+impl From<Region> for String {
+    fn from(region: Region) -> Self {
+        region.0
+    }
+}
+
+/// The region a connecting client is in: the one it asked for, else the one the proxy in
+/// front of the server reported, else the node's own
+pub fn resolve(requested: Option<&str>, header: Option<&str>, node: Option<&Region>) -> Option<Region> {
+    requested.and_then(Region::parse)
+        .or_else(|| header.and_then(Region::parse))
+        .or_else(|| node.cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_normalizes_and_rejects() {
+        assert_eq!(Region::parse(" EU-West ").unwrap().as_str(), "eu-west");
+        assert!(Region::parse("").is_none());
+        assert!(Region::parse("eu west").is_none());
+        assert!(Region::parse(&"a".repeat(33)).is_none());
+    }
+
+    #[test]
+    fn test_resolve_prefers_client_then_header_then_node() {
+        let node = Region::parse("us-east");
+        assert_eq!(resolve(Some("eu-west"), Some("ap-south"), node.as_ref()), Region::parse("eu-west"));
+        // An unusable request falls through
+        assert_eq!(resolve(Some("??"), Some("ap-south"), node.as_ref()), Region::parse("ap-south"));
+        assert_eq!(resolve(None, None, node.as_ref()), node);
+        assert_eq!(resolve(None, None, None), None);
+    }
+}
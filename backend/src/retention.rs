@@ -0,0 +1,154 @@
+use std::time::Duration;
+use chrono::{SubsecRound, Utc};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, ColumnTrait, TransactionTrait};
+use sea_orm::sea_query::Expr;
+use uuid::Uuid;
+use crate::entities::{game, game_player, game_round, lobby, lobby_player, round_deal};
+use tracing::{info, warn};
+
+/// Mark a lobby as deleted, along with its remaining members; it is closed as well if it was
+/// still open. All three carry the same time so `restore_lobby` can tell what the delete did.
+pub async fn soft_delete_lobby(db: &impl ConnectionTrait, lobby_id: Uuid) -> Result<(), DbErr> {
+    // Whole microseconds, which every backend stores exactly
+    let now = Utc::now().trunc_subsecs(6);
+    let result = lobby::Entity::update_many()
+        .col_expr(lobby::Column::DeletedAt, Expr::value(now))
+        .filter(lobby::Column::Id.eq(lobby_id))
+        .filter(lobby::Column::DeletedAt.is_null())
+        .exec(db)
+        .await?;
+    if result.rows_affected == 0 {
+        return Ok(());
+    }
+    lobby::Entity::update_many()
+        .col_expr(lobby::Column::ClosedAt, Expr::value(now))
+        .filter(lobby::Column::Id.eq(lobby_id))
+        .filter(lobby::Column::ClosedAt.is_null())
+        .exec(db)
+        .await?;
+    lobby_player::Entity::update_many()
+        .col_expr(lobby_player::Column::LeftAt, Expr::value(now))
+        .filter(lobby_player::Column::LobbyId.eq(lobby_id))
+        .filter(lobby_player::Column::LeftAt.is_null())
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Undo `soft_delete_lobby`: clear the deletion marker, reopen the lobby if the delete closed
+/// it, and bring back the members it removed. Returns false if no deleted lobby matched.
+pub async fn restore_lobby(db: &DatabaseConnection, lobby_id: Uuid) -> Result<bool, DbErr> {
+    let txn = db.begin().await?;
+    let Some(row) = lobby::Entity::find_by_id(lobby_id).one(&txn).await? else {
+        return Ok(false);
+    };
+    let Some(deleted_at) = row.deleted_at else {
+        return Ok(false);
+    };
+
+    let mut update = lobby::Entity::update_many()
+        .col_expr(lobby::Column::DeletedAt, Expr::value(Option::<chrono::DateTime<Utc>>::None))
+        .filter(lobby::Column::Id.eq(lobby_id));
+    if row.closed_at == Some(deleted_at) {
+        update = update.col_expr(lobby::Column::ClosedAt, Expr::value(Option::<chrono::DateTime<Utc>>::None));
+    }
+    update.exec(&txn).await?;
+    lobby_player::Entity::update_many()
+        .col_expr(lobby_player::Column::LeftAt, Expr::value(Option::<chrono::DateTime<Utc>>::None))
+        .filter(lobby_player::Column::LobbyId.eq(lobby_id))
+        .filter(lobby_player::Column::LeftAt.eq(deleted_at))
+        .exec(&txn)
+        .await?;
+    txn.commit().await?;
+    Ok(true)
+}
+
+/// Mark a game as deleted; returns false if no live game matched
+pub async fn soft_delete_game(db: &DatabaseConnection, game_id: Uuid) -> Result<bool, DbErr> {
+    let result = game::Entity::update_many()
+        .col_expr(game::Column::DeletedAt, Expr::value(Utc::now()))
+        .filter(game::Column::Id.eq(game_id))
+        .filter(game::Column::DeletedAt.is_null())
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected > 0)
+}
+
+/// Clear a game's deletion marker; returns false if no deleted game matched
+pub async fn restore_game(db: &DatabaseConnection, game_id: Uuid) -> Result<bool, DbErr> {
+    let result = game::Entity::update_many()
+        .col_expr(game::Column::DeletedAt, Expr::value(Option::<chrono::DateTime<Utc>>::None))
+        .filter(game::Column::Id.eq(game_id))
+        .filter(game::Column::DeletedAt.is_not_null())
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected > 0)
+}
+
+/// Permanently remove rows soft-deleted more than `retention` ago.
+/// Returns the number of (lobbies, games) purged.
+pub async fn purge_deleted(db: &DatabaseConnection, retention: chrono::Duration) -> Result<(u64, u64), DbErr> {
+    let cutoff = Utc::now() - retention;
+
+    // Games first: they may reference a lobby. Child rows are removed explicitly
+    // rather than relying on ON DELETE CASCADE being present in every schema.
+    let expired_games: Vec<Uuid> = game::Entity::find()
+        .filter(game::Column::DeletedAt.lt(cutoff))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|g| g.id)
+        .collect();
+
+    game_round::Entity::delete_many()
+        .filter(game_round::Column::GameId.is_in(expired_games.clone()))
+        .exec(db)
+        .await?;
+    round_deal::Entity::delete_many()
+        .filter(round_deal::Column::GameId.is_in(expired_games.clone()))
+        .exec(db)
+        .await?;
+    game_player::Entity::delete_many()
+        .filter(game_player::Column::GameId.is_in(expired_games.clone()))
+        .exec(db)
+        .await?;
+    let games = game::Entity::delete_many()
+        .filter(game::Column::Id.is_in(expired_games))
+        .exec(db)
+        .await?;
+
+    let expired_lobbies: Vec<Uuid> = lobby::Entity::find()
+        .filter(lobby::Column::DeletedAt.lt(cutoff))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|l| l.id)
+        .collect();
+
+    lobby_player::Entity::delete_many()
+        .filter(lobby_player::Column::LobbyId.is_in(expired_lobbies.clone()))
+        .exec(db)
+        .await?;
+
+    let lobbies = lobby::Entity::delete_many()
+        .filter(lobby::Column::Id.is_in(expired_lobbies))
+        .exec(db)
+        .await?;
+
+    Ok((lobbies.rows_affected, games.rows_affected))
+}
+
+/// Run `purge_deleted` every `interval` for the lifetime of the server
+pub fn spawn_purge_task(db: DatabaseConnection, retention: chrono::Duration, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match purge_deleted(&db, retention).await {
+                Ok((0, 0)) => {}
+                Ok((lobbies, games)) => info!("Purged {} soft-deleted lobbies and {} games", lobbies, games),
+                Err(e) => warn!("Failed to purge soft-deleted rows: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,336 @@
+#[cfg(feature = "server")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "server")]
+use std::sync::RwLock;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use tokio::sync::{broadcast, mpsc};
+#[cfg(feature = "server")]
+use crate::engine::PlayerId;
+use crate::protocol::{GameId, LobbyId};
+#[cfg(feature = "server")]
+use crate::presence::PresenceSignal;
+#[cfg(feature = "server")]
+use crate::room_feed::{FeedControl, RoomMessage, ROOM_CHANNEL_CAPACITY};
+
+/// A lobby or game whose members receive the same broadcasts
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "id")]
+pub enum RoomId {
+    Lobby(LobbyId),
+    Game(GameId),
+}
+
+impl RoomId {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RoomId::Lobby(_) => "lobby",
+            RoomId::Game(_) => "game",
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+#[derive(Debug, Default)]
+struct Rooms {
+    members: HashMap<RoomId, HashSet<PlayerId>>,
+    /// Each player's rooms, oldest first
+    joined: HashMap<PlayerId, Vec<RoomId>>,
+    active: HashMap<PlayerId, RoomId>,
+    /// Each room's broadcast channel, open while it has members
+    channels: HashMap<RoomId, broadcast::Sender<RoomMessage>>,
+    /// Control of each connected player's room feed
+    feeds: HashMap<PlayerId, mpsc::UnboundedSender<FeedControl>>,
+}
+
+#[cfg(feature = "server")]
+impl Rooms {
+    fn subscribe(&mut self, player_id: &PlayerId, room: RoomId) {
+        let channel = self.channels.entry(room).or_insert_with(|| broadcast::channel(ROOM_CHANNEL_CAPACITY).0);
+        if let Some(feed) = self.feeds.get(player_id) {
+            let _ = feed.send(FeedControl::Subscribe(room, channel.subscribe()));
+        }
+    }
+
+    fn remove_member(&mut self, room: RoomId, player_id: &PlayerId) {
+        if let Some(members) = self.members.get_mut(&room) {
+            members.remove(player_id);
+            // Queued behind everything published so far, so the player still gets those
+            if let Some(channel) = self.channels.get(&room) {
+                let _ = channel.send(RoomMessage::Left(player_id.clone()));
+            }
+            if members.is_empty() {
+                self.members.remove(&room);
+                self.channels.remove(&room);
+            }
+        }
+        let Some(joined) = self.joined.get_mut(player_id) else { return };
+        joined.retain(|r| *r != room);
+        let fallback = joined.last().copied();
+        if joined.is_empty() {
+            self.joined.remove(player_id);
+        }
+        if self.active.get(player_id) == Some(&room) {
+            match fallback {
+                Some(next) => self.active.insert(player_id.clone(), next),
+                None => self.active.remove(player_id),
+            };
+        }
+    }
+
+    fn rooms_of(&self, player_id: &PlayerId) -> &[RoomId] {
+        self.joined.get(player_id).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+/// Who is in which lobby and game, indexed both ways.
+///
+/// A player is in at most one lobby but may sit at any number of games. Each player has an
+/// active room, which game actions without an explicit target apply to; joining a room makes
+/// it active. LobbyManager and GameManager keep this up to date; the router, broadcasts and
+/// reconnect notifications read from it.
+#[cfg(feature = "server")]
+#[derive(Debug, Default)]
+pub struct RoomRegistry {
+    rooms: RwLock<Rooms>,
+    presence: Option<PresenceSignal>,
+}
+
+#[cfg(feature = "server")]
+impl RoomRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Signal membership changes so friends can be told
+    pub fn with_presence(presence: PresenceSignal) -> Self {
+        Self { presence: Some(presence), ..Self::default() }
+    }
+
+    fn notify(&self, player_id: &PlayerId) {
+        if let Some(presence) = &self.presence {
+            presence.notify(player_id);
+        }
+    }
+
+    /// Put a player in a room and make it their active one. Joining a lobby leaves the
+    /// player's previous lobby, which is returned.
+    pub fn join(&self, player_id: &PlayerId, room: RoomId) -> Option<RoomId> {
+        let mut rooms = self.rooms.write().unwrap();
+        let previous = match room {
+            RoomId::Lobby(_) => rooms.rooms_of(player_id).iter()
+                .find(|r| matches!(r, RoomId::Lobby(_)) && **r != room)
+                .copied(),
+            RoomId::Game(_) => None,
+        };
+        if let Some(previous) = previous {
+            rooms.remove_member(previous, player_id);
+        }
+        let joined = rooms.joined.entry(player_id.clone()).or_default();
+        if !joined.contains(&room) {
+            joined.push(room);
+            rooms.subscribe(player_id, room);
+        }
+        rooms.members.entry(room).or_default().insert(player_id.clone());
+        rooms.active.insert(player_id.clone(), room);
+        drop(rooms);
+        self.notify(player_id);
+        previous
+    }
+
+    /// Take a player out of a room; false if they were not in it
+    pub fn leave(&self, player_id: &PlayerId, room: RoomId) -> bool {
+        let mut rooms = self.rooms.write().unwrap();
+        if !rooms.rooms_of(player_id).contains(&room) {
+            return false;
+        }
+        rooms.remove_member(room, player_id);
+        drop(rooms);
+        self.notify(player_id);
+        true
+    }
+
+    /// Drop a room and everyone's membership of it, returning the former members
+    pub fn close(&self, room: RoomId) -> Vec<PlayerId> {
+        let mut rooms = self.rooms.write().unwrap();
+        let members: Vec<PlayerId> = rooms.members.get(&room).map(|m| m.iter().cloned().collect()).unwrap_or_default();
+        for player_id in &members {
+            rooms.remove_member(room, player_id);
+        }
+        drop(rooms);
+        for player_id in &members {
+            self.notify(player_id);
+        }
+        members
+    }
+
+    /// Choose the room a player's game actions apply to; false unless they are in it
+    pub fn set_active(&self, player_id: &PlayerId, room: RoomId) -> bool {
+        let mut rooms = self.rooms.write().unwrap();
+        if !rooms.rooms_of(player_id).contains(&room) {
+            return false;
+        }
+        rooms.active.insert(player_id.clone(), room);
+        drop(rooms);
+        self.notify(player_id);
+        true
+    }
+
+    /// Route the player's room messages to their feed, starting with the rooms they are in
+    pub fn attach_feed(&self, player_id: &PlayerId, feed: mpsc::UnboundedSender<FeedControl>) {
+        let mut rooms = self.rooms.write().unwrap();
+        rooms.feeds.insert(player_id.clone(), feed);
+        for room in rooms.rooms_of(player_id).to_vec() {
+            rooms.subscribe(player_id, room);
+        }
+    }
+
+    pub fn detach_feed(&self, player_id: &PlayerId) {
+        self.rooms.write().unwrap().feeds.remove(player_id);
+    }
+
+    /// Send one message to every member's feed; false if the room has no listeners
+    pub fn publish(&self, room: RoomId, message: RoomMessage) -> bool {
+        let rooms = self.rooms.read().unwrap();
+        rooms.channels.get(&room).is_some_and(|channel| channel.send(message).is_ok())
+    }
+
+    pub fn active_room(&self, player_id: &PlayerId) -> Option<RoomId> {
+        self.rooms.read().unwrap().active.get(player_id).copied()
+    }
+
+    pub fn lobby_of(&self, player_id: &PlayerId) -> Option<LobbyId> {
+        let rooms = self.rooms.read().unwrap();
+        rooms.rooms_of(player_id).iter().find_map(|room| match room {
+            RoomId::Lobby(id) => Some(*id),
+            RoomId::Game(_) => None,
+        })
+    }
+
+    /// Every game the player is seated at, oldest first
+    pub fn games_of(&self, player_id: &PlayerId) -> Vec<GameId> {
+        let rooms = self.rooms.read().unwrap();
+        rooms.rooms_of(player_id).iter().filter_map(|room| match room {
+            RoomId::Game(id) => Some(*id),
+            RoomId::Lobby(_) => None,
+        }).collect()
+    }
+
+    /// The game a player's game actions apply to: their active room if it is a game,
+    /// otherwise the game they joined most recently
+    pub fn active_game(&self, player_id: &PlayerId) -> Option<GameId> {
+        if let Some(RoomId::Game(id)) = self.active_room(player_id) {
+            return Some(id);
+        }
+        self.games_of(player_id).last().copied()
+    }
+
+    pub fn rooms_of(&self, player_id: &PlayerId) -> Vec<RoomId> {
+        self.rooms.read().unwrap().rooms_of(player_id).to_vec()
+    }
+
+    pub fn members(&self, room: RoomId) -> Vec<PlayerId> {
+        let rooms = self.rooms.read().unwrap();
+        rooms.members.get(&room).map(|m| m.iter().cloned().collect()).unwrap_or_default()
+    }
+
+    /// Everyone who shares a lobby or game with the player
+    pub fn room_mates(&self, player_id: &PlayerId) -> Vec<PlayerId> {
+        let mut mates = HashSet::new();
+        for room in self.rooms_of(player_id) {
+            mates.extend(self.members(room));
+        }
+        mates.remove(player_id);
+        mates.into_iter().collect()
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_join_replaces_lobby_but_adds_games() {
+        let registry = RoomRegistry::new();
+        let player = "p1".to_string();
+        let (first, second, game, other_game) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        assert_eq!(registry.join(&player, RoomId::Lobby(first)), None);
+        assert_eq!(registry.join(&player, RoomId::Game(game)), None);
+        assert_eq!(registry.join(&player, RoomId::Lobby(second)), Some(RoomId::Lobby(first)));
+        assert_eq!(registry.join(&player, RoomId::Game(other_game)), None);
+
+        assert!(registry.members(RoomId::Lobby(first)).is_empty());
+        assert_eq!(registry.lobby_of(&player), Some(second));
+        assert_eq!(registry.games_of(&player), vec![game, other_game]);
+        assert_eq!(
+            registry.rooms_of(&player),
+            vec![RoomId::Game(game), RoomId::Lobby(second), RoomId::Game(other_game)]
+        );
+    }
+
+    #[test]
+    fn test_active_room_targets_game_actions() {
+        let registry = RoomRegistry::new();
+        let player = "p1".to_string();
+        let (lobby, game, other_game) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        registry.join(&player, RoomId::Game(game));
+        registry.join(&player, RoomId::Game(other_game));
+        assert_eq!(registry.active_game(&player), Some(other_game));
+
+        assert!(registry.set_active(&player, RoomId::Game(game)));
+        assert_eq!(registry.active_game(&player), Some(game));
+        assert!(!registry.set_active(&player, RoomId::Lobby(lobby)));
+
+        // With a lobby active, game actions go to the newest game
+        registry.join(&player, RoomId::Lobby(lobby));
+        assert_eq!(registry.active_room(&player), Some(RoomId::Lobby(lobby)));
+        assert_eq!(registry.active_game(&player), Some(other_game));
+
+        // Leaving the active room falls back to the most recently joined one left
+        registry.close(RoomId::Lobby(lobby));
+        assert_eq!(registry.active_room(&player), Some(RoomId::Game(other_game)));
+        registry.close(RoomId::Game(other_game));
+        assert_eq!(registry.active_room(&player), Some(RoomId::Game(game)));
+        registry.leave(&player, RoomId::Game(game));
+        assert_eq!(registry.active_room(&player), None);
+    }
+
+    #[test]
+    fn test_leave_only_matching_room() {
+        let registry = RoomRegistry::new();
+        let player = "p1".to_string();
+        let (lobby, other) = (Uuid::new_v4(), Uuid::new_v4());
+
+        registry.join(&player, RoomId::Lobby(lobby));
+        assert!(!registry.leave(&player, RoomId::Lobby(other)));
+        assert!(registry.leave(&player, RoomId::Lobby(lobby)));
+        assert!(!registry.leave(&player, RoomId::Lobby(lobby)));
+        assert_eq!(registry.lobby_of(&player), None);
+    }
+
+    #[test]
+    fn test_close_and_room_mates() {
+        let registry = RoomRegistry::new();
+        let (a, b, c) = ("a".to_string(), "b".to_string(), "c".to_string());
+        let (lobby, game) = (Uuid::new_v4(), Uuid::new_v4());
+
+        registry.join(&a, RoomId::Lobby(lobby));
+        registry.join(&b, RoomId::Lobby(lobby));
+        registry.join(&a, RoomId::Game(game));
+        registry.join(&c, RoomId::Game(game));
+
+        let mut mates = registry.room_mates(&a);
+        mates.sort();
+        assert_eq!(mates, vec![b.clone(), c.clone()]);
+
+        let mut closed = registry.close(RoomId::Game(game));
+        closed.sort();
+        assert_eq!(closed, vec![a.clone(), c.clone()]);
+        assert!(registry.games_of(&c).is_empty());
+        assert_eq!(registry.room_mates(&a), vec![b]);
+    }
+}
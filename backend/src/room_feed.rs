@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use axum::extract::ws::Message;
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{StreamExt, StreamMap};
+use tracing::{debug, warn};
+use crate::connection::PlayerId;
+use crate::game_events::SequencedMessage;
+use crate::outbound::OutboundSender;
+use crate::protocol::ServerMessage;
+use crate::room::RoomId;
+
+/// Messages a room's channel keeps for members whose connection is behind
+pub const ROOM_CHANNEL_CAPACITY: usize = 256;
+
+/// One message published to a room; each member's feed picks out what is theirs
+#[derive(Debug, Clone)]
+pub enum RoomMessage {
+    /// Serialized once, for every member
+    Everyone(Arc<str>),
+    /// A game message for the listed players, each with their own sequence number
+    Sequenced { message: Arc<ServerMessage>, seqs: Arc<HashMap<PlayerId, u64>> },
+    /// The player left the room; their feed stops listening to it here
+    Left(PlayerId),
+}
+
+pub enum FeedControl {
+    /// Start forwarding a room the player joined
+    Subscribe(RoomId, broadcast::Receiver<RoomMessage>),
+    /// The player reconnected on a new socket
+    Attach(OutboundSender),
+    /// The socket closed; room messages are dropped until the player reconnects
+    Detach,
+}
+
+/// Start the task that forwards a player's room messages to their socket.
+///
+/// Publishing to a room is a single channel send; the serializing and filtering for each
+/// member happens here, in the member's own task. The task ends when the returned sender
+/// is dropped.
+pub fn spawn(player_id: PlayerId, socket: OutboundSender) -> mpsc::UnboundedSender<FeedControl> {
+    let (control, mut controls) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let mut socket = Some(socket);
+        let mut rooms: StreamMap<RoomId, BroadcastStream<RoomMessage>> = StreamMap::new();
+        loop {
+            // Control first, so a new socket is in place before the messages that follow it
+            let (room, received) = tokio::select! {
+                biased;
+                control = controls.recv() => {
+                    match control {
+                        Some(FeedControl::Subscribe(room, receiver)) => {
+                            rooms.insert(room, BroadcastStream::new(receiver));
+                        }
+                        Some(FeedControl::Attach(sender)) => socket = Some(sender),
+                        Some(FeedControl::Detach) => socket = None,
+                        None => break,
+                    }
+                    continue;
+                }
+                Some(next) = rooms.next(), if !rooms.is_empty() => next,
+            };
+
+            if matches!(&received, Ok(RoomMessage::Left(left)) if *left == player_id) {
+                rooms.remove(&room);
+                continue;
+            }
+            let Some(sender) = &socket else { continue };
+            match received {
+                Ok(message) => {
+                    if let Some(text) = text_for(&player_id, message) {
+                        let _ = sender.send_room(room, Message::Text(text.to_string()));
+                    }
+                }
+                Err(BroadcastStreamRecvError::Lagged(missed)) => {
+                    warn!("Player {} fell {} messages behind in {:?}", player_id, missed, room);
+                    let _ = sender.resync(room, missed);
+                }
+            }
+        }
+        debug!("Room feed of player {} stopped", player_id);
+    });
+    control
+}
+
+/// The wire text of a room message for this player, or None if it is not for them
+fn text_for(player_id: &PlayerId, message: RoomMessage) -> Option<Arc<str>> {
+    match message {
+        RoomMessage::Everyone(text) => Some(text),
+        RoomMessage::Sequenced { message, seqs } => {
+            let seq = *seqs.get(player_id)?;
+            let sequenced = SequencedMessage { seq, message: (*message).clone() };
+            match serde_json::to_string(&sequenced) {
+                Ok(json) => Some(json.into()),
+                Err(e) => {
+                    warn!("Failed to serialize message for player {}: {}", player_id, e);
+                    None
+                }
+            }
+        }
+        RoomMessage::Left(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::outbound::{self, OutboundReceiver};
+    use crate::room::RoomRegistry;
+    use uuid::Uuid;
+
+    fn received(rx: &mut OutboundReceiver) -> Vec<ServerMessage> {
+        let mut messages = Vec::new();
+        while let Ok(Message::Text(text)) = rx.try_recv() {
+            messages.push(serde_json::from_str(&text).unwrap());
+        }
+        messages
+    }
+
+    #[tokio::test]
+    async fn test_members_get_room_messages_until_they_leave() {
+        let registry = RoomRegistry::new();
+        let (alice, bob) = ("alice".to_string(), "bob".to_string());
+        let (tx, mut alice_rx) = outbound::channel();
+        registry.attach_feed(&alice, spawn(alice.clone(), tx));
+        let (tx, mut bob_rx) = outbound::channel();
+        registry.attach_feed(&bob, spawn(bob.clone(), tx));
+
+        let room = RoomId::Lobby(Uuid::new_v4());
+        registry.join(&alice, room);
+        registry.join(&bob, room);
+        let pong: Arc<str> = serde_json::to_string(&ServerMessage::Pong).unwrap().into();
+        assert!(registry.publish(room, RoomMessage::Everyone(pong.clone())));
+        registry.leave(&bob, room);
+        assert!(registry.publish(room, RoomMessage::Everyone(pong)));
+        tokio::task::yield_now().await;
+
+        assert_eq!(received(&mut alice_rx).len(), 2);
+        // Bob still gets what was published before he left
+        assert_eq!(received(&mut bob_rx).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_lagging_member_is_told_to_resync() {
+        let registry = RoomRegistry::new();
+        let alice = "alice".to_string();
+        let (tx, mut rx) = outbound::channel();
+        registry.attach_feed(&alice, spawn(alice.clone(), tx));
+        let room = RoomId::Game(Uuid::new_v4());
+        registry.join(&alice, room);
+        tokio::task::yield_now().await;
+
+        // Published faster than the feed runs
+        let pong: Arc<str> = serde_json::to_string(&ServerMessage::Pong).unwrap().into();
+        for _ in 0..ROOM_CHANNEL_CAPACITY + 10 {
+            registry.publish(room, RoomMessage::Everyone(pong.clone()));
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+        let messages = received(&mut rx);
+        assert!(matches!(messages[0], ServerMessage::ResyncRequired { room: r, missed: 10 } if r == room));
+        assert_eq!(messages.len(), ROOM_CHANNEL_CAPACITY + 1);
+    }
+}
@@ -1,19 +1,37 @@
 use std::sync::Arc;
-use std::collections::HashMap;
-use tokio::sync::RwLock;
 use crate::connection::{ConnectionManager, PlayerId};
-use crate::lobby::{LobbyManager, LobbyId};
+use crate::lobby::{LobbyManager, LobbyId, SharedAddress};
 use crate::game::{GameManager, GameId};
-use crate::protocol::{ClientMessage, ServerMessage, PlayerAction};
+use crate::room::{RoomId, RoomRegistry};
+use crate::lobby_feed::{LobbyFeed, LobbyFeedStats, DEFAULT_LOBBY_LIST_INTERVAL};
+use crate::matchmaking::{BackfillMatch, MatchmakingQueue, QueueOutcome};
+use crate::cluster::ClusterRegistry;
+use crate::settings_policy::SettingsPolicy;
+use crate::game_trace::GameTraceFilter;
+use crate::accessibility::{self, SuitEncoding};
+use crate::game_trace;
+use crate::protocol::{ClientEnvelope, ClientMessage, LobbyQuery, ServerMessage, PlayerAction, SharedAddressPolicy};
 use crate::error::RouterError;
+use crate::features::{Feature, FeatureFlags};
+use crate::deprecation::{Deprecation, DeprecationTracker};
+use crate::audit::{AuditKind, AuditLog};
 use tracing::{debug, error, info, warn};
 
 pub struct MessageRouter {
     lobby_manager: Arc<LobbyManager>,
     game_manager: Arc<GameManager>,
     connection_manager: Arc<ConnectionManager>,
-    player_to_game: Arc<RwLock<HashMap<PlayerId, GameId>>>,
-    player_to_lobby: Arc<RwLock<HashMap<PlayerId, LobbyId>>>,
+    matchmaking: MatchmakingQueue,
+    cluster: Option<Arc<ClusterRegistry>>,
+    settings_policy: SettingsPolicy,
+    trace_filter: Arc<GameTraceFilter>,
+    rooms: Arc<RoomRegistry>,
+    lobby_feed: LobbyFeed,
+    features: Arc<FeatureFlags>,
+    deprecations: DeprecationTracker,
+    audit: Option<AuditLog>,
+    /// Set once shutdown begins; nothing new may start
+    draining: std::sync::atomic::AtomicBool,
 }
 
 impl MessageRouter {
@@ -23,20 +41,135 @@ impl MessageRouter {
         connection_manager: Arc<ConnectionManager>,
     ) -> Self {
         Self {
+            trace_filter: game_manager.trace_filter(),
+            rooms: connection_manager.rooms(),
+            lobby_feed: LobbyFeed::new(Arc::clone(&lobby_manager), Arc::clone(&connection_manager), DEFAULT_LOBBY_LIST_INTERVAL),
             lobby_manager,
             game_manager,
             connection_manager,
-            player_to_game: Arc::new(RwLock::new(HashMap::new())),
-            player_to_lobby: Arc::new(RwLock::new(HashMap::new())),
+            matchmaking: MatchmakingQueue::new(),
+            cluster: None,
+            settings_policy: SettingsPolicy::default(),
+            features: Arc::default(),
+            deprecations: DeprecationTracker::default(),
+            audit: None,
+            draining: std::sync::atomic::AtomicBool::new(false),
         }
     }
 
+    /// Enable capacity-aware placement using the shared node registry
+    pub fn with_cluster(mut self, cluster: Arc<ClusterRegistry>) -> Self {
+        self.cluster = Some(cluster);
+        self
+    }
+
+    /// Record lobby joins and game actions, with the sender's address, in `audit`
+    pub fn with_audit_log(mut self, audit: AuditLog) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Send lobby browser updates at most once per `interval`
+    pub fn with_lobby_list_interval(mut self, interval: std::time::Duration) -> Self {
+        self.lobby_feed = LobbyFeed::new(Arc::clone(&self.lobby_manager), Arc::clone(&self.connection_manager), interval);
+        self
+    }
+
+    /// Broadcast and suppression counts for lobby browser updates
+    pub fn lobby_feed_stats(&self) -> LobbyFeedStats {
+        self.lobby_feed.stats()
+    }
+
+    /// Apply operator defaults and bounds to new lobbies and matchmaking games
+    pub fn with_settings_policy(mut self, policy: SettingsPolicy) -> Self {
+        self.settings_policy = policy;
+        self
+    }
+
+    pub fn settings_policy(&self) -> &SettingsPolicy {
+        &self.settings_policy
+    }
+
+    /// Seat players who opted in with bots once they have waited `wait` for a full table
+    pub fn with_queue_backfill(mut self, wait: std::time::Duration) -> Self {
+        self.matchmaking = std::mem::take(&mut self.matchmaking).with_backfill_after(wait);
+        self
+    }
+
+    /// Seat queued players only with others from their region until they have waited `wait`
+    pub fn with_region_matching(mut self, wait: std::time::Duration) -> Self {
+        self.matchmaking = std::mem::take(&mut self.matchmaking).with_region_wait(wait);
+        self
+    }
+
+    /// Serve only the subsystems switched on in `features`
+    pub fn with_features(mut self, features: Arc<FeatureFlags>) -> Self {
+        self.features = features;
+        self
+    }
+
+    pub fn features(&self) -> &Arc<FeatureFlags> {
+        &self.features
+    }
+
+    pub fn lobby_manager(&self) -> &Arc<LobbyManager> {
+        &self.lobby_manager
+    }
+
+    /// Stop creating lobbies and starting games, ahead of a shutdown
+    pub fn begin_drain(&self) {
+        self.draining.store(true, std::sync::atomic::Ordering::Release);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(std::sync::atomic::Ordering::Acquire)
+    }
+
     pub async fn route_message(
         &self,
         player_id: PlayerId,
-        message: ClientMessage,
+        message: impl Into<ClientEnvelope>,
     ) -> Result<(), RouterError> {
-        debug!("Routing message from player {}: {:?}", player_id, message);
+        let ClientEnvelope { message, room_id, request_id } = message.into();
+        debug!("Routing message from player {} to {:?}: {:?}", player_id, room_id, message);
+        let received_ms = chrono::Utc::now().timestamp_millis();
+
+        if let Some(deprecation) = message.deprecated_form() {
+            self.note_deprecated(&player_id, deprecation).await;
+        }
+
+        let required = message.required_role();
+        if !self.connection_manager.role(&player_id).await.allows(required) {
+            warn!("Player {} lacks the {} role for {:?}", player_id, required.as_str(), message);
+            let error = RouterError::Forbidden(required);
+            let error_msg = ServerMessage::Error { message: error.to_string(), request_id };
+            self.connection_manager.send_to_player(player_id, error_msg).await;
+            return Err(error);
+        }
+
+        if let Some(feature) = self.disabled_feature(&player_id, &message).await {
+            debug!("Player {} sent {:?} while {} is disabled", player_id, message, feature.as_str());
+            let error = RouterError::FeatureDisabled(feature);
+            let error_msg = ServerMessage::FeatureDisabled { feature, message: error.to_string(), request_id };
+            self.connection_manager.send_to_player(player_id, error_msg).await;
+            return Err(error);
+        }
+
+        if self.is_draining() && message.starts_something() {
+            let error = RouterError::ShuttingDown;
+            let error_msg = ServerMessage::Error { message: error.to_string(), request_id };
+            self.connection_manager.send_to_player(player_id, error_msg).await;
+            return Err(error);
+        }
+
+        if let Err(error) = Self::check_addressable(&message, room_id) {
+            let error_msg = ServerMessage::Error { message: error.to_string(), request_id };
+            self.connection_manager.send_to_player(player_id, error_msg).await;
+            return Err(error);
+        }
+
+        let audited = self.audit.as_ref()
+            .and_then(|audit| message.audit_kind().map(|kind| (audit, kind, serde_json::json!(message))));
 
         // Match on ClientMessage variants and route to appropriate handlers
         // Each handler is isolated and errors won't affect other games
@@ -45,52 +178,178 @@ impl MessageRouter {
             ClientMessage::CreateLobby { settings } => {
                 self.handle_create_lobby(player_id.clone(), settings).await
             }
+            ClientMessage::CreateLobbyFromPreset { preset_id } => {
+                self.handle_create_lobby_from_preset(player_id.clone(), preset_id).await
+            }
+            ClientMessage::ListPresets => {
+                self.handle_list_presets(player_id.clone()).await
+            }
             ClientMessage::JoinLobby { lobby_id } => {
                 self.handle_join_lobby(player_id.clone(), lobby_id).await
             }
             ClientMessage::LeaveLobby => {
-                self.handle_leave_lobby(player_id.clone()).await
+                self.handle_leave_lobby(player_id.clone(), room_id).await
             }
             ClientMessage::StartGame => {
-                self.handle_start_game(player_id.clone()).await
+                self.handle_start_game(player_id.clone(), room_id).await
             }
-            ClientMessage::ListLobbies => {
-                self.handle_list_lobbies(player_id.clone()).await
+            ClientMessage::ListLobbies(query) => {
+                self.handle_list_lobbies(player_id.clone(), query.unwrap_or_default()).await
             }
             ClientMessage::StartNextRound => {
-                self.handle_start_next_round(player_id.clone()).await
+                self.handle_start_next_round(player_id.clone(), room_id).await
+            }
+            ClientMessage::KickPlayer { player_id: target } => {
+                self.handle_kick_player(player_id.clone(), target, room_id).await
+            }
+            ClientMessage::TransferHost { player_id: new_host } => {
+                self.handle_transfer_host(player_id.clone(), new_host, room_id).await
+            }
+            ClientMessage::SetReady { ready } => {
+                self.handle_set_ready(player_id.clone(), ready, room_id).await
+            }
+            ClientMessage::CloseLobby { lobby_id } => {
+                self.handle_close_lobby(player_id.clone(), lobby_id).await
+            }
+
+            ClientMessage::SetActiveRoom { room } => {
+                self.handle_set_active_room(player_id.clone(), room).await
+            }
+
+            // Matchmaking message handlers
+            ClientMessage::JoinQueue { player_count, backfill } => {
+                self.handle_join_queue(player_id.clone(), player_count, backfill).await
+            }
+            ClientMessage::LeaveQueue => {
+                self.handle_leave_queue(player_id.clone()).await
             }
 
             // Game message handlers
-            ClientMessage::PlaceBid { bid } => {
-                self.handle_place_bid(player_id.clone(), bid).await
+            ClientMessage::PlaceBid { bid, state_version } => {
+                self.handle_place_bid(player_id.clone(), bid, state_version, room_id).await
             }
-            ClientMessage::PlayCard { card } => {
-                self.handle_play_card(player_id.clone(), card).await
+            ClientMessage::PlayCard { card, state_version } => {
+                self.handle_play_card(player_id.clone(), card, state_version, room_id).await
             }
             ClientMessage::RequestGameState => {
-                self.handle_request_game_state(player_id.clone()).await
+                self.handle_request_game_state(player_id.clone(), room_id).await
+            }
+            ClientMessage::RequestHint => {
+                self.handle_request_hint(player_id.clone(), room_id).await
+            }
+            ClientMessage::ResyncFrom { seq } => {
+                self.handle_resync_from(player_id.clone(), seq, room_id).await
+            }
+            ClientMessage::OpenSeat { player_id: seat } => {
+                self.handle_open_seat(player_id.clone(), seat, room_id).await
+            }
+            ClientMessage::ForceTurn { player_id: late } => {
+                self.handle_force_turn(player_id.clone(), late, room_id).await
+            }
+            ClientMessage::TakeSeat { game_id, seat } => {
+                self.handle_take_seat(player_id.clone(), game_id, seat).await
+            }
+            ClientMessage::ListOpenSeats => {
+                self.handle_list_open_seats(player_id.clone()).await
+            }
+
+            ClientMessage::SetSuitEncoding { encoding } => {
+                self.handle_set_suit_encoding(player_id.clone(), encoding).await
             }
 
             // Connection message handlers
             ClientMessage::Ping => {
                 self.handle_ping(player_id.clone()).await
             }
+            ClientMessage::TimeSync { client_time_ms } => {
+                self.handle_time_sync(player_id.clone(), client_time_ms, received_ms).await
+            }
         };
 
+        if let Some((audit, kind, mut detail)) = audited {
+            if let Err(e) = &result {
+                detail["error"] = serde_json::json!(e.to_string());
+            }
+            let address = self.connection_manager.address(&player_id).await;
+            crate::audit::note_peer(&mut detail, address, self.connection_manager.peer_address(&player_id).await);
+            audit.record(kind, Some(&player_id), address, detail).await;
+        }
+
         // Convert errors to ServerMessage::Error and send to client
         // This ensures errors are logged and communicated without crashing
-        if let Err(e) = &result {
-            error!("Error routing message from player {}: {}", player_id, e);
-            let error_msg = ServerMessage::Error {
-                message: e.to_string(),
-            };
-            self.connection_manager.send_to_player(player_id, error_msg).await;
+        match (&result, request_id) {
+            (Err(e), request_id) => {
+                error!("Error routing message from player {}: {}", player_id, e);
+                let error_msg = ServerMessage::Error {
+                    message: e.to_string(),
+                    request_id,
+                };
+                self.connection_manager.send_to_player(player_id, error_msg).await;
+            }
+            (Ok(()), Some(request_id)) => {
+                self.connection_manager.send_to_player(player_id, ServerMessage::Ack { request_id }).await;
+            }
+            (Ok(()), None) => {}
         }
 
         result
     }
 
+    /// The switched off subsystem `message` needs, if any. Bot connections need `Bots` for
+    /// everything but keepalive pings, so turning bots off silences those already connected.
+    async fn disabled_feature(&self, player_id: &PlayerId, message: &ClientMessage) -> Option<Feature> {
+        if let Some(feature) = message.required_feature().filter(|f| !self.features.is_enabled(*f)) {
+            return Some(feature);
+        }
+        let bots_off = !self.features.is_enabled(Feature::Bots) && !matches!(message, ClientMessage::Ping);
+        (bots_off && self.connection_manager.is_bot(player_id).await).then_some(Feature::Bots)
+    }
+
+    /// Reject a `room_id` on messages that are not about a single lobby or game
+    fn check_addressable(message: &ClientMessage, room_id: Option<RoomId>) -> Result<(), RouterError> {
+        if room_id.is_none() {
+            return Ok(());
+        }
+        match message {
+            ClientMessage::LeaveLobby
+            | ClientMessage::StartGame
+            | ClientMessage::KickPlayer { .. }
+            | ClientMessage::TransferHost { .. }
+            | ClientMessage::SetReady { .. }
+            | ClientMessage::StartNextRound
+            | ClientMessage::PlaceBid { .. }
+            | ClientMessage::PlayCard { .. }
+            | ClientMessage::RequestGameState
+            | ClientMessage::RequestHint
+            | ClientMessage::ResyncFrom { .. }
+            | ClientMessage::OpenSeat { .. }
+            | ClientMessage::ForceTurn { .. } => Ok(()),
+            _ => Err(RouterError::from("That message does not take a room_id")),
+        }
+    }
+
+    /// The lobby a lobby action applies to: the addressed lobby, or else the player's lobby
+    fn target_lobby(&self, player_id: &PlayerId, room_id: Option<RoomId>) -> Result<Option<LobbyId>, RouterError> {
+        let lobby_id = self.rooms.lobby_of(player_id);
+        match room_id {
+            None => Ok(lobby_id),
+            Some(RoomId::Lobby(id)) if lobby_id == Some(id) => Ok(lobby_id),
+            Some(room @ RoomId::Lobby(_)) => Err(RouterError::NotMember(room)),
+            Some(RoomId::Game(_)) => Err(RouterError::WrongRoom("lobby")),
+        }
+    }
+
+    /// The game a game action applies to: the addressed game, or else the player's active game
+    fn target_game(&self, player_id: &PlayerId, room_id: Option<RoomId>) -> Result<GameId, RouterError> {
+        match room_id {
+            None => self.rooms.active_game(player_id)
+                .ok_or_else(|| crate::error::GameError::GameNotFound.into()),
+            Some(RoomId::Game(id)) if self.rooms.games_of(player_id).contains(&id) => Ok(id),
+            Some(room @ RoomId::Game(_)) => Err(RouterError::NotMember(room)),
+            Some(RoomId::Lobby(_)) => Err(RouterError::WrongRoom("game")),
+        }
+    }
+
     // Lobby message handlers
 
     async fn handle_create_lobby(
@@ -99,73 +358,86 @@ impl MessageRouter {
         settings: crate::protocol::GameSettings,
     ) -> Result<(), RouterError> {
         info!("Player {} creating lobby", player_id);
+        let _ = self.matchmaking.leave(&player_id).await;
+
+        self.settings_policy.validate(&settings)?;
+        self.lobby_manager.check_tournament(&settings).await?;
+
+        if settings.bots_only && !self.connection_manager.is_bot(&player_id).await {
+            return Err(crate::error::LobbyError::BotsOnly.into());
+        }
         
         let lobby_id = self.lobby_manager.create_lobby(player_id.clone(), settings).await;
         
-        // Track player-to-lobby mapping
-        let mut player_to_lobby = self.player_to_lobby.write().await;
-        player_to_lobby.insert(player_id.clone(), lobby_id);
-        drop(player_to_lobby);
-        
-        let msg = ServerMessage::LobbyCreated { lobby_id };
+        let code = self.lobby_manager.get_lobby(lobby_id).await
+            .map(|lobby| lobby.code)
+            .ok_or(crate::error::LobbyError::LobbyNotFound)?;
+        let msg = ServerMessage::LobbyCreated { lobby_id, code };
         self.connection_manager.send_to_player(player_id, msg).await;
 
         // Broadcast updated lobby list to all players
-        let lobbies = self.lobby_manager.list_lobbies().await;
-        let list_msg = ServerMessage::LobbyList { lobbies };
-        let all_players = self.connection_manager.get_active_players().await;
-        self.connection_manager.broadcast_to_players(&all_players, list_msg).await;
+        self.broadcast_lobby_list().await;
         
         Ok(())
     }
 
+    async fn handle_create_lobby_from_preset(
+        &self,
+        player_id: PlayerId,
+        preset_id: uuid::Uuid,
+    ) -> Result<(), RouterError> {
+        let settings = self.lobby_manager.preset_settings(&player_id, preset_id).await?;
+        self.handle_create_lobby(player_id, settings).await
+    }
+
+    async fn handle_list_presets(
+        &self,
+        player_id: PlayerId,
+    ) -> Result<(), RouterError> {
+        debug!("Player {} requesting lobby presets", player_id);
+
+        let presets = self.lobby_manager.presets(&player_id).await?;
+
+        let msg = ServerMessage::PresetList { presets };
+        self.connection_manager.send_to_player(player_id, msg).await;
+
+        Ok(())
+    }
+
     async fn handle_join_lobby(
         &self,
         player_id: PlayerId,
-        lobby_id: crate::lobby::LobbyId,
+        lobby_ref: crate::short_code::IdOrCode,
     ) -> Result<(), RouterError> {
+        let lobby_id = self.lobby_manager.find_lobby(&lobby_ref).await
+            .ok_or(crate::error::LobbyError::LobbyNotFound)?;
         info!("Player {} joining lobby {}", player_id, lobby_id);
+        let _ = self.matchmaking.leave(&player_id).await;
         
         self.lobby_manager.join_lobby(lobby_id, player_id.clone()).await?;
         
-        // Track player-to-lobby mapping
-        let mut player_to_lobby = self.player_to_lobby.write().await;
-        player_to_lobby.insert(player_id.clone(), lobby_id);
-        drop(player_to_lobby);
-        
         // Get lobby info to send back
         if let Some(lobby) = self.lobby_manager.get_lobby(lobby_id).await {
-            // Build Vec<PlayerInfo>
-            let mut players = Vec::new();
-            for player_id in &lobby.players {
-                if let Some(username) = self.connection_manager.get_username(player_id).await {
-                    players.push(crate::protocol::PlayerInfo {
-                        id: player_id.clone(),
-                        username,
-                    });
-                }
-            }
-            
-            let lobby_info = crate::protocol::LobbyInfo {
-                id: lobby.id,
-                host: lobby.host.clone(),
-                players,
-                max_players: lobby.max_players,
-                settings: lobby.settings.clone(),
-            };
+            let lobby_info = self.lobby_manager.lobby_info(&lobby).await;
             
             let msg = ServerMessage::LobbyJoined { lobby: lobby_info.clone() };
-            self.connection_manager.send_to_player(player_id, msg).await;
+            self.connection_manager.send_to_player(player_id.clone(), msg).await;
 
             // Broadcast LobbyUpdated to all players
             let update_msg = ServerMessage::LobbyUpdated { lobby: lobby_info };
-            self.connection_manager.broadcast_to_players(&lobby.players, update_msg).await;
+            self.connection_manager.broadcast_to_room(RoomId::Lobby(lobby_id), update_msg).await;
+
+            if lobby.settings.shared_address == SharedAddressPolicy::Warn {
+                let shared = self.lobby_manager.shared_addresses(&lobby.players).await;
+                if let Some(shared) = shared.into_iter().find(|shared| shared.players.contains(&player_id)) {
+                    info!("Player {} shares an address with {:?} in lobby {}", player_id, shared.players, lobby_id);
+                    let warning = ServerMessage::SharedAddressWarning { lobby_id, players: shared.players };
+                    self.connection_manager.broadcast_to_room(RoomId::Lobby(lobby_id), warning).await;
+                }
+            }
 
             // Broadcast updated lobby list to all players
-            let lobbies = self.lobby_manager.list_lobbies().await;
-            let list_msg = ServerMessage::LobbyList { lobbies };
-            let all_players = self.connection_manager.get_active_players().await;
-            self.connection_manager.broadcast_to_players(&all_players, list_msg).await;
+            self.broadcast_lobby_list().await;
         }
         
         Ok(())
@@ -174,86 +446,80 @@ impl MessageRouter {
     async fn handle_leave_lobby(
         &self,
         player_id: PlayerId,
+        room_id: Option<RoomId>,
     ) -> Result<(), RouterError> {
         info!("Player {} leaving lobby", player_id);
         
-        // Get the lobby ID from the mapping
-        let lobby_id = {
-            let player_to_lobby = self.player_to_lobby.read().await;
-            player_to_lobby.get(&player_id).cloned()
-        };
-        
-        if let Some(lobby_id) = lobby_id {
+        if let Some(lobby_id) = self.target_lobby(&player_id, room_id)? {
             self.lobby_manager.leave_lobby(lobby_id, player_id.clone()).await?;
-            
-            // Remove from mapping
-            let mut player_to_lobby = self.player_to_lobby.write().await;
-            player_to_lobby.remove(&player_id);
-            drop(player_to_lobby);
-
-            // Broadcast update to remaining players if lobby still exists
-            if let Some(lobby) = self.lobby_manager.get_lobby(lobby_id).await {
-                // Build Vec<PlayerInfo>
-                let mut players = Vec::new();
-                for player_id in &lobby.players {
-                    if let Some(username) = self.connection_manager.get_username(player_id).await {
-                        players.push(crate::protocol::PlayerInfo {
-                            id: player_id.clone(),
-                            username,
-                        });
-                    }
-                }
-                
-                let lobby_info = crate::protocol::LobbyInfo {
-                    id: lobby.id,
-                    host: lobby.host.clone(),
-                    players,
-                    max_players: lobby.max_players,
-                    settings: lobby.settings.clone(),
-                };
-                
-                let update_msg = ServerMessage::LobbyUpdated { lobby: lobby_info };
-                self.connection_manager.broadcast_to_players(&lobby.players, update_msg).await;
-                
-                // Also broadcast updated lobby list to everyone (so player count updates)
-                let lobbies = self.lobby_manager.list_lobbies().await;
-                let list_msg = ServerMessage::LobbyList { lobbies };
-                let all_players = self.connection_manager.get_active_players().await;
-                self.connection_manager.broadcast_to_players(&all_players, list_msg).await;
-            } else {
-                // Lobby was removed (empty), broadcast new list to everyone
-                let lobbies = self.lobby_manager.list_lobbies().await;
-                let list_msg = ServerMessage::LobbyList { lobbies };
-                let all_players = self.connection_manager.get_active_players().await;
-                self.connection_manager.broadcast_to_players(&all_players, list_msg).await;
-            }
+
+            // Broadcast update to remaining players if lobby still exists,
+            // then the updated lobby list to everyone (so player count updates)
+            self.broadcast_lobby_update(lobby_id).await;
+            self.broadcast_lobby_list().await;
         }
         
         Ok(())
     }
 
+    /// Tell `player_id` about a deprecated form they used, the first time in their session
+    pub async fn note_deprecated(&self, player_id: &PlayerId, deprecation: Deprecation) {
+        if self.deprecations.first_use(player_id, deprecation) {
+            info!("Player {} used deprecated {:?}", player_id, deprecation);
+            let notice = ServerMessage::DeprecationNotice { feature: deprecation, remove_after: deprecation.remove_after() };
+            self.connection_manager.send_to_player(player_id.clone(), notice).await;
+        }
+    }
+
+    /// Send a player who opens a new session the room they were in before a restart
+    pub async fn restore_session(&self, player_id: &PlayerId) {
+        match self.rooms.active_room(player_id) {
+            Some(RoomId::Game(game_id)) => {
+                if let Err(e) = self.game_manager.send_game_state(game_id, player_id.clone()).await {
+                    debug!("No game state for {} in game {}: {}", player_id, game_id, e);
+                }
+            }
+            Some(RoomId::Lobby(lobby_id)) => {
+                if let Some(lobby) = self.lobby_manager.get_lobby(lobby_id).await {
+                    let lobby = self.lobby_manager.lobby_info(&lobby).await;
+                    self.connection_manager.send_to_player(player_id.clone(), ServerMessage::LobbyJoined { lobby }).await;
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Release everything held by a player whose reconnect window expired
+    pub async fn handle_session_expired(&self, player_id: PlayerId) {
+        self.deprecations.forget(&player_id);
+        let _ = self.matchmaking.leave(&player_id).await;
+
+        if let Err(e) = self.handle_leave_lobby(player_id.clone(), None).await {
+            warn!("Failed to remove expired player {} from lobby: {}", player_id, e);
+        }
+
+        for game_id in self.rooms.games_of(&player_id) {
+            self.rooms.leave(&player_id, RoomId::Game(game_id));
+            if let Err(e) = self.game_manager.handle_player_departed(game_id, player_id.clone()).await {
+                warn!("Failed to hand over seat of expired player {} in game {}: {}", player_id, game_id, e);
+            }
+        }
+    }
+
     async fn handle_start_game(
         &self,
         player_id: PlayerId,
+        room_id: Option<RoomId>,
     ) -> Result<(), RouterError> {
         info!("Player {} starting game", player_id);
         
-        // Get the lobby ID from the mapping
-        let lobby_id = {
-            let player_to_lobby = self.player_to_lobby.read().await;
-            player_to_lobby.get(&player_id).cloned()
-        };
-        
-        if let Some(lobby_id) = lobby_id {
-            // Get all players in the lobby before starting
-            let players = if let Some(lobby) = self.lobby_manager.get_lobby(lobby_id).await {
-                lobby.players.clone()
-            } else {
-                warn!("Lobby {} found in mapping for player {} but not in manager", lobby_id, player_id);
-                return Err(crate::error::RouterError::from(format!("Internal Error: Lobby {} instance not found", lobby_id)));
+        if let Some(lobby_id) = self.target_lobby(&player_id, room_id)? {
+            let shared = match self.lobby_manager.get_lobby(lobby_id).await {
+                Some(lobby) => self.lobby_manager.shared_addresses(&lobby.players).await,
+                None => Vec::new(),
             };
-            
-            // Start the game
+
+            // Starting moves everyone from the lobby's room into the game's
             let game_id = match self.lobby_manager.start_game(lobby_id, player_id.clone()).await {
                 Ok(id) => id,
                 Err(e) => {
@@ -261,53 +527,258 @@ impl MessageRouter {
                     return Err(e.into());
                 }
             };
-            
-            // Update mappings: remove from lobby, add to game
-            let mut player_to_lobby = self.player_to_lobby.write().await;
-            let mut player_to_game = self.player_to_game.write().await;
-            
-            for player in &players {
-                player_to_lobby.remove(player);
-                player_to_game.insert(player.clone(), game_id);
-            }
-            
+
             info!("Game {} started from lobby {}", game_id, lobby_id);
+            self.flag_shared_addresses(game_id, lobby_id, shared).await;
             Ok(())
         } else {
             // Player is not in any lobby
-            warn!("Player {} attempted to start game but is not in any lobby map", player_id);
+            warn!("Player {} attempted to start game but is not in any lobby", player_id);
             Err(crate::error::RouterError::from("You are not in a lobby".to_string())) 
         }
     }
 
+    /// Put a game whose seats share addresses in the audit log for admins to review, whatever
+    /// the lobby's policy: the host may be one of the players
+    async fn flag_shared_addresses(&self, game_id: GameId, lobby_id: LobbyId, shared: Vec<SharedAddress>) {
+        for shared in shared {
+            warn!("Game {} started with players {:?} sharing an address", game_id, shared.players);
+            let Some(audit) = &self.audit else {
+                continue;
+            };
+            let mut players = Vec::new();
+            for player_id in &shared.players {
+                let user_agent = self.connection_manager.user_agent(player_id).await;
+                players.push(serde_json::json!({ "player_id": player_id, "user_agent": user_agent }));
+            }
+            let detail = serde_json::json!({ "game_id": game_id, "lobby_id": lobby_id, "players": players });
+            audit.record(AuditKind::SharedAddress, None, Some(shared.address), detail).await;
+        }
+    }
+
+    async fn handle_kick_player(
+        &self,
+        player_id: PlayerId,
+        target: PlayerId,
+        room_id: Option<RoomId>,
+    ) -> Result<(), RouterError> {
+        info!("Player {} kicking player {}", player_id, target);
+
+        let lobby_id = self.target_lobby(&player_id, room_id)?.ok_or_else(|| RouterError::from("You are not in a lobby"))?;
+
+        self.lobby_manager.kick_player(lobby_id, player_id, target.clone()).await?;
+
+        let msg = ServerMessage::KickedFromLobby { lobby_id };
+        self.connection_manager.send_to_player(target, msg).await;
+
+        self.broadcast_lobby_update(lobby_id).await;
+        self.broadcast_lobby_list().await;
+
+        Ok(())
+    }
+
+    async fn handle_transfer_host(
+        &self,
+        player_id: PlayerId,
+        new_host: PlayerId,
+        room_id: Option<RoomId>,
+    ) -> Result<(), RouterError> {
+        info!("Player {} transferring host to {}", player_id, new_host);
+
+        let lobby_id = self.target_lobby(&player_id, room_id)?.ok_or_else(|| RouterError::from("You are not in a lobby"))?;
+
+        self.lobby_manager.transfer_host(lobby_id, player_id, new_host).await?;
+
+        self.broadcast_lobby_update(lobby_id).await;
+        self.broadcast_lobby_list().await;
+
+        Ok(())
+    }
+
+    async fn handle_set_ready(
+        &self,
+        player_id: PlayerId,
+        ready: bool,
+        room_id: Option<RoomId>,
+    ) -> Result<(), RouterError> {
+        info!("Player {} set ready: {}", player_id, ready);
+
+        let lobby_id = self.target_lobby(&player_id, room_id)?.ok_or_else(|| RouterError::from("You are not in a lobby"))?;
+
+        self.lobby_manager.set_ready(lobby_id, player_id, ready).await?;
+
+        self.broadcast_lobby_update(lobby_id).await;
+
+        Ok(())
+    }
+
+    async fn handle_close_lobby(
+        &self,
+        player_id: PlayerId,
+        lobby_ref: crate::short_code::IdOrCode,
+    ) -> Result<(), RouterError> {
+        let lobby_id = self.lobby_manager.find_lobby(&lobby_ref).await
+            .ok_or(crate::error::LobbyError::LobbyNotFound)?;
+        info!("Moderator {} closing lobby {}", player_id, lobby_id);
+
+        let players = self.lobby_manager.close_lobby(lobby_id).await?;
+        self.connection_manager.broadcast_to_players(&players, ServerMessage::KickedFromLobby { lobby_id }).await;
+        self.broadcast_lobby_list().await;
+
+        Ok(())
+    }
+
     async fn handle_list_lobbies(
         &self,
         player_id: PlayerId,
+        query: LobbyQuery,
     ) -> Result<(), RouterError> {
         debug!("Player {} requesting lobby list", player_id);
         
-        let lobbies = self.lobby_manager.list_lobbies().await;
+        let page = self.lobby_manager.browse_lobbies(&player_id, &query).await;
         
-        let msg = ServerMessage::LobbyList { lobbies };
+        let msg = ServerMessage::LobbyList { lobbies: page.lobbies, next_cursor: page.next_cursor };
         self.connection_manager.send_to_player(player_id, msg).await;
         
         Ok(())
     }
 
+    async fn handle_set_active_room(
+        &self,
+        player_id: PlayerId,
+        room: RoomId,
+    ) -> Result<(), RouterError> {
+        debug!("Player {} switching active room to {:?}", player_id, room);
+
+        if !self.rooms.set_active(&player_id, room) {
+            return Err(RouterError::from("You are not in that room"));
+        }
+
+        let msg = ServerMessage::ActiveRoom {
+            room: self.rooms.active_room(&player_id),
+            rooms: self.rooms.rooms_of(&player_id),
+        };
+        self.connection_manager.send_to_player(player_id.clone(), msg).await;
+
+        // Bring the client up to date with the table it switched to
+        if let RoomId::Game(game_id) = room {
+            self.game_manager.send_game_state(game_id, player_id).await?;
+        }
+
+        Ok(())
+    }
+
+    // Matchmaking message handlers
+
+    async fn handle_join_queue(
+        &self,
+        player_id: PlayerId,
+        player_count: usize,
+        backfill: bool,
+    ) -> Result<(), RouterError> {
+        info!("Player {} joining matchmaking queue for {} players", player_id, player_count);
+
+        if self.rooms.lobby_of(&player_id).is_some() {
+            return Err(RouterError::from("Leave your lobby before joining the queue"));
+        }
+
+        if !self.settings_policy.allows_player_count(player_count) {
+            return Err(crate::error::MatchmakingError::InvalidPlayerCount.into());
+        }
+
+        let region = self.connection_manager.region(&player_id).await;
+
+        // In a multi-node deployment, send the player to the least-loaded node, preferring
+        // nodes in their region
+        if let Some(cluster) = &self.cluster {
+            let local_games = self.game_manager.get_stats().await.active_games;
+            if let Some(node) = cluster.placement_hint(local_games, region.as_ref()).await {
+                info!("Directing player {} to less loaded node {}", player_id, node.node_id);
+                let msg = ServerMessage::ConnectionHint { node_id: node.node_id, url: node.public_url };
+                self.connection_manager.send_to_player(player_id, msg).await;
+                return Ok(());
+            }
+        }
+
+        // Bots only fill seats while the server runs bots at all
+        let backfill_after = self.matchmaking.backfill_after()
+            .filter(|_| backfill && self.features.is_enabled(Feature::Bots));
+        match self.matchmaking.join(player_id.clone(), player_count, backfill_after.is_some(), region).await? {
+            QueueOutcome::Waiting { position } => {
+                let backfill_after_secs = backfill_after.map(|wait| wait.as_secs());
+                let msg = ServerMessage::QueueJoined { player_count, position, backfill_after_secs };
+                self.connection_manager.send_to_player(player_id, msg).await;
+            }
+            QueueOutcome::Matched(players) => self.start_matched_game(player_count, players).await,
+        }
+
+        Ok(())
+    }
+
+    async fn start_matched_game(&self, player_count: usize, players: Vec<PlayerId>) {
+        // create_game seats everyone in the game's room and sends GameStarting and the first YourTurn
+        let settings = self.settings_policy.defaults_for(player_count);
+        let game_id = self.game_manager.create_game_with_settings(players, None, settings).await;
+
+        info!("Game {} started from matchmaking", game_id);
+    }
+
+    /// Seat queued players who waited out the region wait with whoever else is waiting, then
+    /// fill the tables of those who waited out the backfill wait with bots
+    pub async fn run_matchmaking(&self) {
+        if self.is_draining() {
+            return;
+        }
+        for (player_count, players) in self.matchmaking.take_due_matches(std::time::Instant::now()).await {
+            self.start_matched_game(player_count, players).await;
+        }
+        self.backfill_queues().await;
+    }
+
+    /// Start games for queued players who opted into bot backfill and waited it out
+    pub async fn backfill_queues(&self) {
+        if !self.features.is_enabled(Feature::Bots) {
+            return;
+        }
+        for BackfillMatch { player_count, players, bots } in self.matchmaking.take_backfills(std::time::Instant::now()).await {
+            let settings = self.settings_policy.defaults_for(player_count);
+            let game_id = self.game_manager.create_backfilled_game(players.clone(), bots, settings).await;
+            info!("Game {} started from matchmaking with {} bots", game_id, bots);
+
+            let message = format!(
+                "Not enough players joined in time, so {} {} the empty seats. This game is unrated.",
+                bots,
+                if bots == 1 { "bot takes" } else { "bots take" },
+            );
+            for player_id in players {
+                let msg = ServerMessage::QueueBackfilled { game_id, bots, message: message.clone() };
+                self.connection_manager.send_to_player(player_id, msg).await;
+            }
+        }
+    }
+
+    async fn handle_leave_queue(
+        &self,
+        player_id: PlayerId,
+    ) -> Result<(), RouterError> {
+        info!("Player {} leaving matchmaking queue", player_id);
+
+        self.matchmaking.leave(&player_id).await?;
+        self.connection_manager.send_to_player(player_id, ServerMessage::QueueLeft).await;
+
+        Ok(())
+    }
+
     // Game message handlers
 
     async fn handle_start_next_round(
         &self,
         player_id: PlayerId,
+        room_id: Option<RoomId>,
     ) -> Result<(), RouterError> {
         info!("Player {} starting next round", player_id);
         
-        // Get the game ID from the mapping
-        let game_id = {
-            let player_to_game = self.player_to_game.read().await;
-            player_to_game.get(&player_id).cloned()
-                .ok_or(crate::error::GameError::GameNotFound)?
-        };
+        let game_id = self.target_game(&player_id, room_id)?;
         
         self.game_manager.handle_start_next_round(game_id, player_id.clone()).await?;
         
@@ -317,66 +788,172 @@ impl MessageRouter {
     async fn handle_place_bid(
         &self,
         player_id: PlayerId,
-        bid: crate::game_logic::bidding::Bid,
+        bid: crate::engine::bidding::Bid,
+        state_version: Option<u64>,
+        room_id: Option<RoomId>,
     ) -> Result<(), RouterError> {
         info!("Player {} placing bid: {:?}", player_id, bid);
         
-        // Get the game ID from the mapping
-        let game_id = {
-            let player_to_game = self.player_to_game.read().await;
-            player_to_game.get(&player_id).cloned()
-                .ok_or(crate::error::GameError::GameNotFound)?
-        };
+        let game_id = self.target_game(&player_id, room_id)?;
         
         let action = PlayerAction::Bid(bid);
-        self.game_manager.handle_player_action(game_id, player_id.clone(), action).await?;
-        
-        Ok(())
+        self.perform_game_action(game_id, player_id, action, state_version).await
     }
 
     async fn handle_play_card(
         &self,
         player_id: PlayerId,
-        card: crate::game_logic::card::Card,
+        card: crate::engine::card::Card,
+        state_version: Option<u64>,
+        room_id: Option<RoomId>,
     ) -> Result<(), RouterError> {
         info!("Player {} playing card: {:?}", player_id, card);
         
-        // Get the game ID from the mapping
-        let game_id = {
-            let player_to_game = self.player_to_game.read().await;
-            player_to_game.get(&player_id).cloned()
-                .ok_or(crate::error::GameError::GameNotFound)?
-        };
+        let game_id = self.target_game(&player_id, room_id)?;
         
         let action = PlayerAction::PlayCard(card);
-        self.game_manager.handle_player_action(game_id, player_id.clone(), action).await?;
-        
+        self.perform_game_action(game_id, player_id, action, state_version).await
+    }
+
+    /// Apply a game action, checking the state version for sessions that were resumed
+    async fn perform_game_action(
+        &self,
+        game_id: GameId,
+        player_id: PlayerId,
+        action: PlayerAction,
+        state_version: Option<u64>,
+    ) -> Result<(), RouterError> {
+        let needs_resync = self.connection_manager.needs_resync(&player_id).await;
+        game_trace!(self.trace_filter, game_id, player = %player_id, ?state_version, needs_resync, "Routing game action");
+
+        if !needs_resync {
+            self.game_manager.handle_player_action(game_id, player_id, action).await?;
+            return Ok(());
+        }
+
+        let expected = state_version.ok_or(crate::error::GameError::ResyncRequired)?;
+        self.game_manager
+            .handle_player_action_at_version(game_id, player_id.clone(), action, Some(expected))
+            .await?;
+        self.connection_manager.clear_resync(&player_id).await;
+
         Ok(())
     }
 
     async fn handle_request_game_state(
         &self,
         player_id: PlayerId,
+        room_id: Option<RoomId>,
     ) -> Result<(), RouterError> {
         debug!("Player {} requesting game state", player_id);
         
-        // Get the game ID from the mapping
-        let game_id = {
-            let player_to_game = self.player_to_game.read().await;
-            player_to_game.get(&player_id).cloned()
-                .ok_or(crate::error::GameError::GameNotFound)?
-        };
+        let game_id = self.target_game(&player_id, room_id)?;
         
-        let state = self.game_manager.get_game_state(game_id, player_id.clone()).await?;
-        
-        let msg = ServerMessage::GameState { state };
-        self.connection_manager.send_to_player(player_id, msg).await;
+        self.game_manager.send_game_state(game_id, player_id.clone()).await?;
+        self.connection_manager.clear_resync(&player_id).await;
         
         Ok(())
     }
 
+    async fn handle_request_hint(
+        &self,
+        player_id: PlayerId,
+        room_id: Option<RoomId>,
+    ) -> Result<(), RouterError> {
+        let game_id = self.target_game(&player_id, room_id)?;
+        self.game_manager.send_hint(game_id, player_id).await?;
+        Ok(())
+    }
+
+    async fn handle_resync_from(
+        &self,
+        player_id: PlayerId,
+        seq: u64,
+        room_id: Option<RoomId>,
+    ) -> Result<(), RouterError> {
+        debug!("Player {} resyncing from seq {}", player_id, seq);
+
+        let game_id = self.target_game(&player_id, room_id)?;
+
+        self.game_manager.resync_from(game_id, player_id.clone(), seq).await?;
+        self.connection_manager.clear_resync(&player_id).await;
+
+        Ok(())
+    }
+
+    async fn handle_open_seat(
+        &self,
+        player_id: PlayerId,
+        seat: PlayerId,
+        room_id: Option<RoomId>,
+    ) -> Result<(), RouterError> {
+        let game_id = self.target_game(&player_id, room_id)?;
+        self.game_manager.open_seat(game_id, &player_id, seat).await?;
+        Ok(())
+    }
+
+    async fn handle_force_turn(
+        &self,
+        player_id: PlayerId,
+        late: PlayerId,
+        room_id: Option<RoomId>,
+    ) -> Result<(), RouterError> {
+        let game_id = self.target_game(&player_id, room_id)?;
+        self.game_manager.force_turn(game_id, &player_id, late).await?;
+        Ok(())
+    }
+
+    async fn handle_take_seat(
+        &self,
+        player_id: PlayerId,
+        game_ref: crate::short_code::IdOrCode,
+        seat: PlayerId,
+    ) -> Result<(), RouterError> {
+        let game_id = self.game_manager.find_game(&game_ref).await
+            .ok_or(crate::error::GameError::GameNotFound)?;
+        let _ = self.matchmaking.leave(&player_id).await;
+
+        self.game_manager.take_seat(game_id, seat, player_id).await?;
+        Ok(())
+    }
+
+    async fn handle_list_open_seats(&self, player_id: PlayerId) -> Result<(), RouterError> {
+        let seats = self.game_manager.open_seats().await;
+        self.connection_manager.send_to_player(player_id, ServerMessage::OpenSeatList { seats }).await;
+        Ok(())
+    }
+
+    // Broadcast helpers
+
+    /// Send LobbyUpdated to every member of the lobby, if it still exists
+    async fn broadcast_lobby_update(&self, lobby_id: LobbyId) {
+        if let Some(lobby) = self.lobby_manager.get_lobby(lobby_id).await {
+            let lobby_info = self.lobby_manager.lobby_info(&lobby).await;
+            let update_msg = ServerMessage::LobbyUpdated { lobby: lobby_info };
+            self.connection_manager.broadcast_to_room(RoomId::Lobby(lobby.id), update_msg).await;
+        }
+    }
+
+    /// Let every active player's lobby browser know the list changed, coalescing bursts
+    async fn broadcast_lobby_list(&self) {
+        self.lobby_feed.lobbies_changed().await;
+    }
+
     // Connection message handlers
 
+    async fn handle_set_suit_encoding(
+        &self,
+        player_id: PlayerId,
+        encoding: SuitEncoding,
+    ) -> Result<(), RouterError> {
+        debug!("Player {} switching suit encoding to {:?}", player_id, encoding);
+
+        self.connection_manager.set_suit_encoding(&player_id, encoding).await;
+        self.connection_manager.send_to_player(player_id, accessibility::display_profile(encoding)).await;
+
+        Ok(())
+    }
+
     async fn handle_ping(
         &self,
         player_id: PlayerId,
@@ -388,4 +965,57 @@ impl MessageRouter {
         
         Ok(())
     }
+
+    async fn handle_time_sync(
+        &self,
+        player_id: PlayerId,
+        client_time_ms: i64,
+        server_received_ms: i64,
+    ) -> Result<(), RouterError> {
+        let msg = ServerMessage::TimeSync {
+            client_time_ms,
+            server_received_ms,
+            server_sent_ms: chrono::Utc::now().timestamp_millis(),
+        };
+        self.connection_manager.send_to_player(player_id, msg).await;
+
+        Ok(())
+    }
+}
+
+/// Periodically seat matchmaking players whose waits ran out
+pub fn spawn_matchmaking_task(router: Arc<MessageRouter>, interval: std::time::Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            router.run_matchmaking().await;
+        }
+    })
+}
+
+/// Periodically close lobbies nobody touched for `idle` and drop them from everyone's lobby list
+pub fn spawn_lobby_expiry_task(router: Arc<MessageRouter>, idle: std::time::Duration, interval: std::time::Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if !router.lobby_manager.expire_idle_lobbies(idle).await.is_empty() {
+                router.broadcast_lobby_list().await;
+            }
+        }
+    })
+}
+
+/// Periodically drop sessions past their reconnect window and release their lobbies and game seats
+pub fn spawn_session_reaper(router: Arc<MessageRouter>, interval: std::time::Duration) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            for player_id in router.connection_manager.cleanup_expired_sessions().await {
+                router.handle_session_expired(player_id).await;
+            }
+        }
+    })
 }
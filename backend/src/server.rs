@@ -1,9 +1,10 @@
 use crate::error::ServerError;
 use crate::connection::{ConnectionManager, PlayerId};
-use crate::protocol::{ClientMessage, ServerMessage};
+use crate::protocol::{ClientEnvelope, ServerMessage};
 use crate::game::GameManager;
+use crate::rate_limit::RateDecision;
 use axum::{
-    extract::{ws::{WebSocket, WebSocketUpgrade, Message}, State, Query},
+    extract::{ws::{WebSocket, WebSocketUpgrade, Message}, ConnectInfo, State, Query},
     response::{IntoResponse, Json},
     routing::get,
     Router,
@@ -11,7 +12,7 @@ use axum::{
 use std::sync::Arc;
 use std::collections::HashMap;
 use tokio::signal;
-use tokio::sync::mpsc;
+use crate::outbound;
 use tracing::{info, warn, error, debug};
 use futures::{StreamExt, SinkExt};
 
@@ -21,6 +22,51 @@ pub struct ServerConfig {
     pub max_connections: usize,
     pub turn_timeout_secs: u64,
     pub log_level: String,
+    pub bot_messages_per_sec: u32,
+    pub admin_token: Option<String>,
+    pub soft_delete_retention_days: i64,
+    pub node_id: String,
+    /// Set to join the shared node registry; unset runs as a single node
+    pub node_public_url: Option<String>,
+    pub node_max_games: usize,
+    pub leaderboard_refresh_secs: u64,
+    /// Default lobby settings and the ranges players may choose from
+    pub game_settings: crate::settings_policy::SettingsPolicy,
+    /// Message limits for player connections; bots use `bot_messages_per_sec` instead
+    pub rate_limit: crate::rate_limit::RateLimitConfig,
+    pub ping_interval_secs: u64,
+    /// Unanswered keepalive pings before a connection counts as dropped
+    pub max_missed_pongs: u32,
+    /// Minimum gap between lobby browser broadcasts
+    pub lobby_list_interval_ms: u64,
+    pub jwt_keys: crate::auth::JwtKeys,
+    /// Rate limits and cache lifetime for the public read-only endpoints
+    pub public_api: crate::public_api::PublicApiConfig,
+    /// Subsystems switched on at startup; admins can change them at runtime
+    pub features: std::collections::HashSet<crate::features::Feature>,
+    /// Answer LAN discovery probes so desktop clients on the network can find this server
+    pub lan_discovery: bool,
+    /// Name shown to clients that discover this server
+    pub server_name: String,
+    /// Wait before matchmaking fills an opted-in player's table with bots; 0 never does
+    pub queue_backfill_secs: u64,
+    /// Region this node serves; sessions that report none are placed here
+    pub region: Option<crate::region::Region>,
+    /// Wait before matchmaking seats a player with others outside their region
+    pub matchmaking_region_wait_secs: u64,
+    /// After SIGTERM, how long running games may finish before they are saved to resume later
+    pub shutdown_grace_secs: u64,
+    /// How long a disconnected player's turn clock stands still; 0 never pauses it
+    pub disconnect_grace_secs: u64,
+    /// Minutes a lobby may go without activity before it is closed; 0 keeps idle lobbies
+    pub lobby_idle_minutes: u64,
+    /// Serve HTTPS and wss:// with this certificate; unset serves plain HTTP
+    pub tls: Option<crate::tls::TlsConfig>,
+    /// Browser origins allowed to call the HTTP API
+    pub cors_origins: crate::cors::CorsOrigins,
+    /// Proxies whose `X-Forwarded-For` and `X-Real-IP` headers name the client
+    pub trusted_proxies: crate::client_ip::TrustedProxies,
+    pub database_url: String,
 }
 
 pub struct AppState {
@@ -28,6 +74,16 @@ pub struct AppState {
     pub game_manager: Arc<GameManager>,
     pub message_router: Arc<crate::router::MessageRouter>,
     pub db: sea_orm::DatabaseConnection,
+    pub bot_messages_per_sec: u32,
+    pub admin_token: Option<String>,
+    /// Keys for signing and verifying session tokens
+    pub jwt_keys: crate::auth::JwtKeys,
+    pub leaderboards: Arc<crate::leaderboard::LeaderboardCache>,
+    pub presence: Arc<crate::presence::PresenceService>,
+    pub public_api: Arc<crate::public_api::PublicApi>,
+    pub region: Option<crate::region::Region>,
+    pub audit: crate::audit::AuditLog,
+    pub trusted_proxies: crate::client_ip::TrustedProxies,
 }
 
 pub async fn run_server(
@@ -36,6 +92,8 @@ pub async fn run_server(
     game_manager: Arc<GameManager>,
     message_router: Arc<crate::router::MessageRouter>,
     db_pool: sea_orm::DatabaseConnection,
+    leaderboards: Arc<crate::leaderboard::LeaderboardCache>,
+    presence: Arc<crate::presence::PresenceService>,
 ) -> Result<(), ServerError> {
     let addr = format!("{}:{}", config.host, config.port);
     
@@ -43,65 +101,216 @@ pub async fn run_server(
     info!("Configuration: max_connections={}, turn_timeout={}s, log_level={}", 
           config.max_connections, config.turn_timeout_secs, config.log_level);
     
+    if config.lan_discovery {
+        let bind = std::net::SocketAddr::from(([0, 0, 0, 0], crate::discovery::DISCOVERY_PORT));
+        let announcement = crate::discovery::Announcement::new(config.server_name.clone(), config.port);
+        match tokio::net::UdpSocket::bind(bind).await {
+            Ok(socket) => {
+                crate::discovery::spawn_announcer(socket, announcement);
+            }
+            Err(e) => warn!("LAN discovery disabled, could not listen on {}: {}", bind, e),
+        }
+    }
+
+    let draining = drain(
+        Arc::clone(&connection_manager),
+        Arc::clone(&game_manager),
+        Arc::clone(&message_router),
+        std::time::Duration::from_secs(config.shutdown_grace_secs),
+    );
+    let shutdown = async move {
+        shutdown_signal().await;
+        draining.await;
+    };
+    let app = build_app(&config, connection_manager, game_manager, message_router, db_pool, leaderboards, presence);
+
+    // Load the certificate before binding so a bad one fails fast
+    let acceptor = config.tls.as_ref().map(|tls| tls.acceptor()).transpose()?;
+
+    // Create TCP listener
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .map_err(ServerError::Io)?;
+
+    match acceptor {
+        Some(acceptor) => {
+            info!("Server listening on {} with TLS", addr);
+            crate::tls::serve_tls(listener, acceptor, app, shutdown).await
+        }
+        None => {
+            info!("Server listening on {}", addr);
+            serve(listener, app, shutdown).await
+        }
+    }
+}
+
+/// The HTTP and WebSocket routes with their shared state
+pub fn build_app(
+    config: &ServerConfig,
+    connection_manager: Arc<ConnectionManager>,
+    game_manager: Arc<GameManager>,
+    message_router: Arc<crate::router::MessageRouter>,
+    db_pool: sea_orm::DatabaseConnection,
+    leaderboards: Arc<crate::leaderboard::LeaderboardCache>,
+    presence: Arc<crate::presence::PresenceService>,
+) -> Router {
+    let public_api = Arc::new(crate::public_api::PublicApi::new(config.public_api).with_trusted_proxies(config.trusted_proxies.clone()));
+    // A fresh aggregation makes cached leaderboard responses stale
+    let api = Arc::clone(&public_api);
+    leaderboards.on_refresh(move || api.cache().invalidate("/api/leaderboard"));
+    let api = Arc::clone(&public_api);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            api.prune();
+        }
+    });
+
     let app_state = Arc::new(AppState {
         connection_manager,
         game_manager,
         message_router,
+        audit: crate::audit::AuditLog::new(db_pool.clone()),
         db: db_pool,
+        bot_messages_per_sec: config.bot_messages_per_sec,
+        admin_token: config.admin_token.clone(),
+        jwt_keys: config.jwt_keys.clone(),
+        leaderboards,
+        presence,
+        public_api: Arc::clone(&public_api),
+        region: config.region.clone(),
+        trusted_proxies: config.trusted_proxies.clone(),
     });
     
-    // CORS configuration
-    let cors = tower_http::cors::CorsLayer::new()
-        // Allow requests from any origin or specifically the frontend dev server
-        .allow_origin(tower_http::cors::Any)
-        .allow_methods([
-            axum::http::Method::GET,
-            axum::http::Method::POST,
-            axum::http::Method::OPTIONS,
-        ])
-        .allow_headers([
-            axum::http::HeaderName::from_static("content-type"),
-            axum::http::HeaderName::from_static("authorization"),
-        ]);
+    let cors = config.cors_origins.layer();
+
+    // Moderation endpoints; admins pass too
+    let moderator_routes = Router::new()
+        .route("/api/admin/games/:id/trace", axum::routing::put(crate::handlers::admin::enable_game_trace).delete(crate::handlers::admin::disable_game_trace))
+        .route("/api/admin/traces", get(crate::handlers::admin::list_game_traces))
+        .route("/api/admin/bug-reports", get(crate::handlers::admin::list_bug_reports))
+        .route("/api/admin/bug-reports/:id", get(crate::handlers::admin::get_bug_report))
+        .route_layer(axum::middleware::from_fn_with_state(Arc::clone(&app_state), crate::handlers::admin::require_moderator));
+
+    let admin_routes = Router::new()
+        .route("/api/admin/lobbies/:id/restore", axum::routing::post(crate::handlers::admin::restore_lobby))
+        .route("/api/admin/games/:id", axum::routing::delete(crate::handlers::admin::delete_game))
+        .route("/api/admin/games/:id/restore", axum::routing::post(crate::handlers::admin::restore_game))
+        .route("/api/admin/tournaments", get(crate::handlers::admin::list_tournaments).post(crate::handlers::admin::create_tournament))
+        .route("/api/admin/users/:id/role", axum::routing::put(crate::handlers::admin::set_user_role))
+        .route("/api/admin/users/:id/password-reset", axum::routing::post(crate::handlers::admin::issue_password_reset))
+        .route("/api/admin/features", get(crate::handlers::admin::list_features))
+        .route("/api/admin/features/:feature", axum::routing::put(crate::handlers::admin::set_feature))
+        .route("/api/admin/audit", get(crate::handlers::admin::search_audit_log))
+        .route_layer(axum::middleware::from_fn_with_state(Arc::clone(&app_state), crate::handlers::admin::require_admin));
+
+    // Read-only endpoints anyone may poll, rate limited per address and briefly cached
+    let public_routes = Router::new()
+        .route("/api/leaderboard", get(crate::handlers::leaderboard::get_leaderboard))
+        .route("/api/lobbies", get(crate::handlers::lobby::list_lobbies))
+        .route("/api/profiles/:id", get(crate::handlers::user::get_public_profile))
+        .route_layer(axum::middleware::from_fn_with_state(Arc::clone(&public_api), crate::public_api::cache_responses))
+        .route_layer(axum::middleware::from_fn_with_state(public_api, crate::public_api::rate_limit));
 
     // Build the Axum router with shared state
-    let app = Router::new()
+    Router::new()
         .route("/ws", get(ws_handler))
+        .route("/bot/ws", get(bot_ws_handler))
         .route("/health", get(health_check))
         .route("/stats", get(stats_handler))
+        .route("/api/docs", get(crate::openapi::openapi_spec))
         .route("/api/register", axum::routing::post(crate::handlers::auth::register))
         .route("/api/login", axum::routing::post(crate::handlers::auth::login))
+        .route("/api/change-password", axum::routing::post(crate::handlers::auth::change_password))
+        .route("/api/reset-password", axum::routing::post(crate::handlers::auth::reset_password))
+        .route("/api/settings", get(crate::handlers::user::get_settings).put(crate::handlers::user::update_settings))
+        .route("/api/profile", get(crate::handlers::user::get_profile).patch(crate::handlers::user::update_profile))
+        .route("/api/history", get(crate::handlers::user::game_history))
+        .route("/api/games/:id/deals", get(crate::handlers::user::game_deals))
+        .route("/api/games/:id/transcript", get(crate::handlers::user::game_transcript))
+        .route("/api/fairness", get(crate::handlers::fairness::get_fairness))
+        .route("/api/digests", get(crate::handlers::user::weekly_digests))
+        .route("/api/achievements", get(crate::handlers::user::achievements))
+        .route("/api/presets", get(crate::handlers::user::list_presets).post(crate::handlers::user::save_preset))
+        .route("/api/presets/:id", axum::routing::delete(crate::handlers::user::delete_preset))
+        .route("/api/game-settings", get(game_settings_handler))
+        .route("/api/friends", get(crate::handlers::friends::list_friends))
+        .route("/api/friends/:id", axum::routing::put(crate::handlers::friends::add_friend).delete(crate::handlers::friends::remove_friend))
+        .route("/api/presence", get(crate::handlers::friends::get_presence))
+        .route("/api/blocks", get(crate::handlers::friends::list_blocked))
+        .route("/api/blocks/:id", axum::routing::put(crate::handlers::friends::block_user).delete(crate::handlers::friends::unblock_user))
+        .merge(public_routes)
+        .merge(moderator_routes)
+        .merge(admin_routes)
         .layer(cors)
-        .with_state(app_state);
-    
-    // Create TCP listener
-    let listener = tokio::net::TcpListener::bind(&addr)
-        .await
-        .map_err(|e| ServerError::Io(e))?;
-    
-    info!("Server listening on {}", addr);
-    
-    // Run server with graceful shutdown
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
+        .with_state(app_state)
+}
+
+/// How long shutdown waits for queued lobby and round writes to reach the database
+const DB_WRITES_FLUSH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Take the server out of service: tell every player, refuse new lobbies and games, give
+/// running games up to `grace` to finish, then save the rest to resume after the restart and
+/// close every connection
+pub async fn drain(
+    connection_manager: Arc<ConnectionManager>,
+    game_manager: Arc<GameManager>,
+    message_router: Arc<crate::router::MessageRouter>,
+    grace: std::time::Duration,
+) {
+    message_router.begin_drain();
+    let players = connection_manager.get_active_players().await;
+    info!("Draining: {} players connected, {} games running", players.len(), game_manager.unfinished_games().await);
+    connection_manager
+        .broadcast_to_players(&players, ServerMessage::ServerMaintenance { shutdown_in_secs: grace.as_secs() })
+        .await;
+
+    let deadline = tokio::time::Instant::now() + grace;
+    while game_manager.unfinished_games().await > 0 && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(std::time::Duration::from_secs(1).min(grace)).await;
+    }
+
+    let saved = game_manager.suspend_games().await;
+    if saved > 0 {
+        info!("Saved {} unfinished games to resume after the restart", saved);
+    }
+    if tokio::time::timeout(DB_WRITES_FLUSH_TIMEOUT, game_manager.db_writes().flush()).await.is_err() {
+        warn!("Shutting down with lobby and round writes still waiting on the database");
+    }
+    for player_id in connection_manager.get_active_players().await {
+        connection_manager.close_connection(&player_id, "Server restarting for maintenance").await;
+    }
+}
+
+/// Serve `app` on `listener` until `shutdown` completes
+pub async fn serve(
+    listener: tokio::net::TcpListener,
+    app: Router,
+    shutdown: impl std::future::Future<Output = ()> + Send + 'static,
+) -> Result<(), ServerError> {
+    axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+        .with_graceful_shutdown(shutdown)
         .await
-        .map_err(|e| ServerError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
-    
+        .map_err(|e| ServerError::Io(std::io::Error::other(e)))?;
+
     info!("Server shutdown complete");
     Ok(())
 }
 
 async fn ws_handler(
     ws: WebSocketUpgrade,
+    ConnectInfo(addr): ConnectInfo<std::net::SocketAddr>,
     State(app_state): State<Arc<AppState>>,
     Query(params): Query<HashMap<String, String>>,
+    headers: axum::http::HeaderMap,
 ) -> impl IntoResponse {
     // 1. JWT Authentication
     let token = params.get("token").cloned();
     let _reconnect_id = params.get("player_id").and_then(|id| id.parse::<PlayerId>().ok());
     
     let user_info = if let Some(token) = token {
-        match crate::auth::verify_jwt(&token) {
+        match app_state.jwt_keys.verify_jwt(&token) {
             Ok(claims) => Some(claims),
             Err(e) => {
                 warn!("Invalid JWT token: {}", e);
@@ -118,13 +327,182 @@ async fn ws_handler(
         return (axum::http::StatusCode::UNAUTHORIZED, "Missing Token").into_response();
     };
     
-    let (user_id, username) = {
-        let claims = user_info.unwrap(); // We know it's Some here because of return above
-        (claims.sub, claims.username)
-    };
+    let claims = user_info.unwrap(); // We know it's Some here because of return above
+    if let Err(response) = check_session(&app_state, &claims).await {
+        return response;
+    }
+    let (user_id, username, is_bot, role) = (claims.sub, claims.username, claims.is_bot, claims.role);
 
     // Pass validated user_id and username to handle_socket
-    ws.on_upgrade(move |socket| handle_socket(socket, app_state, user_id, username))
+    let Some(slot) = admit(&app_state) else {
+        return server_full();
+    };
+    let origin = ClientOrigin::new(&app_state, addr, &params, &headers);
+    ws.on_upgrade(move |socket| async move {
+        handle_socket(socket, app_state, user_id, username, is_bot, role, origin).await;
+        // The place frees once the socket has closed
+        drop(slot);
+    })
+}
+
+/// Reject tokens issued before the account's sessions were ended by a password reset,
+/// and cache the account's profile for lobby and game views
+async fn check_session(app_state: &AppState, claims: &crate::auth::Claims) -> Result<(), axum::response::Response> {
+    use sea_orm::EntityTrait;
+
+    let account = match uuid::Uuid::parse_str(&claims.sub) {
+        Ok(user_id) => crate::entities::user::Entity::find_by_id(user_id).one(&app_state.db).await,
+        Err(_) => Ok(None),
+    };
+    match account {
+        Ok(Some(account)) if crate::password_reset::session_is_current(&account, claims) => {
+            app_state.connection_manager.user_cache()
+                .insert(claims.sub.clone(), crate::user_cache::PlayerProfile::from(&account))
+                .await;
+            Ok(())
+        }
+        Ok(_) => {
+            warn!("Rejected ended session for user {}", claims.sub);
+            Err((axum::http::StatusCode::UNAUTHORIZED, "Session expired").into_response())
+        }
+        Err(e) => {
+            error!("Failed to check session for user {}: {}", claims.sub, e);
+            Err((axum::http::StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response())
+        }
+    }
+}
+
+/// WebSocket endpoint for the bot developer program; only bot account tokens are accepted
+async fn bot_ws_handler(
+    ws: WebSocketUpgrade,
+    ConnectInfo(addr): ConnectInfo<std::net::SocketAddr>,
+    State(app_state): State<Arc<AppState>>,
+    Query(params): Query<HashMap<String, String>>,
+    headers: axum::http::HeaderMap,
+) -> impl IntoResponse {
+    let Some(token) = params.get("token") else {
+        warn!("No token provided for bot WebSocket connection");
+        return (axum::http::StatusCode::UNAUTHORIZED, "Missing Token").into_response();
+    };
+
+    let claims = match app_state.jwt_keys.verify_jwt(token) {
+        Ok(claims) => claims,
+        Err(e) => {
+            warn!("Invalid bot JWT token: {}", e);
+            return (axum::http::StatusCode::UNAUTHORIZED, "Invalid Token").into_response();
+        }
+    };
+
+    if let Err(response) = check_session(&app_state, &claims).await {
+        return response;
+    }
+
+    if !claims.is_bot {
+        warn!("Non-bot account {} attempted to use the bot endpoint", claims.sub);
+        return (axum::http::StatusCode::FORBIDDEN, "Bot account required").into_response();
+    }
+
+    if let Err(rejection) = app_state.message_router.features().require(crate::features::Feature::Bots) {
+        debug!("Rejected bot connection for {} while bots are disabled", claims.sub);
+        return rejection.into_response();
+    }
+
+    let Some(slot) = admit(&app_state) else {
+        return server_full();
+    };
+    let origin = ClientOrigin::new(&app_state, addr, &params, &headers);
+    ws.on_upgrade(move |socket| async move {
+        handle_socket(socket, app_state, claims.sub, claims.username, true, claims.role, origin).await;
+        drop(slot);
+    })
+}
+
+/// Seconds a client refused for a full server is told to wait before retrying
+const SERVER_FULL_RETRY_SECS: u64 = 30;
+
+fn admit(app_state: &AppState) -> Option<crate::connection::ConnectionSlot> {
+    let slot = app_state.connection_manager.try_admit();
+    if slot.is_none() {
+        warn!("Refused a WebSocket connection, max_connections reached");
+    }
+    slot
+}
+
+/// Refused before the upgrade, so browsers see a failed handshake rather than a closed socket
+fn server_full() -> axum::response::Response {
+    (
+        axum::http::StatusCode::SERVICE_UNAVAILABLE,
+        [(axum::http::header::RETRY_AFTER, SERVER_FULL_RETRY_SECS.to_string())],
+        "Server is full, try again later",
+    )
+        .into_response()
+}
+
+/// Where a connection comes from
+struct ClientOrigin {
+    ip: std::net::IpAddr,
+    /// The socket's own address; a trusted proxy's when `ip` was forwarded
+    peer: std::net::IpAddr,
+    region: Option<crate::region::Region>,
+    user_agent: Option<String>,
+}
+
+impl ClientOrigin {
+    /// The client may name its region with `?region=`; otherwise a proxy header or the
+    /// node's own region is used
+    fn new(app_state: &AppState, addr: std::net::SocketAddr, params: &HashMap<String, String>, headers: &axum::http::HeaderMap) -> Self {
+        let header = headers.get(crate::region::REGION_HEADER).and_then(|v| v.to_str().ok());
+        Self {
+            ip: app_state.trusted_proxies.client_ip(addr.ip(), headers),
+            peer: addr.ip(),
+            region: crate::region::resolve(params.get("region").map(String::as_str), header, app_state.region.as_ref()),
+            user_agent: headers.get(axum::http::header::USER_AGENT).and_then(|v| v.to_str().ok()).map(str::to_string),
+        }
+    }
+}
+
+/// Fixed one-second message budget applied to bot connections
+struct MessageBudget {
+    limit: u32,
+    window_start: std::time::Instant,
+    count: u32,
+}
+
+impl MessageBudget {
+    fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            window_start: std::time::Instant::now(),
+            count: 0,
+        }
+    }
+
+    /// Consume one message from the budget, returning false if the limit is exceeded
+    fn try_consume(&mut self) -> bool {
+        if self.window_start.elapsed() >= std::time::Duration::from_secs(1) {
+            self.window_start = std::time::Instant::now();
+            self.count = 0;
+        }
+        self.count += 1;
+        self.count <= self.limit
+    }
+}
+
+/// The user's saved suit rendering, falling back to the default when unknown
+async fn load_suit_encoding(db: &sea_orm::DatabaseConnection, player_id: &str) -> crate::accessibility::SuitEncoding {
+    use sea_orm::EntityTrait;
+
+    let Ok(user_id) = uuid::Uuid::parse_str(player_id) else {
+        return Default::default();
+    };
+    match crate::entities::user::Entity::find_by_id(user_id).one(db).await {
+        Ok(Some(user)) => crate::accessibility::SuitEncoding::parse(&user.suit_encoding).unwrap_or_default(),
+        Ok(None) => Default::default(),
+        Err(e) => {
+            warn!("Failed to load suit encoding for {}: {}", player_id, e);
+            Default::default()
+        }
+    }
 }
 
 async fn handle_socket(
@@ -132,7 +510,11 @@ async fn handle_socket(
     app_state: Arc<AppState>,
     authenticated_user_id: String,
     authenticated_username: String,
+    is_bot: bool,
+    role: crate::auth::Role,
+    origin: ClientOrigin,
 ) {
+    let client_ip = origin.ip;
     let connection_manager = Arc::clone(&app_state.connection_manager);
     let message_router = Arc::clone(&app_state.message_router);
     info!("New Authenticated WebSocket connection: {} ({})", authenticated_user_id, authenticated_username);
@@ -141,7 +523,7 @@ async fn handle_socket(
     let (mut ws_sender, mut ws_receiver) = socket.split();
     
     // Create a channel for sending messages to this WebSocket
-    let (tx, mut rx) = mpsc::unbounded_channel::<Message>();
+    let (tx, mut rx) = outbound::channel();
     
     // FOR AUTH: We trust the JWT user_id.
     // Check if this user is already connected (reconnection) or new.
@@ -172,7 +554,7 @@ async fn handle_socket(
     let player_id = authenticated_user_id.clone();
     
     // We try to reconnect first
-    let is_reconnection = if let Some(other_players) = connection_manager.reconnect_player(player_id.clone(), tx.clone()).await {
+    let is_reconnection = if connection_manager.reconnect_player(player_id.clone(), tx.clone()).await.is_some() {
         info!("Player {} (User) reconnected", player_id);
         
         // Send Connected message
@@ -184,13 +566,11 @@ async fn handle_socket(
             }
         }
         
-        // Broadcast
-         if !other_players.is_empty() {
-            connection_manager.broadcast_to_players(
-                &other_players,
-                ServerMessage::PlayerReconnected { player_id: player_id.clone() }
-            ).await;
-        }
+        // Let the rest of their lobby or game know
+        connection_manager.broadcast_to_room_mates(
+            &player_id,
+            ServerMessage::PlayerReconnected { player_id: player_id.clone() }
+        ).await;
         true
     } else {
         info!("User {} connecting as new session", player_id);
@@ -211,16 +591,38 @@ async fn handle_socket(
         false
     };
 
+    connection_manager.set_bot(&player_id, is_bot).await;
+    connection_manager.set_role(&player_id, role).await;
+    connection_manager.set_region(&player_id, origin.region).await;
+    connection_manager.set_address(&player_id, client_ip).await;
+    connection_manager.set_peer_address(&player_id, origin.peer).await;
+    connection_manager.set_user_agent(&player_id, origin.user_agent.as_deref()).await;
+
+    // Tell the client how to render suits; a resumed session keeps what it negotiated
+    let suit_encoding = match connection_manager.suit_encoding(&player_id).await {
+        Some(encoding) => encoding,
+        None => {
+            let encoding = load_suit_encoding(&app_state.db, &player_id).await;
+            connection_manager.set_suit_encoding(&player_id, encoding).await;
+            encoding
+        }
+    };
+    connection_manager.send_to_player(player_id.clone(), crate::accessibility::display_profile(suit_encoding)).await;
+
     if is_reconnection {
         info!("Player {} reconnected and restored", player_id);
     } else {
         info!("Player {} connected and registered", player_id);
+        // A new session already in a room means it was restored after a restart
+        message_router.restore_session(&player_id).await;
     }
     
     // Spawn a task to forward messages from the channel to the WebSocket
+    // A Close frame queued by close_connection ends the task once it is sent
     let mut send_task = tokio::spawn(async move {
         while let Some(msg) = rx.recv().await {
-            if ws_sender.send(msg).await.is_err() {
+            let closing = matches!(msg, Message::Close(_));
+            if ws_sender.send(msg).await.is_err() || closing {
                 break;
             }
         }
@@ -231,11 +633,45 @@ async fn handle_socket(
     let connection_manager_clone = connection_manager.clone();
     let message_router_clone = message_router.clone();
     let player_id_clone = player_id.clone();
+    let mut bot_budget = is_bot.then(|| MessageBudget::new(app_state.bot_messages_per_sec));
     
     let mut recv_task = tokio::spawn(async move {
+        let mut kicked = false;
         while let Some(result) = ws_receiver.next().await {
             match result {
                 Ok(msg) => {
+                    // Bots are rate-limited; excess messages are dropped with an error
+                    if let Some(budget) = bot_budget.as_mut() {
+                        if !budget.try_consume() {
+                            connection_manager_clone.send_to_player(
+                                player_id_clone.clone(),
+                                ServerMessage::Error { message: "Rate limit exceeded".to_string(), request_id: None }
+                            ).await;
+                            continue;
+                        }
+                    } else {
+                        // Players share token buckets per account and per address; flooding gets them disconnected
+                        match connection_manager_clone.check_rate(&player_id_clone, Some(client_ip)) {
+                            RateDecision::Allowed => {}
+                            RateDecision::Throttled => {
+                                connection_manager_clone.send_to_player(
+                                    player_id_clone.clone(),
+                                    ServerMessage::Error { message: "Rate limit exceeded".to_string(), request_id: None }
+                                ).await;
+                                continue;
+                            }
+                            RateDecision::Disconnect => {
+                                warn!("Disconnecting player {} ({}) for flooding", player_id_clone, client_ip);
+                                connection_manager_clone.close_connection(
+                                    &player_id_clone,
+                                    "Too many messages; disconnected",
+                                ).await;
+                                kicked = true;
+                                break;
+                            }
+                        }
+                    }
+
                     // Wrap message handling to catch any errors
                     if let Err(e) = handle_message(player_id_clone.clone(), msg, &connection_manager_clone, &message_router_clone).await {
                         warn!("Error handling message from player {}: {}", player_id_clone, e);
@@ -248,7 +684,7 @@ async fn handle_socket(
                 }
             }
         }
-        player_id_clone
+        (player_id_clone, kicked)
     });
     
     // Wait for either task to complete
@@ -259,18 +695,19 @@ async fn handle_socket(
         }
         result = &mut recv_task => {
             debug!("Receive task completed for player {}", player_id);
-            send_task.abort();
-            if let Ok(player_id) = result {
-                // Mark player as inactive and get list of other players to notify
-                let other_players = connection_manager.mark_inactive(player_id.clone()).await;
-                
-                // Notify other players about the disconnection
-                if !other_players.is_empty() {
-                    connection_manager.broadcast_to_players(
-                        &other_players,
-                        ServerMessage::PlayerLeft { player_id }
-                    ).await;
-                }
+            // Give the Error and Close frame queued for a kicked client a moment to go out
+            let kicked = matches!(result, Ok((_, true)));
+            if !kicked || tokio::time::timeout(std::time::Duration::from_secs(1), &mut send_task).await.is_err() {
+                send_task.abort();
+            }
+            if let Ok((player_id, _)) = result {
+                connection_manager.mark_inactive(player_id.clone()).await;
+
+                // Notify the players who share a lobby or game with them
+                connection_manager.broadcast_to_room_mates(
+                    &player_id,
+                    ServerMessage::PlayerLeft { player_id: player_id.clone() }
+                ).await;
             }
         }
     }
@@ -294,7 +731,7 @@ async fn handle_message(
             debug!("Received text message from player {}: {}", player_id, text);
             
             // Deserialize the message
-            match serde_json::from_str::<ClientMessage>(&text) {
+            match serde_json::from_str::<ClientEnvelope>(&text) {
                 Ok(client_msg) => {
                     debug!("Parsed message from player {}: {:?}", player_id, client_msg);
                     
@@ -311,7 +748,7 @@ async fn handle_message(
                     warn!("Failed to parse message from player {}: {}", player_id, error_msg);
                     connection_manager.send_to_player(
                         player_id,
-                        ServerMessage::Error { message: error_msg.clone() }
+                        ServerMessage::Error { message: error_msg.clone(), request_id: unparsed_request_id(text.as_bytes()) }
                     ).await;
                     Err(error_msg)
                 }
@@ -321,9 +758,10 @@ async fn handle_message(
             debug!("Received binary message from player {} ({} bytes)", player_id, data.len());
             
             // Try to deserialize from binary JSON
-            match serde_json::from_slice::<ClientMessage>(&data) {
+            match serde_json::from_slice::<ClientEnvelope>(&data) {
                 Ok(client_msg) => {
                     debug!("Parsed binary message from player {}: {:?}", player_id, client_msg);
+                    message_router.note_deprecated(&player_id, crate::deprecation::Deprecation::BinaryFrames).await;
                     
                     // Route message to appropriate handler
                     if let Err(e) = message_router.route_message(player_id.clone(), client_msg).await {
@@ -338,7 +776,7 @@ async fn handle_message(
                     warn!("Failed to parse binary message from player {}: {}", player_id, error_msg);
                     connection_manager.send_to_player(
                         player_id,
-                        ServerMessage::Error { message: error_msg.clone() }
+                        ServerMessage::Error { message: error_msg.clone(), request_id: unparsed_request_id(&data) }
                     ).await;
                     Err(error_msg)
                 }
@@ -348,17 +786,38 @@ async fn handle_message(
             info!("Received close message from player {}", player_id);
             Err("Connection closed by client".to_string())
         }
-        Message::Ping(_) | Message::Pong(_) => {
-            // WebSocket ping/pong frames are handled automatically
+        Message::Pong(_) => {
+            connection_manager.record_pong(&player_id).await;
+            Ok(())
+        }
+        Message::Ping(_) => {
+            // WebSocket pings are answered automatically
             Ok(())
         }
     }
 }
 
+/// The `request_id` of a frame that is not a valid client message, if it is a JSON object with one
+fn unparsed_request_id(frame: &[u8]) -> Option<String> {
+    #[derive(serde::Deserialize)]
+    struct RequestId {
+        request_id: Option<String>,
+    }
+    serde_json::from_slice::<RequestId>(frame).ok()?.request_id
+}
+
 async fn health_check() -> impl IntoResponse {
     "OK"
 }
 
+#[utoipa::path(
+    get,
+    path = "/stats",
+    tag = "server",
+    responses(
+        (status = 200, description = "Connection, game and lobby list counters", body = ServerStats),
+    ),
+)]
 async fn stats_handler(State(app_state): State<Arc<AppState>>) -> impl IntoResponse {
     let connection_stats = app_state.connection_manager.get_stats().await;
     let game_stats = app_state.game_manager.get_stats().await;
@@ -366,15 +825,22 @@ async fn stats_handler(State(app_state): State<Arc<AppState>>) -> impl IntoRespo
     let stats = ServerStats {
         connections: connection_stats,
         games: game_stats,
+        lobby_list: app_state.message_router.lobby_feed_stats(),
     };
     
     Json(stats)
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
-struct ServerStats {
+/// Default lobby settings and the allowed ranges, so clients can prefill and validate the create form
+async fn game_settings_handler(State(app_state): State<Arc<AppState>>) -> impl IntoResponse {
+    Json(app_state.message_router.settings_policy().clone())
+}
+
+#[derive(Debug, Clone, serde::Serialize, utoipa::ToSchema)]
+pub(crate) struct ServerStats {
     connections: crate::connection::ConnectionStats,
     games: crate::game::GameStats,
+    lobby_list: crate::lobby_feed::LobbyFeedStats,
 }
 
 async fn shutdown_signal() {
@@ -0,0 +1,135 @@
+use serde::Serialize;
+use crate::error::LobbyError;
+use crate::protocol::GameSettings;
+
+/// Operator-configured defaults and bounds for game settings, enforced at lobby creation
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsPolicy {
+    /// Settings used for matchmaking games and offered to clients as a starting point
+    pub defaults: GameSettings,
+    pub min_players: usize,
+    pub max_players: usize,
+    pub min_turn_timeout_secs: u64,
+    pub max_turn_timeout_secs: u64,
+}
+
+impl Default for SettingsPolicy {
+    fn default() -> Self {
+        Self {
+            defaults: GameSettings::default(),
+            min_players: 2,
+            max_players: 6,
+            min_turn_timeout_secs: 5,
+            max_turn_timeout_secs: 300,
+        }
+    }
+}
+
+impl SettingsPolicy {
+    /// Build a policy, pulling the defaults inside the allowed ranges
+    pub fn new(
+        mut defaults: GameSettings,
+        min_players: usize,
+        max_players: usize,
+        min_turn_timeout_secs: u64,
+        max_turn_timeout_secs: u64,
+    ) -> Self {
+        let max_players = max_players.max(min_players);
+        let max_turn_timeout_secs = max_turn_timeout_secs.max(min_turn_timeout_secs);
+        defaults.player_count = defaults.player_count.clamp(min_players, max_players);
+        defaults.turn_timeout_secs = defaults.turn_timeout_secs.clamp(min_turn_timeout_secs, max_turn_timeout_secs);
+
+        Self {
+            defaults,
+            min_players,
+            max_players,
+            min_turn_timeout_secs,
+            max_turn_timeout_secs,
+        }
+    }
+
+    pub fn allows_player_count(&self, player_count: usize) -> bool {
+        (self.min_players..=self.max_players).contains(&player_count)
+    }
+
+    /// Reject settings outside the configured ranges
+    pub fn validate(&self, settings: &GameSettings) -> Result<(), LobbyError> {
+        if !self.allows_player_count(settings.player_count) {
+            return Err(LobbyError::SettingsOutOfRange(format!(
+                "player_count must be between {} and {}",
+                self.min_players, self.max_players
+            )));
+        }
+        if !(self.min_turn_timeout_secs..=self.max_turn_timeout_secs).contains(&settings.turn_timeout_secs) {
+            return Err(LobbyError::SettingsOutOfRange(format!(
+                "turn_timeout_secs must be between {} and {}",
+                self.min_turn_timeout_secs, self.max_turn_timeout_secs
+            )));
+        }
+        for (name, secs) in settings.rules.timer.limits() {
+            if !(self.min_turn_timeout_secs..=self.max_turn_timeout_secs).contains(&secs) {
+                return Err(LobbyError::SettingsOutOfRange(format!(
+                    "rules.timer.{} must be between {} and {}",
+                    name, self.min_turn_timeout_secs, self.max_turn_timeout_secs
+                )));
+            }
+        }
+        if let Some(secs) = settings.intermission_secs {
+            if !(self.min_turn_timeout_secs..=self.max_turn_timeout_secs).contains(&secs) {
+                return Err(LobbyError::SettingsOutOfRange(format!(
+                    "intermission_secs must be between {} and {}",
+                    self.min_turn_timeout_secs, self.max_turn_timeout_secs
+                )));
+            }
+        }
+        settings.rules.validate(settings.player_count).map_err(LobbyError::SettingsOutOfRange)
+    }
+
+    /// The default settings for a table of the given size
+    pub fn defaults_for(&self, player_count: usize) -> GameSettings {
+        GameSettings { player_count, ..self.defaults.clone() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_enforces_ranges() {
+        let policy = SettingsPolicy::new(GameSettings::default(), 3, 4, 15, 120);
+
+        assert!(policy.validate(&GameSettings::default()).is_ok());
+        assert!(policy.validate(&GameSettings { player_count: 8, ..Default::default() }).is_err());
+        assert!(policy.validate(&GameSettings { turn_timeout_secs: 5, ..Default::default() }).is_err());
+        assert!(policy.validate(&GameSettings { turn_timeout_secs: 120, ..Default::default() }).is_ok());
+        assert!(policy.validate(&GameSettings { intermission_secs: Some(0), ..Default::default() }).is_err());
+        assert!(policy.validate(&GameSettings { intermission_secs: Some(30), ..Default::default() }).is_ok());
+    }
+
+    #[test]
+    fn test_validate_checks_rules() {
+        use crate::engine::rules::{RuleSet, TimerPolicy};
+
+        let policy = SettingsPolicy::new(GameSettings::default(), 2, 6, 15, 120);
+        let with_rules = |rules: RuleSet| GameSettings { rules, ..Default::default() };
+
+        let slow_bids = RuleSet { timer: TimerPolicy { bidding_secs: Some(300), playing_secs: None, ..Default::default() }, ..Default::default() };
+        assert!(policy.validate(&with_rules(slow_bids)).is_err());
+        let quick_play = RuleSet { timer: TimerPolicy { bidding_secs: None, playing_secs: Some(15), ..Default::default() }, ..Default::default() };
+        assert!(policy.validate(&with_rules(quick_play)).is_ok());
+        let slow_turns = RuleSet { timer: TimerPolicy { turn_secs: Some(300), ..Default::default() }, ..Default::default() };
+        assert!(policy.validate(&with_rules(slow_turns)).is_err());
+        assert!(policy.validate(&with_rules(RuleSet { max_cards: Some(20), ..Default::default() })).is_err());
+    }
+
+    #[test]
+    fn test_defaults_are_pulled_into_range() {
+        let defaults = GameSettings { player_count: 6, turn_timeout_secs: 5, ..Default::default() };
+        let policy = SettingsPolicy::new(defaults, 2, 4, 10, 60);
+
+        assert_eq!(policy.defaults.player_count, 4);
+        assert_eq!(policy.defaults.turn_timeout_secs, 10);
+        assert!(policy.validate(&policy.defaults).is_ok());
+    }
+}
@@ -0,0 +1,186 @@
+use std::fmt;
+use std::str::FromStr;
+use rand::Rng;
+#[cfg(feature = "server")]
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+#[cfg(feature = "server")]
+use crate::entities::{game, lobby};
+
+pub const CODE_LEN: usize = 6;
+
+/// Crockford base32: no I, L, O or U, so codes survive being read aloud
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Six-character code that names a live lobby or game alongside its UUID
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+#[cfg_attr(feature = "typescript", ts(type = "string"))]
+pub struct ShortCode(String);
+
+impl ShortCode {
+    pub fn generate() -> Self {
+        let mut rng = rand::thread_rng();
+        Self((0..CODE_LEN).map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())] as char).collect())
+    }
+
+    /// A fresh code for which `taken` is false
+    pub fn generate_unique(taken: impl Fn(&ShortCode) -> bool) -> Self {
+        loop {
+            let code = Self::generate();
+            if !taken(&code) {
+                return code;
+            }
+        }
+    }
+
+    /// Accept codes as people type them: any case, with dashes or spaces, O for 0 and I or L for 1
+    pub fn parse(input: &str) -> Option<Self> {
+        let code: String = input
+            .chars()
+            .filter(|c| !matches!(c, '-' | ' '))
+            .map(|c| match c.to_ascii_uppercase() {
+                'O' => '0',
+                'I' | 'L' => '1',
+                c => c,
+            })
+            .collect();
+        (code.len() == CODE_LEN && code.bytes().all(|b| ALPHABET.contains(&b))).then_some(Self(code))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for ShortCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TryFrom<String> for ShortCode {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value).ok_or_else(|| format!("Invalid code '{}'", value))
+    }
+}
+
+impl From<ShortCode> for String {
+    fn from(code: ShortCode) -> Self {
+        code.0
+    }
+}
+
+/// A lobby or game named by UUID or by short code; serialized as a plain string
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+#[cfg_attr(feature = "typescript", ts(type = "string"))]
+pub enum IdOrCode {
+    Id(Uuid),
+    Code(ShortCode),
+}
+
+impl FromStr for IdOrCode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(id) = Uuid::parse_str(s) {
+            return Ok(Self::Id(id));
+        }
+        ShortCode::parse(s)
+            .map(Self::Code)
+            .ok_or_else(|| format!("'{}' is neither an id nor a code", s))
+    }
+}
+
+impl TryFrom<String> for IdOrCode {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<IdOrCode> for String {
+    fn from(value: IdOrCode) -> Self {
+        match value {
+            IdOrCode::Id(id) => id.to_string(),
+            IdOrCode::Code(code) => code.0,
+        }
+    }
+}
+
+impl From<Uuid> for IdOrCode {
+    fn from(id: Uuid) -> Self {
+        Self::Id(id)
+    }
+}
+
+/// The stored game a reference points to; a code means the most recent game that used it
+#[cfg(feature = "server")]
+pub async fn find_game_id(db: &DatabaseConnection, reference: &IdOrCode) -> Result<Option<Uuid>, DbErr> {
+    match reference {
+        IdOrCode::Id(id) => Ok(Some(*id)),
+        IdOrCode::Code(code) => Ok(game::Entity::find()
+            .filter(game::Column::Code.eq(code.as_str()))
+            .order_by_desc(game::Column::CreatedAt)
+            .one(db)
+            .await?
+            .map(|g| g.id)),
+    }
+}
+
+/// The stored lobby a reference points to; a code means the most recent lobby that used it
+#[cfg(feature = "server")]
+pub async fn find_lobby_id(db: &DatabaseConnection, reference: &IdOrCode) -> Result<Option<Uuid>, DbErr> {
+    match reference {
+        IdOrCode::Id(id) => Ok(Some(*id)),
+        IdOrCode::Code(code) => Ok(lobby::Entity::find()
+            .filter(lobby::Column::Code.eq(code.as_str()))
+            .order_by_desc(lobby::Column::CreatedAt)
+            .one(db)
+            .await?
+            .map(|l| l.id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_codes_parse() {
+        for _ in 0..100 {
+            let code = ShortCode::generate();
+            assert_eq!(code.as_str().len(), CODE_LEN);
+            assert_eq!(ShortCode::parse(code.as_str()), Some(code));
+        }
+    }
+
+    #[test]
+    fn test_parse_is_forgiving() {
+        let expected = ShortCode::parse("K7Q1P0").unwrap();
+        assert_eq!(ShortCode::parse("k7q-lpo"), Some(expected.clone()));
+        assert_eq!(ShortCode::parse("K7Q IPO"), Some(expected));
+        assert_eq!(ShortCode::parse("K7Q1P"), None);
+        assert_eq!(ShortCode::parse("K7Q1PU"), None);
+    }
+
+    #[test]
+    fn test_id_or_code_from_json() {
+        let id = Uuid::new_v4();
+        let by_id: IdOrCode = serde_json::from_value(serde_json::json!(id.to_string())).unwrap();
+        assert_eq!(by_id, IdOrCode::Id(id));
+
+        let by_code: IdOrCode = serde_json::from_value(serde_json::json!("abc-def")).unwrap();
+        assert_eq!(by_code, IdOrCode::Code(ShortCode::parse("ABCDEF").unwrap()));
+        assert_eq!(serde_json::to_value(&by_code).unwrap(), serde_json::json!("ABCDEF"));
+
+        assert!(serde_json::from_value::<IdOrCode>(serde_json::json!("nope")).is_err());
+    }
+}
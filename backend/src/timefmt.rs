@@ -0,0 +1,114 @@
+use chrono::{DateTime, Offset, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+pub const DEFAULT_TIME_ZONE: &str = "UTC";
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// A UTC timestamp together with how it should be shown to a particular user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, utoipa::ToSchema)]
+pub struct LocalizedTimestamp {
+    pub utc: DateTime<Utc>,
+    /// RFC 3339 time in the user's zone
+    pub local: String,
+    pub time_zone: String,
+    pub utc_offset_secs: i32,
+    /// BCP 47 tag clients should use to format `local`
+    pub locale: String,
+}
+
+/// A user's time zone and locale preferences
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimePrefs {
+    pub time_zone: Tz,
+    pub locale: String,
+}
+
+impl Default for TimePrefs {
+    fn default() -> Self {
+        Self {
+            time_zone: Tz::UTC,
+            locale: DEFAULT_LOCALE.to_string(),
+        }
+    }
+}
+
+impl TimePrefs {
+    /// Build preferences from stored settings, falling back to defaults for invalid values
+    pub fn new(time_zone: &str, locale: &str) -> Self {
+        Self {
+            time_zone: parse_time_zone(time_zone).unwrap_or(Tz::UTC),
+            locale: if is_valid_locale(locale) { locale.to_string() } else { DEFAULT_LOCALE.to_string() },
+        }
+    }
+
+    pub fn from_user(user: &crate::entities::user::Model) -> Self {
+        Self::new(&user.time_zone, &user.locale)
+    }
+
+    /// Attach the user's presentation metadata to a UTC timestamp
+    pub fn localize(&self, utc: DateTime<Utc>) -> LocalizedTimestamp {
+        let local = utc.with_timezone(&self.time_zone);
+        LocalizedTimestamp {
+            utc,
+            local: local.to_rfc3339(),
+            time_zone: self.time_zone.name().to_string(),
+            utc_offset_secs: local.offset().fix().local_minus_utc(),
+            locale: self.locale.clone(),
+        }
+    }
+}
+
+/// Parse an IANA time zone name such as "Europe/Berlin"
+pub fn parse_time_zone(name: &str) -> Option<Tz> {
+    name.parse().ok()
+}
+
+/// Loose BCP 47 shape check: alphanumeric subtags separated by hyphens
+pub fn is_valid_locale(locale: &str) -> bool {
+    !locale.is_empty()
+        && locale.len() <= 35
+        && locale.split('-').all(|tag| !tag.is_empty() && tag.len() <= 8 && tag.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_localize_applies_zone_offset() {
+        let prefs = TimePrefs::new("Europe/Berlin", "de-DE");
+        let utc = Utc.with_ymd_and_hms(2024, 7, 1, 12, 0, 0).unwrap();
+
+        let ts = prefs.localize(utc);
+        assert_eq!(ts.utc, utc);
+        assert_eq!(ts.local, "2024-07-01T14:00:00+02:00");
+        assert_eq!(ts.time_zone, "Europe/Berlin");
+        assert_eq!(ts.utc_offset_secs, 7200);
+        assert_eq!(ts.locale, "de-DE");
+    }
+
+    #[test]
+    fn test_localize_handles_daylight_saving() {
+        let prefs = TimePrefs::new("Europe/Berlin", "de-DE");
+        let winter = Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+
+        assert_eq!(prefs.localize(winter).utc_offset_secs, 3600);
+    }
+
+    #[test]
+    fn test_invalid_settings_fall_back_to_defaults() {
+        let prefs = TimePrefs::new("Mars/Olympus", "not a locale");
+        assert_eq!(prefs, TimePrefs::default());
+    }
+
+    #[test]
+    fn test_locale_validation() {
+        assert!(is_valid_locale("en"));
+        assert!(is_valid_locale("zh-Hant-TW"));
+        assert!(!is_valid_locale(""));
+        assert!(!is_valid_locale("en--US"));
+        assert!(!is_valid_locale("en_US"));
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::time::Instant;
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+use tokio_util::time::delay_queue::{DelayQueue, Key};
+use crate::connection::PlayerId;
+use crate::game::GameId;
+
+/// What a game is waiting for when its deadline passes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Timer {
+    /// The player's turn, armed at this state version; any action since makes it stale
+    Turn { player_id: PlayerId, version: u64 },
+    /// The pause after `round_number`
+    Intermission { round_number: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimerKind {
+    Turn,
+    Intermission,
+}
+
+impl Timer {
+    pub fn kind(&self) -> TimerKind {
+        match self {
+            Timer::Turn { .. } => TimerKind::Turn,
+            Timer::Intermission { .. } => TimerKind::Intermission,
+        }
+    }
+}
+
+enum Command {
+    Schedule(GameId, Timer, Instant),
+    Cancel(GameId, TimerKind),
+    CancelGame(GameId),
+}
+
+/// Deadlines of every game, kept in one queue by a single task.
+///
+/// A game has at most one timer of each kind; scheduling another replaces it. Expired timers
+/// come out of the receiver returned by `start`, in deadline order.
+#[derive(Clone)]
+pub struct TimerWheel {
+    commands: mpsc::UnboundedSender<Command>,
+}
+
+impl TimerWheel {
+    pub fn start() -> (Self, mpsc::UnboundedReceiver<(GameId, Timer)>) {
+        let (commands, mut pending) = mpsc::unbounded_channel();
+        let (fired, expired) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut queue: DelayQueue<(GameId, Timer)> = DelayQueue::new();
+            let mut keys: HashMap<(GameId, TimerKind), Key> = HashMap::new();
+            loop {
+                // Commands first, so a timer cancelled before its deadline never fires
+                tokio::select! {
+                    biased;
+                    command = pending.recv() => match command {
+                        Some(Command::Schedule(game_id, timer, deadline)) => {
+                            let slot = (game_id, timer.kind());
+                            if let Some(key) = keys.remove(&slot) {
+                                queue.remove(&key);
+                            }
+                            keys.insert(slot, queue.insert_at((game_id, timer), deadline.into()));
+                        }
+                        Some(Command::Cancel(game_id, kind)) => {
+                            if let Some(key) = keys.remove(&(game_id, kind)) {
+                                queue.remove(&key);
+                            }
+                        }
+                        Some(Command::CancelGame(game_id)) => {
+                            for kind in [TimerKind::Turn, TimerKind::Intermission] {
+                                if let Some(key) = keys.remove(&(game_id, kind)) {
+                                    queue.remove(&key);
+                                }
+                            }
+                        }
+                        None => break,
+                    },
+                    Some(expired) = queue.next(), if !queue.is_empty() => {
+                        let (game_id, timer) = expired.into_inner();
+                        keys.remove(&(game_id, timer.kind()));
+                        if fired.send((game_id, timer)).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+        (Self { commands }, expired)
+    }
+
+    /// Fire `timer` for the game at `deadline`, replacing its pending timer of the same kind
+    pub fn schedule(&self, game_id: GameId, timer: Timer, deadline: Instant) {
+        let _ = self.commands.send(Command::Schedule(game_id, timer, deadline));
+    }
+
+    pub fn cancel(&self, game_id: GameId, kind: TimerKind) {
+        let _ = self.commands.send(Command::Cancel(game_id, kind));
+    }
+
+    /// Drop every timer of a game that has ended
+    pub fn cancel_game(&self, game_id: GameId) {
+        let _ = self.commands.send(Command::CancelGame(game_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn test_timers_fire_in_deadline_order_and_replace_each_other() {
+        let (wheel, mut expired) = TimerWheel::start();
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let now = Instant::now();
+        let turn = |version| Timer::Turn { player_id: "p1".to_string(), version };
+
+        wheel.schedule(first, turn(1), now + Duration::from_millis(20));
+        wheel.schedule(second, Timer::Intermission { round_number: 2 }, now + Duration::from_millis(10));
+        // Rescheduling replaces the first game's turn timer; its intermission is separate
+        wheel.schedule(first, turn(2), now + Duration::from_millis(40));
+        wheel.schedule(first, Timer::Intermission { round_number: 1 }, now + Duration::from_millis(30));
+        wheel.cancel(first, TimerKind::Intermission);
+
+        assert_eq!(expired.recv().await, Some((second, Timer::Intermission { round_number: 2 })));
+        assert_eq!(expired.recv().await, Some((first, turn(2))));
+
+        wheel.schedule(first, turn(3), Instant::now() + Duration::from_millis(10));
+        wheel.cancel_game(first);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(expired.try_recv().is_err());
+    }
+}
@@ -0,0 +1,113 @@
+use std::future::Future;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+use axum::extract::ConnectInfo;
+use axum::http::Request;
+use axum::Router;
+use hyper::body::Incoming;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use hyper_util::service::TowerToHyperService;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio_native_tls::TlsAcceptor;
+use tower::ServiceExt;
+use tracing::{debug, info, warn};
+use crate::error::ServerError;
+
+/// Longest a client may take to complete the TLS handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Certificate and key for serving HTTPS and wss:// directly, without a reverse proxy
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    /// PEM certificate chain, leaf first
+    pub cert_path: PathBuf,
+    /// PEM PKCS#8 private key
+    pub key_path: PathBuf,
+}
+
+impl TlsConfig {
+    pub fn acceptor(&self) -> Result<TlsAcceptor, ServerError> {
+        let read = |path: &PathBuf| std::fs::read(path)
+            .map_err(|e| ServerError::Config(format!("Cannot read {}: {}", path.display(), e)));
+        let cert = read(&self.cert_path)?;
+        let key = read(&self.key_path)?;
+        let identity = tokio_native_tls::native_tls::Identity::from_pkcs8(&cert, &key)
+            .map_err(|e| ServerError::Config(format!("Invalid TLS certificate or key: {}", e)))?;
+        let acceptor = tokio_native_tls::native_tls::TlsAcceptor::new(identity)
+            .map_err(|e| ServerError::Config(format!("Cannot set up TLS: {}", e)))?;
+        Ok(TlsAcceptor::from(acceptor))
+    }
+}
+
+/// Serve `app` over TLS on `listener` until `shutdown` completes, then let open connections
+/// finish their requests
+pub async fn serve_tls(
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+    app: Router,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<(), ServerError> {
+    let (stop, stopped) = watch::channel(());
+    tokio::pin!(shutdown);
+
+    loop {
+        let (stream, addr) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    warn!("Failed to accept connection: {}", e);
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+
+        let acceptor = acceptor.clone();
+        let app = app.clone();
+        let mut stopped = stopped.clone();
+        tokio::spawn(async move {
+            let stream = match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                Ok(Ok(stream)) => stream,
+                Ok(Err(e)) => {
+                    debug!("TLS handshake with {} failed: {}", addr, e);
+                    return;
+                }
+                Err(_) => {
+                    debug!("TLS handshake with {} timed out", addr);
+                    return;
+                }
+            };
+
+            // Handlers read the client address the way they do over plain TCP
+            let service = app.map_request(move |mut request: Request<Incoming>| {
+                request.extensions_mut().insert(ConnectInfo::<SocketAddr>(addr));
+                request
+            });
+            let builder = auto::Builder::new(TokioExecutor::new());
+            let connection = builder.serve_connection_with_upgrades(TokioIo::new(stream), TowerToHyperService::new(service));
+            tokio::pin!(connection);
+
+            tokio::select! {
+                result = connection.as_mut() => {
+                    if let Err(e) = result {
+                        debug!("Connection from {} ended with an error: {}", addr, e);
+                    }
+                }
+                _ = stopped.changed() => {
+                    connection.as_mut().graceful_shutdown();
+                    let _ = connection.await;
+                }
+            }
+        });
+    }
+
+    drop(listener);
+    drop(stopped);
+    let _ = stop.send(());
+    stop.closed().await;
+    info!("Server shutdown complete");
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, DatabaseConnection, DbErr, EntityTrait, QueryOrder, Set};
+use uuid::Uuid;
+use crate::entities::tournament;
+use crate::webhook;
+
+/// Create a tournament with a fresh webhook signing secret
+pub async fn create_tournament(
+    db: &DatabaseConnection,
+    name: &str,
+    webhook_url: Option<String>,
+) -> Result<tournament::Model, DbErr> {
+    tournament::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        name: Set(name.to_string()),
+        webhook_url: Set(webhook_url),
+        webhook_secret: Set(webhook::generate_secret()),
+        created_at: Set(Utc::now()),
+    }
+    .insert(db)
+    .await
+}
+
+/// All tournaments, newest first
+pub async fn list_tournaments(db: &DatabaseConnection) -> Result<Vec<tournament::Model>, DbErr> {
+    tournament::Entity::find()
+        .order_by_desc(tournament::Column::CreatedAt)
+        .all(db)
+        .await
+}
+
+pub async fn tournament_exists(db: &DatabaseConnection, id: Uuid) -> Result<bool, DbErr> {
+    Ok(tournament::Entity::find_by_id(id).one(db).await?.is_some())
+}
+
+/// Webhooks must be plain HTTP(S) URLs
+pub fn is_valid_webhook_url(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
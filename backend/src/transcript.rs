@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder, Set};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::connection::PlayerId;
+use crate::deal_review;
+use crate::engine::card::{Card, Suit};
+use crate::engine::scoring::PlayerRoundResult;
+use crate::engine::tiebreak::GameOutcome;
+use crate::entities::{game, game_action, game_player, game_round, user};
+use crate::game::GameId;
+use crate::protocol::PlayerAction;
+use tracing::warn;
+
+/// One applied action, as stored for transcripts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedAction {
+    /// State version the action produced; orders the actions of a game
+    pub version: u64,
+    pub round_number: usize,
+    pub player_id: PlayerId,
+    pub action: PlayerAction,
+    /// Played for the player when their turn timed out
+    pub auto: bool,
+}
+
+pub async fn record_action(db: &impl ConnectionTrait, game_id: GameId, action: &RecordedAction) -> Result<(), DbErr> {
+    game_action::ActiveModel {
+        id: sea_orm::ActiveValue::NotSet,
+        game_id: Set(game_id),
+        version: Set(action.version as i64),
+        round_number: Set(action.round_number as i32),
+        player_id: Set(action.player_id.clone()),
+        action: Set(serde_json::json!(action.action)),
+        auto: Set(action.auto),
+        played_at: Set(Utc::now()),
+    }
+    .insert(db)
+    .await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptPlayer {
+    pub player_id: PlayerId,
+    /// None once the account is gone
+    pub username: Option<String>,
+    pub final_score: Option<i32>,
+    pub placement: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptAction {
+    pub version: u64,
+    pub player_id: PlayerId,
+    pub action: PlayerAction,
+    pub auto: bool,
+    pub played_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptRound {
+    pub round_number: usize,
+    pub trump_suit: Option<Suit>,
+    /// The viewer's own hand as dealt; other players' hands are left out
+    pub your_hand: Vec<Card>,
+    pub actions: Vec<TranscriptAction>,
+    /// Empty for a round the game ended during
+    pub results: Vec<PlayerRoundResult>,
+}
+
+/// A finished game as one participant saw it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transcript {
+    pub game_id: GameId,
+    pub code: Option<String>,
+    pub variant: String,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub abandoned_at: Option<DateTime<Utc>>,
+    pub outcome: Option<GameOutcome>,
+    pub players: Vec<TranscriptPlayer>,
+    pub rounds: Vec<TranscriptRound>,
+}
+
+/// Assemble `game`'s transcript for `viewer` from the stored deals, actions and round results
+pub async fn build(db: &DatabaseConnection, game: &game::Model, viewer: &PlayerId) -> Result<Transcript, DbErr> {
+    let seats = game_player::Entity::find()
+        .filter(game_player::Column::GameId.eq(game.id))
+        .all(db)
+        .await?;
+    let user_ids: Vec<Uuid> = seats.iter().map(|seat| seat.player_id).collect();
+    let usernames: HashMap<Uuid, String> = user::Entity::find()
+        .filter(user::Column::Id.is_in(user_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|u| (u.id, u.username))
+        .collect();
+    let mut players: Vec<TranscriptPlayer> = seats
+        .iter()
+        .map(|seat| TranscriptPlayer {
+            player_id: seat.player_id.to_string(),
+            username: usernames.get(&seat.player_id).cloned(),
+            final_score: seat.final_score,
+            placement: seat.placement,
+        })
+        .collect();
+    players.sort_by_key(|p| (p.placement.is_none(), p.placement, std::cmp::Reverse(p.final_score)));
+
+    let mut rounds: Vec<TranscriptRound> = deal_review::deals_for_game(db, game.id)
+        .await?
+        .into_iter()
+        .map(|deal| TranscriptRound {
+            round_number: deal.round_number,
+            trump_suit: deal.trump_suit,
+            your_hand: deal.hands.into_iter().find(|hand| hand.player_id == *viewer).map(|hand| hand.cards).unwrap_or_default(),
+            actions: Vec::new(),
+            results: Vec::new(),
+        })
+        .collect();
+
+    let actions = game_action::Entity::find()
+        .filter(game_action::Column::GameId.eq(game.id))
+        .order_by_asc(game_action::Column::Version)
+        .all(db)
+        .await?;
+    for row in actions {
+        let action = match serde_json::from_value(row.action.clone()) {
+            Ok(action) => action,
+            Err(e) => {
+                warn!("Skipping unreadable action {} of game {}: {}", row.id, game.id, e);
+                continue;
+            }
+        };
+        if let Some(round) = round_mut(&mut rounds, row.round_number as usize) {
+            round.actions.push(TranscriptAction {
+                version: row.version as u64,
+                player_id: row.player_id,
+                action,
+                auto: row.auto,
+                played_at: row.played_at,
+            });
+        }
+    }
+
+    let results = game_round::Entity::find()
+        .filter(game_round::Column::GameId.eq(game.id))
+        .all(db)
+        .await?;
+    for row in results {
+        match serde_json::from_value(row.player_results.clone()) {
+            Ok(player_results) => {
+                if let Some(round) = round_mut(&mut rounds, row.round_number as usize) {
+                    round.results = player_results;
+                }
+            }
+            Err(e) => warn!("Skipping unreadable results of round {} in game {}: {}", row.round_number, game.id, e),
+        }
+    }
+
+    Ok(Transcript {
+        game_id: game.id,
+        code: game.code.clone(),
+        variant: game.variant.clone(),
+        created_at: game.created_at,
+        completed_at: game.completed_at,
+        abandoned_at: game.abandoned_at,
+        outcome: game.outcome.clone().and_then(|outcome| serde_json::from_value(outcome).ok()),
+        players,
+        rounds,
+    })
+}
+
+fn round_mut(rounds: &mut [TranscriptRound], round_number: usize) -> Option<&mut TranscriptRound> {
+    rounds.iter_mut().find(|round| round.round_number == round_number)
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+#[cfg(feature = "server")]
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use uuid::Uuid;
+use crate::accessibility::suit_name;
+#[cfg(feature = "server")]
+use crate::deal_review::parse_suit;
+#[cfg(feature = "server")]
+use crate::entities::{game, game_player, game_round, round_deal};
+use crate::engine::card::Suit;
+use crate::protocol::PlayerRoundResult;
+#[cfg(feature = "server")]
+use tracing::warn;
+
+/// Rounds under a suit before it can be named a player's best trump in profiles and digests
+pub const MIN_ROUNDS_FOR_BEST: u32 = 3;
+
+/// How a player fares under one trump suit
+#[cfg_attr(feature = "typescript", derive(ts_rs::TS))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrumpStats {
+    /// Null for rounds played without trump
+    pub trump: Option<Suit>,
+    pub rounds: u32,
+    /// Rounds where the bid was made exactly
+    pub made: u32,
+    pub make_rate: f64,
+    pub average_score: f64,
+}
+
+#[derive(Debug, Default)]
+struct Tally {
+    rounds: u32,
+    made: u32,
+    total_score: i64,
+}
+
+/// Stats per trump from one player's rounds, most played first
+pub fn aggregate<'a>(rounds: impl IntoIterator<Item = (Option<Suit>, &'a PlayerRoundResult)>) -> Vec<TrumpStats> {
+    let mut tallies: HashMap<Option<Suit>, Tally> = HashMap::new();
+    for (trump, result) in rounds {
+        let tally = tallies.entry(trump).or_default();
+        tally.rounds += 1;
+        if result.bid == result.tricks_won {
+            tally.made += 1;
+        }
+        tally.total_score += result.score as i64;
+    }
+
+    let mut stats: Vec<TrumpStats> = tallies.into_iter()
+        .map(|(trump, tally)| TrumpStats {
+            trump,
+            rounds: tally.rounds,
+            made: tally.made,
+            make_rate: tally.made as f64 / tally.rounds as f64,
+            average_score: tally.total_score as f64 / tally.rounds as f64,
+        })
+        .collect();
+    stats.sort_by(|a, b| {
+        b.rounds.cmp(&a.rounds).then_with(|| a.trump.map(suit_name).cmp(&b.trump.map(suit_name)))
+    });
+    stats
+}
+
+/// The suit with the best average score among those played at least `min_rounds` times;
+/// make rate settles a level average
+pub fn best_trump(stats: &[TrumpStats], min_rounds: u32) -> Option<Suit> {
+    stats.iter()
+        .filter(|s| s.trump.is_some() && s.rounds >= min_rounds)
+        .max_by(|a, b| {
+            a.average_score.total_cmp(&b.average_score).then(a.make_rate.total_cmp(&b.make_rate))
+        })
+        .and_then(|s| s.trump)
+}
+
+/// The line shown in digests and post-game summaries
+pub fn best_trump_line(suit: Suit) -> String {
+    format!("Your best trump is {}", suit_name(suit))
+}
+
+/// Trump stats of every account that played in the given games. Rounds dealt before deals
+/// were recorded have no known trump and are left out.
+#[cfg(feature = "server")]
+pub async fn stats_for_games(db: &DatabaseConnection, game_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<TrumpStats>>, DbErr> {
+    let trumps: HashMap<(Uuid, i32), Option<Suit>> = round_deal::Entity::find()
+        .filter(round_deal::Column::GameId.is_in(game_ids.iter().copied()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|deal| ((deal.game_id, deal.round_number), deal.trump_suit.as_deref().and_then(parse_suit)))
+        .collect();
+
+    let rounds = game_round::Entity::find()
+        .filter(game_round::Column::GameId.is_in(game_ids.iter().copied()))
+        .all(db)
+        .await?;
+
+    let mut per_player: HashMap<Uuid, Vec<(Option<Suit>, PlayerRoundResult)>> = HashMap::new();
+    for round in rounds {
+        let Some(trump) = trumps.get(&(round.game_id, round.round_number)) else { continue };
+        let results: Vec<PlayerRoundResult> = match serde_json::from_value(round.player_results) {
+            Ok(results) => results,
+            Err(e) => {
+                warn!("Skipping unreadable round {} of game {}: {}", round.round_number, round.game_id, e);
+                continue;
+            }
+        };
+        for result in results {
+            if let Ok(player_id) = Uuid::parse_str(&result.player_id) {
+                per_player.entry(player_id).or_default().push((*trump, result));
+            }
+        }
+    }
+
+    Ok(per_player.into_iter()
+        .map(|(player_id, rounds)| (player_id, aggregate(rounds.iter().map(|(trump, r)| (*trump, r)))))
+        .collect())
+}
+
+/// Trump stats over every non-deleted game the user played
+#[cfg(feature = "server")]
+pub async fn stats_for_user(db: &DatabaseConnection, user_id: Uuid) -> Result<Vec<TrumpStats>, DbErr> {
+    let game_ids: Vec<Uuid> = game_player::Entity::find()
+        .filter(game_player::Column::PlayerId.eq(user_id))
+        .find_also_related(game::Entity)
+        .filter(game::Column::DeletedAt.is_null())
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|(participation, _)| participation.game_id)
+        .collect();
+    Ok(stats_for_games(db, &game_ids).await?.remove(&user_id).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(bid: u8, tricks_won: u8) -> PlayerRoundResult {
+        PlayerRoundResult {
+            player_id: "a".to_string(),
+            bid,
+            tricks_won,
+            penalty: 0,
+            score: crate::engine::scoring::ScoreCalculator::calculate_player_score(bid, tricks_won),
+        }
+    }
+
+    #[test]
+    fn test_aggregate_and_best_trump() {
+        let rounds = [
+            (Some(Suit::Hearts), result(1, 1)),
+            (Some(Suit::Hearts), result(2, 2)),
+            (Some(Suit::Hearts), result(1, 0)),
+            (Some(Suit::Spades), result(3, 3)),
+            (Some(Suit::Clubs), result(0, 2)),
+            (Some(Suit::Clubs), result(1, 2)),
+        ];
+        let stats = aggregate(rounds.iter().map(|(trump, r)| (*trump, r)));
+
+        assert_eq!(stats.iter().map(|s| s.trump).collect::<Vec<_>>(), vec![Some(Suit::Hearts), Some(Suit::Clubs), Some(Suit::Spades)]);
+        let hearts = &stats[0];
+        assert_eq!((hearts.rounds, hearts.made), (3, 2));
+        assert!((hearts.make_rate - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(stats[1].made, 0);
+
+        // Spades scored best but was only trump once
+        assert_eq!(best_trump(&stats, 1), Some(Suit::Spades));
+        assert_eq!(best_trump(&stats, 2), Some(Suit::Hearts));
+        assert_eq!(best_trump(&stats, MIN_ROUNDS_FOR_BEST), Some(Suit::Hearts));
+        assert_eq!(best_trump(&stats, 4), None);
+        assert_eq!(best_trump_line(Suit::Hearts), "Your best trump is Hearts");
+    }
+}
@@ -0,0 +1,147 @@
+#[cfg(feature = "server")]
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use tokio::sync::RwLock;
+#[cfg(feature = "server")]
+use crate::engine::PlayerId;
+#[cfg(feature = "server")]
+use crate::entities::user;
+#[cfg(feature = "server")]
+use tracing::debug;
+
+/// What other players see of a user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerProfile {
+    pub username: String,
+    /// Shown instead of the username when set
+    pub display_name: Option<String>,
+    pub avatar: Option<String>,
+}
+
+impl PlayerProfile {
+    /// A profile with only a username, e.g. for guests
+    pub fn named(username: String) -> Self {
+        Self { username, display_name: None, avatar: None }
+    }
+}
+
+#[cfg(feature = "server")]
+impl From<&user::Model> for PlayerProfile {
+    fn from(user: &user::Model) -> Self {
+        Self {
+            username: user.username.clone(),
+            display_name: user.display_name.clone(),
+            avatar: user.avatar.clone(),
+        }
+    }
+}
+
+/// In-memory cache of player profiles keyed by player ID.
+/// Populated at auth/connect time so lobby listings never hit the database.
+#[cfg(feature = "server")]
+pub struct UserCache {
+    profiles: RwLock<HashMap<PlayerId, PlayerProfile>>,
+}
+
+#[cfg(feature = "server")]
+impl Default for UserCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "server")]
+impl UserCache {
+    pub fn new() -> Self {
+        Self {
+            profiles: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Store or replace the profile for a player
+    pub async fn insert(&self, player_id: PlayerId, profile: PlayerProfile) {
+        let mut profiles = self.profiles.write().await;
+        profiles.insert(player_id, profile);
+    }
+
+    /// Record a player's username, keeping the rest of a cached profile
+    pub async fn set_username(&self, player_id: PlayerId, username: String) {
+        let mut profiles = self.profiles.write().await;
+        match profiles.get_mut(&player_id) {
+            Some(profile) => profile.username = username,
+            None => {
+                profiles.insert(player_id, PlayerProfile::named(username));
+            }
+        }
+    }
+
+    /// Look up a single profile
+    pub async fn get(&self, player_id: &PlayerId) -> Option<PlayerProfile> {
+        let profiles = self.profiles.read().await;
+        profiles.get(player_id).cloned()
+    }
+
+    /// Look up several profiles under a single lock
+    pub async fn get_many(&self, player_ids: &[PlayerId]) -> Vec<Option<PlayerProfile>> {
+        let profiles = self.profiles.read().await;
+        player_ids.iter().map(|id| profiles.get(id).cloned()).collect()
+    }
+
+    /// Drop a cached profile, e.g. after the user is renamed
+    pub async fn invalidate(&self, player_id: &PlayerId) {
+        let mut profiles = self.profiles.write().await;
+        if profiles.remove(player_id).is_some() {
+            debug!("Invalidated cached profile for player {}", player_id);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+
+    fn named(username: &str) -> PlayerProfile {
+        PlayerProfile::named(username.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_insert_and_get() {
+        let cache = UserCache::new();
+        cache.insert("p1".to_string(), named("alice")).await;
+
+        assert_eq!(cache.get(&"p1".to_string()).await, Some(named("alice")));
+        assert_eq!(cache.get(&"p2".to_string()).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_many_preserves_order() {
+        let cache = UserCache::new();
+        cache.insert("p1".to_string(), named("alice")).await;
+        cache.insert("p3".to_string(), named("carol")).await;
+
+        let profiles = cache.get_many(&["p3".to_string(), "p2".to_string(), "p1".to_string()]).await;
+        assert_eq!(profiles, vec![Some(named("carol")), None, Some(named("alice"))]);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate() {
+        let cache = UserCache::new();
+        cache.insert("p1".to_string(), named("alice")).await;
+        cache.invalidate(&"p1".to_string()).await;
+        assert_eq!(cache.get(&"p1".to_string()).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_set_username_keeps_profile() {
+        let cache = UserCache::new();
+        let profile = PlayerProfile { display_name: Some("Ally".to_string()), avatar: Some("fox".to_string()), ..named("alice") };
+        cache.insert("p1".to_string(), profile.clone()).await;
+
+        cache.set_username("p1".to_string(), "alice2".to_string()).await;
+        cache.set_username("p2".to_string(), "bob".to_string()).await;
+
+        assert_eq!(cache.get(&"p1".to_string()).await, Some(PlayerProfile { username: "alice2".to_string(), ..profile }));
+        assert_eq!(cache.get(&"p2".to_string()).await, Some(named("bob")));
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter, ColumnTrait};
+use uuid::Uuid;
+use crate::connection::PlayerId;
+use crate::user_cache::{PlayerProfile, UserCache};
+use tracing::warn;
+
+/// Resolves profiles for any player ID, online or not.
+/// Checks the shared UserCache first and falls back to the users table, caching what it finds.
+#[derive(Clone)]
+pub struct UserDirectory {
+    cache: Arc<UserCache>,
+    db: DatabaseConnection,
+}
+
+impl UserDirectory {
+    pub fn new(cache: Arc<UserCache>, db: DatabaseConnection) -> Self {
+        Self { cache, db }
+    }
+
+    /// Resolve a single profile
+    pub async fn resolve(&self, player_id: &PlayerId) -> Option<PlayerProfile> {
+        self.resolve_many(std::slice::from_ref(player_id)).await
+            .remove(player_id)
+    }
+
+    /// Resolve profiles for several players; IDs that cannot be resolved are left out
+    pub async fn resolve_many(&self, player_ids: &[PlayerId]) -> HashMap<PlayerId, PlayerProfile> {
+        let cached = self.cache.get_many(player_ids).await;
+
+        let mut profiles = HashMap::new();
+        let mut missing = Vec::new();
+        for (player_id, profile) in player_ids.iter().zip(cached) {
+            match profile {
+                Some(profile) => {
+                    profiles.insert(player_id.clone(), profile);
+                }
+                None => {
+                    if let Ok(uuid) = Uuid::parse_str(player_id) {
+                        missing.push(uuid);
+                    }
+                }
+            }
+        }
+
+        if missing.is_empty() {
+            return profiles;
+        }
+
+        match crate::entities::user::Entity::find()
+            .filter(crate::entities::user::Column::Id.is_in(missing))
+            .all(&self.db)
+            .await
+        {
+            Ok(users) => {
+                for user in users {
+                    let player_id = user.id.to_string();
+                    let profile = PlayerProfile::from(&user);
+                    self.cache.insert(player_id.clone(), profile.clone()).await;
+                    profiles.insert(player_id, profile);
+                }
+            }
+            Err(e) => warn!("Failed to load profiles from DB: {}", e),
+        }
+
+        profiles
+    }
+}
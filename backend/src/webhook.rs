@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sea_orm::{DatabaseConnection, EntityTrait};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use uuid::Uuid;
+use crate::connection::PlayerId;
+use crate::entities::tournament;
+use crate::game::GameId;
+use crate::engine::tiebreak::Placement;
+use tracing::{info, warn};
+
+/// Delivery attempts before a webhook is given up
+pub const MAX_ATTEMPTS: u32 = 5;
+
+/// `sha256=<hex HMAC of the body>`, keyed with the tournament's webhook secret
+pub const SIGNATURE_HEADER: &str = "x-webhook-signature";
+/// Same for every retry of one delivery, so receivers can drop duplicates
+pub const DELIVERY_HEADER: &str = "x-webhook-delivery";
+pub const EVENT_HEADER: &str = "x-webhook-event";
+
+pub const GAME_COMPLETED: &str = "game.completed";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerResult {
+    pub player_id: PlayerId,
+    pub username: String,
+    pub final_score: i32,
+    /// 1 for the winner; players level after the tie-breakers share a placement. None for players who forfeited
+    pub placement: Option<usize>,
+    pub forfeited: bool,
+}
+
+/// Body of a `game.completed` webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameCompletedEvent {
+    pub event: String,
+    pub delivery_id: Uuid,
+    pub tournament_id: Uuid,
+    pub game_id: GameId,
+    pub completed_at: DateTime<Utc>,
+    /// Ordered by placement, forfeited players last
+    pub results: Vec<PlayerResult>,
+}
+
+impl GameCompletedEvent {
+    /// `placements` come from the game, so the webhook ranks players the same way GameOver does
+    pub fn new(
+        tournament_id: Uuid,
+        game_id: GameId,
+        scores: &HashMap<PlayerId, i32>,
+        placements: &[Placement],
+        names: &HashMap<PlayerId, String>,
+        forfeited: &[PlayerId],
+    ) -> Self {
+        let result = |player_id: &PlayerId, placement: Option<usize>| PlayerResult {
+            player_id: player_id.clone(),
+            username: names.get(player_id).cloned().unwrap_or_else(|| "Unknown".to_string()),
+            final_score: scores.get(player_id).copied().unwrap_or(0),
+            placement,
+            forfeited: forfeited.contains(player_id),
+        };
+        let mut results: Vec<PlayerResult> = placements.iter()
+            .map(|p| result(&p.player_id, Some(p.place)))
+            .collect();
+
+        // Players without a placement forfeited; they follow by score
+        let mut unplaced: Vec<PlayerResult> = scores.keys()
+            .filter(|player_id| !placements.iter().any(|p| p.player_id == **player_id))
+            .map(|player_id| result(player_id, None))
+            .collect();
+        unplaced.sort_by(|a, b| {
+            b.final_score
+                .cmp(&a.final_score)
+                .then(a.username.cmp(&b.username))
+                .then(a.player_id.cmp(&b.player_id))
+        });
+        results.extend(unplaced);
+
+        Self {
+            event: GAME_COMPLETED.to_string(),
+            delivery_id: Uuid::new_v4(),
+            tournament_id,
+            game_id,
+            completed_at: Utc::now(),
+            results,
+        }
+    }
+}
+
+/// Signature header value for a webhook body
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Check a signature header in constant time
+pub fn verify(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(hex_digest) = signature.strip_prefix("sha256=") else {
+        return false;
+    };
+    let Ok(digest) = hex::decode(hex_digest) else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    mac.verify_slice(&digest).is_ok()
+}
+
+/// Random secret for a new tournament
+pub fn generate_secret() -> String {
+    use rand::RngCore;
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Posts tournament webhooks in the background, retrying with exponential backoff
+pub struct WebhookDispatcher {
+    db: DatabaseConnection,
+    client: reqwest::Client,
+    base_delay: Duration,
+}
+
+impl WebhookDispatcher {
+    pub fn new(db: DatabaseConnection) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("Failed to build webhook HTTP client");
+        Self { db, client, base_delay: Duration::from_secs(2) }
+    }
+
+    /// Delay before the first retry; later retries double it
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.base_delay * 2u32.pow(attempt.saturating_sub(1))
+    }
+
+    /// Deliver a game.completed event to the tournament's webhook, if it has one
+    pub fn game_completed(self: &Arc<Self>, event: GameCompletedEvent) -> tokio::task::JoinHandle<()> {
+        let dispatcher = Arc::clone(self);
+        tokio::spawn(async move { dispatcher.deliver(event).await })
+    }
+
+    async fn deliver(&self, event: GameCompletedEvent) {
+        let tournament = match tournament::Entity::find_by_id(event.tournament_id).one(&self.db).await {
+            Ok(Some(tournament)) => tournament,
+            Ok(None) => {
+                warn!("Game {} completed for unknown tournament {}", event.game_id, event.tournament_id);
+                return;
+            }
+            Err(e) => {
+                warn!("Failed to load tournament {} for webhook: {}", event.tournament_id, e);
+                return;
+            }
+        };
+        let Some(url) = tournament.webhook_url else {
+            return;
+        };
+
+        let body = match serde_json::to_vec(&event) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("Failed to serialize webhook for game {}: {}", event.game_id, e);
+                return;
+            }
+        };
+        let signature = sign(&tournament.webhook_secret, &body);
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            let result = self.client
+                .post(&url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, &signature)
+                .header(DELIVERY_HEADER, event.delivery_id.to_string())
+                .header(EVENT_HEADER, &event.event)
+                .body(body.clone())
+                .send()
+                .await;
+
+            match result {
+                Ok(response) if response.status().is_success() => {
+                    info!("Delivered {} webhook for game {} (attempt {})", event.event, event.game_id, attempt);
+                    return;
+                }
+                Ok(response) => warn!(
+                    "Webhook for game {} returned {} (attempt {}/{})",
+                    event.game_id, response.status(), attempt, MAX_ATTEMPTS
+                ),
+                Err(e) => warn!(
+                    "Webhook for game {} failed: {} (attempt {}/{})",
+                    event.game_id, e, attempt, MAX_ATTEMPTS
+                ),
+            }
+
+            if attempt < MAX_ATTEMPTS {
+                tokio::time::sleep(self.backoff(attempt)).await;
+            }
+        }
+        warn!("Giving up on {} webhook for game {} after {} attempts", event.event, event.game_id, MAX_ATTEMPTS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_round_trip() {
+        let signature = sign("secret", b"{\"a\":1}");
+        assert!(signature.starts_with("sha256="));
+        assert!(verify("secret", b"{\"a\":1}", &signature));
+        assert!(!verify("other", b"{\"a\":1}", &signature));
+        assert!(!verify("secret", b"{\"a\":2}", &signature));
+        assert!(!verify("secret", b"{\"a\":1}", "sha256=zz"));
+    }
+
+    #[test]
+    fn test_placements_share_ties_and_skip_forfeits() {
+        let scores = HashMap::from([
+            ("a".to_string(), 40),
+            ("b".to_string(), 25),
+            ("c".to_string(), 40),
+            ("d".to_string(), 90),
+        ]);
+        let players: Vec<PlayerId> = ["a", "b", "c", "d"].iter().map(|p| p.to_string()).collect();
+        let forfeited = ["d".to_string()];
+        let placements = crate::engine::tiebreak::rank_players(&players, &scores, &[], &[], &forfeited);
+        let event = GameCompletedEvent::new(Uuid::new_v4(), Uuid::new_v4(), &scores, &placements, &HashMap::new(), &forfeited);
+
+        let placements: Vec<_> = event.results.iter().map(|r| (r.player_id.as_str(), r.placement)).collect();
+        assert_eq!(placements, vec![("a", Some(1)), ("c", Some(1)), ("b", Some(3)), ("d", None)]);
+    }
+}
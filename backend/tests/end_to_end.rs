@@ -0,0 +1,646 @@
+//! Drives a real server over HTTP and WebSocket, the way a client does: catches breaks in auth,
+//! routing and serialization that the manager-level tests in integration_tests.rs can't see
+
+use futures::{SinkExt, StreamExt};
+use german_bridge_backend::audit::AuditLog;
+use german_bridge_backend::client_ip::TrustedProxies;
+use german_bridge_backend::connection::{ConnectionManager, PlayerId};
+use german_bridge_backend::engine::rules::RuleSet;
+use german_bridge_backend::entities;
+use german_bridge_backend::engine::GamePhase;
+use german_bridge_backend::game::GameManager;
+use german_bridge_backend::lobby::LobbyManager;
+use german_bridge_backend::presence::PresenceService;
+use german_bridge_backend::rate_limit::RateLimitConfig;
+use german_bridge_backend::protocol::{ClientEnvelope, ClientMessage, GameSettings, PlayerAction, ServerMessage, SharedAddressPolicy};
+use german_bridge_backend::router::MessageRouter;
+use german_bridge_backend::short_code::IdOrCode;
+use german_bridge_backend::tls::{self, TlsConfig};
+use german_bridge_backend::server::ServerConfig;
+use german_bridge_backend::settings_policy::SettingsPolicy;
+use german_bridge_backend::{config, leaderboard, server};
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, EntityTrait, Schema};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::oneshot;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderMap;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+/// Longest a client waits for any one message
+const RECV_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Shared admin token the test server accepts
+const ADMIN_TOKEN: &str = "test-admin-token";
+
+/// In-memory database with tables created from the entity definitions; the migrations are
+/// written for PostgreSQL
+async fn test_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    let schema = Schema::new(db.get_database_backend());
+    create_table(&db, &schema, entities::user::Entity).await;
+    create_table(&db, &schema, entities::lobby::Entity).await;
+    create_table(&db, &schema, entities::lobby_player::Entity).await;
+    create_table(&db, &schema, entities::game::Entity).await;
+    create_table(&db, &schema, entities::game_player::Entity).await;
+    create_table(&db, &schema, entities::game_round::Entity).await;
+    // The migrations key rounds by game and number; entities cannot declare that
+    db.execute_unprepared("CREATE UNIQUE INDEX idx_game_rounds_game_round ON game_rounds (game_id, round_number)").await.unwrap();
+    create_table(&db, &schema, entities::round_deal::Entity).await;
+    create_table(&db, &schema, entities::game_action::Entity).await;
+    create_table(&db, &schema, entities::friendship::Entity).await;
+    create_table(&db, &schema, entities::user_block::Entity).await;
+    create_table(&db, &schema, entities::audit_log::Entity).await;
+    create_table(&db, &schema, entities::user_achievement::Entity).await;
+    db
+}
+
+async fn create_table<E: EntityTrait>(db: &DatabaseConnection, schema: &Schema, entity: E) {
+    let stmt = db.get_database_backend().build(&schema.create_table_from_entity(entity));
+    db.execute(stmt).await.unwrap();
+}
+
+/// A server on an ephemeral port backed by an in-memory SQLite database; stops when dropped
+struct TestServer {
+    addr: SocketAddr,
+    _shutdown: oneshot::Sender<()>,
+}
+
+impl TestServer {
+    async fn start() -> Self {
+        Self::start_with(|_| {}).await
+    }
+
+    /// Start with `configure` applied on top of the default config
+    async fn start_with(configure: impl FnOnce(&mut ServerConfig)) -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Self::app(configure).await;
+        let (shutdown, stopped) = oneshot::channel::<()>();
+        tokio::spawn(server::serve(listener, app, async {
+            let _ = stopped.await;
+        }));
+        Self { addr, _shutdown: shutdown }
+    }
+
+    /// Serve over TLS with the self-signed certificate in tests/fixtures
+    async fn start_tls() -> Self {
+        let tls = TlsConfig {
+            cert_path: concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/localhost.pem").into(),
+            key_path: concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/localhost-key.pem").into(),
+        };
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Self::app(|_| {}).await;
+        let (shutdown, stopped) = oneshot::channel::<()>();
+        tokio::spawn(tls::serve_tls(listener, tls.acceptor().unwrap(), app, async {
+            let _ = stopped.await;
+        }));
+        Self { addr, _shutdown: shutdown }
+    }
+
+    async fn app(configure: impl FnOnce(&mut ServerConfig)) -> axum::Router {
+        let db = test_db().await;
+
+        let mut config = config::load_config().unwrap();
+        config.admin_token = Some(ADMIN_TOKEN.to_string());
+        configure(&mut config);
+        let connection_manager = Arc::new(
+            ConnectionManager::new()
+                .with_max_connections(config.max_connections)
+                .with_rate_limit(config.rate_limit)
+                .with_disconnect_grace(Duration::from_secs(config.disconnect_grace_secs)),
+        );
+        let game_manager = Arc::new(GameManager::new(Arc::clone(&connection_manager), db.clone()));
+        let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&connection_manager), db.clone()));
+        let message_router = Arc::new(
+            MessageRouter::new(lobby_manager, Arc::clone(&game_manager), Arc::clone(&connection_manager))
+                .with_settings_policy(config.game_settings.clone())
+                .with_audit_log(AuditLog::new(db.clone())),
+        );
+        let presence = Arc::new(PresenceService::new(Arc::clone(&connection_manager), db.clone()));
+        let leaderboards = Arc::new(leaderboard::LeaderboardCache::new());
+        server::build_app(&config, connection_manager, game_manager, message_router, db, leaderboards, presence)
+    }
+
+    async fn post(&self, path: &str, body: serde_json::Value) -> (u16, String) {
+        let response = reqwest::Client::new()
+            .post(format!("http://{}{}", self.addr, path))
+            .header("content-type", "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .unwrap();
+        (response.status().as_u16(), response.text().await.unwrap())
+    }
+
+    async fn get_as_admin(&self, path: &str) -> (u16, String) {
+        let response = reqwest::Client::new()
+            .get(format!("http://{}{}", self.addr, path))
+            .header("x-admin-token", ADMIN_TOKEN)
+            .send()
+            .await
+            .unwrap();
+        (response.status().as_u16(), response.text().await.unwrap())
+    }
+
+    /// Register an account and return its session token and user id
+    async fn register(&self, username: &str) -> (String, PlayerId) {
+        let (status, body) = self.post("/api/register", serde_json::json!({ "username": username, "password": "correct horse" })).await;
+        assert_eq!(status, 200, "register failed: {}", body);
+        let session: serde_json::Value = serde_json::from_str(&body).unwrap();
+        (session["token"].as_str().unwrap().to_string(), session["user_id"].as_str().unwrap().to_string())
+    }
+
+    /// Register `username` and open a WebSocket session for them
+    async fn connect(&self, username: &str) -> Client {
+        self.connect_with(username, HeaderMap::new()).await
+    }
+
+    /// Connect as if through a proxy that forwards `client_ip`
+    async fn connect_from(&self, username: &str, client_ip: &str) -> Client {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", client_ip.parse().unwrap());
+        self.connect_with(username, headers).await
+    }
+
+    async fn connect_with(&self, username: &str, headers: HeaderMap) -> Client {
+        let (token, user_id) = self.register(username).await;
+        let mut request = format!("ws://{}/ws?token={}", self.addr, token).into_client_request().unwrap();
+        request.headers_mut().extend(headers);
+        let (socket, _) = tokio_tungstenite::connect_async(request).await.unwrap();
+        let mut client = Client { player_id: user_id, socket };
+        let connected = client.expect(|msg| match msg {
+            ServerMessage::Connected { player_id } => Some(player_id),
+            _ => None,
+        }).await;
+        assert_eq!(connected, client.player_id);
+        client
+    }
+}
+
+struct Client {
+    player_id: PlayerId,
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+impl Client {
+    async fn send(&mut self, message: ClientMessage) {
+        let text = serde_json::to_string(&ClientEnvelope::from(message)).unwrap();
+        self.socket.send(Message::Text(text)).await.unwrap();
+    }
+
+    /// Send a frame as given, for keys the typed messages don't cover
+    async fn send_json(&mut self, frame: serde_json::Value) {
+        self.socket.send(Message::Text(frame.to_string())).await.unwrap();
+    }
+
+    /// The next protocol message; game messages carry an extra `seq`, which is ignored here
+    async fn recv(&mut self) -> ServerMessage {
+        loop {
+            let frame = tokio::time::timeout(RECV_TIMEOUT, self.socket.next())
+                .await
+                .unwrap_or_else(|_| panic!("{} timed out waiting for a message", self.player_id))
+                .expect("server closed the connection")
+                .unwrap();
+            if let Message::Text(text) = frame {
+                return serde_json::from_str(&text).unwrap_or_else(|e| panic!("unreadable message {}: {}", text, e));
+            }
+        }
+    }
+
+    /// Skip messages until `pick` accepts one
+    async fn expect<T>(&mut self, pick: impl Fn(ServerMessage) -> Option<T>) -> T {
+        loop {
+            if let Some(found) = pick(self.recv().await) {
+                return found;
+            }
+        }
+    }
+
+    /// Play the first valid action on every turn and start each next round when it is ours,
+    /// until the game ends. Returns the final scores and any errors the server sent.
+    async fn play_to_the_end(mut self) -> (HashMap<PlayerId, i32>, Vec<String>) {
+        let mut errors = Vec::new();
+        loop {
+            match self.recv().await {
+                ServerMessage::YourTurn { valid_actions, .. } => match valid_actions.first() {
+                    Some(PlayerAction::Bid(bid)) => self.send(ClientMessage::PlaceBid { bid: *bid, state_version: None }).await,
+                    Some(PlayerAction::PlayCard(card)) => self.send(ClientMessage::PlayCard { card: *card, state_version: None }).await,
+                    // Nothing to play between rounds; the GameState below covers it
+                    Some(PlayerAction::HiddenBid) | None => {}
+                },
+                ServerMessage::GameState { state } if state.phase == GamePhase::RoundComplete && state.your_turn => {
+                    self.send(ClientMessage::StartNextRound).await;
+                }
+                ServerMessage::GameOver { final_scores, .. } => return (final_scores, errors),
+                ServerMessage::Error { message, .. } => errors.push(message),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_rejects_websocket_without_a_valid_token() {
+    let server = TestServer::start().await;
+
+    let missing = tokio_tungstenite::connect_async(format!("ws://{}/ws", server.addr)).await;
+    assert!(missing.is_err());
+    let forged = tokio_tungstenite::connect_async(format!("ws://{}/ws?token=not-a-jwt", server.addr)).await;
+    assert!(forged.is_err());
+
+    let (status, _) = server.post("/api/login", serde_json::json!({ "username": "nobody", "password": "wrong" })).await;
+    assert_eq!(status, 401);
+}
+
+#[tokio::test]
+async fn test_request_ids_are_echoed_in_acks_and_errors() {
+    let server = TestServer::start().await;
+    let mut alice = server.connect("alice").await;
+
+    // Replies come before the Ack
+    alice.send_json(serde_json::json!({ "type": "Ping", "request_id": "r1" })).await;
+    alice.expect(|msg| matches!(msg, ServerMessage::Pong).then_some(())).await;
+    assert!(matches!(alice.recv().await, ServerMessage::Ack { request_id } if request_id == "r1"));
+
+    // Not in a game
+    alice.send_json(serde_json::json!({ "type": "RequestGameState", "request_id": "r2" })).await;
+    let request_id = alice.expect(|msg| match msg {
+        ServerMessage::Error { request_id, .. } => Some(request_id),
+        _ => None,
+    }).await;
+    assert_eq!(request_id.as_deref(), Some("r2"));
+
+    // An unknown message still gets its id back, and messages without one get no Ack
+    alice.send_json(serde_json::json!({ "type": "Shuffle", "request_id": "r3" })).await;
+    assert!(matches!(alice.recv().await, ServerMessage::Error { request_id: Some(id), .. } if id == "r3"));
+    alice.send(ClientMessage::Ping).await;
+    assert!(matches!(alice.recv().await, ServerMessage::Pong));
+    alice.send_json(serde_json::json!({ "type": "Ping", "request_id": "r4" })).await;
+    assert!(matches!(alice.recv().await, ServerMessage::Pong));
+    assert!(matches!(alice.recv().await, ServerMessage::Ack { request_id } if request_id == "r4"));
+}
+
+#[tokio::test]
+async fn test_logins_joins_and_admin_changes_are_audited() {
+    let server = TestServer::start().await;
+    let mut alice = server.connect("alice").await;
+    let mut bob = server.connect("bob").await;
+    let (status, _) = server.post("/api/login", serde_json::json!({ "username": "bob", "password": "wrong" })).await;
+    assert_eq!(status, 401);
+
+    alice.send(ClientMessage::CreateLobby { settings: GameSettings::default() }).await;
+    let code = alice.expect(|msg| match msg {
+        ServerMessage::LobbyCreated { code, .. } => Some(code),
+        _ => None,
+    }).await;
+    bob.send(ClientMessage::JoinLobby { lobby_id: IdOrCode::Code(code) }).await;
+    bob.expect(|msg| matches!(msg, ServerMessage::LobbyJoined { .. }).then_some(())).await;
+
+    let response = reqwest::Client::new()
+        .put(format!("http://{}/api/admin/features/ranked", server.addr))
+        .header("x-admin-token", ADMIN_TOKEN)
+        .header("content-type", "application/json")
+        .body(r#"{"enabled": false}"#)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+
+    let (status, body) = server.get_as_admin(&format!("/api/admin/audit?actor={}", bob.player_id)).await;
+    assert_eq!(status, 200, "audit search failed: {}", body);
+    let entries: Vec<entities::audit_log::Model> = serde_json::from_str(&body).unwrap();
+    let kinds: Vec<&str> = entries.iter().map(|e| e.kind.as_str()).collect();
+    assert_eq!(kinds, ["LobbyJoin", "Login"]);
+    assert!(entries.iter().all(|e| e.ip.as_deref() == Some("127.0.0.1")));
+    // Nothing was forwarded, so there is no separate peer
+    assert!(entries.iter().all(|e| e.detail.get("peer").is_none()));
+    assert_eq!(entries[1].detail["success"], false);
+
+    let (_, body) = server.get_as_admin("/api/admin/audit?kind=Admin").await;
+    let entries: Vec<entities::audit_log::Model> = serde_json::from_str(&body).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].actor.as_deref(), Some("admin-token"));
+    assert_eq!(entries[0].detail["path"], "/api/admin/features/ranked");
+}
+
+#[tokio::test]
+async fn test_audit_entries_record_the_forwarded_client_address() {
+    let server = TestServer::start_with(|config| {
+        config.trusted_proxies = TrustedProxies::parse("127.0.0.1").unwrap();
+    }).await;
+    let mut alice = server.connect_from("alice", "203.0.113.8").await;
+    let mut bob = server.connect_from("bob", "203.0.113.9").await;
+    let status = reqwest::Client::new()
+        .post(format!("http://{}/api/login", server.addr))
+        .header("x-forwarded-for", "203.0.113.9")
+        .header("content-type", "application/json")
+        .body(serde_json::json!({ "username": "bob", "password": "wrong" }).to_string())
+        .send()
+        .await
+        .unwrap()
+        .status();
+    assert_eq!(status.as_u16(), 401);
+
+    alice.send(ClientMessage::CreateLobby { settings: GameSettings::default() }).await;
+    let code = alice.expect(|msg| match msg {
+        ServerMessage::LobbyCreated { code, .. } => Some(code),
+        _ => None,
+    }).await;
+    bob.send(ClientMessage::JoinLobby { lobby_id: IdOrCode::Code(code) }).await;
+    bob.expect(|msg| matches!(msg, ServerMessage::LobbyJoined { .. }).then_some(())).await;
+
+    let (_, body) = server.get_as_admin(&format!("/api/admin/audit?actor={}", bob.player_id)).await;
+    let entries: Vec<entities::audit_log::Model> = serde_json::from_str(&body).unwrap();
+    let kinds: Vec<&str> = entries.iter().map(|e| e.kind.as_str()).collect();
+    assert_eq!(kinds, ["LobbyJoin", "Login"]);
+    assert!(entries.iter().all(|e| e.ip.as_deref() == Some("203.0.113.9") && e.detail["peer"] == "127.0.0.1"), "{:?}", entries);
+}
+
+#[tokio::test]
+async fn test_demoted_moderator_loses_access_with_their_old_token() {
+    let server = TestServer::start().await;
+    let (_, user_id) = server.register("mallory").await;
+    let client = reqwest::Client::new();
+    let set_role = |role: &'static str| {
+        client.put(format!("http://{}/api/admin/users/{}/role", server.addr, user_id))
+            .header("x-admin-token", ADMIN_TOKEN)
+            .header("content-type", "application/json")
+            .body(serde_json::json!({ "role": role }).to_string())
+            .send()
+    };
+    let traces = |token: &str| {
+        client.get(format!("http://{}/api/admin/traces", server.addr))
+            .bearer_auth(token)
+            .send()
+    };
+
+    // Promoted, then signed in again for a moderator token
+    assert_eq!(set_role("moderator").await.unwrap().status().as_u16(), 204);
+    let (status, body) = server.post("/api/login", serde_json::json!({ "username": "mallory", "password": "correct horse" })).await;
+    assert_eq!(status, 200, "login failed: {}", body);
+    let session: serde_json::Value = serde_json::from_str(&body).unwrap();
+    let token = session["token"].as_str().unwrap();
+    assert_eq!(traces(token).await.unwrap().status().as_u16(), 200);
+
+    // The token still says moderator, but the demotion applies at once
+    assert_eq!(set_role("player").await.unwrap().status().as_u16(), 204);
+    assert_eq!(traces(token).await.unwrap().status().as_u16(), 403);
+}
+
+#[tokio::test]
+async fn test_refuses_connections_beyond_max_connections() {
+    let server = TestServer::start_with(|config| config.max_connections = 1).await;
+    let alice = server.connect("alice").await;
+
+    let (token, _) = server.register("bob").await;
+    let url = format!("ws://{}/ws?token={}", server.addr, token);
+    match tokio_tungstenite::connect_async(url.as_str()).await {
+        Err(tokio_tungstenite::tungstenite::Error::Http(response)) => {
+            assert_eq!(response.status().as_u16(), 503);
+            assert!(response.headers().contains_key("retry-after"));
+        }
+        other => panic!("expected 503, got {:?}", other.map(|_| ())),
+    }
+
+    let stats = reqwest::get(format!("http://{}/stats", server.addr)).await.unwrap().text().await.unwrap();
+    let stats: serde_json::Value = serde_json::from_str(&stats).unwrap();
+    assert_eq!(stats["connections"]["open_sockets"], 1);
+    assert_eq!(stats["connections"]["max_connections"], 1);
+    assert_eq!(stats["connections"]["rejected_connections"], 1);
+
+    // Alice's place frees once her socket has closed
+    drop(alice);
+    let deadline = tokio::time::Instant::now() + RECV_TIMEOUT;
+    while tokio_tungstenite::connect_async(url.as_str()).await.is_err() {
+        assert!(tokio::time::Instant::now() < deadline, "place was never freed");
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+}
+
+#[tokio::test]
+async fn test_serves_https_and_wss_with_a_configured_certificate() {
+    let server = TestServer::start_tls().await;
+    let client = reqwest::Client::builder().danger_accept_invalid_certs(true).build().unwrap();
+    let response = client.get(format!("https://{}/health", server.addr)).send().await.unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let response = client
+        .post(format!("https://{}/api/register", server.addr))
+        .header("content-type", "application/json")
+        .body(serde_json::json!({ "username": "alice", "password": "correct horse" }).to_string())
+        .send()
+        .await
+        .unwrap();
+    let (status, body) = (response.status().as_u16(), response.text().await.unwrap());
+    assert_eq!(status, 200, "register failed: {}", body);
+    let token = serde_json::from_str::<serde_json::Value>(&body).unwrap()["token"].as_str().unwrap().to_string();
+
+    // Plain HTTP gets no answer
+    assert!(reqwest::get(format!("http://{}/health", server.addr)).await.is_err());
+
+    let connector = tokio_native_tls::TlsConnector::from(
+        tokio_native_tls::native_tls::TlsConnector::builder().danger_accept_invalid_certs(true).build().unwrap(),
+    );
+    let stream = connector.connect("localhost", TcpStream::connect(server.addr).await.unwrap()).await.unwrap();
+    let url = format!("wss://localhost:{}/ws?token={}", server.addr.port(), token);
+    let (mut socket, _) = tokio_tungstenite::client_async(url, stream).await.unwrap();
+    let frame = tokio::time::timeout(RECV_TIMEOUT, socket.next()).await.unwrap().unwrap().unwrap();
+    assert!(matches!(serde_json::from_str(frame.to_text().unwrap()).unwrap(), ServerMessage::Connected { .. }));
+}
+
+#[tokio::test]
+async fn test_plays_a_complete_game_over_the_wire() {
+    let server = TestServer::start().await;
+    let mut alice = server.connect("alice").await;
+    let mut bob = server.connect("bob").await;
+
+    // Three short rounds keep the script quick
+    let settings = GameSettings {
+        player_count: 2,
+        rules: RuleSet { max_cards: Some(3), ..Default::default() },
+        ..Default::default()
+    };
+    alice.send(ClientMessage::CreateLobby { settings }).await;
+    let code = alice.expect(|msg| match msg {
+        ServerMessage::LobbyCreated { code, .. } => Some(code),
+        _ => None,
+    }).await;
+
+    bob.send(ClientMessage::JoinLobby { lobby_id: IdOrCode::Code(code) }).await;
+    let lobby = bob.expect(|msg| match msg {
+        ServerMessage::LobbyJoined { lobby } => Some(lobby),
+        _ => None,
+    }).await;
+    assert_eq!(lobby.host, alice.player_id);
+    bob.send(ClientMessage::SetReady { ready: true }).await;
+    alice.expect(|msg| match msg {
+        ServerMessage::LobbyUpdated { lobby } if lobby.players.iter().all(|p| p.ready || p.id == lobby.host) && lobby.players.len() == 2 => Some(()),
+        _ => None,
+    }).await;
+
+    alice.send(ClientMessage::StartGame).await;
+    let alice_game = alice.expect(|msg| match msg {
+        ServerMessage::GameStarting { game_id, .. } => Some(game_id),
+        _ => None,
+    }).await;
+    let bob_game = bob.expect(|msg| match msg {
+        ServerMessage::GameStarting { game_id, .. } => Some(game_id),
+        _ => None,
+    }).await;
+    assert_eq!(alice_game, bob_game);
+
+    let (alice_id, bob_id) = (alice.player_id.clone(), bob.player_id.clone());
+    let alice_play = tokio::spawn(alice.play_to_the_end());
+    let bob_play = tokio::spawn(bob.play_to_the_end());
+    let (alice_scores, alice_errors) = alice_play.await.unwrap();
+    let (bob_scores, bob_errors) = bob_play.await.unwrap();
+
+    assert!(alice_errors.is_empty(), "alice got errors: {:?}", alice_errors);
+    assert!(bob_errors.is_empty(), "bob got errors: {:?}", bob_errors);
+    assert_eq!(alice_scores, bob_scores);
+    assert!(alice_scores.contains_key(&alice_id) && alice_scores.contains_key(&bob_id));
+}
+
+#[tokio::test]
+async fn test_dropped_player_gets_a_paused_clock_then_an_auto_played_turn() {
+    let server = TestServer::start_with(|config| {
+        config.disconnect_grace_secs = 1;
+        config.game_settings = SettingsPolicy::new(GameSettings::default(), 2, 8, 1, 300);
+    }).await;
+    let mut alice = server.connect("alice").await;
+    let mut bob = server.connect("bob").await;
+
+    let settings = GameSettings { player_count: 2, turn_timeout_secs: 2, ..Default::default() };
+    alice.send(ClientMessage::CreateLobby { settings }).await;
+    let code = alice.expect(|msg| match msg {
+        ServerMessage::LobbyCreated { code, .. } => Some(code),
+        _ => None,
+    }).await;
+    bob.send(ClientMessage::JoinLobby { lobby_id: IdOrCode::Code(code) }).await;
+    bob.send(ClientMessage::SetReady { ready: true }).await;
+    alice.expect(|msg| match msg {
+        ServerMessage::LobbyUpdated { lobby } if lobby.players.len() == 2 && lobby.players.iter().all(|p| p.ready || p.id == lobby.host) => Some(()),
+        _ => None,
+    }).await;
+    alice.send(ClientMessage::StartGame).await;
+    let started = std::time::Instant::now();
+    alice.send(ClientMessage::RequestGameState).await;
+    let current = alice.expect(|msg| match msg {
+        ServerMessage::GameState { state } => Some(state.current_player),
+        _ => None,
+    }).await;
+
+    // The player to act drops mid-turn; the other sees their clock stop for the grace period
+    let (dropped, mut other) = if current == alice.player_id { (alice, bob) } else { (bob, alice) };
+    let dropped_id = dropped.player_id.clone();
+    drop(dropped);
+    let (paused, resume_deadline) = other.expect(|msg| match msg {
+        ServerMessage::PlayerDisconnected { player_id, resume_deadline, .. } => Some((player_id, resume_deadline)),
+        _ => None,
+    }).await;
+    assert_eq!(paused, dropped_id);
+    let resumes_in = resume_deadline - chrono::Utc::now().timestamp_millis();
+    assert!((0..=1_000).contains(&resumes_in), "clock resumes in {}ms", resumes_in);
+
+    // Once the grace period and the rest of the turn run out, their bid is played for them
+    let auto_played = other.expect(|msg| match msg {
+        ServerMessage::PlayerAction { player_id, .. } => Some(player_id),
+        _ => None,
+    }).await;
+    assert_eq!(auto_played, dropped_id);
+    assert!(started.elapsed() >= Duration::from_millis(2_500), "played after {:?}, before the paused deadline", started.elapsed());
+}
+
+/// Send two pings from each client and count the ones the rate limiter turned away
+async fn throttled_pings(clients: &mut [Client]) -> usize {
+    for client in clients.iter_mut() {
+        client.send(ClientMessage::Ping).await;
+        client.send(ClientMessage::Ping).await;
+    }
+    let mut throttled = 0;
+    for client in clients.iter_mut() {
+        for _ in 0..2 {
+            match client.expect(|msg| match msg {
+                ServerMessage::Pong => Some(None),
+                ServerMessage::Error { message, .. } => Some(Some(message)),
+                _ => None,
+            }).await {
+                None => {}
+                Some(message) if message == "Rate limit exceeded" => throttled += 1,
+                Some(message) => panic!("unexpected error: {}", message),
+            }
+        }
+    }
+    throttled
+}
+
+#[tokio::test]
+async fn test_clients_behind_a_trusted_proxy_get_their_own_address_buckets() {
+    // Two messages per player; the address bucket holds 8, fewer than five players send
+    let server = TestServer::start_with(|config| {
+        config.rate_limit = RateLimitConfig { burst: 2, per_sec: 1, max_violations: 1000 };
+        config.trusted_proxies = TrustedProxies::parse("127.0.0.1").unwrap();
+    }).await;
+
+    let mut apart = Vec::new();
+    for i in 0..5 {
+        apart.push(server.connect_from(&format!("apart{}", i), &format!("203.0.113.{}", i + 1)).await);
+    }
+    assert_eq!(throttled_pings(&mut apart).await, 0);
+
+    // The same five behind one forwarded address share its bucket
+    let mut together = Vec::new();
+    for i in 0..5 {
+        together.push(server.connect_from(&format!("together{}", i), "198.51.100.7").await);
+    }
+    assert!(throttled_pings(&mut together).await > 0);
+}
+
+#[tokio::test]
+async fn test_shared_address_policy_compares_forwarded_addresses() {
+    let server = TestServer::start_with(|config| {
+        config.trusted_proxies = TrustedProxies::parse("127.0.0.1").unwrap();
+    }).await;
+    let mut alice = server.connect_from("alice", "203.0.113.1").await;
+    let mut bob = server.connect_from("bob", "203.0.113.2").await;
+    let mut carol = server.connect_from("carol", "203.0.113.1").await;
+
+    let settings = GameSettings { shared_address: SharedAddressPolicy::Prevent, ..Default::default() };
+    alice.send(ClientMessage::CreateLobby { settings }).await;
+    let code = alice.expect(|msg| match msg {
+        ServerMessage::LobbyCreated { code, .. } => Some(code),
+        _ => None,
+    }).await;
+
+    // All three reach the server through the same proxy, but only carol shares alice's address
+    bob.send(ClientMessage::JoinLobby { lobby_id: IdOrCode::Code(code.clone()) }).await;
+    bob.expect(|msg| matches!(msg, ServerMessage::LobbyJoined { .. }).then_some(())).await;
+    carol.send(ClientMessage::JoinLobby { lobby_id: IdOrCode::Code(code) }).await;
+    let refused = carol.expect(|msg| match msg {
+        ServerMessage::Error { message, .. } => Some(message),
+        _ => None,
+    }).await;
+    assert!(refused.ends_with("Another player in this lobby is connected from the same address"), "{}", refused);
+}
+
+#[tokio::test]
+async fn test_stress_client_plays_random_games_cleanly() {
+    // Every seat connects from 127.0.0.1, so they all share one address bucket
+    let server = TestServer::start_with(|config| {
+        config.rate_limit = RateLimitConfig { burst: 1000, per_sec: 1000, ..Default::default() };
+    }).await;
+    let output = tokio::process::Command::new(env!("CARGO_BIN_EXE_stress_client"))
+        .args(["--url", &format!("http://{}", server.addr)])
+        .args(["--tables", "3", "--players", "3", "--games", "2", "--max-cards", "2", "--timeout", "10"])
+        .output()
+        .await
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stress client failed:\n{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("6 game(s)"), "{}", stdout);
+}
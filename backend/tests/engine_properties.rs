@@ -0,0 +1,219 @@
+//! Random legal games played straight against the engine, checking the rules hold after every
+//! action: cards are never duplicated, the hook rule keeps bids off the cards dealt, every round
+//! has one trick per card and scores follow the formula. proptest shrinks a failure down to a
+//! short game; the choices it prints replay it.
+
+use german_bridge_backend::engine::card::{Card, Suit};
+use german_bridge_backend::engine::deck::DeckSeed;
+use german_bridge_backend::engine::rules::{DealDirection, RuleSet, TrumpMode, ZeroBidRule};
+use german_bridge_backend::engine::schedule::RoundSchedule;
+use german_bridge_backend::engine::{GamePhase, GameState, PlayerAction, PlayerId};
+use proptest::prelude::*;
+use proptest::sample::Index;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use std::collections::HashSet;
+
+/// Scoring as the rules describe it, written out independently of ScoreCalculator
+fn expected_score(bid: u8, won: u8) -> i32 {
+    let (bid, won) = (bid as i32, won as i32);
+    if bid == won {
+        10 + won * won
+    } else {
+        -(bid - won).pow(2)
+    }
+}
+
+fn rules() -> impl Strategy<Value = RuleSet> {
+    (
+        prop::option::of(1usize..=8),
+        prop_oneof![Just(DealDirection::Clockwise), Just(DealDirection::CounterClockwise)],
+        prop_oneof![Just(ZeroBidRule::Allowed), Just(ZeroBidRule::NotInOneCardRounds), Just(ZeroBidRule::Never)],
+        prop::option::of(20i32..=80),
+        prop::option::of(1usize..=6),
+        prop_oneof![Just(TrumpMode::Random), Just(TrumpMode::Rotating), Just(TrumpMode::NoTrump)],
+        any::<bool>(),
+    )
+        .prop_map(|(max_cards, deal_direction, zero_bids, target_score, round_limit, trump, seeded_deals)| RuleSet {
+            max_cards,
+            deal_direction,
+            zero_bids,
+            target_score,
+            round_limit,
+            trump,
+            seeded_deals,
+            ..Default::default()
+        })
+}
+
+/// A game to play: its table, rules and deals, and which valid action to take on each turn
+#[derive(Debug, Clone)]
+struct Script {
+    players: Vec<PlayerId>,
+    schedule: RoundSchedule,
+    rules: RuleSet,
+    seed: u64,
+    /// Picks among the valid actions, turn by turn; the first action once they run out
+    choices: Vec<Index>,
+}
+
+fn script() -> impl Strategy<Value = Script> {
+    (
+        2usize..=8,
+        prop_oneof![Just(RoundSchedule::Ascending), Just(RoundSchedule::UpAndDown)],
+        rules(),
+        any::<u64>(),
+        prop::collection::vec(any::<Index>(), 0..300),
+    )
+        .prop_map(|(player_count, schedule, rules, seed, choices)| Script {
+            players: (1..=player_count).map(|seat| format!("p{}", seat)).collect(),
+            schedule,
+            rules,
+            seed,
+            choices,
+        })
+}
+
+fn start(script: &Script) -> GameState {
+    let seed = DeckSeed::from_rng(&mut ChaCha20Rng::seed_from_u64(script.seed));
+    GameState::with_seed(script.players.clone(), script.schedule, script.rules.clone(), seed)
+}
+
+/// Every card of the round is in exactly one place: a hand, the current trick or a completed trick
+fn check_cards(state: &GameState) -> Result<(), TestCaseError> {
+    let mut seen = HashSet::new();
+    let held = state.hands.values().flat_map(|hand| hand.cards().iter());
+    let in_trick = state.current_trick.cards.iter().map(|(_, card)| card);
+    let played = state.completed_tricks.iter().flat_map(|trick| trick.cards.iter().map(|(_, card)| card));
+    for card in held.chain(in_trick).chain(played) {
+        prop_assert!(seen.insert(*card), "{:?} is in two places", card);
+    }
+    prop_assert_eq!(seen.len(), state.cards_per_player * state.players.len());
+    Ok(())
+}
+
+/// The trick went to the highest trump played, or else the highest card of the suit led
+fn check_trick_winner(cards: &[(PlayerId, Card)], winner: &PlayerId, trump: Option<Suit>) -> Result<(), TestCaseError> {
+    let lead = cards[0].1.suit;
+    let best = cards
+        .iter()
+        .filter(|(_, card)| Some(card.suit) == trump)
+        .max_by_key(|(_, card)| card.rank as u8)
+        .or_else(|| cards.iter().filter(|(_, card)| card.suit == lead).max_by_key(|(_, card)| card.rank as u8))
+        .unwrap();
+    prop_assert_eq!(&best.0, winner, "trick {:?} with trump {:?}", cards, trump);
+    Ok(())
+}
+
+/// The round just scored: one trick per card, bids off the hook, scores by the formula
+fn check_round(state: &GameState) -> Result<(), TestCaseError> {
+    let round = state.history.last().unwrap();
+    prop_assert_eq!(round.round_number, state.round_number);
+    prop_assert_eq!(round.player_results.len(), state.players.len());
+    prop_assert_eq!(state.completed_tricks.len(), state.cards_per_player);
+
+    let tricks: usize = round.player_results.iter().map(|r| r.tricks_won as usize).sum();
+    prop_assert_eq!(tricks, state.cards_per_player);
+    let bids: usize = round.player_results.iter().map(|r| r.bid as usize).sum();
+    prop_assert_ne!(bids, state.cards_per_player, "bids add up to the cards dealt");
+
+    for result in &round.player_results {
+        prop_assert!(result.bid as usize <= state.cards_per_player);
+        prop_assert_eq!(result.score, expected_score(result.bid, result.tricks_won) - result.penalty);
+        let won = state.completed_tricks.iter().filter(|trick| trick.winner == result.player_id).count();
+        prop_assert_eq!(won, result.tricks_won as usize);
+    }
+    for player in &state.players {
+        let total: i32 = state.history.iter()
+            .flat_map(|round| &round.player_results)
+            .filter(|r| r.player_id == *player)
+            .map(|r| r.score)
+            .sum();
+        prop_assert_eq!(state.total_scores[player], total);
+    }
+    Ok(())
+}
+
+/// Play the script to the end of the game, checking the rules after every step
+fn play(script: &Script) -> Result<GameState, TestCaseError> {
+    let mut state = start(script);
+    let mut choices = script.choices.iter();
+    loop {
+        match state.phase {
+            GamePhase::GameComplete => return Ok(state),
+            GamePhase::RoundComplete => {
+                let version = state.version;
+                state.advance_to_next_round();
+                prop_assert!(state.version > version);
+                prop_assert!(state.hands.values().all(|hand| hand.cards().len() == state.cards_per_player));
+                check_cards(&state)?;
+                continue;
+            }
+            GamePhase::Bidding | GamePhase::Playing => {}
+        }
+
+        let player = state.current_player.clone();
+        for other in state.players.iter().filter(|p| **p != player) {
+            prop_assert!(state.get_valid_actions(other.clone()).is_empty(), "{} may act out of turn", other);
+        }
+        let actions = state.get_valid_actions(player.clone());
+        prop_assert!(!actions.is_empty(), "{} has no valid action in {:?}", player, state.phase);
+        let action = choices.next().map_or(&actions[0], |choice| choice.get(&actions)).clone();
+
+        // Anyone else trying it is turned away without changing the game
+        let version = state.version;
+        let intruder = state.seat_after(&player);
+        prop_assert!(state.apply_action(intruder, action.clone()).is_err());
+        prop_assert_eq!(state.version, version);
+
+        if let PlayerAction::PlayCard(card) = &action {
+            let lead = state.current_trick.lead_suit;
+            let hand = &state.hands[&player];
+            if let Some(lead) = lead.filter(|lead| hand.cards().iter().any(|c| c.suit == *lead)) {
+                prop_assert_eq!(card.suit, lead, "{} did not follow suit", player);
+            }
+        }
+
+        let tricks_before = state.completed_tricks.len();
+        state.apply_action(player.clone(), action.clone())
+            .map_err(|e| TestCaseError::fail(format!("valid action {:?} by {} rejected: {}", action, player, e)))?;
+        prop_assert!(state.version > version);
+        state.check_invariants().map_err(TestCaseError::fail)?;
+        check_cards(&state)?;
+
+        if state.completed_tricks.len() > tricks_before {
+            let trick = state.completed_tricks.last().unwrap();
+            check_trick_winner(&trick.cards, &trick.winner, state.trump_suit)?;
+        }
+        if matches!(state.phase, GamePhase::RoundComplete | GamePhase::GameComplete) {
+            check_round(&state)?;
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn random_legal_games_keep_the_rules(script in script()) {
+        let state = play(&script)?;
+
+        // Over when the rounds run out or someone reaches the target, not before
+        prop_assert_eq!(state.history.len(), state.round_number);
+        let target_reached = script.rules.target_score
+            .is_some_and(|target| state.total_scores.values().any(|&score| score >= target));
+        prop_assert!(target_reached || state.round_number == state.total_rounds());
+        prop_assert!(state.round_number <= state.total_rounds());
+    }
+
+    #[test]
+    fn the_same_seed_and_choices_replay_the_same_game(script in script()) {
+        let first = play(&script)?;
+        let second = play(&script)?;
+        prop_assert_eq!(first.total_scores, second.total_scores);
+        prop_assert_eq!(
+            serde_json::to_value(first.history.as_ref()).unwrap(),
+            serde_json::to_value(second.history.as_ref()).unwrap()
+        );
+    }
+}
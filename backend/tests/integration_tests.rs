@@ -1,8 +1,101 @@
 use german_bridge_backend::protocol::{ClientMessage, ServerMessage};
 use german_bridge_backend::connection::{ConnectionManager, PlayerId};
-use tokio::sync::mpsc;
+use german_bridge_backend::outbound::{self, OutboundReceiver};
 use axum::extract::ws::Message;
-use serde_json;
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, EntityTrait, Schema};
+use uuid::Uuid;
+use german_bridge_backend::entities;
+
+fn new_player_id() -> PlayerId {
+    Uuid::new_v4().to_string()
+}
+
+/// Empty in-memory database for manager-level tests; persistence failures are only logged
+async fn test_db() -> DatabaseConnection {
+    Database::connect("sqlite::memory:").await.unwrap()
+}
+
+/// In-memory database with tables created from the entity definitions
+async fn test_db_with_schema() -> DatabaseConnection {
+    let db = test_db().await;
+    let schema = Schema::new(db.get_database_backend());
+    create_table(&db, &schema, entities::user::Entity).await;
+    create_table(&db, &schema, entities::lobby::Entity).await;
+    create_table(&db, &schema, entities::lobby_player::Entity).await;
+    create_table(&db, &schema, entities::game::Entity).await;
+    create_table(&db, &schema, entities::game_player::Entity).await;
+    create_table(&db, &schema, entities::game_round::Entity).await;
+    // The migrations key rounds by game and number; entities cannot declare that
+    db.execute_unprepared("CREATE UNIQUE INDEX idx_game_rounds_game_round ON game_rounds (game_id, round_number)").await.unwrap();
+    create_table(&db, &schema, entities::node::Entity).await;
+    create_table(&db, &schema, entities::bug_report::Entity).await;
+    create_table(&db, &schema, entities::weekly_digest::Entity).await;
+    create_table(&db, &schema, entities::lobby_preset::Entity).await;
+    create_table(&db, &schema, entities::tournament::Entity).await;
+    create_table(&db, &schema, entities::round_deal::Entity).await;
+    create_table(&db, &schema, entities::game_action::Entity).await;
+    create_table(&db, &schema, entities::password_reset_token::Entity).await;
+    create_table(&db, &schema, entities::friendship::Entity).await;
+    create_table(&db, &schema, entities::user_block::Entity).await;
+    create_table(&db, &schema, entities::audit_log::Entity).await;
+    create_table(&db, &schema, entities::user_achievement::Entity).await;
+    db
+}
+
+async fn create_table<E: EntityTrait>(db: &DatabaseConnection, schema: &Schema, entity: E) {
+    let stmt = db.get_database_backend().build(&schema.create_table_from_entity(entity));
+    db.execute(stmt).await.unwrap();
+}
+
+/// Insert a user row so foreign keys to users are satisfied
+async fn insert_user(db: &DatabaseConnection, username: &str) -> PlayerId {
+    use sea_orm::{ActiveModelTrait, Set};
+    let id = Uuid::new_v4();
+    entities::user::ActiveModel {
+        id: Set(id),
+        username: Set(username.to_string()),
+        password_hash: Set(String::new()),
+        created_at: Set(chrono::Utc::now()),
+        is_bot: Set(false),
+        time_zone: Set("UTC".to_string()),
+        locale: Set("en-US".to_string()),
+        weekly_digest: Set(true),
+        suit_encoding: Set("standard".to_string()),
+        role: Set("player".to_string()),
+        sessions_valid_after: Set(None),
+        display_name: Set(None),
+        avatar: Set(None),
+        preferred_settings: Set(None),
+    }
+    .insert(db)
+    .await
+    .unwrap();
+    id.to_string()
+}
+
+#[tokio::test]
+async fn test_migrations_run_on_sqlite() {
+    use german_bridge_backend::migrator::{Migrator, MigratorTrait};
+
+    let db = test_db().await;
+    Migrator::up(&db, None).await.unwrap();
+
+    // The migrated schema holds a whole game
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let player1 = insert_user(&db, "alice").await;
+    let player2 = insert_user(&db, "bob").await;
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
+    for _ in 0..4 {
+        game_manager.start_turn_timer(game_id, 0).await;
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    game_manager.db_writes().flush().await;
+    assert_eq!(entities::game_round::Entity::find().all(&db).await.unwrap().len(), 1);
+    assert_eq!(entities::game_action::Entity::find().all(&db).await.unwrap().len(), 4);
+
+    Migrator::down(&db, None).await.unwrap();
+}
 
 #[tokio::test]
 async fn test_player_connection_and_id_assignment() {
@@ -10,13 +103,13 @@ async fn test_player_connection_and_id_assignment() {
     let conn_manager = ConnectionManager::new();
     
     // Create a mock WebSocket sender
-    let (tx, _rx) = mpsc::unbounded_channel();
+    let (tx, _rx) = outbound::channel();
     
     // Add a player
     let player_id = conn_manager.add_player(tx).await;
     
     // Verify player ID is assigned
-    assert_ne!(player_id, PlayerId::nil());
+    assert!(!player_id.is_empty());
     
     // Verify player is in active players list
     let active_players = conn_manager.get_active_players().await;
@@ -43,8 +136,8 @@ async fn test_message_serialization_deserialization() {
     }
     
     // Test ServerMessage serialization
-    let player_id = PlayerId::new_v4();
-    let server_msg = ServerMessage::Connected { player_id };
+    let player_id = new_player_id();
+    let server_msg = ServerMessage::Connected { player_id: player_id.clone() };
     let json = serde_json::to_string(&server_msg).unwrap();
     let deserialized: ServerMessage = serde_json::from_str(&json).unwrap();
     
@@ -59,13 +152,13 @@ async fn test_message_serialization_deserialization() {
 #[tokio::test]
 async fn test_send_to_player() {
     let conn_manager = ConnectionManager::new();
-    let (tx, mut rx) = mpsc::unbounded_channel();
+    let (tx, mut rx) = outbound::channel();
     
     let player_id = conn_manager.add_player(tx).await;
     
     // Send a message to the player
     let msg = ServerMessage::Pong;
-    conn_manager.send_to_player(player_id, msg).await;
+    conn_manager.send_to_player(player_id.clone(), msg).await;
     
     // Receive the message
     let received = rx.recv().await.unwrap();
@@ -87,15 +180,15 @@ async fn test_broadcast_to_players() {
     let conn_manager = ConnectionManager::new();
     
     // Create two players
-    let (tx1, mut rx1) = mpsc::unbounded_channel();
-    let (tx2, mut rx2) = mpsc::unbounded_channel();
+    let (tx1, mut rx1) = outbound::channel();
+    let (tx2, mut rx2) = outbound::channel();
     
     let player1 = conn_manager.add_player(tx1).await;
     let player2 = conn_manager.add_player(tx2).await;
     
     // Broadcast a message
     let msg = ServerMessage::Pong;
-    conn_manager.broadcast_to_players(&[player1, player2], msg).await;
+    conn_manager.broadcast_to_players(&[player1.clone(), player2.clone()], msg).await;
     
     // Both players should receive the message
     let received1 = rx1.recv().await.unwrap();
@@ -118,7 +211,7 @@ async fn test_broadcast_to_players() {
 #[tokio::test]
 async fn test_player_disconnection() {
     let conn_manager = ConnectionManager::new();
-    let (tx, _rx) = mpsc::unbounded_channel();
+    let (tx, _rx) = outbound::channel();
     
     let player_id = conn_manager.add_player(tx).await;
     
@@ -127,7 +220,7 @@ async fn test_player_disconnection() {
     assert_eq!(active_players.len(), 1);
     
     // Mark player as inactive
-    let other_players = conn_manager.mark_inactive(player_id).await;
+    let other_players = conn_manager.mark_inactive(player_id.clone()).await;
     assert_eq!(other_players.len(), 0); // No other players
     
     // Verify player is no longer active
@@ -144,16 +237,16 @@ async fn test_player_disconnection() {
 #[tokio::test]
 async fn test_player_reconnection() {
     let conn_manager = ConnectionManager::new();
-    let (tx1, _rx1) = mpsc::unbounded_channel();
+    let (tx1, _rx1) = outbound::channel();
     
     let player_id = conn_manager.add_player(tx1).await;
     
     // Mark player as inactive
-    conn_manager.mark_inactive(player_id).await;
+    conn_manager.mark_inactive(player_id.clone()).await;
     
     // Reconnect with new sender
-    let (tx2, mut rx2) = mpsc::unbounded_channel();
-    let result = conn_manager.reconnect_player(player_id, tx2).await;
+    let (tx2, mut rx2) = outbound::channel();
+    let result = conn_manager.reconnect_player(player_id.clone(), tx2).await;
     
     assert!(result.is_some());
     
@@ -163,7 +256,7 @@ async fn test_player_reconnection() {
     assert_eq!(active_players[0], player_id);
     
     // Verify we can send messages to reconnected player
-    conn_manager.send_to_player(player_id, ServerMessage::Pong).await;
+    conn_manager.send_to_player(player_id.clone(), ServerMessage::Pong).await;
     let received = rx2.recv().await.unwrap();
     
     match received {
@@ -184,19 +277,19 @@ async fn test_reconnection_timeout() {
     
     // Create connection manager with very short timeout
     let conn_manager = ConnectionManager::with_reconnect_timeout(Duration::from_millis(100));
-    let (tx1, _rx1) = mpsc::unbounded_channel();
+    let (tx1, _rx1) = outbound::channel();
     
     let player_id = conn_manager.add_player(tx1).await;
     
     // Mark player as inactive
-    conn_manager.mark_inactive(player_id).await;
+    conn_manager.mark_inactive(player_id.clone()).await;
     
     // Wait for timeout to expire
     tokio::time::sleep(Duration::from_millis(150)).await;
     
     // Try to reconnect - should fail
-    let (tx2, _rx2) = mpsc::unbounded_channel();
-    let result = conn_manager.reconnect_player(player_id, tx2).await;
+    let (tx2, _rx2) = outbound::channel();
+    let result = conn_manager.reconnect_player(player_id.clone(), tx2).await;
     
     assert!(result.is_none());
 }
@@ -207,12 +300,12 @@ async fn test_cleanup_expired_sessions() {
     
     // Create connection manager with very short timeout
     let conn_manager = ConnectionManager::with_reconnect_timeout(Duration::from_millis(100));
-    let (tx, _rx) = mpsc::unbounded_channel();
+    let (tx, _rx) = outbound::channel();
     
     let player_id = conn_manager.add_player(tx).await;
     
     // Mark player as inactive
-    conn_manager.mark_inactive(player_id).await;
+    conn_manager.mark_inactive(player_id.clone()).await;
     
     // Wait for timeout to expire
     tokio::time::sleep(Duration::from_millis(150)).await;
@@ -233,16 +326,16 @@ async fn test_multiple_players_disconnection_notification() {
     let conn_manager = ConnectionManager::new();
     
     // Create three players
-    let (tx1, _rx1) = mpsc::unbounded_channel();
-    let (tx2, _rx2) = mpsc::unbounded_channel();
-    let (tx3, _rx3) = mpsc::unbounded_channel();
+    let (tx1, _rx1) = outbound::channel();
+    let (tx2, _rx2) = outbound::channel();
+    let (tx3, _rx3) = outbound::channel();
     
     let player1 = conn_manager.add_player(tx1).await;
     let player2 = conn_manager.add_player(tx2).await;
     let player3 = conn_manager.add_player(tx3).await;
     
     // Mark player2 as inactive
-    let other_players = conn_manager.mark_inactive(player2).await;
+    let other_players = conn_manager.mark_inactive(player2.clone()).await;
     
     // Should return player1 and player3 as other active players
     assert_eq!(other_players.len(), 2);
@@ -256,24 +349,25 @@ async fn test_multiple_players_disconnection_notification() {
 
 use german_bridge_backend::lobby::LobbyManager;
 use german_bridge_backend::game::GameManager;
-use german_bridge_backend::protocol::{GameSettings, PlayerCount};
+use german_bridge_backend::protocol::{BotPolicy, GameSettings};
 use german_bridge_backend::error::LobbyError;
 use std::sync::Arc;
 
 #[tokio::test]
 async fn test_lobby_creation() {
     let conn_manager = Arc::new(ConnectionManager::new());
-    let game_manager = Arc::new(GameManager::new(conn_manager));
-    let lobby_manager = LobbyManager::new(game_manager);
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let lobby_manager = LobbyManager::new(game_manager, conn_manager, test_db().await);
     
-    let host_id = PlayerId::new_v4();
+    let host_id = new_player_id();
     let settings = GameSettings {
-        player_count: PlayerCount::Four,
+        player_count: 4,
         turn_timeout_secs: 30,
         allow_reconnect: true,
+        ..Default::default()
     };
     
-    let lobby_id = lobby_manager.create_lobby(host_id, settings).await;
+    let lobby_id = lobby_manager.create_lobby(host_id.clone(), settings).await;
     
     // Verify lobby was created
     let lobby = lobby_manager.get_lobby(lobby_id).await;
@@ -289,17 +383,17 @@ async fn test_lobby_creation() {
 #[tokio::test]
 async fn test_lobby_joining() {
     let conn_manager = Arc::new(ConnectionManager::new());
-    let game_manager = Arc::new(GameManager::new(conn_manager));
-    let lobby_manager = LobbyManager::new(game_manager);
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let lobby_manager = LobbyManager::new(game_manager, conn_manager, test_db().await);
     
-    let host_id = PlayerId::new_v4();
-    let player2_id = PlayerId::new_v4();
+    let host_id = new_player_id();
+    let player2_id = new_player_id();
     let settings = GameSettings::default();
     
-    let lobby_id = lobby_manager.create_lobby(host_id, settings).await;
+    let lobby_id = lobby_manager.create_lobby(host_id.clone(), settings).await;
     
     // Join the lobby
-    let result = lobby_manager.join_lobby(lobby_id, player2_id).await;
+    let result = lobby_manager.join_lobby(lobby_id, player2_id.clone()).await;
     assert!(result.is_ok());
     
     // Verify player was added
@@ -312,27 +406,28 @@ async fn test_lobby_joining() {
 #[tokio::test]
 async fn test_lobby_full_rejection() {
     let conn_manager = Arc::new(ConnectionManager::new());
-    let game_manager = Arc::new(GameManager::new(conn_manager));
-    let lobby_manager = LobbyManager::new(game_manager);
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let lobby_manager = LobbyManager::new(game_manager, conn_manager, test_db().await);
     
-    let host_id = PlayerId::new_v4();
+    let host_id = new_player_id();
     let settings = GameSettings {
-        player_count: PlayerCount::Three,
+        player_count: 3,
         turn_timeout_secs: 30,
         allow_reconnect: true,
+        ..Default::default()
     };
     
-    let lobby_id = lobby_manager.create_lobby(host_id, settings).await;
+    let lobby_id = lobby_manager.create_lobby(host_id.clone(), settings).await;
     
     // Add two more players to fill the lobby (3 total)
-    let player2_id = PlayerId::new_v4();
-    let player3_id = PlayerId::new_v4();
-    lobby_manager.join_lobby(lobby_id, player2_id).await.unwrap();
-    lobby_manager.join_lobby(lobby_id, player3_id).await.unwrap();
+    let player2_id = new_player_id();
+    let player3_id = new_player_id();
+    lobby_manager.join_lobby(lobby_id, player2_id.clone()).await.unwrap();
+    lobby_manager.join_lobby(lobby_id, player3_id.clone()).await.unwrap();
     
     // Try to add a fourth player - should fail
-    let player4_id = PlayerId::new_v4();
-    let result = lobby_manager.join_lobby(lobby_id, player4_id).await;
+    let player4_id = new_player_id();
+    let result = lobby_manager.join_lobby(lobby_id, player4_id.clone()).await;
     
     assert!(result.is_err());
     match result {
@@ -344,18 +439,18 @@ async fn test_lobby_full_rejection() {
 #[tokio::test]
 async fn test_lobby_host_transfer_on_leave() {
     let conn_manager = Arc::new(ConnectionManager::new());
-    let game_manager = Arc::new(GameManager::new(conn_manager));
-    let lobby_manager = LobbyManager::new(game_manager);
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let lobby_manager = LobbyManager::new(game_manager, conn_manager, test_db().await);
     
-    let host_id = PlayerId::new_v4();
-    let player2_id = PlayerId::new_v4();
+    let host_id = new_player_id();
+    let player2_id = new_player_id();
     let settings = GameSettings::default();
     
-    let lobby_id = lobby_manager.create_lobby(host_id, settings).await;
-    lobby_manager.join_lobby(lobby_id, player2_id).await.unwrap();
+    let lobby_id = lobby_manager.create_lobby(host_id.clone(), settings).await;
+    lobby_manager.join_lobby(lobby_id, player2_id.clone()).await.unwrap();
     
     // Host leaves
-    lobby_manager.leave_lobby(lobby_id, host_id).await.unwrap();
+    lobby_manager.leave_lobby(lobby_id, host_id.clone()).await.unwrap();
     
     // Verify host was transferred to player2
     let lobby = lobby_manager.get_lobby(lobby_id).await.unwrap();
@@ -367,16 +462,16 @@ async fn test_lobby_host_transfer_on_leave() {
 #[tokio::test]
 async fn test_lobby_removed_when_empty() {
     let conn_manager = Arc::new(ConnectionManager::new());
-    let game_manager = Arc::new(GameManager::new(conn_manager));
-    let lobby_manager = LobbyManager::new(game_manager);
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let lobby_manager = LobbyManager::new(game_manager, conn_manager, test_db().await);
     
-    let host_id = PlayerId::new_v4();
+    let host_id = new_player_id();
     let settings = GameSettings::default();
     
-    let lobby_id = lobby_manager.create_lobby(host_id, settings).await;
+    let lobby_id = lobby_manager.create_lobby(host_id.clone(), settings).await;
     
     // Host leaves (only player)
-    lobby_manager.leave_lobby(lobby_id, host_id).await.unwrap();
+    lobby_manager.leave_lobby(lobby_id, host_id.clone()).await.unwrap();
     
     // Verify lobby was removed
     let lobby = lobby_manager.get_lobby(lobby_id).await;
@@ -386,16 +481,16 @@ async fn test_lobby_removed_when_empty() {
 #[tokio::test]
 async fn test_list_lobbies() {
     let conn_manager = Arc::new(ConnectionManager::new());
-    let game_manager = Arc::new(GameManager::new(conn_manager));
-    let lobby_manager = LobbyManager::new(game_manager);
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let lobby_manager = LobbyManager::new(game_manager, conn_manager, test_db().await);
     
-    let host1_id = PlayerId::new_v4();
-    let host2_id = PlayerId::new_v4();
+    let host1_id = new_player_id();
+    let host2_id = new_player_id();
     let settings = GameSettings::default();
     
     // Create two lobbies
-    let lobby1_id = lobby_manager.create_lobby(host1_id, settings.clone()).await;
-    let lobby2_id = lobby_manager.create_lobby(host2_id, settings).await;
+    let lobby1_id = lobby_manager.create_lobby(host1_id.clone(), settings.clone()).await;
+    let lobby2_id = lobby_manager.create_lobby(host2_id.clone(), settings).await;
     
     // List lobbies
     let lobbies = lobby_manager.list_lobbies().await;
@@ -409,44 +504,109 @@ async fn test_list_lobbies() {
 #[tokio::test]
 async fn test_list_lobbies_excludes_full() {
     let conn_manager = Arc::new(ConnectionManager::new());
-    let game_manager = Arc::new(GameManager::new(conn_manager));
-    let lobby_manager = LobbyManager::new(game_manager);
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let lobby_manager = LobbyManager::new(game_manager, conn_manager, test_db().await);
     
-    let host_id = PlayerId::new_v4();
+    let host_id = new_player_id();
     let settings = GameSettings {
-        player_count: PlayerCount::Three,
+        player_count: 3,
         turn_timeout_secs: 30,
         allow_reconnect: true,
+        ..Default::default()
     };
     
-    let lobby_id = lobby_manager.create_lobby(host_id, settings).await;
+    let lobby_id = lobby_manager.create_lobby(host_id.clone(), settings).await;
     
     // Fill the lobby
-    let player2_id = PlayerId::new_v4();
-    let player3_id = PlayerId::new_v4();
-    lobby_manager.join_lobby(lobby_id, player2_id).await.unwrap();
-    lobby_manager.join_lobby(lobby_id, player3_id).await.unwrap();
+    let player2_id = new_player_id();
+    let player3_id = new_player_id();
+    lobby_manager.join_lobby(lobby_id, player2_id.clone()).await.unwrap();
+    lobby_manager.join_lobby(lobby_id, player3_id.clone()).await.unwrap();
     
     // List lobbies - should be empty since the only lobby is full
     let lobbies = lobby_manager.list_lobbies().await;
     assert_eq!(lobbies.len(), 0);
 }
 
+#[tokio::test]
+async fn test_lobby_browser_filters_and_pages() {
+    use german_bridge_backend::friends;
+    use german_bridge_backend::protocol::{ClientEnvelope, LobbyQuery};
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = LobbyManager::new(game_manager, conn_manager, db.clone());
+    let viewer = insert_user(&db, "viewer").await;
+    let friend = insert_user(&db, "friend").await;
+    let id = |p: &PlayerId| Uuid::parse_str(p).unwrap();
+    friends::add_friend(&db, id(&viewer), id(&friend)).await.unwrap();
+    friends::add_friend(&db, id(&friend), id(&viewer)).await.unwrap();
+
+    let three = GameSettings { player_count: 3, ..Default::default() };
+    let four = GameSettings { player_count: 4, ..Default::default() };
+    let mut open = Vec::new();
+    for _ in 0..5 {
+        open.push(lobby_manager.create_lobby(new_player_id(), four.clone()).await);
+    }
+    let friends_lobby = lobby_manager.create_lobby(friend.clone(), three.clone()).await;
+    let full = lobby_manager.create_lobby(new_player_id(), three).await;
+    lobby_manager.join_lobby(full, new_player_id()).await.unwrap();
+    lobby_manager.join_lobby(full, new_player_id()).await.unwrap();
+
+    let browse = |query: LobbyQuery| {
+        let lobby_manager = &lobby_manager;
+        let viewer = viewer.clone();
+        async move { lobby_manager.browse_lobbies(&viewer, &query).await }
+    };
+    let ids = |page: &german_bridge_backend::lobby::LobbyPage| page.lobbies.iter().map(|l| l.id).collect::<Vec<_>>();
+
+    // Pages run oldest first and resume after the cursor
+    let first = browse(LobbyQuery { limit: Some(4), ..Default::default() }).await;
+    assert_eq!(ids(&first), open[..4].to_vec());
+    let rest = browse(LobbyQuery { limit: Some(4), after: first.next_cursor, ..Default::default() }).await;
+    assert_eq!(ids(&rest), vec![open[4], friends_lobby]);
+    assert_eq!(rest.next_cursor, None);
+
+    let threes = browse(LobbyQuery { player_count: Some(3), ..Default::default() }).await;
+    assert_eq!(ids(&threes), vec![friends_lobby]);
+    let threes = browse(LobbyQuery { player_count: Some(3), include_full: true, ..Default::default() }).await;
+    assert_eq!(ids(&threes), vec![friends_lobby, full]);
+
+    let hosted = browse(LobbyQuery { friends_only: true, ..Default::default() }).await;
+    assert_eq!(ids(&hosted), vec![friends_lobby]);
+
+    // Host changes move a lobby in and out of the friends filter
+    lobby_manager.join_lobby(open[0], friend.clone()).await.unwrap();
+    let host = lobby_manager.get_lobby(open[0]).await.unwrap().host;
+    lobby_manager.leave_lobby(open[0], host).await.unwrap();
+    let hosted = browse(LobbyQuery { friends_only: true, ..Default::default() }).await;
+    assert_eq!(ids(&hosted), vec![open[0], friends_lobby]);
+
+    // Older clients send no payload and get the first page
+    let envelope: ClientEnvelope = serde_json::from_str(r#"{"type": "ListLobbies"}"#).unwrap();
+    assert!(matches!(envelope.message, ClientMessage::ListLobbies(None)));
+    let envelope: ClientEnvelope =
+        serde_json::from_str(r#"{"type": "ListLobbies", "payload": {"friends_only": true}}"#).unwrap();
+    assert!(matches!(envelope.message, ClientMessage::ListLobbies(Some(LobbyQuery { friends_only: true, .. }))));
+}
+
 #[tokio::test]
 async fn test_game_start_with_correct_player_count() {
     let conn_manager = Arc::new(ConnectionManager::new());
-    let game_manager = Arc::new(GameManager::new(conn_manager));
-    let lobby_manager = LobbyManager::new(game_manager);
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let lobby_manager = LobbyManager::new(game_manager, conn_manager, test_db().await);
     
-    let host_id = PlayerId::new_v4();
-    let player2_id = PlayerId::new_v4();
+    let host_id = new_player_id();
+    let player2_id = new_player_id();
     let settings = GameSettings::default();
     
-    let lobby_id = lobby_manager.create_lobby(host_id, settings).await;
-    lobby_manager.join_lobby(lobby_id, player2_id).await.unwrap();
+    let lobby_id = lobby_manager.create_lobby(host_id.clone(), settings).await;
+    lobby_manager.join_lobby(lobby_id, player2_id.clone()).await.unwrap();
+    lobby_manager.set_ready(lobby_id, player2_id.clone(), true).await.unwrap();
     
     // Start game
-    let result = lobby_manager.start_game(lobby_id, host_id).await;
+    let result = lobby_manager.start_game(lobby_id, host_id.clone()).await;
     assert!(result.is_ok());
     
     // Verify lobby was removed after game start
@@ -457,18 +617,18 @@ async fn test_game_start_with_correct_player_count() {
 #[tokio::test]
 async fn test_game_start_requires_host() {
     let conn_manager = Arc::new(ConnectionManager::new());
-    let game_manager = Arc::new(GameManager::new(conn_manager));
-    let lobby_manager = LobbyManager::new(game_manager);
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let lobby_manager = LobbyManager::new(game_manager, conn_manager, test_db().await);
     
-    let host_id = PlayerId::new_v4();
-    let player2_id = PlayerId::new_v4();
+    let host_id = new_player_id();
+    let player2_id = new_player_id();
     let settings = GameSettings::default();
     
-    let lobby_id = lobby_manager.create_lobby(host_id, settings).await;
-    lobby_manager.join_lobby(lobby_id, player2_id).await.unwrap();
+    let lobby_id = lobby_manager.create_lobby(host_id.clone(), settings).await;
+    lobby_manager.join_lobby(lobby_id, player2_id.clone()).await.unwrap();
     
     // Try to start game as non-host
-    let result = lobby_manager.start_game(lobby_id, player2_id).await;
+    let result = lobby_manager.start_game(lobby_id, player2_id.clone()).await;
     
     assert!(result.is_err());
     match result {
@@ -480,16 +640,16 @@ async fn test_game_start_requires_host() {
 #[tokio::test]
 async fn test_game_start_requires_minimum_players() {
     let conn_manager = Arc::new(ConnectionManager::new());
-    let game_manager = Arc::new(GameManager::new(conn_manager));
-    let lobby_manager = LobbyManager::new(game_manager);
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let lobby_manager = LobbyManager::new(game_manager, conn_manager, test_db().await);
     
-    let host_id = PlayerId::new_v4();
+    let host_id = new_player_id();
     let settings = GameSettings::default();
     
-    let lobby_id = lobby_manager.create_lobby(host_id, settings).await;
+    let lobby_id = lobby_manager.create_lobby(host_id.clone(), settings).await;
     
     // Try to start game with only 1 player
-    let result = lobby_manager.start_game(lobby_id, host_id).await;
+    let result = lobby_manager.start_game(lobby_id, host_id.clone()).await;
     
     assert!(result.is_err());
     match result {
@@ -498,21 +658,199 @@ async fn test_game_start_requires_minimum_players() {
     }
 }
 
+#[tokio::test]
+async fn test_game_start_requires_players_ready() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let lobby_manager = LobbyManager::new(game_manager, Arc::clone(&conn_manager), test_db().await);
+    
+    let (tx1, _rx1) = outbound::channel();
+    let (tx2, _rx2) = outbound::channel();
+    let host_id = conn_manager.add_player(tx1).await;
+    let player2_id = conn_manager.add_player(tx2).await;
+    
+    let lobby_id = lobby_manager.create_lobby(host_id.clone(), GameSettings::default()).await;
+    lobby_manager.join_lobby(lobby_id, player2_id.clone()).await.unwrap();
+    
+    // Player 2 has not readied up yet
+    let result = lobby_manager.start_game(lobby_id, host_id.clone()).await;
+    match result {
+        Err(LobbyError::PlayersNotReady) => {},
+        _ => panic!("Expected PlayersNotReady error"),
+    }
+    
+    // Readiness is exposed in LobbyInfo
+    lobby_manager.set_ready(lobby_id, player2_id.clone(), true).await.unwrap();
+    let lobby = lobby_manager.get_lobby(lobby_id).await.unwrap();
+    let info = lobby_manager.lobby_info(&lobby).await;
+    let player2_info = info.players.iter().find(|p| p.id == player2_id).unwrap();
+    assert!(player2_info.ready);
+    
+    // Host does not need to ready up
+    let result = lobby_manager.start_game(lobby_id, host_id.clone()).await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_bots_only_lobby_rejects_humans() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let lobby_manager = LobbyManager::new(game_manager, Arc::clone(&conn_manager), test_db().await);
+    
+    let (tx1, _rx1) = outbound::channel();
+    let (tx2, _rx2) = outbound::channel();
+    let (tx3, _rx3) = outbound::channel();
+    let bot_host = conn_manager.add_player(tx1).await;
+    let bot_player = conn_manager.add_player(tx2).await;
+    let human = conn_manager.add_player(tx3).await;
+    conn_manager.set_bot(&bot_host, true).await;
+    conn_manager.set_bot(&bot_player, true).await;
+    
+    let settings = GameSettings {
+        bots_only: true,
+        ..Default::default()
+    };
+    let lobby_id = lobby_manager.create_lobby(bot_host.clone(), settings).await;
+    
+    let result = lobby_manager.join_lobby(lobby_id, human.clone()).await;
+    match result {
+        Err(LobbyError::BotsOnly) => {},
+        _ => panic!("Expected BotsOnly error"),
+    }
+    
+    assert!(lobby_manager.join_lobby(lobby_id, bot_player.clone()).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_bot_policy_enforced() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let lobby_manager = LobbyManager::new(game_manager, Arc::clone(&conn_manager), test_db().await);
+    
+    let mut bots = Vec::new();
+    let mut receivers = Vec::new();
+    for _ in 0..3 {
+        let (tx, rx) = outbound::channel();
+        let bot = conn_manager.add_player(tx).await;
+        conn_manager.set_bot(&bot, true).await;
+        bots.push(bot);
+        receivers.push(rx);
+    }
+    
+    // Bot cap: host bot plus one more
+    let settings = GameSettings {
+        bot_policy: BotPolicy {
+            max_bots: Some(2),
+            require_human: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let lobby_id = lobby_manager.create_lobby(bots[0].clone(), settings).await;
+    lobby_manager.join_lobby(lobby_id, bots[1].clone()).await.unwrap();
+    
+    match lobby_manager.join_lobby(lobby_id, bots[2].clone()).await {
+        Err(LobbyError::BotLimitReached) => {},
+        _ => panic!("Expected BotLimitReached error"),
+    }
+    
+    let lobby = lobby_manager.get_lobby(lobby_id).await.unwrap();
+    assert_eq!(lobby_manager.lobby_info(&lobby).await.bot_count, 2);
+    
+    // No human seated yet
+    lobby_manager.set_ready(lobby_id, bots[1].clone(), true).await.unwrap();
+    match lobby_manager.start_game(lobby_id, bots[0].clone()).await {
+        Err(LobbyError::HumanRequired) => {},
+        _ => panic!("Expected HumanRequired error"),
+    }
+    
+    // Bots forbidden entirely
+    let settings = GameSettings {
+        bot_policy: BotPolicy {
+            forbid_bots: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let lobby_id = lobby_manager.create_lobby(new_player_id(), settings).await;
+    match lobby_manager.join_lobby(lobby_id, bots[2].clone()).await {
+        Err(LobbyError::BotsNotAllowed) => {},
+        _ => panic!("Expected BotsNotAllowed error"),
+    }
+}
+
+#[tokio::test]
+async fn test_host_can_kick_player() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let lobby_manager = LobbyManager::new(game_manager, conn_manager, test_db().await);
+    
+    let host_id = new_player_id();
+    let player2_id = new_player_id();
+    let lobby_id = lobby_manager.create_lobby(host_id.clone(), GameSettings::default()).await;
+    lobby_manager.join_lobby(lobby_id, player2_id.clone()).await.unwrap();
+    
+    // Non-host cannot kick
+    let result = lobby_manager.kick_player(lobby_id, player2_id.clone(), host_id.clone()).await;
+    assert!(matches!(result, Err(LobbyError::NotHost)));
+    
+    // Host cannot kick themselves
+    let result = lobby_manager.kick_player(lobby_id, host_id.clone(), host_id.clone()).await;
+    assert!(matches!(result, Err(LobbyError::CannotTargetSelf)));
+    
+    // Host kicks player 2
+    lobby_manager.kick_player(lobby_id, host_id.clone(), player2_id.clone()).await.unwrap();
+    let lobby = lobby_manager.get_lobby(lobby_id).await.unwrap();
+    assert_eq!(lobby.players, vec![host_id.clone()]);
+    
+    // Kicking someone who is no longer there fails
+    let result = lobby_manager.kick_player(lobby_id, host_id, player2_id).await;
+    assert!(matches!(result, Err(LobbyError::PlayerNotInLobby)));
+}
+
+#[tokio::test]
+async fn test_host_can_transfer_host() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let lobby_manager = LobbyManager::new(game_manager, conn_manager, test_db().await);
+    
+    let host_id = new_player_id();
+    let player2_id = new_player_id();
+    let outsider_id = new_player_id();
+    let lobby_id = lobby_manager.create_lobby(host_id.clone(), GameSettings::default()).await;
+    lobby_manager.join_lobby(lobby_id, player2_id.clone()).await.unwrap();
+    
+    // Cannot transfer to someone outside the lobby
+    let result = lobby_manager.transfer_host(lobby_id, host_id.clone(), outsider_id).await;
+    assert!(matches!(result, Err(LobbyError::PlayerNotInLobby)));
+    
+    // Transfer to player 2
+    lobby_manager.transfer_host(lobby_id, host_id.clone(), player2_id.clone()).await.unwrap();
+    let lobby = lobby_manager.get_lobby(lobby_id).await.unwrap();
+    assert_eq!(lobby.host, player2_id);
+    assert_eq!(lobby.players.len(), 2);
+    
+    // Former host no longer has host privileges
+    let result = lobby_manager.transfer_host(lobby_id, host_id.clone(), player2_id).await;
+    assert!(matches!(result, Err(LobbyError::NotHost)));
+}
+
 // ============================================================================
 // Complete Game Flow Tests
 // ============================================================================
 
-use german_bridge_backend::game_state::{GameState, GamePhase};
-use german_bridge_backend::game_logic::card::{Card, Suit, Rank};
-use german_bridge_backend::game_logic::bidding::Bid;
+use german_bridge_backend::engine::{GameState, GamePhase};
+use german_bridge_backend::engine::card::{Card, Suit, Rank};
+use german_bridge_backend::engine::auto_play::LowestLegal;
+use german_bridge_backend::engine::bidding::Bid;
 use german_bridge_backend::protocol::PlayerAction;
 use german_bridge_backend::error::GameError;
 
 #[tokio::test]
 async fn test_game_initialization_and_card_dealing() {
-    let player1 = PlayerId::new_v4();
-    let player2 = PlayerId::new_v4();
-    let players = vec![player1, player2];
+    let player1 = new_player_id();
+    let player2 = new_player_id();
+    let players = vec![player1.clone(), player2.clone()];
     
     let game_state = GameState::new(players.clone());
     
@@ -535,22 +873,22 @@ async fn test_game_initialization_and_card_dealing() {
 
 #[tokio::test]
 async fn test_bidding_phase_completion() {
-    let player1 = PlayerId::new_v4();
-    let player2 = PlayerId::new_v4();
-    let players = vec![player1, player2];
+    let player1 = new_player_id();
+    let player2 = new_player_id();
+    let players = vec![player1.clone(), player2.clone()];
     
     let mut game_state = GameState::new(players.clone());
     
     // Player 1 bids
     let bid1 = PlayerAction::Bid(Bid { tricks: 1 });
-    game_state.apply_action(player1, bid1).unwrap();
+    game_state.apply_action(player1.clone(), bid1).unwrap();
     
     // Should still be in bidding phase
     assert_eq!(game_state.phase, GamePhase::Bidding);
     
     // Player 2 bids (last bidder, cannot bid 0 since 1+0=1 which equals cards)
     let bid2 = PlayerAction::Bid(Bid { tricks: 1 });
-    game_state.apply_action(player2, bid2).unwrap();
+    game_state.apply_action(player2.clone(), bid2).unwrap();
     
     // Should transition to playing phase
     assert_eq!(game_state.phase, GamePhase::Playing);
@@ -559,19 +897,19 @@ async fn test_bidding_phase_completion() {
 
 #[tokio::test]
 async fn test_last_bidder_restriction() {
-    let player1 = PlayerId::new_v4();
-    let player2 = PlayerId::new_v4();
-    let players = vec![player1, player2];
+    let player1 = new_player_id();
+    let player2 = new_player_id();
+    let players = vec![player1.clone(), player2.clone()];
     
     let mut game_state = GameState::new(players.clone());
     
     // Player 1 bids 1
     let bid1 = PlayerAction::Bid(Bid { tricks: 1 });
-    game_state.apply_action(player1, bid1).unwrap();
+    game_state.apply_action(player1.clone(), bid1).unwrap();
     
     // Player 2 tries to bid 0 (sum would be 1, which equals cards_per_player)
     let bid2 = PlayerAction::Bid(Bid { tricks: 0 });
-    let result = game_state.apply_action(player2, bid2);
+    let result = game_state.apply_action(player2.clone(), bid2);
     
     assert!(result.is_err());
     match result {
@@ -580,17 +918,150 @@ async fn test_last_bidder_restriction() {
     }
 }
 
+#[tokio::test]
+async fn test_up_and_down_schedule_runs_back_down() {
+    use german_bridge_backend::engine::schedule::RoundSchedule;
+
+    // 13 players would be unusual, but keeps the schedule short: 4 cards max
+    let players: Vec<PlayerId> = (0..13).map(|_| new_player_id()).collect();
+    let mut game_state = GameState::with_schedule(players, RoundSchedule::UpAndDown);
+
+    let mut dealt = vec![game_state.cards_per_player];
+    while game_state.should_continue_game() {
+        game_state.round_number += 1;
+        game_state.start_round();
+        dealt.push(game_state.cards_per_player);
+    }
+
+    assert_eq!(dealt, vec![1, 2, 3, 4, 4, 3, 2, 1]);
+    assert_eq!(game_state.round_number, 8);
+}
+
+#[tokio::test]
+async fn test_rule_set_shapes_the_game() {
+    use german_bridge_backend::engine::rules::{DealDirection, RuleSet, TrumpMode, ZeroBidRule};
+    use german_bridge_backend::engine::schedule::RoundSchedule;
+
+    let players: Vec<PlayerId> = (0..3).map(|_| new_player_id()).collect();
+    let rules = RuleSet {
+        max_cards: Some(2),
+        deal_direction: DealDirection::CounterClockwise,
+        zero_bids: ZeroBidRule::Never,
+        target_score: Some(1),
+        trump: TrumpMode::NoTrump,
+        ..Default::default()
+    };
+    let mut game_state = GameState::with_rules(players.clone(), RoundSchedule::Ascending, rules);
+    assert_eq!(game_state.trump_suit, None);
+    assert_eq!(game_state.max_cards_per_player(), 2);
+
+    // Zero is off the table and turns run to the previous seat
+    assert!(game_state.apply_action(players[0].clone(), PlayerAction::Bid(Bid { tricks: 0 })).is_err());
+    let constraint = game_state.bidding_state.as_ref().unwrap().constraint();
+    assert_eq!(constraint.forbidden, vec![0]);
+    game_state.apply_action(players[0].clone(), PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
+    assert_eq!(game_state.current_player, players[2]);
+    assert!(matches!(game_state.get_auto_action(&LowestLegal), Some(PlayerAction::Bid(Bid { tricks: 1 }))));
+
+    // Everyone must bid the single card, so whoever takes the trick reaches the target
+    while game_state.phase != GamePhase::RoundComplete && game_state.phase != GamePhase::GameComplete {
+        let player = game_state.current_player.clone();
+        let action = game_state.get_auto_action(&LowestLegal).unwrap();
+        game_state.apply_action(player, action).unwrap();
+    }
+    assert_eq!(game_state.phase, GamePhase::GameComplete);
+    assert_eq!(game_state.round_number, 1);
+}
+
+#[tokio::test]
+async fn test_seeded_deal_matches_its_commitment() {
+    use german_bridge_backend::engine::deck::{Deck, DeckSeed};
+    use german_bridge_backend::engine::rules::RuleSet;
+    use german_bridge_backend::engine::schedule::RoundSchedule;
+
+    let players: Vec<PlayerId> = (0..3).map(|_| new_player_id()).collect();
+    let rules = RuleSet { seeded_deals: true, ..Default::default() };
+    let mut game_state = GameState::with_rules(players.clone(), RoundSchedule::Ascending, rules);
+    let dealt: Vec<_> = players.iter().map(|p| game_state.hands[p].cards().to_vec()).collect();
+
+    let commitment = game_state.deal_commitment().unwrap();
+    assert!(game_state.history.is_empty());
+
+    while game_state.phase != GamePhase::RoundComplete {
+        let player = game_state.current_player.clone();
+        let action = game_state.get_auto_action(&LowestLegal).unwrap();
+        game_state.apply_action(player, action).unwrap();
+    }
+
+    // The revealed seed hashes to the commitment and reproduces the deal and trump
+    let revealed = game_state.history[0].deal_seed.clone().unwrap();
+    let seed = DeckSeed::from_hex(&revealed).unwrap();
+    assert_eq!(seed.commitment(), commitment);
+    let mut rng = seed.rng();
+    let mut deck = Deck::new_german_bridge();
+    deck.shuffle_with(&mut rng);
+    let replayed: Vec<_> = deck.deal(3, 1).into_iter().map(|hand| hand.cards().to_vec()).collect();
+    assert_eq!(replayed, dealt);
+    assert_eq!(game_state.rules.trump.trump_for_round(1, &mut rng), game_state.history[0].trump_suit);
+
+    // Unseeded games have neither
+    let game_state = GameState::new(players.clone());
+    assert!(game_state.deal_commitment().is_none());
+}
+
+#[tokio::test]
+async fn test_round_limit_ends_the_game_early() {
+    use german_bridge_backend::engine::rules::RuleSet;
+    use german_bridge_backend::engine::schedule::RoundSchedule;
+
+    let players: Vec<PlayerId> = (0..4).map(|_| new_player_id()).collect();
+    let rules = RuleSet { round_limit: Some(2), ..Default::default() };
+    let mut game_state = GameState::with_rules(players.clone(), RoundSchedule::Ascending, rules);
+    assert_eq!(game_state.total_rounds(), 2);
+
+    while game_state.phase != GamePhase::GameComplete {
+        if game_state.phase == GamePhase::RoundComplete {
+            game_state.advance_to_next_round();
+            continue;
+        }
+        let player = game_state.current_player.clone();
+        let action = game_state.get_auto_action(&LowestLegal).unwrap();
+        game_state.apply_action(player, action).unwrap();
+    }
+    assert_eq!(game_state.round_number, 2);
+
+    // A limit past the end of the schedule changes nothing
+    let rules = RuleSet { round_limit: Some(100), ..Default::default() };
+    let game_state = GameState::with_rules(players, RoundSchedule::Ascending, rules);
+    assert_eq!(game_state.total_rounds(), 13);
+}
+
+#[tokio::test]
+async fn test_invariants_hold_through_a_round() {
+    let players: Vec<PlayerId> = (0..3).map(|_| new_player_id()).collect();
+    let mut game_state = GameState::new(players);
+    assert_eq!(game_state.check_invariants(), Ok(()));
+
+    // Play out the round with whatever the game offers
+    while game_state.phase != GamePhase::RoundComplete {
+        let player = game_state.current_player.clone();
+        let action = game_state.get_valid_actions(player.clone()).remove(0);
+        game_state.apply_action(player, action).unwrap();
+        assert_eq!(game_state.check_invariants(), Ok(()));
+    }
+}
+
 #[tokio::test]
 async fn test_playing_phase_with_valid_moves() {
-    let player1 = PlayerId::new_v4();
-    let player2 = PlayerId::new_v4();
-    let players = vec![player1, player2];
+    let player1 = new_player_id();
+    let player2 = new_player_id();
+    let players = vec![player1.clone(), player2.clone()];
     
     let mut game_state = GameState::new(players.clone());
     
     // Complete bidding
-    game_state.apply_action(player1, PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
-    game_state.apply_action(player2, PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
+    game_state.apply_action(player1.clone(), PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
+    game_state.apply_action(player2.clone(), PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
     
     assert_eq!(game_state.phase, GamePhase::Playing);
     
@@ -599,10 +1070,10 @@ async fn test_playing_phase_with_valid_moves() {
     let player2_card = game_state.hands.get(&player2).unwrap().cards()[0];
     
     // Player 1 plays their card
-    game_state.apply_action(player1, PlayerAction::PlayCard(player1_card)).unwrap();
+    game_state.apply_action(player1.clone(), PlayerAction::PlayCard(player1_card)).unwrap();
     
     // Player 2 plays their card
-    game_state.apply_action(player2, PlayerAction::PlayCard(player2_card)).unwrap();
+    game_state.apply_action(player2.clone(), PlayerAction::PlayCard(player2_card)).unwrap();
     
     // Round should be complete after both players play their only card
     assert!(game_state.phase == GamePhase::RoundComplete || game_state.phase == GamePhase::Bidding);
@@ -610,19 +1081,19 @@ async fn test_playing_phase_with_valid_moves() {
 
 #[tokio::test]
 async fn test_invalid_card_play_not_in_hand() {
-    let player1 = PlayerId::new_v4();
-    let player2 = PlayerId::new_v4();
-    let players = vec![player1, player2];
+    let player1 = new_player_id();
+    let player2 = new_player_id();
+    let players = vec![player1.clone(), player2.clone()];
     
     let mut game_state = GameState::new(players.clone());
     
     // Complete bidding (player1 bids 1, player2 cannot bid 0 due to last bidder rule)
-    game_state.apply_action(player1, PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
-    game_state.apply_action(player2, PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
+    game_state.apply_action(player1.clone(), PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
+    game_state.apply_action(player2.clone(), PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
     
     // Try to play a card that's not in hand
     let fake_card = Card::new(Suit::Hearts, Rank::Ace);
-    let result = game_state.apply_action(player1, PlayerAction::PlayCard(fake_card));
+    let result = game_state.apply_action(player1.clone(), PlayerAction::PlayCard(fake_card));
     
     assert!(result.is_err());
     match result {
@@ -633,15 +1104,15 @@ async fn test_invalid_card_play_not_in_hand() {
 
 #[tokio::test]
 async fn test_not_player_turn_error() {
-    let player1 = PlayerId::new_v4();
-    let player2 = PlayerId::new_v4();
-    let players = vec![player1, player2];
+    let player1 = new_player_id();
+    let player2 = new_player_id();
+    let players = vec![player1.clone(), player2.clone()];
     
     let mut game_state = GameState::new(players.clone());
     
     // Try to have player 2 bid when it's player 1's turn
     let bid = PlayerAction::Bid(Bid { tricks: 0 });
-    let result = game_state.apply_action(player2, bid);
+    let result = game_state.apply_action(player2.clone(), bid);
     
     assert!(result.is_err());
     match result {
@@ -652,22 +1123,22 @@ async fn test_not_player_turn_error() {
 
 #[tokio::test]
 async fn test_game_completion_and_scoring() {
-    let player1 = PlayerId::new_v4();
-    let player2 = PlayerId::new_v4();
-    let players = vec![player1, player2];
+    let player1 = new_player_id();
+    let player2 = new_player_id();
+    let players = vec![player1.clone(), player2.clone()];
     
     let mut game_state = GameState::new(players.clone());
     
     // Complete bidding
-    game_state.apply_action(player1, PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
-    game_state.apply_action(player2, PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
+    game_state.apply_action(player1.clone(), PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
+    game_state.apply_action(player2.clone(), PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
     
     // Play cards
     let player1_card = game_state.hands.get(&player1).unwrap().cards()[0];
     let player2_card = game_state.hands.get(&player2).unwrap().cards()[0];
     
-    game_state.apply_action(player1, PlayerAction::PlayCard(player1_card)).unwrap();
-    game_state.apply_action(player2, PlayerAction::PlayCard(player2_card)).unwrap();
+    game_state.apply_action(player1.clone(), PlayerAction::PlayCard(player1_card)).unwrap();
+    game_state.apply_action(player2.clone(), PlayerAction::PlayCard(player2_card)).unwrap();
     
     // After round completes, scores should be calculated
     // The game may have moved to next round or completed
@@ -686,9 +1157,9 @@ async fn test_game_completion_and_scoring() {
 
 #[tokio::test]
 async fn test_round_progression() {
-    let player1 = PlayerId::new_v4();
-    let player2 = PlayerId::new_v4();
-    let players = vec![player1, player2];
+    let player1 = new_player_id();
+    let player2 = new_player_id();
+    let players = vec![player1.clone(), player2.clone()];
     
     let mut game_state = GameState::new(players.clone());
     
@@ -696,14 +1167,14 @@ async fn test_round_progression() {
     assert_eq!(game_state.cards_per_player, 1);
     
     // Complete round 1 (player1 bids 1, player2 cannot bid 0 due to last bidder rule, so bids 1)
-    game_state.apply_action(player1, PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
-    game_state.apply_action(player2, PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
+    game_state.apply_action(player1.clone(), PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
+    game_state.apply_action(player2.clone(), PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
     
     let player1_card = game_state.hands.get(&player1).unwrap().cards()[0];
     let player2_card = game_state.hands.get(&player2).unwrap().cards()[0];
     
-    game_state.apply_action(player1, PlayerAction::PlayCard(player1_card)).unwrap();
-    game_state.apply_action(player2, PlayerAction::PlayCard(player2_card)).unwrap();
+    game_state.apply_action(player1.clone(), PlayerAction::PlayCard(player1_card)).unwrap();
+    game_state.apply_action(player2.clone(), PlayerAction::PlayCard(player2_card)).unwrap();
     
     // Should progress to round 2 if game continues
     if game_state.phase == GamePhase::Bidding {
@@ -714,10 +1185,10 @@ async fn test_round_progression() {
 
 #[tokio::test]
 async fn test_three_player_game() {
-    let player1 = PlayerId::new_v4();
-    let player2 = PlayerId::new_v4();
-    let player3 = PlayerId::new_v4();
-    let players = vec![player1, player2, player3];
+    let player1 = new_player_id();
+    let player2 = new_player_id();
+    let player3 = new_player_id();
+    let players = vec![player1.clone(), player2.clone(), player3.clone()];
     
     let game_state = GameState::new(players.clone());
     
@@ -729,149 +1200,810 @@ async fn test_three_player_game() {
     }
 }
 
+#[test]
+fn test_auto_bid_is_lowest_legal_bid() {
+    let players = vec![new_player_id(), new_player_id()];
+
+    // Not the last bidder: 0 is always allowed
+    let game_state = GameState::new(players.clone());
+    let first = game_state.current_player.clone();
+    assert!(matches!(game_state.get_auto_action(&LowestLegal), Some(PlayerAction::Bid(Bid { tricks: 0 }))));
+
+    // Last bidder after the others bid every card: 0 would make the total match
+    let mut game_state = GameState::new(players.clone());
+    game_state.apply_action(first.clone(), PlayerAction::Bid(Bid { tricks: 1 })).unwrap();
+    let action = game_state.get_auto_action(&LowestLegal).unwrap();
+    assert!(matches!(action, PlayerAction::Bid(Bid { tricks: 1 })));
+    let last = game_state.current_player.clone();
+    game_state.apply_action(last, action).unwrap();
+    assert_eq!(game_state.phase, GamePhase::Playing);
+
+    // Last bidder when 0 is still legal
+    let mut game_state = GameState::new(players);
+    game_state.apply_action(first, PlayerAction::Bid(Bid { tricks: 0 })).unwrap();
+    assert!(matches!(game_state.get_auto_action(&LowestLegal), Some(PlayerAction::Bid(Bid { tricks: 0 }))));
+}
+
 #[tokio::test]
-async fn test_game_manager_integration() {
+async fn test_turn_timeout_as_last_bidder_does_not_wedge_game() {
     let conn_manager = Arc::new(ConnectionManager::new());
-    let game_manager = Arc::new(GameManager::new(conn_manager));
-    
-    let player1 = PlayerId::new_v4();
-    let player2 = PlayerId::new_v4();
-    let players = vec![player1, player2];
-    
-    // Create a game
-    let game_id = game_manager.create_game(players.clone()).await;
-    
-    // Get game state for player 1
-    let view = game_manager.get_game_state(game_id, player1).await;
-    assert!(view.is_ok());
-    
-    let view = view.unwrap();
-    assert_eq!(view.game_id, game_id);
-    assert_eq!(view.phase, GamePhase::Bidding);
-    assert_eq!(view.your_hand.len(), 1);
-    
-    // Place a bid
-    let result = game_manager.handle_player_action(
-        game_id,
-        player1,
-        PlayerAction::Bid(Bid { tricks: 0 })
-    ).await;
-    assert!(result.is_ok());
-    
-    // Get updated state
-    let view = game_manager.get_game_state(game_id, player1).await.unwrap();
-    assert_eq!(view.phase, GamePhase::Bidding); // Still bidding, waiting for player 2
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let (player1, player2) = (new_player_id(), new_player_id());
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
+
+    let first = game_manager.get_game_state(game_id, player1.clone()).await.unwrap().current_player;
+    let last = if first == player1 { player2 } else { player1 };
+    game_manager.handle_player_action(game_id, first.clone(), PlayerAction::Bid(Bid { tricks: 1 })).await.unwrap();
+
+    // The last bidder times out; a 0 bid would be refused and leave the game waiting forever
+    game_manager.start_turn_timer(game_id, 1).await;
+    tokio::time::sleep(std::time::Duration::from_millis(1_500)).await;
+    game_manager.cancel_turn_timer(game_id).await;
+
+    let view = game_manager.get_game_state(game_id, first).await.unwrap();
+    assert_eq!(view.phase, GamePhase::Playing);
+    let auto_bid = view.current_round.iter().find(|r| r.player_id == last).unwrap().bid;
+    assert_eq!(auto_bid, 1);
 }
 
 #[tokio::test]
-async fn test_player_not_in_game_error() {
+async fn test_your_turn_follows_timeouts_and_state_requests() {
     let conn_manager = Arc::new(ConnectionManager::new());
-    let game_manager = Arc::new(GameManager::new(conn_manager));
-    
-    let player1 = PlayerId::new_v4();
-    let player2 = PlayerId::new_v4();
-    let player3 = PlayerId::new_v4(); // Not in game
-    let players = vec![player1, player2];
-    
-    let game_id = game_manager.create_game(players).await;
-    
-    // Try to get state for player not in game
-    let result = game_manager.get_game_state(game_id, player3).await;
-    assert!(result.is_err());
-    match result {
-        Err(GameError::PlayerNotInGame) => {},
-        _ => panic!("Expected PlayerNotInGame error"),
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let (tx1, rx1) = outbound::channel();
+    let (tx2, rx2) = outbound::channel();
+    let player1 = conn_manager.add_player(tx1).await;
+    let player2 = conn_manager.add_player(tx2).await;
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
+
+    let first = game_manager.get_game_state(game_id, player1.clone()).await.unwrap().current_player;
+    let (last, mut first_rx, mut last_rx) = if first == player1 { (player2, rx1, rx2) } else { (player1, rx2, rx1) };
+    while last_rx.try_recv().is_ok() {}
+
+    fn valid_bids(rx: &mut OutboundReceiver) -> Option<Vec<u8>> {
+        let mut bids = None;
+        while let Ok(Message::Text(text)) = rx.try_recv() {
+            if let ServerMessage::YourTurn { valid_actions, .. } = serde_json::from_str(&text).unwrap() {
+                bids = Some(valid_actions.iter().map(|a| match a {
+                    PlayerAction::Bid(bid) => bid.tricks,
+                    other => panic!("unexpected {:?}", other),
+                }).collect());
+            }
+        }
+        bids
     }
-    
-    // Try to perform action as player not in game
-    let result = game_manager.handle_player_action(
-        game_id,
-        player3,
-        PlayerAction::Bid(Bid { tricks: 0 })
-    ).await;
-    assert!(result.is_err());
-}
 
-// ============================================================================
-// Reconnection Scenario Tests
-// ============================================================================
+    // The first bidder times out; the auto bid passes the turn on with the last bidder's options
+    game_manager.start_turn_timer(game_id, 1).await;
+    tokio::time::sleep(std::time::Duration::from_millis(1_500)).await;
+    game_manager.cancel_turn_timer(game_id).await;
+    let view = game_manager.get_game_state(game_id, last.clone()).await.unwrap();
+    assert_eq!(view.current_player, last);
+    let auto_bid = view.current_round.iter().find(|r| r.player_id == first).unwrap().bid;
+    // One card dealt: the last bidder may not bring the total to 1
+    let expected: Vec<u8> = (0..=1).filter(|&b| auto_bid + b != 1).collect();
+    assert_eq!(valid_bids(&mut last_rx), Some(expected.clone()));
+
+    // Asking for the state again repeats the turn, but only to the player whose turn it is
+    // Room messages reach the socket through each player's feed task
+    game_manager.send_game_state(game_id, last.clone()).await.unwrap();
+    tokio::task::yield_now().await;
+    assert_eq!(valid_bids(&mut last_rx), Some(expected));
+    while first_rx.try_recv().is_ok() {}
+    game_manager.send_game_state(game_id, first.clone()).await.unwrap();
+    tokio::task::yield_now().await;
+    assert_eq!(valid_bids(&mut first_rx), None);
+}
 
 #[tokio::test]
-async fn test_player_disconnect_during_game() {
-    let conn_manager = Arc::new(ConnectionManager::new());
-    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager)));
-    
-    let (tx1, _rx1) = mpsc::unbounded_channel();
-    let (tx2, _rx2) = mpsc::unbounded_channel();
-    
+async fn test_turn_clock_stops_while_the_player_is_disconnected() {
+    use std::time::Duration;
+
+    let conn_manager = Arc::new(ConnectionManager::new().with_disconnect_grace(Duration::from_secs(2)));
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let (tx1, rx1) = outbound::channel();
+    let (tx2, rx2) = outbound::channel();
     let player1 = conn_manager.add_player(tx1).await;
     let player2 = conn_manager.add_player(tx2).await;
-    
-    // Create a game
-    let game_id = game_manager.create_game(vec![player1, player2]).await;
-    
-    // Verify both players are active
-    let active_players = conn_manager.get_active_players().await;
-    assert_eq!(active_players.len(), 2);
-    
-    // Player 1 disconnects
-    let other_players = conn_manager.mark_inactive(player1).await;
-    assert_eq!(other_players.len(), 1);
-    assert_eq!(other_players[0], player2);
-    
-    // Verify player 1 is inactive
-    let active_players = conn_manager.get_active_players().await;
-    assert_eq!(active_players.len(), 1);
-    assert_eq!(active_players[0], player2);
-    
-    // Game should still exist
-    let result = game_manager.get_game_state(game_id, player2).await;
-    assert!(result.is_ok());
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
+    let first = game_manager.get_game_state(game_id, player1.clone()).await.unwrap().current_player;
+    let mut other_rx = if first == player1 { rx2 } else { rx1 };
+
+    game_manager.start_turn_timer(game_id, 1).await;
+    conn_manager.mark_inactive(first.clone()).await;
+    tokio::time::sleep(Duration::from_millis(1_200)).await;
+
+    // Past the original deadline, but the clock stood still
+    assert_eq!(game_manager.get_game_state(game_id, first.clone()).await.unwrap().current_player, first);
+    let mut told = false;
+    while let Ok(Message::Text(text)) = other_rx.try_recv() {
+        let message: ServerMessage = serde_json::from_str(&text).unwrap();
+        told |= matches!(message, ServerMessage::PlayerDisconnected { player_id, .. } if player_id == first);
+    }
+    assert!(told);
+
+    // Back before the pause ran out: the second they had left when they dropped runs from now
+    let (tx, _rx) = outbound::channel();
+    conn_manager.reconnect_player(first.clone(), tx).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    assert_eq!(game_manager.get_game_state(game_id, first.clone()).await.unwrap().current_player, first);
+    tokio::time::sleep(Duration::from_millis(700)).await;
+    assert_ne!(game_manager.get_game_state(game_id, first.clone()).await.unwrap().current_player, first);
 }
 
 #[tokio::test]
-async fn test_successful_reconnection_and_state_restoration() {
+async fn test_round_complete_is_sent_and_stored_when_timeouts_end_the_round() {
+    let db = test_db_with_schema().await;
     let conn_manager = Arc::new(ConnectionManager::new());
-    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager)));
-    
-    let (tx1, _rx1) = mpsc::unbounded_channel();
-    let (tx2, _rx2) = mpsc::unbounded_channel();
-    
-    let player1 = conn_manager.add_player(tx1).await;
-    let player2 = conn_manager.add_player(tx2).await;
-    
-    // Create a game
-    let game_id = game_manager.create_game(vec![player1, player2]).await;
-    
-    // Player 1 makes a bid
-    game_manager.handle_player_action(
-        game_id,
-        player1,
-        PlayerAction::Bid(Bid { tricks: 1 })
-    ).await.unwrap();
-    
-    // Player 1 disconnects
-    conn_manager.mark_inactive(player1).await;
-    
-    // Player 1 reconnects
-    let (tx1_new, mut rx1_new) = mpsc::unbounded_channel();
-    let result = conn_manager.reconnect_player(player1, tx1_new).await;
-    assert!(result.is_some());
-    
-    // Verify player 1 is active again
-    let active_players = conn_manager.get_active_players().await;
-    assert_eq!(active_players.len(), 2);
-    
-    // Player 1 should be able to get their game state
-    let view = game_manager.get_game_state(game_id, player1).await;
-    assert!(view.is_ok());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let player1 = insert_user(&db, "alice").await;
+    let (tx1, mut rx1) = outbound::channel();
+    conn_manager.register_player(player1.clone(), "alice".to_string(), tx1).await;
+    let player2 = insert_user(&db, "bob").await;
+    let game_id = game_manager.create_game(vec![player1.clone(), player2]).await;
+
+    // Nobody acts: two bids and two cards, each played when the turn times out
+    for _ in 0..4 {
+        game_manager.start_turn_timer(game_id, 0).await;
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    let view = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+    assert_eq!(view.phase, GamePhase::RoundComplete);
+
+    let mut messages = Vec::new();
+    while let Ok(Message::Text(text)) = rx1.try_recv() {
+        messages.push(serde_json::from_str::<ServerMessage>(&text).unwrap());
+    }
+    assert!(messages.iter().any(|msg| matches!(msg, ServerMessage::TrickComplete { round_complete: true, .. })));
+    let results = messages.iter().find_map(|msg| match msg {
+        ServerMessage::RoundComplete { results } => Some(results.clone()),
+        _ => None,
+    }).expect("no RoundComplete sent");
+    assert_eq!(results.round_number, 1);
+    let scores: Vec<i32> = results.player_results.iter().map(|r| r.score).collect();
+    assert_eq!(scores, view.history[0].player_results.iter().map(|r| r.score).collect::<Vec<_>>());
+
+    game_manager.db_writes().flush().await;
+    let rows = entities::game_round::Entity::find().all(&db).await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].player_results, serde_json::json!(results.player_results));
+}
+
+#[tokio::test]
+async fn test_timed_out_turns_can_cost_points() {
+    use german_bridge_backend::engine::rules::{RuleSet, TimeoutPolicy, TimerPolicy};
+    use german_bridge_backend::engine::scoring::ScoreCalculator;
+
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let players = vec![new_player_id(), new_player_id()];
+    let rules = RuleSet {
+        timer: TimerPolicy { on_timeout: TimeoutPolicy::SkipWithPenalty { points: 3 }, ..Default::default() },
+        ..Default::default()
+    };
+    let settings = GameSettings { rules, ..Default::default() };
+    let game_id = game_manager.create_game_with_settings(players.clone(), None, settings).await;
+
+    for _ in 0..4 {
+        game_manager.start_turn_timer(game_id, 0).await;
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    let view = game_manager.get_game_state(game_id, players[0].clone()).await.unwrap();
+    assert_eq!(view.phase, GamePhase::RoundComplete);
+    // Each player timed out on a bid and a card
+    for result in &view.history[0].player_results {
+        assert_eq!(result.penalty, 6);
+        assert_eq!(result.score, ScoreCalculator::calculate_player_score(result.bid, result.tricks_won) - 6);
+    }
+}
+
+#[tokio::test]
+async fn test_timed_out_turn_waits_for_the_host() {
+    use german_bridge_backend::engine::rules::{RuleSet, TimeoutPolicy, TimerPolicy};
+
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let (tx, mut host_rx) = outbound::channel();
+    let host = conn_manager.add_player(tx).await;
+    let guest = new_player_id();
+    let rules = RuleSet {
+        timer: TimerPolicy { on_timeout: TimeoutPolicy::WaitForHost, ..Default::default() },
+        ..Default::default()
+    };
+    let settings = GameSettings { rules, ..Default::default() };
+    let game_id = game_manager.create_game_with_settings(vec![host.clone(), guest.clone()], None, settings).await;
+    let late = game_manager.get_game_state(game_id, host.clone()).await.unwrap().current_player;
+    let other = if late == host { guest.clone() } else { host.clone() };
+
+    // Not overdue yet
+    assert!(matches!(game_manager.force_turn(game_id, &host, late.clone()).await, Err(GameError::TurnNotOverdue)));
+
+    game_manager.start_turn_timer(game_id, 0).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let view = game_manager.get_game_state(game_id, host.clone()).await.unwrap();
+    assert_eq!((view.phase, view.current_player.clone()), (GamePhase::Bidding, late.clone()));
+    let mut told = false;
+    while let Ok(Message::Text(text)) = host_rx.try_recv() {
+        let message: ServerMessage = serde_json::from_str(&text).unwrap();
+        told |= matches!(message, ServerMessage::TurnTimedOut { player_id, .. } if player_id == late);
+    }
+    assert!(told);
+
+    assert!(matches!(game_manager.force_turn(game_id, &guest, late.clone()).await, Err(GameError::NotGameHost)));
+    assert!(matches!(game_manager.force_turn(game_id, &host, other.clone()).await, Err(GameError::TurnNotOverdue)));
+    game_manager.force_turn(game_id, &host, late.clone()).await.unwrap();
+    let view = game_manager.get_game_state(game_id, host.clone()).await.unwrap();
+    assert_eq!(view.current_player, other);
+    // No penalty under this policy
+    assert!(view.current_round.iter().all(|r| r.penalty == 0));
+}
+
+#[tokio::test]
+async fn test_hints_suggest_a_move_for_your_own_turn_only() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let mut players = Vec::new();
+    let mut receivers = std::collections::HashMap::new();
+    for _ in 0..3 {
+        let (tx, rx) = outbound::channel();
+        let player = conn_manager.add_player(tx).await;
+        receivers.insert(player.clone(), rx);
+        players.push(player);
+    }
+    let game_id = game_manager.create_game_with_settings(players.clone(), None, GameSettings::default()).await;
+
+    // A full bidding round and the first card, each taken as hinted
+    for _ in 0..=players.len() {
+        let view = game_manager.get_game_state(game_id, players[0].clone()).await.unwrap();
+        let current = view.current_player.clone();
+        for player in players.iter().filter(|p| **p != current) {
+            assert!(matches!(game_manager.send_hint(game_id, player.clone()).await, Err(GameError::NotPlayerTurn)));
+        }
+
+        game_manager.send_hint(game_id, current.clone()).await.unwrap();
+        let rx = receivers.get_mut(&current).unwrap();
+        let (action, state_version) = loop {
+            let msg = tokio::time::timeout(std::time::Duration::from_secs(1), rx.recv()).await.expect("Hint");
+            if let Some(Message::Text(text)) = msg {
+                if let ServerMessage::Hint { action, state_version } = serde_json::from_str(&text).unwrap() {
+                    break (action, state_version);
+                }
+            }
+        };
+        assert_eq!(state_version, view.state_version);
+        game_manager.handle_player_action_at_version(game_id, current, action, Some(state_version)).await.unwrap();
+    }
+    let view = game_manager.get_game_state(game_id, players[0].clone()).await.unwrap();
+    assert_eq!(view.phase, GamePhase::Playing);
+    assert_eq!(view.current_trick.len(), 1);
+    assert!(matches!(game_manager.send_hint(game_id, new_player_id()).await, Err(GameError::PlayerNotInGame)));
+
+    // Competitive lobbies turn them off
+    let settings = GameSettings { allow_hints: false, ..Default::default() };
+    let game_id = game_manager.create_game_with_settings(players.clone(), None, settings).await;
+    let current = game_manager.get_game_state(game_id, players[0].clone()).await.unwrap().current_player;
+    assert!(matches!(game_manager.send_hint(game_id, current).await, Err(GameError::HintsDisabled)));
+}
+
+#[tokio::test]
+async fn test_queued_writes_wait_out_a_database_outage() {
+    use german_bridge_backend::db_outbox::{DbOutbox, DbWrite};
+    use sea_orm::Set;
+
+    // Only users exist so far; writes to lobbies fail until their tables appear
+    let db = test_db().await;
+    let schema = Schema::new(db.get_database_backend());
+    create_table(&db, &schema, entities::user::Entity).await;
+    let host_id = Uuid::parse_str(&insert_user(&db, "alice").await).unwrap();
+
+    let writes = DbOutbox::start_with_base_delay(db.clone(), std::time::Duration::from_millis(20));
+    let lobby_id = Uuid::new_v4();
+    let lobby = entities::lobby::ActiveModel {
+        id: Set(lobby_id),
+        host_id: Set(host_id),
+        max_players: Set(4),
+        settings: Set(serde_json::json!(GameSettings::default())),
+        created_at: Set(chrono::Utc::now()),
+        closed_at: Set(None),
+        deleted_at: Set(None),
+        code: Set(None),
+    };
+    writes.push(DbWrite::LobbyCreated { lobby, host_id, joined_at: chrono::Utc::now() });
+    writes.push(DbWrite::LobbyClosed { lobby_id, closed_at: chrono::Utc::now() });
+    // An outage outlasting several retries still loses nothing
+    tokio::time::sleep(std::time::Duration::from_millis(800)).await;
+
+    create_table(&db, &schema, entities::lobby::Entity).await;
+    create_table(&db, &schema, entities::lobby_player::Entity).await;
+    writes.flush().await;
+
+    // Both writes landed, in the order they were queued
+    let row = entities::lobby::Entity::find_by_id(lobby_id).one(&db).await.unwrap().unwrap();
+    assert!(row.closed_at.is_some());
+    let members = entities::lobby_player::Entity::find().all(&db).await.unwrap();
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0].player_id, host_id);
+}
+
+#[tokio::test]
+async fn test_retried_writes_are_stored_once() {
+    use german_bridge_backend::db_outbox::{DbOutbox, DbWrite};
+    use sea_orm::{ColumnTrait, QueryFilter, Set};
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let host = insert_user(&db, "alice").await;
+    let guest = insert_user(&db, "bob").await;
+    let game_id = game_manager.create_game(vec![host.clone(), guest.clone()]).await;
+    game_manager.db_writes().flush().await;
+    let (host_id, guest_id) = (Uuid::parse_str(&host).unwrap(), Uuid::parse_str(&guest).unwrap());
+
+    // Each write queued twice, as after a commit whose outcome was lost
+    let writes = DbOutbox::start(db.clone());
+    let lobby_id = Uuid::new_v4();
+    let lobby = entities::lobby::ActiveModel {
+        id: Set(lobby_id),
+        host_id: Set(host_id),
+        max_players: Set(4),
+        settings: Set(serde_json::json!(GameSettings::default())),
+        created_at: Set(chrono::Utc::now()),
+        closed_at: Set(None),
+        deleted_at: Set(None),
+        code: Set(None),
+    };
+    let joined_at = chrono::Utc::now();
+    let round = DbWrite::RoundFinished {
+        game_id,
+        round_number: 1,
+        player_results: serde_json::json!([]),
+        totals: vec![(host_id, 10), (guest_id, 2)],
+    };
+    let action = german_bridge_backend::transcript::RecordedAction {
+        version: 1,
+        round_number: 1,
+        player_id: host.clone(),
+        action: PlayerAction::Bid(Bid { tricks: 0 }),
+        auto: false,
+    };
+    let deal = german_bridge_backend::deal_review::Deal { round_number: 2, trump_suit: None, hands: Vec::new() };
+    for _ in 0..2 {
+        writes.push(DbWrite::LobbyCreated { lobby: lobby.clone(), host_id, joined_at });
+        writes.push(DbWrite::LobbyJoined { lobby_id, player_id: guest_id, joined_at });
+        writes.push(round.clone());
+        writes.push(DbWrite::ActionRecorded { game_id, action: action.clone() });
+        writes.push(DbWrite::DealRecorded { game_id, deal: deal.clone() });
+    }
+    writes.flush().await;
+
+    assert_eq!(entities::lobby::Entity::find().all(&db).await.unwrap().len(), 1);
+    assert_eq!(entities::lobby_player::Entity::find().all(&db).await.unwrap().len(), 2);
+    assert_eq!(entities::game_round::Entity::find().all(&db).await.unwrap().len(), 1);
+    assert_eq!(entities::game_action::Entity::find().all(&db).await.unwrap().len(), 1);
+    // The first round's deal, stored when the game was created, and the second's once
+    assert_eq!(entities::round_deal::Entity::find().all(&db).await.unwrap().len(), 2);
+    let seat = entities::game_player::Entity::find()
+        .filter(entities::game_player::Column::PlayerId.eq(host_id))
+        .one(&db).await.unwrap().unwrap();
+    assert_eq!(seat.final_score, Some(10));
+}
+
+#[tokio::test]
+async fn test_new_games_are_stored_with_their_seats() {
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let player1 = insert_user(&db, "alice").await;
+    let player2 = insert_user(&db, "bob").await;
+
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
+    let game = entities::game::Entity::find_by_id(game_id).one(&db).await.unwrap().unwrap();
+    assert_eq!(game.player_count, 2);
+    assert!(game.completed_at.is_none());
+    let mut seated: Vec<String> = entities::game_player::Entity::find().all(&db).await.unwrap()
+        .into_iter().map(|seat| seat.player_id.to_string()).collect();
+    seated.sort();
+    let mut expected = vec![player1.clone(), player2];
+    expected.sort();
+    assert_eq!(seated, expected);
+
+    // A seat that cannot be stored takes the game row with it
+    let game_id = game_manager.create_game(vec![player1, new_player_id()]).await;
+    assert!(entities::game::Entity::find_by_id(game_id).one(&db).await.unwrap().is_none());
+    assert_eq!(entities::game_player::Entity::find().all(&db).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_running_scores_are_stored_after_each_round() {
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let player1 = insert_user(&db, "alice").await;
+    let player2 = insert_user(&db, "bob").await;
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
+
+    for _ in 0..4 {
+        game_manager.start_turn_timer(game_id, 0).await;
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    let view = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+    assert_eq!(view.phase, GamePhase::RoundComplete);
+
+    game_manager.db_writes().flush().await;
+    let seats = entities::game_player::Entity::find().all(&db).await.unwrap();
+    assert_eq!(seats.len(), 2);
+    for seat in seats {
+        assert_eq!(seat.final_score, view.scores.get(&seat.player_id.to_string()).copied());
+        assert_eq!(seat.placement, None);
+    }
+    assert_eq!(entities::game_round::Entity::find().all(&db).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_draining_saves_games_that_resume_after_a_restart() {
+    use german_bridge_backend::router::MessageRouter;
+    use german_bridge_backend::server;
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db.clone()));
+    let router = Arc::new(MessageRouter::new(lobby_manager, Arc::clone(&game_manager), Arc::clone(&conn_manager)));
+    let player1 = insert_user(&db, "alice").await;
+    let player2 = insert_user(&db, "bob").await;
+    let (tx1, mut rx1) = outbound::channel();
+    conn_manager.register_player(player1.clone(), "alice".to_string(), tx1).await;
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
+
+    // Finish round one and start round two
+    for _ in 0..4 {
+        game_manager.start_turn_timer(game_id, 0).await;
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    game_manager.handle_start_next_round(game_id, player2.clone()).await.unwrap();
+    game_manager.start_turn_timer(game_id, 0).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let before = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+    // Player two has bid
+    assert_eq!((before.round_number, before.current_player.clone()), (2, player1.clone()));
+
+    server::drain(Arc::clone(&conn_manager), Arc::clone(&game_manager), Arc::clone(&router), std::time::Duration::ZERO).await;
+    let mut warned = false;
+    while let Ok(Message::Text(text)) = rx1.try_recv() {
+        if let Ok(ServerMessage::ServerMaintenance { shutdown_in_secs }) = serde_json::from_str(&text) {
+            assert_eq!(shutdown_in_secs, 0);
+            warned = true;
+        }
+    }
+    assert!(warned);
+    assert!(router.route_message(player1.clone(), ClientMessage::CreateLobby { settings: GameSettings::default() }).await.is_err());
+
+    // A fresh server on the same database carries on with round two's hands and bids
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = GameManager::new(Arc::clone(&conn_manager), db.clone());
+    assert_eq!(game_manager.resume_suspended_games().await, vec![game_id]);
+    let after = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+    assert_eq!((after.round_number, after.phase), (2, GamePhase::Bidding));
+    assert_eq!(after.your_hand, before.your_hand);
+    assert_eq!(after.current_player, before.current_player);
+    assert_eq!(after.state_version, before.state_version);
+    assert_eq!(after.scores, before.scores);
+    assert_eq!(after.game_code, before.game_code);
+    assert_eq!(conn_manager.rooms().active_game(&player2), Some(game_id));
+
+    // The checkpoint is used once
+    let row = entities::game::Entity::find_by_id(game_id).one(&db).await.unwrap().unwrap();
+    assert_eq!(row.state, serde_json::json!({}));
+    assert!(GameManager::new(Arc::new(ConnectionManager::new()), db).resume_suspended_games().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_restart_routes_players_back_to_their_lobby() {
+    use german_bridge_backend::router::MessageRouter;
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db.clone());
+    let host = insert_user(&db, "alice").await;
+    let guest = insert_user(&db, "bob").await;
+    let leaver = insert_user(&db, "carol").await;
+    let lobby_id = lobby_manager.create_lobby(host.clone(), GameSettings::default()).await;
+    lobby_manager.join_lobby(lobby_id, guest.clone()).await.unwrap();
+    lobby_manager.join_lobby(lobby_id, leaver.clone()).await.unwrap();
+    lobby_manager.leave_lobby(lobby_id, leaver.clone()).await.unwrap();
+    let code = lobby_manager.get_lobby(lobby_id).await.unwrap().code;
+    game_manager.db_writes().flush().await;
+
+    // A fresh server on the same database has the lobby and its members back
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db.clone()));
+    assert_eq!(lobby_manager.restore_lobbies().await, 1);
+    let lobby = lobby_manager.get_lobby(lobby_id).await.unwrap();
+    assert_eq!(lobby.players, vec![host.clone(), guest.clone()]);
+    assert_eq!((lobby.host, lobby.code), (host.clone(), code));
+    assert_eq!(conn_manager.rooms().lobby_of(&guest), Some(lobby_id));
+    assert_eq!(conn_manager.rooms().lobby_of(&leaver), None);
+
+    // Reconnecting puts the player straight back in the lobby
+    let router = MessageRouter::new(Arc::clone(&lobby_manager), game_manager, Arc::clone(&conn_manager));
+    let (tx, mut rx) = outbound::channel();
+    conn_manager.register_player(guest.clone(), "bob".to_string(), tx).await;
+    router.restore_session(&guest).await;
+    let Ok(Message::Text(text)) = rx.try_recv() else { panic!("no message after reconnecting") };
+    let ServerMessage::LobbyJoined { lobby } = serde_json::from_str(&text).unwrap() else { panic!("expected LobbyJoined: {}", text) };
+    assert_eq!(lobby.id, lobby_id);
+
+    // Restoring twice does not duplicate the lobby
+    assert_eq!(lobby_manager.restore_lobbies().await, 0);
+}
+
+#[tokio::test]
+async fn test_next_round_policies_gate_who_starts_it() {
+    use german_bridge_backend::protocol::NextRoundPolicy;
+
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db_with_schema().await));
+    let (tx1, mut rx1) = outbound::channel();
+    let player1 = conn_manager.add_player(tx1).await;
+    let player2 = new_player_id();
+
+    // Everyone has to ask: the first request only counts a vote
+    let settings = GameSettings { next_round: NextRoundPolicy::AllPlayers, ..Default::default() };
+    let game_id = game_manager.create_game_with_settings(vec![player1.clone(), player2.clone()], None, settings).await;
+    for _ in 0..4 {
+        game_manager.start_turn_timer(game_id, 0).await;
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    let view = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+    assert_eq!(view.phase, GamePhase::RoundComplete);
+    assert!(view.your_turn);
+    assert_eq!(view.intermission.unwrap().waiting_for, vec![player1.clone(), player2.clone()]);
+
+    game_manager.handle_start_next_round(game_id, player1.clone()).await.unwrap();
+    assert!(game_manager.handle_start_next_round(game_id, player1.clone()).await.is_err());
+    let view = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+    assert_eq!(view.phase, GamePhase::RoundComplete);
+    assert!(!view.your_turn);
+    let mut voted = false;
+    while let Ok(Message::Text(text)) = rx1.try_recv() {
+        if let ServerMessage::NextRoundVote { player_id, intermission } = serde_json::from_str(&text).unwrap() {
+            assert_eq!(player_id, player1);
+            assert_eq!(intermission.waiting_for, vec![player2.clone()]);
+            voted = true;
+        }
+    }
+    assert!(voted);
+    game_manager.handle_start_next_round(game_id, player2.clone()).await.unwrap();
+    let view = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+    assert_eq!((view.phase, view.round_number), (GamePhase::Bidding, 2));
+    assert!(view.intermission.is_none());
+
+    // Only the host may start it, unless the intermission runs out first
+    let settings = GameSettings { next_round: NextRoundPolicy::Host, intermission_secs: Some(0), ..Default::default() };
+    let game_id = game_manager.create_game_with_settings(vec![player1.clone(), player2.clone()], None, settings).await;
+    for _ in 0..4 {
+        game_manager.start_turn_timer(game_id, 0).await;
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    let view = game_manager.get_game_state(game_id, player2.clone()).await.unwrap();
+    assert_eq!((view.phase, view.round_number), (GamePhase::Bidding, 2));
+
+    let settings = GameSettings { next_round: NextRoundPolicy::Host, ..Default::default() };
+    let game_id = game_manager.create_game_with_settings(vec![player2.clone(), player1.clone()], None, settings).await;
+    for _ in 0..4 {
+        game_manager.start_turn_timer(game_id, 0).await;
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    assert!(game_manager.handle_start_next_round(game_id, player1.clone()).await.is_err());
+    game_manager.handle_start_next_round(game_id, player2.clone()).await.unwrap();
+    let view = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+    assert_eq!(view.round_number, 2);
+}
+
+#[tokio::test]
+async fn test_no_message_shows_a_card_its_player_may_not_see() {
+    use german_bridge_backend::engine::auto_play::RulesAware;
+    use german_bridge_backend::engine::rules::RuleSet;
+    use german_bridge_backend::redaction::RedactedView;
+    use german_bridge_backend::room::RoomId;
+    use german_bridge_backend::room_feed::RoomMessage;
+
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let mut seats = Vec::new();
+    for _ in 0..3 {
+        let (tx, rx) = outbound::channel();
+        seats.push((conn_manager.add_player(tx).await, rx));
+    }
+    let players = seats.iter().map(|(player_id, _)| player_id.clone()).collect();
+    let settings = GameSettings { player_count: 3, rules: RuleSet { max_cards: Some(3), ..Default::default() }, ..Default::default() };
+    let game_id = game_manager.create_game_with_settings(players, None, settings).await;
+
+    // Every message is checked against the game as it stood when it was sent
+    let sentinel: Arc<str> = serde_json::to_string(&ServerMessage::Pong).unwrap().into();
+    let mut checked = 0;
+    loop {
+        // Room messages reach the socket through each player's feed task, in the order they
+        // were published, so everything sent so far arrives before a marker published now
+        assert!(conn_manager.rooms().publish(RoomId::Game(game_id), RoomMessage::Everyone(sentinel.clone())));
+        let game = game_manager.get_game(game_id).await.unwrap();
+        for (player_id, rx) in seats.iter_mut() {
+            let view = RedactedView::new(&game.state, player_id);
+            loop {
+                let msg = tokio::time::timeout(std::time::Duration::from_secs(1), rx.recv()).await.expect("sentinel");
+                let Some(Message::Text(text)) = msg else { continue };
+                let message: ServerMessage = serde_json::from_str(&text).unwrap();
+                if matches!(message, ServerMessage::Pong) {
+                    break;
+                }
+                if let Err(leak) = view.check(&message) {
+                    panic!("{} was sent {:?}: {}", player_id, message, leak);
+                }
+                checked += 1;
+            }
+        }
+
+        match game.state.phase {
+            GamePhase::GameComplete => break,
+            GamePhase::RoundComplete => {
+                let starter = game.players.iter().find(|p| game.may_start_next_round(p)).unwrap().clone();
+                game_manager.handle_start_next_round(game_id, starter).await.unwrap();
+            }
+            GamePhase::Bidding | GamePhase::Playing => {
+                let player = game.state.current_player.clone();
+                let action = game.state.get_auto_action(&RulesAware).unwrap();
+                game_manager.handle_player_action(game_id, player, action).await.unwrap();
+            }
+        }
+    }
+    assert!(checked > 50, "only {} messages checked", checked);
+}
+
+#[tokio::test]
+async fn test_game_manager_integration() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(conn_manager, test_db().await));
+    
+    let player1 = new_player_id();
+    let player2 = new_player_id();
+    let players = vec![player1.clone(), player2.clone()];
+    
+    // Create a game
+    let game_id = game_manager.create_game(players.clone()).await;
+    
+    // Get game state for player 1
+    let view = game_manager.get_game_state(game_id, player1.clone()).await;
+    assert!(view.is_ok());
+    
+    let view = view.unwrap();
+    assert_eq!(view.game_id, game_id);
+    assert_eq!(view.phase, GamePhase::Bidding);
+    assert_eq!(view.your_hand.len(), 1);
+    
+    // Place a bid
+    let result = game_manager.handle_player_action(
+        game_id,
+        player1.clone(),
+        PlayerAction::Bid(Bid { tricks: 0 })
+    ).await;
+    assert!(result.is_ok());
+    
+    // Get updated state
+    let view = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+    assert_eq!(view.phase, GamePhase::Bidding); // Still bidding, waiting for player 2
+}
+
+#[tokio::test]
+async fn test_player_not_in_game_error() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(conn_manager, test_db().await));
+    
+    let player1 = new_player_id();
+    let player2 = new_player_id();
+    let player3 = new_player_id(); // Not in game
+    let players = vec![player1.clone(), player2.clone()];
+    
+    let game_id = game_manager.create_game(players).await;
+    
+    // Try to get state for player not in game
+    let result = game_manager.get_game_state(game_id, player3.clone()).await;
+    assert!(result.is_err());
+    match result {
+        Err(GameError::PlayerNotInGame) => {},
+        _ => panic!("Expected PlayerNotInGame error"),
+    }
+    
+    // Try to perform action as player not in game
+    let result = game_manager.handle_player_action(
+        game_id,
+        player3.clone(),
+        PlayerAction::Bid(Bid { tricks: 0 })
+    ).await;
+    assert!(result.is_err());
+}
+
+// ============================================================================
+// Reconnection Scenario Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_player_disconnect_during_game() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    
+    let (tx1, _rx1) = outbound::channel();
+    let (tx2, _rx2) = outbound::channel();
+    
+    let player1 = conn_manager.add_player(tx1).await;
+    let player2 = conn_manager.add_player(tx2).await;
+    
+    // Create a game
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
+    
+    // Verify both players are active
+    let active_players = conn_manager.get_active_players().await;
+    assert_eq!(active_players.len(), 2);
+    
+    // Player 1 disconnects
+    let other_players = conn_manager.mark_inactive(player1.clone()).await;
+    assert_eq!(other_players.len(), 1);
+    assert_eq!(other_players[0], player2);
+    
+    // Verify player 1 is inactive
+    let active_players = conn_manager.get_active_players().await;
+    assert_eq!(active_players.len(), 1);
+    assert_eq!(active_players[0], player2);
+    
+    // Game should still exist
+    let result = game_manager.get_game_state(game_id, player2.clone()).await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_successful_reconnection_and_state_restoration() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    
+    let (tx1, _rx1) = outbound::channel();
+    let (tx2, _rx2) = outbound::channel();
+    
+    let player1 = conn_manager.add_player(tx1).await;
+    let player2 = conn_manager.add_player(tx2).await;
+    
+    // Create a game
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
+    
+    // Player 1 makes a bid
+    game_manager.handle_player_action(
+        game_id,
+        player1.clone(),
+        PlayerAction::Bid(Bid { tricks: 1 })
+    ).await.unwrap();
+    
+    // Player 1 disconnects
+    conn_manager.mark_inactive(player1.clone()).await;
+    
+    // Player 1 reconnects
+    let (tx1_new, mut rx1_new) = outbound::channel();
+    let result = conn_manager.reconnect_player(player1.clone(), tx1_new).await;
+    assert!(result.is_some());
+    
+    // Verify player 1 is active again
+    let active_players = conn_manager.get_active_players().await;
+    assert_eq!(active_players.len(), 2);
+    
+    // Player 1 should be able to get their game state
+    let view = game_manager.get_game_state(game_id, player1.clone()).await;
+    assert!(view.is_ok());
     
     let view = view.unwrap();
     assert_eq!(view.game_id, game_id);
     assert_eq!(view.your_hand.len(), 1);
     
     // Verify we can send messages to reconnected player
-    conn_manager.send_to_player(player1, ServerMessage::Pong).await;
+    conn_manager.send_to_player(player1.clone(), ServerMessage::Pong).await;
     let received = rx1_new.recv().await;
     assert!(received.is_some());
 }
@@ -882,26 +2014,26 @@ async fn test_reconnection_timeout_and_player_removal() {
     
     // Create connection manager with very short timeout
     let conn_manager = Arc::new(ConnectionManager::with_reconnect_timeout(Duration::from_millis(100)));
-    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager)));
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
     
-    let (tx1, _rx1) = mpsc::unbounded_channel();
-    let (tx2, _rx2) = mpsc::unbounded_channel();
+    let (tx1, _rx1) = outbound::channel();
+    let (tx2, _rx2) = outbound::channel();
     
     let player1 = conn_manager.add_player(tx1).await;
     let player2 = conn_manager.add_player(tx2).await;
     
     // Create a game
-    let _game_id = game_manager.create_game(vec![player1, player2]).await;
+    let _game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
     
     // Player 1 disconnects
-    conn_manager.mark_inactive(player1).await;
+    conn_manager.mark_inactive(player1.clone()).await;
     
     // Wait for timeout to expire
     tokio::time::sleep(Duration::from_millis(150)).await;
     
     // Try to reconnect - should fail
-    let (tx1_new, _rx1_new) = mpsc::unbounded_channel();
-    let result = conn_manager.reconnect_player(player1, tx1_new).await;
+    let (tx1_new, _rx1_new) = outbound::channel();
+    let result = conn_manager.reconnect_player(player1.clone(), tx1_new).await;
     assert!(result.is_none());
     
     // Cleanup expired sessions
@@ -913,22 +2045,22 @@ async fn test_reconnection_timeout_and_player_removal() {
 #[tokio::test]
 async fn test_multiple_disconnects_and_reconnects() {
     let conn_manager = Arc::new(ConnectionManager::new());
-    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager)));
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
     
-    let (tx1, _rx1) = mpsc::unbounded_channel();
-    let (tx2, _rx2) = mpsc::unbounded_channel();
-    let (tx3, _rx3) = mpsc::unbounded_channel();
+    let (tx1, _rx1) = outbound::channel();
+    let (tx2, _rx2) = outbound::channel();
+    let (tx3, _rx3) = outbound::channel();
     
     let player1 = conn_manager.add_player(tx1).await;
     let player2 = conn_manager.add_player(tx2).await;
     let player3 = conn_manager.add_player(tx3).await;
     
     // Create a game
-    let game_id = game_manager.create_game(vec![player1, player2, player3]).await;
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone(), player3.clone()]).await;
     
     // Player 1 and 3 disconnect
-    conn_manager.mark_inactive(player1).await;
-    conn_manager.mark_inactive(player3).await;
+    conn_manager.mark_inactive(player1.clone()).await;
+    conn_manager.mark_inactive(player3.clone()).await;
     
     // Only player 2 should be active
     let active_players = conn_manager.get_active_players().await;
@@ -936,8 +2068,8 @@ async fn test_multiple_disconnects_and_reconnects() {
     assert_eq!(active_players[0], player2);
     
     // Player 1 reconnects
-    let (tx1_new, _rx1_new) = mpsc::unbounded_channel();
-    let result = conn_manager.reconnect_player(player1, tx1_new).await;
+    let (tx1_new, _rx1_new) = outbound::channel();
+    let result = conn_manager.reconnect_player(player1.clone(), tx1_new).await;
     assert!(result.is_some());
     
     // Now player 1 and 2 should be active
@@ -947,8 +2079,8 @@ async fn test_multiple_disconnects_and_reconnects() {
     assert!(active_players.contains(&player2));
     
     // Player 3 reconnects
-    let (tx3_new, _rx3_new) = mpsc::unbounded_channel();
-    let result = conn_manager.reconnect_player(player3, tx3_new).await;
+    let (tx3_new, _rx3_new) = outbound::channel();
+    let result = conn_manager.reconnect_player(player3.clone(), tx3_new).await;
     assert!(result.is_some());
     
     // All players should be active
@@ -956,105 +2088,2606 @@ async fn test_multiple_disconnects_and_reconnects() {
     assert_eq!(active_players.len(), 3);
     
     // Game should still be accessible
-    let view = game_manager.get_game_state(game_id, player1).await;
+    let view = game_manager.get_game_state(game_id, player1.clone()).await;
     assert!(view.is_ok());
 }
 
 #[tokio::test]
 async fn test_disconnect_during_bidding_phase() {
     let conn_manager = Arc::new(ConnectionManager::new());
-    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager)));
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
     
-    let (tx1, _rx1) = mpsc::unbounded_channel();
-    let (tx2, _rx2) = mpsc::unbounded_channel();
+    let (tx1, _rx1) = outbound::channel();
+    let (tx2, _rx2) = outbound::channel();
     
     let player1 = conn_manager.add_player(tx1).await;
     let player2 = conn_manager.add_player(tx2).await;
     
     // Create a game
-    let game_id = game_manager.create_game(vec![player1, player2]).await;
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
     
     // Player 1 bids
     game_manager.handle_player_action(
         game_id,
-        player1,
+        player1.clone(),
         PlayerAction::Bid(Bid { tricks: 1 })
     ).await.unwrap();
     
     // Player 2 disconnects before bidding
-    conn_manager.mark_inactive(player2).await;
+    conn_manager.mark_inactive(player2.clone()).await;
     
     // Player 2 reconnects
-    let (tx2_new, _rx2_new) = mpsc::unbounded_channel();
-    conn_manager.reconnect_player(player2, tx2_new).await;
+    let (tx2_new, _rx2_new) = outbound::channel();
+    conn_manager.reconnect_player(player2.clone(), tx2_new).await;
     
     // Player 2 should be able to continue and place their bid
     let result = game_manager.handle_player_action(
         game_id,
-        player2,
+        player2.clone(),
         PlayerAction::Bid(Bid { tricks: 1 })
     ).await;
     assert!(result.is_ok());
     
     // Game should have progressed to playing phase
-    let view = game_manager.get_game_state(game_id, player1).await.unwrap();
+    let view = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
     assert_eq!(view.phase, GamePhase::Playing);
 }
 
 #[tokio::test]
 async fn test_disconnect_during_playing_phase() {
     let conn_manager = Arc::new(ConnectionManager::new());
-    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager)));
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
     
-    let (tx1, _rx1) = mpsc::unbounded_channel();
-    let (tx2, _rx2) = mpsc::unbounded_channel();
+    let (tx1, _rx1) = outbound::channel();
+    let (tx2, _rx2) = outbound::channel();
     
     let player1 = conn_manager.add_player(tx1).await;
     let player2 = conn_manager.add_player(tx2).await;
     
     // Create a game and complete bidding
-    let game_id = game_manager.create_game(vec![player1, player2]).await;
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
     
     game_manager.handle_player_action(
         game_id,
-        player1,
+        player1.clone(),
         PlayerAction::Bid(Bid { tricks: 1 })
     ).await.unwrap();
     
     game_manager.handle_player_action(
         game_id,
-        player2,
+        player2.clone(),
         PlayerAction::Bid(Bid { tricks: 1 })
     ).await.unwrap();
     
     // Get player 1's card
-    let view = game_manager.get_game_state(game_id, player1).await.unwrap();
+    let view = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
     let player1_card = view.your_hand[0];
     
     // Player 1 plays a card
     game_manager.handle_player_action(
         game_id,
-        player1,
+        player1.clone(),
         PlayerAction::PlayCard(player1_card)
     ).await.unwrap();
     
     // Player 2 disconnects before playing
-    conn_manager.mark_inactive(player2).await;
+    conn_manager.mark_inactive(player2.clone()).await;
     
     // Player 2 reconnects
-    let (tx2_new, _rx2_new) = mpsc::unbounded_channel();
-    conn_manager.reconnect_player(player2, tx2_new).await;
+    let (tx2_new, _rx2_new) = outbound::channel();
+    conn_manager.reconnect_player(player2.clone(), tx2_new).await;
     
     // Player 2 should be able to get their state and play
-    let view = game_manager.get_game_state(game_id, player2).await.unwrap();
+    let view = game_manager.get_game_state(game_id, player2.clone()).await.unwrap();
     assert_eq!(view.phase, GamePhase::Playing);
     assert_eq!(view.your_hand.len(), 1);
     
     let player2_card = view.your_hand[0];
     let result = game_manager.handle_player_action(
         game_id,
-        player2,
+        player2.clone(),
         PlayerAction::PlayCard(player2_card)
     ).await;
     assert!(result.is_ok());
 }
+
+#[tokio::test]
+async fn test_stale_action_rejected_after_resume() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    
+    let (tx1, _rx1) = outbound::channel();
+    let (tx2, _rx2) = outbound::channel();
+    
+    let player1 = conn_manager.add_player(tx1).await;
+    let player2 = conn_manager.add_player(tx2).await;
+    
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
+    
+    // Player 2 captures the state version, then drops off
+    let stale_version = game_manager.get_game_state(game_id, player2.clone()).await.unwrap().state_version;
+    conn_manager.mark_inactive(player2.clone()).await;
+    
+    // Player 1 acts while player 2 is away
+    game_manager.handle_player_action(
+        game_id,
+        player1.clone(),
+        PlayerAction::Bid(Bid { tricks: 1 })
+    ).await.unwrap();
+    
+    // Resumed session must resync before acting
+    let (tx2_new, _rx2_new) = outbound::channel();
+    conn_manager.reconnect_player(player2.clone(), tx2_new).await;
+    assert!(conn_manager.needs_resync(&player2).await);
+    
+    let result = game_manager.handle_player_action_at_version(
+        game_id,
+        player2.clone(),
+        PlayerAction::Bid(Bid { tricks: 1 }),
+        Some(stale_version),
+    ).await;
+    match result {
+        Err(GameError::StaleState { expected, current }) => {
+            assert_eq!(expected, stale_version);
+            assert!(current > stale_version);
+        }
+        _ => panic!("Expected StaleState error"),
+    }
+    
+    // Acting on the fresh version succeeds
+    let view = game_manager.get_game_state(game_id, player2.clone()).await.unwrap();
+    conn_manager.clear_resync(&player2).await;
+    assert!(!conn_manager.needs_resync(&player2).await);
+    
+    let result = game_manager.handle_player_action_at_version(
+        game_id,
+        player2.clone(),
+        PlayerAction::Bid(Bid { tricks: 1 }),
+        Some(view.state_version),
+    ).await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_usernames_survive_session_expiry() {
+    let conn_manager = Arc::new(ConnectionManager::with_reconnect_timeout(
+        std::time::Duration::from_millis(10)
+    ));
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    
+    let (tx1, _rx1) = outbound::channel();
+    let (tx2, _rx2) = outbound::channel();
+    let player1 = new_player_id();
+    let player2 = new_player_id();
+    conn_manager.register_player(player1.clone(), "alice".to_string(), tx1).await;
+    conn_manager.register_player(player2.clone(), "bob".to_string(), tx2).await;
+    
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
+    
+    // Player 2's session expires entirely
+    conn_manager.mark_inactive(player2.clone()).await;
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    conn_manager.cleanup_expired_sessions().await;
+    
+    let view = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+    assert_eq!(view.player_names.get(&player2), Some(&"bob".to_string()));
+    assert_eq!(conn_manager.get_username(&player2).await, Some("bob".to_string()));
+}
+
+#[tokio::test]
+async fn test_game_trace_cleared_when_game_ends() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+
+    let game_id = game_manager.create_game(vec![new_player_id(), new_player_id()]).await;
+    let trace_filter = game_manager.trace_filter();
+    trace_filter.enable(game_id);
+    assert!(trace_filter.is_traced(game_id));
+
+    game_manager.end_game(game_id).await;
+    assert!(!trace_filter.is_traced(game_id));
+}
+
+#[tokio::test]
+async fn test_blind_bidding_hides_bids_until_bidding_ends() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+
+    let (tx1, _rx1) = outbound::channel();
+    let (tx2, mut rx2) = outbound::channel();
+    let (tx3, _rx3) = outbound::channel();
+    let player1 = conn_manager.add_player(tx1).await;
+    let player2 = conn_manager.add_player(tx2).await;
+    let player3 = conn_manager.add_player(tx3).await;
+
+    let settings = GameSettings { blind_bidding: true, ..Default::default() };
+    let game_id = game_manager.create_game_with_settings(
+        vec![player1.clone(), player2.clone(), player3.clone()],
+        None,
+        settings,
+    ).await;
+    while rx2.try_recv().is_ok() {}
+
+    game_manager.handle_player_action(game_id, player1.clone(), PlayerAction::Bid(Bid { tricks: 1 })).await.unwrap();
+
+    // Other players only learn that a bid was placed
+    let mut saw_hidden_bid = false;
+    while let Ok(Message::Text(text)) = rx2.try_recv() {
+        if let ServerMessage::PlayerAction { player_id, action, .. } = serde_json::from_str(&text).unwrap() {
+            assert_eq!(player_id, player1);
+            assert!(matches!(action, PlayerAction::HiddenBid));
+            saw_hidden_bid = true;
+        }
+    }
+    assert!(saw_hidden_bid);
+
+    let view = game_manager.get_game_state(game_id, player2.clone()).await.unwrap();
+    assert!(view.bids_hidden);
+    assert!(view.current_round.iter().all(|pr| pr.bid == 0));
+
+    // The bidder still sees their own bid
+    let own_view = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+    assert_eq!(own_view.current_round.iter().find(|pr| pr.player_id == player1).unwrap().bid, 1);
+
+    // Clients cannot submit the placeholder action
+    let result = game_manager.handle_player_action(game_id, player2.clone(), PlayerAction::HiddenBid).await;
+    assert!(matches!(result, Err(GameError::InvalidMove(_))));
+
+    // Bids are revealed once bidding is over
+    game_manager.handle_player_action(game_id, player2.clone(), PlayerAction::Bid(Bid { tricks: 1 })).await.unwrap();
+    game_manager.handle_player_action(game_id, player3.clone(), PlayerAction::Bid(Bid { tricks: 1 })).await.unwrap();
+    let view = game_manager.get_game_state(game_id, player2.clone()).await.unwrap();
+    assert!(!view.bids_hidden);
+    assert_eq!(view.current_round.iter().find(|pr| pr.player_id == player1).unwrap().bid, 1);
+}
+
+#[tokio::test]
+async fn test_blind_bid_hint_for_the_last_bidder_is_legal() {
+    use german_bridge_backend::engine::auto_play::estimate_tricks;
+
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let mut players = Vec::new();
+    for _ in 0..3 {
+        let (tx, _rx) = outbound::channel();
+        players.push(conn_manager.add_player(tx).await);
+    }
+    let (tx, mut rx) = outbound::channel();
+    let last = conn_manager.add_player(tx).await;
+    players.push(last.clone());
+
+    let settings = GameSettings { blind_bidding: true, ..Default::default() };
+    let game_id = game_manager.create_game_with_settings(players.clone(), None, settings).await;
+
+    // The others bid so that the last bidder may not bid what their hand looks good for
+    let mut view = game_manager.get_game_state(game_id, last.clone()).await.unwrap();
+    let cards = view.your_hand.len() as u8;
+    let estimate = estimate_tricks(&view.your_hand, view.trump_suit, view.your_hand.len());
+    let mut first = cards - estimate;
+    while view.current_player != last {
+        let action = PlayerAction::Bid(Bid { tricks: std::mem::take(&mut first) });
+        game_manager.handle_player_action(game_id, view.current_player.clone(), action).await.unwrap();
+        view = game_manager.get_game_state(game_id, last.clone()).await.unwrap();
+    }
+    while rx.try_recv().is_ok() {}
+
+    game_manager.send_game_state(game_id, last.clone()).await.unwrap();
+    game_manager.send_hint(game_id, last.clone()).await.unwrap();
+    let mut valid_actions = None;
+    let hint = loop {
+        let msg = tokio::time::timeout(std::time::Duration::from_secs(1), rx.recv()).await.expect("Hint");
+        if let Some(Message::Text(text)) = msg {
+            match serde_json::from_str(&text).unwrap() {
+                ServerMessage::YourTurn { valid_actions: actions, .. } => valid_actions = Some(actions),
+                ServerMessage::Hint { action, .. } => break action,
+                _ => {}
+            }
+        }
+    };
+    let legal: Vec<u8> = valid_actions.expect("YourTurn").iter()
+        .filter_map(|action| match action {
+            PlayerAction::Bid(bid) => Some(bid.tricks),
+            _ => None,
+        })
+        .collect();
+    assert!(!legal.contains(&estimate));
+    assert!(matches!(hint, PlayerAction::Bid(bid) if legal.contains(&bid.tricks)), "{:?} is not one of {:?}", hint, legal);
+}
+
+#[tokio::test]
+async fn test_flooding_connection_is_throttled_then_closed() {
+    use german_bridge_backend::rate_limit::{RateDecision, RateLimitConfig};
+
+    let conn_manager = ConnectionManager::new()
+        .with_rate_limit(RateLimitConfig { burst: 2, per_sec: 1, max_violations: 3 });
+    let (tx, mut rx) = outbound::channel();
+    let player = conn_manager.add_player(tx).await;
+    let ip = "203.0.113.9".parse().unwrap();
+
+    assert_eq!(conn_manager.check_rate(&player, Some(ip)), RateDecision::Allowed);
+    assert_eq!(conn_manager.check_rate(&player, Some(ip)), RateDecision::Allowed);
+    assert_eq!(conn_manager.check_rate(&player, Some(ip)), RateDecision::Throttled);
+    assert_eq!(conn_manager.check_rate(&player, Some(ip)), RateDecision::Throttled);
+    assert_eq!(conn_manager.check_rate(&player, Some(ip)), RateDecision::Disconnect);
+
+    conn_manager.close_connection(&player, "Too many messages; disconnected").await;
+    let Message::Text(text) = rx.recv().await.unwrap() else { panic!("Expected text message") };
+    assert!(matches!(serde_json::from_str(&text).unwrap(), ServerMessage::Error { .. }));
+    assert!(matches!(rx.recv().await.unwrap(), Message::Close(Some(_))));
+}
+
+#[tokio::test]
+async fn test_unanswered_pings_mark_session_inactive() {
+    let conn_manager = ConnectionManager::new();
+    let (tx1, mut rx1) = outbound::channel();
+    let (tx2, mut rx2) = outbound::channel();
+    let silent = conn_manager.add_player(tx1).await;
+    let responsive = conn_manager.add_player(tx2).await;
+
+    for _ in 0..2 {
+        assert!(conn_manager.ping_sessions(2).await.is_empty());
+        assert!(matches!(rx1.try_recv(), Ok(Message::Ping(_))));
+        assert!(matches!(rx2.try_recv(), Ok(Message::Ping(_))));
+        // The pong resets the counter
+        conn_manager.update_activity(responsive.clone()).await;
+    }
+
+    let dropped = conn_manager.ping_sessions(2).await;
+    assert_eq!(dropped, vec![silent.clone()]);
+    assert!(matches!(rx1.try_recv(), Ok(Message::Close(Some(_)))));
+    assert!(matches!(rx2.try_recv(), Ok(Message::Ping(_))));
+    assert_eq!(conn_manager.get_active_players().await, vec![responsive]);
+
+    // Reconnecting starts counting afresh
+    let (tx1, mut rx1) = outbound::channel();
+    assert!(conn_manager.reconnect_player(silent.clone(), tx1).await.is_some());
+    assert!(conn_manager.ping_sessions(2).await.is_empty());
+    assert!(matches!(rx1.try_recv(), Ok(Message::Ping(_))));
+}
+
+#[tokio::test]
+async fn test_your_turn_carries_bidding_insights() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+
+    let (tx1, mut rx1) = outbound::channel();
+    let (tx2, _rx2) = outbound::channel();
+    let (tx3, mut rx3) = outbound::channel();
+    let player1 = conn_manager.add_player(tx1).await;
+    let player2 = conn_manager.add_player(tx2).await;
+    let player3 = conn_manager.add_player(tx3).await;
+
+    let settings = GameSettings { blind_bidding: true, ..Default::default() };
+    let game_id = game_manager.create_game_with_settings(
+        vec![player1.clone(), player2.clone(), player3.clone()],
+        None,
+        settings,
+    ).await;
+    game_manager.handle_player_action(game_id, player1.clone(), PlayerAction::Bid(Bid { tricks: 0 })).await.unwrap();
+    game_manager.handle_player_action(game_id, player2.clone(), PlayerAction::Bid(Bid { tricks: 0 })).await.unwrap();
+
+    let mut insights = None;
+    while let Ok(Message::Text(text)) = rx3.try_recv() {
+        if let ServerMessage::YourTurn { bidding, .. } = serde_json::from_str(&text).unwrap() {
+            insights = bidding;
+        }
+    }
+    let insights = insights.expect("YourTurn with bidding insights");
+
+    let view = game_manager.get_game_state(game_id, player3.clone()).await.unwrap();
+    let trumps = view.your_hand.iter().filter(|c| Some(c.suit) == view.trump_suit).count();
+    assert_eq!(insights.hand_size, view.your_hand.len());
+    assert_eq!(insights.trump_count, trumps);
+    assert!(insights.last_bidder);
+    assert_eq!(insights.forbidden_bid, Some(view.your_hand.len() as u8));
+
+    // Blind bidding still reports who has bid, but not how much
+    let bidders: Vec<_> = insights.bids.iter().map(|b| b.player_id.clone()).collect();
+    assert_eq!(bidders, vec![player1, player2]);
+    assert!(insights.bids.iter().all(|b| b.bid.is_none()));
+
+    // Once bidding is over there is nothing to help with
+    while rx1.try_recv().is_ok() {}
+    game_manager.handle_player_action(game_id, player3.clone(), PlayerAction::Bid(Bid { tricks: 0 })).await.unwrap();
+    let mut saw_play_turn = false;
+    while let Ok(Message::Text(text)) = rx1.try_recv() {
+        if let ServerMessage::YourTurn { bidding, .. } = serde_json::from_str(&text).unwrap() {
+            assert!(bidding.is_none());
+            saw_play_turn = true;
+        }
+    }
+    assert!(saw_play_turn);
+}
+
+#[tokio::test]
+async fn test_set_suit_encoding_returns_display_profile() {
+    use german_bridge_backend::accessibility::SuitEncoding;
+    use german_bridge_backend::router::MessageRouter;
+
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let db = test_db().await;
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db));
+    let router = MessageRouter::new(lobby_manager, game_manager, Arc::clone(&conn_manager));
+
+    let (tx, mut rx) = outbound::channel();
+    let player = conn_manager.add_player(tx).await;
+
+    router.route_message(player.clone(), ClientMessage::SetSuitEncoding { encoding: SuitEncoding::Patterned }).await.unwrap();
+    assert_eq!(conn_manager.suit_encoding(&player).await, Some(SuitEncoding::Patterned));
+
+    let Message::Text(text) = rx.recv().await.unwrap() else { panic!("Expected text message") };
+    match serde_json::from_str(&text).unwrap() {
+        ServerMessage::DisplayProfile { suit_encoding, suits, cards } => {
+            assert_eq!(suit_encoding, SuitEncoding::Patterned);
+            assert!(suits.iter().all(|s| s.pattern.is_some()));
+            assert_eq!(cards.len(), 52);
+        }
+        other => panic!("Expected DisplayProfile, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_lobbies_and_games_can_be_named_by_short_code() {
+    use german_bridge_backend::router::MessageRouter;
+    use german_bridge_backend::short_code::{self, IdOrCode};
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db.clone()));
+    let router = MessageRouter::new(Arc::clone(&lobby_manager), Arc::clone(&game_manager), Arc::clone(&conn_manager));
+
+    let alice = insert_user(&db, "alice").await;
+    let bob = insert_user(&db, "bob").await;
+    let (tx, mut alice_rx) = outbound::channel();
+    conn_manager.register_player(alice.clone(), "alice".to_string(), tx).await;
+    let (tx, mut bob_rx) = outbound::channel();
+    conn_manager.register_player(bob.clone(), "bob".to_string(), tx).await;
+
+    let settings = GameSettings { player_count: 2, ..Default::default() };
+    router.route_message(alice.clone(), ClientMessage::CreateLobby { settings }).await.unwrap();
+    let Message::Text(text) = alice_rx.recv().await.unwrap() else { panic!("Expected text message") };
+    let (lobby_id, code) = match serde_json::from_str(&text).unwrap() {
+        ServerMessage::LobbyCreated { lobby_id, code } => (lobby_id, code),
+        other => panic!("Expected LobbyCreated, got {:?}", other),
+    };
+
+    // Codes are typed loosely over voice chat
+    let spoken = format!("{}-{}", &code.as_str()[..3], &code.as_str()[3..]).to_lowercase();
+    let join: ClientMessage = serde_json::from_value(serde_json::json!({
+        "type": "JoinLobby",
+        "payload": { "lobby_id": spoken },
+    })).unwrap();
+    while bob_rx.try_recv().is_ok() {}
+    router.route_message(bob.clone(), join).await.unwrap();
+    let Message::Text(text) = bob_rx.recv().await.unwrap() else { panic!("Expected text message") };
+    match serde_json::from_str(&text).unwrap() {
+        ServerMessage::LobbyJoined { lobby } => {
+            assert_eq!(lobby.id, lobby_id);
+            assert_eq!(lobby.code, code);
+        }
+        other => panic!("Expected LobbyJoined, got {:?}", other),
+    }
+    game_manager.db_writes().flush().await;
+    let row = entities::lobby::Entity::find_by_id(lobby_id).one(&db).await.unwrap().unwrap();
+    assert_eq!(row.code.as_deref(), Some(code.as_str()));
+
+    let game_id = game_manager.create_game_from_lobby(vec![alice.clone(), bob.clone()], Some(lobby_id)).await;
+    let view = game_manager.get_game_state(game_id, alice.clone()).await.unwrap();
+    let by_code = IdOrCode::Code(view.game_code.clone());
+    assert_eq!(game_manager.find_game(&by_code).await, Some(game_id));
+    assert_eq!(short_code::find_game_id(&db, &by_code).await.unwrap(), Some(game_id));
+}
+
+#[tokio::test]
+async fn test_time_sync_and_turn_deadlines() {
+    use german_bridge_backend::router::MessageRouter;
+
+    let db = test_db().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db));
+    let router = MessageRouter::new(Arc::clone(&lobby_manager), Arc::clone(&game_manager), Arc::clone(&conn_manager));
+
+    let (tx, mut rx) = outbound::channel();
+    let player = conn_manager.add_player(tx).await;
+    let before = chrono::Utc::now().timestamp_millis();
+    router.route_message(player.clone(), ClientMessage::TimeSync { client_time_ms: 42 }).await.unwrap();
+    let Message::Text(text) = rx.recv().await.unwrap() else { panic!("Expected text message") };
+    match serde_json::from_str(&text).unwrap() {
+        ServerMessage::TimeSync { client_time_ms, server_received_ms, server_sent_ms } => {
+            assert_eq!(client_time_ms, 42);
+            assert!(server_received_ms >= before);
+            assert!(server_sent_ms >= server_received_ms);
+        }
+        other => panic!("Expected TimeSync, got {:?}", other),
+    }
+
+    let (tx, mut other_rx) = outbound::channel();
+    let other = conn_manager.add_player(tx).await;
+    let game_id = game_manager.create_game(vec![player.clone(), other.clone()]).await;
+    let current = game_manager.get_game_state(game_id, player.clone()).await.unwrap().current_player;
+
+    // The first turn runs on the lobby's limit from the start
+    let deadline = game_manager.get_game_state(game_id, player.clone()).await.unwrap().turn_deadline.unwrap();
+    assert_eq!(deadline.player_id, current);
+    assert!(deadline.remaining_secs == 30 || deadline.remaining_secs == 29);
+    while rx.try_recv().is_ok() {}
+    while other_rx.try_recv().is_ok() {}
+
+    let current_rx = if current == player { &mut rx } else { &mut other_rx };
+    game_manager.start_turn_timer(game_id, 60).await;
+    let expected = chrono::Utc::now().timestamp_millis() + 60_000;
+
+    // Everyone's view shows the same deadline for the current player
+    let deadline = game_manager.get_game_state(game_id, player.clone()).await.unwrap().turn_deadline.unwrap();
+    assert_eq!(deadline.player_id, current);
+    assert!((deadline.deadline_ms - expected).abs() < 1_000, "deadline {} not ~60s out", deadline.deadline_ms);
+    assert!(deadline.remaining_secs == 60 || deadline.remaining_secs == 59);
+
+    // The current player gets YourTurn again, now with the deadline
+    let mut turn_deadline = None;
+    while let Ok(Message::Text(text)) = current_rx.try_recv() {
+        if let ServerMessage::YourTurn { deadline, .. } = serde_json::from_str(&text).unwrap() {
+            turn_deadline = deadline;
+        }
+    }
+    assert_eq!(turn_deadline.map(|d| d.deadline_ms), Some(deadline.deadline_ms));
+
+    // Acting ends the turn and its deadline; the next player's turn gets the lobby's limit
+    game_manager.handle_player_action(game_id, current.clone(), PlayerAction::Bid(Bid { tricks: 0 })).await.unwrap();
+    let deadline = game_manager.get_game_state(game_id, other).await.unwrap().turn_deadline.unwrap();
+    assert_ne!(deadline.player_id, current);
+    assert!(deadline.remaining_secs == 30 || deadline.remaining_secs == 29);
+    game_manager.cancel_turn_timer(game_id).await;
+}
+
+#[tokio::test]
+async fn test_turn_timers_run_for_games_started_through_the_router() {
+    use german_bridge_backend::router::MessageRouter;
+    use german_bridge_backend::settings_policy::SettingsPolicy;
+    use german_bridge_backend::short_code::IdOrCode;
+
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let db = test_db().await;
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db));
+    let policy = SettingsPolicy::new(GameSettings::default(), 2, 4, 1, 120);
+    let router = MessageRouter::new(Arc::clone(&lobby_manager), Arc::clone(&game_manager), Arc::clone(&conn_manager))
+        .with_settings_policy(policy);
+
+    let (tx, _host_rx) = outbound::channel();
+    let host = conn_manager.add_player(tx).await;
+    let (tx, mut guest_rx) = outbound::channel();
+    let guest = conn_manager.add_player(tx).await;
+    let settings = GameSettings { player_count: 2, turn_timeout_secs: 1, ..Default::default() };
+    router.route_message(host.clone(), ClientMessage::CreateLobby { settings }).await.unwrap();
+    let lobby_id = conn_manager.rooms().lobby_of(&host).unwrap();
+    router.route_message(guest.clone(), ClientMessage::JoinLobby { lobby_id: IdOrCode::Id(lobby_id) }).await.unwrap();
+    for player in [&host, &guest] {
+        router.route_message(player.clone(), ClientMessage::SetReady { ready: true }).await.unwrap();
+    }
+    router.route_message(host.clone(), ClientMessage::StartGame).await.unwrap();
+    let game_id = conn_manager.rooms().active_game(&host).unwrap();
+
+    // The first turn has a deadline without anyone arming it
+    let view = game_manager.get_game_state(game_id, host.clone()).await.unwrap();
+    let current = view.current_player.clone();
+    let waiting = if current == host { guest.clone() } else { host.clone() };
+    assert_eq!(view.turn_deadline.unwrap().player_id, current);
+
+    // Moves the game turns down, from anyone, leave the current turn's clock running
+    let out_of_turn = ClientMessage::PlaceBid { bid: Bid { tricks: 0 }, state_version: Some(view.state_version) };
+    let illegal = ClientMessage::PlaceBid { bid: Bid { tricks: 99 }, state_version: Some(view.state_version) };
+    assert!(router.route_message(waiting.clone(), out_of_turn).await.is_err());
+    assert!(router.route_message(current.clone(), illegal).await.is_err());
+    assert_eq!(game_manager.get_game_state(game_id, host.clone()).await.unwrap().state_version, view.state_version);
+
+    // When it runs out the bid is played for them and the next turn's clock starts
+    tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+    let after = game_manager.get_game_state(game_id, host.clone()).await.unwrap();
+    assert!(after.state_version > view.state_version);
+    assert_eq!(after.current_player, waiting);
+    let mut auto_played = false;
+    while let Ok(Message::Text(text)) = guest_rx.try_recv() {
+        if let Ok(ServerMessage::PlayerAction { player_id, .. }) = serde_json::from_str(&text) {
+            auto_played |= player_id == current;
+        }
+    }
+    assert!(auto_played);
+    game_manager.end_game(game_id).await;
+}
+
+#[tokio::test]
+async fn test_turn_deadlines_follow_the_phase_time_limits() {
+    use german_bridge_backend::engine::rules::{RuleSet, TimerPolicy};
+
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let mut players = Vec::new();
+    let mut receivers = std::collections::HashMap::new();
+    for _ in 0..2 {
+        let (tx, rx) = outbound::channel();
+        let player = conn_manager.add_player(tx).await;
+        receivers.insert(player.clone(), rx);
+        players.push(player);
+    }
+    // Bidding has no limit of its own, so it takes the rules' default over the flat setting
+    let timer = TimerPolicy { turn_secs: Some(40), bidding_secs: None, playing_secs: Some(90), ..Default::default() };
+    let settings = GameSettings { rules: RuleSet { timer, ..Default::default() }, turn_timeout_secs: 30, ..Default::default() };
+    let game_id = game_manager.create_game_with_settings(players.clone(), None, settings).await;
+
+    // Each YourTurn carries the limit of the phase it is in
+    async fn next_turn(rx: &mut OutboundReceiver) -> (u64, PlayerAction) {
+        loop {
+            let msg = tokio::time::timeout(std::time::Duration::from_secs(1), rx.recv()).await.expect("YourTurn");
+            if let Some(Message::Text(text)) = msg {
+                if let Ok(ServerMessage::YourTurn { deadline, valid_actions, .. }) = serde_json::from_str(&text) {
+                    return (deadline.expect("deadline").remaining_secs, valid_actions[0].clone());
+                }
+            }
+        }
+    }
+    for _ in 0..players.len() {
+        let current = game_manager.get_game_state(game_id, players[0].clone()).await.unwrap().current_player;
+        let (secs, bid) = next_turn(receivers.get_mut(&current).unwrap()).await;
+        assert!(secs == 40 || secs == 39, "bidding turn has {}s", secs);
+        game_manager.handle_player_action(game_id, current, bid).await.unwrap();
+    }
+    let view = game_manager.get_game_state(game_id, players[0].clone()).await.unwrap();
+    assert_eq!(view.phase, GamePhase::Playing);
+    let (secs, _) = next_turn(receivers.get_mut(&view.current_player).unwrap()).await;
+    assert!(secs == 90 || secs == 89, "playing turn has {}s", secs);
+    game_manager.end_game(game_id).await;
+}
+
+#[test]
+fn test_token_roles_gate_rest_access() {
+    use axum::http::{header, HeaderMap, StatusCode};
+    use german_bridge_backend::auth::{JwtKeys, Role};
+    use german_bridge_backend::handlers::auth::authorize;
+
+    let bearer = |token: String| {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, format!("Bearer {}", token).parse().unwrap());
+        headers
+    };
+
+    let keys = JwtKeys::development();
+    let moderator = keys.create_jwt("u1", "mod", false, Role::Moderator).unwrap();
+    assert_eq!(keys.verify_jwt(&moderator).unwrap().role, Role::Moderator);
+    assert!(authorize(&keys, &bearer(moderator.clone()), Role::Player).is_ok());
+    assert!(authorize(&keys, &bearer(moderator.clone()), Role::Moderator).is_ok());
+    let (status, message) = authorize(&keys, &bearer(moderator), Role::Admin).unwrap_err();
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(message, "Requires admin role");
+
+    let player = keys.create_jwt("u2", "pat", false, Role::Player).unwrap();
+    assert_eq!(authorize(&keys, &bearer(player), Role::Moderator).unwrap_err().0, StatusCode::FORBIDDEN);
+    assert_eq!(authorize(&keys, &HeaderMap::new(), Role::Player).unwrap_err().0, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_close_lobby_requires_moderator_role() {
+    use german_bridge_backend::auth::Role;
+    use german_bridge_backend::error::RouterError;
+    use german_bridge_backend::router::MessageRouter;
+    use german_bridge_backend::short_code::IdOrCode;
+
+    let db = test_db().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db));
+    let router = MessageRouter::new(Arc::clone(&lobby_manager), Arc::clone(&game_manager), Arc::clone(&conn_manager));
+
+    let (tx, mut host_rx) = outbound::channel();
+    let host = conn_manager.add_player(tx).await;
+    let (tx, mut mod_rx) = outbound::channel();
+    let moderator = conn_manager.add_player(tx).await;
+
+    let lobby_id = lobby_manager.create_lobby(host.clone(), GameSettings::default()).await;
+    let close = ClientMessage::CloseLobby { lobby_id: IdOrCode::Id(lobby_id) };
+
+    // Without the role the request is refused and the lobby stays
+    let result = router.route_message(moderator.clone(), close.clone()).await;
+    assert!(matches!(result, Err(RouterError::Forbidden(Role::Moderator))));
+    let Message::Text(text) = mod_rx.recv().await.unwrap() else { panic!("Expected text message") };
+    assert!(text.contains("Requires moderator role"));
+    assert!(lobby_manager.get_lobby(lobby_id).await.is_some());
+
+    conn_manager.set_role(&moderator, Role::Moderator).await;
+    router.route_message(moderator, close).await.unwrap();
+    assert!(lobby_manager.get_lobby(lobby_id).await.is_none());
+    let Message::Text(text) = host_rx.recv().await.unwrap() else { panic!("Expected text message") };
+    assert!(matches!(serde_json::from_str(&text).unwrap(), ServerMessage::KickedFromLobby { lobby_id: id } if id == lobby_id));
+}
+
+#[tokio::test]
+async fn test_idle_lobbies_are_closed() {
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db.clone()));
+
+    let alice = insert_user(&db, "alice").await;
+    let bob = insert_user(&db, "bob").await;
+    let (tx, mut alice_rx) = outbound::channel();
+    conn_manager.register_player(alice.clone(), "alice".to_string(), tx).await;
+    let (tx, _bob_rx) = outbound::channel();
+    conn_manager.register_player(bob.clone(), "bob".to_string(), tx).await;
+
+    let idle = lobby_manager.create_lobby(alice.clone(), GameSettings::default()).await;
+    let busy = lobby_manager.create_lobby(bob.clone(), GameSettings::default()).await;
+    tokio::time::sleep(std::time::Duration::from_millis(60)).await;
+    // A ready change counts as activity
+    lobby_manager.set_ready(busy, bob.clone(), true).await.unwrap();
+
+    let expired = lobby_manager.expire_idle_lobbies(std::time::Duration::from_millis(50)).await;
+    assert_eq!(expired, vec![idle]);
+    assert!(lobby_manager.get_lobby(idle).await.is_none());
+    assert!(lobby_manager.get_lobby(busy).await.is_some());
+
+    let Message::Text(text) = alice_rx.recv().await.unwrap() else { panic!("Expected text message") };
+    assert!(matches!(serde_json::from_str(&text).unwrap(), ServerMessage::LobbyExpired { lobby_id } if lobby_id == idle));
+
+    game_manager.db_writes().flush().await;
+    let rows = entities::lobby::Entity::find().all(&db).await.unwrap();
+    let closed_at = |id| rows.iter().find(|row| row.id == id).unwrap().closed_at;
+    assert!(closed_at(idle).is_some());
+    assert!(closed_at(busy).is_none());
+}
+
+#[tokio::test]
+async fn test_lobby_list_broadcasts_are_coalesced_into_deltas() {
+    use german_bridge_backend::router::MessageRouter;
+
+    let db = test_db().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db));
+    let router = MessageRouter::new(Arc::clone(&lobby_manager), Arc::clone(&game_manager), Arc::clone(&conn_manager))
+        .with_lobby_list_interval(std::time::Duration::from_millis(100));
+
+    let (tx, mut watcher_rx) = outbound::channel();
+    conn_manager.add_player(tx).await;
+    let mut hosts = Vec::new();
+    for _ in 0..3 {
+        let (tx, rx) = outbound::channel();
+        hosts.push((conn_manager.add_player(tx).await, rx));
+    }
+
+    let next_delta = |rx: &mut OutboundReceiver| match rx.try_recv() {
+        Ok(Message::Text(text)) => match serde_json::from_str(&text).unwrap() {
+            ServerMessage::LobbyListDelta { changed, removed } => Some((changed, removed)),
+            other => panic!("Expected LobbyListDelta, got {:?}", other),
+        },
+        _ => None,
+    };
+
+    // The first change goes out at once; the next two wait for the interval and share a delta
+    for (host, _) in &hosts {
+        let settings = GameSettings { player_count: 2, ..Default::default() };
+        router.route_message(host.clone(), ClientMessage::CreateLobby { settings }).await.unwrap();
+    }
+    let (changed, _) = next_delta(&mut watcher_rx).expect("immediate delta");
+    assert_eq!(changed.len(), 1);
+    assert!(next_delta(&mut watcher_rx).is_none());
+
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    let (changed, removed) = next_delta(&mut watcher_rx).expect("trailing delta");
+    assert_eq!(changed.len(), 2);
+    assert!(removed.is_empty());
+
+    // Leaving an only-player lobby removes it
+    tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+    router.route_message(hosts[0].0.clone(), ClientMessage::LeaveLobby).await.unwrap();
+    let (changed, removed) = next_delta(&mut watcher_rx).expect("removal delta");
+    assert!(changed.is_empty());
+    assert_eq!(removed.len(), 1);
+
+    let stats = router.lobby_feed_stats();
+    assert_eq!(stats.requested, 4);
+    assert_eq!(stats.broadcasts, 3);
+    assert_eq!(stats.suppressed, 1);
+}
+
+#[tokio::test]
+async fn test_room_registry_follows_lobby_and_game_membership() {
+    use german_bridge_backend::room::RoomId;
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db.clone());
+    let rooms = conn_manager.rooms();
+
+    let mut receivers = Vec::new();
+    let mut players = Vec::new();
+    for _ in 0..3 {
+        let (tx, rx) = outbound::channel();
+        players.push(conn_manager.add_player(tx).await);
+        receivers.push(rx);
+    }
+    let (alice, bob, carol) = (players[0].clone(), players[1].clone(), players[2].clone());
+
+    let settings = GameSettings { player_count: 2, ..Default::default() };
+    let lobby_id = lobby_manager.create_lobby(alice.clone(), settings.clone()).await;
+    lobby_manager.join_lobby(lobby_id, bob.clone()).await.unwrap();
+    let other_lobby = lobby_manager.create_lobby(carol.clone(), settings).await;
+
+    assert_eq!(rooms.lobby_of(&bob), Some(lobby_id));
+    assert_eq!(rooms.lobby_of(&carol), Some(other_lobby));
+    assert_eq!(rooms.room_mates(&alice), vec![bob.clone()]);
+
+    // Starting the game moves both players from the lobby's room into the game's
+    lobby_manager.set_ready(lobby_id, bob.clone(), true).await.unwrap();
+    let game_id = lobby_manager.start_game(lobby_id, alice.clone()).await.unwrap();
+    assert!(rooms.members(RoomId::Lobby(lobby_id)).is_empty());
+    assert_eq!(rooms.lobby_of(&alice), None);
+    assert_eq!(rooms.games_of(&bob), vec![game_id]);
+
+    // Disconnect notices only reach the player's own table
+    for rx in receivers.iter_mut() {
+        while rx.try_recv().is_ok() {}
+    }
+    conn_manager.broadcast_to_room_mates(&alice, ServerMessage::PlayerLeft { player_id: alice.clone() }).await;
+    assert!(receivers[1].try_recv().is_ok());
+    assert!(receivers[2].try_recv().is_err());
+
+    game_manager.end_game(game_id).await;
+    assert!(rooms.games_of(&alice).is_empty());
+    assert!(rooms.room_mates(&bob).is_empty());
+}
+
+// ============================================================================
+// Persistence Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_empty_lobby_is_soft_deleted_and_restorable() {
+    use german_bridge_backend::retention;
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = LobbyManager::new(Arc::clone(&game_manager), conn_manager, db.clone());
+    
+    let host_id = insert_user(&db, "alice").await;
+    let guest_id = insert_user(&db, "bob").await;
+    let lobby_id = lobby_manager.create_lobby(host_id.clone(), GameSettings::default()).await;
+    lobby_manager.join_lobby(lobby_id, guest_id.clone()).await.unwrap();
+    lobby_manager.leave_lobby(lobby_id, guest_id.clone()).await.unwrap();
+    lobby_manager.leave_lobby(lobby_id, host_id.clone()).await.unwrap();
+    game_manager.db_writes().flush().await;
+    
+    // Rows are kept with deletion markers
+    let row = entities::lobby::Entity::find_by_id(lobby_id).one(&db).await.unwrap().unwrap();
+    assert!(row.deleted_at.is_some());
+    assert!(row.closed_at.is_some());
+    let members = entities::lobby_player::Entity::find().all(&db).await.unwrap();
+    assert_eq!(members.len(), 2);
+    assert!(members.iter().all(|m| m.left_at.is_some()));
+    
+    // Admin restore reopens it with the last member, and the lobby manager takes it back
+    assert!(retention::restore_lobby(&db, lobby_id).await.unwrap());
+    let row = entities::lobby::Entity::find_by_id(lobby_id).one(&db).await.unwrap().unwrap();
+    assert!(row.deleted_at.is_none());
+    assert!(row.closed_at.is_none());
+    assert!(!retention::restore_lobby(&db, lobby_id).await.unwrap());
+    assert!(lobby_manager.reload_lobby(lobby_id).await);
+    let lobby = lobby_manager.get_lobby(lobby_id).await.unwrap();
+    assert_eq!(lobby.players, vec![host_id.clone()]);
+    assert_eq!(lobby.host, host_id);
+    lobby_manager.join_lobby(lobby_id, guest_id.clone()).await.unwrap();
+    game_manager.db_writes().flush().await;
+    let members = entities::lobby_player::Entity::find().all(&db).await.unwrap();
+    assert!(members.iter().all(|m| m.left_at.is_none()));
+
+    // A member who moved on to another lobby stays there, so this one stays closed
+    lobby_manager.leave_lobby(lobby_id, guest_id.clone()).await.unwrap();
+    lobby_manager.leave_lobby(lobby_id, host_id.clone()).await.unwrap();
+    let other = lobby_manager.create_lobby(host_id.clone(), GameSettings::default()).await;
+    game_manager.db_writes().flush().await;
+    assert!(retention::restore_lobby(&db, lobby_id).await.unwrap());
+    assert!(!lobby_manager.reload_lobby(lobby_id).await);
+    assert!(lobby_manager.get_lobby(lobby_id).await.is_none());
+    assert_eq!(lobby_manager.get_lobby(other).await.unwrap().players, vec![host_id]);
+    game_manager.db_writes().flush().await;
+    let row = entities::lobby::Entity::find_by_id(lobby_id).one(&db).await.unwrap().unwrap();
+    assert!(row.closed_at.is_some());
+}
+
+#[tokio::test]
+async fn test_purge_removes_only_expired_soft_deletes() {
+    use german_bridge_backend::retention;
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    
+    let player1 = insert_user(&db, "alice").await;
+    let player2 = insert_user(&db, "bob").await;
+    let game_id = game_manager.create_game(vec![player1, player2]).await;
+    
+    assert!(retention::soft_delete_game(&db, game_id).await.unwrap());
+    
+    // Still within the retention period
+    let (_, games) = retention::purge_deleted(&db, chrono::Duration::days(30)).await.unwrap();
+    assert_eq!(games, 0);
+    assert!(entities::game::Entity::find_by_id(game_id).one(&db).await.unwrap().is_some());
+    
+    // Past the retention period
+    let (_, games) = retention::purge_deleted(&db, chrono::Duration::zero() - chrono::Duration::seconds(1)).await.unwrap();
+    assert_eq!(games, 1);
+    assert!(entities::game::Entity::find_by_id(game_id).one(&db).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_cluster_placement_prefers_least_loaded_node() {
+    use german_bridge_backend::cluster::ClusterRegistry;
+
+    let db = test_db_with_schema().await;
+    let node_a = ClusterRegistry::new(db.clone(), "a".to_string(), "wss://a.example.com".to_string(), 100, None);
+    let node_b = ClusterRegistry::new(db.clone(), "b".to_string(), "wss://b.example.com".to_string(), 100, None);
+    
+    node_a.heartbeat(80).await.unwrap();
+    node_b.heartbeat(10).await.unwrap();
+    assert_eq!(node_a.live_nodes().await.unwrap().len(), 2);
+    
+    // Busy node A points new games at node B
+    let hint = node_a.placement_hint(80, None).await.unwrap();
+    assert_eq!(hint.node_id, "b");
+    assert_eq!(hint.public_url, "wss://b.example.com");
+    
+    // Node B keeps games local
+    assert!(node_b.placement_hint(10, None).await.is_none());
+    
+    // Heartbeats update the existing row
+    node_b.heartbeat(95).await.unwrap();
+    assert!(node_a.placement_hint(80, None).await.is_none());
+}
+
+#[tokio::test]
+async fn test_cluster_placement_prefers_the_players_region() {
+    use german_bridge_backend::cluster::ClusterRegistry;
+    use german_bridge_backend::region::Region;
+
+    let db = test_db_with_schema().await;
+    let (eu, us) = (Region::parse("eu-west"), Region::parse("us-east"));
+    let node_eu = ClusterRegistry::new(db.clone(), "eu".to_string(), "wss://eu.example.com".to_string(), 100, eu.clone());
+    let node_us = ClusterRegistry::new(db.clone(), "us".to_string(), "wss://us.example.com".to_string(), 100, us.clone());
+    node_eu.heartbeat(10).await.unwrap();
+    node_us.heartbeat(60).await.unwrap();
+
+    let live = node_eu.live_nodes().await.unwrap();
+    assert!(live.iter().any(|n| n.node_id == "us" && n.region == us));
+
+    // A US player is sent home even though the EU node is quieter
+    assert_eq!(node_eu.placement_hint(10, us.as_ref()).await.unwrap().node_id, "us");
+    assert!(node_us.placement_hint(60, us.as_ref()).await.is_none());
+    // Without a region, load decides
+    assert_eq!(node_us.placement_hint(60, None).await.unwrap().node_id, "eu");
+}
+
+#[tokio::test]
+async fn test_bug_report_is_stored_and_listed() {
+    use german_bridge_backend::bug_report::{self, GameJournal};
+
+    let db = test_db_with_schema().await;
+    let players = vec![new_player_id(), new_player_id()];
+    let mut state = GameState::new(players.clone());
+    let mut journal = GameJournal::new(players.clone());
+    journal.record_deal(&state);
+
+    // Corrupt the state: a player loses a card without playing it
+    let hand = state.hands.get_mut(&players[0]).unwrap();
+    let card = hand.cards()[0];
+    hand.play_card(card).unwrap();
+    let violation = state.check_invariants().unwrap_err();
+
+    let game_id = Uuid::new_v4();
+    let bundle = journal
+        .build_report(game_id, &violation, &GameSettings::default(), &state)
+        .unwrap();
+    assert!(!bundle.reason.contains(&players[0]));
+    let report_id = bug_report::file_report(&db, bundle).await.unwrap();
+
+    let reports = bug_report::recent_reports(&db, 10).await.unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].id, report_id);
+    assert_eq!(reports[0].game_id, game_id);
+
+    let stored = bug_report::get_report(&db, report_id).await.unwrap().unwrap();
+    assert_eq!(stored.bundle["deals"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_leaderboards_are_segmented() {
+    use german_bridge_backend::engine::schedule::RoundSchedule;
+    use german_bridge_backend::leaderboard::{self, PlayerBracket, Segment, Variant};
+    use sea_orm::{sea_query::Expr, ColumnTrait, QueryFilter};
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+
+    let alice = insert_user(&db, "alice").await;
+    let bob = insert_user(&db, "bob").await;
+    let carol = insert_user(&db, "carol").await;
+
+    // Record a finished game with the given final scores
+    let finish = |game_id: Uuid, scores: Vec<(PlayerId, i32)>| {
+        let db = db.clone();
+        async move {
+            entities::game::Entity::update_many()
+                .col_expr(entities::game::Column::CompletedAt, Expr::value(chrono::Utc::now()))
+                .filter(entities::game::Column::Id.eq(game_id))
+                .exec(&db).await.unwrap();
+            for (pid, score) in scores {
+                entities::game_player::Entity::update_many()
+                    .col_expr(entities::game_player::Column::FinalScore, Expr::value(score))
+                    .filter(entities::game_player::Column::GameId.eq(game_id))
+                    .filter(entities::game_player::Column::PlayerId.eq(Uuid::parse_str(&pid).unwrap()))
+                    .exec(&db).await.unwrap();
+            }
+        }
+    };
+
+    let heads_up = game_manager.create_game(vec![alice.clone(), bob.clone()]).await;
+    finish(heads_up, vec![(alice.clone(), 50), (bob.clone(), 10)]).await;
+
+    let three_way = game_manager.create_game(vec![alice.clone(), bob.clone(), carol.clone()]).await;
+    finish(three_way, vec![(alice.clone(), 5), (bob.clone(), 40), (carol.clone(), 20)]).await;
+
+    let up_and_down = GameSettings { round_schedule: RoundSchedule::UpAndDown, ..Default::default() };
+    let classic = game_manager.create_game_with_settings(vec![bob.clone(), carol.clone()], None, up_and_down).await;
+    finish(classic, vec![(bob.clone(), 30), (carol.clone(), 60)]).await;
+
+    let board = leaderboard::compute(&db, Segment { players: PlayerBracket::Two, variant: Variant::Standard }, 10).await.unwrap();
+    let names: Vec<_> = board.entries.iter().map(|e| e.username.as_str()).collect();
+    assert_eq!(names, vec!["alice", "bob"]);
+    assert_eq!(board.entries[0].wins, 1);
+    assert_eq!(board.entries[0].games_played, 1);
+
+    let board = leaderboard::compute(&db, Segment { players: PlayerBracket::Three, variant: Variant::Standard }, 10).await.unwrap();
+    assert_eq!(board.entries[0].username, "bob");
+    assert_eq!(board.entries.len(), 3);
+
+    let board = leaderboard::compute(&db, Segment { players: PlayerBracket::Two, variant: Variant::UpAndDown }, 10).await.unwrap();
+    assert_eq!(board.entries[0].username, "carol");
+    assert_eq!(board.entries[1].total_score, 30);
+
+    let board = leaderboard::compute(&db, Segment { players: PlayerBracket::FourPlus, variant: Variant::Standard }, 10).await.unwrap();
+    assert!(board.entries.is_empty());
+}
+
+#[tokio::test]
+async fn test_public_endpoints_are_rate_limited_and_cached_until_refresh() {
+    use axum::{body::Body, extract::ConnectInfo, http::{Request, StatusCode}, routing::get, Router};
+    use german_bridge_backend::client_ip::TrustedProxies;
+    use german_bridge_backend::leaderboard::LeaderboardCache;
+    use german_bridge_backend::public_api::{self, PublicApi, PublicApiConfig};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tower::ServiceExt;
+
+    let db = test_db_with_schema().await;
+    let proxy = std::net::SocketAddr::from(([10, 0, 0, 2], 40000));
+    let api = Arc::new(PublicApi::new(PublicApiConfig { burst: 3, per_sec: 1, cache_ttl: std::time::Duration::from_secs(60) })
+        .with_trusted_proxies(TrustedProxies::parse("10.0.0.0/8").unwrap()));
+    let leaderboards = LeaderboardCache::new();
+    let hook_api = Arc::clone(&api);
+    leaderboards.on_refresh(move || hook_api.cache().invalidate("/api/leaderboard"));
+
+    let renders = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&renders);
+    let app = Router::new()
+        .route("/api/leaderboard", get(move || async move { counter.fetch_add(1, Ordering::SeqCst).to_string() }))
+        .route_layer(axum::middleware::from_fn_with_state(Arc::clone(&api), public_api::cache_responses))
+        .route_layer(axum::middleware::from_fn_with_state(Arc::clone(&api), public_api::rate_limit));
+    let request_from = |app: &Router, client: &str| {
+        let mut request = Request::get("/api/leaderboard?players=Two")
+            .header("x-forwarded-for", client)
+            .body(Body::empty())
+            .unwrap();
+        request.extensions_mut().insert(ConnectInfo(proxy));
+        app.clone().oneshot(request)
+    };
+    let request = |app: &Router| request_from(app, "203.0.113.1");
+
+    let first = request(&app).await.unwrap();
+    assert_eq!(first.headers()["x-cache"], "miss");
+    let second = request(&app).await.unwrap();
+    assert_eq!(second.headers()["x-cache"], "hit");
+    assert_eq!(renders.load(Ordering::SeqCst), 1);
+
+    // The stats job's refresh drops the cached board
+    leaderboards.refresh(&db, 10).await;
+    assert!(api.cache().is_empty());
+    let third = request(&app).await.unwrap();
+    assert_eq!(third.headers()["x-cache"], "miss");
+    assert_eq!(renders.load(Ordering::SeqCst), 2);
+
+    // Cache hits count against the limit too
+    let limited = request(&app).await.unwrap();
+    assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(limited.headers()["retry-after"], "1");
+
+    // Another client behind the same proxy has its own bucket
+    let other = request_from(&app, "203.0.113.2").await.unwrap();
+    assert_eq!(other.status(), StatusCode::OK);
+
+    // A request whose client can't be told apart is refused rather than lumped in with others
+    let unknown = app.clone().oneshot(Request::get("/api/leaderboard").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(unknown.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[tokio::test]
+async fn test_weekly_digest_respects_opt_out_and_is_idempotent() {
+    use german_bridge_backend::digest;
+    use sea_orm::{sea_query::Expr, ColumnTrait, QueryFilter};
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+
+    let alice = insert_user(&db, "alice").await;
+    let bob = insert_user(&db, "bob").await;
+    entities::user::Entity::update_many()
+        .col_expr(entities::user::Column::WeeklyDigest, Expr::value(false))
+        .filter(entities::user::Column::Username.eq("bob"))
+        .exec(&db).await.unwrap();
+
+    let week = digest::last_complete_week(chrono::Utc::now());
+    let mut game_ids = Vec::new();
+    for (alice_score, bob_score) in [(30, 10), (5, 25)] {
+        let game_id = game_manager.create_game(vec![alice.clone(), bob.clone()]).await;
+        entities::game::Entity::update_many()
+            .col_expr(entities::game::Column::CompletedAt, Expr::value(week + chrono::Duration::days(2)))
+            .filter(entities::game::Column::Id.eq(game_id))
+            .exec(&db).await.unwrap();
+        for (pid, score) in [(&alice, alice_score), (&bob, bob_score)] {
+            entities::game_player::Entity::update_many()
+                .col_expr(entities::game_player::Column::FinalScore, Expr::value(score))
+                .filter(entities::game_player::Column::GameId.eq(game_id))
+                .filter(entities::game_player::Column::PlayerId.eq(Uuid::parse_str(pid).unwrap()))
+                .exec(&db).await.unwrap();
+        }
+        game_ids.push(game_id);
+    }
+
+    assert_eq!(digest::generate_weekly_digests(&db, week).await.unwrap(), 1);
+    assert_eq!(digest::generate_weekly_digests(&db, week).await.unwrap(), 0);
+
+    let digests = digest::digests_for_user(&db, Uuid::parse_str(&alice).unwrap()).await.unwrap();
+    assert_eq!(digests.len(), 1);
+    assert_eq!(digests[0].games_played, 2);
+    assert_eq!(digests[0].wins, 1);
+    assert_eq!(digests[0].best_game_id, Some(game_ids[0]));
+    assert_eq!(digests[0].best_score, Some(30));
+    assert!(digests[0].rating_change.is_none());
+
+    // Opted-out users get nothing, and other weeks are untouched
+    assert!(digest::digests_for_user(&db, Uuid::parse_str(&bob).unwrap()).await.unwrap().is_empty());
+    assert_eq!(digest::generate_weekly_digests(&db, week - chrono::Duration::weeks(1)).await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_trump_stats_feed_profiles_and_digests() {
+    use german_bridge_backend::{deal_review, digest, trump_stats};
+    use german_bridge_backend::engine::card::Suit;
+    use german_bridge_backend::protocol::PlayerRoundResult;
+    use sea_orm::{sea_query::Expr, ActiveModelTrait, ColumnTrait, QueryFilter, Set};
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let alice = insert_user(&db, "alice").await;
+    let bob = insert_user(&db, "bob").await;
+    let game_id = game_manager.create_game(vec![alice.clone(), bob.clone()]).await;
+    game_manager.db_writes().flush().await;
+
+    // Three rounds under Hearts, one under Spades; only rounds with a recorded result count
+    let rounds = [(2, Suit::Hearts, 1, 1), (3, Suit::Hearts, 2, 2), (4, Suit::Hearts, 1, 0), (5, Suit::Spades, 3, 3)];
+    for (round_number, trump, bid, tricks_won) in rounds {
+        let deal = deal_review::Deal { round_number, trump_suit: Some(trump), hands: Vec::new() };
+        deal_review::record_deal(&db, game_id, &deal).await.unwrap();
+        let results = vec![
+            PlayerRoundResult { player_id: alice.clone(), bid, tricks_won, penalty: 0, score: if bid == tricks_won { 10 + 2 * bid as i32 } else { -2 } },
+            PlayerRoundResult { player_id: bob.clone(), bid: 0, tricks_won: 0, penalty: 0, score: 10 },
+        ];
+        entities::game_round::ActiveModel {
+            id: sea_orm::ActiveValue::NotSet,
+            game_id: Set(game_id),
+            round_number: Set(round_number as i32),
+            player_results: Set(serde_json::json!(results)),
+        }.insert(&db).await.unwrap();
+    }
+
+    let stats = trump_stats::stats_for_user(&db, Uuid::parse_str(&alice).unwrap()).await.unwrap();
+    assert_eq!(stats.iter().map(|s| (s.trump, s.rounds, s.made)).collect::<Vec<_>>(),
+        vec![(Some(Suit::Hearts), 3, 2), (Some(Suit::Spades), 1, 1)]);
+    // Spades scored more but was trump only once
+    assert_eq!(trump_stats::best_trump(&stats, trump_stats::MIN_ROUNDS_FOR_BEST), Some(Suit::Hearts));
+
+    let week = digest::last_complete_week(chrono::Utc::now());
+    entities::game::Entity::update_many()
+        .col_expr(entities::game::Column::CompletedAt, Expr::value(week + chrono::Duration::days(1)))
+        .filter(entities::game::Column::Id.eq(game_id))
+        .exec(&db).await.unwrap();
+    entities::game_player::Entity::update_many()
+        .col_expr(entities::game_player::Column::FinalScore, Expr::value(30))
+        .filter(entities::game_player::Column::GameId.eq(game_id))
+        .exec(&db).await.unwrap();
+    assert_eq!(digest::generate_weekly_digests(&db, week).await.unwrap(), 2);
+
+    let digests = digest::digests_for_user(&db, Uuid::parse_str(&alice).unwrap()).await.unwrap();
+    assert_eq!(digests[0].best_trump.as_deref(), Some("Hearts"));
+    let digests = digest::digests_for_user(&db, Uuid::parse_str(&bob).unwrap()).await.unwrap();
+    assert_eq!(digests[0].best_trump.as_deref(), Some("Hearts"));
+}
+
+#[tokio::test]
+async fn test_departed_player_seat_is_auto_played() {
+    use german_bridge_backend::protocol::DeparturePolicy;
+
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let player1 = new_player_id();
+    let player2 = new_player_id();
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
+
+    // Player 2 is gone; player 1 bids and the game moves on without waiting
+    game_manager.handle_player_departed(game_id, player2.clone()).await.unwrap();
+    game_manager.handle_player_action(game_id, player1.clone(), PlayerAction::Bid(Bid { tricks: 1 })).await.unwrap();
+
+    let view = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+    assert_eq!(view.departed.get(&player2), Some(&DeparturePolicy::BotTakeover));
+    assert_eq!(view.phase, GamePhase::Playing);
+    assert_eq!(view.current_player, player1);
+}
+
+#[tokio::test]
+async fn test_open_seat_is_taken_over_with_hand_and_score() {
+    use sea_orm::{ColumnTrait, QueryFilter};
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let host = insert_user(&db, "host").await;
+    let guest = insert_user(&db, "guest").await;
+    let leaver = insert_user(&db, "leaver").await;
+    let newcomer = insert_user(&db, "newcomer").await;
+    let game_id = game_manager.create_game(vec![host.clone(), guest.clone(), leaver.clone()]).await;
+
+    // Only departed seats can be opened, and only by the host
+    assert!(matches!(game_manager.open_seat(game_id, &host, leaver.clone()).await, Err(GameError::SeatUnavailable)));
+    game_manager.handle_player_departed(game_id, leaver.clone()).await.unwrap();
+    assert!(matches!(game_manager.open_seat(game_id, &guest, leaver.clone()).await, Err(GameError::NotGameHost)));
+    assert!(matches!(game_manager.take_seat(game_id, leaver.clone(), newcomer.clone()).await, Err(GameError::SeatUnavailable)));
+    game_manager.open_seat(game_id, &host, leaver.clone()).await.unwrap();
+
+    let seats = game_manager.open_seats().await;
+    assert_eq!(seats.len(), 1);
+    assert_eq!((seats[0].game_id, seats[0].seat.as_str(), seats[0].username.as_str()), (game_id, leaver.as_str(), "leaver"));
+
+    let before = game_manager.get_game_state(game_id, leaver.clone()).await.unwrap();
+    assert!(matches!(game_manager.take_seat(game_id, leaver.clone(), guest.clone()).await, Err(GameError::AlreadySeated)));
+    game_manager.take_seat(game_id, leaver.clone(), newcomer.clone()).await.unwrap();
+
+    let after = game_manager.get_game_state(game_id, newcomer.clone()).await.unwrap();
+    assert_eq!(after.your_hand, before.your_hand);
+    assert_eq!(after.scores.get(&newcomer), before.scores.get(&leaver));
+    assert!(after.departed.is_empty());
+    assert_eq!(after.player_names.get(&newcomer).map(String::as_str), Some("newcomer"));
+    let bid_before = before.current_round.iter().find(|r| r.player_id == leaver).map(|r| r.bid);
+    let bid_after = after.current_round.iter().find(|r| r.player_id == newcomer).map(|r| r.bid);
+    assert_eq!(bid_after, bid_before);
+    assert!(game_manager.open_seats().await.is_empty());
+    assert!(conn_manager.rooms().games_of(&newcomer).contains(&game_id));
+    assert!(conn_manager.rooms().games_of(&leaver).is_empty());
+
+    // The old id no longer belongs to the game
+    assert!(matches!(game_manager.get_game_state(game_id, leaver.clone()).await, Err(GameError::PlayerNotInGame)));
+    let seated: Vec<String> = entities::game_player::Entity::find()
+        .filter(entities::game_player::Column::GameId.eq(game_id))
+        .all(&db).await.unwrap()
+        .into_iter().map(|row| row.player_id.to_string()).collect();
+    assert!(seated.contains(&newcomer) && !seated.contains(&leaver));
+}
+
+#[tokio::test]
+async fn test_host_gets_pacing_report_when_game_ends() {
+    use german_bridge_backend::engine::rules::RuleSet;
+
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let (tx1, mut host_rx) = outbound::channel();
+    let (tx2, mut guest_rx) = outbound::channel();
+    let host = conn_manager.add_player(tx1).await;
+    let guest = conn_manager.add_player(tx2).await;
+    let settings = GameSettings {
+        rules: RuleSet { round_limit: Some(1), ..Default::default() },
+        pace_notices: true,
+        ..Default::default()
+    };
+    let game_id = game_manager.create_hosted_game(vec![host.clone(), guest.clone()], None, settings, host.clone()).await;
+
+    loop {
+        let view = game_manager.get_game_state(game_id, host.clone()).await.unwrap();
+        let current = view.current_player.clone();
+        let action = match view.phase {
+            GamePhase::Bidding => {
+                let constraint = view.bid_constraint.unwrap();
+                PlayerAction::Bid(Bid { tricks: (0..=1).find(|&bid| constraint.allows(bid)).unwrap() })
+            }
+            GamePhase::Playing => {
+                let hand = game_manager.get_game_state(game_id, current.clone()).await.unwrap().your_hand;
+                PlayerAction::PlayCard(hand[0])
+            }
+            _ => break,
+        };
+        game_manager.handle_player_action(game_id, current, action).await.unwrap();
+    }
+
+    let received = |rx: &mut OutboundReceiver| {
+        let mut messages = Vec::new();
+        while let Ok(Message::Text(text)) = rx.try_recv() {
+            messages.push(serde_json::from_str::<serde_json::Value>(&text).unwrap());
+        }
+        messages
+    };
+    let report = received(&mut host_rx).into_iter()
+        .find(|msg| msg["type"] == "PacingReport")
+        .expect("host gets the pacing report");
+    let players = report["payload"]["players"].as_array().unwrap();
+    assert_eq!(players.len(), 2);
+    assert_eq!(players[0]["player_id"], host.as_str());
+    assert_eq!(players.iter().map(|p| p["bids"].as_u64().unwrap()).sum::<u64>(), 2);
+    assert_eq!(players.iter().map(|p| p["plays"].as_u64().unwrap()).sum::<u64>(), 2);
+
+    // Quick rounds and guests get neither
+    let guest_messages = received(&mut guest_rx);
+    assert!(guest_messages.iter().all(|msg| msg["type"] != "PacingReport" && msg["type"] != "PaceNotice"));
+}
+
+#[tokio::test]
+async fn test_bid_constraint_survives_reconnect_and_auto_bids() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let (tx1, _rx1) = outbound::channel();
+    let (tx2, _rx2) = outbound::channel();
+    let player1 = conn_manager.add_player(tx1).await;
+    let player2 = conn_manager.add_player(tx2).await;
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
+
+    let view = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+    let (first, last) = if view.current_player == player1 { (player1, player2) } else { (player2, player1) };
+    let constraint = view.bid_constraint.unwrap();
+    assert_eq!(constraint.player_id, first);
+    assert_eq!(constraint.max_bid, 1);
+    assert!(constraint.forbidden.is_empty());
+
+    // One card dealt and one trick bid, so the last bidder may not bid 0
+    game_manager.handle_player_action(game_id, first.clone(), PlayerAction::Bid(Bid { tricks: 1 })).await.unwrap();
+    conn_manager.mark_inactive(last.clone()).await;
+    let (tx, _rx) = outbound::channel();
+    conn_manager.reconnect_player(last.clone(), tx).await.unwrap();
+
+    let constraint = game_manager.get_game_state(game_id, last.clone()).await.unwrap().bid_constraint.unwrap();
+    assert_eq!(constraint.player_id, last);
+    assert_eq!(constraint.forbidden, vec![0]);
+    assert!(!constraint.allows(0));
+    assert!(constraint.allows(1));
+
+    // The departed last bidder's auto-bid must respect the restriction rather than wedge the game
+    game_manager.handle_player_departed(game_id, last.clone()).await.unwrap();
+    let view = game_manager.get_game_state(game_id, first.clone()).await.unwrap();
+    assert_eq!(view.phase, GamePhase::Playing);
+    let auto_bid = view.current_round.iter().find(|r| r.player_id == last).unwrap().bid;
+    assert_eq!(auto_bid, 1);
+    assert_eq!(view.bid_constraint, None);
+}
+
+#[tokio::test]
+async fn test_forfeited_player_gets_no_final_score() {
+    use german_bridge_backend::protocol::DeparturePolicy;
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let player1 = insert_user(&db, "alice").await;
+    let player2 = insert_user(&db, "bob").await;
+
+    let settings = GameSettings { departure_policy: DeparturePolicy::Forfeit, ..Default::default() };
+    let game_id = game_manager.create_game_with_settings(vec![player1.clone(), player2.clone()], None, settings).await;
+    game_manager.handle_player_departed(game_id, player2.clone()).await.unwrap();
+
+    // Player 1 plays out the whole game alone
+    loop {
+        let view = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+        match view.phase {
+            GamePhase::GameComplete => break,
+            GamePhase::RoundComplete => game_manager.handle_start_next_round(game_id, player1.clone()).await.unwrap(),
+            phase => {
+                assert_eq!(view.current_player, player1);
+                // Try candidate moves until one is legal
+                let candidates: Vec<PlayerAction> = if phase == GamePhase::Bidding {
+                    (0..=view.your_hand.len() as u8).map(|tricks| PlayerAction::Bid(Bid { tricks })).collect()
+                } else {
+                    view.your_hand.iter().map(|card| PlayerAction::PlayCard(*card)).collect()
+                };
+                let mut played = false;
+                for action in candidates {
+                    if game_manager.handle_player_action(game_id, player1.clone(), action).await.is_ok() {
+                        played = true;
+                        break;
+                    }
+                }
+                assert!(played);
+            }
+        }
+    }
+
+    game_manager.db_writes().flush().await;
+    let rows = entities::game_player::Entity::find().all(&db).await.unwrap();
+    let score_of = |pid: &PlayerId| rows.iter().find(|r| r.player_id.to_string() == *pid).unwrap().final_score;
+    assert!(score_of(&player1).is_some());
+    assert!(score_of(&player2).is_none());
+
+    // Whatever the scores, a forfeited player cannot win
+    use german_bridge_backend::engine::tiebreak::{GameOutcome, WinReason};
+    let game = entities::game::Entity::find_by_id(game_id).one(&db).await.unwrap().unwrap();
+    assert!(game.completed_at.is_some());
+    let outcome: GameOutcome = serde_json::from_value(game.outcome.unwrap()).unwrap();
+    assert_eq!(outcome, GameOutcome { winners: vec![player1.clone()], reason: WinReason::HighestScore });
+
+    // Only the remaining player is placed
+    let place_of = |pid: &PlayerId| rows.iter().find(|r| r.player_id.to_string() == *pid).unwrap().placement;
+    assert_eq!(place_of(&player1), Some(1));
+    assert_eq!(place_of(&player2), None);
+}
+
+#[tokio::test]
+async fn test_lobby_preset_saved_and_applied() {
+    use german_bridge_backend::lobby_preset;
+    use german_bridge_backend::router::MessageRouter;
+    use german_bridge_backend::error::PresetError;
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db.clone()));
+    let router = MessageRouter::new(Arc::clone(&lobby_manager), game_manager, Arc::clone(&conn_manager));
+
+    let alice = insert_user(&db, "alice").await;
+    let bob = insert_user(&db, "bob").await;
+    let alice_id = Uuid::parse_str(&alice).unwrap();
+
+    // Saving under the same name replaces the preset
+    let first = GameSettings { player_count: 3, ..Default::default() };
+    let house_rules = GameSettings { player_count: 3, blind_bidding: true, ..Default::default() };
+    lobby_preset::save_preset(&db, alice_id, "Thursday", &first).await.unwrap();
+    let preset = lobby_preset::save_preset(&db, alice_id, "Thursday", &house_rules).await.unwrap();
+    assert!(matches!(lobby_preset::save_preset(&db, alice_id, "  ", &first).await, Err(PresetError::InvalidName(_))));
+
+    let presets = lobby_manager.presets(&alice).await.unwrap();
+    assert_eq!(presets.len(), 1);
+    assert!(presets[0].settings.blind_bidding);
+
+    // Other users cannot apply it
+    let (tx, _bob_rx) = outbound::channel();
+    conn_manager.register_player(bob.clone(), "bob".to_string(), tx).await;
+    let result = router.route_message(bob.clone(), ClientMessage::CreateLobbyFromPreset { preset_id: preset.id }).await;
+    assert!(result.is_err());
+
+    let (tx, mut rx) = outbound::channel();
+    conn_manager.register_player(alice.clone(), "alice".to_string(), tx).await;
+    router.route_message(alice.clone(), ClientMessage::CreateLobbyFromPreset { preset_id: preset.id }).await.unwrap();
+
+    let Message::Text(text) = rx.recv().await.unwrap() else { panic!("Expected text message") };
+    let lobby_id = match serde_json::from_str(&text).unwrap() {
+        ServerMessage::LobbyCreated { lobby_id, .. } => lobby_id,
+        other => panic!("Expected LobbyCreated, got {:?}", other),
+    };
+    let lobby = lobby_manager.get_lobby(lobby_id).await.unwrap();
+    assert_eq!(lobby.max_players, 3);
+    assert!(lobby.settings.blind_bidding);
+
+    lobby_preset::delete_preset(&db, alice_id, preset.id).await.unwrap();
+    assert!(lobby_manager.presets(&alice).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_lobby_creation_respects_settings_policy() {
+    use german_bridge_backend::router::MessageRouter;
+    use german_bridge_backend::settings_policy::SettingsPolicy;
+
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let db = test_db().await;
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db));
+    let policy = SettingsPolicy::new(GameSettings::default(), 2, 4, 15, 120);
+    let router = MessageRouter::new(Arc::clone(&lobby_manager), game_manager, Arc::clone(&conn_manager))
+        .with_settings_policy(policy);
+
+    let (tx, _rx) = outbound::channel();
+    let player = conn_manager.add_player(tx).await;
+
+    let marathon = GameSettings { player_count: 8, ..Default::default() };
+    assert!(router.route_message(player.clone(), ClientMessage::CreateLobby { settings: marathon }).await.is_err());
+    let blitz = GameSettings { turn_timeout_secs: 5, ..Default::default() };
+    assert!(router.route_message(player.clone(), ClientMessage::CreateLobby { settings: blitz }).await.is_err());
+    assert!(router.route_message(player.clone(), ClientMessage::JoinQueue { player_count: 6, backfill: false }).await.is_err());
+    assert!(lobby_manager.list_lobbies().await.is_empty());
+
+    router.route_message(player.clone(), ClientMessage::CreateLobby { settings: GameSettings::default() }).await.unwrap();
+    assert_eq!(lobby_manager.list_lobbies().await.len(), 1);
+}
+
+#[tokio::test]
+async fn test_disabled_features_are_rejected_at_dispatch() {
+    use german_bridge_backend::error::RouterError;
+    use german_bridge_backend::features::{Feature, FeatureFlags};
+    use german_bridge_backend::router::MessageRouter;
+
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let db = test_db().await;
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db));
+    let features = Arc::new(FeatureFlags::default());
+    let router = MessageRouter::new(lobby_manager, game_manager, Arc::clone(&conn_manager))
+        .with_features(Arc::clone(&features));
+
+    let (tx, _rx) = outbound::channel();
+    let queued = conn_manager.add_player(tx).await;
+    let (tx, mut rx) = outbound::channel();
+    let player = conn_manager.add_player(tx).await;
+    let (tx, _bot_rx) = outbound::channel();
+    let bot = conn_manager.add_player(tx).await;
+    conn_manager.set_bot(&bot, true).await;
+    router.route_message(queued.clone(), ClientMessage::JoinQueue { player_count: 4, backfill: false }).await.unwrap();
+    router.route_message(bot.clone(), ClientMessage::JoinQueue { player_count: 4, backfill: false }).await.unwrap();
+    while rx.try_recv().is_ok() {}
+
+    // Switching ranked off takes effect for the next message, with a structured error
+    features.set(Feature::Ranked, false);
+    let result = router.route_message(player.clone(), ClientMessage::JoinQueue { player_count: 4, backfill: false }).await;
+    assert!(matches!(result, Err(RouterError::FeatureDisabled(Feature::Ranked))));
+    let Message::Text(text) = rx.recv().await.unwrap() else { panic!("Expected text message") };
+    match serde_json::from_str(&text).unwrap() {
+        ServerMessage::FeatureDisabled { feature, .. } => assert_eq!(feature, Feature::Ranked),
+        other => panic!("Expected FeatureDisabled, got {:?}", other),
+    }
+    // Players already queued can still leave
+    router.route_message(queued, ClientMessage::LeaveQueue).await.unwrap();
+
+    // Turning bots off silences connected bots but keeps their keepalive working
+    features.set(Feature::Bots, false);
+    let result = router.route_message(bot.clone(), ClientMessage::LeaveQueue).await;
+    assert!(matches!(result, Err(RouterError::FeatureDisabled(Feature::Bots))));
+    router.route_message(bot.clone(), ClientMessage::Ping).await.unwrap();
+    features.set(Feature::Bots, true);
+    router.route_message(bot, ClientMessage::LeaveQueue).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_deprecated_forms_are_announced_once_per_session() {
+    use german_bridge_backend::deprecation::Deprecation;
+    use german_bridge_backend::router::MessageRouter;
+
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let db = test_db().await;
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db));
+    let router = MessageRouter::new(lobby_manager, game_manager, Arc::clone(&conn_manager));
+    let (tx, mut rx) = outbound::channel();
+    let player = conn_manager.add_player(tx).await;
+    while rx.try_recv().is_ok() {}
+
+    let notices = |rx: &mut OutboundReceiver| {
+        let mut notices = Vec::new();
+        while let Ok(Message::Text(text)) = rx.try_recv() {
+            if let ServerMessage::DeprecationNotice { feature, remove_after } = serde_json::from_str(&text).unwrap() {
+                notices.push((feature, remove_after));
+            }
+        }
+        notices
+    };
+    let unversioned = || ClientMessage::PlaceBid { bid: Bid { tricks: 1 }, state_version: None };
+
+    // Not in a game, so the bids fail, but the form is still announced, once
+    let _ = router.route_message(player.clone(), unversioned()).await;
+    let _ = router.route_message(player.clone(), unversioned()).await;
+    let _ = router.route_message(player.clone(), ClientMessage::PlaceBid { bid: Bid { tricks: 1 }, state_version: Some(3) }).await;
+    let seen = notices(&mut rx);
+    assert_eq!(seen, vec![(Deprecation::UnversionedAction, Deprecation::UnversionedAction.remove_after())]);
+
+    // A different form gets its own notice, and a new session hears about both again
+    router.note_deprecated(&player, Deprecation::BinaryFrames).await;
+    assert_eq!(notices(&mut rx).len(), 1);
+    router.handle_session_expired(player.clone()).await;
+    let _ = router.route_message(player.clone(), unversioned()).await;
+    assert_eq!(notices(&mut rx).len(), 1);
+}
+
+#[tokio::test]
+async fn test_lan_discovery_answers_probes_only() {
+    use german_bridge_backend::discovery::{self, Announcement};
+    use tokio::net::UdpSocket;
+
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let server = socket.local_addr().unwrap();
+    let announcer = discovery::spawn_announcer(socket, Announcement::new("Club night", 8080));
+    let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let mut buf = [0u8; 512];
+
+    // Unrelated datagrams get no reply
+    client.send_to(b"hello", server).await.unwrap();
+    let silent = tokio::time::timeout(std::time::Duration::from_millis(100), client.recv_from(&mut buf)).await;
+    assert!(silent.is_err());
+
+    client.send_to(discovery::PROBE, server).await.unwrap();
+    let (len, from) = tokio::time::timeout(std::time::Duration::from_secs(2), client.recv_from(&mut buf))
+        .await.unwrap().unwrap();
+    assert_eq!(from, server);
+    let announcement = Announcement::parse(&buf[..len]).unwrap();
+    assert_eq!((announcement.name.as_str(), announcement.port, announcement.ws_path.as_str()), ("Club night", 8080, "/ws"));
+    assert!(Announcement::parse(br#"{"service":"other","name":"x","port":1,"ws_path":"/","version":"1"}"#).is_none());
+    announcer.abort();
+}
+
+#[tokio::test]
+async fn test_transcript_shows_actions_and_only_the_viewers_hands() {
+    use german_bridge_backend::protocol::{DeparturePolicy, PlayerAction};
+    use german_bridge_backend::transcript;
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = GameManager::new(Arc::clone(&conn_manager), db.clone());
+    let player1 = insert_user(&db, "alice").await;
+    let player2 = insert_user(&db, "bob").await;
+
+    let settings = GameSettings { departure_policy: DeparturePolicy::BotTakeover, ..Default::default() };
+    let game_id = game_manager.create_game_with_settings(vec![player1.clone(), player2.clone()], None, settings).await;
+    game_manager.handle_player_departed(game_id, player1.clone()).await.unwrap();
+    game_manager.handle_player_departed(game_id, player2.clone()).await.unwrap();
+    game_manager.db_writes().flush().await;
+
+    let game = entities::game::Entity::find_by_id(game_id).one(&db).await.unwrap().unwrap();
+    let deals = german_bridge_backend::deal_review::deals_for_game(&db, game_id).await.unwrap();
+    let transcript = transcript::build(&db, &game, &player1).await.unwrap();
+
+    assert_eq!(transcript.players.len(), 2);
+    assert!(transcript.players.iter().any(|p| p.player_id == player2 && p.username.as_deref() == Some("bob")));
+    assert_eq!(transcript.rounds.len(), 26);
+    let mut last_version = 0;
+    for (round, deal) in transcript.rounds.iter().zip(&deals) {
+        // Only alice's own hand is included
+        assert_eq!(round.your_hand, deal.hands[0].cards);
+        assert_eq!(round.results.len(), 2);
+        // Two bids, then every card of both hands
+        assert_eq!(round.actions.len(), 2 + 2 * round.round_number);
+        assert!(matches!(round.actions[0].action, PlayerAction::Bid(_)));
+        assert!(round.actions.iter().all(|a| a.version > last_version));
+        last_version = round.actions.last().unwrap().version;
+    }
+    let bobs = transcript::build(&db, &game, &player2).await.unwrap();
+    assert_eq!(bobs.rounds[25].your_hand, deals[25].hands[1].cards);
+}
+
+#[tokio::test]
+async fn test_queue_backfills_opted_in_players_with_bots() {
+    use german_bridge_backend::router::MessageRouter;
+
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let db = test_db_with_schema().await;
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db.clone()));
+    let router = MessageRouter::new(lobby_manager, Arc::clone(&game_manager), Arc::clone(&conn_manager))
+        .with_queue_backfill(std::time::Duration::ZERO);
+
+    let opted_in = insert_user(&db, "alice").await;
+    let (tx, mut rx) = outbound::channel();
+    conn_manager.register_player(opted_in.clone(), "alice".to_string(), tx).await;
+    let (tx, mut waiting_rx) = outbound::channel();
+    let waiting = conn_manager.add_player(tx).await;
+    router.route_message(opted_in.clone(), ClientMessage::JoinQueue { player_count: 4, backfill: true }).await.unwrap();
+    router.route_message(waiting.clone(), ClientMessage::JoinQueue { player_count: 4, backfill: false }).await.unwrap();
+    let Message::Text(text) = rx.recv().await.unwrap() else { panic!("Expected text message") };
+    match serde_json::from_str(&text).unwrap() {
+        ServerMessage::QueueJoined { backfill_after_secs, .. } => assert_eq!(backfill_after_secs, Some(0)),
+        other => panic!("Expected QueueJoined, got {:?}", other),
+    }
+
+    router.backfill_queues().await;
+
+    let mut backfilled = None;
+    while let Ok(Message::Text(text)) = rx.try_recv() {
+        if let ServerMessage::QueueBackfilled { game_id, bots, message } = serde_json::from_str(&text).unwrap() {
+            assert_eq!(bots, 3);
+            assert!(message.contains("unrated"));
+            backfilled = Some(game_id);
+        }
+    }
+    let game_id = backfilled.expect("QueueBackfilled sent");
+    let view = game_manager.get_game_state(game_id, opted_in.clone()).await.unwrap();
+    assert!(view.unrated);
+    assert_eq!(view.players.len(), 4);
+    assert_eq!(view.departed.len(), 3);
+    assert!(view.player_names.values().any(|name| name == "Bot 1"));
+    // Bots have played up to the human's turn
+    assert_eq!(view.current_player, opted_in);
+    let game = entities::game::Entity::find_by_id(game_id).one(&db).await.unwrap().unwrap();
+    assert!(game.unrated);
+
+    // Players who did not opt in keep waiting for a full table
+    while let Ok(Message::Text(text)) = waiting_rx.try_recv() {
+        assert!(!text.contains("GameStarting"));
+    }
+    router.route_message(waiting, ClientMessage::LeaveQueue).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_lobby_list_shows_region_and_latency() {
+    use german_bridge_backend::region::Region;
+
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let lobby_manager = LobbyManager::new(game_manager, Arc::clone(&conn_manager), test_db().await);
+
+    let (tx, _rx) = outbound::channel();
+    let host = conn_manager.add_player(tx).await;
+    conn_manager.set_region(&host, Region::parse("eu-west")).await;
+    lobby_manager.create_lobby(host.clone(), GameSettings::default()).await;
+
+    let lobby = lobby_manager.list_lobbies().await.remove(0);
+    assert_eq!(lobby.region, Region::parse("eu-west"));
+    // Nothing measured before the first keepalive round trip
+    assert_eq!(lobby.latency_ms, None);
+
+    conn_manager.ping_sessions(3).await;
+    conn_manager.record_pong(&host).await;
+    assert!(conn_manager.latency_ms(&host).await.is_some());
+    let lobby = lobby_manager.list_lobbies().await.remove(0);
+    assert_eq!(lobby.latency_ms.map(|ms| ms % 10), Some(0));
+}
+
+#[tokio::test]
+async fn test_queue_matches_within_a_region_first() {
+    use german_bridge_backend::region::Region;
+    use german_bridge_backend::router::MessageRouter;
+
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let db = test_db().await;
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db));
+    let router = MessageRouter::new(lobby_manager, game_manager, Arc::clone(&conn_manager))
+        .with_region_matching(std::time::Duration::from_secs(60));
+
+    let mut players = Vec::new();
+    for region in ["eu-west", "us-east", "eu-west"] {
+        let (tx, _rx) = outbound::channel();
+        let player = conn_manager.add_player(tx).await;
+        conn_manager.set_region(&player, Region::parse(region)).await;
+        router.route_message(player.clone(), ClientMessage::JoinQueue { player_count: 2, backfill: false }).await.unwrap();
+        players.push(player);
+    }
+
+    // The two EU players were seated together; the US player keeps waiting for a neighbour
+    let rooms = conn_manager.rooms();
+    assert_eq!(rooms.games_of(&players[0]).len(), 1);
+    assert_eq!(rooms.games_of(&players[0]), rooms.games_of(&players[2]));
+    router.run_matchmaking().await;
+    assert!(rooms.games_of(&players[1]).is_empty());
+}
+
+#[tokio::test]
+async fn test_player_actions_carry_the_resulting_turn() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+
+    let (tx1, mut rx1) = outbound::channel();
+    let player1 = conn_manager.add_player(tx1).await;
+    let player2 = new_player_id();
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
+    while rx1.try_recv().is_ok() {}
+
+    // Every PlayerAction matches the state the action left, trick by trick
+    let mut tricks_seen = Vec::new();
+    let mut trick_completes = Vec::new();
+    loop {
+        let view = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+        if view.phase == GamePhase::RoundComplete {
+            break;
+        }
+        let actor = view.current_player.clone();
+        let actor_view = game_manager.get_game_state(game_id, actor.clone()).await.unwrap();
+        // A one card round: bid what's allowed, then play the only card
+        let action = match actor_view.bid_constraint {
+            Some(constraint) => PlayerAction::Bid(Bid { tricks: (0..=constraint.max_bid).find(|&b| constraint.allows(b)).unwrap() }),
+            None => PlayerAction::PlayCard(actor_view.your_hand[0]),
+        };
+        game_manager.handle_player_action(game_id, actor.clone(), action).await.unwrap();
+
+        let after = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+        let mut turn = None;
+        while let Ok(Message::Text(text)) = rx1.try_recv() {
+            match serde_json::from_str(&text).unwrap() {
+                ServerMessage::PlayerAction { player_id, turn: sent, .. } => {
+                    assert_eq!(player_id, actor);
+                    turn = Some(sent);
+                }
+                trick @ ServerMessage::TrickComplete { .. } => trick_completes.push(trick),
+                _ => {}
+            }
+        }
+        let turn = turn.expect("no PlayerAction sent");
+        assert_eq!(
+            (&turn.next_player, turn.phase, &turn.current_trick, turn.state_version),
+            (&after.current_player, after.phase, &after.current_trick, after.state_version),
+        );
+        if turn.phase != GamePhase::Bidding {
+            tricks_seen.push(turn.current_trick.len());
+        }
+    }
+
+    // The first card lies on the table; the second takes the trick, ending the one-card round
+    assert_eq!(tricks_seen, vec![0, 1, 0]);
+
+    // The trick comes back whole, with the round's standings
+    let [ServerMessage::TrickComplete { winner, cards, tricks, round_complete }] = &trick_completes[..] else {
+        panic!("expected one TrickComplete, got {:?}", trick_completes);
+    };
+    assert!(*round_complete);
+    assert_eq!(cards.len(), 2);
+    assert_eq!(tricks.iter().map(|t| &t.player_id).collect::<Vec<_>>(), vec![&player1, &player2]);
+    let view = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+    for (count, result) in tricks.iter().zip(&view.current_round) {
+        assert_eq!((count.bid, count.tricks_won), (result.bid, result.tricks_won));
+    }
+    assert_eq!(tricks.iter().find(|t| t.tricks_won == 1).map(|t| &t.player_id), Some(winner));
+}
+
+#[tokio::test]
+async fn test_game_messages_are_sequenced_and_replayable() {
+    use german_bridge_backend::game_events::SequencedMessage;
+
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let (tx1, mut rx1) = outbound::channel();
+    let player1 = conn_manager.add_player(tx1).await;
+    let (tx2, _rx2) = outbound::channel();
+    let player2 = conn_manager.add_player(tx2).await;
+
+    async fn next(rx: &mut OutboundReceiver) -> SequencedMessage {
+        let Message::Text(text) = rx.recv().await.unwrap() else { panic!("Expected text message") };
+        serde_json::from_str(&text).unwrap()
+    }
+
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
+    let starting = next(&mut rx1).await;
+    assert_eq!(starting.seq, 1);
+    assert!(matches!(starting.message, ServerMessage::GameStarting { .. }));
+    let your_turn = next(&mut rx1).await;
+    assert_eq!(your_turn.seq, 2);
+
+    game_manager.handle_player_action(game_id, player1.clone(), PlayerAction::Bid(Bid { tricks: 0 })).await.unwrap();
+    let action = next(&mut rx1).await;
+    assert_eq!(action.seq, 3);
+    assert!(matches!(action.message, ServerMessage::PlayerAction { .. }));
+
+    // Only the missed delta is resent, with the original sequence numbers
+    game_manager.resync_from(game_id, player1.clone(), 1).await.unwrap();
+    assert_eq!(next(&mut rx1).await.seq, 2);
+    assert_eq!(next(&mut rx1).await.seq, 3);
+    assert!(rx1.try_recv().is_err());
+
+    // A sequence number the server never sent gets a full state instead
+    game_manager.resync_from(game_id, player1.clone(), 99).await.unwrap();
+    let full = next(&mut rx1).await;
+    assert_eq!(full.seq, 4);
+    assert!(matches!(full.message, ServerMessage::GameState { .. }));
+}
+
+#[tokio::test]
+async fn test_resync_after_the_round_sends_no_turn() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let (tx1, mut rx1) = outbound::channel();
+    let player1 = conn_manager.add_player(tx1).await;
+    let (tx2, mut rx2) = outbound::channel();
+    let player2 = conn_manager.add_player(tx2).await;
+    let game_id = game_manager.create_game(vec![player1.clone(), player2.clone()]).await;
+
+    for _ in 0..4 {
+        game_manager.start_turn_timer(game_id, 0).await;
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    let view = game_manager.get_game_state(game_id, player1.clone()).await.unwrap();
+    assert_eq!(view.phase, GamePhase::RoundComplete);
+
+    // Whoever the state still names as current player has nothing to do until the next deal
+    for (player, rx) in [(player1, &mut rx1), (player2, &mut rx2)] {
+        while rx.try_recv().is_ok() {}
+        game_manager.resync_from(game_id, player, 99).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let mut messages = Vec::new();
+        while let Ok(Message::Text(text)) = rx.try_recv() {
+            messages.push(serde_json::from_str::<german_bridge_backend::game_events::SequencedMessage>(&text).unwrap().message);
+        }
+        assert!(matches!(messages.as_slice(), [ServerMessage::GameState { .. }]), "{:?}", messages);
+    }
+}
+
+#[tokio::test]
+async fn test_tournament_webhook_is_signed_and_retried() {
+    use axum::{body::Bytes, extract::State, http::{HeaderMap, StatusCode}, routing::post, Router};
+    use german_bridge_backend::{tournament, webhook::{self, GameCompletedEvent, WebhookDispatcher}};
+    use german_bridge_backend::protocol::DeparturePolicy;
+    use std::sync::Mutex;
+
+    // Receiver that fails the first delivery and records the rest
+    type Received = Arc<Mutex<Vec<(HeaderMap, Bytes)>>>;
+    async fn receive(State(received): State<Received>, headers: HeaderMap, body: Bytes) -> StatusCode {
+        let mut received = received.lock().unwrap();
+        received.push((headers, body));
+        if received.len() == 1 { StatusCode::INTERNAL_SERVER_ERROR } else { StatusCode::OK }
+    }
+    let received: Received = Arc::new(Mutex::new(Vec::new()));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let app = Router::new().route("/hook", post(receive)).with_state(Arc::clone(&received));
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let db = test_db_with_schema().await;
+    let cup = tournament::create_tournament(&db, "Autumn Cup", Some(url)).await.unwrap();
+    let webhooks = Arc::new(WebhookDispatcher::new(db.clone()).with_base_delay(std::time::Duration::from_millis(10)));
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = GameManager::new(Arc::clone(&conn_manager), db.clone()).with_webhooks(webhooks);
+    let player1 = insert_user(&db, "alice").await;
+    let player2 = insert_user(&db, "bob").await;
+
+    // Both seats are auto-played once their players are gone, so the game runs to completion
+    let settings = GameSettings { tournament_id: Some(cup.id), departure_policy: DeparturePolicy::BotTakeover, ..Default::default() };
+    let game_id = game_manager.create_game_with_settings(vec![player1.clone(), player2.clone()], None, settings).await;
+    game_manager.handle_player_departed(game_id, player1.clone()).await.unwrap();
+    game_manager.handle_player_departed(game_id, player2.clone()).await.unwrap();
+
+    for _ in 0..200 {
+        if received.lock().unwrap().len() >= 2 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 2);
+    let (headers, body) = &received[1];
+    let signature = headers.get(webhook::SIGNATURE_HEADER).unwrap().to_str().unwrap();
+    assert!(webhook::verify(&cup.webhook_secret, body, signature));
+    assert_eq!(headers.get(webhook::DELIVERY_HEADER), received[0].0.get(webhook::DELIVERY_HEADER));
+
+    let event: GameCompletedEvent = serde_json::from_slice(body).unwrap();
+    assert_eq!(event.event, webhook::GAME_COMPLETED);
+    assert_eq!(event.game_id, game_id);
+    assert_eq!(event.tournament_id, cup.id);
+    assert_eq!(event.results.len(), 2);
+    assert_eq!(event.results[0].placement, Some(1));
+    assert!(event.results.iter().any(|r| r.username == "alice"));
+}
+
+#[tokio::test]
+async fn test_deals_are_persisted_for_review_and_fairness() {
+    use german_bridge_backend::deal_review;
+    use german_bridge_backend::protocol::DeparturePolicy;
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = GameManager::new(Arc::clone(&conn_manager), db.clone());
+    let player1 = insert_user(&db, "alice").await;
+    let player2 = insert_user(&db, "bob").await;
+
+    let settings = GameSettings { departure_policy: DeparturePolicy::BotTakeover, ..Default::default() };
+    let game_id = game_manager.create_game_with_settings(vec![player1.clone(), player2.clone()], None, settings).await;
+
+    // Not counted towards fairness until the game is over
+    assert_eq!(deal_review::compute_fairness(&db).await.unwrap().rounds, 0);
+
+    game_manager.handle_player_departed(game_id, player1.clone()).await.unwrap();
+    game_manager.handle_player_departed(game_id, player2.clone()).await.unwrap();
+    game_manager.db_writes().flush().await;
+
+    let deals = deal_review::deals_for_game(&db, game_id).await.unwrap();
+    assert_eq!(deals.len(), 26);
+    for (index, deal) in deals.iter().enumerate() {
+        assert_eq!(deal.round_number, index + 1);
+        assert_eq!(deal.hands.iter().map(|h| h.player_id.clone()).collect::<Vec<_>>(), vec![player1.clone(), player2.clone()]);
+        assert!(deal.hands.iter().all(|h| h.cards.len() == index + 1));
+    }
+
+    let stats = deal_review::compute_fairness(&db).await.unwrap();
+    assert_eq!(stats.games, 1);
+    assert_eq!(stats.rounds, 26);
+    assert_eq!(stats.cards_dealt, (1..=26).map(|n| 2 * n).sum::<u64>());
+}
+
+#[tokio::test]
+async fn test_password_reset_tokens_end_sessions() {
+    use german_bridge_backend::auth::{self, JwtKeys, Role};
+    use german_bridge_backend::password_reset::{self, ResetError};
+
+    let db = test_db_with_schema().await;
+    let user_id = Uuid::parse_str(&insert_user(&db, "alice").await).unwrap();
+    let keys = JwtKeys::development();
+    let now = chrono::Utc::now();
+
+    let old_session = keys.verify_jwt(&keys.create_jwt(&user_id.to_string(), "alice", false, Role::Player).unwrap()).unwrap();
+    assert!(password_reset::check_session(&db, &old_session).await.unwrap());
+
+    let first = password_reset::issue_reset_token(&db, user_id, now).await.unwrap();
+    let second = password_reset::issue_reset_token(&db, user_id, now).await.unwrap();
+    assert_eq!(first.expires_at, now + password_reset::RESET_TOKEN_TTL);
+    assert!(matches!(
+        password_reset::redeem_reset_token(&db, "not-a-token", String::new(), now).await,
+        Err(ResetError::InvalidToken)
+    ));
+    // Expired tokens are refused
+    let late = now + password_reset::RESET_TOKEN_TTL + chrono::Duration::seconds(1);
+    assert!(matches!(
+        password_reset::redeem_reset_token(&db, &first.token, String::new(), late).await,
+        Err(ResetError::InvalidToken)
+    ));
+
+    let hash = auth::hash_password("new-password").unwrap();
+    let reset_at = now + chrono::Duration::seconds(5);
+    assert_eq!(password_reset::redeem_reset_token(&db, &first.token, hash, reset_at).await.unwrap(), user_id);
+
+    let account = entities::user::Entity::find_by_id(user_id).one(&db).await.unwrap().unwrap();
+    assert!(auth::verify_password("new-password", &account.password_hash).unwrap());
+    assert!(!password_reset::check_session(&db, &old_session).await.unwrap());
+
+    // Sessions issued after the reset are fine, even within the same second
+    let mut new_session = old_session;
+    new_session.iat = reset_at.timestamp() as usize;
+    new_session.iat_ms = reset_at.timestamp_millis() as u64 + 1;
+    assert!(password_reset::session_is_current(&account, &new_session));
+    new_session.iat_ms -= 2;
+    assert!(!password_reset::session_is_current(&account, &new_session));
+    // Tokens with only seconds cannot tell, so they need a later one
+    new_session.iat_ms = 0;
+    assert!(!password_reset::session_is_current(&account, &new_session));
+    new_session.iat += 1;
+    assert!(password_reset::session_is_current(&account, &new_session));
+
+    // Tokens are single use, and redeeming one uses up the others
+    for token in [&first.token, &second.token] {
+        assert!(matches!(
+            password_reset::redeem_reset_token(&db, token, String::new(), reset_at).await,
+            Err(ResetError::InvalidToken)
+        ));
+    }
+}
+
+#[tokio::test]
+async fn test_player_sits_at_two_games_and_switches_active_room() {
+    use german_bridge_backend::room::RoomId;
+    use german_bridge_backend::router::MessageRouter;
+
+    let db = test_db().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db));
+    let router = MessageRouter::new(Arc::clone(&lobby_manager), Arc::clone(&game_manager), Arc::clone(&conn_manager));
+
+    let (tx, mut rx) = outbound::channel();
+    let alice = conn_manager.add_player(tx).await;
+    let (bob, carol) = (new_player_id(), new_player_id());
+    let first = game_manager.create_game(vec![alice.clone(), bob.clone()]).await;
+    let second = game_manager.create_game(vec![alice.clone(), carol.clone()]).await;
+
+    // Joining the second game keeps the first seat
+    let rooms = conn_manager.rooms();
+    assert_eq!(rooms.games_of(&alice), vec![first, second]);
+    assert_eq!(rooms.active_room(&alice), Some(RoomId::Game(second)));
+
+    // Make it alice's turn to bid in both games
+    let mut allowed = Vec::new();
+    for (game_id, other) in [(first, &bob), (second, &carol)] {
+        if game_manager.get_game_state(game_id, alice.clone()).await.unwrap().current_player == *other {
+            game_manager.handle_player_action(game_id, other.clone(), PlayerAction::Bid(Bid { tricks: 0 })).await.unwrap();
+        }
+        let constraint = game_manager.get_game_state(game_id, alice.clone()).await.unwrap().bid_constraint.unwrap();
+        assert_eq!(constraint.player_id, alice);
+        allowed.push((0..=constraint.max_bid).find(|&b| constraint.allows(b)).unwrap());
+    }
+    let bid = |tricks| ClientMessage::PlaceBid { bid: Bid { tricks }, state_version: None };
+    let alice_bid = |view: german_bridge_backend::protocol::PlayerGameView| {
+        view.current_round.iter().find(|r| r.player_id == alice).map(|r| r.bid)
+    };
+
+    // Bids go to the active game
+    router.route_message(alice.clone(), bid(allowed[1])).await.unwrap();
+    assert_eq!(alice_bid(game_manager.get_game_state(second, alice.clone()).await.unwrap()), Some(allowed[1]));
+    assert_eq!(game_manager.get_game_state(first, alice.clone()).await.unwrap().current_player, alice);
+
+    while rx.try_recv().is_ok() {}
+    router.route_message(alice.clone(), ClientMessage::SetActiveRoom { room: RoomId::Game(first) }).await.unwrap();
+    let Message::Text(text) = rx.recv().await.unwrap() else { panic!("Expected text message") };
+    match serde_json::from_str(&text).unwrap() {
+        ServerMessage::ActiveRoom { room, rooms } => {
+            assert_eq!(room, Some(RoomId::Game(first)));
+            assert_eq!(rooms, vec![RoomId::Game(first), RoomId::Game(second)]);
+        }
+        other => panic!("Expected ActiveRoom, got {:?}", other),
+    }
+    let Message::Text(text) = rx.recv().await.unwrap() else { panic!("Expected text message") };
+    assert!(matches!(serde_json::from_str(&text).unwrap(), ServerMessage::GameState { state } if state.game_id == first));
+
+    router.route_message(alice.clone(), bid(allowed[0])).await.unwrap();
+    assert_eq!(alice_bid(game_manager.get_game_state(first, alice.clone()).await.unwrap()), Some(allowed[0]));
+
+    // Rooms the player is not in can't be selected
+    let result = router.route_message(alice.clone(), ClientMessage::SetActiveRoom { room: RoomId::Lobby(Uuid::new_v4()) }).await;
+    assert!(result.is_err());
+
+    // Ending the active game falls back to the other one
+    game_manager.end_game(first).await;
+    assert_eq!(rooms.active_room(&alice), Some(RoomId::Game(second)));
+}
+
+#[tokio::test]
+async fn test_profiles_show_in_lobby_and_game_views() {
+    use german_bridge_backend::handlers::user::UpdateProfileRequest;
+    use german_bridge_backend::user_cache::PlayerProfile;
+    use sea_orm::{ActiveModelTrait, Set};
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db.clone()));
+    let alice = insert_user(&db, "alice").await;
+    let bob = insert_user(&db, "bob").await;
+
+    let account = entities::user::Entity::find_by_id(Uuid::parse_str(&alice).unwrap()).one(&db).await.unwrap().unwrap();
+    let mut active: entities::user::ActiveModel = account.into();
+    active.display_name = Set(Some("Ally".to_string()));
+    active.avatar = Set(Some("fox".to_string()));
+    active.update(&db).await.unwrap();
+
+    // Offline players' profiles are loaded from the users table
+    let lobby_id = lobby_manager.create_lobby(alice.clone(), GameSettings::default()).await;
+    lobby_manager.join_lobby(lobby_id, bob.clone()).await.unwrap();
+    let lobby = lobby_manager.lobby_info(&lobby_manager.get_lobby(lobby_id).await.unwrap()).await;
+    let host = lobby.players.iter().find(|p| p.id == alice).unwrap();
+    assert_eq!((host.username.as_str(), host.display_name.as_deref(), host.avatar.as_deref()), ("alice", Some("Ally"), Some("fox")));
+    assert_eq!(lobby.players.iter().find(|p| p.id == bob).unwrap().display_name, None);
+
+    let game_id = game_manager.create_game(vec![alice.clone(), bob.clone()]).await;
+    let view = game_manager.get_game_state(game_id, bob.clone()).await.unwrap();
+    assert_eq!(view.players.iter().map(|p| p.id.clone()).collect::<Vec<_>>(), vec![alice.clone(), bob.clone()]);
+    assert_eq!(view.players[0].display_name.as_deref(), Some("Ally"));
+    assert_eq!(view.player_names.get(&alice).map(String::as_str), Some("alice"));
+
+    // A profile update refreshes the cache, and with it later lobby views
+    let renamed = PlayerProfile { display_name: Some("Bobby".to_string()), ..PlayerProfile::named("bob".to_string()) };
+    conn_manager.user_cache().insert(bob.clone(), renamed).await;
+    let lobby = lobby_manager.lobby_info(&lobby_manager.get_lobby(lobby_id).await.unwrap()).await;
+    assert_eq!(lobby.players.iter().find(|p| p.id == bob).unwrap().display_name.as_deref(), Some("Bobby"));
+
+    // PATCH bodies tell a cleared field from one left alone
+    let update: UpdateProfileRequest = serde_json::from_str(r#"{"display_name": null}"#).unwrap();
+    assert_eq!(update.display_name, Some(None));
+    assert_eq!(update.avatar, None);
+    assert!(update.username.is_none());
+}
+
+#[tokio::test]
+async fn test_room_addressed_messages_reach_the_named_game() {
+    use german_bridge_backend::protocol::ClientEnvelope;
+    use german_bridge_backend::room::RoomId;
+    use german_bridge_backend::router::MessageRouter;
+
+    let db = test_db().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db));
+    let router = MessageRouter::new(Arc::clone(&lobby_manager), Arc::clone(&game_manager), Arc::clone(&conn_manager));
+
+    let (tx, _rx) = outbound::channel();
+    let alice = conn_manager.add_player(tx).await;
+    let (bob, carol) = (new_player_id(), new_player_id());
+    let first = game_manager.create_game(vec![alice.clone(), bob.clone()]).await;
+    let second = game_manager.create_game(vec![alice.clone(), carol]).await;
+    assert_eq!(conn_manager.rooms().active_room(&alice), Some(RoomId::Game(second)));
+
+    if game_manager.get_game_state(first, alice.clone()).await.unwrap().current_player == bob {
+        game_manager.handle_player_action(first, bob.clone(), PlayerAction::Bid(Bid { tricks: 0 })).await.unwrap();
+    }
+    let constraint = game_manager.get_game_state(first, alice.clone()).await.unwrap().bid_constraint.unwrap();
+    let tricks = (0..=constraint.max_bid).find(|&b| constraint.allows(b)).unwrap();
+
+    // The envelope's room wins over the active room, which stays as it was
+    let text = serde_json::json!({
+        "type": "PlaceBid",
+        "payload": { "bid": { "tricks": tricks } },
+        "room_id": { "kind": "Game", "id": first },
+    }).to_string();
+    let envelope: ClientEnvelope = serde_json::from_str(&text).unwrap();
+    assert_eq!(envelope.room_id, Some(RoomId::Game(first)));
+    router.route_message(alice.clone(), envelope).await.unwrap();
+    let view = game_manager.get_game_state(first, alice.clone()).await.unwrap();
+    assert_eq!(view.current_round.iter().find(|r| r.player_id == alice).map(|r| r.bid), Some(tricks));
+    assert_eq!(conn_manager.rooms().active_room(&alice), Some(RoomId::Game(second)));
+
+    // Messages without a room_id still parse and use the active room
+    let envelope: ClientEnvelope = serde_json::from_str(r#"{"type": "RequestGameState"}"#).unwrap();
+    assert_eq!(envelope.room_id, None);
+    router.route_message(alice.clone(), envelope).await.unwrap();
+
+    let address = |message: ClientMessage, room| ClientEnvelope { message, room_id: Some(room), request_id: None };
+    let err = router.route_message(alice.clone(), address(ClientMessage::RequestGameState, RoomId::Game(Uuid::new_v4()))).await.unwrap_err();
+    assert_eq!(err.to_string(), "You are not a member of that game");
+    let err = router.route_message(alice.clone(), address(ClientMessage::SetReady { ready: true }, RoomId::Lobby(Uuid::new_v4()))).await.unwrap_err();
+    assert_eq!(err.to_string(), "You are not a member of that lobby");
+    let err = router.route_message(alice.clone(), address(ClientMessage::RequestGameState, RoomId::Lobby(Uuid::new_v4()))).await.unwrap_err();
+    assert_eq!(err.to_string(), "That message must be addressed to a game");
+    let err = router.route_message(alice.clone(), address(ClientMessage::Ping, RoomId::Game(first))).await.unwrap_err();
+    assert_eq!(err.to_string(), "That message does not take a room_id");
+}
+
+#[tokio::test]
+async fn test_get_game_returns_the_hands_in_play() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = GameManager::new(Arc::clone(&conn_manager), test_db_with_schema().await);
+    let (tx, _rx) = outbound::channel();
+    let alice = conn_manager.add_player(tx).await;
+    let bob = new_player_id();
+    let game_id = game_manager.create_game(vec![alice.clone(), bob.clone()]).await;
+    game_manager.start_turn_timer(game_id, 0).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    // The copy is the game being played, not a fresh deal
+    let view = game_manager.get_game_state(game_id, alice.clone()).await.unwrap();
+    let game = game_manager.get_game(game_id).await.unwrap();
+    assert_eq!(game.state.hands[&alice].cards(), view.your_hand.as_slice());
+    assert_eq!(game.state.version, view.state_version);
+    assert_eq!(game.state.current_player, view.current_player);
+    assert_eq!(game.state.bidding_state.unwrap().bids.len(), 1);
+}
+
+#[tokio::test]
+async fn test_games_take_actions_concurrently() {
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), test_db().await));
+    let mut games = Vec::new();
+    for _ in 0..50 {
+        let players = vec![new_player_id(), new_player_id()];
+        games.push(game_manager.create_game(players.clone()).await);
+    }
+
+    // Every game bids at once; each must see only its own action
+    let bids = games.iter().map(|&game_id| {
+        let game_manager = Arc::clone(&game_manager);
+        tokio::spawn(async move {
+            let game = game_manager.get_game(game_id).await.unwrap();
+            let first = game.state.current_player.clone();
+            game_manager.handle_player_action(game_id, first.clone(), PlayerAction::Bid(Bid { tricks: 1 })).await.unwrap();
+            first
+        })
+    });
+    let bidders = futures::future::join_all(bids).await;
+
+    for (game_id, bidder) in games.into_iter().zip(bidders) {
+        let game = game_manager.get_game(game_id).await.unwrap();
+        let bids = game.state.bidding_state.unwrap().bids;
+        assert_eq!(bids.len(), 1);
+        assert_eq!(bids.get(&bidder.unwrap()), Some(&1));
+    }
+    assert_eq!(game_manager.get_stats().await.active_games, 50);
+}
+
+#[tokio::test]
+async fn test_reaper_removes_games_everyone_left() {
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = GameManager::new(Arc::clone(&conn_manager), db.clone());
+
+    let alice = insert_user(&db, "alice").await;
+    let (tx, _rx) = outbound::channel();
+    conn_manager.register_player(alice.clone(), "alice".to_string(), tx).await;
+    let bob = insert_user(&db, "bob").await;
+    let game_id = game_manager.create_game(vec![alice.clone(), bob.clone()]).await;
+    game_manager.start_turn_timer(game_id, 3600).await;
+
+    // Alice is still connected
+    assert!(game_manager.reap_dead_games().await.is_empty());
+    assert!(game_manager.get_game(game_id).await.is_ok());
+
+    // Once she departs too, nobody can finish the game
+    game_manager.handle_player_departed(game_id, alice.clone()).await.unwrap();
+    assert_eq!(game_manager.reap_dead_games().await, vec![game_id]);
+    assert!(matches!(game_manager.get_game(game_id).await, Err(GameError::GameNotFound)));
+    assert!(conn_manager.rooms().games_of(&alice).is_empty());
+    assert_eq!(game_manager.get_stats().await.active_games, 0);
+
+    let row = entities::game::Entity::find_by_id(game_id).one(&db).await.unwrap().unwrap();
+    assert!(row.abandoned_at.is_some());
+    assert!(row.completed_at.is_none());
+
+    // Reaping again is a no-op
+    assert!(game_manager.reap_dead_games().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_presence_reaches_mutual_friends_only() {
+    use german_bridge_backend::friends;
+    use german_bridge_backend::presence::{self, Presence, PresenceService};
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db.clone());
+    let service = Arc::new(PresenceService::new(Arc::clone(&conn_manager), db.clone()));
+    presence::spawn_presence_task(Arc::clone(&service));
+
+    let alice = insert_user(&db, "alice").await;
+    let bob = insert_user(&db, "bob").await;
+    let carol = insert_user(&db, "carol").await;
+    let id = |p: &PlayerId| Uuid::parse_str(p).unwrap();
+    friends::add_friend(&db, id(&alice), id(&bob)).await.unwrap();
+    friends::add_friend(&db, id(&bob), id(&alice)).await.unwrap();
+    friends::add_friend(&db, id(&carol), id(&alice)).await.unwrap();
+
+    let (bob_tx, mut bob_rx) = outbound::channel();
+    conn_manager.register_player(bob.clone(), "bob".to_string(), bob_tx).await;
+    let (carol_tx, mut carol_rx) = outbound::channel();
+    conn_manager.register_player(carol.clone(), "carol".to_string(), carol_tx).await;
+
+    async fn next_presence(rx: &mut OutboundReceiver) -> (PlayerId, Presence) {
+        loop {
+            let msg = tokio::time::timeout(std::time::Duration::from_secs(2), rx.recv()).await
+                .expect("no presence update").unwrap();
+            let Message::Text(text) = msg else { continue };
+            if let ServerMessage::PresenceUpdate { player_id, presence } = serde_json::from_str(&text).unwrap() {
+                return (player_id, presence);
+            }
+        }
+    }
+
+    let (alice_tx, _alice_rx) = outbound::channel();
+    conn_manager.register_player(alice.clone(), "alice".to_string(), alice_tx).await;
+    assert_eq!(next_presence(&mut bob_rx).await, (alice.clone(), Presence::Online));
+
+    let lobby_id = lobby_manager.create_lobby(alice.clone(), GameSettings::default()).await;
+    assert_eq!(next_presence(&mut bob_rx).await, (alice.clone(), Presence::InLobby { lobby_id }));
+
+    let listed = service.friends_presence(id(&bob)).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!((listed[0].username.as_str(), listed[0].presence), ("alice", Presence::InLobby { lobby_id }));
+
+    conn_manager.mark_inactive(alice.clone()).await;
+    assert_eq!(next_presence(&mut bob_rx).await, (alice.clone(), Presence::Offline));
+
+    // Carol only added alice, so she hears nothing and sees no one
+    while let Ok(msg) = carol_rx.try_recv() {
+        if let Message::Text(text) = msg {
+            assert!(!matches!(serde_json::from_str(&text).unwrap(), ServerMessage::PresenceUpdate { .. }));
+        }
+    }
+    assert!(service.friends_presence(id(&carol)).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_host_block_list_keeps_players_out_of_lobby() {
+    use german_bridge_backend::{blocks, friends};
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db.clone());
+    let host = insert_user(&db, "host").await;
+    let troll = insert_user(&db, "troll").await;
+    let id = |p: &PlayerId| Uuid::parse_str(p).unwrap();
+
+    friends::add_friend(&db, id(&host), id(&troll)).await.unwrap();
+    friends::add_friend(&db, id(&troll), id(&host)).await.unwrap();
+    blocks::block_user(&db, id(&host), id(&troll)).await.unwrap();
+    blocks::block_user(&db, id(&host), id(&troll)).await.unwrap();
+    assert!(friends::list_friends(&db, id(&host)).await.unwrap().is_empty());
+    let blocked = blocks::list_blocked(&db, id(&host)).await.unwrap();
+    assert_eq!(blocked.iter().map(|b| b.username.as_str()).collect::<Vec<_>>(), vec!["troll"]);
+
+    // Only lobbies that opt in turn blocked players away
+    let open = lobby_manager.create_lobby(host.clone(), GameSettings::default()).await;
+    lobby_manager.join_lobby(open, troll.clone()).await.unwrap();
+    lobby_manager.leave_lobby(open, troll.clone()).await.unwrap();
+
+    let settings = GameSettings { exclude_blocked: true, ..Default::default() };
+    let guarded = lobby_manager.create_lobby(host.clone(), settings).await;
+    let err = lobby_manager.join_lobby(guarded, troll.clone()).await.unwrap_err();
+    assert!(matches!(err, german_bridge_backend::error::LobbyError::BlockedByHost));
+    lobby_manager.join_lobby(guarded, new_player_id()).await.unwrap();
+
+    assert!(blocks::unblock_user(&db, id(&host), id(&troll)).await.unwrap());
+    assert!(!blocks::unblock_user(&db, id(&host), id(&troll)).await.unwrap());
+    lobby_manager.join_lobby(guarded, troll.clone()).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_shared_address_policy_warns_prevents_and_flags_games() {
+    use german_bridge_backend::audit::{AuditKind, AuditLog, AuditQuery};
+    use german_bridge_backend::protocol::SharedAddressPolicy;
+    use german_bridge_backend::router::MessageRouter;
+    use german_bridge_backend::short_code::IdOrCode;
+    use std::net::IpAddr;
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let lobby_manager = Arc::new(LobbyManager::new(Arc::clone(&game_manager), Arc::clone(&conn_manager), db.clone()));
+    let audit = AuditLog::new(db.clone());
+    let router = MessageRouter::new(Arc::clone(&lobby_manager), Arc::clone(&game_manager), Arc::clone(&conn_manager))
+        .with_audit_log(audit.clone());
+
+    let home: IpAddr = "203.0.113.7".parse().unwrap();
+    let elsewhere: IpAddr = "198.51.100.2".parse().unwrap();
+    let mut players = Vec::new();
+    let mut receivers = Vec::new();
+    for address in [home, home, elsewhere, home] {
+        let (tx, rx) = outbound::channel();
+        let player = conn_manager.add_player(tx).await;
+        conn_manager.set_address(&player, address).await;
+        players.push(player);
+        receivers.push(rx);
+    }
+    let (host, twin, stranger, bot) = (players[0].clone(), players[1].clone(), players[2].clone(), players[3].clone());
+    conn_manager.set_user_agent(&twin, Some("Firefox")).await;
+    conn_manager.set_bot(&bot, true).await;
+
+    // Prevent turns away a second seat from the host's address, but not bots or other addresses
+    let settings = GameSettings { shared_address: SharedAddressPolicy::Prevent, ..Default::default() };
+    let guarded = lobby_manager.create_lobby(host.clone(), settings).await;
+    let err = lobby_manager.join_lobby(guarded, twin.clone()).await.unwrap_err();
+    assert!(matches!(err, LobbyError::SharedAddress));
+    lobby_manager.join_lobby(guarded, stranger.clone()).await.unwrap();
+    lobby_manager.join_lobby(guarded, bot.clone()).await.unwrap();
+
+    // Nor does it start once a seated player reconnects from another's address
+    conn_manager.set_address(&stranger, home).await;
+    lobby_manager.set_ready(guarded, stranger.clone(), true).await.unwrap();
+    lobby_manager.set_ready(guarded, bot.clone(), true).await.unwrap();
+    assert!(matches!(lobby_manager.start_game(guarded, host.clone()).await, Err(LobbyError::SharedAddress)));
+    for player in [&bot, &stranger, &host] {
+        lobby_manager.leave_lobby(guarded, player.clone()).await.unwrap();
+    }
+    conn_manager.set_address(&stranger, elsewhere).await;
+
+    // Warn tells the lobby who shares an address when the second of them joins
+    let settings = GameSettings { player_count: 3, shared_address: SharedAddressPolicy::Warn, ..Default::default() };
+    router.route_message(host.clone(), ClientMessage::CreateLobby { settings }).await.unwrap();
+    let lobby_id = conn_manager.rooms().lobby_of(&host).unwrap();
+    for player in [&stranger, &twin] {
+        router.route_message(player.clone(), ClientMessage::JoinLobby { lobby_id: IdOrCode::Id(lobby_id) }).await.unwrap();
+    }
+    let mut warnings = Vec::new();
+    while let Ok(msg) = receivers[0].try_recv() {
+        if let Message::Text(text) = msg {
+            if let ServerMessage::SharedAddressWarning { lobby_id: warned, players } = serde_json::from_str(&text).unwrap() {
+                assert_eq!(warned, lobby_id);
+                warnings.push(players);
+            }
+        }
+    }
+    assert_eq!(warnings, vec![vec![host.clone(), twin.clone()]]);
+
+    // Starting the game puts it in the audit log for admins, with each player's user agent
+    for player in [&stranger, &twin] {
+        router.route_message(player.clone(), ClientMessage::SetReady { ready: true }).await.unwrap();
+    }
+    router.route_message(host.clone(), ClientMessage::StartGame).await.unwrap();
+    let query = AuditQuery { kind: Some(AuditKind::SharedAddress), ..Default::default() };
+    let flagged = audit.search(&query).await.unwrap();
+    assert_eq!(flagged.len(), 1);
+    assert_eq!(flagged[0].ip.as_deref(), Some("203.0.113.7"));
+    assert_eq!(flagged[0].detail["lobby_id"], serde_json::json!(lobby_id));
+    assert!(flagged[0].detail["game_id"].is_string());
+    assert_eq!(flagged[0].detail["players"], serde_json::json!([
+        { "player_id": host, "user_agent": null },
+        { "player_id": twin, "user_agent": "Firefox" },
+    ]));
+}
+
+#[tokio::test]
+async fn test_achievements_unlock_once_and_notify_the_player() {
+    use german_bridge_backend::achievements::{self, Achievement};
+    use german_bridge_backend::engine::rules::RuleSet;
+    use german_bridge_backend::engine::tiebreak::GameOutcome;
+
+    let db = test_db_with_schema().await;
+    let conn_manager = Arc::new(ConnectionManager::new());
+    let game_manager = Arc::new(GameManager::new(Arc::clone(&conn_manager), db.clone()));
+    let mut players = Vec::new();
+    let mut receivers = std::collections::HashMap::new();
+    for name in ["alice", "bob"] {
+        let player = insert_user(&db, name).await;
+        let (tx, rx) = outbound::channel();
+        conn_manager.register_player(player.clone(), name.to_string(), tx).await;
+        receivers.insert(player.clone(), rx);
+        players.push(player);
+    }
+
+    // One round of one card, played out with the first legal move each turn
+    let settings = GameSettings { rules: RuleSet { round_limit: Some(1), ..Default::default() }, ..Default::default() };
+    let game_id = game_manager.create_game_with_settings(players.clone(), None, settings).await;
+    loop {
+        let view = game_manager.get_game_state(game_id, players[0].clone()).await.unwrap();
+        if view.phase == GamePhase::GameComplete {
+            break;
+        }
+        let current = view.current_player.clone();
+        let hand = game_manager.get_game_state(game_id, current.clone()).await.unwrap().your_hand;
+        let candidates: Vec<PlayerAction> = if view.phase == GamePhase::Bidding {
+            (0..=hand.len() as u8).map(|tricks| PlayerAction::Bid(Bid { tricks })).collect()
+        } else {
+            hand.iter().map(|card| PlayerAction::PlayCard(*card)).collect()
+        };
+        let mut played = false;
+        for action in candidates {
+            if game_manager.handle_player_action(game_id, current.clone(), action).await.is_ok() {
+                played = true;
+                break;
+            }
+        }
+        assert!(played);
+    }
+
+    // Winners hear about their first win once it is stored; everyone is a tenth of the way to Veteran
+    game_manager.db_writes().flush().await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let game = entities::game::Entity::find_by_id(game_id).one(&db).await.unwrap().unwrap();
+    let outcome: GameOutcome = serde_json::from_value(game.outcome.unwrap()).unwrap();
+    for player in &players {
+        let rx = receivers.get_mut(player).unwrap();
+        let mut unlocked = Vec::new();
+        while let Ok(Message::Text(text)) = rx.try_recv() {
+            if let Ok(ServerMessage::AchievementUnlocked { achievement, title, .. }) = serde_json::from_str(&text) {
+                assert_eq!(title, achievement.title());
+                unlocked.push(achievement);
+            }
+        }
+        let won = outcome.winners.contains(player);
+        assert_eq!(unlocked.contains(&Achievement::FirstWin), won);
+
+        let user_id = Uuid::parse_str(player).unwrap();
+        let progress = achievements::progress_for_user(&db, user_id).await.unwrap();
+        assert_eq!(progress.len(), Achievement::ALL.len());
+        let veteran = progress.iter().find(|p| p.achievement == Achievement::Veteran).unwrap();
+        assert_eq!((veteran.progress, veteran.unlocked_at), (1, None));
+        let first_win = progress.iter().find(|p| p.achievement == Achievement::FirstWin).unwrap();
+        assert_eq!(first_win.unlocked_at.is_some(), won);
+    }
+
+    // Progress builds up to the target, unlocks once, and then stops counting
+    let user_id = Uuid::parse_str(&players[0]).unwrap();
+    let zero_hero = achievements::progress_for_user(&db, user_id).await.unwrap()
+        .into_iter().find(|p| p.achievement == Achievement::ZeroHero).unwrap().progress;
+    for _ in zero_hero + 1..Achievement::ZeroHero.target() {
+        assert!(achievements::record(&db, user_id, &[Achievement::ZeroHero]).await.unwrap().is_empty());
+    }
+    assert_eq!(achievements::record(&db, user_id, &[Achievement::ZeroHero]).await.unwrap(), vec![Achievement::ZeroHero]);
+    assert!(achievements::record(&db, user_id, &[Achievement::ZeroHero]).await.unwrap().is_empty());
+    let zero_hero = achievements::progress_for_user(&db, user_id).await.unwrap()
+        .into_iter().find(|p| p.achievement == Achievement::ZeroHero).unwrap();
+    assert_eq!(zero_hero.progress, Achievement::ZeroHero.target());
+    assert!(zero_hero.unlocked_at.is_some());
+
+    // Games finishing together each count, and only one of them unlocks it
+    let user_id = Uuid::parse_str(&insert_user(&db, "carol").await).unwrap();
+    let finished = (0..Achievement::Veteran.target()).map(|_| achievements::record(&db, user_id, &[Achievement::Veteran]));
+    let unlocks: Vec<_> = futures::future::join_all(finished).await.into_iter().map(Result::unwrap).collect();
+    assert_eq!(unlocks.concat(), vec![Achievement::Veteran]);
+    let veteran = achievements::progress_for_user(&db, user_id).await.unwrap()
+        .into_iter().find(|p| p.achievement == Achievement::Veteran).unwrap();
+    assert_eq!(veteran.progress, Achievement::Veteran.target());
+}
@@ -0,0 +1,171 @@
+//! Plays complete matches between automatic strategies and prints one JSON line per game,
+//! for checking what a rules change does to real games:
+//!
+//! ```text
+//! cargo run --release --bin simulate -- --games 5000 > before.jsonl
+//! # change the rules
+//! cargo run --release --bin simulate -- --games 5000 > after.jsonl
+//! diff before.jsonl after.jsonl
+//! ```
+//!
+//! Game `n` is dealt from a seed derived from `--seed` and `n`, so the same options give the
+//! same output on every run. Each line holds the game number, the seed and the
+//! [`MatchRecord`]; a summary of the average scores per seat goes to stderr. Only the engine is
+//! needed, so this also builds with `--no-default-features`.
+
+use german_bridge_backend::engine::auto_play::{AutoPlayStrategy, LowestLegal, RulesAware};
+use german_bridge_backend::engine::deck::DeckSeed;
+use german_bridge_backend::engine::rules::{RuleSet, TrumpMode};
+use german_bridge_backend::engine::schedule::RoundSchedule;
+use german_bridge_backend::engine::{GameState, MatchRecord, PlayerId};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use serde::Serialize;
+use std::io::{BufWriter, Write};
+
+const USAGE: &str = "\
+Usage: simulate [options]
+
+  --games N              Matches to play (default 1000)
+  --players N            Seats per table, 2 to 8 (default 4)
+  --seed N               Base seed for the deals (default 0)
+  --strategies LIST      Comma separated strategy per seat, `rules-aware` or `lowest`;
+                         one name applies to every seat (default rules-aware)
+  --schedule NAME        `ascending` or `up-and-down` (default ascending)
+  --max-cards N          Cap on cards per round (default: as many as the deck allows)
+  --trump NAME           `random`, `rotating` or `none` (default random)
+  --no-events            Leave the event log out of each line";
+
+struct Options {
+    games: u64,
+    players: usize,
+    seed: u64,
+    strategies: Vec<String>,
+    schedule: RoundSchedule,
+    rules: RuleSet,
+    events: bool,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self {
+            games: 1000,
+            players: 4,
+            seed: 0,
+            strategies: vec!["rules-aware".to_string()],
+            schedule: RoundSchedule::default(),
+            rules: RuleSet::default(),
+            events: true,
+        };
+        while let Some(flag) = args.next() {
+            match flag.as_str() {
+                "--help" | "-h" => return Err(String::new()),
+                "--no-events" => {
+                    options.events = false;
+                    continue;
+                }
+                _ => {}
+            }
+            let value = args.next().ok_or_else(|| format!("{} needs a value", flag))?;
+            let number = || value.parse::<u64>().map_err(|_| format!("{} takes a number, not {:?}", flag, value));
+            match flag.as_str() {
+                "--games" => options.games = number()?,
+                "--players" => options.players = number()? as usize,
+                "--seed" => options.seed = number()?,
+                "--strategies" => options.strategies = value.split(',').map(str::to_string).collect(),
+                "--schedule" => {
+                    options.schedule = match value.as_str() {
+                        "ascending" => RoundSchedule::Ascending,
+                        "up-and-down" => RoundSchedule::UpAndDown,
+                        _ => return Err(format!("unknown schedule {:?}", value)),
+                    }
+                }
+                "--max-cards" => options.rules.max_cards = Some(number()? as usize),
+                "--trump" => {
+                    options.rules.trump = match value.as_str() {
+                        "random" => TrumpMode::Random,
+                        "rotating" => TrumpMode::Rotating,
+                        "none" => TrumpMode::NoTrump,
+                        _ => return Err(format!("unknown trump mode {:?}", value)),
+                    }
+                }
+                _ => return Err(format!("unknown option {}", flag)),
+            }
+        }
+        if !(2..=8).contains(&options.players) {
+            return Err("--players must be between 2 and 8".to_string());
+        }
+        if options.strategies.len() != 1 && options.strategies.len() != options.players {
+            return Err(format!("--strategies names {} strategies for {} seats", options.strategies.len(), options.players));
+        }
+        options.rules.validate(options.players)?;
+        Ok(options)
+    }
+
+    /// One strategy per seat
+    fn strategies(&self) -> Result<Vec<&'static dyn AutoPlayStrategy>, String> {
+        let names = self.strategies.iter().cycle().take(self.players);
+        names
+            .map(|name| match name.as_str() {
+                "rules-aware" => Ok(&RulesAware as &dyn AutoPlayStrategy),
+                "lowest" => Ok(&LowestLegal as &dyn AutoPlayStrategy),
+                _ => Err(format!("unknown strategy {:?}", name)),
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize)]
+struct Line<'a> {
+    game: u64,
+    seed: String,
+    #[serde(flatten)]
+    record: &'a MatchRecord,
+}
+
+fn main() {
+    let options = Options::parse(std::env::args().skip(1));
+    let (options, strategies) = match options.and_then(|options| options.strategies().map(|s| (options, s))) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            if !e.is_empty() {
+                eprintln!("{}\n", e);
+            }
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    let players: Vec<PlayerId> = (1..=options.players).map(|seat| format!("seat{}", seat)).collect();
+    let mut totals = vec![0i64; players.len()];
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    for game in 0..options.games {
+        let seed = DeckSeed::from_rng(&mut ChaCha20Rng::seed_from_u64(options.seed.wrapping_add(game)));
+        let state = GameState::with_seed(players.clone(), options.schedule, options.rules.clone(), seed);
+        let mut record = match state.play_to_end(&strategies) {
+            Ok(record) => record,
+            Err(e) => {
+                eprintln!("Game {} (seed {}) failed: {}", game, seed.to_hex(), e);
+                std::process::exit(1);
+            }
+        };
+        for (total, player) in totals.iter_mut().zip(&players) {
+            *total += record.final_scores[player] as i64;
+        }
+        if !options.events {
+            record.events.clear();
+        }
+        let line = Line { game, seed: seed.to_hex(), record: &record };
+        let written = serde_json::to_writer(&mut out, &line).map_err(std::io::Error::from).and_then(|()| writeln!(out));
+        if written.is_err() {
+            // stdout closed, e.g. piped into head
+            return;
+        }
+    }
+    let _ = out.flush();
+
+    eprintln!("{} games", options.games);
+    for ((player, total), name) in players.iter().zip(&totals).zip(options.strategies.iter().cycle()) {
+        eprintln!("  {} ({}): average {:.1}", player, name, *total as f64 / options.games.max(1) as f64);
+    }
+}
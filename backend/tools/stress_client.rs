@@ -0,0 +1,470 @@
+//! Plays random legal games against a running server over real WebSockets, checking the
+//! protocol as it goes: a conformance harness for the WebSocket API and a load generator.
+//!
+//! ```text
+//! cargo run --bin stress_client -- --url http://127.0.0.1:8080 --tables 20 --games 5
+//! ```
+//!
+//! Every table registers fresh accounts, opens a lobby, readies up and plays its games to the
+//! end, picking a random valid action on each turn. A table fails on the first server Error,
+//! unreadable frame, gap or repeat in a game's `seq` not announced by ResyncRequired, message
+//! that takes longer than `--timeout`, or final scores the seats disagree on. The process
+//! exits with status 1 if any table failed, so it can gate a deploy or a CI job.
+
+use futures::{SinkExt, StreamExt};
+use german_bridge_backend::connection::PlayerId;
+use german_bridge_backend::engine::rules::RuleSet;
+use german_bridge_backend::engine::GamePhase;
+use german_bridge_backend::game_events::SequencedMessage;
+use german_bridge_backend::protocol::{ClientEnvelope, ClientMessage, GameSettings, PlayerAction, ServerMessage};
+use german_bridge_backend::short_code::IdOrCode;
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+const USAGE: &str = "\
+Usage: stress_client [options]
+
+  --url URL        Server to play against (default http://127.0.0.1:8080)
+  --tables N       Tables playing at the same time (default 1)
+  --players N      Seats per table (default 4)
+  --games N        Games each table plays, one after another (default 1)
+  --max-cards N    Cap on cards per round, for shorter games (default: the full game)
+  --timeout SECS   Longest any seat waits for a message (default 30)";
+
+#[derive(Debug, Clone)]
+struct Options {
+    url: String,
+    tables: usize,
+    players: usize,
+    games: usize,
+    max_cards: Option<usize>,
+    timeout: Duration,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            url: "http://127.0.0.1:8080".to_string(),
+            tables: 1,
+            players: 4,
+            games: 1,
+            max_cards: None,
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        while let Some(flag) = args.next() {
+            if flag == "--help" || flag == "-h" {
+                return Err(String::new());
+            }
+            let value = args.next().ok_or_else(|| format!("{} needs a value", flag))?;
+            let number = || value.parse::<usize>().map_err(|_| format!("{} takes a number, not {:?}", flag, value));
+            match flag.as_str() {
+                "--url" => options.url = value.trim_end_matches('/').to_string(),
+                "--tables" => options.tables = number()?,
+                "--players" => options.players = number()?,
+                "--games" => options.games = number()?,
+                "--max-cards" => options.max_cards = Some(number()?),
+                "--timeout" => options.timeout = Duration::from_secs(number()? as u64),
+                _ => return Err(format!("unknown option {}", flag)),
+            }
+        }
+        if options.tables == 0 || options.games == 0 || options.players < 2 {
+            return Err("--tables and --games must be at least 1 and --players at least 2".to_string());
+        }
+        Ok(options)
+    }
+
+    /// The WebSocket endpoint; only plain ws:// is supported
+    fn ws_url(&self, token: &str) -> Result<String, String> {
+        let host = self.url.strip_prefix("http://").ok_or("--url must be an http:// address")?;
+        Ok(format!("ws://{}/ws?token={}", host, token))
+    }
+}
+
+/// What one table got through before finishing or failing
+#[derive(Debug, Default)]
+struct TableReport {
+    games: usize,
+    actions: usize,
+    messages: usize,
+    /// From sending an action to seeing it applied
+    latencies: Vec<Duration>,
+    failure: Option<String>,
+}
+
+impl TableReport {
+    fn absorb(&mut self, seat: &Seat) {
+        self.actions += seat.actions;
+        self.messages += seat.messages;
+        self.latencies.extend_from_slice(&seat.latencies);
+    }
+}
+
+/// One player's connection, with the checks every received message goes through
+struct Seat {
+    name: String,
+    player_id: PlayerId,
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    timeout: Duration,
+    /// Last `seq` of the current game's stream; None before the first game message
+    last_seq: Option<u64>,
+    /// Set after GameOver, so the next game's stream may start again at 1; the old one can
+    /// still carry a few messages until then
+    stream_ended: bool,
+    /// Set after ResyncRequired until the replay has closed the gap
+    resyncing: bool,
+    /// Our action waiting to be echoed back as a PlayerAction
+    pending_action: Option<Instant>,
+    actions: usize,
+    messages: usize,
+    latencies: Vec<Duration>,
+}
+
+impl Seat {
+    async fn connect(options: &Options, http: &reqwest::Client, name: String) -> Result<Self, String> {
+        let response = http
+            .post(format!("{}/api/register", options.url))
+            .header("content-type", "application/json")
+            .body(serde_json::json!({ "username": name, "password": "stress client" }).to_string())
+            .send()
+            .await
+            .map_err(|e| format!("{}: register failed: {}", name, e))?;
+        let status = response.status();
+        let body = response.text().await.map_err(|e| format!("{}: register failed: {}", name, e))?;
+        if !status.is_success() {
+            return Err(format!("{}: register returned {}: {}", name, status, body));
+        }
+        let session: serde_json::Value = serde_json::from_str(&body).map_err(|e| format!("{}: bad register reply: {}", name, e))?;
+        let token = session["token"].as_str().ok_or_else(|| format!("{}: register reply has no token", name))?;
+
+        let (socket, _) = tokio_tungstenite::connect_async(options.ws_url(token)?)
+            .await
+            .map_err(|e| format!("{}: WebSocket connect failed: {}", name, e))?;
+        let mut seat = Self {
+            name,
+            player_id: String::new(),
+            socket,
+            timeout: options.timeout,
+            last_seq: None,
+            stream_ended: false,
+            resyncing: false,
+            pending_action: None,
+            actions: 0,
+            messages: 0,
+            latencies: Vec::new(),
+        };
+        seat.player_id = seat.expect("Connected", |msg| match msg {
+            ServerMessage::Connected { player_id } => Some(player_id),
+            _ => None,
+        }).await?;
+        if Some(seat.player_id.as_str()) != session["user_id"].as_str() {
+            return Err(format!("{}: Connected names {} instead of the registered user", seat.name, seat.player_id));
+        }
+        Ok(seat)
+    }
+
+    async fn send(&mut self, message: ClientMessage) -> Result<(), String> {
+        let text = serde_json::to_string(&ClientEnvelope::from(message)).map_err(|e| e.to_string())?;
+        self.socket.send(Message::Text(text)).await.map_err(|e| format!("{}: send failed: {}", self.name, e))
+    }
+
+    /// The next message that passes the checks; replayed duplicates are skipped
+    async fn recv(&mut self) -> Result<ServerMessage, String> {
+        loop {
+            let frame = tokio::time::timeout(self.timeout, self.socket.next())
+                .await
+                .map_err(|_| format!("{}: no message for {:?}", self.name, self.timeout))?
+                .ok_or_else(|| format!("{}: server closed the connection", self.name))?
+                .map_err(|e| format!("{}: WebSocket error: {}", self.name, e))?;
+            let Message::Text(text) = frame else { continue };
+            self.messages += 1;
+
+            let json: serde_json::Value = serde_json::from_str(&text)
+                .map_err(|e| format!("{}: frame is not JSON ({}): {}", self.name, e, text))?;
+            let message = if json.get("seq").is_some() {
+                let sequenced: SequencedMessage = serde_json::from_value(json)
+                    .map_err(|e| format!("{}: unreadable game message ({}): {}", self.name, e, text))?;
+                if !self.check_seq(sequenced.seq, &sequenced.message)? {
+                    continue;
+                }
+                sequenced.message
+            } else {
+                serde_json::from_value(json).map_err(|e| format!("{}: unreadable message ({}): {}", self.name, e, text))?
+            };
+
+            match message {
+                ServerMessage::Error { message, .. } => return Err(format!("{}: server error: {}", self.name, message)),
+                ServerMessage::ResyncRequired { missed, .. } => {
+                    let seq = self.last_seq.unwrap_or(0);
+                    println!("{}: missed {} messages, resyncing from seq {}", self.name, missed, seq);
+                    self.resyncing = true;
+                    self.send(ClientMessage::ResyncFrom { seq }).await?;
+                }
+                ServerMessage::PlayerAction { ref player_id, .. } if *player_id == self.player_id => {
+                    if let Some(sent) = self.pending_action.take() {
+                        self.latencies.push(sent.elapsed());
+                    }
+                    return Ok(message);
+                }
+                message => return Ok(message),
+            }
+        }
+    }
+
+    /// Whether a game message is new; errors on a gap or repeat the server did not announce
+    fn check_seq(&mut self, seq: u64, message: &ServerMessage) -> Result<bool, String> {
+        let expected = self.last_seq.map_or(1, |last| last + 1);
+        if seq == expected || (self.stream_ended && seq == 1) {
+            self.last_seq = Some(seq);
+            self.stream_ended &= seq != 1;
+            return Ok(true);
+        }
+        if self.resyncing {
+            if seq < expected {
+                // Replayed or already seen before the replay caught up
+                return Ok(false);
+            }
+            // Too far behind for a replay: the server sends a fresh GameState instead
+            if matches!(message, ServerMessage::GameState { .. }) {
+                self.last_seq = Some(seq);
+                self.resyncing = false;
+                return Ok(true);
+            }
+            // Comes round again in the replay
+            return Ok(false);
+        }
+        Err(format!("{}: expected seq {}, got {} ({:?})", self.name, expected, seq, message))
+    }
+
+    /// Skip messages until `pick` accepts one
+    async fn expect<T>(&mut self, what: &str, pick: impl Fn(ServerMessage) -> Option<T>) -> Result<T, String> {
+        loop {
+            let message = self.recv().await.map_err(|e| format!("{} (waiting for {})", e, what))?;
+            if let Some(found) = pick(message) {
+                return Ok(found);
+            }
+        }
+    }
+
+    /// Play a random valid action on every turn and start each next round when it is ours,
+    /// until the game ends. Returns the final scores.
+    async fn play_to_the_end(mut self) -> (Self, Result<HashMap<PlayerId, i32>, String>) {
+        let result = loop {
+            let message = match self.recv().await {
+                Ok(message) => message,
+                Err(e) => break Err(e),
+            };
+            let sent = match message {
+                ServerMessage::YourTurn { valid_actions, .. } => {
+                    let choice = valid_actions.choose(&mut rand::thread_rng()).cloned();
+                    match choice {
+                        Some(PlayerAction::Bid(bid)) => Some(ClientMessage::PlaceBid { bid, state_version: None }),
+                        Some(PlayerAction::PlayCard(card)) => Some(ClientMessage::PlayCard { card, state_version: None }),
+                        // Nothing to play between rounds; the GameState below covers it
+                        Some(PlayerAction::HiddenBid) | None => None,
+                    }
+                }
+                ServerMessage::GameState { state } if state.phase == GamePhase::RoundComplete && state.your_turn => {
+                    Some(ClientMessage::StartNextRound)
+                }
+                ServerMessage::GameOver { final_scores, .. } => {
+                    self.stream_ended = true;
+                    break Ok(final_scores);
+                }
+                _ => None,
+            };
+            if let Some(message) = sent {
+                if matches!(message, ClientMessage::PlaceBid { .. } | ClientMessage::PlayCard { .. }) {
+                    self.actions += 1;
+                    self.pending_action = Some(Instant::now());
+                }
+                if let Err(e) = self.send(message).await {
+                    break Err(e);
+                }
+            }
+        };
+        (self, result)
+    }
+}
+
+/// Register and connect a table's seats and play `options.games` games on it
+async fn run_table(options: Options, table: usize) -> TableReport {
+    let mut report = TableReport::default();
+    let run = uuid::Uuid::new_v4().simple().to_string();
+    let http = reqwest::Client::new();
+    let mut seats = Vec::with_capacity(options.players);
+    for n in 0..options.players {
+        match Seat::connect(&options, &http, format!("stress-{}-{}", &run[..8], n)).await {
+            Ok(seat) => seats.push(seat),
+            Err(e) => {
+                report.failure = Some(e);
+                return report;
+            }
+        }
+    }
+
+    for game in 1..=options.games {
+        let (played, result) = play_game(&options, seats).await;
+        seats = played;
+        match result {
+            Ok(()) => report.games += 1,
+            Err(e) => {
+                report.failure = Some(format!("table {} game {}: {}", table, game, e));
+                break;
+            }
+        }
+    }
+    for seat in &seats {
+        report.absorb(seat);
+    }
+    report
+}
+
+/// One game from an empty lobby to GameOver, the first seat hosting
+async fn play_game(options: &Options, mut seats: Vec<Seat>) -> (Vec<Seat>, Result<(), String>) {
+    if let Err(e) = fill_lobby(options, &mut seats).await {
+        return (seats, Err(e));
+    }
+
+    let plays: Vec<_> = seats.into_iter().map(|seat| tokio::spawn(seat.play_to_the_end())).collect();
+    let mut seats = Vec::with_capacity(plays.len());
+    let mut scores: Option<HashMap<PlayerId, i32>> = None;
+    let mut failure = None;
+    for play in plays {
+        let (seat, result) = match play.await {
+            Ok(played) => played,
+            Err(e) => return (seats, Err(format!("seat task failed: {}", e))),
+        };
+        match (result, &scores) {
+            (Err(e), _) => failure = failure.or(Some(e)),
+            (Ok(theirs), Some(ours)) if theirs != *ours => {
+                failure = failure.or(Some(format!("{} saw final scores {:?}, others {:?}", seat.name, theirs, ours)));
+            }
+            (Ok(theirs), _) => {
+                if theirs.len() != options.players {
+                    failure = failure.or(Some(format!("{} saw {} final scores for {} seats", seat.name, theirs.len(), options.players)));
+                }
+                scores = Some(theirs);
+            }
+        }
+        seats.push(seat);
+    }
+    (seats, failure.map_or(Ok(()), Err))
+}
+
+/// Create a lobby, have everyone join and ready up, and start the game
+async fn fill_lobby(options: &Options, seats: &mut [Seat]) -> Result<(), String> {
+    let settings = GameSettings {
+        player_count: options.players,
+        rules: RuleSet { max_cards: options.max_cards, ..Default::default() },
+        ..Default::default()
+    };
+    let (host, guests) = seats.split_first_mut().ok_or("table has no seats")?;
+    host.send(ClientMessage::CreateLobby { settings }).await?;
+    let code = host.expect("LobbyCreated", |msg| match msg {
+        ServerMessage::LobbyCreated { code, .. } => Some(code),
+        _ => None,
+    }).await?;
+
+    for guest in guests.iter_mut() {
+        guest.send(ClientMessage::JoinLobby { lobby_id: IdOrCode::Code(code.clone()) }).await?;
+        guest.expect("LobbyJoined", |msg| matches!(msg, ServerMessage::LobbyJoined { .. }).then_some(())).await?;
+        guest.send(ClientMessage::SetReady { ready: true }).await?;
+    }
+    let players = options.players;
+    host.expect("everyone ready", |msg| match msg {
+        ServerMessage::LobbyUpdated { lobby } if lobby.players.len() == players && lobby.players.iter().all(|p| p.ready || p.id == lobby.host) => Some(()),
+        _ => None,
+    }).await?;
+
+    host.send(ClientMessage::StartGame).await?;
+    let game_id = host.expect("GameStarting", |msg| match msg {
+        ServerMessage::GameStarting { game_id, .. } => Some(game_id),
+        _ => None,
+    }).await?;
+    for guest in guests.iter_mut() {
+        let theirs = guest.expect("GameStarting", |msg| match msg {
+            ServerMessage::GameStarting { game_id, .. } => Some(game_id),
+            _ => None,
+        }).await?;
+        if theirs != game_id {
+            return Err(format!("{} was sent to game {} instead of {}", guest.name, theirs, game_id));
+        }
+    }
+    Ok(())
+}
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    sorted[((sorted.len() - 1) as f64 * p).round() as usize]
+}
+
+#[tokio::main]
+async fn main() {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            if !e.is_empty() {
+                eprintln!("{}\n", e);
+            }
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    };
+    println!(
+        "Playing {} game(s) on each of {} table(s) of {} against {}",
+        options.games, options.tables, options.players, options.url
+    );
+
+    let started = Instant::now();
+    let tables: Vec<_> = (1..=options.tables)
+        .map(|table| tokio::spawn(run_table(options.clone(), table)))
+        .collect();
+    let mut total = TableReport::default();
+    let mut failures = Vec::new();
+    for table in tables {
+        let report = table.await.unwrap_or_else(|e| TableReport { failure: Some(format!("table task failed: {}", e)), ..Default::default() });
+        total.games += report.games;
+        total.actions += report.actions;
+        total.messages += report.messages;
+        total.latencies.extend(report.latencies);
+        failures.extend(report.failure);
+    }
+    let elapsed = started.elapsed();
+
+    total.latencies.sort();
+    println!(
+        "{} game(s), {} actions, {} messages in {:.1}s ({:.1} games/min)",
+        total.games,
+        total.actions,
+        total.messages,
+        elapsed.as_secs_f64(),
+        total.games as f64 * 60.0 / elapsed.as_secs_f64().max(f64::EPSILON),
+    );
+    println!(
+        "Action latency: p50 {:?}, p95 {:?}, max {:?}",
+        percentile(&total.latencies, 0.5),
+        percentile(&total.latencies, 0.95),
+        total.latencies.last().copied().unwrap_or_default(),
+    );
+    if failures.is_empty() {
+        println!("No failures");
+    } else {
+        println!("{} of {} table(s) failed:", failures.len(), options.tables);
+        for failure in &failures {
+            println!("  {}", failure);
+        }
+        std::process::exit(1);
+    }
+}
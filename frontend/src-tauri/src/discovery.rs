@@ -0,0 +1,71 @@
+//! Finding German Bridge servers on the local network by broadcasting a discovery probe
+
+use german_bridge_backend::discovery::{Announcement, DISCOVERY_PORT, PROBE};
+use serde::Serialize;
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket};
+use std::time::{Duration, Instant};
+
+/// A server that answered the probe
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiscoveredServer {
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    /// Ready to pass to the WebSocket store
+    pub ws_url: String,
+    pub version: String,
+}
+
+/// The directed broadcast address of `addr`'s network
+fn broadcast_address(addr: Ipv4Addr, prefix: u8) -> Ipv4Addr {
+    let host_mask = u32::MAX.checked_shr(u32::from(prefix)).unwrap_or(0);
+    Ipv4Addr::from(u32::from(addr) | host_mask)
+}
+
+/// Broadcast a probe on every network in `networks` (and the limited broadcast address),
+/// collecting replies until `timeout` passes. Repeated replies from one address are listed
+/// once; a server on several networks is listed for each address it answered from.
+pub fn discover(networks: &[(Ipv4Addr, u8)], timeout: Duration) -> std::io::Result<Vec<DiscoveredServer>> {
+    let socket = UdpSocket::bind(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.set_broadcast(true)?;
+
+    let mut targets: Vec<Ipv4Addr> = networks.iter().map(|&(addr, prefix)| broadcast_address(addr, prefix)).collect();
+    targets.push(Ipv4Addr::BROADCAST);
+    targets.sort_unstable();
+    targets.dedup();
+    for target in targets {
+        // One unreachable network must not stop the others
+        let _ = socket.send_to(PROBE, SocketAddrV4::new(target, DISCOVERY_PORT));
+    }
+
+    let deadline = Instant::now() + timeout;
+    let mut servers: Vec<DiscoveredServer> = Vec::new();
+    let mut buf = [0u8; 1024];
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()).filter(|d| !d.is_zero()) {
+        socket.set_read_timeout(Some(remaining))?;
+        let (len, from) = match socket.recv_from(&mut buf) {
+            Ok(received) => received,
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => break,
+            // e.g. ICMP port unreachable reported on some platforms
+            Err(_) => continue,
+        };
+        let Some(announcement) = Announcement::parse(&buf[..len]) else { continue };
+        let server = discovered(from, announcement);
+        if !servers.iter().any(|known| known.host == server.host && known.port == server.port) {
+            servers.push(server);
+        }
+    }
+    Ok(servers)
+}
+
+fn discovered(from: SocketAddr, announcement: Announcement) -> DiscoveredServer {
+    let host = from.ip().to_string();
+    DiscoveredServer {
+        ws_url: format!("ws://{}:{}{}", host, announcement.port, announcement.ws_path),
+        name: announcement.name,
+        host,
+        port: announcement.port,
+        version: announcement.version,
+    }
+}
@@ -1,26 +1,135 @@
-use std::net::IpAddr;
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Mutex;
+use std::time::Duration;
 use pnet::datalink;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use discovery::DiscoveredServer;
+use german_bridge_backend::engine::card::Card;
+use german_bridge_backend::engine::schedule::RoundSchedule;
+use german_bridge_backend::protocol::ClientMessage;
+use local_game::{LocalGame, LocalGameView};
+use server_connection::{ServerConnection, Session};
 
-// Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
+mod discovery;
+mod local_game;
+mod server_connection;
+
+/// Every `ServerMessage` from the game server is emitted to the webview under this event
+const SERVER_MESSAGE_EVENT: &str = "server-message";
+/// Emitted with a `ConnectionStatus` when the server connection opens or closes
+const CONNECTION_EVENT: &str = "server-connection";
+
+/// The offline game in progress, if any
+#[derive(Default)]
+struct LocalGameSlot(Mutex<Option<LocalGame>>);
+
+/// The connection to a game server, if any
+#[derive(Default)]
+struct ServerSlot(Mutex<Option<ServerConnection>>);
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum ConnectionStatus {
+    Connected { url: String },
+    /// `reason` is set when the connection failed rather than being closed
+    Closed { reason: Option<String> },
+}
+
+/// Log in to the server at `api_url`; pass the session's token to `connect_server`
 #[tauri::command]
-fn greet(name: &str) -> String {
-    format!("Hello, {}! You've been greeted from Rust!", name)
+async fn login(api_url: String, username: String, password: String) -> Result<Session, String> {
+    tauri::async_runtime::spawn_blocking(move || server_connection::login(&api_url, &username, &password))
+        .await
+        .map_err(|e| e.to_string())?
 }
 
-fn get_local_ip() -> Option<String> {
+/// Connect to a server's WebSocket; server messages arrive as `server-message` events.
+/// Disconnect first to switch servers.
+#[tauri::command]
+async fn connect_server(app: AppHandle, slot: tauri::State<'_, ServerSlot>, ws_url: String, token: String) -> Result<(), String> {
+    if slot.0.lock().unwrap().as_ref().is_some_and(ServerConnection::is_open) {
+        return Err("Already connected".to_string());
+    }
+
+    let messages = app.clone();
+    let closed = app.clone();
+    let connection = ServerConnection::connect(
+        &ws_url,
+        &token,
+        move |message| {
+            let _ = messages.emit(SERVER_MESSAGE_EVENT, message);
+        },
+        move |reason| {
+            let _ = closed.emit(CONNECTION_EVENT, ConnectionStatus::Closed { reason });
+        },
+    )
+    .await?;
+    *slot.0.lock().unwrap() = Some(connection);
+    let _ = app.emit(CONNECTION_EVENT, ConnectionStatus::Connected { url: ws_url });
+    Ok(())
+}
+
+/// Send a `ClientMessage`, e.g. `{ "type": "JoinLobby", "payload": { "lobby_id": "K7QF2M" } }`
+#[tauri::command]
+fn send_message(slot: tauri::State<ServerSlot>, message: ClientMessage) -> Result<(), String> {
+    slot.0.lock().unwrap().as_ref().ok_or("Not connected")?.send(message)
+}
+
+#[tauri::command]
+fn disconnect_server(slot: tauri::State<ServerSlot>) {
+    slot.0.lock().unwrap().take();
+}
+
+/// Start an offline game against `bots` bots, replacing any game in progress
+#[tauri::command]
+fn start_local_game(slot: tauri::State<LocalGameSlot>, bots: usize, schedule: Option<RoundSchedule>) -> Result<LocalGameView, String> {
+    let (game, view) = LocalGame::start(bots, schedule.unwrap_or_default())?;
+    *slot.0.lock().unwrap() = Some(game);
+    Ok(view)
+}
+
+#[tauri::command]
+fn place_bid(slot: tauri::State<LocalGameSlot>, tricks: u8) -> Result<LocalGameView, String> {
+    slot.0.lock().unwrap().as_mut().ok_or("No local game in progress")?.place_bid(tricks)
+}
+
+#[tauri::command]
+fn play_card(slot: tauri::State<LocalGameSlot>, card: Card) -> Result<LocalGameView, String> {
+    slot.0.lock().unwrap().as_mut().ok_or("No local game in progress")?.play_card(card)
+}
+
+/// German Bridge servers answering on the local network within `timeout_ms` (default 1500)
+#[tauri::command]
+async fn discover_servers(timeout_ms: Option<u64>) -> Result<Vec<DiscoveredServer>, String> {
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(1500));
+    tauri::async_runtime::spawn_blocking(move || discovery::discover(&lan_networks(), timeout))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// IPv4 addresses of this machine's interfaces with their prefix lengths, leaving out
+/// loopback and link-local ones
+fn lan_networks() -> Vec<(Ipv4Addr, u8)> {
+    let mut networks = Vec::new();
     for iface in datalink::interfaces() {
         for ip in iface.ips {
             match ip.ip() {
                 IpAddr::V4(addr) => {
                     if !addr.is_loopback() && !addr.is_link_local() {
-                        return Some(addr.to_string());
+                        networks.push((addr, ip.prefix()));
                     }
                 }
                 _ => {}
             }
         }
     }
-    None
+    networks
+}
+
+fn get_local_ip() -> Option<String> {
+    lan_networks().first().map(|(addr, _)| addr.to_string())
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -32,7 +141,18 @@ pub fn run() {
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![greet])
+        .manage(LocalGameSlot::default())
+        .manage(ServerSlot::default())
+        .invoke_handler(tauri::generate_handler![
+            login,
+            connect_server,
+            send_message,
+            disconnect_server,
+            discover_servers,
+            start_local_game,
+            place_bid,
+            play_card
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,133 @@
+//! Offline games against bots, run on the embedded game engine without a server
+
+use german_bridge_backend::engine::auto_play::RulesAware;
+use german_bridge_backend::engine::bidding::Bid;
+use german_bridge_backend::engine::card::{Card, Suit};
+use german_bridge_backend::engine::rules::RuleSet;
+use german_bridge_backend::engine::schedule::RoundSchedule;
+use german_bridge_backend::engine::scoring::{PlayerRoundResult, RoundResult};
+use german_bridge_backend::engine::{Event, GamePhase, GameState, PlayerAction, PlayerId};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// The local player's id; bots are "Bot 1", "Bot 2", ...
+pub const LOCAL_PLAYER: &str = "You";
+
+/// Local tables seat the player and one to five bots
+pub const MAX_BOTS: usize = 5;
+
+/// What the frontend renders after each command
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalGameView {
+    pub phase: GamePhase,
+    pub round_number: usize,
+    pub total_rounds: usize,
+    pub cards_per_player: usize,
+    pub trump_suit: Option<Suit>,
+    pub players: Vec<PlayerId>,
+    pub your_hand: Vec<Card>,
+    pub current_trick: Vec<(PlayerId, Card)>,
+    pub current_player: PlayerId,
+    pub your_turn: bool,
+    /// Bids you may place now; empty unless it is your bid
+    pub valid_bids: Vec<u8>,
+    /// Cards you may play now; empty unless it is your card
+    pub valid_cards: Vec<Card>,
+    pub scores: HashMap<PlayerId, i32>,
+    pub current_round: Vec<PlayerRoundResult>,
+    pub history: Vec<RoundResult>,
+    /// Everything that happened since the previous command, bots' turns included, so the
+    /// frontend can animate it in order
+    pub events: Vec<Event>,
+}
+
+pub struct LocalGame {
+    state: GameState,
+}
+
+impl LocalGame {
+    /// Deal the first round for the local player and `bots` bots, and let the bots act
+    /// until it is the player's turn
+    pub fn start(bots: usize, schedule: RoundSchedule) -> Result<(Self, LocalGameView), String> {
+        if !(1..=MAX_BOTS).contains(&bots) {
+            return Err(format!("Play against 1 to {} bots", MAX_BOTS));
+        }
+        let players = std::iter::once(LOCAL_PLAYER.to_string())
+            .chain((1..=bots).map(|n| format!("Bot {}", n)))
+            .collect();
+        let mut game = Self { state: GameState::with_rules(players, schedule, RuleSet::default()) };
+        let events = game.run_bots();
+        let view = game.view(events);
+        Ok((game, view))
+    }
+
+    pub fn place_bid(&mut self, tricks: u8) -> Result<LocalGameView, String> {
+        self.act(PlayerAction::Bid(Bid { tricks }))
+    }
+
+    pub fn play_card(&mut self, card: Card) -> Result<LocalGameView, String> {
+        self.act(PlayerAction::PlayCard(card))
+    }
+
+    /// Apply the local player's action, then the bots' until it is the player's turn again
+    fn act(&mut self, action: PlayerAction) -> Result<LocalGameView, String> {
+        let mut events: Vec<Event> = self.state
+            .apply_action(LOCAL_PLAYER.to_string(), action)
+            .map_err(|e| e.to_string())?
+            .into_vec();
+        events.extend(self.run_bots());
+        Ok(self.view(events))
+    }
+
+    /// Bots play the way the server plays for timed out players; finished rounds are dealt on straight away
+    fn run_bots(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+        loop {
+            match self.state.phase {
+                GamePhase::GameComplete => break,
+                GamePhase::RoundComplete => events.extend(self.state.advance_to_next_round()),
+                _ if self.state.current_player == LOCAL_PLAYER => break,
+                _ => {
+                    let bot = self.state.current_player.clone();
+                    let Some(action) = self.state.get_auto_action(&RulesAware) else { break };
+                    match self.state.apply_action(bot, action) {
+                        Ok(applied) => events.extend(applied),
+                        Err(_) => break,
+                    }
+                }
+            }
+        }
+        events
+    }
+
+    fn view(&self, events: Vec<Event>) -> LocalGameView {
+        let state = &self.state;
+        let your_turn = state.current_player == LOCAL_PLAYER && state.phase != GamePhase::GameComplete;
+        let (mut valid_bids, mut valid_cards) = (Vec::new(), Vec::new());
+        for action in state.get_valid_actions(LOCAL_PLAYER.to_string()) {
+            match action {
+                PlayerAction::Bid(bid) => valid_bids.push(bid.tricks),
+                PlayerAction::PlayCard(card) => valid_cards.push(card),
+                PlayerAction::HiddenBid => {}
+            }
+        }
+        LocalGameView {
+            phase: state.phase,
+            round_number: state.round_number,
+            total_rounds: state.total_rounds(),
+            cards_per_player: state.cards_per_player,
+            trump_suit: state.trump_suit,
+            players: state.players.clone(),
+            your_hand: state.hands.get(LOCAL_PLAYER).map(|hand| hand.cards().to_vec()).unwrap_or_default(),
+            current_trick: state.current_trick.cards.to_vec(),
+            current_player: state.current_player.clone(),
+            your_turn,
+            valid_bids,
+            valid_cards,
+            scores: state.total_scores.clone(),
+            current_round: state.current_round.clone(),
+            history: state.history.to_vec(),
+            events,
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! The connection to a game server, held on the Rust side so the webview sends and receives
+//! typed protocol messages instead of managing a raw WebSocket
+
+use futures_util::{SinkExt, StreamExt};
+use german_bridge_backend::protocol::{ClientMessage, ServerMessage};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+
+/// How often the connection pings the server to stay alive
+pub const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// What `POST /api/login` answers with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub token: String,
+    pub username: String,
+    pub user_id: String,
+}
+
+#[derive(Serialize)]
+struct Credentials<'a> {
+    username: &'a str,
+    password: &'a str,
+}
+
+/// Log in over HTTP at `api_url` (e.g. `http://192.168.1.20:8080`). Blocking.
+pub fn login(api_url: &str, username: &str, password: &str) -> Result<Session, String> {
+    let url = format!("{}/api/login", api_url.trim_end_matches('/'));
+    match ureq::post(&url).send_json(Credentials { username, password }) {
+        Ok(response) => response.into_json().map_err(|e| e.to_string()),
+        // The server explains rejections in the body
+        Err(ureq::Error::Status(_, response)) => Err(response.into_string().unwrap_or_else(|e| e.to_string())),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// An open connection; dropping it closes the socket
+pub struct ServerConnection {
+    outgoing: mpsc::UnboundedSender<ClientMessage>,
+    task: JoinHandle<()>,
+}
+
+impl ServerConnection {
+    /// Connect to the WebSocket at `ws_url` (e.g. `ws://192.168.1.20:8080/ws`) with a session
+    /// token. Every message from the server is handed to `on_message`; `on_close` runs once
+    /// when the connection ends, with the reason if it failed.
+    pub async fn connect(
+        ws_url: &str,
+        token: &str,
+        on_message: impl Fn(ServerMessage) + Send + 'static,
+        on_close: impl FnOnce(Option<String>) + Send + 'static,
+    ) -> Result<Self, String> {
+        let separator = if ws_url.contains('?') { '&' } else { '?' };
+        let url = format!("{}{}token={}", ws_url, separator, token);
+        let (socket, _) = tokio_tungstenite::connect_async(url).await.map_err(|e| e.to_string())?;
+        let (mut sink, mut stream) = socket.split();
+        let (outgoing, mut queued) = mpsc::unbounded_channel::<ClientMessage>();
+
+        let task = tokio::spawn(async move {
+            let mut ping = tokio::time::interval(PING_INTERVAL);
+            let reason = loop {
+                tokio::select! {
+                    message = queued.recv() => {
+                        // The sender half lives in this connection, so this only ends when it is dropped
+                        let Some(message) = message else { break None };
+                        let text = serde_json::to_string(&message).expect("client messages serialize");
+                        if let Err(e) = sink.send(Message::Text(text)).await {
+                            break Some(e.to_string());
+                        }
+                    }
+                    frame = stream.next() => match frame {
+                        Some(Ok(Message::Text(text))) => match serde_json::from_str(&text) {
+                            Ok(message) => on_message(message),
+                            Err(e) => eprintln!("Ignoring unreadable server message {}: {}", text, e),
+                        },
+                        Some(Ok(Message::Close(frame))) => break frame.map(|f| f.reason.to_string()).filter(|r| !r.is_empty()),
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => break Some(e.to_string()),
+                        None => break None,
+                    },
+                    _ = ping.tick() => {
+                        let text = serde_json::to_string(&ClientMessage::Ping).expect("client messages serialize");
+                        if let Err(e) = sink.send(Message::Text(text)).await {
+                            break Some(e.to_string());
+                        }
+                    }
+                }
+            };
+            let _ = sink.close().await;
+            on_close(reason);
+        });
+
+        Ok(Self { outgoing, task })
+    }
+
+    pub fn send(&self, message: ClientMessage) -> Result<(), String> {
+        if self.task.is_finished() {
+            return Err("Not connected".to_string());
+        }
+        self.outgoing.send(message).map_err(|_| "Not connected".to_string())
+    }
+
+    pub fn is_open(&self) -> bool {
+        !self.task.is_finished()
+    }
+}
@@ -0,0 +1,15 @@
+import { invoke } from "@tauri-apps/api/core";
+
+// A German Bridge server that answered on the local network (desktop app only)
+export interface DiscoveredServer {
+  name: string;
+  host: string;
+  port: number;
+  ws_url: string;
+  version: string;
+}
+
+// Broadcast a discovery probe and list the servers that answer within `timeoutMs`
+export function discoverServers(timeoutMs = 1500): Promise<DiscoveredServer[]> {
+  return invoke<DiscoveredServer[]>("discover_servers", { timeoutMs });
+}
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Something a player unlocks by how they play, once it has happened `target` times
+ */
+export type Achievement = "FirstWin" | "Veteran" | "ZeroHero" | "GrandSlam" | "Flawless";
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which bids the current bidder may place
+ */
+export type BidConstraint = { player_id: string, 
+/**
+ * Highest bid allowed, the number of cards dealt
+ */
+max_bid: number, 
+/**
+ * Bids in 0..=max_bid that are still not allowed: the last bidder's hook bid, and zero
+ * where the rules forbid it
+ */
+forbidden: Array<number>, };
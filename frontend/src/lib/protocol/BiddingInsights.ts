@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BidConstraint } from "./BidConstraint";
+import type { PlacedBid } from "./PlacedBid";
+
+/**
+ * Figures a client needs for the bid dialog, sent with YourTurn during bidding
+ */
+export type BiddingInsights = { hand_size: number, trump_count: number, 
+/**
+ * Bids placed so far this round, in bidding order
+ */
+bids: Array<PlacedBid>, last_bidder: boolean, 
+/**
+ * The bid that would make the total equal the cards dealt; only set for the last bidder
+ */
+forbidden_bid: number | null, 
+/**
+ * Bids open to the current bidder
+ */
+constraint: BidConstraint, };
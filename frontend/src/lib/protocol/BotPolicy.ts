@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Host-controlled limits on bot accounts in a lobby
+ */
+export type BotPolicy = { 
+/**
+ * Reject all bot accounts
+ */
+forbid_bots: boolean, 
+/**
+ * Maximum number of bots allowed to join
+ */
+max_bots: number | null, 
+/**
+ * Refuse to start unless at least one human is seated
+ */
+require_human: boolean, };
@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Bid } from "./Bid";
+import type { Card } from "./Card";
+import type { GameSettings } from "./GameSettings";
+import type { IdOrCode } from "./IdOrCode";
+import type { LobbyQuery } from "./LobbyQuery";
+import type { RoomId } from "./RoomId";
+import type { SuitEncoding } from "./SuitEncoding";
+
+/**
+ * A client message with the room it is addressed to, sent as an extra `room_id` key
+ * beside `type` and `payload`. Without one, lobby actions go to the player's lobby and
+ * game actions to their active game. A `request_id` is echoed back in the message's Ack
+ * or Error.
+ */
+export type ClientEnvelope = { room_id?: RoomId | null, request_id?: string, } & ({ "type": "CreateLobby", "payload": { settings: GameSettings, } } | { "type": "CreateLobbyFromPreset", "payload": { preset_id: string, } } | { "type": "ListPresets" } | { "type": "JoinLobby", "payload": { lobby_id: IdOrCode, } } | { "type": "LeaveLobby" } | { "type": "StartGame" } | { "type": "StartNextRound" } | { "type": "ListLobbies", "payload": LobbyQuery | null } | { "type": "KickPlayer", "payload": { player_id: string, } } | { "type": "TransferHost", "payload": { player_id: string, } } | { "type": "SetReady", "payload": { ready: boolean, } } | { "type": "CloseLobby", "payload": { lobby_id: IdOrCode, } } | { "type": "JoinQueue", "payload": { player_count: number, 
+/**
+ * Accept a table completed with bots after waiting too long for enough players;
+ * such games are unrated
+ */
+backfill?: boolean, } } | { "type": "LeaveQueue" } | { "type": "SetActiveRoom", "payload": { room: RoomId, } } | { "type": "SetSuitEncoding", "payload": { encoding: SuitEncoding, } } | { "type": "PlaceBid", "payload": { bid: Bid, state_version?: number, } } | { "type": "PlayCard", "payload": { card: Card, state_version?: number, } } | { "type": "RequestGameState" } | { "type": "RequestHint" } | { "type": "OpenSeat", "payload": { player_id: string, } } | { "type": "ForceTurn", "payload": { player_id: string, } } | { "type": "TakeSeat", "payload": { game_id: IdOrCode, seat: string, } } | { "type": "ListOpenSeats" } | { "type": "ResyncFrom", "payload": { seq: number, } } | { "type": "Ping" } | { "type": "TimeSync", "payload": { client_time_ms: number, } });
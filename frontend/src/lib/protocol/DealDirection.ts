@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Turn order around the table, relative to seat order
+ */
+export type DealDirection = "Clockwise" | "CounterClockwise";
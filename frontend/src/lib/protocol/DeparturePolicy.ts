@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Handling of a player who left a game for good; their turns are auto-played either way
+ */
+export type DeparturePolicy = "BotTakeover" | "Forfeit";
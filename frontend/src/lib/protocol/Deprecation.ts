@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Wire forms still accepted but due to be dropped
+ */
+export type Deprecation = "unversioned_action" | "binary_frames";
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Subsystems an instance can switch on and off at runtime
+ */
+export type Feature = "spectators" | "chat" | "ranked" | "bots";
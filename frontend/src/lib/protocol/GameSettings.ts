@@ -0,0 +1,73 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BotPolicy } from "./BotPolicy";
+import type { DeparturePolicy } from "./DeparturePolicy";
+import type { NextRoundPolicy } from "./NextRoundPolicy";
+import type { RoundSchedule } from "./RoundSchedule";
+import type { RuleSet } from "./RuleSet";
+import type { SharedAddressPolicy } from "./SharedAddressPolicy";
+import type { TieBreaker } from "./TieBreaker";
+
+export type GameSettings = { player_count: number, 
+/**
+ * @deprecated Set `rules.timer.turn_secs` instead, which wins when both are set. Still
+ * required, and used for turns the rules give no limit.
+ */
+turn_timeout_secs: number, allow_reconnect: boolean, 
+/**
+ * Sandbox lobby that only accepts bot accounts
+ */
+bots_only?: boolean, bot_policy?: BotPolicy, 
+/**
+ * @deprecated Set `rules.schedule` instead, which wins when both are set
+ */
+round_schedule?: RoundSchedule, 
+/**
+ * Hide other players' bids until everyone has bid
+ */
+blind_bidding?: boolean, 
+/**
+ * What happens to a seat whose player's reconnect window expires mid-game
+ */
+departure_policy?: DeparturePolicy, 
+/**
+ * Tournament this game counts towards; its webhook is called when the game completes
+ */
+tournament_id?: string | null, 
+/**
+ * Turn away players the host has blocked
+ */
+exclude_blocked?: boolean, 
+/**
+ * Rules for separating players level on points at the end, in order; players still
+ * level after all of them share the win
+ */
+tie_breakers?: Array<TieBreaker>, 
+/**
+ * House rules: hand size cap, turn direction, zero bids, end conditions, trump and timers
+ */
+rules?: RuleSet, 
+/**
+ * Privately tell a round's notably slowest player so, to keep long games moving
+ */
+pace_notices?: boolean, 
+/**
+ * Keep the game off the leaderboard; always set for matchmaking tables backfilled with bots
+ */
+unrated?: boolean, 
+/**
+ * Who starts the next round once a round's results are shown
+ */
+next_round?: NextRoundPolicy, 
+/**
+ * Start the next round on its own this long after a round ends; unset waits for players
+ */
+intermission_secs?: number, 
+/**
+ * What to do when players connected from the same address take seats
+ */
+shared_address?: SharedAddressPolicy, 
+/**
+ * Answer RequestHint with the bid or card the server's bots would choose; off for
+ * competitive play
+ */
+allow_hints?: boolean, };
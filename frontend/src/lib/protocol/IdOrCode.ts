@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A lobby or game named by UUID or by short code; serialized as a plain string
+ */
+export type IdOrCode = string;
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The pause after a round while its results are shown
+ */
+export type Intermission = { 
+/**
+ * Players whose StartNextRound is still needed; under `AllPlayers`, any one of them
+ * otherwise
+ */
+waiting_for: Array<string>, 
+/**
+ * Server epoch milliseconds when the next round starts without them
+ */
+auto_start_ms: number | null, };
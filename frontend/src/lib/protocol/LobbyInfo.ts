@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GameSettings } from "./GameSettings";
+import type { PlayerInfo } from "./PlayerInfo";
+import type { Region } from "./Region";
+import type { ShortCode } from "./ShortCode";
+
+export type LobbyInfo = { id: string, 
+/**
+ * Short code to share instead of the id
+ */
+code: ShortCode, host: string, players: Array<PlayerInfo>, max_players: number, settings: GameSettings, bot_count: number, 
+/**
+ * The host's region, if known
+ */
+region: Region | null, 
+/**
+ * Slowest measured round trip among the lobby's players, rounded to 10 ms
+ */
+latency_ms: number | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GameSettings } from "./GameSettings";
+
+/**
+ * A named bundle of lobby settings saved by a user
+ */
+export type LobbyPreset = { id: string, name: string, settings: GameSettings, updated_at: string, };
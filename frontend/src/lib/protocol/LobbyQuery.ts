@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Filters and paging for the lobby browser; an empty query lists the first page of joinable lobbies
+ */
+export type LobbyQuery = { 
+/**
+ * Only lobbies for this many players
+ */
+player_count?: number, 
+/**
+ * Also list full lobbies waiting for their host to start
+ */
+include_full?: boolean, 
+/**
+ * Only lobbies hosted by mutual friends
+ */
+friends_only?: boolean, 
+/**
+ * `next_cursor` of the previous page
+ */
+after?: number, 
+/**
+ * Lobbies per page; defaults to 20 and is capped at 100
+ */
+limit?: number, };
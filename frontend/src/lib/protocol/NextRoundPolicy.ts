@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Who may start the next round during the intermission after a round
+ */
+export type NextRoundPolicy = "FirstBidder" | "Host" | "AllPlayers";
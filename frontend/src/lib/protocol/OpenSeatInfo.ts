@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ShortCode } from "./ShortCode";
+
+/**
+ * A departed player's seat that the host opened mid-game
+ */
+export type OpenSeatInfo = { game_id: string, code: ShortCode, seat: string, 
+/**
+ * Name of the player who left the seat
+ */
+username: string, 
+/**
+ * Score the new player inherits
+ */
+score: number, round_number: number, player_count: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PlacedBid = { player_id: string, 
+/**
+ * None while blind bidding hides the amount
+ */
+bid: number | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A player's finishing position. Players level on points and every tie-breaker share a
+ * place and the next place is skipped (1st, 1st, 3rd).
+ */
+export type Placement = { player_id: string, 
+/**
+ * 1 for the winners
+ */
+place: number, score: number, };
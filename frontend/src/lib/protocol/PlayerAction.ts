@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Bid } from "./Bid";
+import type { Card } from "./Card";
+
+export type PlayerAction = { "Bid": Bid } | { "PlayCard": Card } | "HiddenBid";
@@ -0,0 +1,51 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BidConstraint } from "./BidConstraint";
+import type { Card } from "./Card";
+import type { DeparturePolicy } from "./DeparturePolicy";
+import type { GamePhase } from "./GamePhase";
+import type { Intermission } from "./Intermission";
+import type { PlayerInfo } from "./PlayerInfo";
+import type { PlayerRoundResult } from "./PlayerRoundResult";
+import type { RoundResult } from "./RoundResult";
+import type { ShortCode } from "./ShortCode";
+import type { Suit } from "./Suit";
+import type { TurnDeadline } from "./TurnDeadline";
+
+export type PlayerGameView = { game_id: string, game_code: ShortCode, phase: GamePhase, your_hand: Array<Card>, current_trick: Array<[string, Card]>, scores: { [key in string]?: number }, 
+/**
+ * Shared with the game state and every other view built from it
+ */
+history: Array<RoundResult>, round_number: number, trump_suit: Suit | null, current_player: string, your_turn: boolean, current_round: Array<PlayerRoundResult>, state_version: number, player_names: { [key in string]?: string }, 
+/**
+ * Everyone at the table in seat order, with their display names and avatars
+ */
+players: Array<PlayerInfo>, 
+/**
+ * True while blind bidding hides other players' bids; their `bid` in `current_round` is then 0
+ */
+bids_hidden: boolean, 
+/**
+ * Players who left for good, and how their seat is handled
+ */
+departed: { [key in string]?: DeparturePolicy }, 
+/**
+ * When the current player's turn times out; None while no turn timer runs
+ */
+turn_deadline: TurnDeadline | null, 
+/**
+ * Bids open to the current bidder during bidding; None otherwise, and for other
+ * players while blind bidding hides bids
+ */
+bid_constraint: BidConstraint | null, 
+/**
+ * SHA-256 of the current round's deal seed, in hex, when deals are seeded
+ */
+deal_commitment: string | null, 
+/**
+ * Results stay off the leaderboard, e.g. at a table backfilled with bots
+ */
+unrated: boolean, 
+/**
+ * Set between rounds: who can start the next one and when it starts on its own
+ */
+intermission: Intermission | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PlayerInfo = { id: string, username: string, 
+/**
+ * Shown instead of `username` when set
+ */
+display_name: string | null, avatar: string | null, 
+/**
+ * Lobby ready flag; always false in game views
+ */
+ready: boolean, };
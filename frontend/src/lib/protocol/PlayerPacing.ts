@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Time one player spent deciding, per phase
+ */
+export type PlayerPacing = { player_id: string, bids: number, bidding_ms: number, plays: number, playing_ms: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type PlayerRoundResult = { player_id: string, bid: number, tricks_won: number, 
+/**
+ * Points taken off for timed-out turns; `score` already includes it
+ */
+penalty: number, score: number, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a player's friends see of them
+ */
+export type Presence = { "status": "Offline" } | { "status": "Online" } | { "status": "InLobby", lobby_id: string, } | { "status": "InGame", game_id: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Coarse location of a player or node, e.g. `eu-west`. Operators choose the names; players
+ * in the same region are matched together first.
+ */
+export type Region = string;
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A lobby or game whose members receive the same broadcasts
+ */
+export type RoomId = { "kind": "Lobby", "id": string } | { "kind": "Game", "id": string };
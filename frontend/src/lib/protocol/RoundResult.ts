@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PlayerRoundResult } from "./PlayerRoundResult";
+import type { Suit } from "./Suit";
+
+export type RoundResult = { round_number: number, 
+/**
+ * None for a round played without trump
+ */
+trump_suit: Suit | null, player_results: Array<PlayerRoundResult>, 
+/**
+ * Seed the round was dealt from, in hex, when deals are seeded
+ */
+deal_seed: string | null, };
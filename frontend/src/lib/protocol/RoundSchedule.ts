@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How the number of cards dealt changes from round to round
+ */
+export type RoundSchedule = "Ascending" | "UpAndDown";
@@ -0,0 +1,35 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DealDirection } from "./DealDirection";
+import type { RoundSchedule } from "./RoundSchedule";
+import type { TimerPolicy } from "./TimerPolicy";
+import type { TrumpMode } from "./TrumpMode";
+import type { ZeroBidRule } from "./ZeroBidRule";
+
+/**
+ * House rules a lobby plays by, validated on lobby creation and carried into the game state
+ */
+export type RuleSet = { 
+/**
+ * Most cards dealt in a round; the round schedule peaks here instead of at a full deck
+ */
+max_cards: number | null, 
+/**
+ * Which way bidding, play and the first bidder move around the table
+ */
+deal_direction: DealDirection, zero_bids: ZeroBidRule, 
+/**
+ * The game ends after the round in which someone reaches this score
+ */
+target_score: number | null, 
+/**
+ * The game ends after this many rounds, or sooner if the round schedule runs out
+ */
+round_limit: number | null, 
+/**
+ * How many cards each round deals; unset uses the settings' deprecated `round_schedule`
+ */
+schedule: RoundSchedule | null, trump: TrumpMode, timer: TimerPolicy, 
+/**
+ * Deal every round from a seed committed to up front and revealed when the round ends
+ */
+seeded_deals: boolean, };
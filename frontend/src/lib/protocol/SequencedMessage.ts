@@ -0,0 +1,82 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Achievement } from "./Achievement";
+import type { BiddingInsights } from "./BiddingInsights";
+import type { Card } from "./Card";
+import type { CardLabel } from "./CardLabel";
+import type { DeparturePolicy } from "./DeparturePolicy";
+import type { Deprecation } from "./Deprecation";
+import type { Feature } from "./Feature";
+import type { GamePhase } from "./GamePhase";
+import type { Intermission } from "./Intermission";
+import type { LobbyInfo } from "./LobbyInfo";
+import type { LobbyPreset } from "./LobbyPreset";
+import type { OpenSeatInfo } from "./OpenSeatInfo";
+import type { Placement } from "./Placement";
+import type { PlayerAction } from "./PlayerAction";
+import type { PlayerGameView } from "./PlayerGameView";
+import type { PlayerPacing } from "./PlayerPacing";
+import type { Presence } from "./Presence";
+import type { RoomId } from "./RoomId";
+import type { RoundResult } from "./RoundResult";
+import type { ShortCode } from "./ShortCode";
+import type { Suit } from "./Suit";
+import type { SuitAsset } from "./SuitAsset";
+import type { SuitEncoding } from "./SuitEncoding";
+import type { TrickCount } from "./TrickCount";
+import type { TrumpStats } from "./TrumpStats";
+import type { TurnDeadline } from "./TurnDeadline";
+import type { WinReason } from "./WinReason";
+
+/**
+ * A game message as sent on the wire: the usual `type`/`payload` plus `seq`
+ */
+export type SequencedMessage = { 
+/**
+ * Position in this player's message stream for the game, starting at 1
+ */
+seq: number, } & ({ "type": "Connected", "payload": { player_id: string, } } | { "type": "DisplayProfile", "payload": { suit_encoding: SuitEncoding, suits: Array<SuitAsset>, cards: Array<CardLabel>, } } | { "type": "Pong" } | { "type": "TimeSync", "payload": { client_time_ms: number, server_received_ms: number, server_sent_ms: number, } } | { "type": "Error", "payload": { message: string, 
+/**
+ * The failed message's `request_id`, if it had one
+ */
+request_id?: string, } } | { "type": "Ack", "payload": { request_id: string, } } | { "type": "FeatureDisabled", "payload": { feature: Feature, message: string, request_id?: string, } } | { "type": "DeprecationNotice", "payload": { feature: Deprecation, remove_after: string, } } | { "type": "ServerMaintenance", "payload": { shutdown_in_secs: number, } } | { "type": "LobbyCreated", "payload": { lobby_id: string, code: ShortCode, } } | { "type": "LobbyJoined", "payload": { lobby: LobbyInfo, } } | { "type": "LobbyUpdated", "payload": { lobby: LobbyInfo, } } | { "type": "LobbyList", "payload": { lobbies: Array<LobbyInfo>, 
+/**
+ * Pass as `after` to fetch the next page; absent on the last page
+ */
+next_cursor: number | null, } } | { "type": "LobbyListDelta", "payload": { changed: Array<LobbyInfo>, removed: Array<string>, } } | { "type": "PresetList", "payload": { presets: Array<LobbyPreset>, } } | { "type": "KickedFromLobby", "payload": { lobby_id: string, } } | { "type": "LobbyExpired", "payload": { lobby_id: string, } } | { "type": "SharedAddressWarning", "payload": { lobby_id: string, players: Array<string>, } } | { "type": "GameStarting", "payload": { game_id: string, code: ShortCode, } } | { "type": "ActiveRoom", "payload": { room: RoomId | null, rooms: Array<RoomId>, } } | { "type": "ResyncRequired", "payload": { room: RoomId, missed: number, } } | { "type": "QueueJoined", "payload": { player_count: number, position: number, 
+/**
+ * Seconds until bots fill the table; only present when backfill was requested and the
+ * server allows it
+ */
+backfill_after_secs: number | null, } } | { "type": "QueueLeft" } | { "type": "QueueBackfilled", "payload": { game_id: string, bots: number, message: string, } } | { "type": "ConnectionHint", "payload": { node_id: string, url: string, } } | { "type": "GameState", "payload": { state: PlayerGameView, } } | { "type": "YourTurn", "payload": { valid_actions: Array<PlayerAction>, 
+/**
+ * Only present during bidding
+ */
+bidding: BiddingInsights | null, 
+/**
+ * Only present while a turn timer runs
+ */
+deadline: TurnDeadline | null, } } | { "type": "Hint", "payload": { action: PlayerAction, state_version: number, } } | { "type": "PlayerAction", "payload": { player_id: string, action: PlayerAction, next_player: string, phase: GamePhase, 
+/**
+ * Cards on the table; empty once the action completed a trick
+ */
+current_trick: Array<[string, Card]>, state_version: number, } } | { "type": "TrickComplete", "payload": { winner: string, 
+/**
+ * The trick as played, in play order
+ */
+cards: Array<[string, Card]>, 
+/**
+ * Every player's tricks so far this round against their bid, in seat order
+ */
+tricks: Array<TrickCount>, 
+/**
+ * This was the round's last trick
+ */
+round_complete: boolean, } } | { "type": "RoundComplete", "payload": { results: RoundResult, } } | { "type": "NextRoundVote", "payload": { player_id: string, intermission: Intermission, } } | { "type": "GameOver", "payload": { final_scores: { [key in string]?: number }, 
+/**
+ * Finishing order, best first; players who forfeited are not placed
+ */
+placements: Array<Placement>, 
+/**
+ * In seat order; several for a shared win
+ */
+winners: Array<string>, win_reason: WinReason, } } | { "type": "GameSummary", "payload": { trump_stats: Array<TrumpStats>, best_trump: Suit | null, best_trump_line: string | null, } } | { "type": "PacingReport", "payload": { players: Array<PlayerPacing>, } } | { "type": "PaceNotice", "payload": { round_number: number, your_secs: number, others_average_secs: number, } } | { "type": "AchievementUnlocked", "payload": { achievement: Achievement, title: string, description: string, } } | { "type": "PlayerJoined", "payload": { player_id: string, } } | { "type": "PlayerLeft", "payload": { player_id: string, } } | { "type": "PlayerReconnected", "payload": { player_id: string, } } | { "type": "PlayerDisconnected", "payload": { game_id: string, player_id: string, resume_deadline: number, } } | { "type": "PlayerDeparted", "payload": { player_id: string, outcome: DeparturePolicy, } } | { "type": "TurnTimedOut", "payload": { game_id: string, player_id: string, } } | { "type": "SeatOpened", "payload": { game_id: string, seat: string, } } | { "type": "SeatTaken", "payload": { seat: string, player_id: string, } } | { "type": "OpenSeatList", "payload": { seats: Array<OpenSeatInfo>, } } | { "type": "PresenceUpdate", "payload": { player_id: string, presence: Presence, } });
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How a lobby treats human players connected from the same address, which may be one person
+ * playing several seats. Bot accounts are left out, as one owner often runs several from a host.
+ */
+export type SharedAddressPolicy = "Allow" | "Warn" | "Prevent";
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Six-character code that names a live lobby or game alongside its UUID
+ */
+export type ShortCode = string;
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Suit } from "./Suit";
+
+/**
+ * Rendering and screen-reader details for one suit
+ */
+export type SuitAsset = { suit: Suit, symbol: string, 
+/**
+ * CSS color name
+ */
+color: string, pattern: string | null, 
+/**
+ * Accessible description, e.g. "Hearts (red, striped)"
+ */
+label: string, };
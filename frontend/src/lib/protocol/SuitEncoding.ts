@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How suits are told apart visually; negotiated per user so every client renders the same deck
+ */
+export type SuitEncoding = "standard" | "four_color" | "patterned";
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A rule for separating players level on points, applied in the order the lobby lists them
+ */
+export type TieBreaker = "MostExactBids" | "SmallestPenalty";
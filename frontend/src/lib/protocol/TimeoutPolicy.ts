@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What happens when a player's turn time runs out
+ */
+export type TimeoutPolicy = "AutoPlay" | { "SkipWithPenalty": { points: number, } } | "WaitForHost";
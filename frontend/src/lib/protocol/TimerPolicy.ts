@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TimeoutPolicy } from "./TimeoutPolicy";
+
+/**
+ * Turn time limits per phase; unset phases use `turn_secs`, else the settings' deprecated
+ * `turn_timeout_secs`
+ */
+export type TimerPolicy = { 
+/**
+ * Limit for a phase without its own
+ */
+turn_secs: number | null, bidding_secs: number | null, playing_secs: number | null, on_timeout: TimeoutPolicy, };
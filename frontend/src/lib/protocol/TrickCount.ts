@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One player's standing in the round, sent with TrickComplete
+ */
+export type TrickCount = { player_id: string, bid: number, tricks_won: number, };
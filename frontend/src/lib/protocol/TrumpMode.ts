@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How each round's trump suit is chosen
+ */
+export type TrumpMode = "Random" | "Rotating" | "NoTrump";
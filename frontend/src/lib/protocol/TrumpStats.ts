@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Suit } from "./Suit";
+
+/**
+ * How a player fares under one trump suit
+ */
+export type TrumpStats = { 
+/**
+ * Null for rounds played without trump
+ */
+trump: Suit | null, rounds: number, 
+/**
+ * Rounds where the bid was made exactly
+ */
+made: number, make_rate: number, average_score: number, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * When a turn times out, as an absolute server time and as the time left when sent
+ */
+export type TurnDeadline = { player_id: string, 
+/**
+ * Server epoch milliseconds; correct for clock skew with TimeSync
+ */
+deadline_ms: number, 
+/**
+ * Whole seconds left, rounded up, for clients that don't sync clocks
+ */
+remaining_secs: number, };
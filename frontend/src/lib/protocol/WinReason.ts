@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TieBreaker } from "./TieBreaker";
+
+/**
+ * How the winners were decided
+ */
+export type WinReason = { "kind": "HighestScore" } | { "kind": "TieBreak", rule: TieBreaker, } | { "kind": "SharedWin" };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * When a bid of zero tricks is allowed. Where a zero bid is ruled out but is the bidder's only
+ * legal bid, it stays allowed.
+ */
+export type ZeroBidRule = "Allowed" | "NotInOneCardRounds" | "Never";
@@ -0,0 +1,83 @@
+import { invoke } from "@tauri-apps/api/core";
+import { writable } from "svelte/store";
+import type {
+  Card,
+  GamePhase,
+  PlayerId,
+  PlayerRoundResult,
+  RoundResult,
+  Suit,
+} from "./websocket";
+
+// --- Types mirroring src-tauri/src/local_game.rs ---
+
+export type RoundSchedule = "Ascending" | "UpAndDown";
+
+export type LocalGameEvent =
+  | { BidPlaced: { player_id: PlayerId; tricks: number } }
+  | { BiddingComplete: { leader: PlayerId } }
+  | { CardPlayed: { player_id: PlayerId; card: Card } }
+  | { TrickWon: { winner: PlayerId } }
+  | { RoundComplete: { round_number: number } }
+  | "GameComplete"
+  | {
+      RoundStarted: {
+        round_number: number;
+        cards_per_player: number;
+        trump_suit: Suit | null;
+      };
+    };
+
+export interface LocalGameView {
+  phase: GamePhase;
+  round_number: number;
+  total_rounds: number;
+  cards_per_player: number;
+  trump_suit: Suit | null;
+  players: PlayerId[];
+  your_hand: Card[];
+  current_trick: [PlayerId, Card][];
+  current_player: PlayerId;
+  your_turn: boolean;
+  valid_bids: number[];
+  valid_cards: Card[];
+  scores: Record<PlayerId, number>;
+  current_round: PlayerRoundResult[];
+  history: RoundResult[];
+  // Everything since the previous command, bots' turns included
+  events: LocalGameEvent[];
+}
+
+export interface LocalGameState {
+  game: LocalGameView | null;
+  error: string | null;
+}
+
+// --- Offline games against bots, played in the desktop app without a server ---
+
+export const localGame = createLocalGameStore();
+
+function createLocalGameStore() {
+  const { subscribe, set, update } = writable<LocalGameState>({
+    game: null,
+    error: null,
+  });
+
+  async function run(command: string, args: Record<string, unknown>) {
+    try {
+      const game = await invoke<LocalGameView>(command, args);
+      set({ game, error: null });
+    } catch (e) {
+      update((s) => ({ ...s, error: String(e) }));
+    }
+  }
+
+  return {
+    subscribe,
+    start: (bots: number, schedule: RoundSchedule = "Ascending") =>
+      run("start_local_game", { bots, schedule }),
+    placeBid: (tricks: number) => run("place_bid", { tricks }),
+    playCard: (card: Card) => run("play_card", { card }),
+    leave: () => set({ game: null, error: null }),
+  };
+}
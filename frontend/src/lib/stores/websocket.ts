@@ -1,38 +1,24 @@
-import { invoke } from "@tauri-apps/api/core";
+import { invoke, isTauri } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { writable, get } from "svelte/store";
 
-// --- Types based on API.md ---
+import type { ClientMessage } from "../protocol/ClientMessage";
+import type { Card } from "../protocol/Card";
+import type { GamePhase } from "../protocol/GamePhase";
+import type { Suit } from "../protocol/Suit";
+import type { TrickCount } from "../protocol/TrickCount";
+import type { Intermission } from "../protocol/Intermission";
 
-export type PlayerId = string;
-export type Suit = "Clubs" | "Spades" | "Hearts" | "Diamonds";
-export type Rank =
-  | "Two"
-  | "Three"
-  | "Four"
-  | "Five"
-  | "Six"
-  | "Seven"
-  | "Eight"
-  | "Nine"
-  | "Ten"
-  | "Jack"
-  | "Queen"
-  | "King"
-  | "Ace";
-
-export interface Card {
-  suit: Suit;
-  rank: Rank;
-}
+// Wire types are generated from the backend (`npm run protocol`); these mirror API.md
 
-export type GamePhase =
-  | "Bidding"
-  | "Playing"
-  | "RoundComplete"
-  | "GameComplete";
+export type { Card, GamePhase, Suit };
+export type { Rank } from "../protocol/Rank";
+export type { ServerMessage } from "../protocol/ServerMessage";
+export type PlayerId = string;
 
 export interface LobbySettings {
   player_count: number;
+  /** @deprecated Set `rules.timer.turn_secs` instead, which wins when both are set */
   turn_timeout_secs: number;
   allow_reconnect: boolean;
 }
@@ -44,6 +30,7 @@ export interface PlayerInfo {
 
 export interface Lobby {
   id: string;
+  code: string;
   host: string;
   players: PlayerInfo[];
   max_players: number;
@@ -52,6 +39,7 @@ export interface Lobby {
 
 export interface GameState {
   game_id: string;
+  game_code: string;
   phase: GamePhase;
   your_hand: Card[];
   current_trick: [PlayerId, Card][];
@@ -62,6 +50,8 @@ export interface GameState {
   current_player: PlayerId;
   your_turn: boolean;
   current_round: PlayerRoundResult[];
+  state_version: number;
+  intermission?: Intermission | null;
 }
 
 export interface RoundResult {
@@ -81,6 +71,12 @@ export interface ValidAction {
   Bid?: { tricks: number };
 }
 
+type PayloadOf<T extends ClientMessage["type"]> =
+  Extract<ClientMessage, { type: T }> extends { payload: infer P } ? P : never;
+type PayloadArgs<T extends ClientMessage["type"]> = [PayloadOf<T>] extends [never]
+  ? []
+  : [PayloadOf<T>];
+
 // --- Store State ---
 
 export interface AppState {
@@ -91,12 +87,25 @@ export interface AppState {
   game: GameState | null;
   lobbies: Lobby[]; // For the lobby list
   validActions: ValidAction[] | null; // actions valid for *your* turn
+  hint: ValidAction | null; // suggested action for your turn, after requestHint()
   error: string | null;
   playerUsernames: Record<string, string>; // Map of player IDs to usernames (populated from lobby.players)
   currentRoundBids: Record<string, number>; // Current round bids being accumulated
   currentRoundMakes: Record<string, number>; // Current round tricks being made
 }
 
+// Returned by the desktop app's `login` command
+export interface Session {
+  token: string;
+  username: string;
+  user_id: string;
+}
+
+// Emitted by the desktop app when its server connection opens or closes
+type ConnectionStatus =
+  | { status: "connected"; url: string }
+  | { status: "closed"; reason: string | null };
+
 const initialState: AppState = {
   connected: false,
   playerId: null,
@@ -105,6 +114,7 @@ const initialState: AppState = {
   game: null,
   lobbies: [],
   validActions: null,
+  hint: null,
   error: null,
   playerUsernames: {},
   currentRoundBids: {},
@@ -117,6 +127,8 @@ function createWebSocketStore() {
   const { subscribe, update, set } = writable<AppState>(initialState);
   let ws: WebSocket | null = null;
   let pingInterval: ReturnType<typeof setInterval>;
+  // In the desktop app the Rust side holds the connection and forwards server messages as events
+  let nativeListeners: UnlistenFn[] | null = null;
 
   async function getApiUrl(): Promise<string> {
     // Use environment variable if set and not empty
@@ -134,7 +146,7 @@ function createWebSocketStore() {
   }
 
   async function connect(token?: string) {
-    if (ws) return;
+    if (ws || nativeListeners) return;
 
     try {
       // Use environment variable if set and not empty
@@ -154,6 +166,11 @@ function createWebSocketStore() {
         wsUrl = `${protocol}//${host}${port}`;
       }
 
+      if (isTauri() && token) {
+        await connectNative(`${wsUrl}/ws`, token);
+        return;
+      }
+
       // Append /ws path and token if provided
       let url = `${wsUrl}/ws`;
       if (token) {
@@ -197,9 +214,40 @@ function createWebSocketStore() {
     };
   }
 
-  function send(type: string, payload?: any) {
-    if (ws && ws.readyState === WebSocket.OPEN) {
-      ws.send(JSON.stringify({ type, payload }));
+  async function connectNative(url: string, token: string) {
+    nativeListeners = [
+      await listen("server-message", (event) => handleMessage(event.payload)),
+      await listen<ConnectionStatus>("server-connection", (event) => {
+        if (event.payload.status === "closed") {
+          console.log("Disconnected", event.payload.reason ?? "");
+          stopNative();
+          update((s) => ({ ...initialState, error: "Disconnected from server" }));
+        }
+      }),
+    ];
+    try {
+      console.log("Connecting to:", url);
+      await invoke("connect_server", { wsUrl: url, token });
+    } catch (e) {
+      console.error("Failed to connect:", e);
+      stopNative();
+      update((s) => ({ ...s, error: String(e) }));
+    }
+  }
+
+  function stopNative() {
+    nativeListeners?.forEach((unlisten) => unlisten());
+    nativeListeners = null;
+  }
+
+  // Sends a ClientMessage; the payload is checked against the message type
+  function send<T extends ClientMessage["type"]>(type: T, ...payload: PayloadArgs<T>) {
+    if (nativeListeners) {
+      invoke("send_message", { message: { type, payload: payload[0] } }).catch((e) =>
+        console.warn("Cannot send message:", e),
+      );
+    } else if (ws && ws.readyState === WebSocket.OPEN) {
+      ws.send(JSON.stringify({ type, payload: payload[0] }));
     } else {
       console.warn("Cannot send message, not connected");
     }
@@ -219,18 +267,29 @@ function createWebSocketStore() {
           if (storedUsername) {
             newState.username = storedUsername;
           }
-          send("ListLobbies");
+          send("ListLobbies", null);
           break;
         case "Pong":
           break;
         case "Error":
           newState.error = msg.payload.message;
           break;
+        case "ServerMaintenance":
+          newState.error = `The server restarts in ${msg.payload.shutdown_in_secs}s; an unfinished game carries on where it stopped`;
+          break;
+        case "ResyncRequired":
+          // We fell behind and missed some of the room's messages; fetch its state again
+          if (msg.payload.room.kind === "Game") {
+            send("RequestGameState");
+          } else {
+            send("JoinLobby", { lobby_id: msg.payload.room.id });
+          }
+          break;
 
         // Lobby Messages
         case "LobbyCreated":
           // Refresh lobby list so others can see it immediately (and us if join fails)
-          send("ListLobbies");
+          send("ListLobbies", null);
           // Auto-join the lobby we just created
           if (msg.payload.lobby_id) {
             send("JoinLobby", { lobby_id: msg.payload.lobby_id });
@@ -264,9 +323,39 @@ function createWebSocketStore() {
             };
           }
           break;
+        case "LobbyExpired":
+          // Closed for sitting idle; back to the lobby browser
+          if (newState.lobby && newState.lobby.id === msg.payload.lobby_id) {
+            newState.lobby = null;
+            newState.error = "The lobby was closed after sitting idle";
+          }
+          break;
+        case "SharedAddressWarning":
+          if (newState.lobby && newState.lobby.id === msg.payload.lobby_id) {
+            const names = msg.payload.players.map(
+              (id: string) => newState.playerUsernames[id] ?? id
+            );
+            newState.error = `${names.join(", ")} are connected from the same address`;
+          }
+          break;
         case "LobbyList":
           newState.lobbies = msg.payload.lobbies;
           break;
+        case "LobbyListDelta": {
+          const removed = new Set<string>(msg.payload.removed);
+          const changed = new Map<string, Lobby>(
+            msg.payload.changed.map((l: Lobby) => [l.id, l])
+          );
+          const kept = newState.lobbies
+            .filter((l) => !removed.has(l.id))
+            .map((l) => changed.get(l.id) ?? l);
+          const known = new Set(kept.map((l) => l.id));
+          newState.lobbies = [
+            ...kept,
+            ...msg.payload.changed.filter((l: Lobby) => !known.has(l.id)),
+          ];
+          break;
+        }
 
         case "GameStarting":
           // We need to request the game state to transition to the game view
@@ -309,36 +398,38 @@ function createWebSocketStore() {
           break;
         case "YourTurn":
           newState.validActions = msg.payload.valid_actions;
+          newState.hint = null;
           if (newState.game) {
             newState.game = { ...newState.game, your_turn: true };
           }
           break;
+        case "Hint":
+          // Drop hints for a turn that has already moved on
+          if (newState.game?.state_version === msg.payload.state_version) {
+            newState.hint = msg.payload.action;
+          }
+          break;
         case "PlayerAction":
-          const { player_id, action, next_player } = msg.payload;
+          const { player_id, action, next_player, phase, current_trick, state_version } = msg.payload;
+          newState.hint = null;
           if (newState.game) {
-            // Update current_player - create new game object for reactivity
-            if (next_player) {
-              newState.game = { ...newState.game, current_player: next_player };
-            }
+            // Follow the turn and phase the action left - create new game object for reactivity
+            newState.game = { ...newState.game, current_player: next_player, phase, state_version };
 
             // Handle PlayCard
             if (action.PlayCard) {
               const card = action.PlayCard;
 
-              // Check if the current trick is full (based on lobby settings or default 4)
-              // If full, we assume this new card starts a new trick, so we clear the old one.
-              // We use >= max_players to be safe.
-              const maxPlayers = newState.lobby?.max_players || 4;
-              if (newState.game.current_trick.length >= maxPlayers) {
-                newState.game.current_trick = [];
+              // An empty trick means this card completed one; keep showing it until the next card
+              if (current_trick.length > 0) {
+                newState.game.current_trick = current_trick;
+              } else {
+                newState.game.current_trick = [
+                  ...newState.game.current_trick,
+                  [player_id, card],
+                ];
               }
 
-              // Add to current trick
-              newState.game.current_trick = [
-                ...newState.game.current_trick,
-                [player_id, card],
-              ];
-
               // If it's me, remove from hand
               if (player_id === newState.playerId) {
                 newState.game.your_hand = newState.game.your_hand.filter(
@@ -365,24 +456,42 @@ function createWebSocketStore() {
           }
           break;
         case "TrickComplete":
-          // Track that a player won a trick
-          if (msg.payload.winner) {
-            newState.currentRoundMakes = {
-              ...newState.currentRoundMakes,
-              [msg.payload.winner]:
-                (newState.currentRoundMakes[msg.payload.winner] || 0) + 1,
-            };
+          // The payload carries every player's tricks so far this round
+          newState.currentRoundMakes = Object.fromEntries(
+            msg.payload.tricks.map((t: TrickCount) => [t.player_id, t.tricks_won]),
+          );
+          if (newState.game) {
+            // Show the whole trick until the next card is played
+            newState.game = { ...newState.game, current_trick: msg.payload.cards };
           }
-          // Clear the current trick after a brief delay would be handled client-side
-          // but the server should send new state that clears it
+          break;
+        case "NextRoundVote":
           if (newState.game) {
-            // Don't clear immediately - let the UI show the winning trick briefly
-            // The next GameState or PlayerAction will update it
+            newState.game = {
+              ...newState.game,
+              intermission: msg.payload.intermission,
+              your_turn: msg.payload.intermission.waiting_for.includes(newState.playerId ?? ""),
+            };
           }
           break;
         case "GameOver":
           // Final scores are in payload
           break;
+        case "PlayerDisconnected": {
+          // Their turn clock stands still for a while in case they come back
+          const name = newState.playerUsernames[msg.payload.player_id] ?? "A player";
+          newState.error = `${name} lost their connection; their turn is paused`;
+          break;
+        }
+        case "TurnTimedOut": {
+          // The game waits for the player, or for the host to play the turn for them
+          const name = newState.playerUsernames[msg.payload.player_id] ?? "A player";
+          newState.error = `${name} ran out of time; waiting for the host`;
+          break;
+        }
+        case "AchievementUnlocked":
+          newState.error = `Achievement unlocked: ${msg.payload.title} (${msg.payload.description})`;
+          break;
 
         // Player Events
         case "PlayerJoined":
@@ -404,10 +513,10 @@ function createWebSocketStore() {
       send("LeaveLobby");
       update((s) => ({ ...s, lobby: null, game: null }));
       // Refresh lobby list after leaving
-      setTimeout(() => send("ListLobbies"), 100);
+      setTimeout(() => send("ListLobbies", null), 100);
     },
     startGame: () => send("StartGame"),
-    listLobbies: () => send("ListLobbies"),
+    listLobbies: () => send("ListLobbies", null),
     placeBid: (bid: number) => {
       send("PlaceBid", { bid: { tricks: bid } });
       // Optimistically hide the bid controls and track the bid
@@ -428,7 +537,9 @@ function createWebSocketStore() {
     },
     playCard: (card: Card) => send("PlayCard", { card }),
     startNextRound: () => send("StartNextRound"),
+    forceTurn: (player_id: string) => send("ForceTurn", { player_id }),
     requestGameState: () => send("RequestGameState"),
+    requestHint: () => send("RequestHint"),
     ping: () => send("Ping"),
     getApiUrl,
     logout: () => {
@@ -438,6 +549,10 @@ function createWebSocketStore() {
         ws = null;
         clearInterval(pingInterval);
       }
+      if (nativeListeners) {
+        stopNative();
+        invoke("disconnect_server");
+      }
       // Clear localStorage
       localStorage.removeItem("auth_token");
       localStorage.removeItem("auth_user");